@@ -1,5 +1,6 @@
 use chrono::Duration;
 use cli::{change_benchmark_datetimes, change_symbols_pair, select_from_list};
+use common::enums::run_mode::RunMode;
 use common::functions::{current_datetime, current_datetime_minute_start};
 use common::traits::exchange::TraderHelper;
 use core::controller::Controller;
@@ -19,11 +20,20 @@ async fn main() {
 
     
     let term = Term::stdout();
-    let mut controller = Controller::new(true);
+    let mut controller = Controller::new(RunMode::BenchmarkOnly);
     loop {
         // term.clear_screen().unwrap(); // comment this to debug
-        let start_datetime = controller.benchmark_settings.datetimes.0.unwrap_or(current_datetime());
-        let end_datetime = controller.benchmark_settings.datetimes.1.unwrap_or(current_datetime());
+        let kline_duration = controller
+            .trader
+            .trader_exchange
+            .get_trading_settings()
+            .granularity
+            .get_chrono_duration();
+        let (start_datetime, end_datetime) = controller
+            .benchmark_settings
+            .window
+            .resolve(current_datetime(), kline_duration);
+        let start_datetime = start_datetime.unwrap_or(current_datetime());
         term.write_line("Glow Backtesting Suite - v0.02.").unwrap();
         term.write_line(
             r#"Gloria Patri, et Filio, et Spiritui Sancto.
@@ -83,7 +93,9 @@ async fn main() {
                 let updated_symbols_pair = updated_symbols_pair.unwrap();
                 let updated_trading_settings =
                     current_trading_settings.patch_symbols_pair(updated_symbols_pair);
-                controller.patch_settings(&updated_trading_settings);
+                if let Err(error) = controller.patch_settings(&updated_trading_settings) {
+                    println!("patch_settings error {:?}", error);
+                }
             }
             2 => {
                 // CHANGE PROVIDER EXCHANGE
@@ -99,7 +111,10 @@ async fn main() {
             }
             6 => {
                 // RUN BENCHMARK
-                controller.init();
+                if let Err(error) = controller.init().await {
+                    println!("init error {:?}", error);
+                    continue;
+                }
                 sleep(StdDuration::new(5, 0)).await;
                 let options = vec!["Press enter to run again"];
                 select_from_list("Benchmark is done", &options, Some(default_index));
@@ -6,4 +6,7 @@ pub const NANOS_IN_SECOND: i64 = 1_000_000_000;
 pub const API_KEY_ENV_SUFFIX: &str = "API_KEY";
 pub const API_SECRET_ENV_SUFFIX: &str = "API_SECRET";
 pub const DATE_INPUT_REGEX: &str = r"^(0[1-9]|[12][0-9]|3[01])-(0[1-9]|1[0-2])-\d{4}$";
-pub const TIME_INPUT_REGEX: &str = r"^(?:[01]\d|2[0-3]):[0-5]\d$";
\ No newline at end of file
+pub const TIME_INPUT_REGEX: &str = r"^(?:[01]\d|2[0-3]):[0-5]\d$";
+pub const DEFAULT_PRICE_LEVEL_EPSILON: f64 = 1e-6;
+pub const CLOCK_SKEW_CHECK_INTERVAL_SECS: u64 = 600;
+pub const CLOCK_SKEW_WARNING_THRESHOLD_MS: i64 = 1_000;
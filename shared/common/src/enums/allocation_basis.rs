@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// What `TradingSettings::allocation_percentage` is applied to when sizing open orders.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum AllocationBasis {
+    /// quote balance available to withdraw.
+    #[default]
+    QuoteBalance,
+    /// value of currently held base asset position, at mark price.
+    BasePosition,
+    /// quote balance available to withdraw plus value of held base asset position, at mark price.
+    TotalEquity,
+}
+
+impl AllocationBasis {
+    /// Gets quote amount allocation percentage is applied to, valuing `position_units` of base
+    /// asset at `mark_price`.
+    pub fn get_allocation_base(
+        &self,
+        available_to_withdraw: f64,
+        position_units: f64,
+        mark_price: f64,
+    ) -> f64 {
+        let position_value = position_units.abs() * mark_price;
+        match self {
+            Self::QuoteBalance => available_to_withdraw,
+            Self::BasePosition => position_value,
+            Self::TotalEquity => available_to_withdraw + position_value,
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::functions::current_timestamp;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Balance {
     pub timestamp: i64,
     pub available_to_withdraw: f64,
@@ -0,0 +1,37 @@
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+/// Period benchmarks are run over, either set by absolute datetimes or relative to when
+/// they're run, so that scheduled runs keep benchmarking the latest period.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum BenchmarkWindow {
+    /// optional start and end datetimes. Missing end stands for now, whereas missing start is
+    /// left for data provider to set.
+    Absolute(Option<NaiveDateTime>, Option<NaiveDateTime>),
+    /// last days, up to now.
+    RelativeDays(i64),
+    /// last bars, of traded granularity, up to now.
+    RelativeBars(u32),
+}
+
+impl Default for BenchmarkWindow {
+    fn default() -> Self {
+        Self::Absolute(None, None)
+    }
+}
+
+impl BenchmarkWindow {
+    /// Resolves window into its start, if set, and end datetimes, against `now` and
+    /// `kline_duration`.
+    pub fn resolve(
+        &self,
+        now: NaiveDateTime,
+        kline_duration: Duration,
+    ) -> (Option<NaiveDateTime>, NaiveDateTime) {
+        match *self {
+            Self::Absolute(start, end) => (start, end.unwrap_or(now)),
+            Self::RelativeDays(days) => (Some(now - Duration::days(days)), now),
+            Self::RelativeBars(bars) => (Some(now - kline_duration * bars as i32), now),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use super::side::Side;
+use serde::{Deserialize, Serialize};
+
+/// How contract's size and margin are denominated.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum ContractKind {
+    /// units are denominated in base coin, margined and settled in quote (e.g. USDT).
+    #[default]
+    Linear,
+    /// units are denominated in quote (e.g. USD), margined and settled in base coin.
+    Inverse,
+}
+
+impl ContractKind {
+    /// Gets position value of `units` at `price`, in margin currency.
+    pub fn calculate_notional(&self, units: f64, price: f64) -> f64 {
+        match self {
+            Self::Linear => units * price,
+            Self::Inverse => units / price,
+        }
+    }
+
+    /// Gets price at which position opened at `price` loses its whole initial margin.
+    pub fn calculate_bankruptcy_price(&self, side: Side, price: f64, leverage_factor: f64) -> f64 {
+        match (self, side) {
+            (Self::Linear, Side::Buy) => price * (leverage_factor - 1.0) / leverage_factor,
+            (Self::Linear, Side::Sell) => price * (leverage_factor + 1.0) / leverage_factor,
+            (Self::Inverse, Side::Buy) => price * leverage_factor / (leverage_factor + 1.0),
+            (Self::Inverse, Side::Sell) => price * leverage_factor / (leverage_factor - 1.0),
+            (_, Side::None) => 0.0,
+        }
+    }
+
+    /// Calculates ((open fee, close fee), order cost) of opening `units` at `price`, in margin
+    /// currency. Order cost is initial margin, plus open fee, plus close fee at bankruptcy price.
+    pub fn calculate_order_cost(
+        &self,
+        side: Side,
+        units: f64,
+        price: f64,
+        leverage_factor: f64,
+        fee_rate: f64,
+    ) -> ((f64, f64), f64) {
+        let initial_margin = self.calculate_notional(units, price) / leverage_factor;
+        let open_fee = self.calculate_notional(units, price) * fee_rate;
+        let bankruptcy_price = self.calculate_bankruptcy_price(side, price, leverage_factor);
+        let close_fee = if bankruptcy_price > 0.0 && bankruptcy_price.is_finite() {
+            self.calculate_notional(units, bankruptcy_price) * fee_rate
+        } else {
+            0.0
+        };
+        ((open_fee, close_fee), initial_margin + open_fee + close_fee)
+    }
+
+    /// Calculates profit and loss, before fees, of `units` position opened at `entry_price`
+    /// and closed at `exit_price`, in margin currency.
+    pub fn calculate_gross_pnl(
+        &self,
+        side: Side,
+        units: f64,
+        entry_price: f64,
+        exit_price: f64,
+    ) -> f64 {
+        let long_pnl = match self {
+            Self::Linear => (exit_price - entry_price) * units,
+            Self::Inverse => units * (1.0 / entry_price - 1.0 / exit_price),
+        };
+        match side {
+            Side::Buy => long_pnl,
+            Side::Sell => -long_pnl,
+            Side::None => 0.0,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// Which exchange environment trader connects to. Defaults to testnet, so that trading
+/// on mainnet is always an explicit choice.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum ExchangeEnvironment {
+    #[default]
+    Testnet,
+    Mainnet,
+}
@@ -6,7 +6,7 @@ use std::{
 };
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Default, Clone, Copy)]
+#[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
 pub enum Granularity {
     #[default]
     m1,
@@ -25,6 +25,24 @@ pub enum Granularity {
     M1,
 }
 
+/// Every granularity, from finest to coarsest.
+const GRANULARITIES: [Granularity; 14] = [
+    Granularity::m1,
+    Granularity::m3,
+    Granularity::m5,
+    Granularity::m10,
+    Granularity::m15,
+    Granularity::m30,
+    Granularity::h1,
+    Granularity::h2,
+    Granularity::h4,
+    Granularity::h6,
+    Granularity::h12,
+    Granularity::d1,
+    Granularity::w1,
+    Granularity::M1,
+];
+
 impl Granularity {
     pub fn get_granularity_in_secs(&self) -> u32 {
         match self {
@@ -35,7 +53,7 @@ impl Granularity {
             Self::m15 => 15 * 60,
             Self::m30 => 30 * 60,
             Self::h1 => 60 * 60,
-            Self::h2 => 2 * 60 * 20,
+            Self::h2 => 2 * 60 * 60,
             Self::h4 => 4 * 60 * 60,
             Self::h6 => 6 * 60 * 60,
             Self::h12 => 12 * 60 * 60,
@@ -59,6 +77,30 @@ impl Granularity {
         let seconds = self.get_granularity_in_secs();
         ChronoDuration::seconds(seconds.into())
     }
+
+    /// Gets coarsest granularity that evenly divides this one among the ones `is_available`, e.g.
+    /// provided by an exchange, so that klines fetched at it can be downsampled to this one.
+    /// Falls back to 1 minute, which every granularity is a multiple of.
+    pub fn get_coarsest_divisor(&self, is_available: impl Fn(Granularity) -> bool) -> Granularity {
+        let granularity_in_secs = self.get_granularity_in_secs();
+        GRANULARITIES
+            .into_iter()
+            .rev()
+            .find(|divisor| {
+                let divisor_in_secs = divisor.get_granularity_in_secs();
+                divisor_in_secs <= granularity_in_secs
+                    && granularity_in_secs.is_multiple_of(divisor_in_secs)
+                    && is_available(*divisor)
+            })
+            .unwrap_or(Self::m1)
+    }
+
+    /// Gets how many klines of this granularity fit in a year, as crypto markets trade
+    /// around the clock
+    pub fn get_periods_per_year(&self) -> f64 {
+        let seconds_per_year = 365.0 * 24.0 * 60.0 * 60.0;
+        seconds_per_year / self.get_granularity_in_secs() as f64
+    }
 }
 
 impl Debug for Granularity {
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// How bars missing from klines are handled. Inserted bars are flagged at `is_gap` column.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum KlineGapHandling {
+    /// missing bars aren't inserted, so that post-gap bars directly follow pre-gap ones.
+    #[default]
+    Keep,
+    /// missing bars are inserted with OHLC set to last known close.
+    ForwardFill,
+    /// missing bars are inserted with null OHLC.
+    Nulls,
+}
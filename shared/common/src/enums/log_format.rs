@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// How log events are printed: human readable lines, or one-line JSON objects for log aggregation.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
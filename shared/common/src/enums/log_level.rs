@@ -1,9 +1,12 @@
+use serde::{Deserialize, Serialize};
+
 #[allow(dead_code)]
-#[derive(Debug, Eq, PartialEq, PartialOrd, Clone, Copy)]
+#[derive(Debug, Eq, PartialEq, PartialOrd, Clone, Copy, Default, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum LogLevel {
     Nothing = 0,
     Results = 1,
     Trades = 2,
+    #[default]
     All = 3,
 }
@@ -1,5 +1,11 @@
+pub mod allocation_basis;
 pub mod balance;
+pub mod benchmark_window;
+pub mod contract_kind;
+pub mod exchange_environment;
 pub mod http_method;
+pub mod kline_gap_handling;
+pub mod log_format;
 pub mod log_level;
 pub mod modifiers;
 pub mod order_action;
@@ -7,11 +13,15 @@ pub mod order_stage;
 pub mod order_status;
 pub mod order_type;
 pub mod processer_action;
+pub mod run_mode;
 pub mod side;
 pub mod signal_category;
 pub mod stop_order_type;
 pub mod time_in_force;
 pub mod trade_status;
 pub mod trading_data_update;
+pub mod ws_compression;
 pub mod granularity;
-pub mod symbol_id;
\ No newline at end of file
+pub mod symbol_id;
+#[cfg(test)]
+mod tests;
\ No newline at end of file
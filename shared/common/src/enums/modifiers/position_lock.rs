@@ -11,3 +11,18 @@ pub enum PositionLock {
     #[serde(rename="loss")]
     Loss = 2, // least percentage for the trade to close
 }
+
+impl PositionLock {
+    /// Whether closing a position is prevented by this lock, given its gross profit and loss
+    /// (before fees) and total fees (open + close).
+    ///
+    /// * `Fee` - locks while fees exceed gross profit and loss;
+    /// * `Loss` - locks while gross profit and loss isn't positive.
+    pub fn is_close_locked(&self, gross_profit_and_loss: f64, total_fee: f64) -> bool {
+        match self {
+            Self::None => false,
+            Self::Fee => total_fee > gross_profit_and_loss,
+            Self::Loss => gross_profit_and_loss <= 0.0,
+        }
+    }
+}
@@ -1,16 +1,97 @@
+use crate::functions::{closest_multiple_below, is_at_or_below};
 use serde::{Deserialize, Serialize};
 
 // uses ROI
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum PriceLevel {
     #[serde(rename="sl")]
     StopLoss(f64), // 0 < f64 <= 75 in bybit
     #[serde(rename="tp")]
     TakeProfit(f64),
+    #[serde(rename="ttp")]
+    TrailingTakeProfit(TrailingTakeProfit),
+    #[serde(rename="tpl")]
+    TakeProfitLadder(TakeProfitLadder),
     // #[serde(rename="tsp")]
     // TrailingStopLoss(TrailingStopLoss),
 }
 
+/// Ratchets take profit up as position returns advance, closing it once returns retrace from
+/// their peak, but only after peak returns exceed `start_percentage`, below which it never trails.
+/// Exchanges don't set it as a price level, so live trading only trails stepped ones, by amending
+/// position's stop loss on Bybit.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum TrailingTakeProfit {
+    #[serde(rename="pcnt")]
+    Percent(f64, f64), // (retrace fraction of peak returns, start_percentage)
+    #[serde(rename="step")]
+    Stepped(f64, f64), // (step percentage, start_percentage)
+}
+
+impl TrailingTakeProfit {
+    pub fn get_start_percentage(&self) -> f64 {
+        match self {
+            TrailingTakeProfit::Percent(_, start_percentage) => *start_percentage,
+            TrailingTakeProfit::Stepped(_, start_percentage) => *start_percentage,
+        }
+    }
+
+    /// Gets returns at or below which position should be closed, given its `peak_returns`.
+    /// Returns None while trailing take profit isn't active, i.e. while `peak_returns` aren't
+    /// above start percentage by more than `epsilon` tolerance.
+    pub fn get_acceptable_returns(&self, peak_returns: f64, epsilon: f64) -> Option<f64> {
+        let start_percentage = self.get_start_percentage();
+        if is_at_or_below(peak_returns, start_percentage, epsilon) {
+            return None;
+        }
+        let acceptable_returns = match self {
+            TrailingTakeProfit::Percent(retrace, _) => peak_returns * (1.0 - retrace),
+            TrailingTakeProfit::Stepped(step, _) => closest_multiple_below(*step, peak_returns),
+        };
+        Some(acceptable_returns.max(start_percentage))
+    }
+}
+
+/// Take profit scaling out of position in steps, as `(percentage, fraction)` levels sorted by
+/// percentage, where fraction is the share of initial position closed once percentage is reached.
+/// Last level closes whatever is left of position, regardless of its fraction.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TakeProfitLadder(pub Vec<(f64, f64)>);
+
+impl TakeProfitLadder {
+    pub fn get_percentage(&self, level: usize) -> Option<f64> {
+        self.0.get(level).map(|(percentage, _)| *percentage)
+    }
+
+    pub fn is_last_level(&self, level: usize) -> bool {
+        level + 1 >= self.0.len()
+    }
+
+    /// Gets fraction of position remaining before `level` that it closes.
+    /// Returns None for last level, as it closes the whole remaining position.
+    pub fn get_remaining_fraction(&self, level: usize) -> Option<f64> {
+        if self.is_last_level(level) {
+            return None;
+        }
+        let closed_fraction: f64 = self.0[..level].iter().map(|(_, fraction)| fraction).sum();
+        let remaining_fraction = 1.0 - closed_fraction;
+        if remaining_fraction <= 0.0 {
+            return None;
+        }
+        Some((self.0[level].1 / remaining_fraction).min(1.0))
+    }
+
+    /// Gets units closed at `level`, out of `initial_units` position, given `closed_units`
+    /// already closed by previous levels.
+    pub fn get_level_units(&self, level: usize, initial_units: f64, closed_units: f64) -> f64 {
+        let remaining_units = (initial_units - closed_units).max(0.0);
+        match self.get_remaining_fraction(level) {
+            Some(fraction) => remaining_units * fraction,
+            None => remaining_units,
+        }
+    }
+}
+
 // TODO: implement this in the future
 // #[derive(Serialize, Deserialize, Debug, Clone)]
 // pub enum TrailingStopLoss {
@@ -25,6 +106,10 @@ impl PriceLevel {
         match &self {
             PriceLevel::StopLoss(percentage) => *percentage,
             PriceLevel::TakeProfit(percentage) => *percentage,
+            PriceLevel::TrailingTakeProfit(trailing_take_profit) => {
+                trailing_take_profit.get_start_percentage()
+            }
+            PriceLevel::TakeProfitLadder(ladder) => ladder.get_percentage(0).unwrap_or_default(),
             // PriceLevel::TrailingStopLoss(trailing_stop_loss) => match trailing_stop_loss {
             //     TrailingStopLoss::Percent(percentage, _) => *percentage,
             //     TrailingStopLoss::Stepped(percentage, _) => *percentage,
@@ -36,6 +121,9 @@ impl PriceLevel {
         match &self {
             PriceLevel::StopLoss(_) => "sl".to_string(),
             PriceLevel::TakeProfit(_) => "tp".to_string(),
+            PriceLevel::TrailingTakeProfit(_) => "ttp".to_string(),
+            // ladder replaces single take profit
+            PriceLevel::TakeProfitLadder(_) => "tp".to_string(),
             // PriceLevel::TrailingStopLoss(_) => "tsp".to_string(),
         }
     }
@@ -4,6 +4,10 @@ use serde::{Deserialize, Serialize};
 pub enum OrderStage {
     Open,
     Close,
+    /// Market close superseding a resting maker close order that wasn't filled in time
+    FallbackClose,
+    /// Reduce-only market order flattening position, should its fallback close fail
+    Flatten,
 }
 
 impl ToString for OrderStage {
@@ -11,6 +15,8 @@ impl ToString for OrderStage {
         match self {
             OrderStage::Open => "open".to_string(),
             OrderStage::Close => "close".to_string(),
+            OrderStage::FallbackClose => "fallback_close".to_string(),
+            OrderStage::Flatten => "flatten".to_string(),
         }
     }
 }
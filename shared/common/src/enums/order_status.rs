@@ -1,4 +1,6 @@
-#[derive(Clone, Debug, PartialEq, Eq, Copy, Default)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Copy, Default, Serialize, Deserialize)]
 pub enum OrderStatus {
     #[default]
     StandBy,
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// What a run does with market data, from initial klines to live trading.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum RunMode {
+    /// market data is streamed and persisted, but no order is ever sent.
+    DataGatherOnly,
+    /// benchmark positions are computed over initial klines, after which data provider exits.
+    BenchmarkOnly,
+    /// benchmark positions are computed and market data is streamed and traded upon.
+    #[default]
+    Live,
+}
+
+impl RunMode {
+    /// Whether data provider keeps streaming market data after emitting initial klines.
+    pub fn streams_market_data(&self) -> bool {
+        *self != Self::BenchmarkOnly
+    }
+
+    /// Whether streamed market data is persisted as it arrives.
+    pub fn persists_market_data(&self) -> bool {
+        *self == Self::DataGatherOnly
+    }
+
+    /// Whether trader acts upon signals and keeps track of exchange orders and positions.
+    pub fn trades(&self) -> bool {
+        *self == Self::Live
+    }
+}
@@ -20,6 +20,17 @@ impl Side {
             }
         }
     }
+
+    /// Worsens `price` by `slippage_bps` basis points for an order of this side,
+    /// i.e. buys fill higher and sells fill lower.
+    pub fn apply_slippage(&self, price: f64, slippage_bps: f64) -> f64 {
+        let slippage = price * slippage_bps / 10_000.0;
+        match self {
+            Self::Buy => price + slippage,
+            Self::Sell => price - slippage,
+            Self::None => price,
+        }
+    }
 }
 
 impl Into<i32> for Side {
@@ -1,7 +1,8 @@
 use super::side::Side;
+use serde::{Deserialize, Serialize};
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Serialize, Deserialize)]
 pub enum SignalCategory {
     GoShort,
     CloseShort,
@@ -15,6 +16,7 @@ pub enum SignalCategory {
     StopLoss,
     // TrailingStopLoss,
     TakeProfit,
+    TrailingTakeProfit,
     LeverageBankrupcty,
 }
 
@@ -39,6 +41,7 @@ impl SignalCategory {
             Self::StopLoss => "stop_loss",
             // Self::TrailingStopLoss => "trailing_stop_loss",
             Self::TakeProfit => "take_profit",
+            Self::TrailingTakeProfit => "trailing_take_profit",
             Self::LeverageBankrupcty => "leverage_bankruptcy",
         }
     }
@@ -56,6 +59,7 @@ impl From<SignalCategory> for Side {
             SignalCategory::StopLoss => unreachable!(),
             // SignalCategory::TrailingStopLoss => unreachable!(),
             SignalCategory::TakeProfit => unreachable!(),
+            SignalCategory::TrailingTakeProfit => unreachable!(),
             SignalCategory::LeverageBankrupcty => unreachable!(),
         }
     }
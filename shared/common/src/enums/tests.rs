@@ -0,0 +1,198 @@
+use super::{
+    allocation_basis::AllocationBasis, benchmark_window::BenchmarkWindow,
+    contract_kind::ContractKind, run_mode::RunMode, side::Side, ws_compression::WsCompression,
+};
+use chrono::{Duration, NaiveDate};
+use flate2::{
+    write::{DeflateEncoder, GzEncoder},
+    Compression,
+};
+use std::io::Write;
+use tokio_tungstenite::tungstenite::Message;
+
+const TOLERANCE: f64 = 1e-9;
+
+fn assert_close(value: f64, expected: f64) {
+    assert!(
+        (value - expected).abs() < TOLERANCE,
+        "{} != {}",
+        value,
+        expected
+    );
+}
+
+// 10,000 BTCUSD contracts at 8,000 USD, 50x leverage, 0.075% taker fee, as in Bybit's
+// inverse contract order cost examples
+const INVERSE_UNITS: f64 = 10_000.0;
+const INVERSE_PRICE: f64 = 8_000.0;
+const INVERSE_LEVERAGE: f64 = 50.0;
+const INVERSE_FEE_RATE: f64 = 0.00075;
+
+#[test]
+fn test_inverse_long_order_cost_matches_exchange_figures() {
+    let contract_kind = ContractKind::Inverse;
+
+    let bankruptcy_price =
+        contract_kind.calculate_bankruptcy_price(Side::Buy, INVERSE_PRICE, INVERSE_LEVERAGE);
+    let ((open_fee, close_fee), order_cost) = contract_kind.calculate_order_cost(
+        Side::Buy,
+        INVERSE_UNITS,
+        INVERSE_PRICE,
+        INVERSE_LEVERAGE,
+        INVERSE_FEE_RATE,
+    );
+
+    assert_close(bankruptcy_price, 8_000.0 * 50.0 / 51.0);
+    assert_close(open_fee, 0.0009375);
+    assert_close(close_fee, 0.00095625);
+    // initial margin of 0.025 BTC, plus fees
+    assert_close(order_cost, 0.02689375);
+}
+
+#[test]
+fn test_inverse_short_order_cost_matches_exchange_figures() {
+    let contract_kind = ContractKind::Inverse;
+
+    let bankruptcy_price =
+        contract_kind.calculate_bankruptcy_price(Side::Sell, INVERSE_PRICE, INVERSE_LEVERAGE);
+    let ((open_fee, close_fee), order_cost) = contract_kind.calculate_order_cost(
+        Side::Sell,
+        INVERSE_UNITS,
+        INVERSE_PRICE,
+        INVERSE_LEVERAGE,
+        INVERSE_FEE_RATE,
+    );
+
+    assert_close(bankruptcy_price, 8_000.0 * 50.0 / 49.0);
+    assert_close(open_fee, 0.0009375);
+    assert_close(close_fee, 0.00091875);
+    assert_close(order_cost, 0.02685625);
+}
+
+#[test]
+fn test_inverse_pnl_is_settled_in_base_coin() {
+    let contract_kind = ContractKind::Inverse;
+
+    // 1,000 contracts from 5,000 to 6,000 USD
+    let long_pnl = contract_kind.calculate_gross_pnl(Side::Buy, 1_000.0, 5_000.0, 6_000.0);
+    let short_pnl = contract_kind.calculate_gross_pnl(Side::Sell, 1_000.0, 5_000.0, 6_000.0);
+
+    assert_close(long_pnl, 1.0 / 30.0);
+    assert_close(short_pnl, -1.0 / 30.0);
+}
+
+#[test]
+fn test_linear_pnl_and_order_cost_are_quoted_in_notional() {
+    let contract_kind = ContractKind::Linear;
+
+    let pnl = contract_kind.calculate_gross_pnl(Side::Sell, 2.0, 100.0, 90.0);
+    let ((open_fee, close_fee), order_cost) =
+        contract_kind.calculate_order_cost(Side::Buy, 2.0, 100.0, 10.0, 0.001);
+
+    assert_close(pnl, 20.0);
+    assert_close(open_fee, 0.2);
+    // bankruptcy price of 90.0
+    assert_close(close_fee, 0.18);
+    assert_close(order_cost, 20.0 + 0.2 + 0.18);
+}
+
+const WS_JSON: &str = r#"{"e":"kline","s":"BTCUSDT"}"#;
+
+#[test]
+fn test_compressed_binary_ws_frames_are_inflated_into_text() {
+    let mut gzip_encoder = GzEncoder::new(Vec::new(), Compression::default());
+    gzip_encoder.write_all(WS_JSON.as_bytes()).unwrap();
+    let mut deflate_encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    deflate_encoder.write_all(WS_JSON.as_bytes()).unwrap();
+    let payloads = [
+        (WsCompression::Gzip, gzip_encoder.finish().unwrap()),
+        (WsCompression::Deflate, deflate_encoder.finish().unwrap()),
+    ];
+    for (ws_compression, payload) in payloads {
+        let decoded = ws_compression
+            .decode_message(Message::Binary(payload))
+            .unwrap();
+        assert_eq!(decoded, Message::Text(WS_JSON.to_string()));
+    }
+
+    // text frames, as well as binary ones without compression, are kept as they are
+    let text_message = Message::Text(WS_JSON.to_string());
+    let decoded = WsCompression::Gzip
+        .decode_message(text_message.clone())
+        .unwrap();
+    assert_eq!(decoded, text_message);
+    let binary_message = Message::Binary(WS_JSON.as_bytes().to_vec());
+    let decoded = WsCompression::None
+        .decode_message(binary_message.clone())
+        .unwrap();
+    assert_eq!(decoded, binary_message);
+}
+
+#[test]
+fn test_run_modes_stream_persist_and_trade_distinctly() {
+    // (run mode, streams market data, persists market data, trades)
+    let cases = [
+        (RunMode::DataGatherOnly, true, true, false),
+        (RunMode::BenchmarkOnly, false, false, false),
+        (RunMode::Live, true, false, true),
+    ];
+    for (run_mode, streams, persists, trades) in cases {
+        assert_eq!(run_mode.streams_market_data(), streams, "{:?}", run_mode);
+        assert_eq!(run_mode.persists_market_data(), persists, "{:?}", run_mode);
+        assert_eq!(run_mode.trades(), trades, "{:?}", run_mode);
+    }
+    assert_eq!(RunMode::default(), RunMode::Live);
+}
+
+#[test]
+fn test_allocation_basis_values_base_position_at_mark_price() {
+    // 1,000 USDT available, 2 units of base asset held at 50 USDT
+    let cases = [
+        (AllocationBasis::QuoteBalance, 1_000.0),
+        (AllocationBasis::BasePosition, 100.0),
+        (AllocationBasis::TotalEquity, 1_100.0),
+    ];
+    for (allocation_basis, expected) in cases {
+        assert_close(
+            allocation_basis.get_allocation_base(1_000.0, 2.0, 50.0),
+            expected,
+        );
+        // short positions are valued by their size as well
+        assert_close(
+            allocation_basis.get_allocation_base(1_000.0, -2.0, 50.0),
+            expected,
+        );
+    }
+    assert_eq!(AllocationBasis::default(), AllocationBasis::QuoteBalance);
+}
+
+#[test]
+fn test_benchmark_window_resolves_relative_windows_up_to_now() {
+    let now = NaiveDate::from_ymd_opt(2024, 3, 1)
+        .unwrap()
+        .and_hms_opt(12, 30, 0)
+        .unwrap();
+    let kline_duration = Duration::minutes(1);
+    let resolve = |window: BenchmarkWindow| window.resolve(now, kline_duration);
+
+    assert_eq!(
+        resolve(BenchmarkWindow::RelativeDays(30)),
+        (Some(now - Duration::days(30)), now)
+    );
+    assert_eq!(
+        resolve(BenchmarkWindow::RelativeBars(5_000)),
+        (Some(now - Duration::minutes(5_000)), now)
+    );
+
+    // absolute windows are kept as set, missing end standing for now
+    let start = now - Duration::days(2);
+    let end = now - Duration::days(1);
+    assert_eq!(
+        resolve(BenchmarkWindow::Absolute(Some(start), Some(end))),
+        (Some(start), end)
+    );
+    assert_eq!(resolve(BenchmarkWindow::default()), (None, now));
+
+    let window: BenchmarkWindow = serde_json::from_str(r#"{"RelativeBars":5000}"#).unwrap();
+    assert_eq!(window, BenchmarkWindow::RelativeBars(5_000));
+}
@@ -7,4 +7,7 @@ pub enum TradingDataUpdate {
     None,
     Initial(DataFrame),
     Market(DataFrame),
+    /// Emitted by trader exchanges once their websocket is (re)subscribed, so position, balance
+    /// and executions missed while the socket was down are refetched.
+    ExchangeRecovery,
 }
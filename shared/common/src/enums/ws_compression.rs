@@ -0,0 +1,33 @@
+use flate2::read::{DeflateDecoder, GzDecoder};
+use glow_error::GlowError;
+use std::io::Read;
+use tokio_tungstenite::tungstenite::Message;
+
+/// How exchange compresses binary websocket frames.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub enum WsCompression {
+    /// frames are sent as text, and binary ones are handled as they are.
+    #[default]
+    None,
+    /// binary frames are gzip compressed JSON.
+    Gzip,
+    /// binary frames are raw deflate compressed JSON.
+    Deflate,
+}
+
+impl WsCompression {
+    /// Inflates binary `message` into a text one, so that it's parsed as exchange's text frames.
+    /// Other messages, or any of them when there's no compression, are returned as they are.
+    pub fn decode_message(&self, message: Message) -> Result<Message, GlowError> {
+        let Message::Binary(payload) = message else {
+            return Ok(message);
+        };
+        let mut json = String::new();
+        match self {
+            Self::None => return Ok(Message::Binary(payload)),
+            Self::Gzip => GzDecoder::new(payload.as_slice()).read_to_string(&mut json)?,
+            Self::Deflate => DeflateDecoder::new(payload.as_slice()).read_to_string(&mut json)?,
+        };
+        Ok(Message::Text(json))
+    }
+}
@@ -1,10 +1,12 @@
-use crate::{functions::get_days_between, structs::Symbol};
+use crate::{
+    enums::exchange_environment::ExchangeEnvironment, functions::get_days_between, structs::Symbol,
+};
 use chrono::{NaiveDate, NaiveDateTime};
 use glow_error::{assert_or_error, GlowError};
 use polars::prelude::*;
 use std::{
     env,
-    fs::{create_dir, create_dir_all, metadata, File},
+    fs::{create_dir, create_dir_all, metadata, File, OpenOptions},
     path::{Path, PathBuf},
 };
 
@@ -138,6 +140,33 @@ pub fn get_tick_data_csv_path(
     path_buf
 }
 
+pub fn get_gathered_klines_csv_path(date: NaiveDate, data_provider_exchange_name: &str) -> PathBuf {
+    let mut path_buf = PathBuf::from("data/gathered");
+    path_buf.push(data_provider_exchange_name);
+    path_buf.push(date.format("%Y").to_string());
+    path_buf.push(date.format("%m").to_string());
+    path_buf.push(format!("{}.csv", date.format("%d")));
+    path_buf
+}
+
+/// Appends `df` rows to csv at `file_path`, creating it, along with its header, if absent.
+pub fn append_df_to_csv(df: &DataFrame, file_path: PathBuf) -> Result<(), GlowError> {
+    let file_exists = file_path.is_file();
+    if let Some(folder_path) = file_path.parent() {
+        create_dir_all(folder_path)?;
+    }
+    let output_file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&file_path)?;
+    let mut df = df.clone();
+    CsvWriter::new(output_file)
+        .has_header(!file_exists)
+        .with_float_precision(Some(6))
+        .finish(&mut df)?;
+    Ok(())
+}
+
 /// Tries to load ticks data between an interval. If a file is absent, push the NaiveDates of absent dates
 pub fn load_interval_tick_dataframe(
     start_datetime: NaiveDateTime,
@@ -197,11 +226,10 @@ fn create_file_directories(file_path: &str) -> Result<(), GlowError> {
     Ok(())
 }
 
-pub fn get_current_env_log_path() -> String {
-    let env = env::var("ENV_NAME").unwrap().to_uppercase();
-    if env == "PROD".to_string() {
-        "data/journals".to_string()
-    } else {
-        "data/test".to_string()
+/// Journals are kept apart per exchange environment, so that testnet runs don't mix with mainnet ones.
+pub fn get_environment_log_path(environment: ExchangeEnvironment) -> String {
+    match environment {
+        ExchangeEnvironment::Mainnet => "data/journals".to_string(),
+        ExchangeEnvironment::Testnet => "data/test".to_string(),
     }
 }
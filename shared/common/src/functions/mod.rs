@@ -14,10 +14,12 @@ use std::{
 
 pub mod csv;
 pub mod performance;
+#[cfg(test)]
+mod tests;
 
 use crate::{
     constants::{NANOS_IN_SECOND, SECONDS_IN_MIN},
-    enums::signal_category::SignalCategory,
+    enums::{kline_gap_handling::KlineGapHandling, signal_category::SignalCategory},
     r#static::SYMBOLS_MAP,
     structs::{Symbol, TickData},
 };
@@ -280,6 +282,54 @@ pub fn coerce_df_to_schema(df: DataFrame, schema: &Schema) -> Result<DataFrame,
     Ok(result_df)
 }
 
+/// Selects `klines_df` start time, `unique_symbols` OHLC and gap flag columns, setting the
+/// remaining `trading_data` columns as null, so that it can be stacked under `trading_data` regardless of
+/// other columns it brings along. Errors naming every kline column which is missing from either
+/// frame, or whose dtypes don't match.
+pub fn normalize_klines_to_trading_data(
+    trading_data: &DataFrame,
+    klines_df: &DataFrame,
+    unique_symbols: &[&Symbol],
+) -> Result<DataFrame, GlowError> {
+    let mut kline_columns = vec!["start_time"];
+    for symbol in unique_symbols {
+        let (open_col, high_col, low_col, close_col) = symbol.get_ohlc_cols();
+        kline_columns.extend([open_col, high_col, low_col, close_col]);
+    }
+
+    let trading_data_schema = trading_data.schema();
+    let klines_schema = klines_df.schema();
+    let mismatches = kline_columns
+        .iter()
+        .filter_map(
+            |column| match (trading_data_schema.get(column), klines_schema.get(column)) {
+                (Some(expected), Some(dtype)) if expected == dtype => None,
+                (Some(expected), Some(dtype)) => {
+                    Some(format!("{} ({} expected, got {})", column, expected, dtype))
+                }
+                (None, _) => Some(format!("{} (missing from trading data)", column)),
+                (_, None) => Some(format!("{} (missing from klines)", column)),
+            },
+        )
+        .collect::<Vec<String>>();
+    if !mismatches.is_empty() {
+        return Err(GlowError::new(
+            String::from("Incompatible Klines Schema"),
+            format!(
+                "klines can't be stacked under trading data, mismatched columns: {}",
+                mismatches.join(", ")
+            ),
+        ));
+    }
+
+    // gap flags are kept, if already set
+    if klines_schema.get("is_gap") == Some(&DataType::Boolean) {
+        kline_columns.push("is_gap");
+    }
+    let klines_df = klines_df.select(kline_columns)?;
+    coerce_df_to_schema(klines_df, &trading_data_schema)
+}
+
 pub fn filter_df_timestamps_to_lf(
     df: DataFrame,
     start_datetime: NaiveDateTime,
@@ -360,6 +410,72 @@ pub fn downsample_tick_lf_to_kline_duration(
     Ok(resampled_data)
 }
 
+/// Inserts bars missing from sorted `kline_df`, according to `gap_handling`, flagging them
+/// at `is_gap` column. Bars already flagged as gaps remain so.
+///
+/// Besides OHLC, inserted bars' columns are null. If gaps are kept, `kline_df` is returned as is.
+pub fn fill_kline_gaps(
+    kline_df: DataFrame,
+    unique_symbols: &Vec<&Symbol>,
+    kline_duration: Duration,
+    gap_handling: KlineGapHandling,
+) -> Result<DataFrame, GlowError> {
+    let bar_duration_ms = kline_duration.num_milliseconds();
+    if gap_handling == KlineGapHandling::Keep || kline_df.height() < 2 || bar_duration_ms <= 0 {
+        return Ok(kline_df);
+    }
+    let start_time_dtype = kline_df.column("start_time")?.dtype().clone();
+    let start_timestamps = kline_df
+        .column("start_time")?
+        .timestamp(TimeUnit::Milliseconds)?;
+    let (Some(first_timestamp), Some(last_timestamp)) =
+        (start_timestamps.min(), start_timestamps.max())
+    else {
+        return Ok(kline_df);
+    };
+    let expected_bars = ((last_timestamp - first_timestamp) / bar_duration_ms + 1) as usize;
+    if expected_bars <= kline_df.height() {
+        return Ok(kline_df);
+    }
+
+    let start_times = (0..expected_bars as i64)
+        .map(|bar| first_timestamp + bar * bar_duration_ms)
+        .collect::<Vec<i64>>();
+    let start_times_df = DataFrame::new(vec![Series::new("start_time", start_times)])?
+        .lazy()
+        .with_column(col("start_time").cast(start_time_dtype));
+    let is_gap = if kline_df.schema().contains("is_gap") {
+        col("is_gap").fill_null(lit(false))
+    } else {
+        lit(false).alias("is_gap")
+    };
+    let kline_lf = kline_df.lazy().with_column(is_gap);
+    let mut filled_lf = start_times_df
+        .left_join(kline_lf, "start_time", "start_time")
+        .with_column(col("is_gap").fill_null(lit(true)));
+
+    if gap_handling == KlineGapHandling::ForwardFill {
+        for symbol in unique_symbols {
+            let (open_col, high_col, low_col, close_col) = symbol.get_ohlc_cols();
+            let last_close = col(close_col).forward_fill(None);
+            let fill_with_last_close = |price_col: &str| {
+                when(col("is_gap"))
+                    .then(last_close.clone())
+                    .otherwise(col(price_col))
+                    .alias(price_col)
+            };
+            filled_lf = filled_lf.with_columns([
+                fill_with_last_close(open_col),
+                fill_with_last_close(high_col),
+                fill_with_last_close(low_col),
+                fill_with_last_close(close_col),
+            ]);
+        }
+    }
+
+    Ok(filled_lf.collect()?)
+}
+
 pub fn get_days_between(
     start_datetime: NaiveDateTime,
     end_datetime: NaiveDateTime,
@@ -549,6 +665,20 @@ pub fn get_trading_columns_values(
     ))
 }
 
+/// (open fees, close fees, funding fees) columns values
+pub type FeeColumnsValues = (Vec<Option<f64>>, Vec<Option<f64>>, Vec<Option<f64>>);
+
+/// Gets fee breakdown columns values, i.e. open fees, close fees and funding fees.
+pub fn get_fee_columns_values(df: &DataFrame) -> Result<FeeColumnsValues, GlowError> {
+    let series_binding = df.columns(["open_fees", "close_fees", "funding_fees"])?;
+
+    let mut series = series_binding.iter();
+    let open_fees: Vec<Option<f64>> = series.next().unwrap().f64()?.into_iter().collect();
+    let close_fees: Vec<Option<f64>> = series.next().unwrap().f64()?.into_iter().collect();
+    let funding_fees: Vec<Option<f64>> = series.next().unwrap().f64()?.into_iter().collect();
+    Ok((open_fees, close_fees, funding_fees))
+}
+
 pub fn check_last_index_for_signal(
     trading_data_df: &DataFrame,
     signal_category: SignalCategory,
@@ -677,3 +807,23 @@ pub fn closest_multiple_below(of: f64, to: f64) -> f64 {
     let floored = quotient.floor();
     floored * of
 }
+
+/// Tolerance around `threshold`, scaled by its magnitude when above 1, so that `epsilon` is
+/// relative for prices and absolute for returns.
+fn get_threshold_tolerance(threshold: f64, epsilon: f64) -> f64 {
+    epsilon * threshold.abs().max(1.0)
+}
+
+/// Whether `value` reached `threshold` from above, within `epsilon` tolerance.
+pub fn is_at_or_below(value: f64, threshold: f64, epsilon: f64) -> bool {
+    value <= threshold + get_threshold_tolerance(threshold, epsilon)
+}
+
+/// Whether `value` reached `threshold` from below, within `epsilon` tolerance.
+pub fn is_at_or_above(value: f64, threshold: f64, epsilon: f64) -> bool {
+    value >= threshold - get_threshold_tolerance(threshold, epsilon)
+}
+
+pub fn is_at_threshold(value: f64, threshold: f64, epsilon: f64) -> bool {
+    (value - threshold).abs() <= get_threshold_tolerance(threshold, epsilon)
+}
@@ -0,0 +1,254 @@
+use super::{
+    csv::{append_df_to_csv, load_csv},
+    downsample_tick_lf_to_kline_duration, fill_kline_gaps, normalize_klines_to_trading_data,
+};
+use crate::{enums::kline_gap_handling::KlineGapHandling, structs::TradingSettings};
+use chrono::Duration;
+use polars::prelude::*;
+use std::{env::temp_dir, fs::remove_file};
+
+fn get_kline_df_missing_two_bars() -> DataFrame {
+    let traded_symbol = TradingSettings::default().get_traded_symbol();
+    let (open_col, high_col, low_col, close_col) = traded_symbol.get_ohlc_cols();
+    df!(
+        "start_time" => [0_i64, 60_000, 240_000],
+        open_col => [100.0, 101.0, 104.0],
+        high_col => [102.0, 103.0, 106.0],
+        low_col => [99.0, 100.0, 103.0],
+        close_col => [101.0, 102.0, 105.0]
+    )
+    .unwrap()
+    .lazy()
+    .with_column(col("start_time").cast(DataType::Datetime(TimeUnit::Milliseconds, None)))
+    .collect()
+    .unwrap()
+}
+
+#[test]
+fn test_fill_kline_gaps_forward_fills_missing_bars_with_last_close() {
+    let trading_settings = TradingSettings::default();
+    let unique_symbols = trading_settings.get_unique_symbols();
+    let (open_col, _, low_col, close_col) = trading_settings.get_traded_symbol().get_ohlc_cols();
+
+    let df = fill_kline_gaps(
+        get_kline_df_missing_two_bars(),
+        &unique_symbols,
+        Duration::minutes(1),
+        KlineGapHandling::ForwardFill,
+    )
+    .unwrap();
+
+    assert_eq!(df.height(), 5);
+    let gaps: Vec<Option<bool>> = df
+        .column("is_gap")
+        .unwrap()
+        .bool()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(
+        gaps,
+        vec![
+            Some(false),
+            Some(false),
+            Some(true),
+            Some(true),
+            Some(false)
+        ]
+    );
+    let opens: Vec<Option<f64>> = df
+        .column(open_col)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(
+        opens,
+        vec![
+            Some(100.0),
+            Some(101.0),
+            Some(102.0),
+            Some(102.0),
+            Some(104.0)
+        ]
+    );
+    let lows: Vec<Option<f64>> = df
+        .column(low_col)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(lows[3], Some(102.0));
+    let closes: Vec<Option<f64>> = df
+        .column(close_col)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(closes[4], Some(105.0));
+}
+
+#[test]
+fn test_fill_kline_gaps_inserts_null_bars_or_keeps_gaps() {
+    let trading_settings = TradingSettings::default();
+    let unique_symbols = trading_settings.get_unique_symbols();
+    let (_, _, _, close_col) = trading_settings.get_traded_symbol().get_ohlc_cols();
+
+    let df = fill_kline_gaps(
+        get_kline_df_missing_two_bars(),
+        &unique_symbols,
+        Duration::minutes(1),
+        KlineGapHandling::Nulls,
+    )
+    .unwrap();
+    let closes: Vec<Option<f64>> = df
+        .column(close_col)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(
+        closes,
+        vec![Some(101.0), Some(102.0), None, None, Some(105.0)]
+    );
+
+    let df = fill_kline_gaps(
+        get_kline_df_missing_two_bars(),
+        &unique_symbols,
+        Duration::minutes(1),
+        KlineGapHandling::Keep,
+    )
+    .unwrap();
+    assert_eq!(df.height(), 3);
+}
+
+#[test]
+fn test_normalized_klines_stack_under_trading_data() {
+    let trading_settings = TradingSettings::default();
+    let unique_symbols = trading_settings.get_unique_symbols();
+    let (_, _, _, close_col) = trading_settings.get_traded_symbol().get_ohlc_cols();
+    let mut trading_data = get_kline_df_missing_two_bars().slice(0, 2);
+    trading_data
+        .with_column(Series::new("ema", [1.0, 2.0]))
+        .unwrap();
+    trading_data
+        .with_column(Series::new("position", [0_i32, 1]))
+        .unwrap();
+    // incoming klines bring along a column unknown to trading data, and lack its other ones
+    let mut klines_df = get_kline_df_missing_two_bars().slice(2, 1);
+    klines_df
+        .with_column(Series::new("pending_ema", [3.0]))
+        .unwrap();
+
+    let klines_df =
+        normalize_klines_to_trading_data(&trading_data, &klines_df, &unique_symbols).unwrap();
+    assert_eq!(klines_df.schema(), trading_data.schema());
+    assert_eq!(klines_df.column("ema").unwrap().null_count(), 1);
+    let stacked_df = trading_data.vstack(&klines_df).unwrap();
+    let closes = stacked_df.column(close_col).unwrap().f64().unwrap();
+    assert_eq!(closes.get(2), Some(105.0));
+}
+
+#[test]
+fn test_normalizing_klines_names_mismatched_columns() {
+    let trading_settings = TradingSettings::default();
+    let unique_symbols = trading_settings.get_unique_symbols();
+    let (open_col, _, _, close_col) = trading_settings.get_traded_symbol().get_ohlc_cols();
+    let trading_data = get_kline_df_missing_two_bars();
+    let klines_df = get_kline_df_missing_two_bars()
+        .lazy()
+        .with_column(col(close_col).cast(DataType::Float32))
+        .drop_columns([open_col])
+        .collect()
+        .unwrap();
+
+    let error =
+        normalize_klines_to_trading_data(&trading_data, &klines_df, &unique_symbols).unwrap_err();
+    assert!(error
+        .description
+        .contains(&format!("{} (missing from klines)", open_col)));
+    assert!(error
+        .description
+        .contains(&format!("{} (f64 expected, got f32)", close_col)));
+}
+
+#[test]
+fn test_downsampling_already_coarse_klines() {
+    let trading_settings = TradingSettings::default();
+    let unique_symbols = trading_settings.get_unique_symbols();
+    let (open_col, high_col, low_col, close_col) =
+        trading_settings.get_traded_symbol().get_ohlc_cols();
+    // natively fetched 5 minutes klines
+    let kline_df = df!(
+        "start_time" => [0_i64, 300_000, 600_000, 900_000, 1_200_000, 1_500_000],
+        open_col => [100.0, 101.0, 102.0, 103.0, 104.0, 105.0],
+        high_col => [102.0, 104.0, 103.0, 106.0, 105.0, 107.0],
+        low_col => [99.0, 100.0, 98.0, 102.0, 103.0, 104.0],
+        close_col => [101.0, 102.0, 103.0, 104.0, 105.0, 106.0]
+    )
+    .unwrap()
+    .lazy()
+    .with_column(col("start_time").cast(DataType::Datetime(TimeUnit::Milliseconds, None)))
+    .sort("start_time", SortOptions::default());
+    let downsample = |kline_duration: Duration| {
+        downsample_tick_lf_to_kline_duration(
+            &unique_symbols,
+            kline_duration,
+            kline_df.clone(),
+            ClosedWindow::Left,
+            None,
+        )
+        .unwrap()
+        .collect()
+        .unwrap()
+    };
+
+    // klines already at kline duration are kept as they are
+    let same_duration_df = downsample(Duration::minutes(5));
+    assert!(same_duration_df
+        .select(["start_time", open_col, high_col, low_col, close_col])
+        .unwrap()
+        .frame_equal(&kline_df.clone().collect().unwrap()));
+
+    let coarser_df = downsample(Duration::minutes(15));
+    assert_eq!(coarser_df.height(), 2);
+    let get_values = |column: &str| -> Vec<f64> {
+        coarser_df
+            .column(column)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    };
+    assert_eq!(get_values(open_col), vec![100.0, 103.0]);
+    assert_eq!(get_values(high_col), vec![104.0, 107.0]);
+    assert_eq!(get_values(low_col), vec![98.0, 102.0]);
+    assert_eq!(get_values(close_col), vec![103.0, 106.0]);
+}
+
+#[test]
+fn test_appending_df_to_csv_writes_header_only_once() {
+    let file_path = temp_dir().join("glow_append_df_to_csv.csv");
+    let _ = remove_file(&file_path);
+    let first_df = df!["close" => &[1.0, 2.0]].unwrap();
+    let second_df = df!["close" => &[3.0]].unwrap();
+
+    append_df_to_csv(&first_df, file_path.clone()).unwrap();
+    append_df_to_csv(&second_df, file_path.clone()).unwrap();
+
+    let schema = Schema::from_iter(vec![Field::new("close", DataType::Float64)]);
+    let loaded_df = load_csv(&file_path, &schema).unwrap();
+    let closes: Vec<Option<f64>> = loaded_df
+        .column("close")
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(closes, vec![Some(1.0), Some(2.0), Some(3.0)]);
+}
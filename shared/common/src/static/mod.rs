@@ -1,9 +1,23 @@
-use crate::{enums::symbol_id::SymbolId, structs::Symbol};
+use crate::{
+    enums::symbol_id::SymbolId,
+    structs::{Metrics, OrderBookDepth, Symbol},
+};
 use phf::{self, phf_map, Map};
-use std::sync::LazyLock;
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, RwLock},
+};
 
 pub const DEFAULT_SYMBOL: &'static str = "BTCUSDT";
 
+/// Trader metrics, shared by exchanges' websocket handlers.
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::default);
+
+/// Latest order book depth of each symbol, keyed by its name, as streamed live by data
+/// providers supporting depth streams. Historical klines carry no depth, so it's empty unless live.
+pub static ORDER_BOOK_DEPTHS: LazyLock<RwLock<HashMap<String, OrderBookDepth>>> =
+    LazyLock::new(Default::default);
+
 pub static SYMBOLS_LIST: LazyLock<[&'static str; 5]> =
     LazyLock::new(|| [DEFAULT_SYMBOL, "ETHUSDT", "SOLUSDT", "ARBUSDT", "LINKUSDT"]);
 
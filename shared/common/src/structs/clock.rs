@@ -0,0 +1,45 @@
+use crate::{functions::current_timestamp_ms, traits::clock::Clock};
+use chrono::Duration;
+use std::sync::{
+    atomic::{AtomicI64, Ordering},
+    Arc,
+};
+
+/// Clock reading system time, with full seconds.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> i64 {
+        current_timestamp_ms()
+    }
+}
+
+/// Clock standing still at a set timestamp, until set or advanced. Clones share their time.
+#[derive(Clone, Debug, Default)]
+pub struct MockClock {
+    timestamp_ms: Arc<AtomicI64>,
+}
+
+impl MockClock {
+    pub fn new(timestamp_ms: i64) -> Self {
+        Self {
+            timestamp_ms: Arc::new(AtomicI64::new(timestamp_ms)),
+        }
+    }
+
+    pub fn set(&self, timestamp_ms: i64) {
+        self.timestamp_ms.store(timestamp_ms, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.timestamp_ms
+            .fetch_add(duration.num_milliseconds(), Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_ms(&self) -> i64 {
+        self.timestamp_ms.load(Ordering::SeqCst)
+    }
+}
@@ -1,14 +1,18 @@
 use super::Symbol;
+use crate::{enums::contract_kind::ContractKind, functions::count_decimal_places};
 use chrono::{Duration, NaiveDateTime, NaiveTime};
+use glow_error::GlowError;
 
 #[derive(Clone, Debug)]
 pub struct Contract {
     pub available_since: NaiveDateTime,
-    _funding_interval: Duration,
+    pub funding_interval: Duration,
     pub funding_rate: f64,
+    pub kind: ContractKind,
     pub max_leverage: f64,
     pub maximum_order_sizes: (f64, f64), // (market, limit) in units
     pub minimum_order_size: f64, // in units
+    pub min_notional: Option<f64>, // minimum order value, in USDT
     pub next_funding: Option<NaiveTime>,
     pub symbol: &'static Symbol,
     pub tick_size: f64, // in USDT
@@ -28,17 +32,29 @@ impl Contract {
     ) -> Self {
         Contract {
             available_since,
-            _funding_interval: funding_interval,
+            funding_interval,
             funding_rate,
+            kind: ContractKind::default(),
             max_leverage,
             maximum_order_sizes,
             minimum_order_size,
+            min_notional: None,
             next_funding,
             symbol,
             tick_size,
         }
     }
 
+    pub fn with_kind(mut self, kind: ContractKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn with_min_notional(mut self, min_notional: Option<f64>) -> Self {
+        self.min_notional = min_notional;
+        self
+    }
+
     pub fn update_next_funding(&mut self, _time: NaiveTime) {
         todo!("implement this");
         // self.next_funding = Some(date_time);
@@ -47,4 +63,34 @@ impl Contract {
     pub fn tick_data_decimal_places(&self) -> usize {
         self.tick_size.to_string().split('.').last().unwrap().len()
     }
+
+    /// Rounds `price` to the nearest multiple of contract's tick size.
+    pub fn round_price_to_tick(&self, price: f64) -> f64 {
+        let steps = (price / self.tick_size).round();
+        round_to_step_decimals(steps * self.tick_size, self.tick_size)
+    }
+
+    /// Rounds `quantity` down to a multiple of contract's quantity step, which is its minimum order size.
+    ///
+    /// Errors if rounded quantity is below contract's minimum order size.
+    pub fn round_qty_to_step(&self, quantity: f64) -> Result<f64, GlowError> {
+        let qty_step = self.minimum_order_size;
+        // tolerance avoids flooring quantities such as 0.3 / 0.1 = 2.9999999999999996
+        let steps = (quantity / qty_step + 1e-9).floor();
+        let rounded_quantity = round_to_step_decimals(steps * qty_step, qty_step);
+        if rounded_quantity < self.minimum_order_size {
+            let error = format!(
+                "{} quantity {} rounds to {}, which is below minimum order size {}",
+                self.symbol.name, quantity, rounded_quantity, self.minimum_order_size
+            );
+            return Err(GlowError::new(String::from("Invalid Order Quantity"), error));
+        }
+        Ok(rounded_quantity)
+    }
+}
+
+/// Strips floating point noise from `value`, by rounding it to `step` decimal places.
+fn round_to_step_decimals(value: f64, step: f64) -> f64 {
+    let multiplier = 10.0_f64.powi(count_decimal_places(step));
+    (value * multiplier).round() / multiplier
 }
@@ -0,0 +1,50 @@
+use glow_error::GlowError;
+use polars::prelude::*;
+
+/// Latest equity of trading data, meant for external monitoring.
+/// Unrealized PnL is only set while a position is open.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EquityPoint {
+    pub timestamp: i64,
+    pub balance: f64,
+    pub unrealized_pnl: f64,
+    pub position: i32,
+}
+
+impl EquityPoint {
+    /// Projects last row of trading data with benchmark/trading columns into an equity point.
+    /// Returns `None` if there's no row, or its balance wasn't computed yet.
+    pub fn from_trading_data(df: &DataFrame) -> Result<Option<Self>, GlowError> {
+        let Some(last_index) = df.height().checked_sub(1) else {
+            return Ok(None);
+        };
+        let Some(balance) = df.column("balance")?.f64()?.get(last_index) else {
+            return Ok(None);
+        };
+        let timestamp = df
+            .column("start_time")?
+            .timestamp(TimeUnit::Milliseconds)?
+            .get(last_index)
+            .unwrap_or_default();
+        let position = df
+            .column("position")?
+            .i32()?
+            .get(last_index)
+            .unwrap_or_default();
+        let unrealized_pnl = if position != 0 {
+            df.column("profit_and_loss")?
+                .f64()?
+                .get(last_index)
+                .unwrap_or_default()
+        } else {
+            0.0
+        };
+
+        Ok(Some(Self {
+            timestamp,
+            balance,
+            unrealized_pnl,
+            position,
+        }))
+    }
+}
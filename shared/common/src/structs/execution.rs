@@ -1,6 +1,7 @@
 use crate::enums::order_type::OrderType;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Execution {
     pub id: String,
     pub order_uuid: String,
@@ -12,6 +13,10 @@ pub struct Execution {
     pub fee_rate: f64,
     pub is_maker: bool,
     pub closed_qty: f64,
+    /// Set for executions standing for closes made out-of-band (e.g. manually or by liquidation)
+    /// at an estimated price, as their actual price is unknown.
+    #[serde(default)]
+    pub is_out_of_band: bool,
 }
 
 impl Execution {
@@ -38,6 +43,7 @@ impl Execution {
             fee_rate,
             is_maker,
             closed_qty,
+            is_out_of_band: false,
         }
     }
 
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+/// Fee rates applied to orders, as fractions of the order notional (e.g. 0.00055 = 0.055%).
+/// Negative rates represent rebates.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FeeModel {
+    pub maker_rate: f64,
+    pub taker_rate: f64,
+}
+
+impl FeeModel {
+    pub fn new(maker_rate: f64, taker_rate: f64) -> Self {
+        Self {
+            maker_rate,
+            taker_rate,
+        }
+    }
+}
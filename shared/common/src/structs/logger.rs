@@ -0,0 +1,74 @@
+use crate::{
+    enums::{log_format::LogFormat, log_level::LogLevel},
+    functions::{current_datetime, current_timestamp_ms},
+};
+use serde::Serialize;
+use serde_json::{to_string as to_json_string, to_value, Map, Value};
+
+/// Event to be logged, alongside the fields it carries when logged as JSON.
+#[derive(Clone, Debug)]
+pub struct LogEvent {
+    pub level: LogLevel,
+    pub event_type: &'static str,
+    pub message: String,
+    pub fields: Map<String, Value>,
+}
+
+impl LogEvent {
+    pub fn new(level: LogLevel, event_type: &'static str, message: String) -> Self {
+        Self {
+            level,
+            event_type,
+            message,
+            fields: Map::new(),
+        }
+    }
+
+    pub fn with_field<T: Serialize>(mut self, key: &str, value: T) -> Self {
+        let value = to_value(value).unwrap_or(Value::Null);
+        self.fields.insert(key.to_string(), value);
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Logger {
+    pub level: LogLevel,
+    pub format: LogFormat,
+}
+
+impl Logger {
+    pub fn new(level: LogLevel, format: LogFormat) -> Self {
+        Self { level, format }
+    }
+
+    /// Formats `event` according to logger format, or returns None if it's above logger level.
+    pub fn format_event(&self, event: &LogEvent) -> Option<String> {
+        if event.level == LogLevel::Nothing || event.level > self.level {
+            return None;
+        }
+        match self.format {
+            LogFormat::Text => Some(format!("\n{:?} | {}", current_datetime(), event.message)),
+            LogFormat::Json => {
+                let mut object = Map::new();
+                object.insert("timestamp".to_string(), current_timestamp_ms().into());
+                object.insert(
+                    "level".to_string(),
+                    to_value(event.level).unwrap_or(Value::Null),
+                );
+                object.insert("event".to_string(), event.event_type.into());
+                object.insert("message".to_string(), event.message.clone().into());
+                for (key, value) in &event.fields {
+                    object.insert(key.clone(), value.clone());
+                }
+                to_json_string(&object).ok()
+            }
+        }
+    }
+
+    pub fn log(&self, event: LogEvent) {
+        if let Some(line) = self.format_event(&event) {
+            println!("{}", line);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use super::TradingSettings;
+use chrono::{NaiveDate, NaiveDateTime};
+
+/// Loss limit which halted opening new positions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LossLimit {
+    ConsecutiveLosses(u32),
+    DailyLoss(f64), // fraction of equity lost over the day
+}
+
+/// Tracks closed trades results against trading settings' loss limits, i.e.
+/// `max_consecutive_losses` and `daily_loss_limit_pct`.
+///
+/// Daily loss resets at UTC midnight, whereas consecutive losses are only reset by a winning
+/// trade or by `reset`, as no trades are opened while they're over their limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LossCircuitBreaker {
+    pub consecutive_losses: u32,
+    pub daily_pnl: f64,
+    pub day_start_equity: f64,
+    pub day: Option<NaiveDate>, // UTC day daily results refer to
+}
+
+impl LossCircuitBreaker {
+    /// Records a trade closed at `timestamp` (in milliseconds) with `pnl`, leaving `equity`.
+    pub fn record_closed_trade(&mut self, pnl: f64, equity: f64, timestamp: i64) {
+        let day = get_utc_day(timestamp);
+        if self.day != day {
+            self.day = day;
+            self.daily_pnl = 0.0;
+            self.day_start_equity = equity - pnl;
+        }
+        self.daily_pnl += pnl;
+        self.consecutive_losses = if pnl < 0.0 {
+            self.consecutive_losses + 1
+        } else {
+            0
+        };
+    }
+
+    /// Gets loss limit reached as of `timestamp` (in milliseconds), if any.
+    pub fn get_reached_limit(
+        &self,
+        trading_settings: &TradingSettings,
+        timestamp: i64,
+    ) -> Option<LossLimit> {
+        if let Some(max_consecutive_losses) = trading_settings.max_consecutive_losses {
+            if self.consecutive_losses >= max_consecutive_losses {
+                return Some(LossLimit::ConsecutiveLosses(self.consecutive_losses));
+            }
+        }
+        let daily_loss_limit = trading_settings.daily_loss_limit_pct?;
+        if self.day != get_utc_day(timestamp) || self.day_start_equity <= 0.0 {
+            return None;
+        }
+        let daily_loss = -self.daily_pnl / self.day_start_equity;
+        if daily_loss >= daily_loss_limit {
+            return Some(LossLimit::DailyLoss(daily_loss));
+        }
+        None
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+fn get_utc_day(timestamp: i64) -> Option<NaiveDate> {
+    NaiveDateTime::from_timestamp_millis(timestamp).map(|datetime| datetime.date())
+}
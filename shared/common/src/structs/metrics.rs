@@ -0,0 +1,145 @@
+use crate::enums::signal_category::SignalCategory;
+use glow_error::GlowError;
+use prometheus::{
+    Gauge, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::net::SocketAddr;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    spawn,
+    task::JoinHandle,
+};
+
+/// Prometheus gauges and counters of the running trader, exposed at `serve` address.
+///
+/// Updating metrics is cheap, so they can be updated regardless, only being exposed once served.
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    pub open_positions: IntGauge,
+    pub balance: Gauge,
+    pub trades: IntCounter,
+    pub last_signal: IntGaugeVec,
+    pub ws_reconnects: IntCounterVec,
+    pub ws_dropped_frames: IntCounterVec,
+    pub order_errors: IntCounter,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new().expect("metrics to have unique names")
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self, GlowError> {
+        let registry = Registry::new();
+        let open_positions = IntGauge::new("glow_open_positions", "Currently open positions")?;
+        let balance = Gauge::new("glow_balance", "Current wallet balance, in USDT")?;
+        let trades = IntCounter::new("glow_trades_total", "Trades opened")?;
+        let last_signal = IntGaugeVec::new(
+            Opts::new("glow_last_signal", "Last emitted signal, set to 1"),
+            &["signal"],
+        )?;
+        let ws_reconnects = IntCounterVec::new(
+            Opts::new("glow_ws_reconnects_total", "Websocket reconnections"),
+            &["exchange"],
+        )?;
+        let ws_dropped_frames = IntCounterVec::new(
+            Opts::new(
+                "glow_ws_dropped_frames_total",
+                "Websocket frames dropped, either unparseable or unhandled",
+            ),
+            &["exchange", "reason"],
+        )?;
+        let order_errors = IntCounter::new("glow_order_errors_total", "Failed order requests")?;
+        registry.register(Box::new(open_positions.clone()))?;
+        registry.register(Box::new(balance.clone()))?;
+        registry.register(Box::new(trades.clone()))?;
+        registry.register(Box::new(last_signal.clone()))?;
+        registry.register(Box::new(ws_reconnects.clone()))?;
+        registry.register(Box::new(ws_dropped_frames.clone()))?;
+        registry.register(Box::new(order_errors.clone()))?;
+
+        Ok(Self {
+            registry,
+            open_positions,
+            balance,
+            trades,
+            last_signal,
+            ws_reconnects,
+            ws_dropped_frames,
+            order_errors,
+        })
+    }
+
+    /// Sets `signal` as the only last signal.
+    pub fn set_last_signal(&self, signal: SignalCategory) {
+        self.last_signal.reset();
+        self.last_signal
+            .with_label_values(&[signal.get_column()])
+            .set(1);
+    }
+
+    pub fn inc_ws_reconnects(&self, exchange: &str) {
+        self.ws_reconnects.with_label_values(&[exchange]).inc();
+    }
+
+    /// Counts a frame dropped by `exchange` websocket, for `reason`, such as `unparseable`.
+    pub fn inc_ws_dropped_frames(&self, exchange: &str, reason: &str) {
+        self.ws_dropped_frames
+            .with_label_values(&[exchange, reason])
+            .inc();
+    }
+
+    /// Encodes every metric in Prometheus text exposition format.
+    pub fn encode(&self) -> Result<String, GlowError> {
+        let encoded = TextEncoder::new().encode_to_string(&self.registry.gather())?;
+        Ok(encoded)
+    }
+
+    /// Serves encoded metrics at `address`, answering any request with them.
+    pub fn serve(&self, address: SocketAddr) -> JoinHandle<()> {
+        let metrics = self.clone();
+        spawn(async move {
+            let listener = match TcpListener::bind(address).await {
+                Ok(listener) => listener,
+                Err(error) => {
+                    println!("Metrics::serve bind error {:?}", error);
+                    return;
+                }
+            };
+            loop {
+                let mut stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(error) => {
+                        println!("Metrics::serve accept error {:?}", error);
+                        continue;
+                    }
+                };
+                let metrics = metrics.clone();
+                spawn(async move {
+                    let mut request = [0; 1024];
+                    if stream.read(&mut request).await.is_err() {
+                        return;
+                    }
+                    let response = match metrics.encode() {
+                        Ok(body) => format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                            body.len(),
+                            body
+                        ),
+                        Err(error) => {
+                            println!("Metrics::serve encode error {:?}", error);
+                            String::from(
+                                "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                            )
+                        }
+                    };
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        })
+    }
+}
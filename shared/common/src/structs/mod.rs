@@ -1,15 +1,39 @@
 mod behavior_subject;
 pub use behavior_subject::*;
 
+mod clock;
+pub use clock::*;
+
 mod contract;
 pub use contract::*;
 
+mod equity_point;
+pub use equity_point::*;
+
 mod execution;
 pub use execution::*;
 
+mod fee_model;
+pub use fee_model::*;
+
+mod logger;
+pub use logger::*;
+
+mod loss_circuit_breaker;
+pub use loss_circuit_breaker::*;
+
+mod metrics;
+pub use metrics::*;
+
 mod order;
 pub use order::*;
 
+mod order_book_depth;
+pub use order_book_depth::*;
+
+mod position_snapshot;
+pub use position_snapshot::*;
+
 mod tick_data;
 pub use tick_data::*;
 
@@ -19,8 +43,20 @@ pub use trade::*;
 mod trading_settings;
 pub use trading_settings::*;
 
+mod trailing_stop;
+pub use trailing_stop::*;
+
+mod signal_priority;
+pub use signal_priority::*;
+
 mod statistics;
 pub use statistics::*;
 
 mod symbol;
 pub use symbol::*;
+
+mod ticker;
+pub use ticker::*;
+
+#[cfg(test)]
+mod tests;
@@ -1,10 +1,13 @@
 use crate::enums::{
-    order_status::OrderStatus, order_type::OrderType, side::Side, time_in_force::TimeInForce,
+    contract_kind::ContractKind, order_status::OrderStatus, order_type::OrderType, side::Side,
+    time_in_force::TimeInForce,
 };
 
-use super::Execution;
+use super::{Contract, Execution};
+use glow_error::GlowError;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Order {
     pub avg_price: Option<f64>,
     // TODO: remove this
@@ -29,6 +32,46 @@ pub struct Order {
     pub uuid: String,
 }
 
+/// Values an order is amended to, each left as `None` when it isn't to be changed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OrderAmendment {
+    pub units: Option<f64>,
+    pub price: Option<f64>,
+    pub stop_loss_price: Option<f64>,
+    pub take_profit_price: Option<f64>,
+}
+
+impl OrderAmendment {
+    /// Rounds units to `contract`'s quantity step and prices to its tick size, as exchange would.
+    pub fn new(
+        contract: &Contract,
+        units: Option<f64>,
+        price: Option<f64>,
+        stop_loss_price: Option<f64>,
+        take_profit_price: Option<f64>,
+    ) -> Result<Self, GlowError> {
+        let units = units
+            .map(|units| contract.round_qty_to_step(units))
+            .transpose()?;
+        let round_price =
+            |price: Option<f64>| price.map(|price| contract.round_price_to_tick(price));
+        Ok(Self {
+            units,
+            price: round_price(price),
+            stop_loss_price: round_price(stop_loss_price),
+            take_profit_price: round_price(take_profit_price),
+        })
+    }
+
+    /// Whether amendment has nothing to change, so that it needn't be sent.
+    pub fn is_empty(&self) -> bool {
+        self.units.is_none()
+            && self.price.is_none()
+            && self.stop_loss_price.is_none()
+            && self.take_profit_price.is_none()
+    }
+}
+
 // TODO: add initial_margin, order_cost and bankruptcy_price
 impl Order {
     // uuid: String,
@@ -94,54 +137,58 @@ impl Order {
         }
     }
 
-    fn get_order_initial_margin(&self) -> Option<f64> {
-        let order_value = self.get_order_value();
-        if order_value.is_none() {
+    /// Gets order cost, in margin currency of `contract_kind`, i.e. initial margin, plus open fee,
+    /// plus close fee at bankruptcy price.
+    pub fn get_order_cost(&self, contract_kind: ContractKind) -> Option<f64> {
+        let open_price = self.avg_price?;
+        if self.side == Side::None {
             return None;
         }
-        let order_value = order_value.unwrap();
-        Some(order_value / self.leverage_factor)
+        let (_, order_cost) = contract_kind.calculate_order_cost(
+            self.side,
+            self.units,
+            open_price,
+            self.leverage_factor,
+            self.taker_fee_rate,
+        );
+        Some(order_cost)
     }
 
-    pub fn get_order_cost(&self) -> Option<f64> {
-        let initial_margin = self.get_order_initial_margin();
-        if initial_margin.is_none() {
-            return None;
-        }
-        let initial_margin = initial_margin.unwrap();
-
-        if self.avg_price.is_none() {
-            return None;
-        }
-        let open_price = self.avg_price.unwrap();
-
-        let bankruptcy_price = self.get_bankruptcy_price();
-        if bankruptcy_price.is_none() {
+    /// Gets price at which order's position loses its whole initial margin, for `contract_kind`.
+    pub fn get_bankruptcy_price(&self, contract_kind: ContractKind) -> Option<f64> {
+        let price = self.avg_price?;
+        if self.side == Side::None {
             return None;
         }
-
-        let bankruptcy_price = bankruptcy_price.unwrap();
-        let open_position_fee = self.units * open_price * self.taker_fee_rate;
-        let close_position_fee = self.units * bankruptcy_price * self.taker_fee_rate;
-
-        Some(initial_margin + open_position_fee + close_position_fee)
+        Some(contract_kind.calculate_bankruptcy_price(self.side, price, self.leverage_factor))
     }
 
-    pub fn get_bankruptcy_price(&self) -> Option<f64> {
-        if self.side == Side::Buy {
-            if self.avg_price.is_none() {
-                return None;
-            }
-            let price = self.avg_price.unwrap();
-            Some(price * (self.leverage_factor - 1.0) / self.leverage_factor)
-        } else if self.side == Side::Sell {
-            if self.avg_price.is_none() {
-                return None;
-            }
-            let price = self.avg_price.unwrap();
-            Some(price * (self.leverage_factor + 1.0) / self.leverage_factor)
-        } else {
-            None
+    /// Drops `amendment` values matching order's current ones, i.e. those within half a
+    /// `contract` quantity step or tick, as exchanges reject amends that don't modify an order.
+    /// Order's price is its `avg_price`, which is its limit price until it's filled.
+    pub fn diff_amendment(&self, amendment: OrderAmendment, contract: &Contract) -> OrderAmendment {
+        let differs = |amended: Option<f64>, current: Option<f64>, step: f64| {
+            amended.filter(|amended| {
+                current.is_none_or(|current| (amended - current).abs() >= step / 2.0)
+            })
+        };
+        OrderAmendment {
+            units: differs(
+                amendment.units,
+                Some(self.units),
+                contract.minimum_order_size,
+            ),
+            price: differs(amendment.price, self.avg_price, contract.tick_size),
+            stop_loss_price: differs(
+                amendment.stop_loss_price,
+                self.stop_loss_price,
+                contract.tick_size,
+            ),
+            take_profit_price: differs(
+                amendment.take_profit_price,
+                self.take_profit_price,
+                contract.tick_size,
+            ),
         }
     }
 
@@ -158,6 +205,11 @@ impl Order {
             .fold(0.0, |acc, execution| acc + execution.qty)
     }
 
+    /// Gets quantity still resting on the book, i.e. which wasn't executed yet.
+    pub fn get_unfilled_quantity(&self) -> f64 {
+        (self.units - self.get_executed_quantity()).max(0.0)
+    }
+
     pub fn get_executed_avg_price(&self) -> f64 {
         let total_qty = self.get_executed_quantity();
 
@@ -174,11 +226,12 @@ impl Order {
         Some(price * self.units)
     }
 
-    pub fn get_executed_order_value(&self) -> f64 {
+    /// Gets executed order value, in margin currency of `contract_kind`.
+    pub fn get_executed_order_value(&self, contract_kind: ContractKind) -> f64 {
         match self.status {
             OrderStatus::Cancelled | OrderStatus::StandBy | OrderStatus::Closed => 0.0,
             _ => self.executions.iter().fold(0.0, |acc, execution| {
-                acc + (execution.qty * execution.price)
+                acc + contract_kind.calculate_notional(execution.qty, execution.price)
             }),
         }
     }
@@ -199,6 +252,63 @@ impl Order {
             .fold(0.0, |acc, execution| acc + execution.closed_qty)
     }
 
+    /// Closes units left to be closed by close order, as if they were executed at `price` by
+    /// `timestamp`. Meant for positions closed out-of-band (e.g. manually or by liquidation),
+    /// whose actual closing executions aren't going to be reported by exchange.
+    pub fn close_out_of_band(&self, price: f64, timestamp: i64) -> Order {
+        let units_left = self.units - self.get_closed_quanitity();
+        let mut execution = Execution::new(
+            format!("{}_out_of_band", self.id),
+            self.uuid.clone(),
+            self.order_type,
+            timestamp,
+            price,
+            units_left,
+            0.0,
+            0.0,
+            self.order_type == OrderType::Limit,
+            units_left,
+        );
+        execution.is_out_of_band = true;
+        let mut closed_order = self.push_executions_if_new(vec![execution]);
+        closed_order.avg_price = Some(closed_order.avg_price.unwrap_or(price));
+        closed_order.updated_at = timestamp;
+        closed_order
+    }
+
+    /// Closes units left to be closed by close order with `executions` that closed the position
+    /// out-of-band, as reported by exchange. Executions already tracked by order are ignored, and
+    /// `None` is returned when the remaining ones don't close all units left.
+    pub fn close_by_executions(&self, executions: Vec<Execution>) -> Option<Order> {
+        let units_left = self.units - self.get_closed_quanitity();
+        let mut closing_executions = vec![];
+        let mut closing_qty = 0.0;
+        for execution in executions {
+            if closing_qty >= units_left
+                || execution.closed_qty <= 0.0
+                || self.executions.iter().any(|e| e.id == execution.id)
+            {
+                continue;
+            }
+            closing_qty += execution.closed_qty;
+            closing_executions.push(execution);
+        }
+        if closing_executions.is_empty() || closing_qty < units_left * (1.0 - 1e-9) {
+            return None;
+        }
+        let updated_at = closing_executions
+            .iter()
+            .map(|execution| execution.timestamp)
+            .max()
+            .unwrap_or(self.updated_at);
+        let mut closed_order = self.clone();
+        closed_order.executions.extend(closing_executions);
+        closed_order.avg_price = Some(closed_order.get_executed_avg_price());
+        closed_order.updated_at = updated_at;
+        closed_order.update_units(closed_order.get_closed_quanitity());
+        Some(closed_order)
+    }
+
     pub fn get_executions_between_interval(
         &self,
         start_timestamp: i64,
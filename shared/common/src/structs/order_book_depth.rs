@@ -0,0 +1,36 @@
+/// Snapshot of the top levels of a symbol's order book, as `(price, quantity)` per level.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderBookDepth {
+    pub symbol: String,
+    pub timestamp: i64,
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
+impl OrderBookDepth {
+    pub fn new(
+        symbol: String,
+        timestamp: i64,
+        bids: Vec<(f64, f64)>,
+        asks: Vec<(f64, f64)>,
+    ) -> Self {
+        Self {
+            symbol,
+            timestamp,
+            bids,
+            asks,
+        }
+    }
+
+    /// Difference between bid and ask quantities over their sum, ranging from -1, when there
+    /// are only asks, to 1, when there are only bids. `None` if book is empty.
+    pub fn get_imbalance(&self) -> Option<f64> {
+        let bids_quantity: f64 = self.bids.iter().map(|(_, quantity)| quantity).sum();
+        let asks_quantity: f64 = self.asks.iter().map(|(_, quantity)| quantity).sum();
+        let total_quantity = bids_quantity + asks_quantity;
+        if total_quantity <= 0.0 {
+            return None;
+        }
+        Some((bids_quantity - asks_quantity) / total_quantity)
+    }
+}
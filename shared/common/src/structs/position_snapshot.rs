@@ -0,0 +1,62 @@
+use super::Trade;
+use crate::enums::{contract_kind::ContractKind, side::Side, trade_status::TradeStatus};
+
+/// Point-in-time view of current exposure, meant for external monitoring.
+/// Flat positions have `Side::None` and zeroed values, except for mark price.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PositionSnapshot {
+    pub side: Side,
+    pub units: f64,
+    pub entry_price: f64,
+    pub mark_price: f64,
+    pub unrealized_pnl: f64,
+    pub returns: f64,
+    pub updated_at: i64,
+}
+
+impl PositionSnapshot {
+    pub fn flat(mark_price: f64, updated_at: i64) -> Self {
+        Self {
+            mark_price,
+            updated_at,
+            ..Default::default()
+        }
+    }
+
+    /// Snapshot of `trade` position marked at `mark_price`, whose pnl is in margin currency of
+    /// `contract_kind`.
+    pub fn new(
+        trade: Option<&Trade>,
+        contract_kind: ContractKind,
+        mark_price: f64,
+        updated_at: i64,
+    ) -> Self {
+        let Some(trade) = trade else {
+            return Self::flat(mark_price, updated_at);
+        };
+        match trade.status() {
+            TradeStatus::New | TradeStatus::Cancelled | TradeStatus::Closed => {
+                return Self::flat(mark_price, updated_at);
+            }
+            _ => {}
+        }
+        let entry_price = trade.open_order.get_executed_avg_price();
+        // until a price is known, position is marked at its entry price
+        let mark_price = if mark_price > 0.0 {
+            mark_price
+        } else {
+            entry_price
+        };
+        let (unrealized_pnl, returns) =
+            trade.calculate_unrealized_pnl_and_returns(contract_kind, mark_price);
+        Self {
+            side: trade.open_order.side,
+            units: trade.get_current_position_size(),
+            entry_price,
+            mark_price,
+            unrealized_pnl,
+            returns,
+            updated_at,
+        }
+    }
+}
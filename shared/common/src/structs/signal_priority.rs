@@ -0,0 +1,58 @@
+use crate::enums::signal_category::SignalCategory;
+use glow_error::GlowError;
+
+/// Signals which positions are opened, added to or closed upon.
+pub const POSITION_SIGNALS: [SignalCategory; 4] = [
+    SignalCategory::GoLong,
+    SignalCategory::GoShort,
+    SignalCategory::CloseLong,
+    SignalCategory::CloseShort,
+];
+
+/// Order in which position signals fired at the same bar are acted upon, first ones first.
+///
+/// Only signals applicable to current position compete: opens when there's no position,
+/// or its closing signal and, if position can still be added to, its same side open signal.
+/// By default, opens take precedence over closes and longs over shorts, so that an open
+/// position that can be added to is added to, rather than closed, when both signals fire.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignalPriority(Vec<SignalCategory>);
+
+impl Default for SignalPriority {
+    fn default() -> Self {
+        Self(POSITION_SIGNALS.to_vec())
+    }
+}
+
+impl SignalPriority {
+    /// Fails unless `order` has every position signal exactly once.
+    pub fn new(order: Vec<SignalCategory>) -> Result<Self, GlowError> {
+        let has_every_signal_once = order.len() == POSITION_SIGNALS.len()
+            && POSITION_SIGNALS
+                .iter()
+                .all(|signal| order.iter().filter(|&other| other == signal).count() == 1);
+        if !has_every_signal_once {
+            let error = format!(
+                "{:?} must have each of {:?} exactly once",
+                order, POSITION_SIGNALS
+            );
+            return Err(GlowError::new(
+                String::from("Invalid Signal Priority"),
+                error,
+            ));
+        }
+        Ok(Self(order))
+    }
+
+    pub fn get_order(&self) -> &Vec<SignalCategory> {
+        &self.0
+    }
+
+    /// Picks the highest priority among `fired_signals`, if any.
+    pub fn resolve(&self, fired_signals: &[SignalCategory]) -> Option<SignalCategory> {
+        self.0
+            .iter()
+            .find(|signal| fired_signals.contains(signal))
+            .copied()
+    }
+}
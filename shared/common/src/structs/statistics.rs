@@ -13,6 +13,10 @@ pub struct Statistics {
     sharpe_ratio: f64,
     sortino_ratio: f64,
     calmar_ratio: f64,
+    trade_fees: f64,
+    open_fees: f64,
+    close_fees: f64,
+    funding_fees: f64,
 }
 
 impl Display for Statistics {
@@ -29,7 +33,11 @@ impl Display for Statistics {
 ⏳ Max drawdown duration: {}h{}
 📝 Sharpe: {:.2}
 📝 Sortino: {:.2}
-📝 Calmar: {:.2}"#,
+📝 Calmar: {:.2}
+🧾 Trade fees (USDT): {:.4}
+📥 Open fees (USDT): {:.4}
+📤 Close fees (USDT): {:.4}
+🏦 Funding fees (USDT): {:.4}"#,
             self.success_rate,
             self.current_balance,
             self.risk,
@@ -40,7 +48,11 @@ impl Display for Statistics {
             self.max_drawdown_duration.num_minutes() % 60,
             self.sharpe_ratio,
             self.sortino_ratio,
-            self.calmar_ratio
+            self.calmar_ratio,
+            self.trade_fees,
+            self.open_fees,
+            self.close_fees,
+            self.funding_fees
         )
     }
 }
@@ -57,6 +69,10 @@ impl Statistics {
         sharpe_ratio: f64,
         sortino_ratio: f64,
         calmar_ratio: f64,
+        trade_fees: f64,
+        open_fees: f64,
+        close_fees: f64,
+        funding_fees: f64,
     ) -> Self {
         Statistics {
             success_rate,
@@ -69,6 +85,10 @@ impl Statistics {
             sharpe_ratio,
             sortino_ratio,
             calmar_ratio,
+            trade_fees,
+            open_fees,
+            close_fees,
+            funding_fees,
         }
     }
 }
@@ -86,6 +106,10 @@ impl Default for Statistics {
             sharpe_ratio: 0.0,
             sortino_ratio: 0.0,
             calmar_ratio: 0.0,
+            trade_fees: 0.0,
+            open_fees: 0.0,
+            close_fees: 0.0,
+            funding_fees: 0.0,
         }
     }
 }
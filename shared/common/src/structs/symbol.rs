@@ -56,6 +56,11 @@ impl Symbol {
         (self.open, self.high, self.low, self.close)
     }
 
+    /// Base asset symbol is quoted by, e.g. `BTC` for `BTCUSDT`
+    pub fn get_base_asset(&self) -> &'static str {
+        self.name.strip_suffix("USDT").unwrap_or(self.name)
+    }
+
     pub fn derive_symbol_tick_data_schema(&self) -> Schema {
         let mut schema = Schema::new();
         let _ = schema.insert_at_index(
@@ -0,0 +1,569 @@
+use super::{
+    Contract, Execution, LossCircuitBreaker, LossLimit, Metrics, Order, OrderAmendment,
+    SignalPriority, Trade, TradingSettings, TrailingStop,
+};
+use crate::enums::{
+    balance::Balance,
+    contract_kind::ContractKind,
+    modifiers::{
+        leverage::Leverage,
+        position_lock::PositionLock,
+        price_level::{PriceLevel, TakeProfitLadder, TrailingTakeProfit},
+    },
+    order_status::OrderStatus,
+    order_type::OrderType,
+    side::Side,
+    signal_category::SignalCategory,
+};
+use crate::r#static::SYMBOLS_MAP;
+use chrono::NaiveDateTime;
+use serde_json::{from_str, to_string, to_value};
+use std::time::Duration;
+
+fn get_test_contract() -> Contract {
+    Contract::new(
+        NaiveDateTime::default(),
+        chrono::Duration::hours(8),
+        0.0,
+        100.0,
+        (119.0, 1190.0),
+        0.001,
+        None,
+        SYMBOLS_MAP.get("BTCUSDT").unwrap(),
+        0.1,
+    )
+}
+
+#[test]
+fn test_trading_settings_round_trips_through_json() {
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.allocation_percentage = 50.0;
+    trading_settings.leverage = Leverage::Cross(5);
+    trading_settings.position_lock_modifier = PositionLock::Fee;
+    trading_settings.signals_revert_its_opposite = true;
+    trading_settings.trade_cooldown = Some(Duration::from_secs(300));
+    trading_settings
+        .exit_cooldowns
+        .insert(SignalCategory::StopLoss, Duration::from_secs(900));
+    for price_level in [
+        PriceLevel::StopLoss(0.05),
+        PriceLevel::TakeProfit(0.1),
+        PriceLevel::TrailingTakeProfit(TrailingTakeProfit::Stepped(0.02, 0.04)),
+    ] {
+        trading_settings
+            .price_level_modifier_map
+            .insert(price_level.get_hash_key(), price_level);
+    }
+
+    let json = to_string(&trading_settings).unwrap();
+    let loaded_settings: TradingSettings = from_str(&json).unwrap();
+
+    assert_eq!(
+        to_value(&loaded_settings).unwrap(),
+        to_value(&trading_settings).unwrap()
+    );
+    assert_eq!(loaded_settings.leverage, Leverage::Cross(5));
+    assert_eq!(loaded_settings.position_lock_modifier, PositionLock::Fee);
+    assert_eq!(
+        loaded_settings.price_level_modifier_map,
+        trading_settings.price_level_modifier_map
+    );
+    assert_eq!(
+        loaded_settings.get_traded_symbol().name,
+        trading_settings.get_traded_symbol().name
+    );
+}
+
+#[test]
+fn test_trading_settings_rejects_price_level_keyed_by_another_level() {
+    let mut json = to_value(TradingSettings::default()).unwrap();
+    json["price_level_modifier_map"] = from_str(r#"{ "sl": { "tp": 0.1 } }"#).unwrap();
+
+    let result = serde_json::from_value::<TradingSettings>(json);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_trading_settings_validate_rejects_each_violated_invariant() {
+    let contract = get_test_contract();
+    assert!(TradingSettings::default().validate(&contract).is_ok());
+
+    let assert_invalid = |patch: &dyn Fn(&mut TradingSettings), expected_violation: &str| {
+        let mut trading_settings = TradingSettings::default();
+        patch(&mut trading_settings);
+        let error = trading_settings.validate(&contract).unwrap_err();
+        assert_eq!(error.title, "Invalid Trading Settings");
+        assert!(
+            error.description.contains(expected_violation),
+            "{} doesn't mention {}",
+            error.description,
+            expected_violation
+        );
+    };
+    assert_invalid(
+        &|trading_settings| trading_settings.allocation_percentage = 0.0,
+        "allocation percentage",
+    );
+    assert_invalid(
+        &|trading_settings| trading_settings.allocation_percentage = 150.0,
+        "allocation percentage",
+    );
+    assert_invalid(
+        &|trading_settings| trading_settings.leverage = Leverage::Isolated(0),
+        "leverage factor",
+    );
+    assert_invalid(
+        &|trading_settings| trading_settings.leverage = Leverage::Cross(125),
+        "leverage factor",
+    );
+    for price_level in [
+        PriceLevel::StopLoss(-0.05),
+        PriceLevel::TakeProfit(-0.1),
+        PriceLevel::TrailingTakeProfit(TrailingTakeProfit::Stepped(0.02, -0.04)),
+        PriceLevel::TakeProfitLadder(TakeProfitLadder(vec![(0.05, 0.5), (-0.1, 0.5)])),
+    ] {
+        assert_invalid(
+            &|trading_settings| {
+                trading_settings
+                    .price_level_modifier_map
+                    .insert(price_level.get_hash_key(), price_level.clone());
+            },
+            "non-negative percentages",
+        );
+    }
+}
+
+#[test]
+fn test_exit_cooldowns_take_precedence_over_trade_cooldown() {
+    let mut trading_settings = TradingSettings::default();
+    assert!(!trading_settings.is_in_trade_cooldown(Some((0, SignalCategory::StopLoss)), 1));
+
+    trading_settings.trade_cooldown = Some(Duration::from_secs(60));
+    trading_settings
+        .exit_cooldowns
+        .insert(SignalCategory::StopLoss, Duration::from_secs(600));
+    trading_settings
+        .exit_cooldowns
+        .insert(SignalCategory::TakeProfit, Duration::ZERO);
+
+    let minutes_after_exit = 5 * 60_000;
+    let is_in_cooldown_after = |exit_reason| {
+        trading_settings.is_in_trade_cooldown(Some((0, exit_reason)), minutes_after_exit)
+    };
+    assert!(is_in_cooldown_after(SignalCategory::StopLoss));
+    assert!(!is_in_cooldown_after(SignalCategory::TakeProfit));
+    assert!(!is_in_cooldown_after(SignalCategory::CloseLong));
+    assert!(trading_settings.is_in_trade_cooldown(Some((0, SignalCategory::CloseLong)), 30_000));
+    assert!(!trading_settings.is_in_trade_cooldown(None, minutes_after_exit));
+}
+
+#[test]
+fn test_loss_circuit_breaker_trips_after_consecutive_losses_until_a_win() {
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.max_consecutive_losses = Some(2);
+    let mut circuit_breaker = LossCircuitBreaker::default();
+    let timestamp = 1_704_067_200_000; // 2024-01-01T00:00:00Z
+
+    circuit_breaker.record_closed_trade(-1.0, 99.0, timestamp);
+    assert_eq!(
+        circuit_breaker.get_reached_limit(&trading_settings, timestamp),
+        None
+    );
+
+    circuit_breaker.record_closed_trade(-1.0, 98.0, timestamp);
+    assert_eq!(
+        circuit_breaker.get_reached_limit(&trading_settings, timestamp),
+        Some(LossLimit::ConsecutiveLosses(2))
+    );
+    // consecutive losses don't reset at UTC midnight
+    let next_day_timestamp = timestamp + 86_400_000;
+    assert!(circuit_breaker
+        .get_reached_limit(&trading_settings, next_day_timestamp)
+        .is_some());
+
+    circuit_breaker.record_closed_trade(0.5, 98.5, next_day_timestamp);
+    assert_eq!(
+        circuit_breaker.get_reached_limit(&trading_settings, next_day_timestamp),
+        None
+    );
+}
+
+#[test]
+fn test_loss_circuit_breaker_trips_on_daily_loss_and_resets_at_utc_midnight() {
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.daily_loss_limit_pct = Some(0.05);
+    let mut circuit_breaker = LossCircuitBreaker::default();
+    let timestamp = 1_704_067_200_000; // 2024-01-01T00:00:00Z
+
+    circuit_breaker.record_closed_trade(-3.0, 97.0, timestamp);
+    circuit_breaker.record_closed_trade(1.0, 98.0, timestamp + 60_000);
+    assert_eq!(
+        circuit_breaker.get_reached_limit(&trading_settings, timestamp + 60_000),
+        None
+    );
+
+    // day started at 100, so losing 5 overall reaches the limit
+    circuit_breaker.record_closed_trade(-3.0, 95.0, timestamp + 120_000);
+    let reached_limit = circuit_breaker.get_reached_limit(&trading_settings, timestamp + 120_000);
+    assert!(matches!(
+        reached_limit,
+        Some(LossLimit::DailyLoss(daily_loss)) if (daily_loss - 0.05).abs() < 1e-9
+    ));
+
+    let next_day_timestamp = timestamp + 86_400_000;
+    assert_eq!(
+        circuit_breaker.get_reached_limit(&trading_settings, next_day_timestamp),
+        None
+    );
+}
+
+#[test]
+fn test_balance_is_stale_when_empty_or_older_than_max_age() {
+    let mut trading_settings = TradingSettings::default();
+    let timestamp = 1_704_067_200_000;
+    assert!(trading_settings.is_balance_stale(&Balance::new(timestamp, 0.0, 0.0), timestamp));
+    let old_balance = Balance::new(timestamp - 120_000, 100.0, 100.0);
+    assert!(!trading_settings.is_balance_stale(&old_balance, timestamp));
+
+    trading_settings.balance_max_age = Some(Duration::from_secs(60));
+    assert!(trading_settings.is_balance_stale(&old_balance, timestamp));
+    let fresh_balance = Balance::new(timestamp - 30_000, 100.0, 100.0);
+    assert!(!trading_settings.is_balance_stale(&fresh_balance, timestamp));
+}
+
+#[test]
+fn test_metrics_are_encoded_in_prometheus_text_format() {
+    let metrics = Metrics::new().unwrap();
+    metrics.balance.set(250.5);
+    metrics.open_positions.set(1);
+    metrics.trades.inc();
+    metrics.inc_ws_reconnects("bybit");
+    metrics.set_last_signal(SignalCategory::GoLong);
+    metrics.set_last_signal(SignalCategory::CloseLong);
+
+    let encoded = metrics.encode().unwrap();
+
+    assert!(encoded.contains("glow_balance 250.5"));
+    assert!(encoded.contains("glow_open_positions 1"));
+    assert!(encoded.contains("glow_trades_total 1"));
+    assert!(encoded.contains("glow_ws_reconnects_total{exchange=\"bybit\"} 1"));
+    assert!(encoded.contains("glow_order_errors_total 0"));
+    let close_long = SignalCategory::CloseLong.get_column();
+    assert!(encoded.contains(&format!("glow_last_signal{{signal=\"{}\"}} 1", close_long)));
+    let go_long = SignalCategory::GoLong.get_column();
+    assert!(!encoded.contains(&format!("glow_last_signal{{signal=\"{}\"}}", go_long)));
+}
+
+#[test]
+fn test_signal_priority_requires_every_position_signal_once() {
+    let default_priority = SignalPriority::default();
+    assert_eq!(
+        default_priority.resolve(&[SignalCategory::CloseLong, SignalCategory::GoLong]),
+        Some(SignalCategory::GoLong)
+    );
+    assert_eq!(default_priority.resolve(&[]), None);
+
+    assert!(SignalPriority::new(vec![SignalCategory::GoLong, SignalCategory::GoShort]).is_err());
+    assert!(SignalPriority::new(vec![
+        SignalCategory::GoLong,
+        SignalCategory::GoLong,
+        SignalCategory::CloseLong,
+        SignalCategory::CloseShort,
+    ])
+    .is_err());
+    let closes_first_priority = SignalPriority::new(vec![
+        SignalCategory::CloseShort,
+        SignalCategory::CloseLong,
+        SignalCategory::GoShort,
+        SignalCategory::GoLong,
+    ])
+    .unwrap();
+    assert_eq!(
+        closes_first_priority.resolve(&[SignalCategory::GoShort, SignalCategory::CloseShort]),
+        Some(SignalCategory::CloseShort)
+    );
+}
+
+#[test]
+fn test_trailing_stop_advances_by_steps_over_rising_then_falling_returns() {
+    let trailing_take_profit = TrailingTakeProfit::Stepped(0.02, 0.04);
+    let epsilon = 1e-9;
+    let mut trailing_stop = TrailingStop::default();
+    let mut trail = |trade_id: &str, returns: f64| {
+        trailing_stop.next(trade_id, returns, &trailing_take_profit, epsilon)
+    };
+    let assert_trailed_to = |stop_returns: Option<f64>, expected: f64| {
+        let stop_returns = stop_returns.expect("stop to be trailed");
+        assert!((stop_returns - expected).abs() < epsilon);
+    };
+
+    // stop isn't trailed until peak returns exceed start percentage
+    assert_eq!(trail("trade", 0.01), None);
+    assert_eq!(trail("trade", 0.04), None);
+    assert_trailed_to(trail("trade", 0.045), 0.04);
+    // and it's only moved once peak returns reach next step
+    assert_eq!(trail("trade", 0.05), None);
+    assert_trailed_to(trail("trade", 0.061), 0.06);
+    assert_eq!(trail("trade", 0.07), None);
+    assert_trailed_to(trail("trade", 0.081), 0.08);
+    // falling returns never loosen stop
+    assert_eq!(trail("trade", 0.05), None);
+    assert_eq!(trail("trade", 0.02), None);
+    assert_eq!(trail("trade", 0.085), None);
+    // a new trade starts over from its own peak
+    assert_eq!(trail("next_trade", 0.03), None);
+    assert_trailed_to(trail("next_trade", 0.042), 0.04);
+}
+
+#[test]
+fn test_order_amendment_drops_values_matching_current_order() {
+    let contract = get_test_contract();
+    let order = Order {
+        avg_price: Some(30_000.0),
+        stop_loss_price: Some(29_000.0),
+        take_profit_price: None,
+        units: 0.5,
+        ..Default::default()
+    };
+
+    // values round to current ones, so there's nothing to amend
+    let amendment = OrderAmendment::new(
+        &contract,
+        Some(0.5004),
+        Some(30_000.04),
+        Some(29_000.0),
+        None,
+    )
+    .unwrap();
+    assert!(order.diff_amendment(amendment, &contract).is_empty());
+
+    let amendment = OrderAmendment::new(
+        &contract,
+        Some(0.5004),
+        Some(30_000.06),
+        Some(29_000.0),
+        Some(31_000.0),
+    )
+    .unwrap();
+    let diffed_amendment = order.diff_amendment(amendment, &contract);
+    assert_eq!(
+        diffed_amendment,
+        OrderAmendment {
+            units: None,
+            price: Some(30_000.1),
+            stop_loss_price: None,
+            take_profit_price: Some(31_000.0),
+        }
+    );
+}
+
+fn get_execution(id: &str, order_uuid: &str, price: f64, qty: f64) -> Execution {
+    Execution::new(
+        id.to_string(),
+        order_uuid.to_string(),
+        OrderType::Market,
+        0,
+        price,
+        qty,
+        0.0,
+        0.0,
+        false,
+        0.0,
+    )
+}
+
+fn get_open_order(id: &str, units: f64, executions: Vec<Execution>) -> Order {
+    let mut order = Order {
+        id: id.to_string(),
+        uuid: id.to_string(),
+        side: Side::Buy,
+        units,
+        ..Default::default()
+    };
+    order = order.push_executions_if_new(executions);
+    order
+}
+
+#[test]
+fn test_close_out_of_band_stays_closed_through_updates() {
+    let close_order = Order {
+        is_close: true,
+        side: Side::Sell,
+        ..get_open_order("BTCUSDT_1_close", 1.0, vec![])
+    };
+    assert_eq!(close_order.status, OrderStatus::StandBy);
+
+    let closed_order = close_order.close_out_of_band(105.0, 1_000);
+    assert_eq!(closed_order.status, OrderStatus::Closed);
+    assert_eq!(closed_order.get_closed_quanitity(), 1.0);
+    assert_eq!(closed_order.avg_price, Some(105.0));
+    assert_eq!(closed_order.updated_at, 1_000);
+
+    // an update carrying no executions doesn't turn it back to stand by
+    let updated_order = closed_order.update(
+        closed_order.created_at,
+        2_000,
+        OrderStatus::StandBy,
+        closed_order.units,
+        closed_order.avg_price,
+        None,
+        None,
+        vec![],
+    );
+    assert_eq!(updated_order.status, OrderStatus::Closed);
+    assert_eq!(updated_order.executions.len(), 1);
+
+    // estimated close price doesn't make up realized pnl
+    let trade = Trade::new(
+        get_open_order(
+            "BTCUSDT_1_open",
+            1.0,
+            vec![get_execution("open_exec", "BTCUSDT_1_open", 100.0, 1.0)],
+        ),
+        Some(updated_order),
+    );
+    assert!(trade.is_closed_out_of_band());
+    assert_eq!(trade.calculate_pnl_and_returns(ContractKind::Linear).0, 0.0);
+}
+
+fn get_closed_trade(side: Side, units: f64, entry_price: f64, exit_price: f64) -> Trade {
+    let open_order = Order {
+        side,
+        leverage_factor: 2.0,
+        ..get_open_order(
+            "BTCUSD_1_open",
+            units,
+            vec![get_execution(
+                "open_exec",
+                "BTCUSD_1_open",
+                entry_price,
+                units,
+            )],
+        )
+    };
+    let close_execution = Execution {
+        closed_qty: units,
+        timestamp: 1_000,
+        ..get_execution("close_exec", "BTCUSD_1_close", exit_price, units)
+    };
+    let close_order = Order {
+        is_close: true,
+        side: side.get_opposite_side().unwrap(),
+        updated_at: 1_000,
+        ..get_open_order("BTCUSD_1_close", units, vec![close_execution])
+    };
+    Trade::new(open_order, Some(close_order))
+}
+
+#[test]
+fn test_calculate_pnl_and_returns_of_inverse_trades() {
+    // 1,000 USD contracts are worth 0.2 BTC at 5,000 and 1/6 BTC at 6,000, margined at 0.1 BTC
+    let long_trade = get_closed_trade(Side::Buy, 1_000.0, 5_000.0, 6_000.0);
+    let (pnl, returns) = long_trade.calculate_pnl_and_returns(ContractKind::Inverse);
+    assert!((pnl - 1.0 / 30.0).abs() < 1e-9);
+    assert!((returns - 1.0 / 3.0).abs() < 1e-9);
+
+    let short_trade = get_closed_trade(Side::Sell, 1_000.0, 5_000.0, 6_000.0);
+    let (pnl, returns) = short_trade.calculate_pnl_and_returns(ContractKind::Inverse);
+    assert!((pnl + 1.0 / 30.0).abs() < 1e-9);
+    assert!((returns + 1.0 / 3.0).abs() < 1e-9);
+
+    // same trades are quoted in USD when linear
+    let (pnl, returns) = long_trade.calculate_pnl_and_returns(ContractKind::Linear);
+    assert!((pnl - 1_000_000.0).abs() < 1e-9);
+    assert!((returns - 0.4).abs() < 1e-9);
+}
+
+#[test]
+fn test_close_by_executions_requires_untracked_executions_closing_units_left() {
+    let closing_execution = |id: &str, price: f64, qty: f64, timestamp: i64| Execution {
+        closed_qty: qty,
+        timestamp,
+        ..get_execution(id, "manual_close", price, qty)
+    };
+    let close_order = Order {
+        is_close: true,
+        side: Side::Sell,
+        ..get_open_order("BTCUSDT_1_close", 2.0, vec![])
+    };
+    let partially_closed_order =
+        close_order.push_executions_if_new(vec![closing_execution("exec_1", 110.0, 1.0, 1_000)]);
+    assert_eq!(partially_closed_order.status, OrderStatus::PartiallyClosed);
+
+    // already tracked executions, or those opening positions, don't close units left
+    assert!(partially_closed_order
+        .close_by_executions(vec![
+            closing_execution("exec_1", 110.0, 1.0, 1_000),
+            get_execution("open_exec", "manual_open", 90.0, 1.0),
+        ])
+        .is_none());
+
+    let closed_order = partially_closed_order
+        .close_by_executions(vec![
+            closing_execution("exec_1", 110.0, 1.0, 1_000),
+            closing_execution("exec_2", 90.0, 0.5, 2_000),
+            closing_execution("exec_3", 80.0, 0.5, 3_000),
+        ])
+        .unwrap();
+    assert_eq!(closed_order.status, OrderStatus::Closed);
+    assert_eq!(closed_order.get_closed_quanitity(), 2.0);
+    assert_eq!(closed_order.avg_price, Some(97.5));
+    assert_eq!(closed_order.updated_at, 3_000);
+    assert!(closed_order
+        .executions
+        .iter()
+        .all(|execution| !execution.is_out_of_band));
+}
+
+#[test]
+fn test_add_to_position_weights_entry_price_by_executed_quantity() {
+    let open_execution = get_execution("open_exec", "BTCUSDT_1_open", 100.0, 1.0);
+    let trade = Trade::new(
+        get_open_order("BTCUSDT_1_open", 1.0, vec![open_execution]),
+        None,
+    );
+    let add_execution = get_execution("add_exec", "BTCUSDT_2_open", 200.0, 3.0);
+    let added_order = get_open_order("BTCUSDT_2_open", 3.0, vec![add_execution]);
+
+    let updated_trade = trade.add_to_position(added_order).unwrap();
+
+    assert_eq!(updated_trade.open_order.units, 4.0);
+    assert_eq!(updated_trade.open_order.status, OrderStatus::Filled);
+    assert_eq!(updated_trade.open_order.avg_price, Some(175.0));
+
+    let opposite_order = Order {
+        side: Side::Sell,
+        ..get_open_order("BTCUSDT_3_open", 1.0, vec![])
+    };
+    assert!(updated_trade.add_to_position(opposite_order).is_err());
+}
+
+#[test]
+fn test_add_to_position_merges_partial_adds_executions_once() {
+    let open_execution = get_execution("open_exec", "BTCUSDT_1_open", 100.0, 1.0);
+    let trade = Trade::new(
+        get_open_order("BTCUSDT_1_open", 1.0, vec![open_execution]),
+        None,
+    );
+    let first_fill = get_execution("add_exec_1", "BTCUSDT_2_open", 200.0, 0.5);
+    let partially_filled_add = get_open_order("BTCUSDT_2_open", 2.0, vec![first_fill.clone()]);
+    assert_eq!(partially_filled_add.status, OrderStatus::PartiallyFilled);
+
+    let updated_trade = trade.add_to_position(partially_filled_add.clone()).unwrap();
+    assert_eq!(updated_trade.open_order.units, 1.5);
+    assert_eq!(updated_trade.open_order.status, OrderStatus::Filled);
+    assert!((updated_trade.open_order.avg_price.unwrap() - 200.0 / 1.5).abs() < 1e-9);
+
+    // repeated update of the same add doesn't add its executions twice
+    let repeated_trade = updated_trade.add_to_position(partially_filled_add).unwrap();
+    assert_eq!(repeated_trade.open_order.units, 1.5);
+    assert_eq!(repeated_trade.open_order.executions.len(), 2);
+
+    let second_fill = get_execution("add_exec_2", "BTCUSDT_2_open", 300.0, 1.5);
+    let filled_add = get_open_order("BTCUSDT_2_open", 2.0, vec![first_fill, second_fill]);
+    let filled_trade = repeated_trade.add_to_position(filled_add).unwrap();
+    assert_eq!(filled_trade.open_order.units, 3.0);
+    assert_eq!(filled_trade.open_order.executions.len(), 3);
+    assert!((filled_trade.open_order.avg_price.unwrap() - 650.0 / 3.0).abs() < 1e-9);
+}
@@ -0,0 +1,35 @@
+use crate::enums::side::Side;
+
+/// Snapshot of traded symbol's top of book, as well as its last traded price.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ticker {
+    pub timestamp: i64,
+    pub bid_price: f64,
+    pub ask_price: f64,
+    pub last_price: f64,
+}
+
+impl Ticker {
+    pub fn new(timestamp: i64, bid_price: f64, ask_price: f64, last_price: f64) -> Self {
+        Self {
+            timestamp,
+            bid_price,
+            ask_price,
+            last_price,
+        }
+    }
+
+    /// Price an order of given side is expected to be filled at: best ask for buys,
+    /// best bid for sells, and last price otherwise.
+    pub fn get_price_for_side(&self, side: Side) -> f64 {
+        match side {
+            Side::Buy => self.ask_price,
+            Side::Sell => self.bid_price,
+            Side::None => self.last_price,
+        }
+    }
+
+    pub fn get_spread(&self) -> f64 {
+        self.ask_price - self.bid_price
+    }
+}
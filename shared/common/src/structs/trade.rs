@@ -1,13 +1,15 @@
 use chrono::Utc;
 use glow_error::GlowError;
+use serde::{Deserialize, Serialize};
 
 use super::{execution::Execution, order::Order};
 use crate::enums::{
-    order_stage::OrderStage, order_status::OrderStatus, order_type::OrderType, side::Side,
-    time_in_force::TimeInForce, trade_status::TradeStatus,
+    contract_kind::ContractKind, order_stage::OrderStage, order_status::OrderStatus,
+    order_type::OrderType, side::Side, signal_category::SignalCategory, time_in_force::TimeInForce,
+    trade_status::TradeStatus,
 };
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Trade {
     /// defined as `{traded_symbol}_{timestamp}
     pub id: String,
@@ -38,18 +40,15 @@ impl Trade {
         self.open_order.leverage_factor
     }
 
-    pub fn get_threshold_prices(&self) -> (Option<f64>, Option<f64>) {
+    pub fn get_threshold_prices(&self, contract_kind: ContractKind) -> (Option<f64>, Option<f64>) {
+        let bankruptcy_price = || self.open_order.get_bankruptcy_price(contract_kind);
         match self.open_order.side {
             Side::Sell => (
                 self.open_order.take_profit_price,
-                self.open_order
-                    .stop_loss_price
-                    .or_else(|| self.open_order.get_bankruptcy_price()),
+                self.open_order.stop_loss_price.or_else(bankruptcy_price),
             ),
             Side::Buy => (
-                self.open_order
-                    .stop_loss_price
-                    .or_else(|| self.open_order.get_bankruptcy_price()),
+                self.open_order.stop_loss_price.or_else(bankruptcy_price),
                 self.open_order.take_profit_price,
             ),
             Side::None => unreachable!(),
@@ -64,6 +63,41 @@ impl Trade {
         }
     }
 
+    /// Gets why trade's position was exited, if it has a close order: the stop its close order
+    /// was triggered by, if any, or else the close signal of its side, as signal closes of
+    /// either side, i.e. `ClosePosition`, can't be told apart from it.
+    pub fn get_exit_reason(&self, contract_kind: ContractKind) -> Option<SignalCategory> {
+        let close_order = self.close_order.as_ref()?;
+        let exit_reason = match close_order.status {
+            OrderStatus::StoppedSL => SignalCategory::StopLoss,
+            OrderStatus::StoppedTP => SignalCategory::TakeProfit,
+            OrderStatus::StoppedBR => SignalCategory::LeverageBankrupcty,
+            // stop statuses are replaced once executions are pushed, so stops are told apart
+            // by whether they closed at a loss
+            _ if close_order.is_stop => {
+                let (profit_and_loss, _) = self.calculate_pnl_and_returns(contract_kind);
+                if profit_and_loss < 0.0 {
+                    SignalCategory::StopLoss
+                } else {
+                    SignalCategory::TakeProfit
+                }
+            }
+            _ if self.open_order.side == Side::Sell => SignalCategory::CloseShort,
+            _ => SignalCategory::CloseLong,
+        };
+        Some(exit_reason)
+    }
+
+    /// Gets trade's open or close order identified by `order_id`, if any.
+    pub fn get_order_by_id(&self, order_id: &str) -> Option<&Order> {
+        if self.open_order.id == order_id {
+            return Some(&self.open_order);
+        }
+        self.close_order
+            .as_ref()
+            .filter(|close_order| close_order.id == order_id)
+    }
+
     pub fn get_current_position(&self) -> i32 {
         self.get_current_order().side.into()
     }
@@ -101,8 +135,9 @@ impl Trade {
         }
     }
 
-    pub fn calculate_initial_margin(&self) -> f64 {
-        let open_order_executed_value = self.open_order.get_executed_order_value();
+    /// Calculates initial margin of executed open order, in margin currency of `contract_kind`.
+    pub fn calculate_initial_margin(&self, contract_kind: ContractKind) -> f64 {
+        let open_order_executed_value = self.open_order.get_executed_order_value(contract_kind);
         let leverage_factor = self.get_leverage_factor();
         if leverage_factor != 0.0 {
             open_order_executed_value / leverage_factor
@@ -156,15 +191,50 @@ impl Trade {
         Ok(order)
     }
 
+    /// Gets id of trade's order at `stage`, i.e. `{trade_id}_{stage}`.
+    pub fn get_order_id(&self, stage: OrderStage) -> String {
+        format!("{}_{}", self.id, stage.to_string())
+    }
+
+    /// Market close order for trade's units left to close, superseding its resting close order.
+    pub fn new_fallback_close_order(&self, est_price: f64) -> Result<Order, GlowError> {
+        let mut order = self.new_close_order(OrderType::Market, est_price)?;
+        order.id = self.get_order_id(OrderStage::FallbackClose);
+        order.units = self.get_units_left_to_close();
+        Ok(order)
+    }
+
+    /// Gets open order units that weren't closed yet by close order executions.
+    pub fn get_units_left_to_close(&self) -> f64 {
+        let closed_units = self
+            .close_order
+            .as_ref()
+            .map(|close_order| close_order.get_closed_quanitity())
+            .unwrap_or_default();
+        (self.open_order.units - closed_units).max(0.0)
+    }
+
+    /// Whether `order_id` is a close order superseding trade's resting one, i.e. its market close
+    /// fallback or position flattening.
+    fn is_superseding_close_order_id(&self, order_id: &str) -> bool {
+        order_id == self.get_order_id(OrderStage::FallbackClose)
+            || order_id == self.get_order_id(OrderStage::Flatten)
+    }
+
     pub fn calculate_current_pnl_and_returns(
         &self,
+        contract_kind: ContractKind,
         end_timestamp: i64,
         current_price: f64,
     ) -> (f64, f64) {
-        let realized_pnl =
-            self.get_interval_profit_and_loss(self.open_order.created_at, end_timestamp);
-        let (unrealized_pnl, _) = self.calculate_unrealized_pnl_and_returns(current_price);
-        let initial_margin = self.calculate_initial_margin();
+        let realized_pnl = self.get_interval_profit_and_loss(
+            contract_kind,
+            self.open_order.created_at,
+            end_timestamp,
+        );
+        let (unrealized_pnl, _) =
+            self.calculate_unrealized_pnl_and_returns(contract_kind, current_price);
+        let initial_margin = self.calculate_initial_margin(contract_kind);
         let returns = if initial_margin != 0.0 {
             (realized_pnl + unrealized_pnl) / initial_margin
         } else {
@@ -173,15 +243,20 @@ impl Trade {
         ((realized_pnl + unrealized_pnl), returns)
     }
 
-    pub fn calculate_pnl_and_returns(&self) -> (f64, f64) {
+    /// Calculates realized profit and loss, and returns, of closed trade, in margin currency
+    /// of `contract_kind`.
+    pub fn calculate_pnl_and_returns(&self, contract_kind: ContractKind) -> (f64, f64) {
         let closed_order = &self
             .close_order
             .clone()
             .expect("calculate_pnl_and_returns close order unwrap");
         let end_timestamp = closed_order.updated_at;
-        let realized_pnl =
-            self.get_interval_profit_and_loss(self.open_order.created_at, end_timestamp);
-        let initial_margin = self.calculate_initial_margin();
+        let realized_pnl = self.get_interval_profit_and_loss(
+            contract_kind,
+            self.open_order.created_at,
+            end_timestamp,
+        );
+        let initial_margin = self.calculate_initial_margin(contract_kind);
         let returns = if initial_margin != 0.0 {
             realized_pnl / initial_margin
         } else {
@@ -190,29 +265,76 @@ impl Trade {
         (realized_pnl, returns)
     }
 
-    pub fn calculate_unrealized_pnl_and_returns(&self, current_price: f64) -> (f64, f64) {
-        // short: Unrealized P&L = (Average Entry Price - Current Mark Price) × Position Size, ROI = [(Entry Price − Mark Price) × Position Size/ Initial Margin] × 100%
-        // long: Unrealized P&L = (Current Mark Price - Average Entry Price) × Position Size, ROI = [(Mark Price − Entry Price) × Position Size/ Initial Margin] × 100%
-        let avg_entry_price = self.open_order.get_executed_avg_price();
+    /// Checks whether trade was closed out-of-band at an estimated price, in which case its
+    /// realized profit and loss is unknown.
+    pub fn is_closed_out_of_band(&self) -> bool {
+        self.close_order.as_ref().is_some_and(|close_order| {
+            close_order
+                .executions
+                .iter()
+                .any(|execution| execution.is_out_of_band)
+        })
+    }
+
+    /// Calculates profit and loss of the open position size at `current_price`, before fees,
+    /// in margin currency of `contract_kind`.
+    pub fn calculate_gross_pnl(&self, contract_kind: ContractKind, current_price: f64) -> f64 {
+        contract_kind.calculate_gross_pnl(
+            self.open_order.side,
+            self.get_current_position_size(),
+            self.open_order.get_executed_avg_price(),
+            current_price,
+        )
+    }
+
+    /// Gets executed units that haven't been closed yet.
+    pub fn get_current_position_size(&self) -> f64 {
         let executed_qty = self.open_order.get_executed_quantity();
         let closed_qty = if let Some(close_order) = &self.close_order {
             close_order.get_closed_quanitity()
         } else {
             0.0
         };
-        let current_position_size = executed_qty - closed_qty;
-        let bankruptcy_price = self.open_order.get_bankruptcy_price().unwrap_or_default();
-        let provisional_close_fee =
-            current_position_size * bankruptcy_price * self.open_order.taker_fee_rate;
-        // TODO: CHECK THIS =>  here, we don't subtract fees since their effects result in having less units
-        let unrealized_pnl = if self.open_order.side == Side::Sell {
-            (avg_entry_price - current_price) * current_position_size - provisional_close_fee
-        } else if self.open_order.side == Side::Buy {
-            (current_price - avg_entry_price) * current_position_size - provisional_close_fee
-        } else {
+        executed_qty - closed_qty
+    }
+
+    /// Estimates the fees paid by the trade, in case its open position is closed at `close_price`.
+    pub fn estimate_total_fee(
+        &self,
+        contract_kind: ContractKind,
+        close_price: f64,
+        close_fee_rate: f64,
+    ) -> f64 {
+        let current_position_size = self.get_current_position_size();
+        self.open_order.get_executed_order_fee()
+            + contract_kind.calculate_notional(current_position_size, close_price) * close_fee_rate
+    }
+
+    /// Calculates profit and loss, and returns, of the open position size at `current_price`,
+    /// net of its provisional close fee at bankruptcy price, in margin currency of `contract_kind`.
+    pub fn calculate_unrealized_pnl_and_returns(
+        &self,
+        contract_kind: ContractKind,
+        current_price: f64,
+    ) -> (f64, f64) {
+        if self.open_order.side == Side::None {
             panic!("calculate_unrealized_profit -> open order position is different from -1 or 1");
+        }
+        let current_position_size = self.get_current_position_size();
+        let bankruptcy_price = self
+            .open_order
+            .get_bankruptcy_price(contract_kind)
+            .unwrap_or_default();
+        let provisional_close_fee = if bankruptcy_price > 0.0 && bankruptcy_price.is_finite() {
+            contract_kind.calculate_notional(current_position_size, bankruptcy_price)
+                * self.open_order.taker_fee_rate
+        } else {
+            0.0
         };
-        let initial_margin = self.calculate_initial_margin();
+        // TODO: CHECK THIS =>  here, we don't subtract fees since their effects result in having less units
+        let unrealized_pnl =
+            self.calculate_gross_pnl(contract_kind, current_price) - provisional_close_fee;
+        let initial_margin = self.calculate_initial_margin(contract_kind);
         let unrealized_returns = if initial_margin != 0.0 {
             unrealized_pnl / initial_margin
         } else {
@@ -255,23 +377,31 @@ impl Trade {
         open_units - closed_units
     }
 
-    pub fn get_interval_profit_and_loss(&self, start_timestamp: i64, end_timestamp: i64) -> f64 {
+    /// Gets profit and loss realized by close executions between interval, net of fees executed
+    /// in it, in margin currency of `contract_kind`.
+    pub fn get_interval_profit_and_loss(
+        &self,
+        contract_kind: ContractKind,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> f64 {
         let avg_entry_price = self.open_order.get_executed_avg_price();
         let entry_side = self.open_order.side;
+        if entry_side == Side::None {
+            panic!("get_interval_profit_and_loss -> entry_position is different from -1 or 1");
+        }
         let close_interval_executions =
             self.get_interval_close_executions(start_timestamp, end_timestamp);
         let result = close_interval_executions
             .iter()
+            .filter(|execution| !execution.is_out_of_band)
             .fold(0.0, |acc, execution| {
-                if entry_side == Side::Sell {
-                    acc + ((avg_entry_price - execution.price) * execution.closed_qty)
-                } else if entry_side == Side::Buy {
-                    acc + ((execution.price - avg_entry_price) * execution.closed_qty)
-                } else {
-                    panic!(
-                        "get_interval_profit_and_loss -> entry_position is different from -1 or 1"
-                    );
-                }
+                acc + contract_kind.calculate_gross_pnl(
+                    entry_side,
+                    execution.closed_qty,
+                    avg_entry_price,
+                    execution.price,
+                )
             });
         let total_fees = self.get_executed_fees_between_interval(start_timestamp, end_timestamp);
         result - total_fees
@@ -300,6 +430,24 @@ impl Trade {
             .fold(0.0, |acc, execution| acc + execution.fee)
     }
 
+    /// Gets (open fees, close fees) executed between interval, so that they can be told apart.
+    pub fn get_executed_open_and_close_fees_between_interval(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> (f64, f64) {
+        let open_fees = self
+            .open_order
+            .get_executions_between_interval(start_timestamp, end_timestamp)
+            .iter()
+            .fold(0.0, |acc, execution| acc + execution.fee);
+        let close_fees = self
+            .get_interval_close_executions(start_timestamp, end_timestamp)
+            .iter()
+            .fold(0.0, |acc, execution| acc + execution.fee);
+        (open_fees, close_fees)
+    }
+
     pub fn updated_at(&self) -> i64 {
         let mut updated_at = self.open_order.updated_at;
         if let Some(close_order) = &self.close_order {
@@ -340,11 +488,69 @@ impl Trade {
         }
     }
 
+    /// Merges executions of a same side open order, adding to trade's position, into its open
+    /// order, whatever added order status is, so that partially filled adds count as well.
+    /// Only executions not merged yet add to open order units, so that repeated updates of
+    /// the same added order don't. As executions are merged, open order executed average price
+    /// is the weighted entry price.
+    pub fn add_to_position(&self, added_order: Order) -> Result<Trade, GlowError> {
+        if added_order.is_close
+            || added_order.side != self.open_order.side
+            || self.close_order.is_some()
+        {
+            let error = format!(
+                "add_to_position -> order can't be added to trade position. Added Order {:?}, Trade {:?}",
+                &added_order, &self
+            );
+            return Err(GlowError::new(String::from("Invalid Position Add"), error));
+        }
+        let new_executions: Vec<Execution> = added_order
+            .executions
+            .into_iter()
+            .filter(|execution| {
+                !self
+                    .open_order
+                    .executions
+                    .iter()
+                    .any(|merged_execution| merged_execution.id == execution.id)
+            })
+            .collect();
+        if new_executions.is_empty() {
+            return Ok(self.clone());
+        }
+        let mut open_order = self.open_order.clone();
+        open_order.units = new_executions
+            .iter()
+            .fold(open_order.units, |acc, execution| acc + execution.qty);
+        open_order.updated_at = added_order.updated_at;
+        open_order = open_order.push_executions_if_new(new_executions);
+        open_order.avg_price = Some(open_order.get_executed_avg_price());
+
+        let mut updated_trade = self.clone();
+        updated_trade.open_order = open_order;
+        Ok(updated_trade)
+    }
+
     pub fn update_trade(&self, order: Order) -> Result<Trade, GlowError> {
         let mut updated_trade = self.clone();
         if order.is_close {
             match &self.close_order {
                 Some(close_order) => {
+                    // superseding close orders carry over executions of the one they replace, so
+                    // units it already closed count towards them
+                    let (close_order, superseded_units) =
+                        if self.is_superseding_close_order_id(&order.id) {
+                            let mut superseding_order = order.clone();
+                            superseding_order.executions = close_order.executions.clone();
+                            let superseded_units = close_order
+                                .executions
+                                .iter()
+                                .filter(|execution| execution.order_uuid != order.uuid)
+                                .fold(0.0, |acc, execution| acc + execution.closed_qty);
+                            (superseding_order, superseded_units)
+                        } else {
+                            (close_order.clone(), 0.0)
+                        };
                     // otherwise, check if it's related to close order
                     // by comparing their ids
                     if close_order.id == order.id {
@@ -353,7 +559,7 @@ impl Trade {
                             order.created_at,
                             order.updated_at,
                             order.status,
-                            order.units,
+                            order.units + superseded_units,
                             order.avg_price,
                             order.stop_loss_price,
                             order.take_profit_price,
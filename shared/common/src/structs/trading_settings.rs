@@ -1,12 +1,26 @@
-use super::{Symbol, SymbolsPair};
-use crate::enums::{
-    granularity::Granularity,
-    modifiers::{leverage::Leverage, position_lock::PositionLock, price_level::PriceLevel},
-    order_type::OrderType,
-    symbol_id::SymbolId,
+use super::{Contract, FeeModel, Logger, Symbol, SymbolsPair};
+use crate::{
+    constants::DEFAULT_PRICE_LEVEL_EPSILON,
+    enums::{
+        allocation_basis::AllocationBasis,
+        balance::Balance,
+        exchange_environment::ExchangeEnvironment,
+        granularity::Granularity,
+        kline_gap_handling::KlineGapHandling,
+        log_format::LogFormat,
+        log_level::LogLevel,
+        modifiers::{
+            leverage::Leverage,
+            position_lock::PositionLock,
+            price_level::{PriceLevel, TakeProfitLadder, TrailingTakeProfit},
+        },
+        order_type::OrderType,
+        signal_category::SignalCategory,
+        symbol_id::SymbolId,
+    },
 };
 use glow_error::GlowError;
-use serde::{Deserialize, Serialize};
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize};
 use serde_json::{from_reader, to_writer};
 use std::{
     collections::HashMap,
@@ -14,6 +28,8 @@ use std::{
     fmt::{Debug, Formatter, Result as DebugResult},
     fs::File,
     io::{BufReader, Result as IoResult},
+    net::SocketAddr,
+    time::Duration,
 };
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -23,11 +39,121 @@ pub struct TradingSettings {
     pub leverage: Leverage,
     pub order_types: (OrderType, OrderType), // for opening / closing
     pub position_lock_modifier: PositionLock,
+    /// keyed by `PriceLevel::get_hash_key`, i.e. "sl", "tp" (either single take profit or its ladder) or "ttp".
+    #[serde(deserialize_with = "deserialize_price_level_modifier_map")]
     pub price_level_modifier_map: HashMap<String, PriceLevel>,
     pub signals_revert_its_opposite: bool,
     pub symbols_pair: SymbolsPair,
     pub bechmark_minimum_days: u32,
     pub granularity: Granularity,
+    /// when set, benchmark uses these fee rates instead of exchange's. Live trading is unaffected.
+    #[serde(default)]
+    pub benchmark_fee_override: Option<FeeModel>,
+    /// adverse slippage, in basis points, applied to benchmark fills. Live trading is unaffected.
+    #[serde(default)]
+    pub benchmark_slippage_bps: f64,
+    /// when set, benchmark charges this funding rate every funding interval instead of traded contract's.
+    #[serde(default)]
+    pub benchmark_funding_rate: Option<f64>,
+    /// when set, trader cancels open orders and closes live positions on init, starting from a flat state.
+    #[serde(default)]
+    pub start_clean: bool,
+    /// minimum time after a position closes before open signals are acted upon again.
+    #[serde(default)]
+    pub trade_cooldown: Option<Duration>,
+    /// cooldowns after positions exited by each reason, e.g. `StopLoss` or `CloseLong`, taking
+    /// precedence over `trade_cooldown`, which still applies to exit reasons not set here.
+    #[serde(default)]
+    pub exit_cooldowns: HashMap<SignalCategory, Duration>,
+    /// fraction of position closed when take profit ("tp") price level is reached, letting the rest ride.
+    #[serde(default)]
+    pub take_profit_partial_fraction: Option<f64>,
+    /// events above this level aren't logged.
+    #[serde(default)]
+    pub log_level: LogLevel,
+    /// whether events are logged as human readable lines or as one-line JSON objects.
+    #[serde(default)]
+    pub log_format: LogFormat,
+    /// whether trader exchange is reached at its testnet or mainnet endpoints.
+    #[serde(default)]
+    pub environment: ExchangeEnvironment,
+    /// minimum number of bars a position is held before close signals are acted upon.
+    /// Stop loss and bankruptcy prices apply regardless.
+    #[serde(default)]
+    pub position_lock_bars: usize,
+    /// maximum number of times a winning position is added to on repeated same side open signals.
+    /// Each add is sized from remaining balance. 0 disables pyramiding.
+    #[serde(default)]
+    pub max_pyramid_adds: usize,
+    /// whether live orders are priced at current best ask (for buys) or bid (for sells),
+    /// instead of last close.
+    #[serde(default)]
+    pub use_live_spread: bool,
+    /// how bars missing from exchange klines are handled.
+    #[serde(default)]
+    pub kline_gap_handling: KlineGapHandling,
+    /// when set, benchmark closes a trade still open at last bar at its close price,
+    /// instead of discarding it. Live trading is unaffected.
+    #[serde(default)]
+    pub close_open_at_end: bool,
+    /// when set and close orders are limit ones, time a maker close order is given to fill before
+    /// it's cancelled and position is closed at market.
+    #[serde(default)]
+    pub maker_close_timeout: Option<Duration>,
+    /// relative tolerance for price level thresholds comparisons, so that prices and returns
+    /// rounded at their boundaries bind consistently. Defaults to `DEFAULT_PRICE_LEVEL_EPSILON`.
+    #[serde(default)]
+    pub price_level_epsilon: Option<f64>,
+    /// when set, no positions are opened after this many consecutive losing trades, until reset.
+    #[serde(default)]
+    pub max_consecutive_losses: Option<u32>,
+    /// when set, no positions are opened for the rest of the UTC day once closed trades lost
+    /// this fraction of the equity day started with.
+    #[serde(default)]
+    pub daily_loss_limit_pct: Option<f64>,
+    /// whether open orders are cancelled and positions flattened once a loss limit is reached.
+    #[serde(default)]
+    pub flatten_on_loss_limit: bool,
+    /// when set, balance older than this is refetched from exchange before opening positions.
+    #[serde(default)]
+    pub balance_max_age: Option<Duration>,
+    /// address Prometheus metrics are served at, if any. Metrics aren't served by default.
+    #[serde(default)]
+    pub metrics_address: Option<SocketAddr>,
+    /// when set, benchmark balances are rounded to traded contract's tick size decimals after
+    /// every update, so that float residuals accumulated over many bars can't enable or block
+    /// minimum size orders. Tradeoff is each update being off by up to half a tick.
+    #[serde(default)]
+    pub quantize_benchmark_balances: bool,
+    /// what allocation percentage is applied to when sizing open orders. Defaults to quote balance
+    /// available to withdraw.
+    #[serde(default)]
+    pub allocation_basis: AllocationBasis,
+    /// seed of randomized behavior, so that runs over identical input are reproducible. No subsystem
+    /// samples randomness yet: paper fills, websocket reconnects and parameter sweeps are all
+    /// deterministic. Randomized behavior added to any of them must be seeded by it, if set.
+    #[serde(default)]
+    pub random_seed: Option<u64>,
+}
+
+/// Rejects price levels keyed other than by their hash key, as modifiers are looked up by it.
+fn deserialize_price_level_modifier_map<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<String, PriceLevel>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let price_level_modifier_map = HashMap::<String, PriceLevel>::deserialize(deserializer)?;
+    for (key, price_level) in price_level_modifier_map.iter() {
+        let hash_key = price_level.get_hash_key();
+        if key != &hash_key {
+            return Err(DeError::custom(format!(
+                "price level {:?} must be keyed by \"{}\", not \"{}\"",
+                price_level, hash_key, key
+            )));
+        }
+    }
+    Ok(price_level_modifier_map)
 }
 
 impl TradingSettings {
@@ -54,6 +180,31 @@ impl TradingSettings {
             symbols_pair: SymbolsPair::new(&anchor_contract_symbol, &traded_contract_symbol),
             bechmark_minimum_days,
             granularity,
+            benchmark_fee_override: None,
+            benchmark_slippage_bps: 0.0,
+            benchmark_funding_rate: None,
+            start_clean: false,
+            trade_cooldown: None,
+            exit_cooldowns: HashMap::new(),
+            take_profit_partial_fraction: None,
+            log_level: LogLevel::default(),
+            log_format: LogFormat::default(),
+            environment: ExchangeEnvironment::default(),
+            position_lock_bars: 0,
+            max_pyramid_adds: 0,
+            use_live_spread: false,
+            kline_gap_handling: KlineGapHandling::default(),
+            close_open_at_end: false,
+            maker_close_timeout: None,
+            price_level_epsilon: None,
+            max_consecutive_losses: None,
+            daily_loss_limit_pct: None,
+            flatten_on_loss_limit: false,
+            balance_max_age: None,
+            metrics_address: None,
+            quantize_benchmark_balances: false,
+            allocation_basis: AllocationBasis::QuoteBalance,
+            random_seed: None,
         }
     }
 
@@ -112,6 +263,154 @@ impl TradingSettings {
         self.order_types.1
     }
 
+    /// Checks whether `balance` can't be used to size new positions as of `timestamp`, i.e. it
+    /// has nothing available or it's older than `balance_max_age`. Timestamps are in milliseconds.
+    pub fn is_balance_stale(&self, balance: &Balance, timestamp: i64) -> bool {
+        if balance.available_to_withdraw <= 0.0 {
+            return true;
+        }
+        match self.balance_max_age {
+            Some(balance_max_age) => {
+                timestamp - balance.timestamp > balance_max_age.as_millis() as i64
+            }
+            None => false,
+        }
+    }
+
+    /// Checks settings invariants against traded `contract`, so that runs don't start off settings
+    /// sizing orders beyond balance or liquidating positions right away: allocation percentage
+    /// must be within (0, 100], leverage from 1x up to contract's maximum, and price levels'
+    /// percentages non-negative. Errors with every violated invariant.
+    pub fn validate(&self, contract: &Contract) -> Result<(), GlowError> {
+        let mut violations = vec![];
+        if !(self.allocation_percentage > 0.0 && self.allocation_percentage <= 100.0) {
+            violations.push(format!(
+                "allocation percentage must be within (0, 100], got {}",
+                self.allocation_percentage
+            ));
+        }
+        let leverage_factor = self.leverage.get_factor();
+        if leverage_factor < 1.0 || leverage_factor > contract.max_leverage {
+            violations.push(format!(
+                "leverage factor must be within [1, {}] for {}, got {}",
+                contract.max_leverage, contract.symbol.name, leverage_factor
+            ));
+        }
+        for price_level in self.price_level_modifier_map.values() {
+            let percentages = match price_level {
+                PriceLevel::StopLoss(percentage) | PriceLevel::TakeProfit(percentage) => {
+                    vec![*percentage]
+                }
+                PriceLevel::TrailingTakeProfit(
+                    TrailingTakeProfit::Percent(first, start_percentage)
+                    | TrailingTakeProfit::Stepped(first, start_percentage),
+                ) => vec![*first, *start_percentage],
+                PriceLevel::TakeProfitLadder(ladder) => ladder
+                    .0
+                    .iter()
+                    .flat_map(|(percentage, fraction)| [*percentage, *fraction])
+                    .collect(),
+            };
+            if percentages.iter().any(|percentage| *percentage < 0.0) {
+                violations.push(format!(
+                    "price level {:?} must have non-negative percentages",
+                    price_level
+                ));
+            }
+        }
+        if !violations.is_empty() {
+            return Err(GlowError::new(
+                String::from("Invalid Trading Settings"),
+                violations.join("; "),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Gets cooldown after a position exited by `exit_reason`, as set at `exit_cooldowns`, or
+    /// else `trade_cooldown`.
+    pub fn get_exit_cooldown(&self, exit_reason: SignalCategory) -> Option<Duration> {
+        self.exit_cooldowns
+            .get(&exit_reason)
+            .copied()
+            .or(self.trade_cooldown)
+    }
+
+    /// Whether open signals at `timestamp` must be skipped, as last position exited, at
+    /// `last_exit`'s timestamp and by its reason, less than that reason's cooldown ago.
+    /// See `get_exit_cooldown`. Timestamps are in milliseconds.
+    pub fn is_in_trade_cooldown(
+        &self,
+        last_exit: Option<(i64, SignalCategory)>,
+        timestamp: i64,
+    ) -> bool {
+        let Some((last_exit_timestamp, exit_reason)) = last_exit else {
+            return false;
+        };
+        match self.get_exit_cooldown(exit_reason) {
+            Some(cooldown) => timestamp - last_exit_timestamp < cooldown.as_millis() as i64,
+            None => false,
+        }
+    }
+
+    /// Whether close signals at `timestamp` must be skipped, as position opened at `open_timestamp`
+    /// wasn't held for `position_lock_bars` bars yet. Timestamps are in milliseconds.
+    pub fn is_in_position_lock_bars(&self, open_timestamp: Option<i64>, timestamp: i64) -> bool {
+        let Some(open_timestamp) = open_timestamp else {
+            return false;
+        };
+        let bar_duration_ms = self.granularity.get_chrono_duration().num_milliseconds();
+        timestamp - open_timestamp < self.position_lock_bars as i64 * bar_duration_ms
+    }
+
+    /// Whether bar at `timestamp` doesn't directly follow bar at `previous_timestamp`,
+    /// i.e. bars are missing in between. Timestamps are in milliseconds.
+    pub fn is_after_kline_gap(&self, previous_timestamp: i64, timestamp: i64) -> bool {
+        let bar_duration_ms = self.granularity.get_chrono_duration().num_milliseconds();
+        timestamp - previous_timestamp > bar_duration_ms
+    }
+
+    /// Fraction of position to be closed at take profit price, if it leaves part of position open.
+    /// Fractions outside (0, 1) mean closing the whole position, and take profit ladders set
+    /// their own fractions.
+    pub fn get_take_profit_partial_fraction(&self) -> Option<f64> {
+        if self.get_take_profit_ladder().is_some() {
+            return None;
+        }
+        self.take_profit_partial_fraction
+            .filter(|fraction| *fraction > 0.0 && *fraction < 1.0)
+    }
+
+    /// Take profit ladder, if take profit ("tp") price level is set as one with any level.
+    pub fn get_take_profit_ladder(&self) -> Option<TakeProfitLadder> {
+        match self.price_level_modifier_map.get("tp") {
+            Some(PriceLevel::TakeProfitLadder(ladder)) if !ladder.0.is_empty() => {
+                Some(ladder.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Trailing take profit, if trailing take profit ("ttp") price level is set as a stepped one.
+    pub fn get_stepped_trailing_take_profit(&self) -> Option<TrailingTakeProfit> {
+        match self.price_level_modifier_map.get("ttp") {
+            Some(PriceLevel::TrailingTakeProfit(
+                trailing_take_profit @ TrailingTakeProfit::Stepped(..),
+            )) => Some(*trailing_take_profit),
+            _ => None,
+        }
+    }
+
+    pub fn get_price_level_epsilon(&self) -> f64 {
+        self.price_level_epsilon
+            .filter(|epsilon| *epsilon >= 0.0)
+            .unwrap_or(DEFAULT_PRICE_LEVEL_EPSILON)
+    }
+
+    pub fn get_logger(&self) -> Logger {
+        Logger::new(self.log_level, self.log_format)
+    }
+
     pub fn patch_symbols_pair(&self, updated_symbols_pair: SymbolsPair) -> Self {
         let mut result = self.clone();
         result.symbols_pair = updated_symbols_pair;
@@ -150,6 +449,31 @@ impl Default for TradingSettings {
             symbols_pair: SymbolsPair::default(),
             granularity: Granularity::default(),
             bechmark_minimum_days: 1,
+            benchmark_fee_override: None,
+            benchmark_slippage_bps: 0.0,
+            benchmark_funding_rate: None,
+            start_clean: false,
+            trade_cooldown: None,
+            exit_cooldowns: HashMap::new(),
+            take_profit_partial_fraction: None,
+            log_level: LogLevel::default(),
+            log_format: LogFormat::default(),
+            environment: ExchangeEnvironment::default(),
+            position_lock_bars: 0,
+            max_pyramid_adds: 0,
+            use_live_spread: false,
+            kline_gap_handling: KlineGapHandling::default(),
+            close_open_at_end: false,
+            maker_close_timeout: None,
+            price_level_epsilon: None,
+            max_consecutive_losses: None,
+            daily_loss_limit_pct: None,
+            flatten_on_loss_limit: false,
+            balance_max_age: None,
+            metrics_address: None,
+            quantize_benchmark_balances: false,
+            allocation_basis: AllocationBasis::QuoteBalance,
+            random_seed: None,
         }
     }
 }
@@ -167,7 +491,31 @@ impl Debug for TradingSettings {
             🎭 Price Modifiers: {:?}
             🔒 Position Lock: {:?}
             🔁 Revert Opposite Signals {}
-            📅 Minimum days for benchmarking {}"#,
+            📅 Minimum days for benchmarking {}
+            💸 Benchmark fee override: {:?}
+            🧊 Benchmark slippage (bps): {}
+            🏦 Benchmark funding rate: {:?}
+            🧹 Start clean: {}
+            ⏳ Trade cooldown: {:?}
+            ⌛ Exit cooldowns: {:?}
+            🪜 Take profit partial fraction: {:?}
+            📝 Logging: {:?}, {:?}
+            🌐 Exchange environment: {:?}
+            📌 Position lock bars: {}
+            🔺 Max pyramid adds: {}
+            📖 Use live spread: {}
+            🕳️ Kline gap handling: {:?}
+            🏁 Close open trade at benchmark end: {}
+            ⌛ Maker close timeout: {:?}
+            🎯 Price level epsilon: {:?}
+            🧯 Max consecutive losses: {:?}
+            🧯 Daily loss limit (fraction of equity): {:?}
+            🧯 Flatten on loss limit: {}
+            ⏱️ Balance max age: {:?}
+            📡 Metrics address: {:?}
+            🧮 Quantize benchmark balances: {}
+            🧺 Allocation basis: {:?}
+            🎲 Random seed: {:?}"#,
             self.symbols_pair,
             self.granularity,
             self.allocation_percentage,
@@ -177,7 +525,32 @@ impl Debug for TradingSettings {
             self.fmt_price_level_modifiers(),
             self.position_lock_modifier,
             self.signals_revert_its_opposite,
-            self.bechmark_minimum_days
+            self.bechmark_minimum_days,
+            self.benchmark_fee_override,
+            self.benchmark_slippage_bps,
+            self.benchmark_funding_rate,
+            self.start_clean,
+            self.trade_cooldown,
+            self.exit_cooldowns,
+            self.take_profit_partial_fraction,
+            self.log_level,
+            self.log_format,
+            self.environment,
+            self.position_lock_bars,
+            self.max_pyramid_adds,
+            self.use_live_spread,
+            self.kline_gap_handling,
+            self.close_open_at_end,
+            self.maker_close_timeout,
+            self.price_level_epsilon,
+            self.max_consecutive_losses,
+            self.daily_loss_limit_pct,
+            self.flatten_on_loss_limit,
+            self.balance_max_age,
+            self.metrics_address,
+            self.quantize_benchmark_balances,
+            self.allocation_basis,
+            self.random_seed
         )
     }
 }
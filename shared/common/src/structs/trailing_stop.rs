@@ -0,0 +1,43 @@
+use crate::{enums::modifiers::price_level::TrailingTakeProfit, functions::is_at_or_below};
+
+/// Tracks an open trade's peak returns, so that an exchange side stop trailing them is only
+/// moved when trailing take profit's acceptable returns advance, as benchmark simulates it.
+///
+/// With `TrailingTakeProfit::Stepped`, acceptable returns only advance once peak returns reach
+/// the next step, so exchange stop is amended once per step rather than on every price update.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrailingStop {
+    pub trade_id: String,
+    pub peak_returns: f64,
+    pub stop_returns: Option<f64>, // acceptable returns exchange stop was last set at
+}
+
+impl TrailingStop {
+    /// Records `returns` reached by `trade_id` trade, starting over whenever trade changes.
+    /// Gets returns exchange stop should be moved to, if they advanced since last set.
+    pub fn next(
+        &mut self,
+        trade_id: &str,
+        returns: f64,
+        trailing_take_profit: &TrailingTakeProfit,
+        epsilon: f64,
+    ) -> Option<f64> {
+        if self.trade_id != trade_id {
+            *self = Self {
+                trade_id: trade_id.to_string(),
+                ..Default::default()
+            };
+        }
+        self.peak_returns = self.peak_returns.max(returns);
+        let stop_returns =
+            trailing_take_profit.get_acceptable_returns(self.peak_returns, epsilon)?;
+        if self
+            .stop_returns
+            .is_some_and(|set_returns| is_at_or_below(stop_returns, set_returns, epsilon))
+        {
+            return None;
+        }
+        self.stop_returns = Some(stop_returns);
+        Some(stop_returns)
+    }
+}
@@ -0,0 +1,13 @@
+use chrono::NaiveDateTime;
+
+/// Source of current time, so that time dependent behavior can be driven by other than the system clock.
+pub trait Clock: Send + Sync {
+    /// Gets current timestamp in milliseconds.
+    fn now_ms(&self) -> i64;
+
+    /// Gets current datetime.
+    fn now_datetime(&self) -> NaiveDateTime {
+        NaiveDateTime::from_timestamp_millis(self.now_ms())
+            .expect("now_datetime -> timestamp out of range")
+    }
+}
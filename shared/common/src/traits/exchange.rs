@@ -1,16 +1,24 @@
 use crate::{
     enums::{
-        balance::Balance, modifiers::leverage::Leverage, order_action::OrderAction,
-        order_status::OrderStatus, order_type::OrderType, side::Side, symbol_id::SymbolId,
-        trade_status::TradeStatus, trading_data_update::TradingDataUpdate,
+        balance::Balance, benchmark_window::BenchmarkWindow, modifiers::leverage::Leverage,
+        order_action::OrderAction, order_status::OrderStatus, order_type::OrderType,
+        run_mode::RunMode, side::Side, symbol_id::SymbolId, trade_status::TradeStatus,
+        trading_data_update::TradingDataUpdate, ws_compression::WsCompression,
+    },
+    r#static::METRICS,
+    structs::{
+        BehaviorSubject, Contract, Execution, Order, OrderBookDepth, Symbol, Ticker, Trade,
+        TradingSettings,
     },
-    structs::{BehaviorSubject, Contract, Execution, Order, Symbol, Trade, TradingSettings},
 };
 use chrono::NaiveDateTime;
 use glow_error::GlowError;
+use log::debug;
 use polars::prelude::Schema;
 use reqwest::Client;
-use std::{collections::HashMap, future::Future};
+use serde::de::DeserializeOwned;
+use serde_json::from_str;
+use std::{collections::HashMap, fmt::Debug, future::Future};
 use tokio::net::TcpStream;
 use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
 use url::Url;
@@ -27,6 +35,19 @@ pub trait TraderHelper {
     fn calculate_order_stop_loss_price(&self, side: Side, price: f64) -> Option<f64>;
     fn calculate_order_take_profit_price(&self, side: Side, price: f64) -> Option<f64>;
 
+    /// Gets price, rounded to traded contract tick, at which a `side` position opened at `price`
+    /// has leveraged `percentage` returns, as take profit prices are calculated.
+    fn calculate_returns_price(&self, side: Side, price: f64, percentage: f64) -> Option<f64> {
+        let leverage_factor = self.get_leverage_factor();
+        let position_mod = match side {
+            Side::Sell => leverage_factor - percentage,
+            Side::Buy => leverage_factor + percentage,
+            Side::None => return None,
+        };
+        let contract = self.get_traded_contract();
+        Some(contract.round_price_to_tick(price * position_mod / leverage_factor))
+    }
+
     fn calculate_open_order_units_and_balance_remainder(
         &self,
         side: Side,
@@ -87,10 +108,15 @@ pub trait TraderExchange: TraderHelper {
     fn get_http_client(&self) -> &Client;
     fn get_ws_ping_interval(&self) -> u64;
     fn get_ws_ping_message(&self) -> Result<Message, GlowError>;
+    /// How exchange compresses binary ws frames, which are inflated before being processed.
+    fn get_ws_compression(&self) -> WsCompression {
+        WsCompression::None
+    }
     fn get_balance_update_emitter(&self) -> &BehaviorSubject<Balance>;
     fn get_executions_update_emitter(&self) -> &BehaviorSubject<Vec<Execution>>;
     fn get_order_update_emitter(&self) -> &BehaviorSubject<OrderAction>;
     fn get_trade_update_emitter(&self) -> &BehaviorSubject<Option<Trade>>;
+    fn get_exchange_recovery_emitter(&self) -> &BehaviorSubject<TradingDataUpdate>;
     // async methods
     // ws
     fn auth_ws(
@@ -131,12 +157,26 @@ pub trait TraderExchange: TraderHelper {
     ) -> impl Future<Output = Result<Trade, GlowError>> + Send;
     fn fetch_current_usdt_balance(&self)
         -> impl Future<Output = Result<Balance, GlowError>> + Send;
+    /// Fetches units of traded symbol's base asset held at wallet, zero if none is held
+    fn fetch_base_asset_balance(&self) -> impl Future<Output = Result<f64, GlowError>> + Send;
+    /// Fetches traded symbol's current best bid and ask, as well as its last price
+    fn fetch_ticker(&self) -> impl Future<Output = Result<Ticker, GlowError>> + Send;
+    /// Fetches anchor and traded symbols' contract specs from exchange's instruments endpoint,
+    /// mapped by symbol name
+    fn fetch_instruments_info(
+        &self,
+    ) -> impl Future<Output = Result<HashMap<String, Contract>, GlowError>> + Send;
+    /// Fetches exchange server time, in milliseconds
+    fn server_time(&self) -> impl Future<Output = Result<i64, GlowError>> + Send;
     fn open_order(
         &self,
         side: Side,
         amount: f64,
         expected_price: f64,
     ) -> impl Future<Output = Result<Order, GlowError>> + Send;
+    /// Amends order's units, price, stop loss and take profit, rounded to traded contract.
+    /// Values matching order's current ones are left out, and if none is left, nothing is sent
+    /// and it returns `true`, so that `false` only means exchange rejected the amend
     fn amend_order(
         &self,
         order_id: String,
@@ -145,16 +185,30 @@ pub trait TraderExchange: TraderHelper {
         updated_stop_loss_price: Option<f64>,
         updated_take_profit_price: Option<f64>,
     ) -> impl Future<Output = Result<bool, GlowError>> + Send;
+    /// Sets a take profit for `units` of trade's position at `price`, so that only part of it is closed
+    fn scale_out_position(
+        &self,
+        trade: &Trade,
+        units: f64,
+        price: f64,
+    ) -> impl Future<Output = Result<bool, GlowError>> + Send;
+    /// Sends trade's close order. When `reduce_only` is set, order can only reduce the position,
+    /// so that it never flips it in case position was reduced out-of-band
     fn try_close_position(
         &self,
         trade: &Trade,
         est_price: f64,
+        reduce_only: bool,
     ) -> impl Future<Output = Result<Order, GlowError>> + Send;
     /// this function is meant to be run by trades with status TradeStatus::PartiallyOpen and TradeStatus::CloseOrderStandBy
     fn cancel_order(
         &self,
         order_id: String,
     ) -> impl Future<Output = Result<bool, GlowError>> + Send;
+    /// Cancels every open order at traded symbol, returning how many were cancelled
+    fn cancel_all_orders(&self) -> impl Future<Output = Result<usize, GlowError>> + Send;
+    /// Cancels all open orders and market closes any live position at traded symbol, leaving the account flat
+    fn flatten_positions(&self) -> impl Future<Output = Result<(), GlowError>> + Send;
 
     fn set_leverage(
         &self,
@@ -198,6 +252,24 @@ pub trait BenchmarkExchange: TraderHelper {
 
     fn get_minimum_notional_value(&self) -> Option<f64>;
 
+    /// Returns (maker, taker) fee rates for benchmarking, preferring `TradingSettings::benchmark_fee_override`
+    /// over exchange defaults.
+    fn get_benchmark_fee_rates(&self) -> (f64, f64) {
+        match self.get_trading_settings().benchmark_fee_override {
+            Some(fee_model) => (fee_model.maker_rate, fee_model.taker_rate),
+            None => (self.get_maker_fee(), self.get_taker_fee()),
+        }
+    }
+
+    fn get_benchmark_order_fee_rate(&self, order_type: OrderType) -> (f64, bool) {
+        let (maker_fee_rate, taker_fee_rate) = self.get_benchmark_fee_rates();
+        if order_type == OrderType::Limit {
+            (maker_fee_rate, true)
+        } else {
+            (taker_fee_rate, false)
+        }
+    }
+
     // fn check_price_level_modifiers(
     //     &self,
     //     trade: &Trade,
@@ -208,8 +280,52 @@ pub trait BenchmarkExchange: TraderHelper {
 }
 
 pub trait DataProviderExchange: Clone {
+    /// Name exchange's websocket metrics are labelled with.
+    fn get_exchange_name(&self) -> &'static str;
+
     fn get_kline_data_emitter(&self) -> &BehaviorSubject<TradingDataUpdate>;
 
+    /// Parses incoming websocket `json` before it's dispatched. Frames not matching `T` are
+    /// counted as `unparseable` dropped frames, their raw payload being logged at debug level,
+    /// so that changes to exchange's message format don't go unnoticed.
+    fn parse_ws_message<T: DeserializeOwned>(&self, json: &str) -> Option<T> {
+        match from_str::<T>(json) {
+            Ok(message) => Some(message),
+            Err(error) => {
+                let exchange = self.get_exchange_name();
+                METRICS.inc_ws_dropped_frames(exchange, "unparseable");
+                eprintln!("{} websocket message parse error: {:?}", exchange, error);
+                debug!("{} unparseable websocket message: {}", exchange, json);
+                None
+            }
+        }
+    }
+
+    /// Drops a parsed websocket `message` that has no handler, counting it as `unhandled`.
+    fn drop_unhandled_ws_message(&self, message: impl Debug) {
+        let exchange = self.get_exchange_name();
+        METRICS.inc_ws_dropped_frames(exchange, "unhandled");
+        println!("unhandled {} websocket message {:?}", exchange, message);
+    }
+
+    /// How exchange compresses binary ws frames, which are inflated before ticks are parsed.
+    fn get_ws_compression(&self) -> WsCompression {
+        WsCompression::None
+    }
+
+    /// Emits order book depth snapshots, if exchange streams them. As historical klines carry
+    /// no depth, snapshots are only available live.
+    fn get_depth_emitter(&self) -> Option<&BehaviorSubject<Option<OrderBookDepth>>> {
+        None
+    }
+
+    /// Counts klines updates handled by klines consumer, initial ones included, if exchange paces
+    /// its updates by them. It's `None` until consumer starts acknowledging updates, so that exchange doesn't
+    /// wait on them otherwise.
+    fn get_kline_ack_emitter(&self) -> Option<&BehaviorSubject<Option<usize>>> {
+        None
+    }
+
     fn handle_committed_ticks_data(
         &self,
         discard_ticks_before: NaiveDateTime,
@@ -220,9 +336,8 @@ pub trait DataProviderExchange: Clone {
 
     fn init(
         &mut self,
-        benchmark_start: Option<NaiveDateTime>,
-        benchmark_end: Option<NaiveDateTime>,
-        run_benchmark_only: bool,
+        benchmark_window: BenchmarkWindow,
+        run_mode: RunMode,
         trading_data_schema: Schema,
     ) -> impl Future<Output = Result<(), GlowError>> + Send;
 
@@ -236,4 +351,12 @@ pub trait DataProviderExchange: Clone {
         &mut self,
         wss: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
     ) -> impl Future<Output = Result<(), GlowError>> + Send;
+
+    /// Subscribes to order book depth stream over `wss`, if exchange supports it.
+    fn subscribe_to_depth_stream(
+        &mut self,
+        _wss: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> impl Future<Output = Result<(), GlowError>> + Send {
+        async { Ok(()) }
+    }
 }
@@ -1,6 +1,6 @@
+pub mod clock;
 pub mod exchange;
-// TODO: deprecate this
-// pub mod indicator;
-// TODO: deprecate this
-// pub mod signal;
+pub mod indicator;
+pub mod signal;
+pub mod trade_event_sink;
 pub mod ws_processer;
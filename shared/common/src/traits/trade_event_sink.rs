@@ -0,0 +1,27 @@
+use crate::{
+    enums::signal_category::SignalCategory,
+    structs::{Order, Trade},
+};
+
+/// Hook notified of trade lifecycle events as trader acts upon them, so that they can be reacted
+/// upon, e.g. by sending notifications or persisting them, without changing the trader.
+///
+/// Every method defaults to a no-op, so implementors only override events they're interested in.
+/// Methods are called from trader's handlers, so they shouldn't block for long.
+pub trait TradeEventSink {
+    /// Called once `order` opening a new trade is first updated.
+    fn on_open(&self, _order: &Order) {}
+
+    /// Called once `trade` is closed.
+    fn on_close(&self, _trade: &Trade) {}
+
+    /// Called once `trade` open order is cancelled.
+    fn on_cancel(&self, _trade: &Trade) {}
+
+    /// Called once `trade` is updated by an exchange side stop.
+    fn on_stop(&self, _trade: &Trade) {}
+
+    /// Called for each emitted `signal`, other than `SignalCategory::KeepPosition`, before it's
+    /// acted upon.
+    fn on_signal(&self, _signal: SignalCategory) {}
+}
@@ -0,0 +1,123 @@
+use super::functions::count_position_trades;
+use glow_error::GlowError;
+use polars::prelude::*;
+use std::collections::BTreeMap;
+
+/// Benchmark outcome of a single bar, as set by `BenchmarkColumns::set_columns`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BarState {
+    pub position: i32,
+    pub action: String,
+    pub balance: f64,
+    pub returns: f64,
+}
+
+impl BarState {
+    fn differs_from(&self, other: &BarState) -> bool {
+        let differs = |value: f64, other_value: f64| {
+            value != other_value && !(value.is_nan() && other_value.is_nan())
+        };
+        self.position != other.position
+            || self.action != other.action
+            || differs(self.balance, other.balance)
+            || differs(self.returns, other.returns)
+    }
+}
+
+/// Bar whose benchmark outcome diverged between runs. Missing states mean the bar wasn't
+/// benchmarked by that run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BarDiff {
+    pub start_time: i64,
+    pub old: Option<BarState>,
+    pub new: Option<BarState>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BenchmarkDiff {
+    /// diverging bars, sorted by start time.
+    pub bars: Vec<BarDiff>,
+    pub final_balance_change: f64,
+    pub trade_count_change: i64,
+}
+
+impl BenchmarkDiff {
+    pub fn is_empty(&self) -> bool {
+        self.bars.is_empty() && self.final_balance_change == 0.0 && self.trade_count_change == 0
+    }
+}
+
+/// Compares two benchmark result frames, aligning their bars by `start_time`, and reports
+/// bars where `position`, `action`, `balance` or `returns` differ, as well as how final
+/// balance and trade count changed from `old` to `new`.
+pub fn diff(old: &DataFrame, new: &DataFrame) -> Result<BenchmarkDiff, GlowError> {
+    let old_states = get_bar_states(old)?;
+    let new_states = get_bar_states(new)?;
+
+    let mut start_times = old_states
+        .keys()
+        .chain(new_states.keys())
+        .collect::<Vec<_>>();
+    start_times.sort();
+    start_times.dedup();
+    let bars = start_times
+        .into_iter()
+        .filter_map(|start_time| {
+            let old_state = old_states.get(start_time);
+            let new_state = new_states.get(start_time);
+            let differs = match (old_state, new_state) {
+                (Some(old_state), Some(new_state)) => old_state.differs_from(new_state),
+                _ => true,
+            };
+            differs.then(|| BarDiff {
+                start_time: *start_time,
+                old: old_state.cloned(),
+                new: new_state.cloned(),
+            })
+        })
+        .collect();
+
+    let get_final_balance = |states: &BTreeMap<i64, BarState>| {
+        states
+            .values()
+            .last()
+            .map(|state| state.balance)
+            .unwrap_or_default()
+    };
+    let get_trade_count = |states: &BTreeMap<i64, BarState>| {
+        let positions = states
+            .values()
+            .map(|state| state.position)
+            .collect::<Vec<i32>>();
+        count_position_trades(&positions) as i64
+    };
+
+    Ok(BenchmarkDiff {
+        bars,
+        final_balance_change: get_final_balance(&new_states) - get_final_balance(&old_states),
+        trade_count_change: get_trade_count(&new_states) - get_trade_count(&old_states),
+    })
+}
+
+fn get_bar_states(df: &DataFrame) -> Result<BTreeMap<i64, BarState>, GlowError> {
+    let start_times = df.column("start_time")?.timestamp(TimeUnit::Milliseconds)?;
+    let positions = df.column("position")?.i32()?;
+    let actions = df.column("action")?.utf8()?;
+    let balances = df.column("balance")?.f64()?;
+    let returns = df.column("returns")?.f64()?;
+
+    let mut states = BTreeMap::new();
+    for index in 0..df.height() {
+        let Some(start_time) = start_times.get(index) else {
+            continue;
+        };
+        let state = BarState {
+            position: positions.get(index).unwrap_or_default(),
+            action: actions.get(index).unwrap_or_default().to_string(),
+            balance: balances.get(index).unwrap_or(f64::NAN),
+            returns: returns.get(index).unwrap_or(f64::NAN),
+        };
+        states.insert(start_time, state);
+    }
+    Ok(states)
+}
@@ -1,16 +1,24 @@
-use super::{round_down_nth_decimal, round_nth_decimal, BenchmarkTradeError};
+use super::{
+    get_margin_decimals, quantize_balance, round_down_nth_decimal, round_nth_decimal,
+    BenchmarkTradeError,
+};
 use crate::benchmark::{
     count_decimal_places, new_benchmark_trade, BenchmarkTrade, NewBenchmarkTradeParams, PriceLock,
 };
+use crate::config::DEFAULT_BENCHMARK_INITIAL_BALANCE;
 use crate::trader::Trader;
+use common::enums::modifiers::price_level::PriceLevel;
 use common::enums::order_type::OrderType;
 use common::enums::side::Side;
 use common::enums::signal_category::SignalCategory;
-use common::functions::{get_price_columns_f32, get_signal_col_values};
+use common::functions::{
+    get_price_columns_f32, get_signal_col_values, is_at_or_above, is_at_or_below,
+};
+use common::structs::{SignalPriority, TradingSettings};
 use common::traits::exchange::{BenchmarkExchange, TraderHelper};
 use glow_error::GlowError;
 use polars::prelude::*;
-use std::time::Instant;
+use std::{mem::take, time::Instant};
 
 #[derive(Clone, Copy, Debug)]
 enum IterationsError {
@@ -20,7 +28,7 @@ enum IterationsError {
 
 #[derive(Clone, Debug)]
 struct IterationData {
-    fee: f32,
+    fees: (f32, f32), // (open fee, close fee)
     units: f32,
     pnl: f32,
     roi: f32,
@@ -32,7 +40,7 @@ struct IterationData {
 
 impl IterationData {
     pub fn new(
-        fee: f32,
+        fees: (f32, f32),
         units: f32,
         pnl: f32,
         roi: f32,
@@ -42,7 +50,7 @@ impl IterationData {
         action: String,
     ) -> Self {
         Self {
-            fee,
+            fees,
             units,
             pnl,
             roi,
@@ -54,34 +62,339 @@ impl IterationData {
     }
 }
 
+/// Per-bar state of the benchmark loop.
+///
+/// It's checkpointed at the end of every run, so that a later run can resume from it,
+/// processing only the bars appended since then.
+#[derive(Clone, Debug)]
+pub struct BenchmarkCheckpoint {
+    open_fees: Vec<f32>,
+    close_fees: Vec<f32>,
+    units: Vec<f32>,
+    profit_and_loss: Vec<f32>,
+    returns: Vec<f32>,
+    balances: Vec<f32>,
+    fundings: Vec<f32>,
+    funding_fees: Vec<f32>,
+    positions: Vec<i32>,
+    actions: Vec<String>,
+    current_trade: Option<BenchmarkTrade>,
+    current_min_price_threshold: Option<f32>,
+    current_max_price_threshold: Option<f32>,
+    current_peak_returns: f32, // peak favorable returns of current trade, for trailing take profit
+    halted: bool, // whether an iteration failed, so remaining bars just repeat last values
+    last_exit: Option<(i64, SignalCategory)>, // last trade's exit timestamp and reason
+    current_open_timestamp: Option<i64>,
+    current_pyramid_adds: usize,      // times current trade was added to
+    current_take_profit_level: usize, // take profit ladder levels current trade scaled out at
+    processed_start_times: Option<(i64, i64)>, // start times of first and last processed bars
+}
+
+impl Default for BenchmarkCheckpoint {
+    fn default() -> Self {
+        Self::new(DEFAULT_BENCHMARK_INITIAL_BALANCE as f32)
+    }
+}
+
+impl BenchmarkCheckpoint {
+    /// Checkpoint preceding any processed bar, starting off with `initial_balance`.
+    pub fn new(initial_balance: f32) -> Self {
+        Self {
+            open_fees: vec![0.0],
+            close_fees: vec![0.0],
+            units: vec![0.0],
+            profit_and_loss: vec![0.0],
+            returns: vec![0.0],
+            balances: vec![initial_balance],
+            fundings: vec![0_f32],
+            funding_fees: vec![0_f32],
+            positions: vec![0],
+            actions: vec![SignalCategory::KeepPosition.get_column().to_owned()],
+            current_trade: None,
+            current_min_price_threshold: None,
+            current_max_price_threshold: None,
+            current_peak_returns: 0.0,
+            halted: false,
+            last_exit: None,
+            current_open_timestamp: None,
+            current_pyramid_adds: 0,
+            current_take_profit_level: 0,
+            processed_start_times: None,
+        }
+    }
+
+    /// Marks the first `warmup_bars` as processed without any position, so that benchmark
+    /// only acts upon signals emitted once indicators have their whole lookback.
+    pub fn skip_warmup(mut self, warmup_bars: usize) -> Self {
+        let missing_bars = warmup_bars.saturating_sub(self.get_processed_bars());
+        let keep_position = SignalCategory::KeepPosition.get_column().to_owned();
+        let initial_balance = self.balances[0];
+        self.open_fees.extend(vec![0.0; missing_bars]);
+        self.close_fees.extend(vec![0.0; missing_bars]);
+        self.units.extend(vec![0.0; missing_bars]);
+        self.profit_and_loss.extend(vec![0.0; missing_bars]);
+        self.returns.extend(vec![0.0; missing_bars]);
+        self.balances.extend(vec![initial_balance; missing_bars]);
+        self.fundings.extend(vec![0.0; missing_bars]);
+        self.funding_fees.extend(vec![0.0; missing_bars]);
+        self.positions.extend(vec![0; missing_bars]);
+        self.actions.extend(vec![keep_position; missing_bars]);
+        self
+    }
+
+    pub fn get_processed_bars(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Checks whether bars starting at `start_times` are the ones processed by checkpoint,
+    /// optionally followed by appended bars, so that it can be resumed over them.
+    pub fn is_resumable_over(&self, start_times: &[i64]) -> bool {
+        let Some((first_start_time, last_start_time)) = self.processed_start_times else {
+            return false;
+        };
+        start_times.first() == Some(&first_start_time)
+            && start_times.get(self.get_processed_bars() - 1) == Some(&last_start_time)
+    }
+}
+
+/// Benchmark signal columns, one value per bar.
+#[derive(Clone, Debug, Default)]
+pub struct BenchmarkSignals {
+    pub shorts: Vec<i32>,
+    pub longs: Vec<i32>,
+    pub close_shorts: Vec<i32>,
+    pub close_longs: Vec<i32>,
+    /// whether each bar was inserted in place of a missing one. Empty if no bar was.
+    pub gaps: Vec<bool>,
+    /// resolves signals fired at the same bar, same as live trading.
+    pub priority: SignalPriority,
+}
+
+impl BenchmarkSignals {
+    pub fn new(df: &DataFrame) -> Result<Self, GlowError> {
+        let gaps = match df.column("is_gap") {
+            Ok(series) => series
+                .bool()?
+                .into_iter()
+                .map(|is_gap| is_gap.unwrap_or_default())
+                .collect(),
+            Err(_) => vec![],
+        };
+        Ok(Self {
+            shorts: get_signal_col_values(df, SignalCategory::GoShort)?,
+            longs: get_signal_col_values(df, SignalCategory::GoLong)?,
+            close_shorts: get_signal_col_values(df, SignalCategory::CloseShort)?,
+            close_longs: get_signal_col_values(df, SignalCategory::CloseLong)?,
+            gaps,
+            priority: SignalPriority::default(),
+        })
+    }
+
+    pub fn with_priority(mut self, priority: SignalPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    fn is_gap(&self, index: usize) -> bool {
+        self.gaps.get(index).copied().unwrap_or_default()
+    }
+}
+
+/// Benchmark results, one value per bar.
+///
+/// Balances already account for funds suspended from trading, as well as funding fees,
+/// which are also reported apart from trade fees. Trade fees are the sum of open and close fees.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BenchmarkColumns {
+    pub trade_fees: Vec<f32>,
+    pub open_fees: Vec<f32>,
+    pub close_fees: Vec<f32>,
+    pub funding_fees: Vec<f32>,
+    pub units: Vec<f32>,
+    pub profit_and_loss: Vec<f32>,
+    pub returns: Vec<f32>,
+    pub balances: Vec<f32>,
+    pub positions: Vec<i32>,
+    pub actions: Vec<String>,
+}
+
+impl BenchmarkColumns {
+    /// Sets results as `df` columns, which must have one row per bar.
+    pub fn set_columns(self, df: &mut DataFrame) -> Result<(), GlowError> {
+        let to_f64 = |values: Vec<f32>| values.into_iter().map(f64::from).collect::<Vec<f64>>();
+        df.with_column(Series::new("trade_fees", to_f64(self.trade_fees)))?;
+        df.with_column(Series::new("open_fees", to_f64(self.open_fees)))?;
+        df.with_column(Series::new("close_fees", to_f64(self.close_fees)))?;
+        df.with_column(Series::new("funding_fees", to_f64(self.funding_fees)))?;
+        df.with_column(Series::new("units", to_f64(self.units)))?;
+        df.with_column(Series::new("profit_and_loss", to_f64(self.profit_and_loss)))?;
+        df.with_column(Series::new("returns", to_f64(self.returns)))?;
+        df.with_column(Series::new("balance", to_f64(self.balances)))?;
+        df.with_column(Series::new("position", self.positions))?;
+        df.with_column(Series::new("action", self.actions))?;
+        Ok(())
+    }
+
+    /// Balances marked at trades' closes, i.e. balances of bars without position, carried over
+    /// bars with an open position, as their balance doesn't account for its margin.
+    pub fn get_marked_balances(&self, initial_balance: f32) -> Vec<f64> {
+        let mut marked_balance = initial_balance as f64;
+        self.balances
+            .iter()
+            .zip(self.positions.iter())
+            .map(|(&balance, &position)| {
+                if position == 0 {
+                    marked_balance = balance as f64;
+                }
+                marked_balance
+            })
+            .collect()
+    }
+}
+
+/// Counts trades opened over per-bar positions, i.e. bars whose position is set and
+/// differs from previous bar's one.
+pub fn count_position_trades(positions: &[i32]) -> usize {
+    positions
+        .windows(2)
+        .filter(|positions| positions[1] != 0 && positions[1] != positions[0])
+        .count()
+}
+
+/// Computes benchmark positions over the whole `initial_strategy_df`.
 pub fn compute_benchmark_positions(
     trader: &Trader,
     initial_strategy_df: DataFrame,
 ) -> Result<DataFrame, GlowError> {
-    // let data = data.to_owned();
+    let mut checkpoint = BenchmarkCheckpoint::default();
+    resume_benchmark_positions(trader, initial_strategy_df, &mut checkpoint)
+}
+
+/// Computes benchmark positions only for the bars of `strategy_df` which weren't processed
+/// by `checkpoint` yet, updating it afterwards.
+///
+/// Bars already processed are expected to be unchanged, i.e. `strategy_df` must only have
+/// appended bars since the checkpoint run.
+pub fn resume_benchmark_positions(
+    trader: &Trader,
+    strategy_df: DataFrame,
+    checkpoint: &mut BenchmarkCheckpoint,
+) -> Result<DataFrame, GlowError> {
     // TODO: TRY TO IMPLEMENT THIS USING LAZYFRAMES
     let perf_start = Instant::now();
 
-    let mut df = initial_strategy_df;
+    let mut df = strategy_df;
     let df_height = df.height();
+    let processed_bars = checkpoint.get_processed_bars();
+    if df_height < processed_bars {
+        let error = format!(
+            "strategy data has {} bars, but checkpoint has already processed {}",
+            df_height, processed_bars
+        );
+        return Err(GlowError::new(
+            String::from("Invalid Benchmark Checkpoint"),
+            error,
+        ));
+    }
 
     let traded_symbol = trader.trader_exchange.get_traded_symbol();
-    let traded_contract = trader.trader_exchange.get_traded_contract();
-    let (opens, highs, lows, closes) = get_price_columns_f32(&df, &traded_symbol)?;
-    let shorts = get_signal_col_values(&df, SignalCategory::GoShort)?;
-    let longs = get_signal_col_values(&df, SignalCategory::GoShort)?;
-    let close_shorts = get_signal_col_values(&df, SignalCategory::CloseShort)?;
-    let close_longs = get_signal_col_values(&df, SignalCategory::CloseLong)?;
-
-    let mut trade_fees = vec![0.0];
-    let mut units = vec![0.0];
-    let mut profit_and_loss = vec![0.0];
-    let mut returns = vec![0.0];
-    let mut balances = vec![100.0];
-    let mut fundings = vec![0_f32];
-    let mut positions = vec![0];
-    let mut actions = vec![SignalCategory::KeepPosition.get_column().to_owned()];
-    let trading_settings = trader.trader_exchange.get_trading_settings();
+    let (opens, highs, lows, closes) = get_price_columns_f32(&df, traded_symbol)?;
+    let timestamps = df
+        .column("start_time")?
+        .timestamp(TimeUnit::Milliseconds)?
+        .into_no_null_iter()
+        .collect::<Vec<i64>>();
+    let signals = BenchmarkSignals::new(&df)?.with_priority(trader.get_signal_priority());
+
+    let benchmark_columns = resume_simulated_positions(
+        &opens,
+        &highs,
+        &lows,
+        &closes,
+        &timestamps,
+        &signals,
+        trader.trader_exchange.get_trading_settings(),
+        &trader.trader_exchange,
+        checkpoint,
+    );
+
+    let elapsed_time = perf_start.elapsed();
+    let elapsed_millis = elapsed_time.as_nanos();
+    println!(
+        "compute_benchmark_positions => Elapsed time in nanos: {}",
+        elapsed_millis
+    );
+
+    benchmark_columns.set_columns(&mut df)?;
+
+    Ok(df)
+}
+
+/// Simulates benchmark positions over per-bar prices and signals, starting off with `initial_balance`.
+///
+/// Signals are acted upon at the opening of the bar following the one which emitted them.
+/// If a bar can't be processed, e.g. due to insufficient funds, remaining bars repeat last values.
+#[allow(clippy::too_many_arguments)]
+pub fn simulate_positions(
+    opens: &[f32],
+    highs: &[f32],
+    lows: &[f32],
+    closes: &[f32],
+    timestamps: &[i64],
+    signals: &BenchmarkSignals,
+    settings: &TradingSettings,
+    exchange: &dyn BenchmarkExchange,
+    initial_balance: f32,
+) -> BenchmarkColumns {
+    let mut checkpoint = BenchmarkCheckpoint::new(initial_balance);
+    resume_simulated_positions(
+        opens,
+        highs,
+        lows,
+        closes,
+        timestamps,
+        signals,
+        settings,
+        exchange,
+        &mut checkpoint,
+    )
+}
+
+/// Same as `simulate_positions`, but only processing bars after the ones already processed by
+/// `checkpoint`, which gets updated afterwards.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn resume_simulated_positions(
+    opens: &[f32],
+    highs: &[f32],
+    lows: &[f32],
+    closes: &[f32],
+    timestamps: &[i64],
+    signals: &BenchmarkSignals,
+    trading_settings: &TradingSettings,
+    exchange: &dyn BenchmarkExchange,
+    checkpoint: &mut BenchmarkCheckpoint,
+) -> BenchmarkColumns {
+    let bars = timestamps.len();
+    let processed_bars = checkpoint.get_processed_bars();
+    let BenchmarkSignals {
+        shorts,
+        longs,
+        close_shorts,
+        close_longs,
+        ..
+    } = signals;
+
+    let mut open_fees = take(&mut checkpoint.open_fees);
+    let mut close_fees = take(&mut checkpoint.close_fees);
+    let mut units = take(&mut checkpoint.units);
+    let mut profit_and_loss = take(&mut checkpoint.profit_and_loss);
+    let mut returns = take(&mut checkpoint.returns);
+    let mut balances = take(&mut checkpoint.balances);
+    let mut fundings = take(&mut checkpoint.fundings);
+    let mut funding_fees = take(&mut checkpoint.funding_fees);
+    let mut positions = take(&mut checkpoint.positions);
+    let mut actions = take(&mut checkpoint.actions);
+    let traded_contract = exchange.get_traded_contract();
     let leverage_factor = trading_settings.leverage.get_factor() as f32;
     let has_leverage = leverage_factor > 1.0;
 
@@ -92,10 +405,17 @@ pub fn compute_benchmark_positions(
     let take_profit: Option<PriceLock> = price_level_modifier_map_binding
         .get("tp")
         .map_or(None, |tp| Some(tp.clone().into()));
-    let should_check_price_modifiers = has_leverage || stop_loss.is_some() || take_profit.is_some();
+    let trailing_take_profit = match price_level_modifier_map_binding.get("ttp") {
+        Some(PriceLevel::TrailingTakeProfit(trailing_take_profit)) => Some(*trailing_take_profit),
+        _ => None,
+    };
+    let should_check_price_modifiers = has_leverage
+        || stop_loss.is_some()
+        || take_profit.is_some()
+        || trailing_take_profit.is_some();
 
-    let maker_fee_rate = trader.trader_exchange.get_maker_fee() as f32;
-    let taker_fee_rate = trader.trader_exchange.get_taker_fee() as f32;
+    let (maker_fee_rate, taker_fee_rate) = exchange.get_benchmark_fee_rates();
+    let (maker_fee_rate, taker_fee_rate) = (maker_fee_rate as f32, taker_fee_rate as f32);
     let open_order_fee_rate = if trading_settings.order_types.0 == OrderType::Market {
         taker_fee_rate
     } else {
@@ -116,36 +436,70 @@ pub fn compute_benchmark_positions(
     );
     let tick_size = traded_contract.tick_size;
     let price_locks = (stop_loss, take_profit);
-    let minimum_notional_value = trader
-        .trader_exchange
-        .get_minimum_notional_value()
+    let minimum_notional_value = traded_contract
+        .min_notional
+        .or(exchange.get_minimum_notional_value())
         .map(|v| v as f32);
+    let funding_rate = trading_settings
+        .benchmark_funding_rate
+        .unwrap_or(traded_contract.funding_rate) as f32;
+    let funding_interval_ms = traded_contract.funding_interval.num_milliseconds();
 
-    let mut current_trade: Option<BenchmarkTrade> = None;
-    // let mut current_peak_returns = 0.0;
-    let mut current_min_price_threshold = None;
-    let mut current_max_price_threshold = None;
+    let mut current_trade: Option<BenchmarkTrade> = checkpoint.current_trade;
+    let mut current_peak_returns = checkpoint.current_peak_returns;
+    let mut current_min_price_threshold = checkpoint.current_min_price_threshold;
+    let mut current_max_price_threshold = checkpoint.current_max_price_threshold;
+    let mut halted = checkpoint.halted;
+    let mut last_exit = checkpoint.last_exit;
+    let mut current_open_timestamp = checkpoint.current_open_timestamp;
+    let mut current_pyramid_adds = checkpoint.current_pyramid_adds;
+    let mut current_take_profit_level = checkpoint.current_take_profit_level;
+    let mut skipped_open_signals = 0;
+    let mut skipped_sub_notional_signals = 0;
     let symbol_decimals = count_decimal_places(order_sizes.0);
     let tick_decimals = count_decimal_places(tick_size as f32);
+    let margin_decimals = get_margin_decimals(traded_contract.kind, tick_decimals);
     let allocation_pct = trading_settings.allocation_percentage as f32;
+    let position_lock = trading_settings.position_lock_modifier;
+    let max_pyramid_adds = trading_settings.max_pyramid_adds;
+    let slippage_bps = trading_settings.benchmark_slippage_bps;
+    let take_profit_partial_fraction = trading_settings
+        .get_take_profit_partial_fraction()
+        .map(|fraction| fraction as f32);
+    let take_profit_ladder = trading_settings.get_take_profit_ladder();
+    let price_level_epsilon = trading_settings.get_price_level_epsilon();
+    let quantize_balances = trading_settings.quantize_benchmark_balances;
 
     // need to be updated
-    // trade_fees, units, profit_and_loss, returns, balances, positions, actions
-    let mut index = 0;
-
-    while index < df_height {
-        if index == 0 {
-            index += 1;
-            continue;
-        }
+    // open_fees, close_fees, units, profit_and_loss, returns, balances, positions, actions
+    let mut index = processed_bars;
 
+    while index < bars && !halted {
         let current_position = positions[index - 1];
         let current_units = units[index - 1];
-        let current_balance = balances[index - 1];
+        // bars inserted in place of missing ones just keep position, and signals emitted before
+        // a gap aren't acted upon after it, as bars around it aren't adjacent
+        let is_gap_bar = signals.is_gap(index);
+        let is_after_gap = signals.is_gap(index - 1)
+            || trading_settings.is_after_kline_gap(timestamps[index - 1], timestamps[index]);
+        let has_signal = |signal_values: &[i32]| !is_after_gap && signal_values[index - 1] == 1;
+        // funding is charged on open positions whenever a funding time is crossed
+        let funding_fee = match current_trade {
+            Some(trade)
+                if !is_gap_bar
+                    && funding_interval_ms > 0
+                    && timestamps[index] / funding_interval_ms
+                        > timestamps[index - 1] / funding_interval_ms =>
+            {
+                trade.get_funding_fee(opens[index], funding_rate)
+            }
+            _ => 0.0,
+        };
+        let current_balance = balances[index - 1] - funding_fee;
         let current_funding = fundings[index - 1];
 
         let default_results = IterationData::new(
-            0_f32,
+            (0.0, 0.0),
             current_units,
             0_f32,
             0_f32,
@@ -155,11 +509,27 @@ pub fn compute_benchmark_positions(
             SignalCategory::KeepPosition.get_column().to_owned(),
         );
 
-        let result: Result<IterationData, IterationsError> = if current_position == 0 {
-            let should_short = shorts[index - 1] == 1;
-            let should_long = longs[index - 1] == 1;
-            if should_short || should_long {
-                let open_price = opens[index];
+        let result: Result<IterationData, IterationsError> = if is_gap_bar {
+            Ok(default_results)
+        } else if current_position == 0 {
+            let mut fired_signals = vec![];
+            if has_signal(longs) {
+                fired_signals.push(SignalCategory::GoLong);
+            }
+            if has_signal(shorts) {
+                fired_signals.push(SignalCategory::GoShort);
+            }
+            let resolved_signal = signals.priority.resolve(&fired_signals);
+            let should_short = resolved_signal == Some(SignalCategory::GoShort);
+            let should_long = resolved_signal == Some(SignalCategory::GoLong);
+            let is_in_cooldown = (should_short || should_long)
+                && trading_settings.is_in_trade_cooldown(last_exit, timestamps[index]);
+            if is_in_cooldown {
+                skipped_open_signals += 1;
+            }
+            if (should_short || should_long) && !is_in_cooldown {
+                let side = if should_short { Side::Sell } else { Side::Buy };
+                let open_price = side.apply_slippage(opens[index] as f64, slippage_bps) as f32;
                 let close_price = closes[index];
                 let new_benchmark_trade_params = NewBenchmarkTradeParams::new(
                     allocation_pct,
@@ -170,11 +540,12 @@ pub fn compute_benchmark_positions(
                     order_sizes,
                     open_price,
                     price_locks,
-                    if should_short { Side::Sell } else { Side::Buy },
+                    side,
                     symbol_decimals,
                     taker_fee_rate,
                     tick_decimals,
-                );
+                )
+                .with_contract_kind(traded_contract.kind);
                 let trade_result_params = TradeResultParams::new(
                     close_price,
                     close_order_fee_rate,
@@ -193,6 +564,14 @@ pub fn compute_benchmark_positions(
                         );
                         Ok(result)
                     }
+                    // exchanges reject orders below minimum notional value, so, unless there are
+                    // suspended funds to retry with, signal is skipped, as live trading would
+                    Err(BenchmarkTradeError::ValueLessThanNotionalMin { .. })
+                        if current_funding == 0.0 =>
+                    {
+                        skipped_sub_notional_signals += 1;
+                        Ok(default_results)
+                    }
                     Err(error) => {
                         let result = on_open_trade_error(
                             error,
@@ -215,26 +594,48 @@ pub fn compute_benchmark_positions(
         } else {
             let trade = &current_trade.unwrap();
             let current_side = trade.side;
+            let close_side = current_side.get_opposite_side().unwrap_or_default();
             let stopped_result = if should_check_price_modifiers {
                 let min_price = lows[index];
                 let max_price = highs[index];
-                let binds_on_min_price =
-                    min_price <= current_min_price_threshold.unwrap_or_default();
+                // trailing take profit price is only known after prior bars, and being
+                // on the profit side, it's reached before stop loss or bankruptcy prices
+                let trailing_price = trailing_take_profit
+                    .and_then(|ttp| {
+                        ttp.get_acceptable_returns(current_peak_returns as f64, price_level_epsilon)
+                    })
+                    .map(|returns| trade.get_price_at_returns(returns as f32));
+                let (min_price_threshold, max_price_threshold) = get_trailing_threshold_prices(
+                    current_side,
+                    (current_min_price_threshold, current_max_price_threshold),
+                    trailing_price,
+                );
+                let binds_on_min_price = min_price_threshold.is_some_and(|threshold| {
+                    is_at_or_below(min_price as f64, threshold as f64, price_level_epsilon)
+                });
                 let binds_on_max_price = !binds_on_min_price
-                    && max_price >= current_max_price_threshold.unwrap_or_default();
+                    && max_price_threshold.is_some_and(|threshold| {
+                        is_at_or_above(max_price as f64, threshold as f64, price_level_epsilon)
+                    });
 
                 if binds_on_min_price || binds_on_max_price {
                     // let prev_close_price = closes[index - 1];
                     // let prev_end_timestamp = end_timestamps[index - 1];
-                    let (pnl, roi, close_fee) = trade.get_pnl_returns_and_fees(
-                        if binds_on_min_price {
-                            current_min_price_threshold.unwrap()
-                        } else {
-                            current_max_price_threshold.unwrap()
-                        },
-                        close_order_fee_rate,
-                    );
+                    let binding_price = if binds_on_min_price {
+                        min_price_threshold.unwrap()
+                    } else {
+                        max_price_threshold.unwrap()
+                    };
+                    let close_price =
+                        close_side.apply_slippage(binding_price as f64, slippage_bps) as f32;
+                    let binds_on_trailing_price = trailing_price == Some(binding_price)
+                        && match current_side {
+                            Side::Buy => binds_on_min_price,
+                            Side::Sell => binds_on_max_price,
+                            Side::None => false,
+                        };
                     let action = match (current_side, binds_on_max_price, binds_on_min_price) {
+                        _ if binds_on_trailing_price => SignalCategory::TrailingTakeProfit,
                         (Side::Buy, true, _) => SignalCategory::TakeProfit,
                         (Side::Buy, _, true) => {
                             if trade.prices.2.is_some() {
@@ -253,25 +654,77 @@ pub fn compute_benchmark_positions(
                         (Side::Sell, _, true) => SignalCategory::TakeProfit,
                         (_, _, _) => unreachable!(),
                     };
-                    let result = IterationData::new(
-                        close_fee,
-                        0.0,
-                        pnl,
-                        roi,
-                        f32::max(
+                    // take profit only scales out of position if the remaining units are tradable,
+                    // and ladders close their last level's remaining position
+                    let scale_out_fraction = match &take_profit_ladder {
+                        Some(ladder) => ladder
+                            .get_remaining_fraction(current_take_profit_level)
+                            .map(|fraction| fraction as f32),
+                        None => take_profit_partial_fraction,
+                    };
+                    let scaled_out_trades = if action == SignalCategory::TakeProfit
+                        && trade.prices.3 == Some(binding_price)
+                    {
+                        scale_out_fraction
+                            .and_then(|fraction| trade.scale_out(fraction))
+                            .filter(|(_, remaining_trade)| remaining_trade.units >= order_sizes.0)
+                    } else {
+                        None
+                    };
+                    if let Some((closed_trade, mut remaining_trade)) = scaled_out_trades {
+                        let (pnl, roi, close_fee) = closed_trade
+                            .get_pnl_returns_and_fees(close_price, close_order_fee_rate);
+                        let result = IterationData::new(
+                            (0.0, close_fee),
+                            remaining_trade.units,
+                            pnl,
+                            roi,
+                            f32::max(
+                                0.0,
+                                round_nth_decimal(
+                                    current_balance + closed_trade.initial_margin + pnl,
+                                    margin_decimals,
+                                ),
+                            ),
+                            current_funding,
+                            current_position,
+                            action.get_column().to_owned(),
+                        );
+                        // remaining position takes profit at next ladder level, if any
+                        if let Some(ladder) = &take_profit_ladder {
+                            current_take_profit_level += 1;
+                            remaining_trade.prices.3 = ladder
+                                .get_percentage(current_take_profit_level)
+                                .map(|percentage| trade.get_price_at_returns(percentage as f32));
+                        }
+                        (current_min_price_threshold, current_max_price_threshold) =
+                            remaining_trade.get_threshold_prices();
+                        current_trade = Some(remaining_trade);
+                        Some(result)
+                    } else {
+                        let (pnl, roi, close_fee) =
+                            trade.get_pnl_returns_and_fees(close_price, close_order_fee_rate);
+                        let result = IterationData::new(
+                            (0.0, close_fee),
                             0.0,
-                            round_nth_decimal(
-                                current_balance + trade.initial_margin + pnl,
-                                tick_decimals,
+                            pnl,
+                            roi,
+                            f32::max(
+                                0.0,
+                                round_nth_decimal(
+                                    current_balance + trade.initial_margin + pnl,
+                                    margin_decimals,
+                                ),
                             ),
-                        ),
-                        current_funding,
-                        0,
-                        action.get_column().to_owned(),
-                    );
-                    (current_min_price_threshold, current_max_price_threshold) = (None, None);
-                    current_trade = None;
-                    Some(result)
+                            current_funding,
+                            0,
+                            action.get_column().to_owned(),
+                        );
+                        (current_min_price_threshold, current_max_price_threshold) = (None, None);
+                        current_trade = None;
+                        last_exit = Some((timestamps[index], action));
+                        Some(result)
+                    }
                 } else {
                     None
                 }
@@ -283,44 +736,147 @@ pub fn compute_benchmark_positions(
                 Ok(stopped_result)
             } else {
                 let open_price = opens[index];
-                let (pnl, roi, close_fee) =
+                let (pnl, roi, _) =
                     trade.get_pnl_returns_and_fees(open_price, close_order_fee_rate);
-                let was_short_closed = close_shorts[index - 1] == 1 && current_side == Side::Sell;
-                let was_long_closed = close_longs[index - 1] == 1 && current_side == Side::Buy;
-
-                let (close_fee, units, balance, position, action) =
-                    if was_short_closed || was_long_closed {
-                        (current_min_price_threshold, current_max_price_threshold) = (None, None);
-                        current_trade = None;
-                        (
-                            close_fee,
-                            0_f32,
-                            f32::max(
-                                0.0,
-                                round_nth_decimal(
-                                    current_balance + trade.initial_margin + pnl,
-                                    tick_decimals,
-                                ),
-                            ),
-                            0,
-                            if was_short_closed {
-                                SignalCategory::CloseShort.get_column().to_owned()
-                            } else {
-                                SignalCategory::CloseLong.get_column().to_owned()
-                            },
-                        )
-                    } else {
-                        (
-                            0_f32,
-                            current_units,
+                let close_price = close_side.apply_slippage(open_price as f64, slippage_bps) as f32;
+                let (close_pnl, close_roi, close_fee) =
+                    trade.get_pnl_returns_and_fees(close_price, close_order_fee_rate);
+                // mirrors live trading position lock, as close fees are estimated at close price
+                let total_fee = trade.open_fee + close_fee;
+                let is_close_locked = position_lock
+                    .is_close_locked((close_pnl + total_fee) as f64, total_fee as f64)
+                    || trading_settings
+                        .is_in_position_lock_bars(current_open_timestamp, timestamps[index]);
+                let (close_signal, close_signals, open_signal, open_signals) = match current_side {
+                    Side::Sell => (
+                        SignalCategory::CloseShort,
+                        close_shorts,
+                        SignalCategory::GoShort,
+                        shorts,
+                    ),
+                    _ => (
+                        SignalCategory::CloseLong,
+                        close_longs,
+                        SignalCategory::GoLong,
+                        longs,
+                    ),
+                };
+                let mut fired_signals = vec![];
+                if !is_close_locked && has_signal(close_signals) {
+                    fired_signals.push(close_signal);
+                }
+                // winning positions are added to on same side open signals, sized from remaining
+                // balance, whereas adds that can't be afforded just keep position
+                if current_pyramid_adds < max_pyramid_adds && pnl > 0.0 && has_signal(open_signals)
+                {
+                    fired_signals.push(open_signal);
+                }
+                let resolved_signal = signals.priority.resolve(&fired_signals);
+                let was_short_closed = resolved_signal == Some(SignalCategory::CloseShort);
+                let was_long_closed = resolved_signal == Some(SignalCategory::CloseLong);
+                let should_add_to_position = resolved_signal == Some(open_signal);
+                let added_trade = if should_add_to_position {
+                    let add_price =
+                        current_side.apply_slippage(open_price as f64, slippage_bps) as f32;
+                    new_benchmark_trade(
+                        NewBenchmarkTradeParams::new(
+                            allocation_pct,
                             current_balance,
-                            current_position,
-                            default_results.action,
+                            leverage_factor,
+                            minimum_notional_value,
+                            open_order_fee_rate,
+                            order_sizes,
+                            add_price,
+                            price_locks,
+                            current_side,
+                            symbol_decimals,
+                            taker_fee_rate,
+                            tick_decimals,
                         )
+                        .with_contract_kind(traded_contract.kind),
+                    )
+                    .ok()
+                } else {
+                    None
+                };
+
+                let (fees, units, pnl, roi, balance, position, action) = if was_short_closed
+                    || was_long_closed
+                {
+                    (current_min_price_threshold, current_max_price_threshold) = (None, None);
+                    current_trade = None;
+                    let exit_reason = if was_short_closed {
+                        SignalCategory::CloseShort
+                    } else {
+                        SignalCategory::CloseLong
                     };
+                    last_exit = Some((timestamps[index], exit_reason));
+                    (
+                        (0.0, close_fee),
+                        0_f32,
+                        close_pnl,
+                        close_roi,
+                        f32::max(
+                            0.0,
+                            round_nth_decimal(
+                                current_balance + trade.initial_margin + close_pnl,
+                                margin_decimals,
+                            ),
+                        ),
+                        0,
+                        exit_reason.get_column().to_owned(),
+                    )
+                } else if let Some(added_trade) = added_trade {
+                    let mut merged_trade = trade.add_to_position(&added_trade, price_locks);
+                    // take profit ladder resumes from its current level, at merged entry price
+                    if let Some(ladder) = &take_profit_ladder {
+                        if current_take_profit_level > 0 && merged_trade.prices.3.is_some() {
+                            merged_trade.prices.3 = ladder
+                                .get_percentage(current_take_profit_level)
+                                .map(|percentage| {
+                                    merged_trade.get_price_at_returns(percentage as f32)
+                                });
+                        }
+                    }
+                    let (pnl, roi, _) =
+                        merged_trade.get_pnl_returns_and_fees(closes[index], close_order_fee_rate);
+                    (current_min_price_threshold, current_max_price_threshold) =
+                        merged_trade.get_threshold_prices();
+                    current_trade = Some(merged_trade);
+                    current_pyramid_adds += 1;
+                    (
+                        (added_trade.open_fee, 0.0),
+                        merged_trade.units,
+                        pnl,
+                        roi,
+                        f32::max(
+                            0.0,
+                            round_nth_decimal(
+                                current_balance - added_trade.initial_margin - added_trade.open_fee,
+                                margin_decimals,
+                            ),
+                        ),
+                        current_position,
+                        if current_side == Side::Sell {
+                            SignalCategory::GoShort.get_column().to_owned()
+                        } else {
+                            SignalCategory::GoLong.get_column().to_owned()
+                        },
+                    )
+                } else {
+                    (
+                        (0.0, 0.0),
+                        current_units,
+                        pnl,
+                        roi,
+                        current_balance,
+                        current_position,
+                        default_results.action,
+                    )
+                };
 
                 Ok(IterationData::new(
-                    close_fee,
+                    fees,
                     units,
                     pnl,
                     roi,
@@ -334,13 +890,13 @@ pub fn compute_benchmark_positions(
 
         if result.is_err() {
             println!("result is error {:?}", result);
-            index += 1;
+            halted = true;
 
             break;
         }
 
         let IterationData {
-            fee,
+            fees: (open_fee, close_fee),
             units: iteration_units,
             pnl,
             roi,
@@ -349,8 +905,18 @@ pub fn compute_benchmark_positions(
             position,
             action,
         } = result.unwrap();
+        let (balance, funding) = if quantize_balances {
+            (
+                quantize_balance(balance, margin_decimals),
+                quantize_balance(funding, margin_decimals),
+            )
+        } else {
+            (balance, funding)
+        };
 
-        trade_fees.push(fee);
+        open_fees.push(open_fee);
+        close_fees.push(close_fee);
+        funding_fees.push(funding_fee);
         units.push(iteration_units);
         profit_and_loss.push(pnl);
         returns.push(roi);
@@ -358,14 +924,39 @@ pub fn compute_benchmark_positions(
         fundings.push(funding);
         positions.push(position);
         actions.push(action);
+        current_open_timestamp = match current_trade {
+            Some(_) if current_position == 0 => Some(timestamps[index]),
+            Some(_) => current_open_timestamp,
+            None => None,
+        };
+        if current_trade.is_none() {
+            current_pyramid_adds = 0;
+            current_take_profit_level = 0;
+        }
+        // peak is updated after bar is processed, as intrabar prices order is unknown
+        current_peak_returns = match current_trade {
+            Some(_) if is_gap_bar => current_peak_returns,
+            Some(trade) => {
+                let favorable_price = if trade.side == Side::Sell {
+                    lows[index]
+                } else {
+                    highs[index]
+                };
+                current_peak_returns.max(trade.get_price_returns(favorable_price))
+            }
+            None => 0.0,
+        };
         index += 1;
     }
 
-    if index < df_height {
-        let missing_data_no = df_height - index + 1;
+    if positions.len() < bars {
+        let missing_data_no = bars - positions.len();
 
-        let last_fee = trade_fees.last().unwrap().clone();
-        trade_fees.extend(vec![last_fee; missing_data_no]);
+        let last_open_fee = *open_fees.last().unwrap();
+        open_fees.extend(vec![last_open_fee; missing_data_no]);
+        let last_close_fee = *close_fees.last().unwrap();
+        close_fees.extend(vec![last_close_fee; missing_data_no]);
+        funding_fees.extend(vec![0.0; missing_data_no]);
         let last_units = units.last().unwrap().clone();
         units.extend(vec![last_units; missing_data_no]);
         let last_pnl = profit_and_loss.last().unwrap().clone();
@@ -382,8 +973,68 @@ pub fn compute_benchmark_positions(
         actions.extend(vec![last_action; missing_data_no]);
     }
 
-    // if last position was taken
-    if positions.last().unwrap() != &0 {
+    *checkpoint = BenchmarkCheckpoint {
+        open_fees: open_fees.clone(),
+        close_fees: close_fees.clone(),
+        units: units.clone(),
+        profit_and_loss: profit_and_loss.clone(),
+        returns: returns.clone(),
+        balances: balances.clone(),
+        fundings: fundings.clone(),
+        funding_fees: funding_fees.clone(),
+        positions: positions.clone(),
+        actions: actions.clone(),
+        current_trade,
+        current_min_price_threshold,
+        current_max_price_threshold,
+        current_peak_returns,
+        halted,
+        last_exit,
+        current_open_timestamp,
+        current_pyramid_adds,
+        current_take_profit_level,
+        processed_start_times: timestamps.first().copied().zip(timestamps.last().copied()),
+    };
+
+    if skipped_open_signals > 0 {
+        println!(
+            "compute_benchmark_positions => ⏳ {} open signals skipped due to trade cooldown",
+            skipped_open_signals
+        );
+    }
+    if skipped_sub_notional_signals > 0 {
+        println!(
+            "compute_benchmark_positions => 🪙 {} open signals skipped due to orders below minimum notional value",
+            skipped_sub_notional_signals
+        );
+    }
+
+    // trade still open at last bar is either marked to market at its close price or discarded
+    let trailing_trade = current_trade.filter(|_| positions.last().unwrap() != &0);
+    if let Some(trade) = trailing_trade.filter(|_| trading_settings.close_open_at_end) {
+        let last_index = bars - 1;
+        let close_side = trade.side.get_opposite_side().unwrap_or_default();
+        let close_price = close_side.apply_slippage(closes[last_index] as f64, slippage_bps) as f32;
+        let (close_pnl, close_roi, close_fee) =
+            trade.get_pnl_returns_and_fees(close_price, close_order_fee_rate);
+        close_fees[last_index] += close_fee;
+        units[last_index] = 0.0;
+        profit_and_loss[last_index] = close_pnl;
+        returns[last_index] = close_roi;
+        balances[last_index] = f32::max(
+            0.0,
+            round_nth_decimal(
+                balances[last_index] + trade.initial_margin + close_pnl,
+                margin_decimals,
+            ),
+        );
+        positions[last_index] = 0;
+        actions[last_index] = if trade.side == Side::Sell {
+            SignalCategory::CloseShort.get_column().to_owned()
+        } else {
+            SignalCategory::CloseLong.get_column().to_owned()
+        };
+    } else if positions.last().unwrap() != &0 {
         if let Some((before_last_order_index, _)) = positions // over positions vector
             .iter() // iterate over
             .enumerate() // an enumeration
@@ -393,7 +1044,7 @@ pub fn compute_benchmark_positions(
         {
             // splices results vectors to values before opening the order
             // note that even though the vector was reversed, before_last_order_index keeps being the original vector index. Thanks, Rust <3
-            let range = before_last_order_index..df_height;
+            let range = before_last_order_index..bars;
             let zeroed_float_patch: Vec<f32> = range.clone().map(|_| 0.0 as f32).collect();
             let zeroed_integer_patch: Vec<i32> = range.clone().map(|_| 0 as i32).collect();
             let keep_position_action_patch: Vec<String> = range
@@ -401,7 +1052,9 @@ pub fn compute_benchmark_positions(
                 .map(|_| SignalCategory::KeepPosition.get_column().to_owned())
                 .collect();
 
-            trade_fees.splice(range.clone(), zeroed_float_patch.clone());
+            open_fees.splice(range.clone(), zeroed_float_patch.clone());
+            close_fees.splice(range.clone(), zeroed_float_patch.clone());
+            funding_fees.splice(range.clone(), zeroed_float_patch.clone());
             units.splice(range.clone(), zeroed_float_patch.clone());
             profit_and_loss.splice(range.clone(), zeroed_float_patch.clone());
 
@@ -418,52 +1071,36 @@ pub fn compute_benchmark_positions(
         }
     }
 
-    let elapsed_time = perf_start.elapsed();
-    let elapsed_millis = elapsed_time.as_nanos();
-    println!(
-        "compute_benchmark_positions => Elapsed time in nanos: {}",
-        elapsed_millis
-    );
+    let balances = balances
+        .iter()
+        .zip(fundings.iter())
+        .map(|(&balance, &funding)| {
+            if quantize_balances {
+                quantize_balance(balance + funding, margin_decimals)
+            } else {
+                balance + funding
+            }
+        })
+        .collect();
 
-    let trade_fee_series = Series::new(
-        "trade_fees",
-        trade_fees.iter().map(|&f| f as f64).collect::<Vec<f64>>(),
-    );
-    let units_series = Series::new(
-        "units",
-        units.iter().map(|&u| u as f64).collect::<Vec<f64>>(),
-    );
-    let profit_and_loss_series = Series::new(
-        "profit_and_loss",
-        profit_and_loss
-            .iter()
-            .map(|&p| p as f64)
-            .collect::<Vec<f64>>(),
-    );
-    let returns_series = Series::new(
-        "returns",
-        returns.iter().map(|&r| r as f64).collect::<Vec<f64>>(),
-    );
-    let balance_series = Series::new(
-        "balance",
-        balances
-            .iter()
-            .zip(fundings.iter())
-            .map(|(&a, &b)| (a + b) as f64)
-            .collect::<Vec<f64>>(),
-    );
-    let position_series = Series::new("position", positions);
-    let action_series = Series::new("action", actions);
-
-    let df = df.with_column(trade_fee_series)?;
-    let df = df.with_column(units_series)?;
-    let df = df.with_column(profit_and_loss_series)?;
-    let df = df.with_column(returns_series)?;
-    let df = df.with_column(balance_series)?;
-    let df = df.with_column(position_series)?;
-    let df = df.with_column(action_series)?;
-
-    Ok(df.clone())
+    let trade_fees = open_fees
+        .iter()
+        .zip(close_fees.iter())
+        .map(|(&open_fee, &close_fee)| open_fee + close_fee)
+        .collect();
+
+    BenchmarkColumns {
+        trade_fees,
+        open_fees,
+        close_fees,
+        funding_fees,
+        units,
+        profit_and_loss,
+        returns,
+        balances,
+        positions,
+        actions,
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -500,7 +1137,7 @@ struct OnOpenTradeParams {
     pub current_funding: f32,
     pub should_short: bool,
     pub trade: BenchmarkTrade,
-    pub tick_decimals: i32,
+    pub margin_decimals: i32,
 }
 
 impl OnOpenTradeParams {
@@ -520,11 +1157,32 @@ impl OnOpenTradeParams {
             // remainder: success_params.1,
             should_short,
             trade,
-            tick_decimals: trade.tick_decimals,
+            margin_decimals: get_margin_decimals(trade.contract_kind, trade.tick_decimals),
         }
     }
 }
 
+/// Tightens trade's `threshold_prices` (min, max) with `trailing_price`,
+/// which is set on the adverse side of trade, above entry price for longs.
+fn get_trailing_threshold_prices(
+    side: Side,
+    threshold_prices: (Option<f32>, Option<f32>),
+    trailing_price: Option<f32>,
+) -> (Option<f32>, Option<f32>) {
+    let (min_price_threshold, max_price_threshold) = threshold_prices;
+    match (side, trailing_price) {
+        (Side::Buy, Some(price)) => (
+            Some(min_price_threshold.map_or(price, |threshold| threshold.max(price))),
+            max_price_threshold,
+        ),
+        (Side::Sell, Some(price)) => (
+            min_price_threshold,
+            Some(max_price_threshold.map_or(price, |threshold| threshold.min(price))),
+        ),
+        _ => threshold_prices,
+    }
+}
+
 fn on_open_trade(
     params: OnOpenTradeParams,
     current_trade: &mut Option<BenchmarkTrade>,
@@ -539,7 +1197,7 @@ fn on_open_trade(
         // remainder,
         should_short,
         trade,
-        tick_decimals,
+        margin_decimals,
     } = params;
     (*current_min_price_threshold, *current_max_price_threshold) = trade.get_threshold_prices();
     let side = trade.side.into();
@@ -548,7 +1206,7 @@ fn on_open_trade(
     let (pnl, roi, _) = trade.get_pnl_returns_and_fees(close_price, close_order_fee_rate);
     *current_trade = Some(trade);
     IterationData::new(
-        open_fee,
+        (open_fee, 0.0),
         units,
         pnl,
         roi,
@@ -556,7 +1214,7 @@ fn on_open_trade(
             0.0,
             round_nth_decimal(
                 current_balance - trade.initial_margin - open_fee,
-                tick_decimals,
+                margin_decimals,
             ),
         ),
         current_funding,
@@ -581,6 +1239,10 @@ fn on_open_trade_error(
 ) -> Result<IterationData, IterationsError> {
     let mut current_balance = trade_result_params.current_balance;
     let mut current_funding = trade_result_params.current_funding;
+    let margin_decimals = get_margin_decimals(
+        new_trade_params.contract_kind,
+        new_trade_params.tick_decimals,
+    );
     (current_balance, current_funding) = match error {
         BenchmarkTradeError::ZeroUnits => {
             if current_funding == 0.0 {
@@ -592,26 +1254,18 @@ fn on_open_trade_error(
             max_expenditure,
             expenditure,
         } => {
-            let suspend_amount = round_down_nth_decimal(
-                expenditure - max_expenditure,
-                new_trade_params.tick_decimals,
-            );
-            let updated_balance = round_down_nth_decimal(
-                current_balance - suspend_amount,
-                new_trade_params.tick_decimals,
-            );
-            let updated_funding = round_down_nth_decimal(
-                current_funding + suspend_amount,
-                new_trade_params.tick_decimals,
-            );
+            let suspend_amount =
+                round_down_nth_decimal(expenditure - max_expenditure, margin_decimals);
+            let updated_balance =
+                round_down_nth_decimal(current_balance - suspend_amount, margin_decimals);
+            let updated_funding =
+                round_down_nth_decimal(current_funding + suspend_amount, margin_decimals);
             (updated_balance, updated_funding)
         }
         BenchmarkTradeError::UnitsLessThanMinSize { min_expenditure }
         | BenchmarkTradeError::ValueLessThanNotionalMin { min_expenditure } => {
-            let total_funds = round_down_nth_decimal(
-                current_funding + current_balance,
-                new_trade_params.tick_decimals,
-            );
+            let total_funds =
+                round_down_nth_decimal(current_funding + current_balance, margin_decimals);
             if current_funding == 0.0 || total_funds < min_expenditure {
                 return Err(IterationsError::InsufficientFunds);
             }
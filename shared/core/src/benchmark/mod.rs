@@ -1,8 +1,49 @@
-use common::enums::{modifiers::price_level::PriceLevel, side::Side};
+use common::enums::{contract_kind::ContractKind, modifiers::price_level::PriceLevel, side::Side};
+mod diff;
+pub use diff::*;
 pub mod functions;
+pub mod portfolio;
+pub mod sweep;
+#[cfg(test)]
+mod tests;
+
+/// Base coin decimals margin amounts of inverse contracts are rounded to, i.e. satoshis.
+const INVERSE_MARGIN_DECIMALS: i32 = 8;
+
+/// Gets decimals margin amounts of `contract_kind` are rounded to, `linear_decimals` being the
+/// ones of linear contracts, whose margin is quoted, while inverse ones are margined in base coin.
+pub fn get_margin_decimals(contract_kind: ContractKind, linear_decimals: i32) -> i32 {
+    match contract_kind {
+        ContractKind::Linear => linear_decimals,
+        ContractKind::Inverse => INVERSE_MARGIN_DECIMALS,
+    }
+}
+
+/// Gets position value of `units` at `price`, in margin currency of `contract_kind`.
+fn get_notional(contract_kind: ContractKind, units: f32, price: f32) -> f32 {
+    match contract_kind {
+        ContractKind::Linear => units * price,
+        ContractKind::Inverse => units / price,
+    }
+}
+
+/// Gets price at which position opened at `price` moved by `price_mod`, i.e. at which its returns,
+/// relative to its initial margin, are `price_mod` signed by position side.
+fn get_price_at_mod(
+    contract_kind: ContractKind,
+    price: f32,
+    leverage_factor: f32,
+    price_mod: f32,
+) -> f32 {
+    match contract_kind {
+        ContractKind::Linear => price * (leverage_factor + price_mod) / leverage_factor,
+        ContractKind::Inverse => price * leverage_factor / (leverage_factor - price_mod),
+    }
+}
 
 #[derive(Clone, Copy, Debug)]
 pub struct BenchmarkTrade {
+    pub contract_kind: ContractKind,
     pub initial_margin: f32,
     pub leverage_factor: f32,
     pub open_fee: f32,
@@ -22,6 +63,13 @@ impl From<PriceLevel> for PriceLock {
             PriceLevel::StopLoss(factor) | PriceLevel::TakeProfit(factor) => {
                 PriceLock(factor as f32)
             }
+            // ladder locks its first level, next ones being set as position scales out
+            PriceLevel::TakeProfitLadder(ladder) => {
+                PriceLock(ladder.get_percentage(0).unwrap_or_default() as f32)
+            }
+            PriceLevel::TrailingTakeProfit(_) => {
+                unreachable!("trailing take profit doesn't lock a fixed price")
+            }
         }
     }
 }
@@ -42,6 +90,7 @@ impl LockType {
 
 impl BenchmarkTrade {
     pub fn new(
+        contract_kind: ContractKind,
         initial_margin: f32,
         leverage_factor: f32,
         open_order_fee_rate: f32,
@@ -54,28 +103,44 @@ impl BenchmarkTrade {
     ) -> Self {
         let mut bankruptcy_price = None;
         if leverage_factor != 1.0 {
+            // whole margin is lost once returns reach -100%
+            let price_mod = if side == Side::Sell { 1.0 } else { -1.0 };
             bankruptcy_price = Some(round_down_nth_decimal(
-                price * (leverage_factor + if side == Side::Sell { 1.0 } else { -1.0 })
-                    / leverage_factor,
+                get_price_at_mod(contract_kind, price, leverage_factor, price_mod),
                 tick_decimals,
             ))
         }
         let mut stop_loss_price = None;
         if let Some(lock) = price_locks.0 {
             let pct = lock.0;
-            let position_mod = leverage_factor + LockType::StopLoss.get_price_mod(side, pct);
-            let sl_price = round_nth_decimal(price * position_mod / leverage_factor, tick_decimals);
+            let price_mod = LockType::StopLoss.get_price_mod(side, pct);
+            let sl_price = round_nth_decimal(
+                get_price_at_mod(contract_kind, price, leverage_factor, price_mod),
+                tick_decimals,
+            );
             stop_loss_price = Some(sl_price);
         }
         let mut take_profit_price = None;
         if let Some(lock) = price_locks.1 {
             let pct = lock.0;
-            let position_mod = leverage_factor + LockType::TakeProfit.get_price_mod(side, pct);
-            let tp_price = round_nth_decimal(price * position_mod / leverage_factor, tick_decimals);
+            let price_mod = LockType::TakeProfit.get_price_mod(side, pct);
+            let tp_price = round_nth_decimal(
+                get_price_at_mod(contract_kind, price, leverage_factor, price_mod),
+                tick_decimals,
+            );
             take_profit_price = Some(tp_price);
         }
-        let open_fee = round_nth_decimal(units * open_order_fee_rate * price, tick_decimals);
+        let open_fee = match contract_kind {
+            ContractKind::Linear => {
+                round_nth_decimal(units * open_order_fee_rate * price, tick_decimals)
+            }
+            ContractKind::Inverse => round_nth_decimal(
+                get_notional(contract_kind, units, price) * open_order_fee_rate,
+                INVERSE_MARGIN_DECIMALS,
+            ),
+        };
         Self {
+            contract_kind,
             initial_margin,
             leverage_factor,
             open_fee,
@@ -95,20 +160,31 @@ impl BenchmarkTrade {
         // short: Unrealized P&L = (Average Entry Price - Current Mark Price) × Position Size, ROI = [(Entry Price − Mark Price) × Position Size/ Initial Margin] × 100%
         // long: Unrealized P&L = (Current Mark Price - Average Entry Price) × Position Size, ROI = [(Mark Price − Entry Price) × Position Size/ Initial Margin] × 100%
         // TODO: CHECK THIS =>  here, we don't subtract fees since their effects result in having less units
+        let margin_decimals = get_margin_decimals(self.contract_kind, self.symbol_decimals);
         let close_fee = round_nth_decimal(
-            self.units * price * close_order_fee_rate,
-            self.symbol_decimals,
+            get_notional(self.contract_kind, self.units, price) * close_order_fee_rate,
+            margin_decimals,
         );
-        let price = if self.side == Side::Sell {
-            self.prices.0 - price
-        } else {
-            price - self.prices.0
+        let gross_pnl = match self.contract_kind {
+            ContractKind::Linear => {
+                let price_change = if self.side == Side::Sell {
+                    self.prices.0 - price
+                } else {
+                    price - self.prices.0
+                };
+                price_change * self.units
+            }
+            ContractKind::Inverse => {
+                let long_pnl = self.units * (1.0 / self.prices.0 - 1.0 / price);
+                if self.side == Side::Sell {
+                    -long_pnl
+                } else {
+                    long_pnl
+                }
+            }
         };
         // TODO: (self.prices.0 - price) * self.units - (close_fee + self.open_fee) make sure close_fee is not double-counted
-        let pnl = round_nth_decimal(
-            price * self.units - (self.open_fee + close_fee),
-            self.symbol_decimals,
-        );
+        let pnl = round_nth_decimal(gross_pnl - (self.open_fee + close_fee), margin_decimals);
         let roi = if self.initial_margin != 0.0 {
             pnl / self.initial_margin
         } else {
@@ -117,6 +193,120 @@ impl BenchmarkTrade {
         (pnl, roi, close_fee)
     }
 
+    /// Splits trade into `fraction` of its units, to be closed, and the remaining trade, which
+    /// keeps stop loss but drops take profit price. Margin and open fee are split proportionally.
+    /// Returns None if either part would end up without units.
+    pub fn scale_out(&self, fraction: f32) -> Option<(BenchmarkTrade, BenchmarkTrade)> {
+        let closed_units = round_down_nth_decimal(self.units * fraction, self.symbol_decimals);
+        let remaining_units = round_nth_decimal(self.units - closed_units, self.symbol_decimals);
+        if closed_units <= 0.0 || remaining_units <= 0.0 {
+            return None;
+        }
+        let remaining_ratio = remaining_units / self.units;
+        let margin_decimals = get_margin_decimals(self.contract_kind, self.tick_decimals);
+        let mut remaining_trade = *self;
+        remaining_trade.units = remaining_units;
+        remaining_trade.initial_margin =
+            round_nth_decimal(self.initial_margin * remaining_ratio, margin_decimals);
+        remaining_trade.open_fee =
+            round_nth_decimal(self.open_fee * remaining_ratio, margin_decimals);
+        remaining_trade.prices.3 = None;
+
+        let mut closed_trade = *self;
+        closed_trade.units = closed_units;
+        closed_trade.initial_margin = self.initial_margin - remaining_trade.initial_margin;
+        closed_trade.open_fee = self.open_fee - remaining_trade.open_fee;
+
+        Some((closed_trade, remaining_trade))
+    }
+
+    /// Merges `added_trade`, of same side, into the trade, at their units weighted average entry
+    /// price, from which price locks are set again. Margin and open fee are summed, and take
+    /// profit already scaled out of position isn't set again.
+    pub fn add_to_position(
+        &self,
+        added_trade: &BenchmarkTrade,
+        price_locks: (Option<PriceLock>, Option<PriceLock>), // (stop_loss, take_profit)
+    ) -> BenchmarkTrade {
+        let units = round_nth_decimal(self.units + added_trade.units, self.symbol_decimals);
+        // inverse entry price averages contracts' value, i.e. it's units weighted harmonic mean
+        let average_price = match self.contract_kind {
+            ContractKind::Linear => {
+                (self.prices.0 * self.units + added_trade.prices.0 * added_trade.units) / units
+            }
+            ContractKind::Inverse => {
+                units / (self.units / self.prices.0 + added_trade.units / added_trade.prices.0)
+            }
+        };
+        let price = round_nth_decimal(average_price, self.tick_decimals);
+        let margin_decimals = get_margin_decimals(self.contract_kind, self.tick_decimals);
+        let mut merged_trade = BenchmarkTrade::new(
+            self.contract_kind,
+            round_nth_decimal(
+                self.initial_margin + added_trade.initial_margin,
+                margin_decimals,
+            ),
+            self.leverage_factor,
+            0.0,
+            price,
+            price_locks,
+            self.side,
+            self.symbol_decimals,
+            units,
+            self.tick_decimals,
+        );
+        merged_trade.open_fee =
+            round_nth_decimal(self.open_fee + added_trade.open_fee, margin_decimals);
+        if self.prices.3.is_none() {
+            merged_trade.prices.3 = None;
+        }
+
+        merged_trade
+    }
+
+    /// Gets funding fee paid for holding the trade at `price`, when charged at `funding_rate`.
+    /// As longs pay shorts when funding rate is positive, it's negative when trade receives funding.
+    pub fn get_funding_fee(&self, price: f32, funding_rate: f32) -> f32 {
+        let funding_fee = get_notional(self.contract_kind, self.units, price) * funding_rate;
+        let funding_fee = if self.side == Side::Sell {
+            -funding_fee
+        } else {
+            funding_fee
+        };
+        round_nth_decimal(
+            funding_fee,
+            get_margin_decimals(self.contract_kind, self.tick_decimals),
+        )
+    }
+
+    /// Gets trade returns at `price`, not accounting for fees, as price locks are set.
+    pub fn get_price_returns(&self, price: f32) -> f32 {
+        let price_change = if self.side == Side::Sell {
+            self.prices.0 - price
+        } else {
+            price - self.prices.0
+        };
+        match self.contract_kind {
+            ContractKind::Linear => price_change * self.leverage_factor / self.prices.0,
+            // inverse contracts are worth 1 / price, so returns are relative to current price
+            ContractKind::Inverse => price_change * self.leverage_factor / price,
+        }
+    }
+
+    /// Gets price at which trade has `returns`, being the inverse of `get_price_returns`.
+    pub fn get_price_at_returns(&self, returns: f32) -> f32 {
+        let price_mod = LockType::TakeProfit.get_price_mod(self.side, returns);
+        round_nth_decimal(
+            get_price_at_mod(
+                self.contract_kind,
+                self.prices.0,
+                self.leverage_factor,
+                price_mod,
+            ),
+            self.tick_decimals,
+        )
+    }
+
     pub fn get_threshold_prices(&self) -> (Option<f32>, Option<f32>) {
         match self.side {
             Side::Sell => (self.prices.3, self.prices.2.or_else(|| self.prices.1)),
@@ -165,9 +355,17 @@ pub fn round_nth_decimal(n: f32, decimals: i32) -> f32 {
     (n * multiplier).round() / multiplier
 }
 
+/// Rounds `balance` to `decimals` at f64 precision, so that already quantized balances are
+/// kept exactly as they are, unlike `round_nth_decimal`, whose f32 scaling may shift them.
+pub fn quantize_balance(balance: f32, decimals: i32) -> f32 {
+    let multiplier = 10.0_f64.powi(decimals);
+    ((balance as f64 * multiplier).round() / multiplier) as f32
+}
+
 #[derive(Clone, Copy)]
 pub struct NewBenchmarkTradeParams {
     pub allocation_pct: f32,
+    pub contract_kind: ContractKind,
     pub current_balance: f32,
     pub leverage_factor: f32,
     pub minimum_notional_value: Option<f32>,
@@ -198,6 +396,7 @@ impl NewBenchmarkTradeParams {
     ) -> Self {
         Self {
             allocation_pct,
+            contract_kind: ContractKind::default(),
             current_balance,
             leverage_factor,
             minimum_notional_value,
@@ -211,6 +410,12 @@ impl NewBenchmarkTradeParams {
             tick_decimals,
         }
     }
+
+    /// Sets kind of traded contract, which is linear unless set otherwise.
+    pub fn with_contract_kind(mut self, contract_kind: ContractKind) -> Self {
+        self.contract_kind = contract_kind;
+        self
+    }
 }
 
 pub fn new_benchmark_trade(
@@ -218,6 +423,7 @@ pub fn new_benchmark_trade(
 ) -> Result<BenchmarkTrade, BenchmarkTradeError> {
     let NewBenchmarkTradeParams {
         allocation_pct,
+        contract_kind,
         current_balance,
         leverage_factor,
         minimum_notional_value,
@@ -237,11 +443,28 @@ pub fn new_benchmark_trade(
     } else {
         unreachable!();
     };
+    // order cost is initial margin, plus open fee, plus close fee at bankruptcy price, which,
+    // for inverse contracts, is priced at the reciprocal of entry price
+    let cost_factor = match contract_kind {
+        ContractKind::Linear => {
+            ((2.0 * taker_fee_rate) * leverage_factor) + (1.0 + price_lock_modifier)
+        }
+        ContractKind::Inverse => {
+            ((2.0 * taker_fee_rate) * leverage_factor) + (1.0 - price_lock_modifier)
+        }
+    };
+    let get_units_cost = |units: f32| match contract_kind {
+        ContractKind::Linear => units * (price * cost_factor) / leverage_factor,
+        ContractKind::Inverse => units * (cost_factor / price) / leverage_factor,
+    };
+    let margin_decimals = get_margin_decimals(contract_kind, tick_decimals);
     let expenditure =
-        round_down_nth_decimal(allocation_pct * current_balance / 100_f32, tick_decimals);
+        round_down_nth_decimal(allocation_pct * current_balance / 100_f32, margin_decimals);
     let units = round_down_nth_decimal(
-        expenditure * leverage_factor
-            / (price * (((2.0 * taker_fee_rate) * leverage_factor) + (1.0 + price_lock_modifier))),
+        match contract_kind {
+            ContractKind::Linear => expenditure * leverage_factor / (price * cost_factor),
+            ContractKind::Inverse => expenditure * leverage_factor * price / cost_factor,
+        },
         symbol_decimals,
     );
 
@@ -249,43 +472,42 @@ pub fn new_benchmark_trade(
         return Err(BenchmarkTradeError::ZeroUnits);
     }
     if units < order_sizes.0 {
-        let min_units = order_sizes.0;
-        let min_expenditure = round_down_nth_decimal(
-            min_units
-                * (price
-                    * (((2.0 * taker_fee_rate) * leverage_factor) + (1.0 + price_lock_modifier)))
-                / leverage_factor,
-            tick_decimals,
-        );
+        let min_expenditure =
+            round_down_nth_decimal(get_units_cost(order_sizes.0), margin_decimals);
         return Err(BenchmarkTradeError::UnitsLessThanMinSize { min_expenditure });
     }
     if units > order_sizes.1 {
-        let max_units = order_sizes.1;
-        let max_expenditure = round_down_nth_decimal(
-            max_units
-                * (price
-                    * (((2.0 * taker_fee_rate) * leverage_factor) + (1.0 + price_lock_modifier)))
-                / leverage_factor,
-            tick_decimals,
-        );
+        let max_expenditure =
+            round_down_nth_decimal(get_units_cost(order_sizes.1), margin_decimals);
         return Err(BenchmarkTradeError::UnitsMoreThanMaxSize {
             max_expenditure,
             expenditure,
         });
     }
 
-    let order_value = round_nth_decimal(units * price, tick_decimals);
+    let order_value = round_nth_decimal(get_notional(contract_kind, units, price), margin_decimals);
+    // minimum notional value is quoted, which inverse contracts units already are
+    let quote_value = match contract_kind {
+        ContractKind::Linear => order_value,
+        ContractKind::Inverse => units,
+    };
     if let Some(minimum_notional_value) = minimum_notional_value {
-        if order_value < minimum_notional_value {
-            return Err(BenchmarkTradeError::ValueLessThanNotionalMin {
-                min_expenditure: minimum_notional_value,
-            });
+        if quote_value < minimum_notional_value {
+            let min_expenditure = match contract_kind {
+                ContractKind::Linear => minimum_notional_value,
+                ContractKind::Inverse => round_nth_decimal(
+                    get_notional(contract_kind, minimum_notional_value, price),
+                    margin_decimals,
+                ),
+            };
+            return Err(BenchmarkTradeError::ValueLessThanNotionalMin { min_expenditure });
         }
     }
-    let initial_margin = round_nth_decimal(order_value / leverage_factor, tick_decimals);
+    let initial_margin = round_nth_decimal(order_value / leverage_factor, margin_decimals);
     // let balance_remainder = round_down_nth_decimal(expenditure - initial_margin, tick_decimals);
 
     let trade = BenchmarkTrade::new(
+        contract_kind,
         initial_margin,
         leverage_factor,
         open_order_fee_rate,
@@ -0,0 +1,178 @@
+use super::sweep::{get_max_drawdown, simulate_strategy, BacktestResult};
+use common::{structs::TradingSettings, traits::exchange::BenchmarkExchange};
+use glow_error::GlowError;
+use polars::prelude::*;
+use strategy::{Strategy, StrategyId};
+
+/// Strategy run by a `Portfolio`, with the fraction of the shared balance it's allocated.
+#[derive(Clone)]
+pub struct PortfolioStrategy {
+    pub strategy: Strategy,
+    pub allocation: f64,
+}
+
+impl PortfolioStrategy {
+    pub fn new(strategy: Strategy, allocation: f64) -> Self {
+        Self {
+            strategy,
+            allocation,
+        }
+    }
+}
+
+/// Runs multiple strategies over the same account, each of them trading its own slice
+/// of the shared balance. Balance not allocated to any strategy is kept idle.
+#[derive(Clone)]
+pub struct Portfolio {
+    strategies: Vec<PortfolioStrategy>,
+}
+
+impl Portfolio {
+    /// Fails if any allocation isn't within (0, 1], or if they add up to more than whole balance.
+    pub fn new(strategies: Vec<PortfolioStrategy>) -> Result<Self, GlowError> {
+        if let Some((index, invalid)) = strategies
+            .iter()
+            .enumerate()
+            .find(|(_, strategy)| !(strategy.allocation > 0.0 && strategy.allocation <= 1.0))
+        {
+            let error = format!(
+                "allocation {} of strategy #{} ({:?}) must be within (0, 1]",
+                invalid.allocation, index, invalid.strategy.id
+            );
+            return Err(GlowError::new(
+                String::from("Invalid Portfolio Allocation"),
+                error,
+            ));
+        }
+        let total_allocation: f64 = strategies.iter().map(|strategy| strategy.allocation).sum();
+        if total_allocation > 1.0 + f64::EPSILON {
+            let error = format!(
+                "strategies allocations add up to {}, exceeding whole balance",
+                total_allocation
+            );
+            return Err(GlowError::new(
+                String::from("Invalid Portfolio Allocation"),
+                error,
+            ));
+        }
+
+        Ok(Self { strategies })
+    }
+
+    pub fn get_strategies(&self) -> &Vec<PortfolioStrategy> {
+        &self.strategies
+    }
+
+    /// Balance not allocated to any strategy.
+    pub fn get_idle_balance(&self, balance: f64) -> f64 {
+        let total_allocation: f64 = self
+            .strategies
+            .iter()
+            .map(|strategy| strategy.allocation)
+            .sum();
+        (balance * (1.0 - total_allocation)).max(0.0)
+    }
+
+    /// Benchmarks every strategy over `tick_data`, starting off with its allocated slice of
+    /// `initial_balance`, and sums their balances marked at trades' closes, along with the idle
+    /// one, into a single equity curve.
+    pub fn benchmark<E: BenchmarkExchange>(
+        &self,
+        trading_settings: &TradingSettings,
+        exchange: &E,
+        tick_data: &DataFrame,
+        initial_balance: f32,
+    ) -> Result<PortfolioResult, GlowError> {
+        let idle_balance = self.get_idle_balance(initial_balance as f64);
+        let mut equity = vec![idle_balance; tick_data.height()];
+        let mut trades = 0;
+        let mut strategies_results = vec![];
+        for portfolio_strategy in self.strategies.iter() {
+            let allocated_balance = (initial_balance as f64 * portfolio_strategy.allocation) as f32;
+            let columns = simulate_strategy(
+                &portfolio_strategy.strategy,
+                trading_settings,
+                exchange,
+                tick_data,
+                allocated_balance,
+            )?;
+            let marked_balances = columns.get_marked_balances(allocated_balance);
+            for (equity, balance) in equity.iter_mut().zip(marked_balances) {
+                *equity += balance;
+            }
+            let result = BacktestResult::new(&columns, allocated_balance);
+            trades += result.trades;
+            strategies_results.push((portfolio_strategy, allocated_balance as f64, result));
+        }
+
+        let initial_balance = initial_balance as f64;
+        let final_balance = equity.last().copied().unwrap_or(initial_balance);
+        let total_returns = if initial_balance != 0.0 {
+            final_balance / initial_balance - 1.0
+        } else {
+            0.0
+        };
+        let max_drawdown = get_max_drawdown(initial_balance, equity.iter().copied());
+        let total_pnl = final_balance - initial_balance;
+        let attributions = strategies_results
+            .into_iter()
+            .map(|(portfolio_strategy, allocated_balance, result)| {
+                let pnl = result.final_balance - allocated_balance;
+                StrategyAttribution {
+                    id: portfolio_strategy.strategy.id,
+                    allocation: portfolio_strategy.allocation,
+                    initial_balance: allocated_balance,
+                    pnl,
+                    pnl_share: if total_pnl != 0.0 {
+                        pnl / total_pnl
+                    } else {
+                        0.0
+                    },
+                    result,
+                }
+            })
+            .collect();
+
+        Ok(PortfolioResult {
+            equity,
+            combined: BacktestResult {
+                final_balance,
+                total_returns,
+                max_drawdown,
+                trades,
+            },
+            attributions,
+        })
+    }
+}
+
+/// Contribution of a single strategy to `Portfolio` results, in the order strategies were set.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrategyAttribution {
+    pub id: StrategyId,
+    pub allocation: f64,
+    pub initial_balance: f64,
+    pub pnl: f64,
+    /// fraction of portfolio profit and loss due to this strategy.
+    pub pnl_share: f64,
+    pub result: BacktestResult,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PortfolioResult {
+    /// summed balance of all strategies, plus idle one, per bar.
+    pub equity: Vec<f64>,
+    pub combined: BacktestResult,
+    pub attributions: Vec<StrategyAttribution>,
+}
+
+impl PortfolioResult {
+    /// Equity curve as a `balance` column, keyed by `tick_data` start times.
+    pub fn get_equity_df(&self, tick_data: &DataFrame) -> Result<DataFrame, GlowError> {
+        let df = DataFrame::new(vec![
+            tick_data.column("start_time")?.clone(),
+            Series::new("balance", self.equity.clone()),
+        ])?;
+        Ok(df)
+    }
+}
@@ -0,0 +1,250 @@
+use super::functions::{
+    count_position_trades, resume_simulated_positions, BenchmarkCheckpoint, BenchmarkColumns,
+    BenchmarkSignals,
+};
+use common::{
+    enums::modifiers::price_level::PriceLevel, functions::get_price_columns_f32,
+    structs::TradingSettings, traits::exchange::BenchmarkExchange,
+};
+use glow_error::GlowError;
+use polars::prelude::*;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use strategy::{
+    params::{Param, ParamId},
+    Strategy,
+};
+
+/// Values to be swept for strategy params, as well as for price level modifiers,
+/// keyed by `PriceLevel::get_hash_key`. Every combination of them is benchmarked.
+#[derive(Clone, Debug, Default)]
+pub struct ParamGrid {
+    pub strategy_params: HashMap<ParamId, Vec<Param>>,
+    pub price_levels: HashMap<String, Vec<PriceLevel>>,
+}
+
+impl ParamGrid {
+    /// Cartesian product of grid values. If any param has no values, there's no combination.
+    ///
+    /// Combinations are ordered by sorted param ids and hash keys, rather than by maps' iteration
+    /// order, which varies across runs, so that equally ranked sweep results are kept in the same
+    /// order over identical grids.
+    pub fn get_param_sets(&self) -> Vec<ParamSet> {
+        let mut strategy_params: Vec<_> = self.strategy_params.iter().collect();
+        strategy_params.sort_by_key(|(param_id, _)| **param_id);
+        let mut price_levels: Vec<_> = self.price_levels.iter().collect();
+        price_levels.sort_by_key(|(hash_key, _)| *hash_key);
+
+        let mut param_sets = vec![ParamSet::default()];
+        for (param_id, values) in strategy_params {
+            param_sets = param_sets
+                .iter()
+                .flat_map(|param_set| {
+                    values.iter().map(move |value| {
+                        let mut param_set = param_set.clone();
+                        param_set.strategy_params.insert(*param_id, value.clone());
+                        param_set
+                    })
+                })
+                .collect();
+        }
+        for (hash_key, values) in price_levels {
+            param_sets = param_sets
+                .iter()
+                .flat_map(|param_set| {
+                    values.iter().map(move |value| {
+                        let mut param_set = param_set.clone();
+                        param_set
+                            .price_levels
+                            .insert(hash_key.clone(), value.clone());
+                        param_set
+                    })
+                })
+                .collect();
+        }
+        param_sets
+    }
+}
+
+/// One combination of `ParamGrid` values.
+#[derive(Clone, Debug, Default)]
+pub struct ParamSet {
+    pub strategy_params: HashMap<ParamId, Param>,
+    pub price_levels: HashMap<String, PriceLevel>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BacktestResult {
+    /// balance marked at last trade close, so that it includes trades' margins.
+    pub final_balance: f64,
+    pub total_returns: f64,
+    /// largest decline of balances marked at trades' closes from a previous peak, as a fraction
+    /// of it.
+    pub max_drawdown: f64,
+    pub trades: usize,
+}
+
+impl BacktestResult {
+    pub(super) fn new(columns: &BenchmarkColumns, initial_balance: f32) -> Self {
+        let marked_balances = columns.get_marked_balances(initial_balance);
+        let initial_balance = initial_balance as f64;
+        let final_balance = marked_balances.last().copied().unwrap_or(initial_balance);
+        let total_returns = if initial_balance != 0.0 {
+            final_balance / initial_balance - 1.0
+        } else {
+            0.0
+        };
+        let max_drawdown = get_max_drawdown(initial_balance, marked_balances.into_iter());
+        let trades = count_position_trades(&columns.positions);
+
+        Self {
+            final_balance,
+            total_returns,
+            max_drawdown,
+            trades,
+        }
+    }
+}
+
+/// Largest decline of `balances` from a previous peak, starting off at `initial_balance`,
+/// as a fraction of that peak.
+pub(super) fn get_max_drawdown(initial_balance: f64, balances: impl Iterator<Item = f64>) -> f64 {
+    let mut peak_balance = initial_balance;
+    let mut max_drawdown: f64 = 0.0;
+    for balance in balances {
+        peak_balance = peak_balance.max(balance);
+        if peak_balance > 0.0 {
+            max_drawdown = max_drawdown.max((peak_balance - balance) / peak_balance);
+        }
+    }
+    max_drawdown
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SweepMetric {
+    #[default]
+    TotalReturns,
+    MaxDrawdown,
+    ReturnsOverDrawdown,
+}
+
+impl SweepMetric {
+    /// Score by which results are ranked, the higher the better.
+    fn get_score(&self, result: &BacktestResult) -> f64 {
+        match self {
+            Self::TotalReturns => result.total_returns,
+            Self::MaxDrawdown => -result.max_drawdown,
+            Self::ReturnsOverDrawdown => {
+                if result.max_drawdown > 0.0 {
+                    result.total_returns / result.max_drawdown
+                } else {
+                    result.total_returns
+                }
+            }
+        }
+    }
+}
+
+/// Benchmarks `strategy` over `tick_data` with every `param_grid` combination, in parallel,
+/// returning results ranked by `metric`, best first. Ties keep `ParamGrid::get_param_sets` order.
+///
+/// Price levels of each combination are set over `trading_settings` ones, so that modifiers
+/// which aren't swept are kept. Fails if any combination can't be benchmarked, e.g. due to
+/// a param value out of its bounds.
+pub fn sweep<E: BenchmarkExchange + Sync>(
+    strategy: &Strategy,
+    trading_settings: &TradingSettings,
+    exchange: &E,
+    tick_data: &DataFrame,
+    param_grid: &ParamGrid,
+    metric: SweepMetric,
+    initial_balance: f32,
+) -> Result<Vec<(ParamSet, BacktestResult)>, GlowError> {
+    let mut results = param_grid
+        .get_param_sets()
+        .into_par_iter()
+        .map(|param_set| {
+            let result = backtest_param_set(
+                strategy,
+                trading_settings,
+                exchange,
+                tick_data,
+                &param_set,
+                initial_balance,
+            )?;
+            Ok((param_set, result))
+        })
+        .collect::<Result<Vec<_>, GlowError>>()?;
+    results.sort_by(|(_, result), (_, other_result)| {
+        metric
+            .get_score(other_result)
+            .total_cmp(&metric.get_score(result))
+    });
+
+    Ok(results)
+}
+
+fn backtest_param_set<E: BenchmarkExchange>(
+    strategy: &Strategy,
+    trading_settings: &TradingSettings,
+    exchange: &E,
+    tick_data: &DataFrame,
+    param_set: &ParamSet,
+    initial_balance: f32,
+) -> Result<BacktestResult, GlowError> {
+    let mut strategy = strategy.clone();
+    for (param_id, value) in param_set.strategy_params.iter() {
+        strategy = strategy.patch_param(*param_id, value.clone())?;
+    }
+    let mut trading_settings = trading_settings.clone();
+    trading_settings
+        .price_level_modifier_map
+        .extend(param_set.price_levels.clone());
+
+    let columns = simulate_strategy(
+        &strategy,
+        &trading_settings,
+        exchange,
+        tick_data,
+        initial_balance,
+    )?;
+
+    Ok(BacktestResult::new(&columns, initial_balance))
+}
+
+/// Appends `strategy` indicators and signals to `tick_data`, simulating positions over them
+/// once indicators are warmed up.
+pub(super) fn simulate_strategy<E: BenchmarkExchange>(
+    strategy: &Strategy,
+    trading_settings: &TradingSettings,
+    exchange: &E,
+    tick_data: &DataFrame,
+    initial_balance: f32,
+) -> Result<BenchmarkColumns, GlowError> {
+    let lf = strategy.append_indicators_to_lf(tick_data.clone().lazy())?;
+    let df = strategy.append_signals_to_lf(lf)?.collect()?;
+    let traded_symbol = trading_settings.get_traded_symbol();
+    let (opens, highs, lows, closes) = get_price_columns_f32(&df, traded_symbol)?;
+    let timestamps = df
+        .column("start_time")?
+        .timestamp(TimeUnit::Milliseconds)?
+        .into_no_null_iter()
+        .collect::<Vec<i64>>();
+    let signals = BenchmarkSignals::new(&df)?.with_priority(strategy.signal_priority.clone());
+    let warmup_bars = (strategy.get_indicator_warmup_bars() as usize).min(df.height());
+    let mut checkpoint = BenchmarkCheckpoint::new(initial_balance).skip_warmup(warmup_bars);
+
+    let columns = resume_simulated_positions(
+        &opens,
+        &highs,
+        &lows,
+        &closes,
+        &timestamps,
+        &signals,
+        trading_settings,
+        exchange,
+        &mut checkpoint,
+    );
+
+    Ok(columns)
+}
@@ -0,0 +1,1485 @@
+use super::{
+    diff,
+    functions::{
+        count_position_trades, resume_simulated_positions, simulate_positions, BenchmarkCheckpoint,
+        BenchmarkColumns, BenchmarkSignals,
+    },
+    new_benchmark_trade,
+    portfolio::{Portfolio, PortfolioStrategy},
+    sweep::{simulate_strategy, sweep, BacktestResult, ParamGrid, ParamSet, SweepMetric},
+    BenchmarkTrade, NewBenchmarkTradeParams,
+};
+use chrono::{Duration, NaiveDateTime};
+use common::{
+    enums::{
+        contract_kind::ContractKind,
+        granularity::Granularity,
+        modifiers::price_level::{PriceLevel, TakeProfitLadder, TrailingTakeProfit},
+        order_status::OrderStatus,
+        order_type::OrderType,
+        side::Side,
+        signal_category::SignalCategory,
+        symbol_id::SymbolId,
+    },
+    structs::{Contract, Order, SignalPriority, Trade, TradingSettings},
+    traits::exchange::{BenchmarkExchange, TraderHelper},
+};
+use glow_error::GlowError;
+use polars::prelude::*;
+use std::{collections::HashMap, time::Duration as StdDuration};
+use strategy::{
+    params::{NumberParamConfig, Param, ParamId},
+    Strategy,
+};
+
+/// Fee free exchange, so that benchmark outcomes only depend on prices, unless `fee_rate` is set
+struct TestExchange {
+    contracts: HashMap<SymbolId, Contract>,
+    trading_settings: TradingSettings,
+    fee_rate: f64,
+}
+
+impl TestExchange {
+    fn new(trading_settings: TradingSettings) -> Self {
+        let traded_symbol = trading_settings.get_traded_symbol();
+        let contract = Contract::new(
+            NaiveDateTime::default(),
+            Duration::hours(8),
+            0.0,
+            100.0,
+            (1_000.0, 1_000.0),
+            0.001,
+            None,
+            traded_symbol,
+            0.01,
+        );
+        let contracts = HashMap::from([(traded_symbol.id, contract)]);
+        Self {
+            contracts,
+            trading_settings,
+            fee_rate: 0.0,
+        }
+    }
+}
+
+/// Simulation only sizes trades off contract and fee rates, so no orders are ever built.
+fn get_unsupported_order_error(operation: &str) -> GlowError {
+    let error = format!("TestExchange doesn't support {}", operation);
+    GlowError::new(String::from("Unsupported Operation"), error)
+}
+
+impl TraderHelper for TestExchange {
+    fn calculate_order_fees(
+        &self,
+        order_type: OrderType,
+        _side: Side,
+        units: f64,
+        price: f64,
+    ) -> ((f64, f64), f64, bool) {
+        let (fee_rate, is_maker) = self.get_order_fee_rate(order_type);
+        let fee = units * price * fee_rate;
+        ((fee, fee), fee_rate, is_maker)
+    }
+
+    fn calculate_order_stop_loss_price(&self, _side: Side, _price: f64) -> Option<f64> {
+        None
+    }
+
+    fn calculate_order_take_profit_price(&self, _side: Side, _price: f64) -> Option<f64> {
+        None
+    }
+
+    fn calculate_open_order_units_and_balance_remainder(
+        &self,
+        _side: Side,
+        order_cost: f64,
+        price: f64,
+    ) -> Result<(f64, f64), GlowError> {
+        Ok((order_cost / price, 0.0))
+    }
+
+    fn get_contracts(&self) -> &HashMap<SymbolId, Contract> {
+        &self.contracts
+    }
+
+    fn get_trading_settings(&self) -> &TradingSettings {
+        &self.trading_settings
+    }
+
+    fn get_taker_fee(&self) -> f64 {
+        self.fee_rate
+    }
+
+    fn get_maker_fee(&self) -> f64 {
+        self.fee_rate
+    }
+
+    fn get_order_fee_rate(&self, order_type: OrderType) -> (f64, bool) {
+        (self.fee_rate, order_type == OrderType::Limit)
+    }
+}
+
+impl BenchmarkExchange for TestExchange {
+    fn new_benchmark_open_order(
+        &self,
+        _timestamp: i64,
+        _side: Side,
+        _order_cost: f64,
+        _price: f64,
+    ) -> Result<Order, GlowError> {
+        Err(get_unsupported_order_error("new_benchmark_open_order"))
+    }
+
+    fn new_benchmark_close_order(
+        &self,
+        _timestamp: i64,
+        _trade_id: &String,
+        _close_price: f64,
+        _open_order: Order,
+        _final_status: OrderStatus,
+    ) -> Result<Order, GlowError> {
+        Err(get_unsupported_order_error("new_benchmark_close_order"))
+    }
+
+    fn close_benchmark_trade_on_binding_price(
+        &self,
+        _trade: &Trade,
+        _current_timestamp: i64,
+        _binding_price: f64,
+    ) -> Result<Trade, GlowError> {
+        Err(get_unsupported_order_error(
+            "close_benchmark_trade_on_binding_price",
+        ))
+    }
+
+    fn get_minimum_notional_value(&self) -> Option<f64> {
+        None
+    }
+}
+
+/// Runs `simulate_positions` over one minute bars whose open, high, low and close prices are all `prices`
+fn simulate_flat_bars(prices: &[f64], signals: &BenchmarkSignals) -> BenchmarkColumns {
+    simulate_flat_bars_with_settings(prices, signals, TradingSettings::default())
+}
+
+fn simulate_flat_bars_with_settings(
+    prices: &[f64],
+    signals: &BenchmarkSignals,
+    trading_settings: TradingSettings,
+) -> BenchmarkColumns {
+    simulate_flat_bars_every(prices, signals, trading_settings, 60_000)
+}
+
+fn simulate_flat_bars_every(
+    prices: &[f64],
+    signals: &BenchmarkSignals,
+    trading_settings: TradingSettings,
+    bar_duration_ms: i64,
+) -> BenchmarkColumns {
+    let prices: Vec<f32> = prices.iter().map(|&price| price as f32).collect();
+    let exchange = TestExchange::new(trading_settings.clone());
+    let timestamps: Vec<i64> = (0..prices.len() as i64)
+        .map(|index| index * bar_duration_ms)
+        .collect();
+    simulate_positions(
+        &prices,
+        &prices,
+        &prices,
+        &prices,
+        &timestamps,
+        signals,
+        &trading_settings,
+        &exchange,
+        100.0,
+    )
+}
+
+fn get_actions(signal_categories: &[SignalCategory]) -> Vec<String> {
+    signal_categories
+        .iter()
+        .map(|category| category.get_column().to_owned())
+        .collect()
+}
+
+fn assert_balances(balances: &[f32], expected: &[f32]) {
+    assert_eq!(balances.len(), expected.len());
+    for (index, (balance, expected)) in balances.iter().zip(expected.iter()).enumerate() {
+        assert!(
+            (balance - expected).abs() < 1e-3,
+            "balance differs at index {}: {} != {}",
+            index,
+            balance,
+            expected
+        );
+    }
+}
+
+/// Simulates a trade of `side` opened at 1,000 and closed at `close_price`, on flat bars,
+/// with fills slipped by `slippage_bps`.
+fn simulate_slipped_round_trip(
+    side: Side,
+    close_price: f64,
+    slippage_bps: f64,
+) -> BenchmarkColumns {
+    let (open_signals, close_signals) = (vec![1, 0, 0, 0], vec![0, 1, 0, 0]);
+    let signals = if side == Side::Sell {
+        BenchmarkSignals {
+            shorts: open_signals,
+            longs: vec![0; 4],
+            close_shorts: close_signals,
+            close_longs: vec![0; 4],
+            ..Default::default()
+        }
+    } else {
+        BenchmarkSignals {
+            shorts: vec![0; 4],
+            longs: open_signals,
+            close_shorts: vec![0; 4],
+            close_longs: close_signals,
+            ..Default::default()
+        }
+    };
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.benchmark_slippage_bps = slippage_bps;
+    simulate_flat_bars_with_settings(
+        &[1_000.0, 1_000.0, close_price, close_price],
+        &signals,
+        trading_settings,
+    )
+}
+
+#[test]
+fn test_slippage_lowers_benchmark_returns() {
+    // (side, close price, slipped open price, slipped close price)
+    let cases = [
+        (Side::Buy, 1_050.0, 1_001.0, 1_048.95),
+        (Side::Sell, 950.0, 999.0, 950.95),
+    ];
+    for (side, close_price, slipped_open_price, slipped_close_price) in cases {
+        let columns = simulate_slipped_round_trip(side, close_price, 0.0);
+        let slipped_columns = simulate_slipped_round_trip(side, close_price, 10.0);
+
+        assert_eq!(columns.positions, slipped_columns.positions);
+        assert!(
+            slipped_columns.returns[2] < columns.returns[2],
+            "{:?}: {} should be lower than {}",
+            side,
+            slipped_columns.returns[2],
+            columns.returns[2]
+        );
+        // units are sized off slipped open price, and closed at slipped close price
+        let slipped_units = (100.0_f64 / slipped_open_price * 1_000.0).floor() / 1_000.0;
+        assert!((f64::from(slipped_columns.units[1]) - slipped_units).abs() < 1e-6);
+        let price_delta = if side == Side::Sell {
+            slipped_open_price - slipped_close_price
+        } else {
+            slipped_close_price - slipped_open_price
+        };
+        let expected_pnl = price_delta * slipped_units;
+        assert!(
+            (f64::from(slipped_columns.profit_and_loss[2]) - expected_pnl).abs() < 1e-3,
+            "{:?}: {} != {}",
+            side,
+            slipped_columns.profit_and_loss[2],
+            expected_pnl
+        );
+        let unslipped_pnl = (close_price - 1_000.0).abs() * f64::from(columns.units[1]);
+        assert!((f64::from(columns.profit_and_loss[2]) - unslipped_pnl).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_slippage_is_adverse_to_order_side() {
+    assert_eq!(Side::Buy.apply_slippage(1_000.0, 10.0), 1_001.0);
+    assert_eq!(Side::Sell.apply_slippage(1_000.0, 10.0), 999.0);
+    assert_eq!(Side::Buy.apply_slippage(1_000.0, 0.0), 1_000.0);
+}
+
+#[test]
+fn test_simulate_positions_opens_and_closes_long_at_next_bar_open() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 5],
+        longs: vec![1, 0, 0, 0, 0],
+        close_shorts: vec![0; 5],
+        close_longs: vec![0, 0, 1, 0, 0],
+        ..Default::default()
+    };
+    let columns = simulate_flat_bars(&[100.0, 100.0, 105.0, 110.0, 110.0], &signals);
+
+    assert_eq!(columns.positions, vec![0, 1, 1, 0, 0]);
+    assert_eq!(
+        columns.actions,
+        get_actions(&[
+            SignalCategory::KeepPosition,
+            SignalCategory::GoLong,
+            SignalCategory::KeepPosition,
+            SignalCategory::CloseLong,
+            SignalCategory::KeepPosition,
+        ])
+    );
+    assert_eq!(columns.units, vec![0.0, 1.0, 1.0, 0.0, 0.0]);
+    // whole balance is allocated as margin while the trade is open
+    assert_balances(&columns.balances, &[100.0, 0.0, 0.0, 110.0, 110.0]);
+    assert!((columns.profit_and_loss[3] - 10.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_backtest_result_of_winning_trade_has_no_drawdown() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 5],
+        longs: vec![1, 0, 0, 0, 0],
+        close_shorts: vec![0; 5],
+        close_longs: vec![0, 0, 1, 0, 0],
+        ..Default::default()
+    };
+    let columns = simulate_flat_bars(&[100.0, 100.0, 105.0, 110.0, 110.0], &signals);
+
+    let result = BacktestResult::new(&columns, 100.0);
+
+    assert!(result.max_drawdown.abs() < 1e-6);
+    assert!((result.final_balance - 110.0).abs() < 1e-3);
+    assert!((result.total_returns - 0.1).abs() < 1e-5);
+    assert_eq!(result.trades, 1);
+}
+
+#[test]
+fn test_quantized_balance_stays_constant_over_long_flat_sequence() {
+    let bars = 50_000;
+    let signals = BenchmarkSignals {
+        shorts: vec![0; bars],
+        longs: vec![0; bars],
+        close_shorts: vec![0; bars],
+        close_longs: vec![0; bars],
+        ..Default::default()
+    };
+    let prices = vec![100.0; bars];
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.quantize_benchmark_balances = true;
+    let exchange = TestExchange::new(trading_settings.clone());
+    let timestamps: Vec<i64> = (0..bars as i64).map(|index| index * 60_000).collect();
+    let columns = simulate_positions(
+        &prices,
+        &prices,
+        &prices,
+        &prices,
+        &timestamps,
+        &signals,
+        &trading_settings,
+        &exchange,
+        1_234.57,
+    );
+
+    assert_eq!(columns.balances.len(), bars);
+    assert_eq!(columns.balances[0], 1_234.57);
+    assert!(columns
+        .balances
+        .iter()
+        .all(|&balance| balance == columns.balances[0]));
+}
+
+#[test]
+fn test_simulate_positions_splits_trade_fees_into_open_and_close_fees() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 5],
+        longs: vec![1, 0, 0, 0, 0],
+        close_shorts: vec![0; 5],
+        close_longs: vec![0, 0, 1, 0, 0],
+        ..Default::default()
+    };
+    let prices = [100.0, 100.0, 105.0, 110.0, 110.0];
+    let trading_settings = TradingSettings::default();
+    let mut exchange = TestExchange::new(trading_settings.clone());
+    exchange.fee_rate = 0.001;
+    let timestamps: Vec<i64> = (0..prices.len() as i64)
+        .map(|index| index * 60_000)
+        .collect();
+    let columns = simulate_positions(
+        &prices,
+        &prices,
+        &prices,
+        &prices,
+        &timestamps,
+        &signals,
+        &trading_settings,
+        &exchange,
+        100.0,
+    );
+
+    assert_eq!(columns.positions, vec![0, 1, 1, 0, 0]);
+    assert!(columns.open_fees[1] > 0.0);
+    assert!(columns.close_fees[3] > 0.0);
+    for index in [0, 2, 3, 4] {
+        assert_eq!(columns.open_fees[index], 0.0);
+    }
+    for index in [0, 1, 2, 4] {
+        assert_eq!(columns.close_fees[index], 0.0);
+    }
+    for index in 0..prices.len() {
+        assert_eq!(
+            columns.trade_fees[index],
+            columns.open_fees[index] + columns.close_fees[index]
+        );
+    }
+}
+
+#[test]
+fn test_simulate_positions_profits_from_short_on_falling_prices() {
+    let signals = BenchmarkSignals {
+        shorts: vec![1, 0, 0, 0],
+        longs: vec![0; 4],
+        close_shorts: vec![0, 1, 0, 0],
+        close_longs: vec![0; 4],
+        ..Default::default()
+    };
+    let columns = simulate_flat_bars(&[100.0, 100.0, 80.0, 80.0], &signals);
+
+    assert_eq!(columns.positions, vec![0, -1, 0, 0]);
+    assert_eq!(
+        columns.actions,
+        get_actions(&[
+            SignalCategory::KeepPosition,
+            SignalCategory::GoShort,
+            SignalCategory::CloseShort,
+            SignalCategory::KeepPosition,
+        ])
+    );
+    assert_balances(&columns.balances, &[100.0, 0.0, 120.0, 120.0]);
+}
+
+#[test]
+fn test_simulate_positions_ignores_close_signals_of_opposite_side() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 4],
+        longs: vec![1, 0, 0, 0],
+        close_shorts: vec![0, 1, 0, 0],
+        close_longs: vec![0, 0, 1, 0],
+        ..Default::default()
+    };
+    let columns = simulate_flat_bars(&[100.0, 100.0, 100.0, 90.0], &signals);
+
+    assert_eq!(columns.positions, vec![0, 1, 1, 0]);
+    assert_balances(&columns.balances, &[100.0, 0.0, 0.0, 90.0]);
+}
+
+#[test]
+fn test_simulate_positions_without_signals_keeps_initial_balance() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 3],
+        longs: vec![0; 3],
+        close_shorts: vec![0; 3],
+        close_longs: vec![0; 3],
+        ..Default::default()
+    };
+    let columns = simulate_flat_bars(&[100.0, 120.0, 80.0], &signals);
+
+    assert_eq!(columns.positions, vec![0, 0, 0]);
+    assert_eq!(columns.trade_fees, vec![0.0, 0.0, 0.0]);
+    assert_balances(&columns.balances, &[100.0, 100.0, 100.0]);
+}
+
+#[test]
+fn test_simulate_positions_skips_orders_below_contract_minimum_notional() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 5],
+        longs: vec![1, 0, 0, 0, 0],
+        close_shorts: vec![0; 5],
+        close_longs: vec![0, 0, 1, 0, 0],
+        ..Default::default()
+    };
+    let prices = [100.0, 100.0, 110.0, 110.0, 110.0];
+    let timestamps: Vec<i64> = (0..prices.len() as i64)
+        .map(|index| index * 60_000)
+        .collect();
+    let trading_settings = TradingSettings::default();
+    let mut exchange = TestExchange::new(trading_settings.clone());
+    let simulate = |exchange: &TestExchange| {
+        simulate_positions(
+            &prices,
+            &prices,
+            &prices,
+            &prices,
+            &timestamps,
+            &signals,
+            &trading_settings,
+            exchange,
+            2.0,
+        )
+    };
+
+    // without minimum notional, tiny balance buys 0.02 units, worth 2 USDT
+    let columns = simulate(&exchange);
+    assert_eq!(columns.positions, vec![0, 1, 1, 0, 0]);
+
+    let traded_symbol_id = trading_settings.get_traded_symbol().id;
+    let contract = exchange.contracts.remove(&traded_symbol_id).unwrap();
+    exchange
+        .contracts
+        .insert(traded_symbol_id, contract.with_min_notional(Some(5.0)));
+    let columns = simulate(&exchange);
+
+    assert_eq!(columns.positions, vec![0; 5]);
+    assert_eq!(
+        columns.actions,
+        get_actions(&[SignalCategory::KeepPosition; 5])
+    );
+    assert_eq!(columns.trade_fees, vec![0.0; 5]);
+    assert_balances(&columns.balances, &[2.0; 5]);
+}
+
+#[test]
+fn test_simulate_positions_resolves_conflicting_signals_by_priority() {
+    // by default, longs are preferred over shorts
+    let signals = BenchmarkSignals {
+        shorts: vec![1, 0, 0, 0],
+        longs: vec![1, 0, 0, 0],
+        close_shorts: vec![0, 0, 1, 0],
+        close_longs: vec![0, 0, 1, 0],
+        ..Default::default()
+    };
+    let columns = simulate_flat_bars(&[100.0, 100.0, 110.0, 110.0], &signals);
+    assert_eq!(columns.positions, vec![0, 1, 1, 0]);
+
+    let signals = signals.with_priority(
+        SignalPriority::new(vec![
+            SignalCategory::GoShort,
+            SignalCategory::GoLong,
+            SignalCategory::CloseShort,
+            SignalCategory::CloseLong,
+        ])
+        .unwrap(),
+    );
+    let columns = simulate_flat_bars(&[100.0, 100.0, 110.0, 110.0], &signals);
+    assert_eq!(columns.positions, vec![0, -1, -1, 0]);
+
+    // winning position that can be added to is, by default, added to rather than closed
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 5],
+        longs: vec![1, 1, 0, 0, 0],
+        close_shorts: vec![0; 5],
+        close_longs: vec![0, 1, 0, 1, 0],
+        ..Default::default()
+    };
+    let prices = [100.0, 100.0, 110.0, 120.0, 120.0];
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.allocation_percentage = 50.0;
+    trading_settings.max_pyramid_adds = 1;
+    let columns = simulate_flat_bars_with_settings(&prices, &signals, trading_settings.clone());
+    assert_eq!(columns.positions, vec![0, 1, 1, 1, 0]);
+    assert_eq!(columns.actions[2], SignalCategory::GoLong.get_column());
+
+    let closes_first_priority = SignalPriority::new(vec![
+        SignalCategory::CloseLong,
+        SignalCategory::CloseShort,
+        SignalCategory::GoLong,
+        SignalCategory::GoShort,
+    ])
+    .unwrap();
+    let signals = signals.with_priority(closes_first_priority);
+    let columns = simulate_flat_bars_with_settings(&prices, &signals, trading_settings);
+    assert_eq!(columns.positions, vec![0, 1, 0, 0, 0]);
+    assert_eq!(columns.actions[2], SignalCategory::CloseLong.get_column());
+}
+
+#[test]
+fn test_simulate_positions_closes_trade_still_open_at_last_bar_close() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 4],
+        longs: vec![0, 1, 0, 0],
+        close_shorts: vec![0; 4],
+        close_longs: vec![0; 4],
+        ..Default::default()
+    };
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.close_open_at_end = true;
+    let columns =
+        simulate_flat_bars_with_settings(&[100.0, 100.0, 110.0, 120.0], &signals, trading_settings);
+
+    // long opened at 110 is marked to market at last close, 120
+    assert_eq!(columns.positions, vec![0, 0, 1, 0]);
+    assert_eq!(columns.actions[3], SignalCategory::CloseLong.get_column());
+    assert_eq!(columns.units[3], 0.0);
+    assert!((columns.profit_and_loss[3] - 9.09).abs() < 1e-3);
+    // 0.909 units are bought at 110, so that 0.01 of balance remains unallocated
+    assert_balances(&columns.balances, &[100.0, 100.0, 0.01, 109.09]);
+}
+
+#[test]
+fn test_simulate_positions_discards_trade_still_open_at_last_bar() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 4],
+        longs: vec![0, 1, 0, 0],
+        close_shorts: vec![0; 4],
+        close_longs: vec![0; 4],
+        ..Default::default()
+    };
+    let columns = simulate_flat_bars(&[100.0, 100.0, 110.0, 120.0], &signals);
+
+    // unrealized trades don't count towards benchmark results
+    assert_eq!(columns.positions, vec![0, 0, 0, 0]);
+    assert_eq!(
+        columns.actions,
+        get_actions(&[SignalCategory::KeepPosition; 4])
+    );
+    assert_eq!(columns.units, vec![0.0; 4]);
+    assert_balances(&columns.balances, &[100.0, 100.0, 100.0, 100.0]);
+}
+
+#[test]
+fn test_simulate_positions_skips_open_signals_within_trade_cooldown() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 7],
+        longs: vec![1, 0, 1, 1, 1, 0, 0],
+        close_shorts: vec![0; 7],
+        close_longs: vec![0, 1, 0, 0, 0, 1, 0],
+        ..Default::default()
+    };
+    let prices = [100.0; 7];
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.trade_cooldown = Some(StdDuration::from_secs(180));
+    let columns = simulate_flat_bars_with_settings(&prices, &signals, trading_settings);
+
+    // long closes at bar 2, so open signals are skipped until bar 5, when 3 minutes have passed
+    assert_eq!(columns.positions, vec![0, 1, 0, 0, 0, 1, 0]);
+
+    let columns = simulate_flat_bars(&prices, &signals);
+    assert_eq!(columns.positions[..4], [0, 1, 0, 1]);
+}
+
+#[test]
+fn test_simulate_positions_applies_cooldown_of_last_exit_reason() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 7],
+        longs: vec![1, 0, 1, 1, 1, 0, 0],
+        close_shorts: vec![0; 7],
+        close_longs: vec![0, 1, 0, 0, 0, 1, 0],
+        ..Default::default()
+    };
+    let prices = [100.0; 7];
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.trade_cooldown = Some(StdDuration::from_secs(180));
+    trading_settings
+        .exit_cooldowns
+        .insert(SignalCategory::CloseLong, StdDuration::from_secs(120));
+    // a longer cooldown after stops doesn't apply to signal closes
+    trading_settings
+        .exit_cooldowns
+        .insert(SignalCategory::StopLoss, StdDuration::from_secs(600));
+    let columns = simulate_flat_bars_with_settings(&prices, &signals, trading_settings);
+
+    // long closes by signal at bar 2, so its 2 minutes cooldown, rather than trade cooldown,
+    // skips open signals until bar 4
+    assert_eq!(columns.positions[..5], [0, 1, 0, 0, 1]);
+}
+
+#[test]
+fn test_simulate_positions_ignores_close_signals_within_position_lock_bars() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 6],
+        longs: vec![1, 0, 0, 0, 0, 0],
+        close_shorts: vec![0; 6],
+        close_longs: vec![0, 1, 1, 1, 0, 0],
+        ..Default::default()
+    };
+    let prices = [100.0; 6];
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.position_lock_bars = 3;
+    let columns = simulate_flat_bars_with_settings(&prices, &signals, trading_settings);
+
+    // long opens at bar 1, so close signals are ignored until bar 4, once it was held for 3 bars
+    assert_eq!(columns.positions, vec![0, 1, 1, 1, 0, 0]);
+    assert_eq!(columns.actions[4], SignalCategory::CloseLong.get_column());
+
+    let columns = simulate_flat_bars(&prices, &signals);
+    assert_eq!(columns.positions[..3], [0, 1, 0]);
+}
+
+#[test]
+fn test_simulate_positions_adds_to_winning_position_up_to_max_pyramid_adds() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 6],
+        longs: vec![1, 1, 1, 0, 0, 0],
+        close_shorts: vec![0; 6],
+        close_longs: vec![0, 0, 0, 1, 0, 0],
+        ..Default::default()
+    };
+    let prices = [100.0, 100.0, 110.0, 120.0, 120.0, 120.0];
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.allocation_percentage = 50.0;
+    trading_settings.max_pyramid_adds = 1;
+    let columns = simulate_flat_bars_with_settings(&prices, &signals, trading_settings.clone());
+
+    // long opens 0.5 units at bar 1, and is added 0.227 units at bar 2, as it's winning,
+    // whereas bar 3 open signal is ignored, as max pyramid adds were reached
+    assert_eq!(columns.positions, vec![0, 1, 1, 1, 0, 0]);
+    assert_eq!(columns.actions[2], SignalCategory::GoLong.get_column());
+    assert_eq!(
+        columns.actions[3],
+        SignalCategory::KeepPosition.get_column()
+    );
+    assert_eq!(columns.units[..4], [0.0, 0.5, 0.727, 0.727]);
+    // merged trade entry price is 103.12, so that it closes 0.727 units at 120
+    assert_balances(
+        &columns.balances,
+        &[100.0, 50.0, 25.03, 25.03, 112.27, 112.27],
+    );
+
+    trading_settings.max_pyramid_adds = 0;
+    let columns = simulate_flat_bars_with_settings(&prices, &signals, trading_settings);
+    assert_eq!(columns.units[..4], [0.0, 0.5, 0.5, 0.5]);
+    assert_balances(&columns.balances, &[100.0, 50.0, 50.0, 50.0, 110.0, 110.0]);
+}
+
+fn get_take_profit_settings(take_profit_partial_fraction: Option<f64>) -> TradingSettings {
+    let mut trading_settings = TradingSettings::default();
+    let take_profit = PriceLevel::TakeProfit(0.1);
+    trading_settings
+        .price_level_modifier_map
+        .insert(take_profit.get_hash_key(), take_profit);
+    trading_settings.take_profit_partial_fraction = take_profit_partial_fraction;
+    trading_settings
+}
+
+#[test]
+fn test_simulate_positions_closes_whole_position_at_take_profit() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 5],
+        longs: vec![1, 0, 0, 0, 0],
+        close_shorts: vec![0; 5],
+        close_longs: vec![0; 5],
+        ..Default::default()
+    };
+    let trading_settings = get_take_profit_settings(None);
+    let columns = simulate_flat_bars_with_settings(
+        &[100.0, 100.0, 100.0, 120.0, 120.0],
+        &signals,
+        trading_settings,
+    );
+
+    assert_eq!(columns.positions, vec![0, 1, 1, 0, 0]);
+    assert_eq!(columns.actions[3], SignalCategory::TakeProfit.get_column());
+    // take profit price is 110, 10% over open price
+    assert_balances(&columns.balances, &[100.0, 0.0, 0.0, 110.0, 110.0]);
+}
+
+#[test]
+fn test_simulate_positions_binds_take_profit_at_exact_boundary_price() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 5],
+        longs: vec![1, 0, 0, 0, 0],
+        close_shorts: vec![0; 5],
+        close_longs: vec![0; 5],
+        ..Default::default()
+    };
+    // take profit price is 110, 10% over open price
+    let boundary_price = 110.0_f32;
+    let rounded_boundary_price = f32::from_bits(boundary_price.to_bits() - 1);
+    for price in [boundary_price, rounded_boundary_price] {
+        let trading_settings = get_take_profit_settings(None);
+        let columns = simulate_flat_bars_with_settings(
+            &[100.0, 100.0, 100.0, f64::from(price), f64::from(price)],
+            &signals,
+            trading_settings,
+        );
+        assert_eq!(columns.positions, vec![0, 1, 1, 0, 0], "price = {}", price);
+        assert_eq!(columns.actions[3], SignalCategory::TakeProfit.get_column());
+    }
+
+    // without tolerance, rounded boundary price falls short of take profit price
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 6],
+        longs: vec![1, 0, 0, 0, 0, 0],
+        close_shorts: vec![0; 6],
+        close_longs: vec![0; 6],
+        ..Default::default()
+    };
+    let mut trading_settings = get_take_profit_settings(None);
+    trading_settings.price_level_epsilon = Some(0.0);
+    let columns = simulate_flat_bars_with_settings(
+        &[
+            100.0,
+            100.0,
+            100.0,
+            f64::from(rounded_boundary_price),
+            120.0,
+            120.0,
+        ],
+        &signals,
+        trading_settings,
+    );
+    assert_eq!(columns.positions, vec![0, 1, 1, 1, 0, 0]);
+    assert_eq!(columns.actions[4], SignalCategory::TakeProfit.get_column());
+}
+
+#[test]
+fn test_simulate_positions_scales_out_at_take_profit_and_lets_remainder_ride() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 7],
+        longs: vec![1, 0, 0, 0, 0, 0, 0],
+        close_shorts: vec![0; 7],
+        close_longs: vec![0, 0, 0, 0, 1, 0, 0],
+        ..Default::default()
+    };
+    let trading_settings = get_take_profit_settings(Some(0.5));
+    let columns = simulate_flat_bars_with_settings(
+        &[100.0, 100.0, 100.0, 120.0, 120.0, 130.0, 130.0],
+        &signals,
+        trading_settings,
+    );
+
+    assert_eq!(columns.positions, vec![0, 1, 1, 1, 1, 0, 0]);
+    assert_eq!(
+        columns.actions,
+        get_actions(&[
+            SignalCategory::KeepPosition,
+            SignalCategory::GoLong,
+            SignalCategory::KeepPosition,
+            SignalCategory::TakeProfit,
+            SignalCategory::KeepPosition,
+            SignalCategory::CloseLong,
+            SignalCategory::KeepPosition,
+        ])
+    );
+    assert_eq!(columns.units, vec![0.0, 1.0, 1.0, 0.5, 0.5, 0.0, 0.0]);
+    // half position is closed at 110 take profit price, returning its margin plus 5 of profit
+    assert!((columns.profit_and_loss[3] - 5.0).abs() < 1e-3);
+    // remainder keeps open, without take profit, until close signal at 130
+    assert!((columns.profit_and_loss[5] - 15.0).abs() < 1e-3);
+    assert_balances(
+        &columns.balances,
+        &[100.0, 0.0, 0.0, 55.0, 55.0, 120.0, 120.0],
+    );
+}
+
+#[test]
+fn test_simulate_positions_scales_out_at_each_take_profit_ladder_level() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 7],
+        longs: vec![1, 0, 0, 0, 0, 0, 0],
+        close_shorts: vec![0; 7],
+        close_longs: vec![0; 7],
+        ..Default::default()
+    };
+    let mut trading_settings = TradingSettings::default();
+    // half position is closed at 10%, a quarter at 20% and the rest at 30%
+    let take_profit_ladder =
+        PriceLevel::TakeProfitLadder(TakeProfitLadder(vec![(0.1, 0.5), (0.2, 0.25), (0.3, 0.25)]));
+    trading_settings
+        .price_level_modifier_map
+        .insert(take_profit_ladder.get_hash_key(), take_profit_ladder);
+    let columns = simulate_flat_bars_with_settings(
+        &[100.0, 100.0, 100.0, 115.0, 125.0, 135.0, 135.0],
+        &signals,
+        trading_settings,
+    );
+
+    assert_eq!(columns.positions, vec![0, 1, 1, 1, 1, 0, 0]);
+    assert_eq!(
+        columns.actions,
+        get_actions(&[
+            SignalCategory::KeepPosition,
+            SignalCategory::GoLong,
+            SignalCategory::KeepPosition,
+            SignalCategory::TakeProfit,
+            SignalCategory::TakeProfit,
+            SignalCategory::TakeProfit,
+            SignalCategory::KeepPosition,
+        ])
+    );
+    assert_eq!(columns.units, vec![0.0, 1.0, 1.0, 0.5, 0.25, 0.0, 0.0]);
+    // each level closes its units at its own take profit price, i.e. 110, 120 and 130
+    for (index, expected_pnl) in [(3, 5.0), (4, 5.0), (5, 7.5)] {
+        assert!((columns.profit_and_loss[index] - expected_pnl).abs() < 1e-3);
+    }
+    assert_balances(
+        &columns.balances,
+        &[100.0, 0.0, 0.0, 55.0, 85.0, 117.5, 117.5],
+    );
+}
+
+#[test]
+fn test_simulate_positions_closes_at_trailing_take_profit_after_retrace_from_peak() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 7],
+        longs: vec![1, 0, 0, 0, 0, 0, 0],
+        close_shorts: vec![0; 7],
+        close_longs: vec![0; 7],
+        ..Default::default()
+    };
+    let mut trading_settings = TradingSettings::default();
+    // activates past 10% returns, then closes if returns give back half of their peak
+    let trailing_take_profit =
+        PriceLevel::TrailingTakeProfit(TrailingTakeProfit::Percent(0.5, 0.1));
+    trading_settings
+        .price_level_modifier_map
+        .insert(trailing_take_profit.get_hash_key(), trailing_take_profit);
+    let columns = simulate_flat_bars_with_settings(
+        &[100.0, 100.0, 120.0, 115.0, 130.0, 110.0, 120.0],
+        &signals,
+        trading_settings,
+    );
+
+    // 115 doesn't retrace from 20% peak returns to 10%, but 110 does so from 30% to 15%
+    assert_eq!(columns.positions, vec![0, 1, 1, 1, 1, 0, 0]);
+    assert_eq!(
+        columns.actions[5],
+        SignalCategory::TrailingTakeProfit.get_column()
+    );
+    // position is closed at 115, trailing take profit price
+    assert_balances(
+        &columns.balances,
+        &[100.0, 0.0, 0.0, 0.0, 0.0, 115.0, 115.0],
+    );
+}
+
+#[test]
+fn test_simulate_positions_charges_funding_at_funding_times() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 11],
+        longs: vec![1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        close_shorts: vec![0; 11],
+        close_longs: vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0],
+        ..Default::default()
+    };
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.allocation_percentage = 50.0;
+    trading_settings.benchmark_funding_rate = Some(0.01);
+    // hourly bars, so that position is held through 8h funding time
+    trading_settings.granularity = Granularity::h1;
+    let columns = simulate_flat_bars_every(&[100.0; 11], &signals, trading_settings, 3_600_000);
+
+    assert_eq!(columns.positions, vec![0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0]);
+    let mut expected_funding_fees = vec![0.0; 11];
+    expected_funding_fees[8] = 0.5;
+    assert_eq!(columns.funding_fees, expected_funding_fees);
+    // long pays funding over its notional value, regardless of trade profit and loss
+    assert_balances(
+        &columns.balances,
+        &[
+            100.0, 50.0, 50.0, 50.0, 50.0, 50.0, 50.0, 50.0, 49.5, 49.5, 99.5,
+        ],
+    );
+}
+
+#[test]
+fn test_short_receives_funding_on_positive_funding_rate() {
+    let params = NewBenchmarkTradeParams::new(
+        100.0,
+        100.0,
+        1.0,
+        None,
+        0.0,
+        (0.001, 100.0),
+        100.0,
+        (None, None),
+        Side::Sell,
+        3,
+        0.0,
+        2,
+    );
+    let trade = new_benchmark_trade(params).unwrap();
+
+    assert_eq!(trade.get_funding_fee(100.0, 0.01), -1.0);
+}
+
+fn get_inverse_benchmark_trade(side: Side) -> BenchmarkTrade {
+    // a tenth of 1 BTC balance is spent on 2x position at 5,000
+    let params = NewBenchmarkTradeParams::new(
+        10.0,
+        1.0,
+        2.0,
+        Some(1.0),
+        0.0,
+        (1.0, 1_000_000.0),
+        5_000.0,
+        (None, None),
+        side,
+        0,
+        0.0,
+        1,
+    )
+    .with_contract_kind(ContractKind::Inverse);
+    new_benchmark_trade(params).unwrap()
+}
+
+#[test]
+fn test_inverse_benchmark_trades_are_margined_and_settled_in_base_coin() {
+    let long_trade = get_inverse_benchmark_trade(Side::Buy);
+    assert_eq!(long_trade.units, 1_000.0);
+    assert!((long_trade.initial_margin - 0.1).abs() < 1e-6);
+    let expected_bankruptcy_price =
+        ContractKind::Inverse.calculate_bankruptcy_price(Side::Buy, 5_000.0, 2.0) as f32;
+    assert!((long_trade.prices.1.unwrap() - expected_bankruptcy_price).abs() <= 0.1);
+    let (pnl, roi, close_fee) = long_trade.get_pnl_returns_and_fees(6_000.0, 0.0);
+    assert_eq!(close_fee, 0.0);
+    assert!((pnl - 1.0 / 30.0).abs() < 1e-6);
+    assert!((roi - 1.0 / 3.0).abs() < 1e-5);
+
+    let short_trade = get_inverse_benchmark_trade(Side::Sell);
+    assert_eq!(short_trade.units, 1_000.0);
+    let expected_bankruptcy_price =
+        ContractKind::Inverse.calculate_bankruptcy_price(Side::Sell, 5_000.0, 2.0) as f32;
+    assert!((short_trade.prices.1.unwrap() - expected_bankruptcy_price).abs() <= 0.1);
+    let (pnl, roi, _) = short_trade.get_pnl_returns_and_fees(6_000.0, 0.0);
+    assert!((pnl + 1.0 / 30.0).abs() < 1e-6);
+    assert!((roi + 1.0 / 3.0).abs() < 1e-5);
+    // whole margin is lost at bankruptcy price
+    let (pnl, _, _) = short_trade.get_pnl_returns_and_fees(expected_bankruptcy_price, 0.0);
+    assert!((pnl + short_trade.initial_margin).abs() < 1e-6);
+}
+
+#[test]
+fn test_simulate_positions_ignores_signals_within_warmup_bars() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 6],
+        longs: vec![1, 0, 0, 1, 0, 0],
+        close_shorts: vec![0; 6],
+        close_longs: vec![0, 0, 0, 0, 1, 0],
+        ..Default::default()
+    };
+    let prices = [100.0, 100.0, 100.0, 100.0, 100.0, 110.0];
+    let trading_settings = TradingSettings::default();
+    let exchange = TestExchange::new(trading_settings.clone());
+    let timestamps: Vec<i64> = (0..prices.len() as i64)
+        .map(|index| index * 60_000)
+        .collect();
+    let mut checkpoint = BenchmarkCheckpoint::new(100.0).skip_warmup(3);
+
+    let columns = resume_simulated_positions(
+        &prices,
+        &prices,
+        &prices,
+        &prices,
+        &timestamps,
+        &signals,
+        &trading_settings,
+        &exchange,
+        &mut checkpoint,
+    );
+
+    // long signal at first bar is emitted while indicators are still warming up
+    assert_eq!(columns.positions, vec![0, 0, 0, 0, 1, 0]);
+    assert_balances(&columns.balances, &[100.0, 100.0, 100.0, 100.0, 0.0, 110.0]);
+}
+
+#[test]
+fn test_resumed_positions_over_appended_bars_match_full_run() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0],
+        longs: vec![1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        close_shorts: vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0],
+        close_longs: vec![0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0],
+        ..Default::default()
+    };
+    let prices = [
+        100.0, 101.0, 103.0, 102.0, 105.0, 104.0, 106.0, 103.0, 99.0, 100.0, 98.0, 97.0,
+    ];
+    let trading_settings = TradingSettings::default();
+    let mut exchange = TestExchange::new(trading_settings.clone());
+    exchange.fee_rate = 0.001;
+    let timestamps: Vec<i64> = (0..prices.len() as i64)
+        .map(|index| index * 60_000)
+        .collect();
+    let full_columns = simulate_positions(
+        &prices,
+        &prices,
+        &prices,
+        &prices,
+        &timestamps,
+        &signals,
+        &trading_settings,
+        &exchange,
+        100.0,
+    );
+
+    // resumed while long is open, then while short is, then over the whole frame
+    let mut checkpoint = BenchmarkCheckpoint::new(100.0);
+    let mut resumed_columns = None;
+    for bars in [4, 8, prices.len()] {
+        assert!(bars == 4 || checkpoint.is_resumable_over(&timestamps[..bars]));
+        resumed_columns = Some(resume_simulated_positions(
+            &prices[..bars],
+            &prices[..bars],
+            &prices[..bars],
+            &prices[..bars],
+            &timestamps[..bars],
+            &signals,
+            &trading_settings,
+            &exchange,
+            &mut checkpoint,
+        ));
+    }
+    let resumed_columns = resumed_columns.unwrap();
+
+    assert_eq!(count_position_trades(&full_columns.positions), 2);
+    assert_eq!(
+        count_position_trades(&resumed_columns.positions),
+        count_position_trades(&full_columns.positions)
+    );
+    assert_eq!(resumed_columns.positions, full_columns.positions);
+    assert_eq!(resumed_columns.actions, full_columns.actions);
+    assert_eq!(resumed_columns.balances, full_columns.balances);
+    assert_eq!(
+        resumed_columns.profit_and_loss,
+        full_columns.profit_and_loss
+    );
+    assert_eq!(resumed_columns.trade_fees, full_columns.trade_fees);
+    // bars other than the processed ones followed by appended ones can't be resumed over
+    assert!(!checkpoint.is_resumable_over(&timestamps[1..]));
+    assert!(!checkpoint.is_resumable_over(&timestamps[..8]));
+}
+
+/// Flat one minute bars of traded symbol, whose prices oscillate around 100 over `bars`
+fn get_oscillating_tick_data(trading_settings: &TradingSettings, bars: i64) -> DataFrame {
+    let traded_symbol = trading_settings.get_traded_symbol();
+    let (open_col, high_col, low_col, close_col) = traded_symbol.get_ohlc_cols();
+    let start_times: Vec<i64> = (0..bars).map(|index| index * 60_000).collect();
+    let prices: Vec<f64> = (0..bars)
+        .map(|index| 100.0 + 10.0 * (index as f64 / 15.0).sin())
+        .collect();
+    df!(
+        "start_time" => start_times,
+        open_col => prices.clone(),
+        high_col => prices.clone(),
+        low_col => prices.clone(),
+        close_col => prices
+    )
+    .unwrap()
+    .lazy()
+    .with_column(col("start_time").cast(DataType::Datetime(TimeUnit::Milliseconds, None)))
+    .collect()
+    .unwrap()
+}
+
+#[test]
+fn test_sweep_ranks_every_param_grid_combination() {
+    let trading_settings = TradingSettings::default();
+    let exchange = TestExchange::new(trading_settings.clone());
+    let tick_data = get_oscillating_tick_data(&trading_settings, 300);
+    let strategy = Strategy::default();
+    let fast_span_config = NumberParamConfig::new(20, Some(1), Some(50));
+    let param_grid = ParamGrid {
+        strategy_params: HashMap::from([(
+            ParamId::FastSpan,
+            vec![
+                Param::UInt32(5, fast_span_config),
+                Param::UInt32(20, fast_span_config),
+            ],
+        )]),
+        price_levels: HashMap::from([(
+            PriceLevel::StopLoss(0.0).get_hash_key(),
+            vec![PriceLevel::StopLoss(0.01), PriceLevel::StopLoss(0.1)],
+        )]),
+    };
+
+    let results = sweep(
+        &strategy,
+        &trading_settings,
+        &exchange,
+        &tick_data,
+        &param_grid,
+        SweepMetric::TotalReturns,
+        100.0,
+    )
+    .unwrap();
+
+    assert_eq!(results.len(), 4);
+    for (param_set, _) in results.iter() {
+        assert!(param_set.strategy_params.contains_key(&ParamId::FastSpan));
+        assert!(param_set.price_levels.contains_key("sl"));
+    }
+    assert!(results
+        .windows(2)
+        .all(|pair| pair[0].1.total_returns >= pair[1].1.total_returns));
+    assert!(results.iter().any(|(_, result)| result.trades > 0));
+}
+
+#[test]
+fn test_sweep_orders_identical_grids_identically() {
+    let fast_span_config = NumberParamConfig::new(20, Some(1), Some(50));
+    let slow_span_config = NumberParamConfig::new(50, Some(1), Some(200));
+    // each grid gets its own maps, iterated in their own, randomly seeded, order
+    let get_param_grid = || ParamGrid {
+        strategy_params: HashMap::from([
+            (
+                ParamId::FastSpan,
+                vec![
+                    Param::UInt32(5, fast_span_config),
+                    Param::UInt32(20, fast_span_config),
+                ],
+            ),
+            (
+                ParamId::SlowSpan,
+                vec![
+                    Param::UInt32(50, slow_span_config),
+                    Param::UInt32(100, slow_span_config),
+                ],
+            ),
+        ]),
+        price_levels: HashMap::from([
+            (
+                PriceLevel::StopLoss(0.0).get_hash_key(),
+                vec![PriceLevel::StopLoss(0.01), PriceLevel::StopLoss(0.1)],
+            ),
+            (
+                PriceLevel::TakeProfit(0.0).get_hash_key(),
+                vec![PriceLevel::TakeProfit(0.01), PriceLevel::TakeProfit(0.1)],
+            ),
+        ]),
+    };
+    let describe = |param_sets: Vec<ParamSet>| -> Vec<String> {
+        param_sets
+            .iter()
+            .map(|param_set| {
+                let mut strategy_params: Vec<_> = param_set.strategy_params.iter().collect();
+                strategy_params.sort_by_key(|(param_id, _)| **param_id);
+                let mut price_levels: Vec<_> = param_set.price_levels.iter().collect();
+                price_levels.sort_by_key(|(hash_key, _)| *hash_key);
+                format!("{:?} {:?}", strategy_params, price_levels)
+            })
+            .collect()
+    };
+
+    let expected = describe(get_param_grid().get_param_sets());
+    assert_eq!(expected.len(), 16);
+    for _ in 0..10 {
+        assert_eq!(describe(get_param_grid().get_param_sets()), expected);
+    }
+
+    // combinations ranking the same, e.g. by swept params the strategy ignores, keep grid order
+    let trading_settings = TradingSettings::default();
+    let exchange = TestExchange::new(trading_settings.clone());
+    let tick_data = get_oscillating_tick_data(&trading_settings, 300);
+    let results = sweep(
+        &Strategy::default(),
+        &trading_settings,
+        &exchange,
+        &tick_data,
+        &get_param_grid(),
+        SweepMetric::MaxDrawdown,
+        100.0,
+    )
+    .unwrap();
+    let other_results = sweep(
+        &Strategy::default(),
+        &trading_settings,
+        &exchange,
+        &tick_data,
+        &get_param_grid(),
+        SweepMetric::MaxDrawdown,
+        100.0,
+    )
+    .unwrap();
+    let (param_sets, backtest_results): (Vec<_>, Vec<_>) = results.into_iter().unzip();
+    let (other_param_sets, other_backtest_results): (Vec<_>, Vec<_>) =
+        other_results.into_iter().unzip();
+    assert_eq!(describe(param_sets), describe(other_param_sets));
+    assert_eq!(backtest_results, other_backtest_results);
+}
+
+#[test]
+fn test_portfolio_sums_strategies_equity_and_attributes_pnl() {
+    let trading_settings = TradingSettings::default();
+    let exchange = TestExchange::new(trading_settings.clone());
+    let tick_data = get_oscillating_tick_data(&trading_settings, 300);
+    let fast_span_config = NumberParamConfig::new(20, Some(1), Some(50));
+    let fast_strategy = Strategy::default()
+        .patch_param(ParamId::FastSpan, Param::UInt32(5, fast_span_config))
+        .unwrap();
+    let portfolio = Portfolio::new(vec![
+        PortfolioStrategy::new(fast_strategy, 0.5),
+        PortfolioStrategy::new(Strategy::default(), 0.3),
+    ])
+    .unwrap();
+
+    let result = portfolio
+        .benchmark(&trading_settings, &exchange, &tick_data, 100.0)
+        .unwrap();
+
+    assert_eq!(result.equity.len(), tick_data.height());
+    assert!((result.equity[0] - 100.0).abs() < 1e-4);
+    assert_eq!(result.attributions.len(), 2);
+    assert!((result.attributions[0].initial_balance - 50.0).abs() < 1e-4);
+    assert!((result.attributions[1].initial_balance - 30.0).abs() < 1e-4);
+    let strategies_final_balance: f64 = result
+        .attributions
+        .iter()
+        .map(|attribution| attribution.result.final_balance)
+        .sum();
+    assert!((result.combined.final_balance - (strategies_final_balance + 20.0)).abs() < 1e-3);
+    let strategies_pnl: f64 = result
+        .attributions
+        .iter()
+        .map(|attribution| attribution.pnl)
+        .sum();
+    assert!((result.combined.final_balance - 100.0 - strategies_pnl).abs() < 1e-3);
+    if strategies_pnl != 0.0 {
+        let pnl_shares: f64 = result
+            .attributions
+            .iter()
+            .map(|attribution| attribution.pnl_share)
+            .sum();
+        assert!((pnl_shares - 1.0).abs() < 1e-6);
+    }
+    assert!(result.combined.max_drawdown >= 0.0 && result.combined.max_drawdown <= 1.0);
+    let mut marked_equity = vec![20.0; tick_data.height()];
+    for (portfolio_strategy, attribution) in portfolio
+        .get_strategies()
+        .iter()
+        .zip(result.attributions.iter())
+    {
+        let allocated_balance = attribution.initial_balance as f32;
+        let columns = simulate_strategy(
+            &portfolio_strategy.strategy,
+            &trading_settings,
+            &exchange,
+            &tick_data,
+            allocated_balance,
+        )
+        .unwrap();
+        for (equity, balance) in marked_equity
+            .iter_mut()
+            .zip(columns.get_marked_balances(allocated_balance))
+        {
+            *equity += balance;
+        }
+    }
+    assert!(result
+        .equity
+        .iter()
+        .zip(marked_equity.iter())
+        .all(|(equity, marked_equity)| (equity - marked_equity).abs() < 1e-3));
+    assert_eq!(
+        result.combined.trades,
+        result.attributions[0].result.trades + result.attributions[1].result.trades
+    );
+
+    assert!(Portfolio::new(vec![
+        PortfolioStrategy::new(Strategy::default(), 0.7),
+        PortfolioStrategy::new(Strategy::default(), 0.4),
+    ])
+    .is_err());
+    assert!(Portfolio::new(vec![PortfolioStrategy::new(Strategy::default(), 0.0)]).is_err());
+}
+
+fn get_benchmark_result_df(
+    start_times: Vec<i64>,
+    positions: Vec<i32>,
+    actions: Vec<&str>,
+    balances: Vec<f64>,
+    returns: Vec<f64>,
+) -> DataFrame {
+    df!(
+        "start_time" => start_times,
+        "position" => positions,
+        "action" => actions,
+        "balance" => balances,
+        "returns" => returns
+    )
+    .unwrap()
+    .lazy()
+    .with_column(col("start_time").cast(DataType::Datetime(TimeUnit::Milliseconds, None)))
+    .collect()
+    .unwrap()
+}
+
+#[test]
+fn test_diff_reports_diverging_bars_and_summary_deltas() {
+    let old = get_benchmark_result_df(
+        vec![0, 60_000, 120_000, 180_000],
+        vec![0, 1, 0, 0],
+        vec!["", "LongOpen", "LongClose", ""],
+        vec![100.0, 0.0, 110.0, 110.0],
+        vec![0.0, 0.0, 0.1, 0.0],
+    );
+    let unchanged = diff(&old, &old).unwrap();
+    assert!(unchanged.is_empty());
+
+    let new = get_benchmark_result_df(
+        vec![0, 60_000, 120_000, 180_000, 240_000],
+        vec![0, 1, 1, 0, -1],
+        vec!["", "LongOpen", "", "LongClose", "ShortOpen"],
+        vec![100.0, 0.0, 0.0, 105.0, 0.0],
+        vec![0.0, 0.0, 0.0, 0.05, 0.0],
+    );
+
+    let benchmark_diff = diff(&old, &new).unwrap();
+
+    let diverging_start_times = benchmark_diff
+        .bars
+        .iter()
+        .map(|bar| bar.start_time)
+        .collect::<Vec<i64>>();
+    assert_eq!(diverging_start_times, vec![120_000, 180_000, 240_000]);
+    let last_bar = benchmark_diff.bars.last().unwrap();
+    assert!(last_bar.old.is_none());
+    assert_eq!(last_bar.new.as_ref().unwrap().position, -1);
+    assert_eq!(benchmark_diff.final_balance_change, -110.0);
+    assert_eq!(benchmark_diff.trade_count_change, 1);
+}
+
+#[test]
+fn test_simulate_positions_ignores_signals_emitted_before_missing_bars() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 5],
+        longs: vec![1, 0, 1, 0, 0],
+        close_shorts: vec![0; 5],
+        close_longs: vec![0, 0, 0, 1, 0],
+        ..Default::default()
+    };
+    let trading_settings = TradingSettings::default();
+    let exchange = TestExchange::new(trading_settings.clone());
+    let prices = [100.0; 5];
+    // two minutes are missing between first and second bars
+    let timestamps = vec![0, 180_000, 240_000, 300_000, 360_000];
+
+    let columns = simulate_positions(
+        &prices,
+        &prices,
+        &prices,
+        &prices,
+        &timestamps,
+        &signals,
+        &trading_settings,
+        &exchange,
+        100.0,
+    );
+
+    assert_eq!(columns.positions, vec![0, 0, 0, 1, 0]);
+}
+
+#[test]
+fn test_simulate_positions_keeps_position_through_gap_bars() {
+    let signals = BenchmarkSignals {
+        shorts: vec![0; 5],
+        longs: vec![1, 0, 0, 0, 0],
+        close_shorts: vec![0; 5],
+        close_longs: vec![0, 1, 1, 1, 0],
+        gaps: vec![false, false, true, false, false],
+        ..Default::default()
+    };
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.price_level_modifier_map.insert(
+        PriceLevel::StopLoss(0.0).get_hash_key(),
+        PriceLevel::StopLoss(0.05),
+    );
+    let exchange = TestExchange::new(trading_settings.clone());
+    // gap bar has null prices, which are read as zeroes
+    let prices = [100.0, 100.0, 0.0, 100.0, 100.0];
+    let timestamps: Vec<i64> = (0..5).map(|index| index * 60_000).collect();
+
+    let columns = simulate_positions(
+        &prices,
+        &prices,
+        &prices,
+        &prices,
+        &timestamps,
+        &signals,
+        &trading_settings,
+        &exchange,
+        100.0,
+    );
+
+    // close signal before gap bar isn't acted upon at it, nor is the one emitted by it
+    assert_eq!(columns.positions, vec![0, 1, 1, 1, 0]);
+    assert_eq!(columns.actions[4], SignalCategory::CloseLong.get_column());
+}
@@ -1,5 +1,4 @@
-use chrono::NaiveDateTime;
-use common::functions::current_datetime;
+use common::{enums::benchmark_window::BenchmarkWindow, functions::current_datetime};
 use exchanges::enums::{DataProviderExchangeId, TraderExchangeId};
 use glow_error::GlowError;
 use serde::{Deserialize, Serialize};
@@ -11,12 +10,29 @@ use std::{
 };
 use strategy::StrategyId;
 
+pub const DEFAULT_BENCHMARK_INITIAL_BALANCE: f64 = 100.0;
+
+fn default_benchmark_initial_balance() -> f64 {
+    DEFAULT_BENCHMARK_INITIAL_BALANCE
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct BenchmarkSettings {
-    pub datetimes: (Option<NaiveDateTime>, Option<NaiveDateTime>),
+    /// period benchmarks are run over, see `BenchmarkWindow`.
+    pub window: BenchmarkWindow,
     pub strategy_id: StrategyId,
     pub data_provider_id: DataProviderExchangeId,
     pub trader_exchange_id: TraderExchangeId,
+    /// balance, in USDT, which benchmark positions start off with.
+    #[serde(default = "default_benchmark_initial_balance")]
+    pub initial_balance: f64,
+    /// whether strategy signals are only taken from fully closed bars, see `Strategy::use_closed_bars_only`.
+    #[serde(default)]
+    pub use_closed_bars_only: bool,
+    /// file trading sessions are persisted to and resumed from, see `Trader::restore`.
+    /// Sessions aren't persisted if it isn't set.
+    #[serde(default)]
+    pub session_state_path: Option<String>,
 }
 
 impl BenchmarkSettings {
@@ -57,10 +73,13 @@ impl BenchmarkSettings {
 impl Default for BenchmarkSettings {
     fn default() -> Self {
         Self {
-            datetimes: (None::<NaiveDateTime>, Some(current_datetime())),
+            window: BenchmarkWindow::Absolute(None, Some(current_datetime())),
             strategy_id: StrategyId::default(),
             data_provider_id: DataProviderExchangeId::default(),
             trader_exchange_id: TraderExchangeId::default(),
+            initial_balance: DEFAULT_BENCHMARK_INITIAL_BALANCE,
+            use_closed_bars_only: false,
+            session_state_path: None,
         }
     }
 }
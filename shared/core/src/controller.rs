@@ -2,9 +2,12 @@ use crate::{config::BenchmarkSettings, data_feed::DataFeed, trader::Trader};
 
 use super::performance::Performance;
 use chrono::{Duration, NaiveDateTime};
+use common::enums::{benchmark_window::BenchmarkWindow, run_mode::RunMode};
+use common::functions::current_datetime;
 use common::structs::TradingSettings;
 use common::traits::exchange::TraderHelper;
 use exchanges::enums::{DataProviderExchangeWrapper, TraderExchangeWrapper};
+use glow_error::GlowError;
 use strategy::{Strategy, StrategyId};
 
 #[derive(Clone)]
@@ -16,39 +19,56 @@ pub struct Controller {
 }
 
 impl Controller {
-    pub fn new(run_benchmark_only: bool) -> Self {
+    pub fn new(run_mode: RunMode) -> Self {
         let benchmark_settings = BenchmarkSettings::load_or_default();
         let BenchmarkSettings {
-            datetimes,
+            window,
             strategy_id,
             data_provider_id,
             trader_exchange_id,
+            initial_balance,
+            use_closed_bars_only,
+            ..
         } = benchmark_settings;
         let trading_settings = TradingSettings::load_or_default();
-        let strategy = Strategy::new(strategy_id, trading_settings.symbols_pair);
+        let strategy = Strategy::new(strategy_id, trading_settings.symbols_pair)
+            .expect("strategy signals to have their dependencies met")
+            .patch_use_closed_bars_only(use_closed_bars_only);
 
         let default_data_provider_exchange =
             DataProviderExchangeWrapper::new(data_provider_id, &strategy, &trading_settings);
 
         let data_feed = DataFeed::new(
-            datetimes,
+            window,
             default_data_provider_exchange,
-            run_benchmark_only,
+            run_mode,
             &strategy,
             &trading_settings,
         );
 
         let default_trader_exchange =
-            TraderExchangeWrapper::new(trader_exchange_id, &trading_settings);
+            TraderExchangeWrapper::new(trader_exchange_id, &trading_settings)
+                .expect("trader exchange config to be provided");
+        trading_settings
+            .validate(default_trader_exchange.get_traded_contract())
+            .expect("trading settings to be valid");
 
         let trader = Trader::new(
             &data_feed.strategy_data_emitter,
             default_trader_exchange,
             &data_feed.trading_data,
             &data_feed.minimum_klines_for_benchmarking,
+            &data_feed.indicator_warmup_bars,
+            &data_feed.signal_priority,
+            initial_balance,
+            run_mode,
         );
 
-        let initial_datetime = datetimes.1.unwrap() + Duration::days(1);
+        let (_, benchmark_end) = window.resolve(
+            current_datetime(),
+            trading_settings.granularity.get_chrono_duration(),
+        );
+        let initial_datetime = benchmark_end + Duration::days(1);
 
         let performance = Performance::new(
             initial_datetime,
@@ -69,7 +89,7 @@ impl Controller {
         benchmark_start: Option<NaiveDateTime>,
         benchmark_end: Option<NaiveDateTime>,
     ) {
-        self.benchmark_settings.datetimes = (benchmark_start, benchmark_end);
+        self.benchmark_settings.window = BenchmarkWindow::Absolute(benchmark_start, benchmark_end);
         let _ = self.benchmark_settings.save_config();
         self.data_feed
             .patch_benchmark_datetimes(benchmark_start, benchmark_end);
@@ -77,11 +97,20 @@ impl Controller {
             .patch_benchmark_datetimes(benchmark_start, benchmark_end);
     }
 
-    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
+    pub fn patch_benchmark_initial_balance(&mut self, initial_balance: f64) {
+        self.benchmark_settings.initial_balance = initial_balance;
+        let _ = self.benchmark_settings.save_config();
+        self.trader.patch_benchmark_initial_balance(initial_balance);
+    }
+
+    /// Trader's settings are patched first, as they're validated, so that invalid settings
+    /// aren't applied anywhere.
+    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) -> Result<(), GlowError> {
+        self.trader.patch_settings(trading_settings)?;
         self.data_feed.patch_trading_settings(trading_settings);
-        self.trader.patch_settings(trading_settings);
         self.performance.patch_settings(trading_settings);
         let _ = trading_settings.save_config();
+        Ok(())
     }
 
     pub fn patch_strategy_id(&mut self, strategy_id: StrategyId) {
@@ -92,13 +121,27 @@ impl Controller {
             .trader_exchange
             .get_trading_settings()
             .symbols_pair;
-        let updated_strategy = Strategy::new(strategy_id, symbols_pair);
-        self.data_feed.patch_strategy(&updated_strategy);
+        match Strategy::new(strategy_id, symbols_pair) {
+            Ok(updated_strategy) => self.data_feed.patch_strategy(
+                &updated_strategy
+                    .patch_use_closed_bars_only(self.benchmark_settings.use_closed_bars_only),
+            ),
+            Err(error) => println!("patch_strategy_id error {:?}", error),
+        }
     }
 
-    pub fn init(&self) {
+    /// Data feed is only initialized once trader is, so that it doesn't trade on a dirty start.
+    ///
+    /// Trading sessions are resumed from `session_state_path` setting beforehand, if it's set.
+    pub async fn init(&mut self) -> Result<(), GlowError> {
+        if let Some(path) = &self.benchmark_settings.session_state_path {
+            if self.trader.get_run_mode().trades() {
+                self.trader = self.trader.clone().restore(path).await?;
+            }
+        }
         self.performance.init();
-        self.trader.init();
+        self.trader.init().await?;
         self.data_feed.init();
+        Ok(())
     }
 }
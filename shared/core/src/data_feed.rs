@@ -1,6 +1,12 @@
-use chrono::NaiveDateTime;
-use common::enums::trading_data_update::TradingDataUpdate;
-use common::structs::{Symbol, TradingSettings};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use common::enums::{
+    benchmark_window::BenchmarkWindow, kline_gap_handling::KlineGapHandling, log_level::LogLevel,
+    run_mode::RunMode, trading_data_update::TradingDataUpdate,
+};
+use common::functions::csv::{append_df_to_csv, get_gathered_klines_csv_path};
+use common::functions::{fill_kline_gaps, normalize_klines_to_trading_data};
+use common::r#static::ORDER_BOOK_DEPTHS;
+use common::structs::{LogEvent, Logger, SignalPriority, Symbol, TradingSettings};
 use common::{structs::BehaviorSubject, traits::exchange::DataProviderExchange};
 use exchanges::enums::DataProviderExchangeWrapper;
 use glow_error::GlowError;
@@ -12,11 +18,16 @@ use tokio_stream::StreamExt;
 
 #[derive(Clone)]
 pub struct DataFeed {
-    benchmark_datetimes: (Option<NaiveDateTime>, Option<NaiveDateTime>), // (start, end)
+    benchmark_window: BenchmarkWindow,
     data_provider_exchange: DataProviderExchangeWrapper,
     kline_data_listener: BehaviorSubject<TradingDataUpdate>,
-    run_benchmark_only: bool, // TODO check if this is really necessary
+    pub indicator_warmup_bars: Arc<RwLock<u32>>,
+    kline_duration: Duration,
+    kline_gap_handling: KlineGapHandling,
+    logger: Logger,
     pub minimum_klines_for_benchmarking: Arc<RwLock<u32>>,
+    run_mode: RunMode,
+    pub signal_priority: Arc<RwLock<SignalPriority>>,
     pub strategy: Strategy,
     pub strategy_data_emitter: BehaviorSubject<TradingDataUpdate>,
     pub trading_data: Arc<Mutex<DataFrame>>,
@@ -33,6 +44,7 @@ impl DataFeed {
             schema_fields.push(Field::new(&low_col, DataType::Float64));
             schema_fields.push(Field::new(&close_col, DataType::Float64));
         }
+        schema_fields.push(Field::new("is_gap", DataType::Boolean));
     }
 
     fn insert_indicators_fields(schema_fields: &mut Vec<Field>, strategy: &Strategy) {
@@ -52,7 +64,10 @@ impl DataFeed {
     }
 
     fn insert_trading_fields(schema_fields: &mut Vec<Field>) -> Schema {
-        schema_fields.push(Field::new("trade_fees", DataType::Float64));
+        schema_fields.push(Field::new("trade_fees", DataType::Float64)); // open_fees + close_fees
+        schema_fields.push(Field::new("open_fees", DataType::Float64));
+        schema_fields.push(Field::new("close_fees", DataType::Float64));
+        schema_fields.push(Field::new("funding_fees", DataType::Float64));
         schema_fields.push(Field::new("units", DataType::Float64));
         schema_fields.push(Field::new("profit_and_loss", DataType::Float64));
         schema_fields.push(Field::new("returns", DataType::Float64));
@@ -63,7 +78,9 @@ impl DataFeed {
     }
 
     fn update_trading_data_df(trading_data: &Arc<Mutex<DataFrame>>, trading_data_df: &DataFrame) {
-        let mut trading_data_lock = trading_data.lock().unwrap();
+        let mut trading_data_lock = trading_data
+            .lock()
+            .expect("update_trading_data_df -> trading data deadlock");
         *trading_data_lock = trading_data_df.clone();
     }
 
@@ -71,7 +88,9 @@ impl DataFeed {
         minimum_klines_for_benchmarking: &Arc<RwLock<u32>>,
         value: u32,
     ) {
-        let mut lock = minimum_klines_for_benchmarking.write().unwrap();
+        let mut lock = minimum_klines_for_benchmarking
+            .write()
+            .expect("update_minimum_klines_for_benchmarking -> minimum klines deadlock");
         *lock = value;
     }
 
@@ -83,7 +102,7 @@ impl DataFeed {
         Self::insert_kline_fields(&mut schema_fields, &unique_symbols);
         Self::insert_indicators_fields(&mut schema_fields, &strategy);
         Self::insert_signals_fields(&mut schema_fields, &strategy);
-        let minimum_klines_for_benchmarking = strategy.get_minimum_klines_for_calculation();
+        let minimum_klines_for_benchmarking = strategy.get_warmup_klines();
         let trading_data_schema = Self::insert_trading_fields(&mut schema_fields);
         let trading_data_df = DataFrame::from(&trading_data_schema);
         (
@@ -94,13 +113,15 @@ impl DataFeed {
     }
 
     pub fn new(
-        benchmark_datetimes: (Option<NaiveDateTime>, Option<NaiveDateTime>),
+        benchmark_window: BenchmarkWindow,
         data_provider_exchange: DataProviderExchangeWrapper,
-        run_benchmark_only: bool,
+        run_mode: RunMode,
         strategy: &Strategy,
         trading_settings: &TradingSettings,
     ) -> DataFeed {
-        if let (Some(benchmark_start), Some(benchmark_end)) = benchmark_datetimes {
+        if let BenchmarkWindow::Absolute(Some(benchmark_start), Some(benchmark_end)) =
+            benchmark_window
+        {
             assert!(
                 benchmark_start < benchmark_end,
                 "Benchmark start must be before benchmark end"
@@ -116,11 +137,16 @@ impl DataFeed {
         let kline_data_listener = data_provider_exchange.get_kline_data_emitter().clone();
 
         DataFeed {
-            benchmark_datetimes,
+            benchmark_window,
             data_provider_exchange,
-            run_benchmark_only,
             kline_data_listener,
+            indicator_warmup_bars: Arc::new(RwLock::new(strategy.get_indicator_warmup_bars())),
+            signal_priority: Arc::new(RwLock::new(strategy.signal_priority.clone())),
+            kline_duration: trading_settings.granularity.get_chrono_duration(),
+            kline_gap_handling: trading_settings.kline_gap_handling,
+            logger: trading_settings.get_logger(),
             minimum_klines_for_benchmarking: Arc::new(RwLock::new(minimum_klines_for_benchmarking)),
+            run_mode,
             strategy: strategy.clone(),
             strategy_data_emitter,
             trading_data,
@@ -135,11 +161,13 @@ impl DataFeed {
         benchmark_start: Option<NaiveDateTime>,
         benchmark_end: Option<NaiveDateTime>,
     ) {
-        self.benchmark_datetimes = (benchmark_start, benchmark_end)
+        self.benchmark_window = BenchmarkWindow::Absolute(benchmark_start, benchmark_end)
     }
 
     pub fn patch_trading_settings(&mut self, trading_settings: &TradingSettings) {
         self.data_provider_exchange.patch_settings(trading_settings);
+        self.kline_duration = trading_settings.granularity.get_chrono_duration();
+        self.kline_gap_handling = trading_settings.kline_gap_handling;
         let unique_symbols = trading_settings.symbols_pair.get_unique_symbols();
         let (trading_data_schema, trading_data_df, minimum_klines_for_benchmarking) =
             Self::set_schema(&self.strategy, &unique_symbols);
@@ -160,6 +188,20 @@ impl DataFeed {
             &self.minimum_klines_for_benchmarking,
             minimum_klines_for_benchmarking,
         );
+        {
+            let mut lock = self
+                .indicator_warmup_bars
+                .write()
+                .expect("patch_strategy -> indicator warmup bars deadlock");
+            *lock = strategy.get_indicator_warmup_bars();
+        }
+        {
+            let mut lock = self
+                .signal_priority
+                .write()
+                .expect("patch_strategy -> signal priority deadlock");
+            *lock = strategy.signal_priority.clone();
+        }
         self.trading_data_schema = trading_data_schema;
     }
 
@@ -173,7 +215,17 @@ impl DataFeed {
         Ok(initial_strategy_lf)
     }
 
+    fn fill_kline_gaps(&self, klines_df: DataFrame) -> Result<DataFrame, GlowError> {
+        fill_kline_gaps(
+            klines_df,
+            &self.unique_symbols,
+            self.kline_duration,
+            self.kline_gap_handling,
+        )
+    }
+
     fn handle_initial_klines(&self, initial_klines_df: DataFrame) -> Result<DataFrame, GlowError> {
+        let initial_klines_df = self.fill_kline_gaps(initial_klines_df)?;
         let initial_klines_lf = initial_klines_df.lazy();
         let initial_klines_lf = self.set_initial_strategy_data(initial_klines_lf)?;
         let initial_strategy_data = initial_klines_lf.collect()?;
@@ -184,10 +236,17 @@ impl DataFeed {
         let trading_data: DataFrame;
 
         {
-            let trading_data_lock = self.trading_data.lock().unwrap();
+            let trading_data_lock = self.trading_data.lock()?;
             trading_data = trading_data_lock.clone();
         }
+        let market_klines_df = normalize_klines_to_trading_data(
+            &trading_data,
+            &market_klines_df,
+            &self.unique_symbols,
+        )?;
         let updated_strategy_data = trading_data.vstack(&market_klines_df)?;
+        // bars missing between last known and newly committed klines are inserted as well
+        let updated_strategy_data = self.fill_kline_gaps(updated_strategy_data)?;
 
         let updated_strategy_data = self
             .strategy
@@ -196,14 +255,64 @@ impl DataFeed {
         Ok(updated_strategy_data)
     }
 
+    /// Appends market klines to the gathered klines csv of the day each of them starts at.
+    fn persist_market_klines(&self, market_klines_df: &DataFrame) -> Result<(), GlowError> {
+        let start_dates: Vec<Option<NaiveDate>> = market_klines_df
+            .column("start_time")?
+            .datetime()?
+            .as_datetime_iter()
+            .map(|start_time| start_time.map(|start_time| start_time.date()))
+            .collect();
+        let mut dates = start_dates.iter().flatten().copied().collect::<Vec<_>>();
+        dates.dedup();
+        for date in dates {
+            let mask: BooleanChunked = start_dates
+                .iter()
+                .map(|start_date| *start_date == Some(date))
+                .collect();
+            let file_path =
+                get_gathered_klines_csv_path(date, self.data_provider_exchange.get_name());
+            append_df_to_csv(&market_klines_df.filter(&mask)?, file_path)?;
+        }
+        Ok(())
+    }
+
     fn handle_market_klines(&self, market_klines_df: DataFrame) -> Result<DataFrame, GlowError> {
         let updated_strategy_df = self.update_strategy_data(market_klines_df)?;
+        self.log_latest_indicator_values(&updated_strategy_df);
         Ok(updated_strategy_df)
     }
 
+    /// Logs indicators' values at the bar just appended, at the most verbose log level, so that
+    /// a signal that didn't fire can be traced back to the values it was computed upon.
+    fn log_latest_indicator_values(&self, strategy_df: &DataFrame) {
+        let latest_values = self.strategy.latest_indicator_values(strategy_df);
+        let mut sorted_values: Vec<_> = latest_values.iter().collect();
+        sorted_values.sort_by_key(|(column, _)| *column);
+        let values_summary = sorted_values
+            .iter()
+            .map(|(column, value)| format!("{} = {}", column, value))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.logger.log(
+            LogEvent::new(
+                LogLevel::All,
+                "indicator_values",
+                format!("🔎 Latest indicator values: {}", values_summary),
+            )
+            .with_field("values", latest_values),
+        );
+    }
+
     fn init_kline_data_handler(&self) -> JoinHandle<()> {
         let data_feed = self.clone();
+        // acknowledges handled klines updates, for data providers pacing them
+        let kline_ack_emitter = self.data_provider_exchange.get_kline_ack_emitter().cloned();
+        if let Some(kline_ack_emitter) = &kline_ack_emitter {
+            kline_ack_emitter.next(Some(0));
+        }
         spawn(async move {
+            let mut handled_updates = 0;
             let mut subscription = data_feed.kline_data_listener.subscribe();
             while let Some(klines_data) = subscription.next().await {
                 match klines_data {
@@ -219,6 +328,11 @@ impl DataFeed {
                         }
                     }
                     TradingDataUpdate::Market(market_klines_df) => {
+                        if data_feed.run_mode.persists_market_data() {
+                            if let Err(error) = data_feed.persist_market_klines(&market_klines_df) {
+                                println!("persist_market_klines error {:?}", error);
+                            }
+                        }
                         match data_feed.handle_market_klines(market_klines_df) {
                             Ok(updated_strategy_df) => {
                                 let payload = TradingDataUpdate::Market(updated_strategy_df);
@@ -229,33 +343,52 @@ impl DataFeed {
                             }
                         }
                     }
-                    _ => {}
+                    _ => continue,
+                }
+                handled_updates += 1;
+                if let Some(kline_ack_emitter) = &kline_ack_emitter {
+                    kline_ack_emitter.next(Some(handled_updates));
                 }
             }
         })
     }
 
+    /// Keeps latest order book depth streamed by data provider, if any, for live only indicators.
+    fn init_depth_handler(&self) -> Option<JoinHandle<()>> {
+        let depth_listener = self.data_provider_exchange.get_depth_emitter()?.clone();
+        let handle = spawn(async move {
+            let mut subscription = depth_listener.subscribe();
+            while let Some(depth) = subscription.next().await {
+                let Some(depth) = depth else {
+                    continue;
+                };
+                match ORDER_BOOK_DEPTHS.write() {
+                    Ok(mut depths) => {
+                        depths.insert(depth.symbol.clone(), depth);
+                    }
+                    Err(error) => println!("init_depth_handler error {:?}", error),
+                }
+            }
+        });
+        Some(handle)
+    }
+
     fn init_data_provider_handler(&self) -> JoinHandle<()> {
         let mut data_provider_binding = self.data_provider_exchange.clone();
-        let run_benchmark_only = self.run_benchmark_only;
+        let run_mode = self.run_mode;
         let trading_data_schema = self.trading_data_schema.clone();
-        let benchmark_start = self.benchmark_datetimes.0;
-        let benchmark_end = self.benchmark_datetimes.1;
+        let benchmark_window = self.benchmark_window;
 
         spawn(async move {
             let _ = data_provider_binding
-                .init(
-                    benchmark_start,
-                    benchmark_end,
-                    run_benchmark_only,
-                    trading_data_schema,
-                )
+                .init(benchmark_window, run_mode, trading_data_schema)
                 .await;
         })
     }
 
     pub fn init(&self) {
         self.init_kline_data_handler();
+        self.init_depth_handler();
         self.init_data_provider_handler();
     }
 }
@@ -1,9 +1,13 @@
 use chrono::{Duration, NaiveDateTime};
 use common::{
     constants::DAY_IN_MS,
-    enums::trading_data_update::TradingDataUpdate,
+    enums::{
+        exchange_environment::ExchangeEnvironment, granularity::Granularity, side::Side,
+        trading_data_update::TradingDataUpdate,
+    },
     functions::{
-        csv::{get_current_env_log_path, save_csv},
+        csv::{get_environment_log_path, save_csv},
+        get_trading_columns_values,
         performance::{
             calculate_calmar_ratio, calculate_max_drawdown_and_duration,
             calculate_risk_adjusted_returns, calculate_sharpe_ratio, calculate_sortino_ratio,
@@ -20,9 +24,26 @@ use std::sync::Mutex;
 use tokio::{spawn, task::JoinHandle};
 use tokio_stream::StreamExt;
 
+#[cfg(test)]
+mod tests;
+
+/// Summary of a single complete trade, as reconstructed from trading data.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TradeRecord {
+    pub entry_time: NaiveDateTime,
+    pub exit_time: NaiveDateTime,
+    pub side: Side,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub pnl: f64,
+    pub returns: f64,
+    pub exit_reason: String,
+}
+
 #[derive(Clone)]
 pub struct Performance {
     benchmark_stats: Arc<Mutex<Statistics>>,
+    environment: ExchangeEnvironment,
     _http: Client,
     risk_free_returns: f64,
     initial_datetime: NaiveDateTime,
@@ -40,6 +61,7 @@ impl Performance {
         let symbols = trading_settings.symbols_pair;
         Self {
             benchmark_stats: Arc::new(Mutex::new(Statistics::default())),
+            environment: trading_settings.environment,
             _http: Client::new(),
             risk_free_returns: 0.0,
             initial_datetime,
@@ -62,7 +84,126 @@ impl Performance {
 
     pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
         self.symbols = trading_settings.symbols_pair.clone();
+        self.environment = trading_settings.environment;
     }
+
+    /// Reconstructs complete trades from trading data `position` changes. Trades still open
+    /// at the last bar are left out.
+    ///
+    /// Entry price is traded symbol's open at entry bar, while exit price is derived from
+    /// exit bar gross profit and loss, so that price level exits are accounted for.
+    /// PnL is the balance change over the trade, hence net of fees.
+    pub fn extract_trades(&self, df: &DataFrame) -> Result<Vec<TradeRecord>, GlowError> {
+        let (start_times, trades_fees, units, pnls, returns, balances, positions, actions) =
+            get_trading_columns_values(df)?;
+        let opens: Vec<Option<f64>> = df
+            .column(self.symbols.traded.get_open_col())?
+            .f64()?
+            .into_iter()
+            .collect();
+
+        let mut trades = vec![];
+        let mut entry_index: Option<usize> = None;
+        let mut previous_position = 0;
+        for (index, position) in positions.iter().enumerate() {
+            let position = position.unwrap_or_default();
+            if position == previous_position {
+                continue;
+            }
+            if let Some(entry) = entry_index.take() {
+                let entry_price = opens[entry].unwrap_or_default();
+                let previous_units = units[index - 1].unwrap_or_default();
+                let fees =
+                    trades_fees[entry].unwrap_or_default() + trades_fees[index].unwrap_or_default();
+                let exit_price = if previous_units > 0.0 {
+                    let gross_pnl = pnls[index].unwrap_or_default() + fees;
+                    entry_price + previous_position as f64 * gross_pnl / previous_units
+                } else {
+                    opens[index].unwrap_or_default()
+                };
+                let balance_before_entry = balances[entry.saturating_sub(1)].unwrap_or_default();
+                trades.push(TradeRecord {
+                    entry_time: get_datetime(start_times[entry])?,
+                    exit_time: get_datetime(start_times[index])?,
+                    side: if previous_position > 0 {
+                        Side::Buy
+                    } else {
+                        Side::Sell
+                    },
+                    entry_price,
+                    exit_price,
+                    pnl: balances[index].unwrap_or_default() - balance_before_entry,
+                    returns: returns[index].unwrap_or_default(),
+                    exit_reason: actions[index].unwrap_or_default().to_string(),
+                });
+            }
+            if position != 0 {
+                entry_index = Some(index);
+            }
+            previous_position = position;
+        }
+
+        Ok(trades)
+    }
+
+    /// Downsamples trading data `balance` to `granularity` buckets, keeping last balance of each
+    /// bucket, alongside bucket returns and drawdown.
+    ///
+    /// Buckets are aligned to epoch multiples of granularity and labeled by their start time.
+    /// First bucket returns are relative to first balance of the data, while drawdown is the
+    /// relative distance to the highest bucket balance so far.
+    pub fn resample_equity(
+        &self,
+        df: &DataFrame,
+        granularity: Granularity,
+    ) -> Result<DataFrame, GlowError> {
+        let start_time_dtype = df.column("start_time")?.dtype().clone();
+        let Some(initial_balance) = df.column("balance")?.f64()?.into_iter().flatten().next()
+        else {
+            return Ok(DataFrame::new(vec![
+                Series::new_empty("start_time", &start_time_dtype),
+                Series::new_empty("balance", &DataType::Float64),
+                Series::new_empty("returns", &DataType::Float64),
+                Series::new_empty("drawdown", &DataType::Float64),
+            ])?);
+        };
+        let bucket_in_ms = granularity.get_chrono_duration().num_milliseconds();
+        let start_time = col("start_time").cast(DataType::Int64);
+
+        let resampled_df = df
+            .clone()
+            .lazy()
+            .select([
+                (start_time.clone() - start_time % lit(bucket_in_ms)).alias("start_time"),
+                col("balance"),
+            ])
+            .filter(col("balance").is_not_null())
+            .group_by([col("start_time")])
+            .agg([col("balance").last()])
+            .sort("start_time", SortOptions::default())
+            .with_columns([
+                (col("balance") / col("balance").shift(1).fill_null(lit(initial_balance))
+                    - lit(1.0))
+                .alias("returns"),
+                ((col("balance").cummax(false) - col("balance")) / col("balance").cummax(false))
+                    .alias("drawdown"),
+            ])
+            .with_column(col("start_time").cast(start_time_dtype))
+            .collect()?;
+
+        Ok(resampled_df)
+    }
+}
+
+fn get_datetime(timestamp: Option<i64>) -> Result<NaiveDateTime, GlowError> {
+    timestamp
+        .and_then(NaiveDateTime::from_timestamp_millis)
+        .ok_or_else(|| {
+            GlowError::new(
+                String::from("Invalid start time"),
+                format!("start_time {:?} isn't a valid timestamp", timestamp),
+            )
+        })
 }
 
 // risk-adjusted-return = reward / risk = mean returns / std of returns
@@ -86,7 +227,7 @@ impl Performance {
             journey_formmated_datetime_start, self.symbols.anchor.name, self.symbols.traded.name
         );
 
-        let path = get_current_env_log_path();
+        let path = get_environment_log_path(self.environment);
         let file_name = format!("{}_benchmark_data.csv", trading_journey_identifier);
         save_csv(path.clone(), file_name, &benchmark_trading_df, true)?;
 
@@ -129,7 +270,7 @@ impl Performance {
             .column("start_time")?
             .gt_eq(trading_journey_start)?;
         let trading_data = traded_data.filter(&filter_mask)?;
-        let path = get_current_env_log_path();
+        let path = get_environment_log_path(self.environment);
         let file_name = format!("{}_trading_data.csv", trading_journey_identifier);
         save_csv(path.clone(), file_name, &trading_data, true)?;
         let (trading_data, trading_stats) = update_trading_data(
@@ -297,6 +438,9 @@ pub fn calculate_trading_sessions(
         col("balance").last().keep_name(),
         col("returns").std(0).alias("risk"),
         col("trade_fees").sum().keep_name(),
+        col("open_fees").fill_null(lit(0.0)).sum().keep_name(),
+        col("close_fees").fill_null(lit(0.0)).sum().keep_name(),
+        col("funding_fees").fill_null(lit(0.0)).sum().keep_name(),
         col("returns")
             .apply_many(
                 |series| {
@@ -404,6 +548,10 @@ pub fn calculate_trading_stats(
     let sortino_ratio =
         calculate_sortino_ratio(returns_series, downside_risk_series, risk_free_returns)?;
     let calmar_ratio = calculate_calmar_ratio(balance_series, max_drawdown)?;
+    let trade_fees = df.column("trade_fees")?.sum().unwrap_or_default();
+    let open_fees = df.column("open_fees")?.sum().unwrap_or_default();
+    let close_fees = df.column("close_fees")?.sum().unwrap_or_default();
+    let funding_fees = df.column("funding_fees")?.sum().unwrap_or_default();
 
     Ok(Statistics::new(
         success_rate,
@@ -416,6 +564,10 @@ pub fn calculate_trading_stats(
         sharpe_ratio,
         sortino_ratio,
         calmar_ratio,
+        trade_fees,
+        open_fees,
+        close_fees,
+        funding_fees,
     ))
 }
 
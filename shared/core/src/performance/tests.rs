@@ -0,0 +1,175 @@
+use super::{Performance, TradeRecord};
+use chrono::{NaiveDate, NaiveDateTime};
+use common::{
+    enums::{granularity::Granularity, side::Side, trading_data_update::TradingDataUpdate},
+    structs::{BehaviorSubject, TradingSettings},
+};
+use polars::prelude::*;
+
+const TOLERANCE: f64 = 1e-9;
+
+fn get_datetime(minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, minute, 0)
+        .unwrap()
+}
+
+fn assert_trade_matches(trade: &TradeRecord, expected: &TradeRecord) {
+    assert_eq!(trade.entry_time, expected.entry_time);
+    assert_eq!(trade.exit_time, expected.exit_time);
+    assert_eq!(trade.side, expected.side);
+    assert_eq!(trade.exit_reason, expected.exit_reason);
+    for (value, expected_value) in [
+        (trade.entry_price, expected.entry_price),
+        (trade.exit_price, expected.exit_price),
+        (trade.pnl, expected.pnl),
+        (trade.returns, expected.returns),
+    ] {
+        assert!(
+            (value - expected_value).abs() < TOLERANCE,
+            "{:?} != {:?}",
+            trade,
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_extract_trades_reconstructs_complete_trades() {
+    let trading_settings = TradingSettings::default();
+    let performance = Performance::new(
+        get_datetime(0),
+        &trading_settings,
+        &BehaviorSubject::new(TradingDataUpdate::default()),
+    );
+    let open_col = trading_settings.symbols_pair.traded.get_open_col();
+    let start_times: Vec<i64> = (0..10)
+        .map(|minute| get_datetime(minute).timestamp_millis())
+        .collect();
+    // long opens at bar 1 and is closed by signal at bar 3,
+    // short opens at bar 5 and is stopped at bar 7, while last long is still open
+    let df = df!(
+        "start_time" => start_times,
+        open_col => [100.0, 100.0, 102.0, 104.0, 104.0, 100.0, 98.0, 96.0, 96.0, 97.0],
+        "trade_fees" => [0.0, 0.1, 0.0, 0.1, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        "units" => [0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0],
+        "profit_and_loss" => [0.0, -0.2, 1.8, 3.8, 0.0, 0.0, 2.0, 4.0, 0.0, 0.0],
+        "returns" => [0.0, -0.002, 0.018, 0.038, 0.0, 0.0, 0.02, 0.04, 0.0, 0.0],
+        "balance" => [100.0, 0.0, 0.0, 103.7, 103.7, 0.0, 0.0, 107.7, 107.7, 0.0],
+        "position" => [0, 1, 1, 0, 0, -1, -1, 0, 0, 1],
+        "action" => [
+            "position_keep",
+            "long",
+            "position_keep",
+            "long_close",
+            "position_keep",
+            "short",
+            "position_keep",
+            "stop_loss",
+            "position_keep",
+            "long"
+        ]
+    )
+    .unwrap();
+    let df = df
+        .lazy()
+        .with_column(col("start_time").cast(DataType::Datetime(TimeUnit::Milliseconds, None)))
+        .collect()
+        .unwrap();
+
+    let trades = performance.extract_trades(&df).unwrap();
+
+    assert_eq!(trades.len(), 2);
+    assert_trade_matches(
+        &trades[0],
+        &TradeRecord {
+            entry_time: get_datetime(1),
+            exit_time: get_datetime(3),
+            side: Side::Buy,
+            entry_price: 100.0,
+            exit_price: 104.0,
+            pnl: 3.7,
+            returns: 0.038,
+            exit_reason: String::from("long_close"),
+        },
+    );
+    assert_trade_matches(
+        &trades[1],
+        &TradeRecord {
+            entry_time: get_datetime(5),
+            exit_time: get_datetime(7),
+            side: Side::Sell,
+            entry_price: 100.0,
+            exit_price: 96.0,
+            pnl: 4.0,
+            returns: 0.04,
+            exit_reason: String::from("stop_loss"),
+        },
+    );
+}
+
+#[test]
+fn test_resample_equity_keeps_last_balance_per_bucket() {
+    let performance = Performance::new(
+        get_datetime(0),
+        &TradingSettings::default(),
+        &BehaviorSubject::new(TradingDataUpdate::default()),
+    );
+    let start_times: Vec<i64> = (0..12)
+        .map(|minute| get_datetime(minute).timestamp_millis())
+        .collect();
+    let df = df!(
+        "start_time" => start_times,
+        "balance" => [100.0, 101.0, 102.0, 104.0, 103.0, 98.0, 99.0, 97.0, 96.0, 110.0, 108.0, 105.0],
+    )
+    .unwrap();
+    let df = df
+        .lazy()
+        .with_column(col("start_time").cast(DataType::Datetime(TimeUnit::Milliseconds, None)))
+        .collect()
+        .unwrap();
+
+    let resampled_df = performance.resample_equity(&df, Granularity::m5).unwrap();
+
+    assert_eq!(
+        resampled_df.column("start_time").unwrap().dtype(),
+        &DataType::Datetime(TimeUnit::Milliseconds, None)
+    );
+    let start_times: Vec<i64> = resampled_df
+        .column("start_time")
+        .unwrap()
+        .datetime()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(
+        start_times,
+        [0, 5, 10]
+            .map(|minute| get_datetime(minute).timestamp_millis())
+            .to_vec()
+    );
+    for (column, expected_values) in [
+        ("balance", [103.0, 110.0, 105.0]),
+        ("returns", [0.03, 110.0 / 103.0 - 1.0, 105.0 / 110.0 - 1.0]),
+        ("drawdown", [0.0, 0.0, 5.0 / 110.0]),
+    ] {
+        let values: Vec<f64> = resampled_df
+            .column(column)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(values.len(), expected_values.len());
+        for (value, expected_value) in values.iter().zip(expected_values) {
+            assert!(
+                (value - expected_value).abs() < TOLERANCE,
+                "{}: {:?} != {:?}",
+                column,
+                values,
+                expected_values
+            );
+        }
+    }
+}
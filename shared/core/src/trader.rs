@@ -1,37 +1,74 @@
 use common::{
+    constants::{CLOCK_SKEW_CHECK_INTERVAL_SECS, CLOCK_SKEW_WARNING_THRESHOLD_MS},
     enums::{
-        balance::Balance, order_action::OrderAction, side::Side, signal_category::SignalCategory,
-        trade_status::TradeStatus, trading_data_update::TradingDataUpdate,
+        allocation_basis::AllocationBasis, balance::Balance, contract_kind::ContractKind,
+        log_level::LogLevel, modifiers::price_level::TakeProfitLadder, order_action::OrderAction,
+        run_mode::RunMode, side::Side, signal_category::SignalCategory, trade_status::TradeStatus,
+        trading_data_update::TradingDataUpdate,
     },
-    functions::{
-        check_last_index_for_signal, current_datetime, current_timestamp_ms,
-        get_trading_columns_values,
+    functions::{check_last_index_for_signal, get_fee_columns_values, get_trading_columns_values},
+    r#static::METRICS,
+    structs::{
+        BehaviorSubject, EquityPoint, Execution, LogEvent, LossCircuitBreaker, Order,
+        PositionSnapshot, SignalPriority, SystemClock, Trade, TradingSettings,
+    },
+    traits::{
+        clock::Clock,
+        exchange::{TraderExchange, TraderHelper},
+        trade_event_sink::TradeEventSink,
     },
-    structs::{BehaviorSubject, Execution, Order, Trade, TradingSettings},
-    traits::exchange::{TraderExchange, TraderHelper},
 };
-use exchanges::enums::TraderExchangeWrapper;
+use exchanges::{enums::TraderExchangeWrapper, structs::HttpRetryPolicy};
 use glow_error::GlowError;
 use polars::prelude::*;
-use std::sync::{Arc, Mutex, RwLock};
-use tokio::{spawn, task::JoinHandle};
+use std::{
+    future::Future,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+use tokio::{spawn, task::JoinHandle, time::sleep};
 use tokio_stream::StreamExt;
 
-use crate::benchmark::functions::compute_benchmark_positions;
+use crate::benchmark::functions::{resume_benchmark_positions, BenchmarkCheckpoint};
+use session_state::SessionState;
+pub mod session_state;
+#[cfg(test)]
+mod tests;
 
 #[derive(Clone)]
 pub struct Trader {
+    benchmark_checkpoint: Arc<Mutex<Option<BenchmarkCheckpoint>>>,
+    benchmark_initial_balance: Arc<RwLock<f64>>,
+    clock: Arc<dyn Clock>,
+    clock_skew_ms: Arc<RwLock<i64>>, // exchange server time minus local time
     current_balance_listener: BehaviorSubject<Balance>,
     current_trade_listener: BehaviorSubject<Option<Trade>>,
+    pub equity_emitter: BehaviorSubject<EquityPoint>,
+    exchange_recovery_listener: BehaviorSubject<TradingDataUpdate>,
     executions_update_listener: BehaviorSubject<Vec<Execution>>,
+    indicator_warmup_bars: Arc<RwLock<u32>>,
+    last_exit: Arc<Mutex<Option<(i64, SignalCategory)>>>,
+    loss_circuit_breaker: Arc<Mutex<LossCircuitBreaker>>,
     order_update_listener: BehaviorSubject<OrderAction>,
     pub performance_data_emitter: BehaviorSubject<TradingDataUpdate>,
+    pub position_snapshot_emitter: BehaviorSubject<PositionSnapshot>,
+    pyramid_adds: Arc<Mutex<(String, usize)>>, // (trade id, times its position was added to)
+    /// start time, in ms, of the last bar processed by restored session, see `restore`.
+    restored_last_bar_timestamp: Option<i64>,
+    run_mode: RunMode,
+    scaled_out_trade_id: Arc<Mutex<Option<String>>>,
+    session_state_path: Option<PathBuf>,
     signal_listener: BehaviorSubject<SignalCategory>,
+    signal_priority: Arc<RwLock<SignalPriority>>,
     strategy_data_listener: BehaviorSubject<TradingDataUpdate>,
+    take_profit_ladder_level: Arc<Mutex<(String, usize, f64)>>, // (trade id, ladder level set, units closed when set)
     temp_executions: Arc<Mutex<Vec<Execution>>>,
+    trade_event_sink: Option<Arc<dyn TradeEventSink + Send + Sync>>,
     pub trader_exchange: TraderExchangeWrapper,
     trading_data: Arc<Mutex<DataFrame>>,
     trading_data_klines_limit: Arc<RwLock<u32>>,
+    trading_enabled: BehaviorSubject<bool>,
 }
 
 impl Trader {
@@ -61,6 +98,10 @@ impl Trader {
         trader_exchange: TraderExchangeWrapper,
         trading_data: &Arc<Mutex<DataFrame>>,
         trading_data_klines_limit: &Arc<RwLock<u32>>,
+        indicator_warmup_bars: &Arc<RwLock<u32>>,
+        signal_priority: &Arc<RwLock<SignalPriority>>,
+        benchmark_initial_balance: f64,
+        run_mode: RunMode,
     ) -> Trader {
         let performance_data_emitter = BehaviorSubject::new(TradingDataUpdate::default());
         let (
@@ -69,52 +110,231 @@ impl Trader {
             order_update_listener,
             current_trade_listener,
         ) = Self::get_listeners(&trader_exchange);
+        let exchange_recovery_listener = trader_exchange.get_exchange_recovery_emitter().clone();
         Trader {
+            benchmark_checkpoint: Arc::new(Mutex::new(None)),
+            benchmark_initial_balance: Arc::new(RwLock::new(benchmark_initial_balance)),
+            clock: Arc::new(SystemClock),
+            clock_skew_ms: Arc::new(RwLock::new(0)),
             current_balance_listener: current_balance_listener.clone(),
             current_trade_listener: current_trade_listener.clone(),
+            equity_emitter: BehaviorSubject::new(EquityPoint::default()),
+            exchange_recovery_listener,
             executions_update_listener: executions_update_listener.clone(),
+            indicator_warmup_bars: indicator_warmup_bars.clone(),
+            last_exit: Arc::new(Mutex::new(None)),
+            loss_circuit_breaker: Arc::new(Mutex::new(LossCircuitBreaker::default())),
             order_update_listener: order_update_listener.clone(),
             performance_data_emitter: performance_data_emitter.clone(),
+            position_snapshot_emitter: BehaviorSubject::new(PositionSnapshot::default()),
+            pyramid_adds: Arc::new(Mutex::new((String::new(), 0))),
+            restored_last_bar_timestamp: None,
+            run_mode,
+            scaled_out_trade_id: Arc::new(Mutex::new(None)),
+            session_state_path: None,
             signal_listener: BehaviorSubject::new(SignalCategory::default()),
+            signal_priority: signal_priority.clone(),
             temp_executions: Arc::new(Mutex::new(Vec::new())),
             strategy_data_listener: strategy_data_listener.clone(),
+            take_profit_ladder_level: Arc::new(Mutex::new((String::new(), 0, 0.0))),
+            trade_event_sink: None,
             trader_exchange,
             trading_data: trading_data.clone(),
             trading_data_klines_limit: trading_data_klines_limit.clone(),
+            trading_enabled: BehaviorSubject::new(true),
+        }
+    }
+
+    /// Replaces system clock by `clock`, e.g. to drive time dependent behavior deterministically.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Sets `trade_event_sink` to be notified of trade lifecycle events. None is set by default.
+    pub fn with_trade_event_sink(
+        mut self,
+        trade_event_sink: Arc<dyn TradeEventSink + Send + Sync>,
+    ) -> Self {
+        self.trade_event_sink = Some(trade_event_sink);
+        self
+    }
+
+    /// Resumes session persisted at `path`, if any, which session state is persisted to on
+    /// every update, once trader is initialized.
+    ///
+    /// Exchange's current position takes precedence over persisted trade, which is only kept
+    /// if position can't be fetched, e.g. when exchange is unreachable. Signals of bars up to
+    /// the last processed one aren't acted upon again.
+    pub async fn restore(mut self, path: impl Into<PathBuf>) -> Result<Self, GlowError> {
+        let path = path.into();
+        let persisted_session_state = SessionState::load(&path)?;
+        let is_persisted = persisted_session_state.is_some();
+        let session_state = persisted_session_state.unwrap_or_default();
+        let current_trade = match self.trader_exchange.fetch_current_trade_position().await {
+            Ok(current_trade) => current_trade,
+            Err(error) => {
+                println!(
+                    "restore -> fetch_current_trade_position error {:?}, keeping persisted trade",
+                    error
+                );
+                session_state.current_trade.clone()
+            }
+        };
+        let persisted_trade_id = session_state
+            .current_trade
+            .as_ref()
+            .map(|trade| trade.id.clone());
+        let current_trade_id = current_trade.as_ref().map(|trade| trade.id.clone());
+        self.log(
+            LogEvent::new(
+                LogLevel::Trades,
+                "session_restored",
+                format!(
+                    "💾 Restored session, last processed bar at {:?}, persisted trade {:?}, current trade {:?}",
+                    session_state.last_bar_timestamp, persisted_trade_id, current_trade_id
+                ),
+            )
+            .with_field("last_bar_timestamp", session_state.last_bar_timestamp)
+            .with_field("persisted_trade_id", persisted_trade_id)
+            .with_field("current_trade_id", current_trade_id),
+        );
+        {
+            let mut lock = self.temp_executions.lock()?;
+            *lock = session_state.temp_executions;
+        }
+        if is_persisted {
+            self.current_balance_listener.next(session_state.balance);
+        }
+        self.current_trade_listener.next(current_trade);
+        self.restored_last_bar_timestamp = session_state.last_bar_timestamp;
+        self.session_state_path = Some(path);
+        Ok(self)
+    }
+
+    pub fn get_run_mode(&self) -> RunMode {
+        self.run_mode
+    }
+
+    /// Whether `df`'s last bar was already processed by restored session, see `restore`.
+    fn is_processed_by_restored_session(&self, df: &DataFrame) -> Result<bool, GlowError> {
+        let Some(restored_last_bar_timestamp) = self.restored_last_bar_timestamp else {
+            return Ok(false);
+        };
+        Ok(get_last_bar_timestamp(df)?
+            .is_some_and(|last_bar_timestamp| last_bar_timestamp <= restored_last_bar_timestamp))
+    }
+
+    /// Persists current session state, if trader was restored, see `restore`.
+    fn persist_session_state(&self) -> Result<(), GlowError> {
+        let Some(path) = &self.session_state_path else {
+            return Ok(());
+        };
+        let last_bar_timestamp = {
+            let trading_data = self.trading_data.lock()?;
+            get_last_bar_timestamp(&trading_data)?
+        };
+        let session_state = SessionState {
+            current_trade: self.current_trade_listener.value(),
+            temp_executions: self.get_temp_executions()?,
+            balance: self.current_balance_listener.value(),
+            last_bar_timestamp,
+        };
+        session_state.save(path)
+    }
+
+    /// Notifies trade event sink, if any.
+    fn notify_trade_event_sink(&self, notify: impl FnOnce(&(dyn TradeEventSink + Send + Sync))) {
+        if let Some(trade_event_sink) = &self.trade_event_sink {
+            notify(trade_event_sink.as_ref());
+        }
+    }
+
+    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) -> Result<(), GlowError> {
+        self.trader_exchange.patch_settings(trading_settings)?;
+        // benchmark results depend on settings, so these must be fully recomputed
+        self.clear_benchmark_checkpoint();
+        Ok(())
+    }
+
+    pub fn patch_benchmark_initial_balance(&self, initial_balance: f64) {
+        {
+            let mut lock = self
+                .benchmark_initial_balance
+                .write()
+                .expect("patch_benchmark_initial_balance -> benchmark initial balance deadlock");
+            *lock = initial_balance;
         }
+        // every balance derives from initial one, so benchmark must be fully recomputed
+        self.clear_benchmark_checkpoint();
     }
 
-    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
-        self.trader_exchange.patch_settings(trading_settings);
+    pub fn clear_benchmark_checkpoint(&self) {
+        let mut lock = self
+            .benchmark_checkpoint
+            .lock()
+            .expect("clear_benchmark_checkpoint -> benchmark checkpoint deadlock");
+        *lock = None;
+    }
+
+    pub fn get_signal_priority(&self) -> SignalPriority {
+        self.signal_priority
+            .read()
+            .expect("get_signal_priority -> signal priority deadlock")
+            .clone()
+    }
+
+    /// Gets current exposure and unrealized PnL, as of last trade or price update.
+    pub fn get_position_snapshot(&self) -> PositionSnapshot {
+        self.position_snapshot_emitter.value()
+    }
+
+    /// Emits a new position snapshot from current trade, marked at `mark_price`,
+    /// or at the last known mark price when not provided.
+    fn update_position_snapshot(&self, mark_price: Option<f64>) {
+        let mark_price =
+            mark_price.unwrap_or_else(|| self.position_snapshot_emitter.value().mark_price);
+        let current_trade = self.current_trade_listener.value();
+        let snapshot = PositionSnapshot::new(
+            current_trade.as_ref(),
+            self.trader_exchange.get_traded_contract().kind,
+            mark_price,
+            self.clock.now_ms(),
+        );
+        self.position_snapshot_emitter.next(snapshot);
     }
 
     fn get_trading_data(&self) -> Result<DataFrame, GlowError> {
         let trading_data: DataFrame;
         {
-            let lock = self.trading_data.lock().expect("trading data deadlock");
+            let lock = self.trading_data.lock()?;
             trading_data = lock.clone();
         }
         Ok(trading_data)
     }
 
+    /// Gets equity as of last trading data row.
+    pub fn get_equity_point(&self) -> EquityPoint {
+        self.equity_emitter.value()
+    }
+
+    /// Trading data remains the source of truth, whose last row is projected into an equity point.
     fn update_trading_data(&self, payload: DataFrame) -> Result<(), GlowError> {
+        let equity_point = EquityPoint::from_trading_data(&payload)?;
         {
-            let mut lock = self
-                .trading_data
-                .lock()
-                .expect("update trading data deadlock");
+            let mut lock = self.trading_data.lock()?;
             *lock = payload;
         }
+        if let Some(equity_point) = equity_point {
+            self.equity_emitter.next(equity_point);
+        }
         Ok(())
     }
 
     fn get_temp_executions(&self) -> Result<Vec<Execution>, GlowError> {
         let temp_executions: Vec<Execution>;
         {
-            let lock = self
-                .temp_executions
-                .lock()
-                .expect("temp_executions deadlock");
+            let lock = self.temp_executions.lock()?;
             temp_executions = lock.clone();
         }
         Ok(temp_executions)
@@ -122,10 +342,7 @@ impl Trader {
 
     fn push_to_temp_executions(&self, payload: Vec<Execution>) -> Result<(), GlowError> {
         {
-            let mut lock = self
-                .temp_executions
-                .lock()
-                .expect("update temp_executions deadlock");
+            let mut lock = self.temp_executions.lock()?;
             let mut updated_value = lock.clone();
             updated_value.extend(payload);
             *lock = updated_value;
@@ -133,7 +350,474 @@ impl Trader {
         Ok(())
     }
 
+    /// Checks whether trading settings' position lock prevents closing the trade at `close_price`.
+    fn is_position_close_locked(&self, trade: &Trade, close_price: f64) -> bool {
+        let trading_settings = self.trader_exchange.get_trading_settings();
+        let position_lock = trading_settings.position_lock_modifier;
+        let (close_fee_rate, _) = self
+            .trader_exchange
+            .get_order_fee_rate(trading_settings.get_close_order_type());
+        let contract_kind = self.trader_exchange.get_traded_contract().kind;
+        let gross_profit_and_loss = trade.calculate_gross_pnl(contract_kind, close_price);
+        let total_fee = trade.estimate_total_fee(contract_kind, close_price, close_fee_rate);
+        let is_locked = position_lock.is_close_locked(gross_profit_and_loss, total_fee);
+        if is_locked {
+            println!(
+                "\n{:?} | 🔒 Close signal skipped due to {:?} position lock -> gross profit and loss = {}, total fee = {}",
+                self.clock.now_datetime(),
+                position_lock,
+                gross_profit_and_loss,
+                total_fee
+            );
+        }
+        is_locked
+    }
+
+    /// Current time, in milliseconds, as measured by exchange clock, i.e. local time corrected by
+    /// last measured clock skew, so that it can be compared against exchange timestamps.
+    fn now_exchange_ms(&self) -> i64 {
+        let clock_skew_ms = self
+            .clock_skew_ms
+            .read()
+            .expect("now_exchange_ms -> clock skew deadlock");
+        self.clock.now_ms() + *clock_skew_ms
+    }
+
+    /// Measures exchange clock skew against local clock, assuming server time was taken halfway
+    /// through the request.
+    async fn measure_clock_skew(&self) -> Result<i64, GlowError> {
+        let request_start = self.clock.now_ms();
+        let server_time = self.trader_exchange.server_time().await?;
+        let request_end = self.clock.now_ms();
+        let clock_skew_ms = server_time - (request_start + request_end) / 2;
+        {
+            let mut lock = self.clock_skew_ms.write()?;
+            *lock = clock_skew_ms;
+        }
+        self.log(
+            LogEvent::new(
+                LogLevel::All,
+                "clock_skew",
+                format!("⏱️ Exchange clock skew measured at {} ms", clock_skew_ms),
+            )
+            .with_field("skew_ms", clock_skew_ms),
+        );
+        if clock_skew_ms.abs() > CLOCK_SKEW_WARNING_THRESHOLD_MS {
+            println!(
+                "\n{:?} | ⚠️ Exchange clock skew of {} ms exceeds {} ms, local clock should be synced",
+                self.clock.now_datetime(),
+                clock_skew_ms,
+                CLOCK_SKEW_WARNING_THRESHOLD_MS
+            );
+        }
+        Ok(clock_skew_ms)
+    }
+
+    /// Logs `event` according to trading settings' log level and format.
+    fn log(&self, event: LogEvent) {
+        self.trader_exchange
+            .get_trading_settings()
+            .get_logger()
+            .log(event);
+    }
+
+    /// Checks whether trading settings' cooldown after last trade's exit reason prevents acting
+    /// upon open `signal`.
+    fn is_in_trade_cooldown(&self, signal: SignalCategory) -> bool {
+        if signal != SignalCategory::GoLong && signal != SignalCategory::GoShort {
+            return false;
+        }
+        let trading_settings = self.trader_exchange.get_trading_settings();
+        let last_exit = *self
+            .last_exit
+            .lock()
+            .expect("is_in_trade_cooldown -> last exit deadlock");
+        let is_in_cooldown = trading_settings.is_in_trade_cooldown(last_exit, self.clock.now_ms());
+        if let (true, Some((_, exit_reason))) = (is_in_cooldown, last_exit) {
+            println!(
+                "\n{:?} | ⏳ {:?} signal skipped due to {:?} cooldown after {:?} exit",
+                self.clock.now_datetime(),
+                signal,
+                trading_settings.get_exit_cooldown(exit_reason),
+                exit_reason
+            );
+        }
+        is_in_cooldown
+    }
+
+    /// Gets balance available to open a position off `signal`. If current balance is stale, as
+    /// set by `TradingSettings::is_balance_stale`, it's refetched from exchange first, so that no
+    /// order is sized from an uninitialized balance. Returns `None`, logging why, if no usable
+    /// balance could be got.
+    async fn get_open_balance(&self, signal: SignalCategory) -> Option<f64> {
+        let trading_settings = self.trader_exchange.get_trading_settings();
+        let balance = self.current_balance_listener.value();
+        if !trading_settings.is_balance_stale(&balance, self.clock.now_ms()) {
+            return Some(balance.available_to_withdraw);
+        }
+        let reason = match self.trader_exchange.fetch_current_usdt_balance().await {
+            Ok(balance) => {
+                self.current_balance_listener.next(balance);
+                if balance.available_to_withdraw > 0.0 {
+                    return Some(balance.available_to_withdraw);
+                }
+                String::from("refetched balance has nothing available")
+            }
+            Err(error) => format!("balance is stale and refetching it failed: {:?}", error),
+        };
+        self.log(
+            LogEvent::new(
+                LogLevel::Trades,
+                "open_skipped",
+                format!("💤 {:?} signal skipped as {}", signal, reason),
+            )
+            .with_field("signal", signal.get_column())
+            .with_field("reason", reason),
+        );
+        None
+    }
+
+    /// Checks whether a loss limit reached by closed trades prevents acting upon open `signal`.
+    fn is_loss_limit_reached(&self, signal: SignalCategory) -> bool {
+        if signal != SignalCategory::GoLong && signal != SignalCategory::GoShort {
+            return false;
+        }
+        let reached_limit = self
+            .loss_circuit_breaker
+            .lock()
+            .expect("is_loss_limit_reached -> loss circuit breaker deadlock")
+            .get_reached_limit(
+                self.trader_exchange.get_trading_settings(),
+                self.clock.now_ms(),
+            );
+        if let Some(reached_limit) = reached_limit {
+            self.log(
+                LogEvent::new(
+                    LogLevel::Trades,
+                    "open_skipped",
+                    format!(
+                        "🧯 {:?} signal skipped due to {:?} loss limit",
+                        signal, reached_limit
+                    ),
+                )
+                .with_field("signal", signal.get_column())
+                .with_field("limit", format!("{:?}", reached_limit)),
+            );
+        }
+        reached_limit.is_some()
+    }
+
+    /// Pauses, or resumes, acting upon signals, which keep being generated and logged meanwhile.
+    /// Data feed and open positions, along with their exchange side stops, are unaffected.
+    pub fn set_trading_enabled(&self, trading_enabled: bool) {
+        if self.trading_enabled.value() == trading_enabled {
+            return;
+        }
+        let message = if trading_enabled {
+            "▶️ Trading resumed, signals will be acted upon."
+        } else {
+            "⏸️ Trading paused, signals won't be acted upon until it's resumed."
+        };
+        self.log(
+            LogEvent::new(LogLevel::Trades, "trading_toggled", String::from(message))
+                .with_field("trading_enabled", trading_enabled),
+        );
+        self.trading_enabled.next(trading_enabled);
+    }
+
+    pub fn is_trading_enabled(&self) -> bool {
+        self.trading_enabled.value()
+    }
+
+    /// Resets closed trades results tracked for loss limits, so that positions are opened again.
+    pub fn reset_loss_circuit_breaker(&self) {
+        self.loss_circuit_breaker
+            .lock()
+            .expect("reset_loss_circuit_breaker -> loss circuit breaker deadlock")
+            .reset();
+    }
+
+    /// Records closed `trade` results for loss limits, logging it once one of them is reached
+    /// and, if trading settings say so, cancelling open orders and flattening positions.
+    async fn on_close_trade_check_loss_limits(&self, trade: &Trade) -> Result<(), GlowError> {
+        if trade.is_closed_out_of_band() {
+            self.log(
+                LogEvent::new(
+                    LogLevel::Trades,
+                    "loss_limit_record_skipped",
+                    format!(
+                        "🧯 trade {} was closed out-of-band, its unknown pnl won't count towards loss limits",
+                        trade.id
+                    ),
+                )
+                .with_field("trade_id", trade.id.clone()),
+            );
+            return Ok(());
+        }
+        let trading_settings = self.trader_exchange.get_trading_settings();
+        let contract_kind = self.trader_exchange.get_traded_contract().kind;
+        let (pnl, _) = trade.calculate_pnl_and_returns(contract_kind);
+        let equity = self.current_balance_listener.value().wallet_balance;
+        let timestamp = self.clock.now_ms();
+        let (previous_limit, reached_limit) = {
+            let mut circuit_breaker = self.loss_circuit_breaker.lock()?;
+            let previous_limit = circuit_breaker.get_reached_limit(trading_settings, timestamp);
+            circuit_breaker.record_closed_trade(pnl, equity, timestamp);
+            (
+                previous_limit,
+                circuit_breaker.get_reached_limit(trading_settings, timestamp),
+            )
+        };
+        let Some(reached_limit) = reached_limit.filter(|_| previous_limit.is_none()) else {
+            return Ok(());
+        };
+        self.log(
+            LogEvent::new(
+                LogLevel::Trades,
+                "loss_limit_reached",
+                format!(
+                    "🧯 {:?} loss limit reached, no positions will be opened until it's lifted",
+                    reached_limit
+                ),
+            )
+            .with_field("limit", format!("{:?}", reached_limit))
+            .with_field("pnl", pnl)
+            .with_field("equity", equity),
+        );
+        if trading_settings.flatten_on_loss_limit {
+            self.trader_exchange.cancel_all_orders().await?;
+            self.trader_exchange.flatten_positions().await?;
+        }
+        Ok(())
+    }
+
+    /// Whether `signal` closing `trade` must be skipped, as it wasn't held for trading settings'
+    /// position lock bars since its open order was created.
+    fn is_in_position_lock_bars(&self, trade: &Trade, signal: SignalCategory) -> bool {
+        let trading_settings = self.trader_exchange.get_trading_settings();
+        let is_locked = trading_settings
+            .is_in_position_lock_bars(Some(trade.open_order.created_at), self.now_exchange_ms());
+        if is_locked {
+            self.log(
+                LogEvent::new(
+                    LogLevel::Trades,
+                    "close_skipped",
+                    format!(
+                        "📌 {:?} signal skipped due to {} position lock bars",
+                        signal, trading_settings.position_lock_bars
+                    ),
+                )
+                .with_field("signal", signal.get_column())
+                .with_field("position_lock_bars", trading_settings.position_lock_bars),
+            );
+        }
+        is_locked
+    }
+
+    /// Once `trade` open order is filled, sets a take profit for trading settings' take profit
+    /// partial fraction of its position, if any, so that the rest of it keeps open.
+    async fn scale_out_trade(&self, trade: &Trade) -> Result<(), GlowError> {
+        let trading_settings = self.trader_exchange.get_trading_settings();
+        if let Some(ladder) = trading_settings.get_take_profit_ladder() {
+            return self.scale_out_trade_ladder(trade, ladder).await;
+        }
+        let fraction = trading_settings.get_take_profit_partial_fraction();
+        if fraction.is_none() {
+            return Ok(());
+        }
+        {
+            let mut scaled_out_trade_id = self.scaled_out_trade_id.lock()?;
+            if scaled_out_trade_id.as_ref() == Some(&trade.id) {
+                return Ok(());
+            }
+            *scaled_out_trade_id = Some(trade.id.clone());
+        }
+        let open_order = &trade.open_order;
+        let take_profit_price = open_order.take_profit_price.or_else(|| {
+            self.trader_exchange.calculate_order_take_profit_price(
+                open_order.side,
+                open_order.get_executed_avg_price(),
+            )
+        });
+        if take_profit_price.is_none() {
+            return Ok(());
+        }
+        let take_profit_price = take_profit_price.unwrap();
+        let units = open_order.get_executed_quantity() * fraction.unwrap();
+        let scaled_out = self
+            .trader_exchange
+            .scale_out_position(trade, units, take_profit_price)
+            .await?;
+        if !scaled_out {
+            let error = format!(
+                "scale_out_trade -> scale out position returned false for trade {:?}",
+                trade.id
+            );
+            return Err(GlowError::new(String::from("Scale Out Error"), error));
+        }
+        self.log(
+            LogEvent::new(
+                LogLevel::Trades,
+                "position_scaled_out",
+                format!(
+                    "🪜 {:?} position will scale out {} units at {} take profit price",
+                    open_order.side, units, take_profit_price
+                ),
+            )
+            .with_field("side", open_order.side)
+            .with_field("units", units)
+            .with_field("take_profit_price", take_profit_price),
+        );
+        Ok(())
+    }
+
+    /// Sets `trade` take profit at `ladder` first level once its open order is filled, and at
+    /// each following level once previous one partially closed position, so that every level
+    /// scales out its own share of the position.
+    async fn scale_out_trade_ladder(
+        &self,
+        trade: &Trade,
+        ladder: TakeProfitLadder,
+    ) -> Result<(), GlowError> {
+        let closed_units = trade
+            .close_order
+            .as_ref()
+            .map_or(0.0, |close_order| close_order.get_executed_quantity());
+        let level = {
+            let mut take_profit_ladder_level = self.take_profit_ladder_level.lock()?;
+            let (trade_id, set_level, set_closed_units) = &*take_profit_ladder_level;
+            let level = if trade_id != &trade.id {
+                0
+            } else if closed_units > *set_closed_units {
+                set_level + 1
+            } else {
+                return Ok(());
+            };
+            if ladder.get_percentage(level).is_none() {
+                return Ok(());
+            }
+            *take_profit_ladder_level = (trade.id.clone(), level, closed_units);
+            level
+        };
+        let open_order = &trade.open_order;
+        let take_profit_price = self.trader_exchange.calculate_returns_price(
+            open_order.side,
+            open_order.get_executed_avg_price(),
+            ladder.get_percentage(level).unwrap(),
+        );
+        if take_profit_price.is_none() {
+            return Ok(());
+        }
+        let take_profit_price = take_profit_price.unwrap();
+        let units = ladder.get_level_units(level, open_order.get_executed_quantity(), closed_units);
+        let scaled_out = self
+            .trader_exchange
+            .scale_out_position(trade, units, take_profit_price)
+            .await?;
+        if !scaled_out {
+            let error = format!(
+                "scale_out_trade_ladder -> scale out position returned false for trade {:?} at level {}",
+                trade.id, level
+            );
+            return Err(GlowError::new(String::from("Scale Out Error"), error));
+        }
+        self.log(
+            LogEvent::new(
+                LogLevel::Trades,
+                "position_scaled_out",
+                format!(
+                    "🪜 {:?} position will scale out {} units at {} take profit price (level {})",
+                    open_order.side,
+                    units,
+                    take_profit_price,
+                    level + 1
+                ),
+            )
+            .with_field("side", open_order.side)
+            .with_field("units", units)
+            .with_field("take_profit_price", take_profit_price)
+            .with_field("level", level + 1),
+        );
+        Ok(())
+    }
+
+    /// Adds to winning `trade` position on a same side open `signal`, sized from available balance,
+    /// as long as it wasn't added to trading settings' max pyramid adds times yet.
+    async fn add_to_position(
+        &self,
+        trade: &Trade,
+        signal: SignalCategory,
+        last_price: f64,
+    ) -> Result<(), GlowError> {
+        let max_pyramid_adds = self.trader_exchange.get_trading_settings().max_pyramid_adds;
+        let contract_kind = self.trader_exchange.get_traded_contract().kind;
+        let (unrealized_pnl, _) =
+            trade.calculate_unrealized_pnl_and_returns(contract_kind, last_price);
+        if max_pyramid_adds == 0 || unrealized_pnl <= 0.0 || self.is_loss_limit_reached(signal) {
+            return Ok(());
+        }
+        {
+            let mut pyramid_adds = self.pyramid_adds.lock()?;
+            if pyramid_adds.0 != trade.id {
+                *pyramid_adds = (trade.id.clone(), 0);
+            }
+            if pyramid_adds.1 >= max_pyramid_adds {
+                self.log(
+                    LogEvent::new(
+                        LogLevel::Trades,
+                        "position_add_skipped",
+                        format!(
+                            "🔺 {:?} signal skipped as position was added to {} times already",
+                            signal, pyramid_adds.1
+                        ),
+                    )
+                    .with_field("signal", signal.get_column())
+                    .with_field("pyramid_adds", pyramid_adds.1),
+                );
+                return Ok(());
+            }
+        }
+        let Some(available_to_withdraw) = self.get_open_balance(signal).await else {
+            return Ok(());
+        };
+        open_order(
+            &self.trader_exchange,
+            signal.into(),
+            available_to_withdraw,
+            last_price,
+        )
+        .await?;
+        self.pyramid_adds.lock()?.1 += 1;
+        self.log(
+            LogEvent::new(
+                LogLevel::Trades,
+                "position_added",
+                format!(
+                    "🔺 {:?} position will be added to, as it's winning and received a same side open signal.",
+                    trade.open_order.side
+                ),
+            )
+            .with_field("side", trade.open_order.side)
+            .with_field("signal", signal.get_column()),
+        );
+        Ok(())
+    }
+
     async fn process_last_signal(&self, signal: SignalCategory) -> Result<(), GlowError> {
+        if !self.is_trading_enabled() {
+            self.log(
+                LogEvent::new(
+                    LogLevel::All,
+                    "signal_skipped",
+                    format!(
+                        "⏸️ Trading paused, {:?} signal won't be acted upon.",
+                        signal
+                    ),
+                )
+                .with_field("signal", signal.get_column()),
+            );
+            return Ok(());
+        }
         let current_trade = self.current_trade_listener.value();
         let traded_symbol = self.trader_exchange.get_traded_symbol();
         let close_col = traded_symbol.get_close_col();
@@ -147,7 +831,12 @@ impl Trader {
             .expect("process_last_signal -> SignalCategory::GoLong -> missing last price");
 
         if current_trade.is_none() {
-            let available_to_withdraw = self.current_balance_listener.value().available_to_withdraw;
+            if self.is_in_trade_cooldown(signal) || self.is_loss_limit_reached(signal) {
+                return Ok(());
+            }
+            let Some(available_to_withdraw) = self.get_open_balance(signal).await else {
+                return Ok(());
+            };
             return Ok(open_order(
                 &self.trader_exchange,
                 signal.into(),
@@ -166,44 +855,57 @@ impl Trader {
             (TradeStatus::New, SignalCategory::GoLong, Side::Sell) | // non-executed order received opposite signal
             (TradeStatus::New, SignalCategory::GoShort, Side::Buy) // non-executed order received opposite signal
              => {
-                match self.trader_exchange
-                        .cancel_order(current_trade.open_order.id.clone())
-                        .await
+                match retry_rate_limited(&HttpRetryPolicy::default(), || {
+                        self.trader_exchange
+                            .cancel_order(current_trade.open_order.id.clone())
+                    })
+                    .await
                     {
                         Ok(cancel_result) => {
                             // TODO: separate in fn
                             if cancel_result {
                                 if signal == SignalCategory::CloseLong || signal == SignalCategory::CloseShort || signal == SignalCategory::ClosePosition {
                                     // simple close signal received
-                                    println!(
-                                        "\n{:?} | ⚠️ Current order {:?} position, without executions, will be cancelled as it received a close signal.",
-                                        current_datetime(),
-                                        current_trade.open_order.side
-                                    );
+                                    self.log(LogEvent::new(
+                                        LogLevel::Trades,
+                                        "order_cancelled",
+                                        format!("⚠️ Current order {:?} position, without executions, will be cancelled as it received a close signal.", current_trade.open_order.side),
+                                    )
+                                    .with_field("side", current_trade.open_order.side)
+                                    .with_field("signal", signal.get_column()));
                                     return Ok(())
                                 }
-                                println!(
-                                    "\n{:?} | ⚠️ Current idle order {:?} position, without executions, will be cancelled as it received an opposite side open signal.",
-                                    current_datetime(),
-                                    current_trade.open_order.side
-                                );
+                                self.log(LogEvent::new(
+                                    LogLevel::Trades,
+                                    "order_cancelled",
+                                    format!("⚠️ Current idle order {:?} position, without executions, will be cancelled as it received an opposite side open signal.", current_trade.open_order.side),
+                                )
+                                .with_field("side", current_trade.open_order.side)
+                                .with_field("signal", signal.get_column()));
 
-                                let wallet_balance = self.current_balance_listener.value().wallet_balance;
+                                if self.is_in_trade_cooldown(signal) || self.is_loss_limit_reached(signal) {
+                                    return Ok(());
+                                }
+                                let Some(available_to_withdraw) = self.get_open_balance(signal).await else {
+                                    return Ok(());
+                                };
 
                                 match open_order(
                                     &self.trader_exchange,
                                     signal.into(),
-                                    wallet_balance,
+                                    available_to_withdraw,
                                     last_price,
                                 )
                                 .await
                                 {
                                     Ok(()) => {
-                                        println!(
-                                            "\n{:?} | ♻️ Current idle order, {:?} position, will be recycled as it received an opposite side open signal.",
-                                            current_datetime(),
-                                            current_trade.open_order.side
-                                        );
+                                        self.log(LogEvent::new(
+                                            LogLevel::Trades,
+                                            "order_recycled",
+                                            format!("♻️ Current idle order, {:?} position, will be recycled as it received an opposite side open signal.", current_trade.open_order.side),
+                                        )
+                                        .with_field("side", current_trade.open_order.side)
+                                        .with_field("signal", signal.get_column()));
                                         Ok(())
                                     }
                                     Err(error) => {
@@ -230,29 +932,30 @@ impl Trader {
             (TradeStatus::PartiallyOpen | TradeStatus::PendingCloseOrder, SignalCategory::ClosePosition, _)
              => {
                 if current_trade_status == &TradeStatus::PartiallyOpen {
-                    let mut open_order = current_trade.open_order.clone();
-                    let left_units = open_order.get_executed_quantity() - open_order.units;
-                    let updated_units = Some(left_units);
+                    // order is amended down to its executed quantity, so that its unfilled
+                    // remainder stops resting and the close order covers exactly what was filled
+                    let executed_units = current_trade.open_order.get_executed_quantity();
+                    let updated_units = Some(executed_units);
                     let updated_price = None;
                     let updated_stop_loss_price = None;
                     let updated_take_profit_price = None;
-                    let amend_result = self.trader_exchange
-                        .amend_order(
+                    let amend_result = retry_rate_limited(&HttpRetryPolicy::default(), || {
+                        self.trader_exchange.amend_order(
                             current_trade.open_order.id.clone(),
                             updated_units,
                             updated_price,
                             updated_stop_loss_price,
                             updated_take_profit_price,
                         )
-                        .await;
+                    })
+                    .await;
                     match amend_result {
                         Ok(amended) => {
                             if amended {
-                                open_order.update_units(left_units);
-                                current_trade = current_trade.update_trade(open_order)?;
+                                current_trade = drop_unfilled_open_units(&current_trade)?;
                             } else {
                                 let error = format!(
-                                    "TradeStatus::PartiallyOpen -> amend order returned false"
+                                    "TradeStatus::PartiallyOpen -> amend order was rejected by exchange"
                                 );
                                 let error = GlowError::new(String::from("Amend Order Error"), error);
                                 return Err(error);
@@ -269,12 +972,17 @@ impl Trader {
                     }
                 }
 
-                match self.trader_exchange
-                    .try_close_position(
-                        &current_trade,
-                        last_price,
-                    )
-                    .await
+                if self.is_position_close_locked(&current_trade, last_price)
+                    || self.is_in_position_lock_bars(&current_trade, signal)
+                {
+                    return Ok(());
+                }
+
+                match retry_rate_limited(&HttpRetryPolicy::default(), || {
+                    self.trader_exchange
+                        .try_close_position(&current_trade, last_price, true)
+                })
+                .await
                 {
                     Ok(close_order) => {
                         println!("TradeStatus::PartiallyOpen | TradeStatus::PendingCloseOrder -> try_close_position result {:?}", close_order);
@@ -289,6 +997,9 @@ impl Trader {
                     }
                 }
             }
+            (TradeStatus::PendingCloseOrder, SignalCategory::GoLong, Side::Buy) |
+            (TradeStatus::PendingCloseOrder, SignalCategory::GoShort, Side::Sell)
+             => self.add_to_position(&current_trade, signal, last_price).await,
             (current_trade_status, signal, open_order_side) => {
                 println!("process_last_signal NOOP current_trade_status = {:?}, signal = {:?}, open_order_side = {:?}", current_trade_status, signal, open_order_side);
                 Ok(())
@@ -304,9 +1015,11 @@ impl Trader {
                 if signal == SignalCategory::KeepPosition {
                     continue;
                 }
+                trader.notify_trade_event_sink(|sink| sink.on_signal(signal));
                 match trader.process_last_signal(signal).await {
                     Ok(()) => {}
                     Err(error) => {
+                        METRICS.order_errors.inc();
                         println!("process_last_signal error {:?}", error);
                     }
                 }
@@ -314,6 +1027,73 @@ impl Trader {
         })
     }
 
+    /// Serves metrics at `metrics_address` setting, if any, updating them from trader listeners.
+    fn init_metrics_handler(&self) -> Option<JoinHandle<()>> {
+        let address = self
+            .trader_exchange
+            .get_trading_settings()
+            .metrics_address?;
+        METRICS.serve(address);
+
+        let trader = self.clone();
+        spawn(async move {
+            let mut subscription = trader.current_balance_listener.subscribe();
+            while let Some(balance) = subscription.next().await {
+                METRICS.balance.set(balance.wallet_balance);
+            }
+        });
+
+        let trader = self.clone();
+        spawn(async move {
+            let mut subscription = trader.signal_listener.subscribe();
+            while let Some(signal) = subscription.next().await {
+                METRICS.set_last_signal(signal);
+            }
+        });
+
+        let trader = self.clone();
+        Some(spawn(async move {
+            let mut subscription = trader.current_trade_listener.subscribe();
+            let mut last_trade_id: Option<String> = None;
+            while let Some(current_trade) = subscription.next().await {
+                let Some(trade) = current_trade else {
+                    METRICS.open_positions.set(0);
+                    continue;
+                };
+                if last_trade_id.as_ref() != Some(&trade.id) {
+                    METRICS.trades.inc();
+                    last_trade_id = Some(trade.id.clone());
+                }
+                let is_open = !matches!(
+                    trade.status(),
+                    TradeStatus::New | TradeStatus::Cancelled | TradeStatus::Closed
+                );
+                METRICS.open_positions.set(is_open as i64);
+            }
+        }))
+    }
+
+    /// Persists session state on every trade, balance, executions or strategy data update,
+    /// if trader was restored.
+    fn init_session_state_handler(&self) -> Option<JoinHandle<()>> {
+        self.session_state_path.as_ref()?;
+        let trader = self.clone();
+        Some(spawn(async move {
+            let mut updates = trader
+                .current_trade_listener
+                .subscribe()
+                .map(|_| ())
+                .merge(trader.current_balance_listener.subscribe().map(|_| ()))
+                .merge(trader.executions_update_listener.subscribe().map(|_| ()))
+                .merge(trader.strategy_data_listener.subscribe().map(|_| ()));
+            while updates.next().await.is_some() {
+                if let Err(error) = trader.persist_session_state() {
+                    println!("persist_session_state error {:?}", error);
+                }
+            }
+        }))
+    }
+
     // fn init_balance_update_handler(&self) -> JoinHandle<()> {
     //     let trader = self.clone();
     //     spawn(async move {
@@ -326,6 +1106,31 @@ impl Trader {
     //     })
     // }
 
+    /// Whether `order` is a same side open order, other than `current_trade`'s own one,
+    /// adding to its position.
+    fn is_position_add(&self, current_trade: &Trade, order: &Order) -> bool {
+        !order.is_close
+            && order.id != current_trade.open_order.id
+            && order.side == current_trade.open_order.side
+            && self.trader_exchange.get_trading_settings().max_pyramid_adds > 0
+    }
+
+    /// Merges executions of `added_order` not merged yet into `current_trade` position,
+    /// emitting updated trade only if there were any.
+    fn merge_position_add(&self, current_trade: &Trade, added_order: Order) {
+        let merged_executions = current_trade.open_order.executions.len();
+        match current_trade.add_to_position(added_order) {
+            Ok(updated_trade) => {
+                if updated_trade.open_order.executions.len() > merged_executions {
+                    self.current_trade_listener.next(Some(updated_trade));
+                }
+            }
+            Err(error) => {
+                println!("merge_position_add -> add to position error {:?}", error);
+            }
+        }
+    }
+
     fn add_executions_to_order_and_remove_from_temp(&self, order: Order) -> Order {
         // let mut updated_order = order.clone();
         let mut temp_executions_guard = self
@@ -384,27 +1189,56 @@ impl Trader {
                                 println!("OrderAction::Update | OrderAction::Stop -> received a close order update with an empty trade");
                                 continue;
                             }
-                            println!(
-                                "\n{:?} | 📖 Opened {:?} order ({:?} units)",
-                                current_datetime(),
-                                updated_order.side,
-                                &updated_order.units,
+                            trader.log(
+                                LogEvent::new(
+                                    LogLevel::Trades,
+                                    "trade_opened",
+                                    format!(
+                                        "📖 Opened {:?} order ({:?} units)",
+                                        updated_order.side, &updated_order.units,
+                                    ),
+                                )
+                                .with_field("side", updated_order.side)
+                                .with_field("units", updated_order.units),
                             );
+                            trader.notify_trade_event_sink(|sink| sink.on_open(&updated_order));
                             let new_trade = Trade::new(updated_order, None);
                             trader.current_trade_listener.next(Some(new_trade));
                             continue;
                         }
                         let current_trade = current_trade.unwrap();
+                        // same side open orders, other than trade's, add their executions to its position
+                        if trader.is_position_add(&current_trade, &updated_order) {
+                            trader.merge_position_add(&current_trade, updated_order);
+                            continue;
+                        }
                         match current_trade.update_trade(updated_order.clone()) {
                             Ok(updated_trade) => {
                                 // println!("match trade, updated {:?}", &updated_trade);
                                 if let OrderAction::Stop(_) = order_action {
-                                    let (pnl, returns) = updated_trade.calculate_pnl_and_returns();
-                                    println!("\n{:?} | {} Position {:?} was stopped. Profit and loss = {}, returns = {}",  current_datetime(),
-                                    if pnl > 0.0 { "📈" } else { "📉" },
-                                    updated_trade.open_order.side,
-                                    pnl,
-                                    returns);
+                                    let contract_kind =
+                                        trader.trader_exchange.get_traded_contract().kind;
+                                    let (pnl, returns) =
+                                        updated_trade.calculate_pnl_and_returns(contract_kind);
+                                    trader.log(
+                                        LogEvent::new(
+                                            LogLevel::Trades,
+                                            "trade_stopped",
+                                            format!(
+                                                "{} Position {:?} was stopped. Profit and loss = {}, returns = {}",
+                                                if pnl > 0.0 { "📈" } else { "📉" },
+                                                updated_trade.open_order.side,
+                                                pnl,
+                                                returns
+                                            ),
+                                        )
+                                        .with_field("side", updated_trade.open_order.side)
+                                        .with_field("pnl", pnl)
+                                        .with_field("returns", returns),
+                                    );
+                                    trader.notify_trade_event_sink(|sink| {
+                                        sink.on_stop(&updated_trade)
+                                    });
                                 }
                                 trader.current_trade_listener.next(Some(updated_trade));
                             }
@@ -427,6 +1261,13 @@ impl Trader {
                             continue;
                         }
                         let current_trade = current_trade.unwrap();
+                        // adds cancelled after being partially filled still add what was executed
+                        if trader.is_position_add(&current_trade, &cancelled_order) {
+                            let cancelled_order = trader
+                                .add_executions_to_order_and_remove_from_temp(cancelled_order);
+                            trader.merge_position_add(&current_trade, cancelled_order);
+                            continue;
+                        }
                         // check if cancelled order is open order
                         if cancelled_order.id != current_trade.open_order.id {
                             println!(
@@ -497,24 +1338,33 @@ impl Trader {
             mut positions,
             mut actions,
         ) = get_trading_columns_values(&trading_data)?;
+        let (mut open_fees_col, mut close_fees_col, mut funding_fees_col) =
+            get_fee_columns_values(&trading_data)?;
 
-        let index = start_times.len() - 1;
+        let (index, _) = get_last_and_previous_indexes(&start_times)?;
         let balance = self.current_balance_listener.value();
         let signal = self.signal_listener.value();
 
-        let (fees, pnl, returns) = if trade_status == TradeStatus::Cancelled {
-            (0.0, 0.0, 0.0)
+        let ((open_fees, close_fees), pnl, returns) = if trade_status == TradeStatus::Cancelled {
+            ((0.0, 0.0), 0.0, 0.0)
         } else {
             let start_timestamp = start_times[index].expect(
                 "on_close_trade_update_trading_data -> TradeStatus::Closed arm -> interval_start_timestamp unwrap",
             );
-            let end_timestamp = current_timestamp_ms() as i64;
-            let (pnl, returns) = current_trade.calculate_pnl_and_returns();
-            let fees =
-                current_trade.get_executed_fees_between_interval(start_timestamp, end_timestamp);
-            (fees, pnl, returns)
+            let clock_skew_ms = *self.clock_skew_ms.read()?;
+            get_closed_trade_interval_results(
+                &current_trade,
+                self.trader_exchange.get_traded_contract().kind,
+                start_timestamp,
+                self.clock.as_ref(),
+                clock_skew_ms,
+            )
         };
-        fees_col[index] = Some(fees);
+        fees_col[index] = Some(open_fees + close_fees);
+        open_fees_col[index] = Some(open_fees);
+        close_fees_col[index] = Some(close_fees);
+        // funding isn't tracked by live trades
+        funding_fees_col[index] = Some(0.0);
         units[index] = Some(0.0);
         pnl_col[index] = Some(pnl);
         returns_col[index] = Some(returns);
@@ -524,6 +1374,12 @@ impl Trader {
 
         let mut trading_data = trading_data.clone();
         trading_data.replace("trade_fees", Series::new("trade_fees", fees_col))?;
+        trading_data.replace("open_fees", Series::new("open_fees", open_fees_col))?;
+        trading_data.replace("close_fees", Series::new("close_fees", close_fees_col))?;
+        trading_data.replace(
+            "funding_fees",
+            Series::new("funding_fees", funding_fees_col),
+        )?;
         trading_data.replace("units", Series::new("units", units))?;
         trading_data.replace("profit_and_loss", Series::new("profit_and_loss", pnl_col))?;
         trading_data.replace("returns", Series::new("returns", returns_col))?;
@@ -543,33 +1399,70 @@ impl Trader {
         spawn(async move {
             let mut subscription = trader.current_trade_listener.subscribe();
             while let Some(current_trade) = subscription.next().await {
+                trader.update_position_snapshot(None);
                 if current_trade.is_none() {
                     continue;
                 }
 
                 let current_trade = current_trade.unwrap();
                 let trade_status = current_trade.status();
+                if trade_status == TradeStatus::PendingCloseOrder
+                    || trade_status == TradeStatus::PartiallyClosed
+                {
+                    if let Err(error) = trader.scale_out_trade(&current_trade).await {
+                        println!("scale_out_trade error {:?}", error);
+                    }
+                    continue;
+                }
                 if trade_status != TradeStatus::Cancelled && trade_status != TradeStatus::Closed {
                     continue;
                 }
 
+                let contract_kind = trader.trader_exchange.get_traded_contract().kind;
                 if trade_status == TradeStatus::Closed {
+                    if let Some(exit_reason) = current_trade.get_exit_reason(contract_kind) {
+                        let mut last_exit = trader
+                            .last_exit
+                            .lock()
+                            .expect("init_trade_update_handler -> last exit deadlock");
+                        *last_exit = Some((trader.clock.now_ms(), exit_reason));
+                    }
+                    if let Err(error) = trader
+                        .on_close_trade_check_loss_limits(&current_trade)
+                        .await
+                    {
+                        println!("on_close_trade_check_loss_limits error {:?}", error);
+                    }
                     let close_order = current_trade.clone().close_order.unwrap();
-                    let (pnl, returns) = current_trade.calculate_pnl_and_returns();
-                    println!(
-                            "\n{:?} | 📕 Closed Order {:?} side ({:?} units), profit/loss: {}, returns: {}",
-                            current_datetime(),
-                            current_trade.open_order.side,
-                            &close_order.units,
-                            pnl,
-                            returns
-                        );
+                    let (pnl, returns) = current_trade.calculate_pnl_and_returns(contract_kind);
+                    trader.log(
+                        LogEvent::new(
+                            LogLevel::Trades,
+                            "trade_closed",
+                            format!(
+                                "📕 Closed Order {:?} side ({:?} units), profit/loss: {}, returns: {}",
+                                current_trade.open_order.side, &close_order.units, pnl, returns
+                            ),
+                        )
+                        .with_field("side", current_trade.open_order.side)
+                        .with_field("units", close_order.units)
+                        .with_field("pnl", pnl)
+                        .with_field("returns", returns),
+                    );
+                    trader.notify_trade_event_sink(|sink| sink.on_close(&current_trade));
                 } else {
-                    println!(
-                        "\n{:?} | ❌ Current Order side {:?} cancelled successfully!",
-                        current_datetime(),
-                        current_trade.open_order.side,
+                    trader.log(
+                        LogEvent::new(
+                            LogLevel::Trades,
+                            "trade_cancelled",
+                            format!(
+                                "❌ Current Order side {:?} cancelled successfully!",
+                                current_trade.open_order.side
+                            ),
+                        )
+                        .with_field("side", current_trade.open_order.side),
                     );
+                    trader.notify_trade_event_sink(|sink| sink.on_cancel(&current_trade));
                 }
 
                 match trader.on_close_trade_update_trading_data() {
@@ -580,18 +1473,64 @@ impl Trader {
         })
     }
 
+    /// Computes benchmark positions over the whole `initial_strategy_df`, except for its
+    /// indicators warmup bars, which are kept without positions so that they don't skew stats.
     fn compute_benchmark_positions(
         &self,
         initial_strategy_df: DataFrame,
     ) -> Result<DataFrame, GlowError> {
-        compute_benchmark_positions(&self, initial_strategy_df)
+        let warmup_bars = {
+            let lock = self.indicator_warmup_bars.read()?;
+            *lock as usize
+        };
+        let warmup_bars = warmup_bars.min(initial_strategy_df.height());
+        let initial_balance = {
+            let lock = self.benchmark_initial_balance.read()?;
+            *lock as f32
+        };
+        let mut checkpoint = BenchmarkCheckpoint::new(initial_balance).skip_warmup(warmup_bars);
+        let result = resume_benchmark_positions(self, initial_strategy_df, &mut checkpoint)?;
+        {
+            let mut lock = self.benchmark_checkpoint.lock()?;
+            *lock = Some(checkpoint);
+        }
+        Ok(result)
+    }
+
+    /// Resumes benchmark positions computation from last run checkpoint, so only bars appended
+    /// to `strategy_df` since then are processed. Runs over the whole frame if there's no checkpoint,
+    /// or if `strategy_df` doesn't extend the bars it processed.
+    pub fn resume_benchmark_positions(
+        &self,
+        strategy_df: DataFrame,
+    ) -> Result<DataFrame, GlowError> {
+        let checkpoint = {
+            let lock = self.benchmark_checkpoint.lock()?;
+            lock.clone()
+        };
+        let start_times = strategy_df
+            .column("start_time")?
+            .timestamp(TimeUnit::Milliseconds)?
+            .into_no_null_iter()
+            .collect::<Vec<i64>>();
+        let Some(mut checkpoint) =
+            checkpoint.filter(|checkpoint| checkpoint.is_resumable_over(&start_times))
+        else {
+            return self.compute_benchmark_positions(strategy_df);
+        };
+        let result = resume_benchmark_positions(self, strategy_df, &mut checkpoint)?;
+        {
+            let mut lock = self.benchmark_checkpoint.lock()?;
+            *lock = Some(checkpoint);
+        }
+        Ok(result)
     }
 
     fn handle_initial_strategy_data(
         &self,
         initial_strategy_df: DataFrame,
     ) -> Result<(), GlowError> {
-        let benchmark_data = self.compute_benchmark_positions(initial_strategy_df)?;
+        let benchmark_data = self.resume_benchmark_positions(initial_strategy_df)?;
         self.update_trading_data(benchmark_data.clone())?;
         let trading_data_update = TradingDataUpdate::Initial(benchmark_data);
         self.performance_data_emitter.next(trading_data_update);
@@ -614,15 +1553,10 @@ impl Trader {
             mut positions,
             mut actions,
         ) = get_trading_columns_values(&updated_strategy_df)?;
+        let (mut open_fees, mut close_fees, mut funding_fees) =
+            get_fee_columns_values(&updated_strategy_df)?;
 
-        if start_times.is_empty() {
-            let error = "start_times vector is empty".to_string();
-            let error = GlowError::new(String::from("Empty start times"), error);
-            return Err(error);
-        }
-
-        let index = start_times.len() - 1;
-        let previous_index = index - 1;
+        let (index, previous_index) = get_last_and_previous_indexes(&start_times)?;
 
         let balance = self.current_balance_listener.value();
         balances[index] = Some(balance.available_to_withdraw);
@@ -633,6 +1567,10 @@ impl Trader {
         let close_col = traded_symbol.get_close_col();
 
         trades_fees[index] = Some(0.0);
+        open_fees[index] = Some(0.0);
+        close_fees[index] = Some(0.0);
+        // funding isn't tracked by live trades
+        funding_fees[index] = Some(0.0);
         units[index] = Some(0.0);
         pnl[index] = Some(0.0);
         returns[index] = Some(0.0);
@@ -644,38 +1582,62 @@ impl Trader {
             let current_trade = trade.unwrap();
             let trade_status = current_trade.status();
             if trade_status != TradeStatus::Cancelled && trade_status != TradeStatus::Closed {
-                let current_price = &updated_strategy_df
-                    .column(&close_col)
-                    .unwrap()
+                let current_price = updated_strategy_df
+                    .column(close_col)?
                     .f64()?
                     .into_iter()
                     .last()
-                    .unwrap()
-                    .unwrap();
+                    .flatten()
+                    .ok_or_else(|| {
+                        let error = format!("{} has no last close price", close_col);
+                        GlowError::new(String::from("Missing Close Price"), error)
+                    })?;
 
                 let interval_start_timestamp = start_times[previous_index].unwrap();
                 let interval_end_timestamp = start_times[index].unwrap();
 
                 let (profit_and_loss, current_returns) = current_trade
-                    .calculate_current_pnl_and_returns(interval_end_timestamp, *current_price);
+                    .calculate_current_pnl_and_returns(
+                        self.trader_exchange.get_traded_contract().kind,
+                        interval_end_timestamp,
+                        current_price,
+                    );
 
-                let interval_fee = current_trade.get_executed_fees_between_interval(
-                    interval_start_timestamp,
-                    interval_end_timestamp,
-                );
+                let (interval_open_fee, interval_close_fee) = current_trade
+                    .get_executed_open_and_close_fees_between_interval(
+                        interval_start_timestamp,
+                        interval_end_timestamp,
+                    );
 
                 let current_units = current_trade.get_interval_units(interval_end_timestamp);
 
-                trades_fees[index] = Some(interval_fee);
+                trades_fees[index] = Some(interval_open_fee + interval_close_fee);
+                open_fees[index] = Some(interval_open_fee);
+                close_fees[index] = Some(interval_close_fee);
                 units[index] = Some(current_units);
                 pnl[index] = Some(profit_and_loss);
                 returns[index] = Some(current_returns);
                 positions[index] = Some(current_trade.open_order.side.into());
+                // partial take profit fills are recorded at bar they happened
+                let was_partially_closed = trade_status == TradeStatus::PartiallyClosed
+                    && current_trade
+                        .close_order
+                        .as_ref()
+                        .is_some_and(|close_order| {
+                            close_order.updated_at > interval_start_timestamp
+                                && close_order.updated_at <= interval_end_timestamp
+                        });
+                if was_partially_closed {
+                    actions[index] = Some(SignalCategory::TakeProfit.get_column());
+                }
             }
         }
 
         let mut updated_strategy_df = updated_strategy_df.clone();
         updated_strategy_df.replace("trade_fees", Series::new("trade_fees", trades_fees))?;
+        updated_strategy_df.replace("open_fees", Series::new("open_fees", open_fees))?;
+        updated_strategy_df.replace("close_fees", Series::new("close_fees", close_fees))?;
+        updated_strategy_df.replace("funding_fees", Series::new("funding_fees", funding_fees))?;
         updated_strategy_df.replace("units", Series::new("units", units))?;
         updated_strategy_df.replace("profit_and_loss", Series::new("profit_and_loss", pnl))?;
         updated_strategy_df.replace("returns", Series::new("returns", returns))?;
@@ -686,36 +1648,55 @@ impl Trader {
         Ok(updated_strategy_df)
     }
 
+    /// Takes position signal from last bar of `trading_data_df`, whose signals are the ones
+    /// emitted by the previous, fully closed, bar if strategy `use_closed_bars_only`.
     pub fn generate_last_position_signal(
         &self,
         trading_data_df: &DataFrame,
     ) -> Result<SignalCategory, GlowError> {
         let current_trade = self.current_trade_listener.value();
-        let mut emitted_signal = SignalCategory::KeepPosition;
+        let Some(current_trade) = current_trade else {
+            let signal_priority = self.signal_priority.read()?;
+            return get_last_position_signal(trading_data_df, None, false, &signal_priority);
+        };
+        let trade_status = current_trade.status();
+        // trade is about to be cleared, so nothing is acted upon until then
+        if trade_status == TradeStatus::Cancelled || trade_status == TradeStatus::Closed {
+            return Ok(SignalCategory::KeepPosition);
+        }
 
-        if current_trade.is_none() {
-            if check_last_index_for_signal(trading_data_df, SignalCategory::GoLong)? {
-                emitted_signal = SignalCategory::GoLong;
-            } else if check_last_index_for_signal(trading_data_df, SignalCategory::GoShort)? {
-                emitted_signal = SignalCategory::GoShort;
-            }
-        } else {
-            let current_trade = current_trade.unwrap();
-            let trade_status = current_trade.status();
-            if trade_status != TradeStatus::Cancelled && trade_status != TradeStatus::Closed {
-                let current_trade_side = current_trade.open_order.side;
-                if current_trade_side == Side::Buy
-                    && check_last_index_for_signal(trading_data_df, SignalCategory::CloseLong)?
-                {
-                    emitted_signal = SignalCategory::CloseLong;
-                } else if current_trade_side == Side::Sell
-                    && check_last_index_for_signal(trading_data_df, SignalCategory::CloseShort)?
-                {
-                    emitted_signal = SignalCategory::CloseShort;
-                }
+        // mirrors `add_to_position` checks, so that adds which would be skipped don't outrank closes
+        let max_pyramid_adds = self.trader_exchange.get_trading_settings().max_pyramid_adds;
+        let pyramid_adds = {
+            let lock = self.pyramid_adds.lock()?;
+            if lock.0 == current_trade.id {
+                lock.1
+            } else {
+                0
             }
-        }
-        Ok(emitted_signal)
+        };
+        let traded_symbol = self.trader_exchange.get_traded_contract().symbol;
+        let last_close_price = trading_data_df
+            .column(traded_symbol.get_close_col())?
+            .f64()?
+            .into_iter()
+            .last()
+            .flatten();
+        let can_add_to_position = pyramid_adds < max_pyramid_adds
+            && last_close_price.is_some_and(|last_close_price| {
+                let (unrealized_pnl, _) = current_trade.calculate_unrealized_pnl_and_returns(
+                    self.trader_exchange.get_traded_contract().kind,
+                    last_close_price,
+                );
+                unrealized_pnl > 0.0
+            });
+        let signal_priority = self.signal_priority.read()?;
+        get_last_position_signal(
+            trading_data_df,
+            Some(current_trade.open_order.side),
+            can_add_to_position,
+            &signal_priority,
+        )
     }
 
     fn clean_temp_executions(&self) -> Result<(), GlowError> {
@@ -724,10 +1705,7 @@ impl Trader {
             return Ok(());
         }
         let current_trade = current_trade.unwrap();
-        let mut temp_executions_guard = self
-            .temp_executions
-            .lock()
-            .expect("TradingDataUpdate::CleanUp -> temp_executions deadlock");
+        let mut temp_executions_guard = self.temp_executions.lock()?;
         if temp_executions_guard.len() <= 0 {
             return Ok(());
         }
@@ -746,9 +1724,7 @@ impl Trader {
         if pending_executions.len() <= 0 {
             return Ok(());
         }
-        let updated_trade = current_trade
-            .update_executions(pending_executions)
-            .expect("TradingDataUpdate::CleanUp update_executions unwrap");
+        let updated_trade = current_trade.update_executions(pending_executions)?;
         if updated_trade.is_none() {
             return Ok(());
         }
@@ -764,8 +1740,7 @@ impl Trader {
     }
 
     fn clean_trading_data(&self, trading_data: DataFrame) -> Result<DataFrame, GlowError> {
-        let trading_data_klines_limit = self.trading_data_klines_limit.read().unwrap();
-        let trading_data_klines_limit = trading_data_klines_limit.clone();
+        let trading_data_klines_limit = *self.trading_data_klines_limit.read()?;
         let trading_data = trading_data.tail(Some(trading_data_klines_limit as usize));
 
         Ok(trading_data)
@@ -777,8 +1752,20 @@ impl Trader {
     ) -> Result<(), GlowError> {
         // updates trading columns with latest indicators/signals
         let updated_df = self.update_trading_columns(updated_strategy_df)?;
-        // derives latest signal from them
-        let signal = self.generate_last_position_signal(&updated_df)?;
+        let traded_symbol = self.trader_exchange.get_traded_contract().symbol;
+        let last_close_price = updated_df
+            .column(traded_symbol.get_close_col())?
+            .f64()?
+            .into_iter()
+            .last()
+            .flatten();
+        self.update_position_snapshot(last_close_price);
+        // derives latest signal from them, unless its bar was processed by restored session
+        let signal = if self.is_processed_by_restored_session(&updated_df)? {
+            SignalCategory::KeepPosition
+        } else {
+            self.generate_last_position_signal(&updated_df)?
+        };
         // emits it.
         self.signal_listener.next(signal);
         // cleans trade executions
@@ -789,6 +1776,40 @@ impl Trader {
         Ok(())
     }
 
+    /// Trails open trade's exchange side stop by the last bar's most favorable price, i.e. its high
+    /// for long positions and its low for short ones, as benchmark updates peak returns by bar.
+    async fn trail_stop(&self) -> Result<(), GlowError> {
+        let Some(trade) = self.current_trade_listener.value() else {
+            return Ok(());
+        };
+        if matches!(
+            trade.status(),
+            TradeStatus::New | TradeStatus::Closed | TradeStatus::Cancelled
+        ) {
+            return Ok(());
+        }
+        let (_, high_col, low_col, _) = self.trader_exchange.get_traded_symbol().get_ohlc_cols();
+        let price_col = match trade.open_order.side {
+            Side::Buy => high_col,
+            Side::Sell => low_col,
+            Side::None => return Ok(()),
+        };
+        let trading_data = self.get_trading_data()?;
+        let price = trading_data
+            .column(price_col)?
+            .f64()?
+            .into_iter()
+            .last()
+            .flatten();
+        let Some(price) = price else {
+            return Ok(());
+        };
+        self.trader_exchange
+            .update_trailing_stop(&trade, price)
+            .await?;
+        Ok(())
+    }
+
     fn init_strategy_data_handler(&self) -> JoinHandle<()> {
         let trader = self.clone();
         spawn(async move {
@@ -799,7 +1820,10 @@ impl Trader {
                         trader.handle_initial_strategy_data(initial_strategy_df)
                     }
                     TradingDataUpdate::Market(updated_strategy_df) => {
-                        trader.handle_updated_strategy_data(updated_strategy_df)
+                        match trader.handle_updated_strategy_data(updated_strategy_df) {
+                            Ok(()) if trader.run_mode.trades() => trader.trail_stop().await,
+                            result => result,
+                        }
                     }
                     _ => Ok(()),
                 };
@@ -811,7 +1835,59 @@ impl Trader {
         })
     }
 
-    pub fn init(&self) {
+    async fn handle_exchange_recovery(&self) -> Result<(), GlowError> {
+        self.trader_exchange
+            .update_position_data_on_faulty_exchange_ws()
+            .await
+    }
+
+    fn init_exchange_recovery_handler(&self) -> JoinHandle<()> {
+        let trader = self.clone();
+        spawn(async move {
+            let mut subscription = trader.exchange_recovery_listener.subscribe();
+            while let Some(exchange_update) = subscription.next().await {
+                let result = match exchange_update {
+                    TradingDataUpdate::ExchangeRecovery => trader.handle_exchange_recovery().await,
+                    _ => Ok(()),
+                };
+
+                if result.is_err() {
+                    println!("init_exchange_recovery_handler error {:?}", result);
+                }
+            }
+        })
+    }
+
+    /// Periodically measures exchange clock skew, which offsets local time whenever it's compared
+    /// against exchange timestamps
+    fn init_clock_skew_handler(&self) -> JoinHandle<()> {
+        let trader = self.clone();
+        spawn(async move {
+            loop {
+                if let Err(error) = trader.measure_clock_skew().await {
+                    println!("init_clock_skew_handler error {:?}", error);
+                }
+                sleep(Duration::from_secs(CLOCK_SKEW_CHECK_INTERVAL_SECS)).await;
+            }
+        })
+    }
+
+    /// Cancels resting orders and flattens positions left over by previous sessions.
+    async fn start_clean(&self) -> Result<(), GlowError> {
+        let cancelled_orders = self.trader_exchange.cancel_all_orders().await?;
+        println!("start_clean -> {} orders cancelled", cancelled_orders);
+        self.trader_exchange.flatten_positions().await
+    }
+
+    /// Benchmark positions are computed over strategy data regardless of `run_mode`, while
+    /// handlers sending orders or tracking exchange state are only initialized when it trades.
+    ///
+    /// When trading settings start clean, exchange is cleaned up before any handler is, failing
+    /// otherwise.
+    pub async fn init(&self) -> Result<(), GlowError> {
+        if self.run_mode.trades() && self.trader_exchange.get_trading_settings().start_clean {
+            self.start_clean().await?;
+        }
         // let leverage_listener = self.leverage_listener.clone();
 
         // TODO: This query should be run at trader exchange level, same as balance
@@ -835,25 +1911,186 @@ impl Trader {
         //         }
         //     }
         // });
+        self.init_metrics_handler();
         self.init_strategy_data_handler();
+        if !self.run_mode.trades() {
+            return Ok(());
+        }
+        self.init_clock_skew_handler();
+        self.init_exchange_recovery_handler();
         // self.init_balance_update_handler();
         self.init_executions_update_handler();
         self.init_order_update_handler();
         self.init_signal_handler();
         self.init_trade_update_handler();
+        self.init_session_state_handler();
         // self.init_trading_data_update_handler();
+        Ok(())
     }
 }
 
+/// Start time, in ms, of `df`'s last bar, if it has any.
+fn get_last_bar_timestamp(df: &DataFrame) -> Result<Option<i64>, GlowError> {
+    match df.column("start_time") {
+        Ok(start_times) => Ok(start_times.datetime()?.into_iter().flatten().last()),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Gets signal to be acted upon at the last row of `trading_data_df`, given the side of the
+/// currently open trade, if any, which `None` stands for.
+///
+/// Without an open trade, open signals are applicable. Otherwise, the signal closing trade's
+/// side is, as well as its same side open signal, if `can_add_to_position`. Applicable signals
+/// fired at the same row are resolved by `signal_priority`.
+fn get_last_position_signal(
+    trading_data_df: &DataFrame,
+    open_side: Option<Side>,
+    can_add_to_position: bool,
+    signal_priority: &SignalPriority,
+) -> Result<SignalCategory, GlowError> {
+    let applicable_signals = match open_side {
+        None => vec![SignalCategory::GoLong, SignalCategory::GoShort],
+        Some(Side::Buy) if can_add_to_position => {
+            vec![SignalCategory::CloseLong, SignalCategory::GoLong]
+        }
+        Some(Side::Buy) => vec![SignalCategory::CloseLong],
+        Some(Side::Sell) if can_add_to_position => {
+            vec![SignalCategory::CloseShort, SignalCategory::GoShort]
+        }
+        Some(Side::Sell) => vec![SignalCategory::CloseShort],
+        Some(Side::None) => vec![],
+    };
+    let mut fired_signals = vec![];
+    for signal in applicable_signals {
+        if check_last_index_for_signal(trading_data_df, signal)? {
+            fired_signals.push(signal);
+        }
+    }
+    let emitted_signal = signal_priority
+        .resolve(&fired_signals)
+        .unwrap_or(SignalCategory::KeepPosition);
+    Ok(emitted_signal)
+}
+
+/// Drops the unfilled remainder of trade's open order, so that the trade is closed
+/// for exactly its executed quantity.
+fn drop_unfilled_open_units(trade: &Trade) -> Result<Trade, GlowError> {
+    let mut open_order = trade.open_order.clone();
+    open_order.update_units(open_order.get_executed_quantity());
+    trade.update_trade(open_order)
+}
+
+/// Gets (last, previous) row indexes of trading data with `start_times`, erroring if it has
+/// fewer than two rows, as trading columns are updated over the interval between them.
+fn get_last_and_previous_indexes(start_times: &[Option<i64>]) -> Result<(usize, usize), GlowError> {
+    if start_times.len() < 2 {
+        let error = format!(
+            "trading data has {} rows, whereas at least 2 are required to update its last interval",
+            start_times.len()
+        );
+        return Err(GlowError::new(
+            String::from("Insufficient Trading Data"),
+            error,
+        ));
+    }
+    let index = start_times.len() - 1;
+    Ok((index, index - 1))
+}
+
+/// Gets (fees, pnl, returns) of closed `trade`, charging fees of executions between last bar
+/// `start_timestamp` and `clock` current time, offset by `clock_skew_ms` to exchange time.
+/// Pnl is in margin currency of `contract_kind`.
+fn get_closed_trade_interval_results(
+    trade: &Trade,
+    contract_kind: ContractKind,
+    start_timestamp: i64,
+    clock: &dyn Clock,
+    clock_skew_ms: i64,
+) -> ((f64, f64), f64, f64) {
+    let end_timestamp = clock.now_ms() + clock_skew_ms;
+    let (pnl, returns) = trade.calculate_pnl_and_returns(contract_kind);
+    let fees =
+        trade.get_executed_open_and_close_fees_between_interval(start_timestamp, end_timestamp);
+    (fees, pnl, returns)
+}
+
+/// Runs exchange `operation`, retrying it according to `retry_policy` backoff for as long as
+/// it's rejected by rate limits, so that signals aren't dropped due to them.
+/// Any other result is returned as it is.
+async fn retry_rate_limited<T, F, Fut>(
+    retry_policy: &HttpRetryPolicy,
+    mut operation: F,
+) -> Result<T, GlowError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, GlowError>>,
+{
+    let mut retry = 0;
+    loop {
+        match operation().await {
+            Err(error) if error.is_rate_limited() && retry < retry_policy.max_retries => {
+                let delay = retry_policy.get_backoff_delay(retry);
+                println!(
+                    "retry_rate_limited -> {}, retrying in {:?} ({}/{})",
+                    error.description,
+                    delay,
+                    retry + 1,
+                    retry_policy.max_retries
+                );
+                sleep(delay).await;
+                retry += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Opens order at last price or, when `use_live_spread` is set, at current best ask (for buys)
+/// or bid (for sells). If ticker can't be fetched, last price is used instead.
+///
+/// Order is sized off trading settings' allocation basis, valuing base asset units held at
+/// wallet at last price. Fails if there's nothing to allocate from.
 async fn open_order(
     exchange: &TraderExchangeWrapper,
     side: Side,
     available_to_withdraw: f64,
     last_price: f64,
 ) -> Result<(), GlowError> {
-    match exchange
-        .open_order(side, available_to_withdraw, last_price)
-        .await
+    let allocation_basis = exchange.get_trading_settings().allocation_basis;
+    let position_units = if allocation_basis == AllocationBasis::QuoteBalance {
+        0.0
+    } else {
+        retry_rate_limited(&HttpRetryPolicy::default(), || {
+            exchange.fetch_base_asset_balance()
+        })
+        .await?
+    };
+    let allocation_base =
+        allocation_basis.get_allocation_base(available_to_withdraw, position_units, last_price);
+    if allocation_base <= 0.0 {
+        let error = format!(
+            "Open order error. side {:?}, nothing to allocate from by {:?} basis",
+            side, allocation_basis
+        );
+        return Err(GlowError::new(String::from("Open Order Error"), error));
+    }
+    let mut last_price = last_price;
+    if exchange.get_trading_settings().use_live_spread {
+        match exchange.fetch_ticker().await {
+            Ok(ticker) => last_price = ticker.get_price_for_side(side),
+            Err(error) => {
+                println!(
+                    "open_order -> fetch_ticker error, using last price {:?}",
+                    error
+                )
+            }
+        }
+    }
+    match retry_rate_limited(&HttpRetryPolicy::default(), || {
+        exchange.open_order(side, allocation_base, last_price)
+    })
+    .await
     {
         Ok(open_order) => Ok(()),
         Err(error) => {
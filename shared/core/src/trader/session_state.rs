@@ -0,0 +1,50 @@
+use common::{
+    enums::balance::Balance,
+    structs::{Execution, Trade},
+};
+use glow_error::GlowError;
+use serde::{Deserialize, Serialize};
+use serde_json::{from_reader, to_writer};
+use std::{
+    fs::{rename, File},
+    io::{BufReader, BufWriter, ErrorKind, Write},
+    path::Path,
+};
+
+/// Essential state of a live session, persisted on every update, so that a session can be
+/// resumed by `Trader::restore` after the process dies.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SessionState {
+    pub current_trade: Option<Trade>,
+    /// executions received before their orders' updates.
+    pub temp_executions: Vec<Execution>,
+    pub balance: Balance,
+    /// start time, in ms, of the last processed bar.
+    pub last_bar_timestamp: Option<i64>,
+}
+
+impl SessionState {
+    /// Loads state persisted at `path`, if any was.
+    pub fn load(path: &Path) -> Result<Option<Self>, GlowError> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+        let session_state = from_reader(BufReader::new(file))?;
+        Ok(Some(session_state))
+    }
+
+    /// Persists state at `path`. It's written to a sibling file first, which then replaces
+    /// `path`, so that a crash while saving doesn't corrupt previously persisted state.
+    pub fn save(&self, path: &Path) -> Result<(), GlowError> {
+        let temp_path = path.with_extension("tmp");
+        {
+            let mut writer = BufWriter::new(File::create(&temp_path)?);
+            to_writer(&mut writer, self)?;
+            writer.flush()?;
+        }
+        rename(&temp_path, path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,1719 @@
+use super::{
+    drop_unfilled_open_units, get_closed_trade_interval_results, get_last_and_previous_indexes,
+    get_last_position_signal, open_order, retry_rate_limited, session_state::SessionState, Trader,
+};
+use crate::benchmark::functions::{simulate_positions, BenchmarkSignals};
+use common::{
+    enums::{
+        allocation_basis::AllocationBasis,
+        balance::Balance,
+        contract_kind::ContractKind,
+        modifiers::{
+            position_lock::PositionLock,
+            price_level::{PriceLevel, TakeProfitLadder},
+        },
+        order_action::OrderAction,
+        order_stage::OrderStage,
+        order_status::OrderStatus,
+        order_type::OrderType,
+        run_mode::RunMode,
+        side::Side,
+        signal_category::SignalCategory,
+        time_in_force::TimeInForce,
+        trade_status::TradeStatus,
+        trading_data_update::TradingDataUpdate,
+    },
+    structs::{
+        BehaviorSubject, EquityPoint, Execution, FeeModel, MockClock, Order, PositionSnapshot,
+        SignalPriority, Trade, TradingSettings,
+    },
+    traits::{exchange::TraderExchange, trade_event_sink::TradeEventSink},
+};
+use exchanges::{
+    bybit::{functions::get_rate_limit_error, BybitTraderExchange},
+    enums::TraderExchangeWrapper,
+    structs::{ApiCredentials, ApiEndpoints, ExchangeConfig, HttpRetryPolicy},
+};
+use glow_error::GlowError;
+use polars::prelude::*;
+use reqwest::{header::HeaderMap, StatusCode};
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    env::temp_dir,
+    fs::remove_file,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    spawn,
+    time::{sleep, timeout},
+};
+use url::Url;
+
+const OPEN_TIMESTAMP: i64 = 1_704_067_200_000;
+
+/// Trade whose 1 unit open order was filled by `executed_units`
+fn get_partially_open_trade(executed_units: f64) -> Trade {
+    let execution = Execution::new(
+        String::from("execution_1"),
+        String::from("open_order_uuid"),
+        OrderType::Limit,
+        1_704_067_200_000,
+        100.0,
+        executed_units,
+        0.0,
+        0.0,
+        true,
+        0.0,
+    );
+    let open_order = Order::new(
+        Some(100.0),
+        0.0,
+        1_704_067_200_000,
+        vec![execution],
+        String::from("BTCUSDT_1704067200000_open"),
+        false,
+        false,
+        1.0,
+        OrderType::Limit,
+        Side::Buy,
+        OrderStatus::PartiallyFilled,
+        None,
+        String::from("BTCUSDT"),
+        None,
+        0.0,
+        TimeInForce::GTC,
+        1.0,
+        1_704_067_200_000,
+        String::from("open_order_uuid"),
+    );
+    Trade::new(open_order, None)
+}
+
+#[test]
+fn test_partially_open_trade_drops_unfilled_remainder() {
+    let trade = get_partially_open_trade(0.3);
+    assert_eq!(trade.status(), TradeStatus::PartiallyOpen);
+    assert!((trade.open_order.get_unfilled_quantity() - 0.7).abs() < 1e-9);
+
+    let trade = drop_unfilled_open_units(&trade).unwrap();
+
+    assert_eq!(trade.open_order.units, 0.3);
+    assert_eq!(trade.open_order.get_unfilled_quantity(), 0.0);
+    assert_eq!(trade.open_order.status, OrderStatus::Filled);
+    assert_eq!(trade.status(), TradeStatus::PendingCloseOrder);
+}
+
+#[test]
+fn test_partially_open_trade_closes_exactly_filled_quantity() {
+    let trade = get_partially_open_trade(0.3);
+
+    let trade = drop_unfilled_open_units(&trade).unwrap();
+    let close_order = trade.new_close_order(OrderType::Market, 100.0).unwrap();
+
+    assert_eq!(close_order.units, 0.3);
+    assert_eq!(close_order.side, Side::Sell);
+}
+
+#[test]
+fn test_position_snapshot_marks_open_units_at_current_price() {
+    let trade = get_partially_open_trade(0.3);
+
+    let snapshot =
+        PositionSnapshot::new(Some(&trade), ContractKind::Linear, 110.0, 1_704_067_260_000);
+
+    assert_eq!(snapshot.side, Side::Buy);
+    assert!((snapshot.units - 0.3).abs() < 1e-9);
+    assert!((snapshot.entry_price - 100.0).abs() < 1e-9);
+    assert!((snapshot.unrealized_pnl - 3.0).abs() < 1e-9);
+    assert!((snapshot.returns - 0.1).abs() < 1e-9);
+
+    let flat_snapshot = PositionSnapshot::new(None, ContractKind::Linear, 110.0, 1_704_067_260_000);
+    assert_eq!(flat_snapshot.side, Side::None);
+    assert_eq!(flat_snapshot.units, 0.0);
+    assert_eq!(flat_snapshot.mark_price, 110.0);
+}
+
+#[test]
+fn test_equity_point_projects_last_trading_data_row() {
+    let trading_data = df!(
+        "start_time" => [1_704_067_200_000_i64, 1_704_067_260_000],
+        "profit_and_loss" => [0.0, 2.5],
+        "balance" => [100.0, 50.0],
+        "position" => [0, 1]
+    )
+    .unwrap()
+    .lazy()
+    .with_column(col("start_time").cast(DataType::Datetime(TimeUnit::Milliseconds, None)))
+    .collect()
+    .unwrap();
+
+    let equity_point = EquityPoint::from_trading_data(&trading_data)
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        equity_point,
+        EquityPoint {
+            timestamp: 1_704_067_260_000,
+            balance: 50.0,
+            unrealized_pnl: 2.5,
+            position: 1,
+        }
+    );
+    let empty_data = trading_data.head(Some(0));
+    assert!(EquityPoint::from_trading_data(&empty_data)
+        .unwrap()
+        .is_none());
+}
+
+const RATE_LIMITED_BODY: &str = r#"{"retCode":10006,"retMsg":"Too many visits!","result":{},"retExtInfo":{},"time":1704067200000}"#;
+
+fn get_test_retry_policy() -> HttpRetryPolicy {
+    HttpRetryPolicy {
+        max_retries: 2,
+        base_delay: Duration::from_millis(1),
+    }
+}
+
+#[tokio::test]
+async fn test_retry_rate_limited_retries_until_operation_succeeds() {
+    let rate_limit_error =
+        get_rate_limit_error(StatusCode::OK, &HeaderMap::new(), RATE_LIMITED_BODY)
+            .expect("retCode 10006 should be parsed as a rate limit");
+    assert!(rate_limit_error.is_rate_limited());
+    let attempts = Cell::new(0);
+
+    let result = retry_rate_limited(&get_test_retry_policy(), || {
+        attempts.set(attempts.get() + 1);
+        let result = if attempts.get() == 1 {
+            Err(rate_limit_error.clone())
+        } else {
+            Ok(true)
+        };
+        async move { result }
+    })
+    .await;
+
+    assert_eq!(result, Ok(true));
+    assert_eq!(attempts.get(), 2);
+}
+
+#[tokio::test]
+async fn test_retry_rate_limited_returns_other_errors_without_retrying() {
+    let attempts = Cell::new(0);
+
+    let result: Result<bool, GlowError> = retry_rate_limited(&get_test_retry_policy(), || {
+        attempts.set(attempts.get() + 1);
+        async { Err(GlowError::new_str("Wrong Response Error", "invalid qty")) }
+    })
+    .await;
+
+    assert_eq!(result.unwrap_err().title, "Wrong Response Error");
+    assert_eq!(attempts.get(), 1);
+    assert!(get_rate_limit_error(StatusCode::OK, &HeaderMap::new(), "{\"retCode\":0}").is_none());
+}
+
+/// Filled 1 unit order, whose single execution at `timestamp` was charged `fee`
+fn get_filled_order(side: Side, price: f64, fee: f64, timestamp: i64, is_close: bool) -> Order {
+    let uuid = format!("{:?}_order_uuid", side);
+    let execution = Execution::new(
+        format!("{:?}_execution", side),
+        uuid.clone(),
+        OrderType::Market,
+        timestamp,
+        price,
+        1.0,
+        fee,
+        0.00055,
+        false,
+        if is_close { 1.0 } else { 0.0 },
+    );
+    Order::new(
+        Some(price),
+        0.0,
+        timestamp,
+        vec![execution],
+        format!("BTCUSDT_{}", timestamp),
+        is_close,
+        false,
+        1.0,
+        OrderType::Market,
+        side,
+        if is_close {
+            OrderStatus::Closed
+        } else {
+            OrderStatus::Filled
+        },
+        None,
+        String::from("BTCUSDT"),
+        None,
+        0.00055,
+        TimeInForce::GTC,
+        1.0,
+        timestamp,
+        uuid,
+    )
+}
+
+#[test]
+fn test_closed_trade_interval_fees_only_count_executions_until_clock_time() {
+    let open_order = get_filled_order(Side::Buy, 100.0, 0.055, OPEN_TIMESTAMP, false);
+    let close_timestamp = OPEN_TIMESTAMP + 90_000;
+    let close_order = get_filled_order(Side::Sell, 110.0, 0.0605, close_timestamp, true);
+    let trade = Trade::new(open_order, Some(close_order));
+    assert_eq!(trade.status(), TradeStatus::Closed);
+
+    // last bar started after open execution, so only close execution is charged on it
+    let bar_start_timestamp = OPEN_TIMESTAMP + 60_000;
+    let clock = MockClock::new(close_timestamp - 1_000);
+    let (fees, _, _) = get_closed_trade_interval_results(
+        &trade,
+        ContractKind::Linear,
+        bar_start_timestamp,
+        &clock,
+        0,
+    );
+    assert_eq!(fees, (0.0, 0.0));
+
+    // local clock lagging behind exchange's is offset by measured skew
+    let ((open_fees, close_fees), _, _) = get_closed_trade_interval_results(
+        &trade,
+        ContractKind::Linear,
+        bar_start_timestamp,
+        &clock,
+        1_000,
+    );
+    assert_eq!(open_fees, 0.0);
+    assert!((close_fees - 0.0605).abs() < 1e-9);
+
+    clock.advance(chrono::Duration::seconds(1));
+    let ((open_fees, close_fees), pnl, returns) = get_closed_trade_interval_results(
+        &trade,
+        ContractKind::Linear,
+        bar_start_timestamp,
+        &clock,
+        0,
+    );
+    assert_eq!(open_fees, 0.0);
+    assert!((close_fees - 0.0605).abs() < 1e-9);
+    assert_eq!(
+        (pnl, returns),
+        trade.calculate_pnl_and_returns(ContractKind::Linear)
+    );
+}
+
+#[test]
+fn test_last_and_previous_indexes_require_two_rows() {
+    let error = get_last_and_previous_indexes(&[Some(OPEN_TIMESTAMP)]).unwrap_err();
+    assert_eq!(error.title, "Insufficient Trading Data");
+    assert!(get_last_and_previous_indexes(&[]).is_err());
+
+    let start_times = [Some(OPEN_TIMESTAMP), Some(OPEN_TIMESTAMP + 60_000)];
+    assert_eq!(get_last_and_previous_indexes(&start_times).unwrap(), (1, 0));
+}
+
+#[test]
+fn test_last_position_signal_resolves_conflicting_signals_by_priority() {
+    let df = df!(
+        SignalCategory::GoLong.get_column() => [0, 1],
+        SignalCategory::GoShort.get_column() => [0, 1],
+        SignalCategory::CloseLong.get_column() => [0, 1],
+        SignalCategory::CloseShort.get_column() => [0, 0]
+    )
+    .unwrap();
+    let default_priority = SignalPriority::default();
+    let closes_first_priority = SignalPriority::new(vec![
+        SignalCategory::CloseLong,
+        SignalCategory::CloseShort,
+        SignalCategory::GoShort,
+        SignalCategory::GoLong,
+    ])
+    .unwrap();
+
+    let signal = |open_side, can_add_to_position, priority| {
+        get_last_position_signal(&df, open_side, can_add_to_position, priority).unwrap()
+    };
+    // without position, longs are preferred by default
+    assert_eq!(
+        signal(None, false, &default_priority),
+        SignalCategory::GoLong
+    );
+    assert_eq!(
+        signal(None, false, &closes_first_priority),
+        SignalCategory::GoShort
+    );
+    // open position is only closed unless it can be added to
+    assert_eq!(
+        signal(Some(Side::Buy), false, &default_priority),
+        SignalCategory::CloseLong
+    );
+    assert_eq!(
+        signal(Some(Side::Buy), true, &default_priority),
+        SignalCategory::GoLong
+    );
+    assert_eq!(
+        signal(Some(Side::Buy), true, &closes_first_priority),
+        SignalCategory::CloseLong
+    );
+    // opposite side open signals never apply to an open position
+    assert_eq!(
+        signal(Some(Side::Sell), true, &default_priority),
+        SignalCategory::GoShort
+    );
+    assert_eq!(
+        signal(Some(Side::Sell), false, &default_priority),
+        SignalCategory::KeepPosition
+    );
+}
+
+const LIQUIDATION_ORDER_WS_MESSAGE: &str = r#"{
+    "topic": "order",
+    "id": "5923240c6880ab-c59f-420b-9adb-3639adc9dd90",
+    "creationTime": 1704067500000,
+    "data": [
+        {
+            "category": "linear",
+            "symbol": "BTCUSDT",
+            "orderId": "liquidation_order_uuid",
+            "orderLinkId": "",
+            "blockTradeId": "",
+            "side": "Sell",
+            "positionIdx": 0,
+            "orderStatus": "Filled",
+            "cancelType": "UNKNOWN",
+            "rejectReason": "EC_NoError",
+            "timeInForce": "IOC",
+            "isLeverage": "",
+            "price": "89.5",
+            "qty": "1",
+            "avgPrice": "90",
+            "leavesQty": "0",
+            "leavesValue": "0",
+            "cumExecQty": "1",
+            "cumExecValue": "90",
+            "cumExecFee": "0.0495",
+            "orderType": "Market",
+            "stopOrderType": "UNKNOWN",
+            "orderIv": "",
+            "triggerPrice": "0.00",
+            "takeProfit": "",
+            "stopLoss": "",
+            "triggerBy": "UNKNOWN",
+            "tpTriggerBy": "UNKNOWN",
+            "slTriggerBy": "UNKNOWN",
+            "triggerDirection": 0,
+            "placeType": "",
+            "lastPriceOnCreated": "90.1",
+            "closeOnTrigger": false,
+            "reduceOnly": true,
+            "smpGroup": 0,
+            "smpType": "None",
+            "smpOrderId": "",
+            "tpslMode": "",
+            "tpLimitPrice": "",
+            "slLimitPrice": "",
+            "createType": "CreateByLiq",
+            "createdTime": "1704067499000",
+            "updatedTime": "1704067500000"
+        }
+    ]
+}"#;
+
+#[tokio::test]
+async fn test_liquidation_order_closes_trade_as_bankruptcy_stop() {
+    let (http_url, _) = serve_bybit_requests(get_bybit_ok_response).await;
+    let trader = get_bybit_trader(http_url, &TradingSettings::default());
+    trader.init_order_update_handler();
+    let process_message = |message: &str| {
+        trader
+            .trader_exchange
+            .process_ws_message(&message.to_string())
+            .unwrap();
+    };
+    let is_stop_emitted = || matches!(trader.order_update_listener.value(), OrderAction::Stop(_));
+
+    // liquidations without a current trade are ignored
+    process_message(LIQUIDATION_ORDER_WS_MESSAGE);
+    assert!(!is_stop_emitted());
+
+    let trade = get_partially_open_trade(1.0);
+    trader.current_trade_listener.next(Some(trade.clone()));
+    // as are the ones not filled yet
+    process_message(
+        &LIQUIDATION_ORDER_WS_MESSAGE
+            .replace(r#""orderStatus": "Filled""#, r#""orderStatus": "New""#),
+    );
+    assert!(!is_stop_emitted());
+
+    // liquidation fill is streamed as an execution of exchange's order
+    let execution = Execution::new(
+        String::from("liquidation_execution"),
+        String::from("liquidation_order_uuid"),
+        OrderType::Market,
+        1_704_067_500_000,
+        90.0,
+        1.0,
+        0.0495,
+        0.00055,
+        false,
+        1.0,
+    );
+    trader.push_to_temp_executions(vec![execution]).unwrap();
+    process_message(LIQUIDATION_ORDER_WS_MESSAGE);
+
+    let OrderAction::Stop(liquidation_order) = trader.order_update_listener.value() else {
+        panic!("liquidation order wasn't emitted as a stop");
+    };
+    assert_eq!(liquidation_order.id, "BTCUSDT_1704067200000_close");
+    assert_eq!(liquidation_order.uuid, "liquidation_order_uuid");
+    assert_eq!(liquidation_order.status, OrderStatus::StoppedBR);
+    assert!(liquidation_order.is_close && liquidation_order.is_stop);
+    wait_until(|| {
+        trader
+            .current_trade_listener
+            .value()
+            .is_some_and(|trade| trade.status() == TradeStatus::Closed)
+    })
+    .await;
+    let trade = trader.current_trade_listener.value().unwrap();
+    assert_eq!(trade.close_order.unwrap().avg_price, Some(90.0));
+}
+
+/// Session state file path unique to `test_name` and current test run
+fn get_session_state_path(test_name: &str) -> PathBuf {
+    temp_dir().join(format!(
+        "glow_session_state_{}_{}.json",
+        test_name,
+        std::process::id()
+    ))
+}
+
+#[test]
+fn test_session_state_is_restored_as_persisted() {
+    let path = get_session_state_path("restored_as_persisted");
+    let _ = remove_file(&path);
+    assert!(SessionState::load(&path).unwrap().is_none());
+
+    let trade = get_partially_open_trade(0.3);
+    let temp_execution = get_filled_order(Side::Sell, 101.0, 0.05, OPEN_TIMESTAMP, true)
+        .executions
+        .remove(0);
+    let session_state = SessionState {
+        current_trade: Some(trade.clone()),
+        temp_executions: vec![temp_execution],
+        balance: Balance::new(OPEN_TIMESTAMP, 90.0, 100.0),
+        last_bar_timestamp: Some(OPEN_TIMESTAMP),
+    };
+    session_state.save(&path).unwrap();
+
+    let restored = SessionState::load(&path).unwrap().unwrap();
+    let restored_trade = restored.current_trade.unwrap();
+    assert_eq!(restored_trade.id, trade.id);
+    assert_eq!(restored_trade.status(), TradeStatus::PartiallyOpen);
+    assert_eq!(restored_trade.open_order.executions.len(), 1);
+    assert_eq!(restored.temp_executions.len(), 1);
+    assert_eq!(restored.temp_executions[0].order_uuid, "Sell_order_uuid");
+    assert_eq!(restored.balance.wallet_balance, 100.0);
+    assert_eq!(restored.last_bar_timestamp, Some(OPEN_TIMESTAMP));
+    let _ = remove_file(&path);
+}
+
+/// Bybit REST request received by `serve_bybit_requests`, whose params are all in its query
+#[derive(Clone, Debug)]
+struct BybitRequest {
+    path: String,
+    params: HashMap<String, String>,
+}
+
+/// Serves Bybit REST requests by `respond`'s (return code, result) pair, recording them in order.
+async fn serve_bybit_requests<F>(respond: F) -> (&'static str, Arc<Mutex<Vec<BybitRequest>>>)
+where
+    F: Fn(&BybitRequest) -> (i32, String) + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(vec![]));
+    let server_requests = requests.clone();
+    spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![];
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            let request = String::from_utf8_lossy(&request).to_string();
+            let target = request.split_whitespace().nth(1).unwrap_or_default();
+            let target = Url::parse(&format!("http://127.0.0.1{}", target)).unwrap();
+            let request = BybitRequest {
+                path: target.path().to_string(),
+                params: target.query_pairs().into_owned().collect(),
+            };
+            let (ret_code, result) = respond(&request);
+            server_requests.lock().unwrap().push(request);
+            let ret_message = if ret_code == 0 { "OK" } else { "Error" };
+            let body = format!(
+                r#"{{"retCode":{},"retMsg":"{}","result":{},"retExtInfo":{{}},"time":{}}}"#,
+                ret_code, ret_message, result, OPEN_TIMESTAMP
+            );
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            stream.shutdown().await.unwrap();
+        }
+    });
+    (Box::leak(url.into_boxed_str()), requests)
+}
+
+/// Successful Bybit responses: tickers touch at 100, orders are acknowledged by their link id.
+fn get_bybit_ok_response(request: &BybitRequest) -> (i32, String) {
+    match request.path.as_str() {
+        "/v5/market/tickers" => (
+            0,
+            format!(
+                r#"{{"list":[{{"symbol":"{}","lastPrice":"100","bid1Price":"100","ask1Price":"100.1"}}]}}"#,
+                request.params["symbol"]
+            ),
+        ),
+        "/v5/order/create" | "/v5/order/cancel" => {
+            let order_link_id = &request.params["orderLinkId"];
+            (
+                0,
+                format!(
+                    r#"{{"orderId":"{}_uuid","orderLinkId":"{}"}}"#,
+                    order_link_id, order_link_id
+                ),
+            )
+        }
+        _ => (0, String::from("{}")),
+    }
+}
+
+/// Live trader over a Bybit exchange served at `http_url`
+fn get_bybit_trader(http_url: &'static str, trading_settings: &TradingSettings) -> Trader {
+    let config = ExchangeConfig {
+        credentials: ApiCredentials {
+            key: "key",
+            secret: "secret",
+        },
+        endpoints: ApiEndpoints {
+            ws: "ws://127.0.0.1",
+            http: http_url,
+        },
+    };
+    let trader_exchange =
+        TraderExchangeWrapper::Bybit(BybitTraderExchange::from_config(trading_settings, config));
+    Trader::new(
+        &BehaviorSubject::new(TradingDataUpdate::default()),
+        trader_exchange,
+        &Arc::new(Mutex::new(DataFrame::default())),
+        &Arc::new(RwLock::new(0)),
+        &Arc::new(RwLock::new(0)),
+        &Arc::new(RwLock::new(SignalPriority::default())),
+        100.0,
+        RunMode::Live,
+    )
+}
+
+/// Trader whose trades close by maker orders falling back to market ones after
+/// `maker_close_timeout`, and which has `trade` open.
+fn get_maker_closing_trader(
+    http_url: &'static str,
+    maker_close_timeout: Duration,
+    trade: &Trade,
+) -> Trader {
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.order_types = (OrderType::Market, OrderType::Limit);
+    trading_settings.position_lock_modifier = PositionLock::None;
+    trading_settings.maker_close_timeout = Some(maker_close_timeout);
+    let trader = get_bybit_trader(http_url, &trading_settings);
+    trader.current_trade_listener.next(Some(trade.clone()));
+    trader
+}
+
+/// Close order update for `trade`'s order `order_id`, which closed `closed_units` of it.
+fn get_close_order_update(trade: &Trade, order_id: String, units: f64, closed_units: f64) -> Order {
+    let order_uuid = format!("{}_uuid", order_id);
+    let executions = vec![Execution::new(
+        format!("{}_execution", order_id),
+        order_uuid.clone(),
+        OrderType::Limit,
+        OPEN_TIMESTAMP,
+        100.0,
+        closed_units,
+        0.0,
+        0.0,
+        true,
+        closed_units,
+    )];
+    Order::new(
+        Some(100.0),
+        0.0,
+        OPEN_TIMESTAMP,
+        executions,
+        order_id,
+        true,
+        false,
+        1.0,
+        OrderType::Limit,
+        Side::Sell,
+        OrderStatus::PartiallyClosed,
+        None,
+        trade.open_order.symbol.clone(),
+        None,
+        0.0,
+        TimeInForce::GTC,
+        units,
+        OPEN_TIMESTAMP,
+        order_uuid,
+    )
+}
+
+/// Polls `condition` until it holds, failing after a second.
+async fn wait_until(condition: impl Fn() -> bool) {
+    timeout(Duration::from_secs(1), async {
+        while !condition() {
+            sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("condition to hold in time");
+}
+
+fn count_requests(requests: &Arc<Mutex<Vec<BybitRequest>>>, path: &str) -> usize {
+    requests
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|request| request.path == path)
+        .count()
+}
+
+#[tokio::test]
+async fn test_maker_close_filled_before_timeout_isnt_fallen_back() {
+    let (http_url, requests) = serve_bybit_requests(get_bybit_ok_response).await;
+    let trade = drop_unfilled_open_units(&get_partially_open_trade(1.0)).unwrap();
+    let trader = get_maker_closing_trader(http_url, Duration::from_secs(5), &trade);
+    trader.init_order_update_handler();
+
+    let close_trader = trader.clone();
+    let close_trade = trade.clone();
+    let close_handle = spawn(async move {
+        close_trader
+            .trader_exchange
+            .try_close_position(&close_trade, 100.0, true)
+            .await
+    });
+    wait_until(|| count_requests(&requests, "/v5/order/create") == 1).await;
+    let maker_close_order_id = trade.get_order_id(OrderStage::Close);
+    trader
+        .order_update_listener
+        .next(OrderAction::Update(get_close_order_update(
+            &trade,
+            maker_close_order_id.clone(),
+            1.0,
+            1.0,
+        )));
+
+    let maker_close_order = timeout(Duration::from_secs(1), close_handle)
+        .await
+        .expect("maker close to resolve once filled")
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(maker_close_order.id, maker_close_order_id);
+    assert_eq!(maker_close_order.order_type, OrderType::Limit);
+    assert_eq!(count_requests(&requests, "/v5/order/cancel"), 0);
+    let trade = trader.current_trade_listener.value().unwrap();
+    assert_eq!(trade.status(), TradeStatus::Closed);
+}
+
+#[tokio::test]
+async fn test_maker_close_falls_back_to_market_close_superseding_it() {
+    let (http_url, requests) = serve_bybit_requests(get_bybit_ok_response).await;
+    let trade = drop_unfilled_open_units(&get_partially_open_trade(1.0)).unwrap();
+    let trader = get_maker_closing_trader(http_url, Duration::from_millis(200), &trade);
+    trader.init_order_update_handler();
+
+    let close_trader = trader.clone();
+    let close_trade = trade.clone();
+    let close_handle = spawn(async move {
+        close_trader
+            .trader_exchange
+            .try_close_position(&close_trade, 100.0, true)
+            .await
+    });
+    wait_until(|| count_requests(&requests, "/v5/order/create") == 1).await;
+    // maker close order is partially filled before timing out
+    let maker_close_order_id = trade.get_order_id(OrderStage::Close);
+    trader
+        .order_update_listener
+        .next(OrderAction::Update(get_close_order_update(
+            &trade,
+            maker_close_order_id.clone(),
+            1.0,
+            0.4,
+        )));
+
+    let market_close_order = close_handle.await.unwrap().unwrap();
+
+    let market_close_order_id = trade.get_order_id(OrderStage::FallbackClose);
+    assert_eq!(market_close_order.id, market_close_order_id);
+    assert_eq!(market_close_order.order_type, OrderType::Market);
+    {
+        let requests = requests.lock().unwrap();
+        let paths: Vec<&str> = requests
+            .iter()
+            .map(|request| request.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/v5/market/tickers",
+                "/v5/order/create",
+                "/v5/order/cancel",
+                "/v5/order/create"
+            ]
+        );
+        assert_eq!(requests[2].params["orderLinkId"], maker_close_order_id);
+        assert_eq!(requests[3].params["orderLinkId"], market_close_order_id);
+        assert_eq!(requests[3].params["orderType"], "Market");
+        assert_eq!(requests[3].params["qty"].parse::<f64>().unwrap(), 0.6);
+    }
+    wait_until(|| {
+        trader
+            .current_trade_listener
+            .value()
+            .and_then(|trade| trade.close_order)
+            .is_some_and(|close_order| close_order.id == market_close_order_id)
+    })
+    .await;
+
+    trader
+        .order_update_listener
+        .next(OrderAction::Update(get_close_order_update(
+            &trade,
+            market_close_order_id.clone(),
+            0.6,
+            0.6,
+        )));
+    wait_until(|| {
+        trader
+            .current_trade_listener
+            .value()
+            .is_some_and(|trade| trade.status() == TradeStatus::Closed)
+    })
+    .await;
+    let close_order = trader
+        .current_trade_listener
+        .value()
+        .unwrap()
+        .close_order
+        .unwrap();
+    assert_eq!(close_order.executions.len(), 2);
+    assert_eq!(close_order.get_closed_quanitity(), 1.0);
+}
+
+#[tokio::test]
+async fn test_maker_close_cancel_failure_is_returned() {
+    let (http_url, requests) = serve_bybit_requests(|request| match request.path.as_str() {
+        "/v5/order/cancel" => (10001, String::from("{}")),
+        _ => get_bybit_ok_response(request),
+    })
+    .await;
+    let trade = drop_unfilled_open_units(&get_partially_open_trade(1.0)).unwrap();
+    let trader = get_maker_closing_trader(http_url, Duration::from_millis(50), &trade);
+
+    let error = trader
+        .trader_exchange
+        .try_close_position(&trade, 100.0, true)
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.title, "Close Position Error");
+    assert_eq!(count_requests(&requests, "/v5/order/cancel"), 1);
+    assert_eq!(count_requests(&requests, "/v5/order/create"), 1);
+}
+
+#[tokio::test]
+async fn test_failed_market_close_fallback_flattens_trade_position() {
+    let (http_url, requests) = serve_bybit_requests(|request| match request.path.as_str() {
+        "/v5/order/create" if request.params["orderLinkId"].ends_with("fallback_close") => {
+            (10001, String::from("{}"))
+        }
+        "/v5/order/cancel-all" => (0, String::from(r#"{"list":[]}"#)),
+        "/v5/position/list" => (
+            0,
+            format!(
+                r#"{{"list":[{{"positionIdx":0,"riskId":1,"riskLimitValue":"2000000","symbol":"{}","side":"Buy","size":"1","avgPrice":"100","positionValue":"100","tradeMode":0,"positionStatus":"Normal","autoAddMargin":0,"adlRankIndicator":2,"leverage":"1","positionBalance":"100","markPrice":"100","liqPrice":"","bustPrice":"0.1","positionMM":"1","positionIM":"100","tpslMode":"Full","takeProfit":"0","stopLoss":"0","trailingStop":"0","unrealisedPnl":"0","cumRealisedPnl":"0","createdTime":"1704067200000","updatedTime":"1704067200000"}}]}}"#,
+                request.params["symbol"]
+            ),
+        ),
+        _ => get_bybit_ok_response(request),
+    })
+    .await;
+    let trade = drop_unfilled_open_units(&get_partially_open_trade(1.0)).unwrap();
+    let trader = get_maker_closing_trader(http_url, Duration::from_millis(50), &trade);
+
+    let close_order = trader
+        .trader_exchange
+        .try_close_position(&trade, 100.0, true)
+        .await
+        .unwrap();
+
+    assert_eq!(close_order.id, trade.get_order_id(OrderStage::Close));
+    let requests = requests.lock().unwrap();
+    let flatten_request = requests.last().unwrap();
+    assert_eq!(flatten_request.path, "/v5/order/create");
+    assert_eq!(
+        flatten_request.params["orderLinkId"],
+        trade.get_order_id(OrderStage::Flatten)
+    );
+    assert_eq!(flatten_request.params["reduceOnly"], "true");
+}
+
+/// Same side open order adding 2 units to trade's position, executed by `executions`.
+fn get_position_add_order(status: OrderStatus, executions: Vec<(f64, f64)>) -> Order {
+    let executions = executions
+        .into_iter()
+        .enumerate()
+        .map(|(index, (price, qty))| {
+            Execution::new(
+                format!("add_execution_{}", index),
+                String::from("add_order_uuid"),
+                OrderType::Limit,
+                OPEN_TIMESTAMP,
+                price,
+                qty,
+                0.0,
+                0.0,
+                true,
+                0.0,
+            )
+        })
+        .collect();
+    Order::new(
+        None,
+        0.0,
+        OPEN_TIMESTAMP,
+        executions,
+        String::from("BTCUSDT_1704067260000_open"),
+        false,
+        false,
+        1.0,
+        OrderType::Limit,
+        Side::Buy,
+        status,
+        None,
+        String::from("BTCUSDT"),
+        None,
+        0.0,
+        TimeInForce::GTC,
+        2.0,
+        OPEN_TIMESTAMP,
+        String::from("add_order_uuid"),
+    )
+}
+
+#[tokio::test]
+async fn test_partially_filled_then_cancelled_add_merges_executed_quantity() {
+    let (http_url, _) = serve_bybit_requests(get_bybit_ok_response).await;
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.max_pyramid_adds = 1;
+    let trader = get_bybit_trader(http_url, &trading_settings);
+    let trade = drop_unfilled_open_units(&get_partially_open_trade(1.0)).unwrap();
+    trader.current_trade_listener.next(Some(trade));
+    trader.init_order_update_handler();
+    let open_units = |trader: &Trader| {
+        let trade = trader.current_trade_listener.value().unwrap();
+        trade.open_order.units
+    };
+
+    trader
+        .order_update_listener
+        .next(OrderAction::Update(get_position_add_order(
+            OrderStatus::PartiallyFilled,
+            vec![(200.0, 0.5)],
+        )));
+    wait_until(|| open_units(&trader) == 1.5).await;
+
+    // remainder is cancelled after another partial fill
+    trader
+        .order_update_listener
+        .next(OrderAction::Cancel(get_position_add_order(
+            OrderStatus::Cancelled,
+            vec![(200.0, 0.5), (300.0, 0.25)],
+        )));
+    wait_until(|| open_units(&trader) == 1.75).await;
+
+    let trade = trader.current_trade_listener.value().unwrap();
+    assert_eq!(trade.open_order.executions.len(), 3);
+    let expected_entry_price = (100.0 + 200.0 * 0.5 + 300.0 * 0.25) / 1.75;
+    assert!((trade.open_order.avg_price.unwrap() - expected_entry_price).abs() < 1e-9);
+    assert_eq!(trade.status(), TradeStatus::PendingCloseOrder);
+}
+
+/// Take profit fill of `trade` position, closing `closed_units` by one execution each.
+fn get_take_profit_fill(trade: &Trade, closed_units: &[f64]) -> Order {
+    let order_id = trade.get_order_id(OrderStage::Close);
+    let order_uuid = format!("{}_uuid", order_id);
+    let executions = closed_units
+        .iter()
+        .enumerate()
+        .map(|(index, &units)| {
+            Execution::new(
+                format!("take_profit_execution_{}", index),
+                order_uuid.clone(),
+                OrderType::Limit,
+                OPEN_TIMESTAMP,
+                100.0,
+                units,
+                0.0,
+                0.0,
+                true,
+                units,
+            )
+        })
+        .collect();
+    Order::new(
+        Some(100.0),
+        0.0,
+        OPEN_TIMESTAMP,
+        executions,
+        order_id,
+        true,
+        false,
+        1.0,
+        OrderType::Limit,
+        Side::Sell,
+        OrderStatus::PartiallyClosed,
+        None,
+        trade.open_order.symbol.clone(),
+        None,
+        0.0,
+        TimeInForce::GTC,
+        1.0,
+        OPEN_TIMESTAMP,
+        order_uuid,
+    )
+}
+
+#[tokio::test]
+async fn test_take_profit_ladder_advances_a_level_per_partial_close_fill() {
+    let (http_url, requests) = serve_bybit_requests(get_bybit_ok_response).await;
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.price_level_modifier_map.insert(
+        String::from("tp"),
+        PriceLevel::TakeProfitLadder(TakeProfitLadder(vec![(0.1, 0.5), (0.2, 0.25), (0.3, 0.25)])),
+    );
+    let trader = get_bybit_trader(http_url, &trading_settings);
+    trader.init_order_update_handler();
+    trader.init_trade_update_handler();
+    let trade = drop_unfilled_open_units(&get_partially_open_trade(1.0)).unwrap();
+    let count_take_profits = || count_requests(&requests, "/v5/position/trading-stop");
+
+    trader.current_trade_listener.next(Some(trade.clone()));
+    wait_until(|| count_take_profits() == 1).await;
+    trader
+        .order_update_listener
+        .next(OrderAction::Update(get_take_profit_fill(&trade, &[0.5])));
+    wait_until(|| count_take_profits() == 2).await;
+    trader
+        .order_update_listener
+        .next(OrderAction::Update(get_take_profit_fill(
+            &trade,
+            &[0.5, 0.25],
+        )));
+    wait_until(|| count_take_profits() == 3).await;
+
+    let take_profits: Vec<(f64, f64)> = requests
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|request| request.path == "/v5/position/trading-stop")
+        .map(|request| {
+            (
+                request.params["takeProfit"].parse().unwrap(),
+                request.params["tpSize"].parse().unwrap(),
+            )
+        })
+        .collect();
+    // each level scales out its share of the initial position, last one whatever remains
+    assert_eq!(
+        take_profits,
+        vec![(110.0, 0.5), (120.0, 0.25), (130.0, 0.25)]
+    );
+    let trade = trader.current_trade_listener.value().unwrap();
+    assert_eq!(trade.status(), TradeStatus::PartiallyClosed);
+    // repeated fill doesn't advance past last level
+    trader
+        .order_update_listener
+        .next(OrderAction::Update(get_take_profit_fill(
+            &trade,
+            &[0.5, 0.25],
+        )));
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(count_take_profits(), 3);
+}
+
+/// Bybit closing execution of 1 unit at `price` by an order placed out-of-band
+fn get_bybit_closing_execution_json(price: &str) -> String {
+    format!(
+        r#"{{"blockTradeId":"","category":"linear","execFee":"0.0588","execId":"manual_close_execution","execPrice":"{}","execQty":"1","execTime":"1704067260000","execType":"Trade","execValue":"{}","feeRate":"0.0006","indexPrice":"0","isLeverage":"","isMaker":false,"leavesQty":"0","markIv":"","markPrice":"{}","orderId":"manual_close_uuid","orderLinkId":"","orderPrice":"{}","orderQty":"1","orderType":"Market","symbol":"BTCUSDT","stopOrderType":"UNKNOWN","side":"Sell","tradeIv":"","underlyingPrice":"","closedSize":"1"}}"#,
+        price, price, price, price
+    )
+}
+
+/// Closes trade partially opened at 100 by a reduce-only order rejected as position is zero,
+/// with `executions` (json list) listed by exchange, returning the trader and its requests.
+async fn close_flat_trade(executions: String) -> (Trader, Arc<Mutex<Vec<BybitRequest>>>) {
+    let (http_url, requests) = serve_bybit_requests(move |request| match request.path.as_str() {
+        "/v5/order/create" => (110017, String::from("{}")),
+        "/v5/execution/list" => (0, format!(r#"{{"list":[{}]}}"#, executions)),
+        _ => get_bybit_ok_response(request),
+    })
+    .await;
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.position_lock_modifier = PositionLock::None;
+    let trader = get_bybit_trader(http_url, &trading_settings);
+    let trade = drop_unfilled_open_units(&get_partially_open_trade(1.0)).unwrap();
+    trader.current_trade_listener.next(Some(trade.clone()));
+    trader.init_order_update_handler();
+
+    let close_order = trader
+        .trader_exchange
+        .try_close_position(&trade, 105.0, true)
+        .await
+        .unwrap();
+
+    assert_eq!(close_order.status, OrderStatus::Closed);
+    assert_eq!(close_order.get_closed_quanitity(), 1.0);
+    {
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].path, "/v5/order/create");
+        assert_eq!(requests[0].params["reduceOnly"], "true");
+        assert_eq!(requests[1].path, "/v5/execution/list");
+        assert_eq!(requests[1].params["startTime"], "1704067200000");
+        assert!(!requests[1].params.contains_key("orderId"));
+    }
+    // order update doesn't turn close order back to stand by, as it has no executions of its own
+    wait_until(|| {
+        trader
+            .current_trade_listener
+            .value()
+            .is_some_and(|trade| trade.status() == TradeStatus::Closed)
+    })
+    .await;
+    (trader, requests)
+}
+
+#[tokio::test]
+async fn test_reduce_only_close_without_position_is_closed_by_actual_closing_executions() {
+    let (trader, _) = close_flat_trade(get_bybit_closing_execution_json("98")).await;
+
+    let closed_trade = trader.current_trade_listener.value().unwrap();
+    assert!(!closed_trade.is_closed_out_of_band());
+    let close_order = closed_trade.close_order.clone().unwrap();
+    assert_eq!(close_order.get_executed_avg_price(), 98.0);
+    assert_eq!(close_order.updated_at, 1_704_067_260_000);
+    // position was actually closed at 98 paying its fee, rather than at estimated 105
+    let (pnl, _) = closed_trade.calculate_pnl_and_returns(ContractKind::Linear);
+    assert!((pnl - (98.0 - 100.0 - 0.0588)).abs() < 1e-9);
+    trader
+        .on_close_trade_check_loss_limits(&closed_trade)
+        .await
+        .unwrap();
+    let circuit_breaker = trader.loss_circuit_breaker.lock().unwrap();
+    assert_eq!(circuit_breaker.consecutive_losses, 1);
+    assert!((circuit_breaker.daily_pnl - pnl).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_reduce_only_close_without_position_is_flagged_out_of_band_without_executions() {
+    let (trader, _) = close_flat_trade(String::new()).await;
+
+    let closed_trade = trader.current_trade_listener.value().unwrap();
+    assert!(closed_trade.is_closed_out_of_band());
+    // close order is kept at the price it was posted at, for reference only
+    let close_price = closed_trade
+        .close_order
+        .clone()
+        .unwrap()
+        .get_executed_avg_price();
+    assert!((close_price - 105.0).abs() < 0.01);
+    // estimated close isn't made up into realized pnl, nor into loss limits
+    let (pnl, _) = closed_trade.calculate_pnl_and_returns(ContractKind::Linear);
+    assert_eq!(pnl, 0.0);
+    trader
+        .on_close_trade_check_loss_limits(&closed_trade)
+        .await
+        .unwrap();
+    let circuit_breaker = trader.loss_circuit_breaker.lock().unwrap();
+    assert_eq!(circuit_breaker.consecutive_losses, 0);
+    assert_eq!(circuit_breaker.day, None);
+}
+
+fn get_start_clean_trading_settings() -> TradingSettings {
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.start_clean = true;
+    trading_settings
+}
+
+#[tokio::test]
+async fn test_start_clean_cancels_orders_and_flattens_positions_before_init_returns() {
+    let (http_url, requests) = serve_bybit_requests(|request| match request.path.as_str() {
+        "/v5/order/cancel-all" | "/v5/position/list" => (0, String::from(r#"{"list":[]}"#)),
+        _ => get_bybit_ok_response(request),
+    })
+    .await;
+    let trader = get_bybit_trader(http_url, &get_start_clean_trading_settings());
+
+    trader.init().await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    let paths: Vec<&str> = requests
+        .iter()
+        .take(3)
+        .map(|request| request.path.as_str())
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            "/v5/order/cancel-all",
+            "/v5/order/cancel-all",
+            "/v5/position/list"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_start_clean_failure_fails_init() {
+    let (http_url, requests) = serve_bybit_requests(|request| match request.path.as_str() {
+        "/v5/order/cancel-all" => (10001, String::from(r#"{"list":[]}"#)),
+        _ => get_bybit_ok_response(request),
+    })
+    .await;
+    let trader = get_bybit_trader(http_url, &get_start_clean_trading_settings());
+
+    let error = trader.init().await.unwrap_err();
+
+    assert_eq!(error.title, "Cancel Orders Error");
+    sleep(Duration::from_millis(50)).await;
+    // no handler was initialized to query exchange
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+/// Bybit contract account wallet holding 1,000 USDT and 2 BTC
+fn get_bybit_wallet_balance_response(request: &BybitRequest) -> (i32, String) {
+    if request.path != "/v5/account/wallet-balance" {
+        return get_bybit_ok_response(request);
+    }
+    let (coin, wallet_balance) = match request.params["coin"].as_str() {
+        "USDT" => ("USDT", "1000"),
+        "BTC" => ("BTC", "2"),
+        _ => return (0, String::from(r#"{"list":[]}"#)),
+    };
+    (
+        0,
+        format!(
+            r#"{{"list":[{{"accountType":"CONTRACT","accountIMRate":"","accountMMRate":"","accountLTV":"","totalEquity":"","totalWalletBalance":"","totalMarginBalance":"","totalAvailableBalance":"","totalPerpUPL":"","totalInitialMargin":"","totalMaintenanceMargin":"","coin":[{{"coin":"{}","equity":"{}","usdValue":"","walletBalance":"{}","availableToWithdraw":"{}","borrowAmount":"","availableToBorrow":"","accruedInterest":"","totalOrderIM":"0","totalPositionIM":"0","totalPositionMM":"","unrealisedPnl":"0","cumRealisedPnl":"0"}}]}}]}}"#,
+            coin, wallet_balance, wallet_balance, wallet_balance
+        ),
+    )
+}
+
+fn get_bybit_empty_wallet_response(request: &BybitRequest) -> (i32, String) {
+    let (ret_code, body) = get_bybit_wallet_balance_response(request);
+    (ret_code, body.replace(r#""1000""#, r#""0""#))
+}
+
+#[tokio::test]
+async fn test_recycled_idle_order_is_only_reopened_off_a_usable_balance() {
+    let cases: [(fn(&BybitRequest) -> (i32, String), usize); 2] = [
+        (get_bybit_empty_wallet_response, 0),
+        (get_bybit_wallet_balance_response, 1),
+    ];
+    for (respond, expected_opens) in cases {
+        let (http_url, requests) = serve_bybit_requests(respond).await;
+        let trader = get_bybit_trader(http_url, &TradingSettings::default());
+        let close_col = TradingSettings::default()
+            .get_traded_symbol()
+            .get_close_col();
+        *trader.trading_data.lock().unwrap() = df!(close_col => [100.0]).unwrap();
+        // long order without executions, while balance wasn't fetched yet
+        let open_order = Order {
+            executions: vec![],
+            status: OrderStatus::StandBy,
+            ..get_partially_open_trade(1.0).open_order
+        };
+        let trade = Trade::new(open_order, None);
+        assert_eq!(trade.status(), TradeStatus::New);
+        trader.current_trade_listener.next(Some(trade));
+
+        trader
+            .process_last_signal(SignalCategory::GoShort)
+            .await
+            .unwrap();
+
+        assert_eq!(count_requests(&requests, "/v5/order/cancel"), 1);
+        assert_eq!(
+            count_requests(&requests, "/v5/order/create"),
+            expected_opens
+        );
+        if expected_opens > 0 {
+            let requests = requests.lock().unwrap();
+            let create_request = requests
+                .iter()
+                .find(|request| request.path == "/v5/order/create")
+                .unwrap();
+            assert_eq!(create_request.params["side"], "Sell");
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_open_order_is_sized_off_each_allocation_basis() {
+    // 1,000 USDT available and 2 BTC held, valued at 10,000 USDT each
+    let cases = [
+        (AllocationBasis::QuoteBalance, 1_000.0, 0),
+        (AllocationBasis::BasePosition, 20_000.0, 1),
+        (AllocationBasis::TotalEquity, 21_000.0, 1),
+    ];
+    let mut sizes = vec![];
+    for (allocation_basis, allocation_base, wallet_requests) in cases {
+        let (http_url, requests) = serve_bybit_requests(get_bybit_wallet_balance_response).await;
+        let mut trading_settings = TradingSettings::default();
+        trading_settings.allocation_basis = allocation_basis;
+        let trader = get_bybit_trader(http_url, &trading_settings);
+
+        open_order(&trader.trader_exchange, Side::Buy, 1_000.0, 10_000.0)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            count_requests(&requests, "/v5/account/wallet-balance"),
+            wallet_requests,
+            "{:?}",
+            allocation_basis
+        );
+        let requests = requests.lock().unwrap();
+        let create_order = requests
+            .iter()
+            .find(|request| request.path == "/v5/order/create")
+            .expect("open order to be created");
+        if wallet_requests > 0 {
+            assert_eq!(requests[0].params["coin"], "BTC");
+        }
+        let units: f64 = create_order.params["qty"].parse().unwrap();
+        sizes.push((allocation_base, units));
+    }
+
+    let (quote_base, quote_units) = sizes[0];
+    for (allocation_base, units) in sizes {
+        let expected_units = quote_units * allocation_base / quote_base;
+        // units are rounded down to contract's quantity step
+        assert!(
+            (units / expected_units - 1.0).abs() < 1e-3,
+            "{} units, expected {}",
+            units,
+            expected_units
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_open_order_fails_without_base_asset_to_allocate_from() {
+    let (http_url, requests) = serve_bybit_requests(|request| {
+        if request.path == "/v5/account/wallet-balance" {
+            return (0, String::from(r#"{"list":[]}"#));
+        }
+        get_bybit_ok_response(request)
+    })
+    .await;
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.allocation_basis = AllocationBasis::BasePosition;
+    let trader = get_bybit_trader(http_url, &trading_settings);
+
+    let error = open_order(&trader.trader_exchange, Side::Buy, 1_000.0, 100.0)
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.title, "Open Order Error");
+    assert_eq!(count_requests(&requests, "/v5/order/create"), 0);
+}
+
+fn get_bars_df(start_times: Vec<i64>) -> DataFrame {
+    df!("start_time" => start_times)
+        .unwrap()
+        .lazy()
+        .with_column(col("start_time").cast(DataType::Datetime(TimeUnit::Milliseconds, None)))
+        .collect()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_restore_skips_bars_processed_by_persisted_session() {
+    let (http_url, _) = serve_bybit_requests(get_bybit_ok_response).await;
+    let path = get_session_state_path("skips_processed_bars");
+    let _ = remove_file(&path);
+    let next_bar_timestamp = OPEN_TIMESTAMP + 60_000;
+
+    let first_run_balance = Balance::new(OPEN_TIMESTAMP, 50.0, 60.0);
+    let trader = get_bybit_trader(http_url, &TradingSettings::default());
+    trader
+        .current_balance_listener
+        .next(first_run_balance.clone());
+    let trader = trader.restore(&path).await.unwrap();
+    // nothing was persisted, so balance isn't reset
+    assert_eq!(trader.current_balance_listener.value().wallet_balance, 60.0);
+    assert!(!trader
+        .is_processed_by_restored_session(&get_bars_df(vec![OPEN_TIMESTAMP]))
+        .unwrap());
+
+    SessionState {
+        current_trade: None,
+        temp_executions: vec![],
+        balance: Balance::new(OPEN_TIMESTAMP, 90.0, 100.0),
+        last_bar_timestamp: Some(OPEN_TIMESTAMP),
+    }
+    .save(&path)
+    .unwrap();
+    let trader = get_bybit_trader(http_url, &TradingSettings::default())
+        .restore(&path)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        trader.current_balance_listener.value().wallet_balance,
+        100.0
+    );
+    assert!(trader
+        .is_processed_by_restored_session(&get_bars_df(vec![
+            OPEN_TIMESTAMP - 60_000,
+            OPEN_TIMESTAMP
+        ]))
+        .unwrap());
+    assert!(!trader
+        .is_processed_by_restored_session(&get_bars_df(vec![OPEN_TIMESTAMP, next_bar_timestamp]))
+        .unwrap());
+    let _ = remove_file(&path);
+}
+
+/// Records trades closed by trader
+#[derive(Default)]
+struct ClosedTradesSink(Mutex<Vec<Trade>>);
+
+impl TradeEventSink for ClosedTradesSink {
+    fn on_close(&self, trade: &Trade) {
+        self.0.lock().unwrap().push(trade.clone());
+    }
+}
+
+/// Update of market order created by Bybit `request`, fully filled at `price` by `timestamp`.
+fn get_created_order_fill(request: &BybitRequest, price: f64, timestamp: i64) -> Order {
+    let order_id = request.params["orderLinkId"].clone();
+    let order_uuid = format!("{}_uuid", order_id);
+    let units: f64 = request.params["qty"].parse().unwrap();
+    let is_close = request.params["reduceOnly"] == "true";
+    let side = if request.params["side"] == "Sell" {
+        Side::Sell
+    } else {
+        Side::Buy
+    };
+    let execution = Execution::new(
+        format!("{}_execution", order_id),
+        order_uuid.clone(),
+        OrderType::Market,
+        timestamp,
+        price,
+        units,
+        0.0,
+        0.0,
+        false,
+        if is_close { units } else { 0.0 },
+    );
+    Order::new(
+        Some(price),
+        0.0,
+        timestamp,
+        vec![execution],
+        order_id,
+        is_close,
+        false,
+        1.0,
+        OrderType::Market,
+        side,
+        if is_close {
+            OrderStatus::Closed
+        } else {
+            OrderStatus::Filled
+        },
+        None,
+        request.params["symbol"].clone(),
+        None,
+        0.0,
+        TimeInForce::GTC,
+        units,
+        timestamp,
+        order_uuid,
+    )
+}
+
+/// Benchmark columns of trader's trading data
+fn get_benchmark_columns(trader: &Trader) -> DataFrame {
+    trader
+        .trading_data
+        .lock()
+        .unwrap()
+        .select([
+            "start_time",
+            "balance",
+            "profit_and_loss",
+            "position",
+            "action",
+        ])
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_initial_strategy_data_resumes_benchmark_over_appended_bars() {
+    let (http_url, _) = serve_bybit_requests(get_bybit_ok_response).await;
+    let trading_settings = TradingSettings::default();
+    let traded_symbol = trading_settings.get_traded_symbol();
+    let (open_col, high_col, low_col, close_col) = traded_symbol.get_ohlc_cols();
+    let prices = vec![
+        100.0, 101.0, 103.0, 102.0, 105.0, 104.0, 106.0, 103.0, 99.0, 100.0,
+    ];
+    let bars = prices.len();
+    let signal_values = |indexes: &[usize]| -> Vec<i32> {
+        (0..bars)
+            .map(|index| indexes.contains(&index) as i32)
+            .collect()
+    };
+    let bars_df = df!(
+        "start_time" => (0..bars as i64).map(|index| OPEN_TIMESTAMP + index * 60_000).collect::<Vec<_>>(),
+        open_col => prices.clone(),
+        high_col => prices.clone(),
+        low_col => prices.clone(),
+        close_col => prices,
+        SignalCategory::GoLong.get_column() => signal_values(&[0]),
+        SignalCategory::CloseLong.get_column() => signal_values(&[4]),
+        SignalCategory::GoShort.get_column() => signal_values(&[5]),
+        SignalCategory::CloseShort.get_column() => signal_values(&[8])
+    )
+    .unwrap()
+    .lazy()
+    .with_column(col("start_time").cast(DataType::Datetime(TimeUnit::Milliseconds, None)))
+    .collect()
+    .unwrap();
+    let full_run_trader = get_bybit_trader(http_url, &trading_settings);
+    full_run_trader
+        .handle_initial_strategy_data(bars_df.clone())
+        .unwrap();
+    let trader = get_bybit_trader(http_url, &trading_settings);
+
+    // each open trade is checkpointed, then resumed over bars appended since
+    for bars in [3, 7, bars] {
+        trader
+            .handle_initial_strategy_data(bars_df.head(Some(bars)))
+            .unwrap();
+    }
+
+    assert_eq!(
+        trader
+            .benchmark_checkpoint
+            .lock()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .get_processed_bars(),
+        bars
+    );
+    let positions = get_benchmark_columns(&trader)
+        .column("position")
+        .unwrap()
+        .i32()
+        .unwrap()
+        .into_no_null_iter()
+        .collect::<Vec<_>>();
+    assert_eq!(positions, vec![0, 1, 1, 1, 1, 0, -1, -1, -1, 0]);
+    assert!(get_benchmark_columns(&trader).frame_equal(&get_benchmark_columns(&full_run_trader)));
+
+    // frames not extending checkpointed bars, e.g. rolled over ones, are fully recomputed
+    let rolled_df = bars_df.slice(1, bars - 1);
+    trader
+        .handle_initial_strategy_data(rolled_df.clone())
+        .unwrap();
+    full_run_trader.clear_benchmark_checkpoint();
+    full_run_trader
+        .handle_initial_strategy_data(rolled_df)
+        .unwrap();
+    assert!(get_benchmark_columns(&trader).frame_equal(&get_benchmark_columns(&full_run_trader)));
+}
+
+/// Row `index` of `bars_df`, with the columns of `trading_data` it lacks set to null.
+fn get_bar_row(bars_df: &DataFrame, index: usize, trading_data: &DataFrame) -> DataFrame {
+    let bar = bars_df.slice(index as i64, 1);
+    let columns = trading_data
+        .get_columns()
+        .iter()
+        .map(|column| match bar.column(column.name()) {
+            Ok(bar_column) => bar_column.cast(column.dtype()).unwrap(),
+            Err(_) => Series::full_null(column.name(), 1, column.dtype()),
+        })
+        .collect();
+    DataFrame::new(columns).unwrap()
+}
+
+#[tokio::test]
+async fn test_live_trader_acts_upon_signals_as_benchmark_simulates() {
+    let (http_url, requests) = serve_bybit_requests(get_bybit_wallet_balance_response).await;
+    let mut trading_settings = TradingSettings::default();
+    trading_settings.position_lock_modifier = PositionLock::None;
+    trading_settings.benchmark_fee_override = Some(FeeModel::new(0.0, 0.0));
+    let closed_trades = Arc::new(ClosedTradesSink::default());
+    let trader =
+        get_bybit_trader(http_url, &trading_settings).with_trade_event_sink(closed_trades.clone());
+    trader.init_order_update_handler();
+    trader.init_trade_update_handler();
+
+    let closes = vec![
+        100.0, 100.0, 104.0, 108.0, 106.0, 103.0, 99.0, 95.0, 97.0, 101.0, 98.0, 94.0, 92.0,
+    ];
+    let bars = closes.len();
+    // bars open at previous close, which live orders are sized off
+    let opens: Vec<f64> = (0..bars)
+        .map(|index| closes[index.saturating_sub(1)])
+        .collect();
+    let highs: Vec<f64> = (0..bars)
+        .map(|index| opens[index].max(closes[index]))
+        .collect();
+    let lows: Vec<f64> = (0..bars)
+        .map(|index| opens[index].min(closes[index]))
+        .collect();
+    let start_times: Vec<i64> = (0..bars as i64)
+        .map(|index| OPEN_TIMESTAMP + index * 60_000)
+        .collect();
+    let signal_values = |indexes: &[usize]| -> Vec<i32> {
+        (0..bars)
+            .map(|index| indexes.contains(&index) as i32)
+            .collect()
+    };
+    let traded_symbol = trading_settings.get_traded_symbol();
+    let (open_col, high_col, low_col, close_col) = traded_symbol.get_ohlc_cols();
+    // redundant opens and closes of the opposite side are expected to be ignored by both
+    let bars_df = df!(
+        "start_time" => start_times.clone(),
+        open_col => opens.clone(),
+        high_col => highs.clone(),
+        low_col => lows.clone(),
+        close_col => closes.clone(),
+        SignalCategory::GoLong.get_column() => signal_values(&[1, 2]),
+        SignalCategory::CloseLong.get_column() => signal_values(&[4, 7]),
+        SignalCategory::GoShort.get_column() => signal_values(&[6, 8]),
+        SignalCategory::CloseShort.get_column() => signal_values(&[3, 9])
+    )
+    .unwrap()
+    .lazy()
+    .with_column(col("start_time").cast(DataType::Datetime(TimeUnit::Milliseconds, None)))
+    .collect()
+    .unwrap();
+
+    *trader.trading_data_klines_limit.write().unwrap() = bars as u32;
+    trader
+        .handle_initial_strategy_data(bars_df.slice(0, 1))
+        .unwrap();
+    let keep_position = SignalCategory::KeepPosition.get_column().to_owned();
+    let mut positions = vec![0];
+    let mut actions = vec![keep_position.clone()];
+    let mut filled_orders = 0;
+    for index in 1..bars {
+        // orders placed off previous bar's signal are filled at this bar's open
+        let signal = trader.signal_listener.value();
+        let created_orders: Vec<BybitRequest> = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request.path == "/v5/order/create")
+            .skip(filled_orders)
+            .cloned()
+            .collect();
+        filled_orders += created_orders.len();
+        let mut action = keep_position.clone();
+        for request in created_orders {
+            let order = get_created_order_fill(&request, opens[index], start_times[index]);
+            let is_close = order.is_close;
+            trader
+                .order_update_listener
+                .next(OrderAction::Update(order));
+            wait_until(|| match trader.current_trade_listener.value() {
+                Some(trade) => !is_close && trade.status() == TradeStatus::PendingCloseOrder,
+                None => is_close,
+            })
+            .await;
+            action = signal.get_column().to_owned();
+        }
+        positions.push(
+            trader
+                .current_trade_listener
+                .value()
+                .map_or(0, |trade| trade.open_order.side.into()),
+        );
+        actions.push(action);
+
+        let trading_data = trader.get_trading_data().unwrap();
+        let updated_df = trading_data
+            .vstack(&get_bar_row(&bars_df, index, &trading_data))
+            .unwrap();
+        trader.handle_updated_strategy_data(updated_df).unwrap();
+        let signal = trader.signal_listener.value();
+        if signal != SignalCategory::KeepPosition {
+            trader.process_last_signal(signal).await.unwrap();
+        }
+    }
+
+    let to_f32 = |values: &[f64]| values.iter().map(|&value| value as f32).collect::<Vec<_>>();
+    let benchmark_columns = simulate_positions(
+        &to_f32(&opens),
+        &to_f32(&highs),
+        &to_f32(&lows),
+        &to_f32(&closes),
+        &start_times,
+        &BenchmarkSignals::new(&bars_df).unwrap(),
+        &trading_settings,
+        &trader.trader_exchange,
+        1_000.0,
+    );
+    assert_eq!(positions, benchmark_columns.positions);
+    assert_eq!(actions, benchmark_columns.actions);
+    // live trades are sized off exchange's wallet balance, so only their returns are compared
+    let close_indexes: Vec<usize> = (1..bars)
+        .filter(|&index| positions[index - 1] != 0 && positions[index] != positions[index - 1])
+        .collect();
+    let closed_trades = closed_trades.0.lock().unwrap();
+    assert_eq!(closed_trades.len(), 2);
+    assert_eq!(closed_trades.len(), close_indexes.len());
+    for (trade, index) in closed_trades.iter().zip(close_indexes) {
+        let (_, returns) = trade.calculate_pnl_and_returns(ContractKind::Linear);
+        let benchmark_returns = benchmark_columns.returns[index] as f64;
+        assert!(
+            (returns - benchmark_returns).abs() < 1e-4,
+            "live trade returns {} differ from benchmark's {} at bar {}",
+            returns,
+            benchmark_returns,
+            index
+        );
+    }
+}
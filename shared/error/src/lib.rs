@@ -1,4 +1,5 @@
 use polars::prelude::PolarsError;
+use prometheus::Error as PrometheusError;
 use reqwest::Error as ReqwestError;
 use serde::{Deserialize, Serialize};
 use serde_json::Error as SerdeError;
@@ -10,10 +11,13 @@ use std::{
     io::Error as IoError,
     num::{ParseFloatError, ParseIntError},
     string::FromUtf8Error,
+    sync::PoisonError,
 };
 use tokio_tungstenite::tungstenite::Error as TugsteniteError;
 use url::ParseError as UrlParseError;
 
+const RATE_LIMIT_ERROR_TITLE: &str = "Rate Limit Error";
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GlowError {
     pub title: String,
@@ -39,6 +43,18 @@ impl GlowError {
         }
     }
 
+    /// Exchange rejected request for exceeding its rate limit, so that it may be retried later.
+    pub fn new_rate_limit_error(description: String) -> Self {
+        Self {
+            title: String::from(RATE_LIMIT_ERROR_TITLE),
+            description,
+        }
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        self.title == RATE_LIMIT_ERROR_TITLE
+    }
+
     pub fn new_assert_error<T: Display>(assertion: T) -> Self {
         Self {
             title: String::from("Assert Error"),
@@ -115,12 +131,24 @@ impl From<ParseIntError> for GlowError {
     }
 }
 
+impl From<PrometheusError> for GlowError {
+    fn from(error: PrometheusError) -> Self {
+        Self::new(String::from("Prometheus Error"), error.to_string())
+    }
+}
+
 impl From<IoError> for GlowError {
     fn from(error: IoError) -> Self {
         Self::new(String::from("I/O Error"), error.to_string())
     }
 }
 
+impl<T> From<PoisonError<T>> for GlowError {
+    fn from(error: PoisonError<T>) -> Self {
+        Self::new(String::from("Poison Error"), error.to_string())
+    }
+}
+
 #[macro_export]
 macro_rules! assert_or_error {
     ($cond:expr) => {
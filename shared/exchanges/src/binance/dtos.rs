@@ -46,6 +46,18 @@ pub mod ws {
             pub id: i64,
         }
 
+        #[derive(Debug, Deserialize)]
+        pub struct ErrorMessage {
+            pub code: i64,
+            pub msg: String,
+        }
+
+        #[derive(Debug, Deserialize)]
+        pub struct ErrorResponseMessage {
+            pub error: ErrorMessage,
+            pub id: Option<i64>,
+        }
+
         #[derive(Debug, Deserialize)]
         pub struct TickMessage {
             #[serde(rename = "e")]
@@ -58,6 +70,21 @@ pub mod ws {
             pub data: TickMessageData,
         }
 
+        /// Partial book depth update, holding top levels as `[price, quantity]`.
+        #[derive(Debug, Deserialize)]
+        pub struct DepthMessage {
+            #[serde(rename = "e")]
+            pub event_type: String, // Event type
+            #[serde(rename = "E")]
+            pub event_time: i64, // Event time
+            #[serde(rename = "s")]
+            pub symbol: String, // Symbol
+            #[serde(rename = "b")]
+            pub bids: Vec<[String; 2]>, // Bids, best first
+            #[serde(rename = "a")]
+            pub asks: Vec<[String; 2]>, // Asks, best first
+        }
+
         #[allow(dead_code)]
         #[derive(Debug, Deserialize)]
         pub struct TickMessageData {
@@ -1,4 +1,4 @@
-use super::dtos::ws::incoming::TickMessage;
+use super::dtos::ws::incoming::{DepthMessage, EmptyMessage, ErrorResponseMessage, TickMessage};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -20,6 +20,11 @@ pub enum OutgoingWsMessageMethod {
 pub enum IncomingWsMessage {
     None,
     Tick(TickMessage),
+    Depth(DepthMessage),
+    /// requests' rejection. Must precede `Response`, which it'd be parsed as otherwise.
+    ErrorResponse(ErrorResponseMessage),
+    /// subscription requests' acknowledgement.
+    Response(EmptyMessage),
 }
 
 impl Default for IncomingWsMessage {
@@ -1,5 +1,9 @@
-use super::dtos::ws::incoming::TickMessage;
-use common::structs::TickData;
+use super::dtos::ws::incoming::{DepthMessage, TickMessage};
+use common::{
+    enums::granularity::Granularity,
+    structs::{OrderBookDepth, TickData},
+};
+use glow_error::GlowError;
 
 pub fn from_tick_to_tick_data(
     tick: TickMessage,
@@ -18,3 +22,57 @@ pub fn from_tick_to_tick_data(
         low: tick.data.low,
     }
 }
+
+/// Maps depth update levels to `(price, quantity)`, skipping those which can't be parsed.
+pub fn from_depth_to_order_book_depth(depth: DepthMessage) -> OrderBookDepth {
+    let parse_levels = |levels: Vec<[String; 2]>| -> Vec<(f64, f64)> {
+        levels
+            .iter()
+            .filter_map(|[price, quantity]| Some((price.parse().ok()?, quantity.parse().ok()?)))
+            .collect()
+    };
+    OrderBookDepth::new(
+        depth.symbol,
+        depth.event_time,
+        parse_levels(depth.bids),
+        parse_levels(depth.asks),
+    )
+}
+
+/// Maps granularity to Binance kline interval code.
+///
+/// Binance doesn't provide 10 minutes klines.
+pub fn get_binance_interval(granularity: Granularity) -> Result<&'static str, GlowError> {
+    let interval = match granularity {
+        Granularity::m1 => "1m",
+        Granularity::m3 => "3m",
+        Granularity::m5 => "5m",
+        Granularity::m10 => {
+            return Err(GlowError::new(
+                String::from("Invalid Binance interval"),
+                format!("Binance doesn't provide {:?} klines", granularity),
+            ))
+        }
+        Granularity::m15 => "15m",
+        Granularity::m30 => "30m",
+        Granularity::h1 => "1h",
+        Granularity::h2 => "2h",
+        Granularity::h4 => "4h",
+        Granularity::h6 => "6h",
+        Granularity::h12 => "12h",
+        Granularity::d1 => "1d",
+        Granularity::w1 => "1w",
+        Granularity::M1 => "1M",
+    };
+    Ok(interval)
+}
+
+/// Gets granularity at which klines are fetched for benchmarking at `granularity`, being
+/// the coarsest Binance interval it's a multiple of. Klines are cached by day, so fetched
+/// ones are at most daily.
+pub fn get_binance_fetch_granularity(granularity: Granularity) -> Granularity {
+    granularity.get_coarsest_divisor(|divisor| {
+        divisor.get_granularity_in_secs() <= Granularity::d1.get_granularity_in_secs()
+            && get_binance_interval(divisor).is_ok()
+    })
+}
@@ -3,12 +3,23 @@ use super::{
     enums::OutgoingWsMessageMethod,
 };
 use crate::{
-    binance::{enums::IncomingWsMessage, functions::from_tick_to_tick_data},
-    config::WS_RECONNECT_INTERVAL_IN_SECS,
+    binance::{
+        enums::IncomingWsMessage,
+        functions::{
+            from_depth_to_order_book_depth, from_tick_to_tick_data, get_binance_fetch_granularity,
+            get_binance_interval,
+        },
+    },
+    config::{BINANCE_HTTP_REQUESTS_PER_SECOND, WS_RECONNECT_INTERVAL_IN_SECS},
+    shared::http::send_with_retry,
+    structs::{HttpRateLimiter, HttpRetryPolicy},
 };
 use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
 use common::{
-    enums::trading_data_update::TradingDataUpdate,
+    enums::{
+        benchmark_window::BenchmarkWindow, granularity::Granularity, run_mode::RunMode,
+        trading_data_update::TradingDataUpdate,
+    },
     functions::{
         coerce_df_to_schema,
         csv::{load_interval_tick_dataframe, save_kline_df_to_csv},
@@ -17,7 +28,7 @@ use common::{
         get_date_start_and_end_timestamps, map_ticks_data_to_df, timestamp_minute_end,
         timestamp_minute_start,
     },
-    structs::{BehaviorSubject, LogKlines, SymbolsPair, TickData, TradingSettings},
+    structs::{BehaviorSubject, LogKlines, OrderBookDepth, SymbolsPair, TickData, TradingSettings},
     traits::exchange::DataProviderExchange,
 };
 use futures_util::SinkExt;
@@ -28,9 +39,9 @@ use polars::{
     time::ClosedWindow,
 };
 use reqwest::Client;
-use serde_json::{from_str, to_string};
+use serde_json::to_string;
 use std::{
-    collections::HashMap,
+    collections::BTreeMap,
     env::var as env_var,
     sync::{Arc, Mutex},
     time::Duration as StdDuration,
@@ -38,24 +49,48 @@ use std::{
 use strategy::Strategy;
 use tokio::{
     net::TcpStream,
-    spawn,
-    time::{sleep, sleep_until, Instant},
+    pin, select, spawn,
+    time::{sleep, sleep_until, timeout, Instant},
+};
+use tokio_stream::{Stream, StreamExt};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{Error as TungsteniteError, Message},
+    MaybeTlsStream, WebSocketStream,
 };
-use tokio_stream::StreamExt;
-use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
 use url::Url;
 
+/// Binance server sends a ping frame every 20 seconds
+const BINANCE_WS_PING_INTERVAL_IN_SECS: u64 = 20;
+/// number of ping intervals without any incoming frame before the connection is deemed dead
+const WS_HEARTBEAT_TIMEOUT_PING_INTERVALS: u64 = 3;
+/// maximum time to wait for a subscription request to be responded to
+const WS_SUBSCRIPTION_RESPONSE_TIMEOUT_IN_SECS: u64 = 10;
+
 #[derive(Clone)]
 pub struct BinanceDataProvider {
+    depth_emitter: BehaviorSubject<Option<OrderBookDepth>>,
+    fetch_granularity: Granularity, // granularity of klines fetched for benchmarking
     fetch_leeway: StdDuration,
     http: Client,
+    http_rate_limiter: HttpRateLimiter, // shared by all clones, so that every fetch counts against the same budget
+    http_retry_policy: HttpRetryPolicy,
     kline_duration: Duration,
+    last_committed_minute: Arc<Mutex<Option<NaiveDateTime>>>, // start of the last kline minute committed or backfilled
     last_ws_error_ts: Arc<Mutex<Option<i64>>>,
     minimum_klines_for_benchmarking: u32,
-    staged_ticks: HashMap<u32, Vec<TickData>>, // TODO: change to array to avoid heap allocation
+    streams_depth: bool, // whether strategy is live only, thus requiring order book depth
+    staged_kline_minute: u32, // minute of the kline whose ticks are currently staged
+    staged_ticks: BTreeMap<u32, Vec<TickData>>, // keyed by second, so that commit order is stable. TODO: change to array to avoid heap allocation
     symbols: SymbolsPair,
     ticks_to_commit: BehaviorSubject<Vec<TickData>>, // TODO: change to array to avoid heap allocation
     klines_data_update_emitter: BehaviorSubject<TradingDataUpdate>,
+    ws_heartbeat_timeout: StdDuration, // maximum interval without incoming frames before reconnecting
+}
+
+/// Rate limiter allowing bursts of up to a second worth of requests.
+fn get_http_rate_limiter(requests_per_second: f64) -> HttpRateLimiter {
+    HttpRateLimiter::new(requests_per_second, requests_per_second.ceil() as u32)
 }
 
 /// A single connection to stream.binance.com is only valid for 24 hours; expect to be disconnected at the 24 hour mark
@@ -64,43 +99,228 @@ impl BinanceDataProvider {
         let symbols = trading_settings.symbols_pair;
         let kline_duration = trading_settings.granularity.get_chrono_duration();
         let last_ws_error_ts = Arc::new(Mutex::new(None));
-        let minimum_klines_for_benchmarking = strategy.get_minimum_klines_for_calculation();
+        let minimum_klines_for_benchmarking = strategy.get_warmup_klines();
         let klines_data_update_emitter = BehaviorSubject::new(TradingDataUpdate::default());
         Self {
+            depth_emitter: BehaviorSubject::new(None),
+            fetch_granularity: get_binance_fetch_granularity(trading_settings.granularity),
             fetch_leeway: StdDuration::from_secs(5),
             http: Client::new(),
+            http_rate_limiter: get_http_rate_limiter(*BINANCE_HTTP_REQUESTS_PER_SECOND),
+            http_retry_policy: HttpRetryPolicy::default(),
             // kline_data_schema,
             kline_duration,
+            last_committed_minute: Arc::new(Mutex::new(None)),
             last_ws_error_ts,
             minimum_klines_for_benchmarking,
-            staged_ticks: HashMap::new(),
+            streams_depth: strategy.is_live_only(),
+            staged_kline_minute: 0,
+            staged_ticks: BTreeMap::new(),
             symbols,
             ticks_to_commit: BehaviorSubject::new(vec![]),
             // trading_data_schema,
             klines_data_update_emitter,
+            ws_heartbeat_timeout: StdDuration::from_secs(
+                BINANCE_WS_PING_INTERVAL_IN_SECS * WS_HEARTBEAT_TIMEOUT_PING_INTERVALS,
+            ),
         }
     }
 
     pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
         self.symbols = trading_settings.symbols_pair;
         self.kline_duration = trading_settings.granularity.get_chrono_duration();
+        self.fetch_granularity = get_binance_fetch_granularity(trading_settings.granularity);
+    }
+
+    /// Gets name klines fetched for benchmarking are cached under. Minute klines keep being cached
+    /// under exchange name, whereas coarser ones are suffixed by their duration, e.g. `binance_5m`.
+    fn get_cache_name(&self) -> String {
+        match self.fetch_granularity {
+            Granularity::m1 => String::from("binance"),
+            granularity => format!("binance_{}m", granularity.get_granularity_in_mins()),
+        }
     }
 
     pub fn patch_strategy(&mut self, strategy: &Strategy) {
-        self.minimum_klines_for_benchmarking = strategy.get_minimum_klines_for_calculation();
+        self.minimum_klines_for_benchmarking = strategy.get_warmup_klines();
+        self.streams_depth = strategy.is_live_only();
+    }
+
+    /// this must be run before init
+    pub fn patch_ws_heartbeat_timeout(&mut self, ws_heartbeat_timeout: StdDuration) {
+        self.ws_heartbeat_timeout = ws_heartbeat_timeout;
+    }
+
+    /// Waits for response to subscription request `id`, so that a rejected subscription doesn't
+    /// silently leave stream without ticks. Errors if exchange rejects it, or if `wss` closes or
+    /// doesn't respond within `WS_SUBSCRIPTION_RESPONSE_TIMEOUT_IN_SECS`. Other frames received
+    /// meanwhile are dropped.
+    pub(crate) async fn await_subscription_response<S>(
+        &self,
+        wss: &mut S,
+        id: i64,
+    ) -> Result<(), GlowError>
+    where
+        S: Stream<Item = Result<Message, TungsteniteError>> + Unpin,
+    {
+        let ws_compression = self.get_ws_compression();
+        let response_timeout = StdDuration::from_secs(WS_SUBSCRIPTION_RESPONSE_TIMEOUT_IN_SECS);
+        let response = timeout(response_timeout, async {
+            while let Some(message) = wss.try_next().await? {
+                let Message::Text(json) = ws_compression.decode_message(message)? else {
+                    continue;
+                };
+                match self.parse_ws_message::<IncomingWsMessage>(&json) {
+                    Some(IncomingWsMessage::Response(response)) if response.id == id => {
+                        return Ok(());
+                    }
+                    Some(IncomingWsMessage::ErrorResponse(response))
+                        if response.id.is_none_or(|response_id| response_id == id) =>
+                    {
+                        let error = format!(
+                            "subscription {} was rejected with code {}: {}",
+                            id, response.error.code, response.error.msg
+                        );
+                        return Err(GlowError::new(
+                            String::from("WebSocket Subscription Error"),
+                            error,
+                        ));
+                    }
+                    Some(unhandled) => self.drop_unhandled_ws_message(unhandled),
+                    None => {}
+                }
+            }
+            let error = format!("connection closed before subscription {} was responded", id);
+            Err(GlowError::new(
+                String::from("WebSocket Subscription Error"),
+                error,
+            ))
+        })
+        .await;
+
+        response.unwrap_or_else(|_| {
+            let error = format!(
+                "subscription {} wasn't responded within {} seconds",
+                id, WS_SUBSCRIPTION_RESPONSE_TIMEOUT_IN_SECS
+            );
+            Err(GlowError::new(
+                String::from("WebSocket Subscription Timeout"),
+                error,
+            ))
+        })
+    }
+
+    /// this must be run before init
+    pub fn patch_http_retry_policy(&mut self, http_retry_policy: HttpRetryPolicy) {
+        self.http_retry_policy = http_retry_policy;
+    }
+
+    /// this must be run before init
+    pub fn patch_http_requests_per_second(&mut self, requests_per_second: f64) {
+        self.http_rate_limiter = get_http_rate_limiter(requests_per_second);
+    }
+
+    /// Records websocket error timestamp (in seconds) and discards the ticks staged for the
+    /// in-progress minute, as they are going to be backfilled via REST once the socket reconnects
+    pub(super) fn on_listen_ticks_error(&mut self, error_timestamp: i64) {
+        {
+            let mut last_error_guard = self
+                .last_ws_error_ts
+                .lock()
+                .expect("on_listen_ticks_error -> last_error_guard unwrap");
+            *last_error_guard = Some(error_timestamp);
+        }
+        self.staged_ticks.clear();
+    }
+
+    /// Gets staged ticks ordered by second and, within the same second, by symbol
+    pub(super) fn get_staged_ticks_to_commit(&self) -> Vec<TickData> {
+        self.staged_ticks
+            .values()
+            .flat_map(|second_ticks| {
+                let mut second_ticks = second_ticks.clone();
+                second_ticks.sort_by(|a, b| a.symbol.cmp(b.symbol));
+                second_ticks
+            })
+            .collect()
     }
 
+    /// Stages `tick` at its second. Ticks are assumed to belong to the staged kline as long as
+    /// their minute matches it, so a tick from another minute means that staged kline is complete:
+    /// its ticks are returned to be committed and `tick` starts staging the next kline.
+    ///
+    /// Returns `None` if no kline was completed or nothing was staged for it.
+    pub(super) fn stage_tick(&mut self, tick: TickData) -> Option<Vec<TickData>> {
+        let tick_time = tick.start_time.time();
+        let tick_minute = tick_time.minute();
+        let tick_second = tick_time.second();
+        if tick_minute == self.staged_kline_minute {
+            self.staged_ticks.entry(tick_second).or_default().push(tick);
+            return None;
+        }
+
+        let committed_ticks = self.get_staged_ticks_to_commit();
+        self.staged_ticks.clear();
+        self.staged_ticks.insert(tick_second, vec![tick]);
+        self.staged_kline_minute = tick_minute;
+
+        if committed_ticks.is_empty() {
+            return None;
+        }
+        Some(committed_ticks)
+    }
+
+    #[cfg(test)]
+    pub(super) fn stage_ticks(&mut self, second: u32, ticks: Vec<TickData>) {
+        self.staged_ticks.insert(second, ticks);
+    }
+
+    #[cfg(test)]
+    pub(super) fn has_staged_ticks(&self) -> bool {
+        !self.staged_ticks.is_empty()
+    }
+
+    pub(super) fn set_last_committed_minute(&self, minute_start: NaiveDateTime) {
+        let mut last_committed_minute_guard = self
+            .last_committed_minute
+            .lock()
+            .expect("set_last_committed_minute -> last_committed_minute_guard unwrap");
+        *last_committed_minute_guard = Some(minute_start);
+    }
+
+    /// Gets (start, end) timestamps in milliseconds for the klines missed during a websocket outage.
+    ///
+    /// Starts at the minute following the last committed minute, or at last error's minute start
+    /// if nothing was committed yet. Ends at the end of the minute of `now_ts` (ms).
+    pub(super) fn get_backfill_interval(&self, last_error_ts: i64, now_ts: i64) -> (i64, i64) {
+        let last_committed_minute = {
+            let last_committed_minute_guard = self
+                .last_committed_minute
+                .lock()
+                .expect("get_backfill_interval -> last_committed_minute_guard unwrap");
+            *last_committed_minute_guard
+        };
+        let start_ms = match last_committed_minute {
+            Some(minute_start) => (minute_start + Duration::minutes(1)).timestamp_millis(),
+            None => timestamp_minute_start(false, Some(last_error_ts)) * 1000,
+        };
+        let end_ms = timestamp_minute_end(false, Some(now_ts / 1000)) * 1000 + 999;
+        (start_ms, end_ms)
+    }
+
+    /// Loads cached klines between `start_datetime` and `end_datetime`, fetching missing days at
+    /// fetch granularity, then downsamples them to kline duration.
     async fn load_or_fetch_kline_data(
         &self,
         trading_data_schema: &Schema,
         start_datetime: NaiveDateTime,
         end_datetime: NaiveDateTime,
-        tick_duration: Duration,
     ) -> Result<DataFrame, GlowError> {
+        let cache_name = self.get_cache_name();
         let mut kline_df = DataFrame::from(trading_data_schema);
         for symbol in &self.symbols.get_unique_symbols() {
             let (loaded_data_df, not_loaded_dates) =
-                load_interval_tick_dataframe(start_datetime, end_datetime, &symbol, "binance")?;
+                load_interval_tick_dataframe(start_datetime, end_datetime, symbol, &cache_name)?;
 
             let mut result_df =
                 loaded_data_df.unwrap_or_else(|| DataFrame::from(trading_data_schema));
@@ -109,26 +329,29 @@ impl BinanceDataProvider {
                 result_df = coerce_df_to_schema(result_df, trading_data_schema)?;
             }
 
-            for (i, date) in not_loaded_dates.into_iter().enumerate() {
+            for date in not_loaded_dates {
                 let datetimes = get_date_start_and_end_timestamps(date);
                 let mut day_ticks_data = vec![];
-                if i > 0 {
-                    // avoid spamming API
-                    sleep(StdDuration::from_secs(1)).await;
-                }
                 for (start_timestamp_ms, end_timestamp_ms) in datetimes {
                     let fetched_ticks = self
-                        .fetch_tick_data(symbol.name, start_timestamp_ms, end_timestamp_ms, 720)
+                        .fetch_tick_data(
+                            symbol.name,
+                            start_timestamp_ms,
+                            end_timestamp_ms,
+                            720,
+                            self.fetch_granularity,
+                        )
                         .await?;
                     day_ticks_data.extend(fetched_ticks);
                 }
                 let fetched_data_df = map_ticks_data_to_df(&day_ticks_data)?;
 
                 let total_klines = fetched_data_df.height() as i64;
-                let daily_klines = Duration::days(1).num_seconds() / tick_duration.num_seconds();
+                let daily_klines = Duration::days(1).num_seconds()
+                    / self.fetch_granularity.get_granularity_in_secs() as i64;
 
                 if total_klines == daily_klines {
-                    let _ = save_kline_df_to_csv(&fetched_data_df, date, "binance", &symbol.name)?;
+                    save_kline_df_to_csv(&fetched_data_df, date, &cache_name, symbol.name)?;
                 }
                 let fetched_data_df = coerce_df_to_schema(fetched_data_df, &trading_data_schema)?;
                 match &result_df.vstack(&fetched_data_df) {
@@ -180,13 +403,15 @@ impl BinanceDataProvider {
         start_timestamp_ms: i64, // ms
         end_timestamp_ms: i64,   // ms
         limit: i64,              //Default 500; max 1000.
+        granularity: Granularity,
     ) -> Result<Vec<TickData>, GlowError> {
         assert!(limit <= 1000, "Limit must be equal or less than 1000");
         assert!(limit > 0, "Limit must be greater than 0");
+        let interval = get_binance_interval(granularity)?;
 
         let url = format!(
             "https://api3.binance.com/api/v3/klines?symbol={}&interval={}&startTime={}&endTime={}&limit={}",
-            symbol, "1m", start_timestamp_ms, end_timestamp_ms, limit
+            symbol, interval, start_timestamp_ms, end_timestamp_ms, limit
         );
 
         println!(
@@ -198,7 +423,15 @@ impl BinanceDataProvider {
             NaiveDateTime::from_timestamp_millis(end_timestamp_ms).unwrap()
         );
 
-        let result: Vec<BinanceHttpKlineResponse> = self.http.get(url).send().await?.json().await?;
+        let result: Vec<BinanceHttpKlineResponse> = send_with_retry(
+            &self.http,
+            &url,
+            &self.http_retry_policy,
+            &self.http_rate_limiter,
+        )
+        .await?
+        .json()
+        .await?;
         let result = result
             .into_iter()
             .map(move |data| {
@@ -224,9 +457,8 @@ impl BinanceDataProvider {
         sleep_until(wait_until).await;
 
         let mut ticks_data = Vec::new();
-        let kline_duration_in_secs = self.kline_duration.num_seconds();
-        let current_limit =
-            (end_timestamp_ms - start_timestamp_ms) / (kline_duration_in_secs * 1000);
+        // ticks are fetched by minute
+        let current_limit = ((end_timestamp_ms - start_timestamp_ms) / 60_000 + 1).min(1000);
         for symbol in &self.symbols.get_unique_symbols() {
             let symbol_kline_data = self
                 .fetch_tick_data(
@@ -234,6 +466,7 @@ impl BinanceDataProvider {
                     start_timestamp_ms,
                     end_timestamp_ms,
                     current_limit,
+                    Granularity::m1,
                 )
                 .await
                 .expect("fetch data to work");
@@ -253,12 +486,7 @@ impl BinanceDataProvider {
         trading_data_schema: &Schema,
     ) -> Result<(), GlowError> {
         let initial_kline_data_df = self
-            .load_or_fetch_kline_data(
-                trading_data_schema,
-                benchmark_start,
-                benchmark_end,
-                self.kline_duration,
-            )
+            .load_or_fetch_kline_data(trading_data_schema, benchmark_start, benchmark_end)
             .await?;
 
         let current_datetime = current_datetime();
@@ -297,11 +525,19 @@ impl BinanceDataProvider {
 }
 
 impl DataProviderExchange for BinanceDataProvider {
+    fn get_exchange_name(&self) -> &'static str {
+        "binance"
+    }
+
     #[inline]
     fn get_kline_data_emitter(&self) -> &BehaviorSubject<TradingDataUpdate> {
         &self.klines_data_update_emitter
     }
 
+    fn get_depth_emitter(&self) -> Option<&BehaviorSubject<Option<OrderBookDepth>>> {
+        Some(&self.depth_emitter)
+    }
+
     async fn handle_committed_ticks_data(
         &self,
         discard_ticks_before: NaiveDateTime,
@@ -365,15 +601,22 @@ impl DataProviderExchange for BinanceDataProvider {
         if last_error_ts.is_none() {
             return None;
         }
+        let last_error_ts = last_error_ts.unwrap();
         let now = Instant::now();
         let now_ts = current_timestamp_ms();
 
-        let start_ms = timestamp_minute_start(true, last_error_ts);
-        let end_ms = timestamp_minute_end(true, Some(now_ts));
+        let (start_ms, end_ms) = self.get_backfill_interval(last_error_ts, now_ts);
         let result = NaiveDateTime::from_timestamp_millis(end_ms).unwrap();
+        println!(
+            "{:?} | 🩹 Backfilling klines between {} and {}",
+            current_datetime(),
+            NaiveDateTime::from_timestamp_millis(start_ms).unwrap(),
+            result
+        );
         let data_provider = self.clone();
         let _ = spawn(async move {
-            let duration_until_available = StdDuration::from_millis((now_ts - end_ms) as u64);
+            let duration_until_available =
+                StdDuration::from_millis((end_ms - now_ts).max(0) as u64);
 
             let wait_until = now + duration_until_available + data_provider.fetch_leeway;
             let pending_kline_df = data_provider
@@ -382,6 +625,9 @@ impl DataProviderExchange for BinanceDataProvider {
                 .expect("pending kline df");
             let market_data = TradingDataUpdate::Market(pending_kline_df);
             data_provider.klines_data_update_emitter.next(market_data);
+            data_provider.set_last_committed_minute(
+                NaiveDateTime::from_timestamp_millis(end_ms - 59_999).unwrap(),
+            );
 
             {
                 let mut last_error_guard = data_provider.last_ws_error_ts.lock().unwrap();
@@ -393,14 +639,12 @@ impl DataProviderExchange for BinanceDataProvider {
 
     async fn init(
         &mut self,
-        benchmark_start: Option<NaiveDateTime>,
-        benchmark_end: Option<NaiveDateTime>,
-        run_benchmark_only: bool,
+        benchmark_window: BenchmarkWindow,
+        run_mode: RunMode,
         trading_data_schema: Schema,
     ) -> Result<(), GlowError> {
         let (benchmark_start, benchmark_end) = adjust_benchmark_datetimes(
-            benchmark_start,
-            benchmark_end,
+            benchmark_window,
             self.kline_duration,
             Some(1),
             self.minimum_klines_for_benchmarking as i32,
@@ -410,7 +654,7 @@ impl DataProviderExchange for BinanceDataProvider {
             .handle_initial_klines_fetch(benchmark_start, benchmark_end, &trading_data_schema)
             .await?;
 
-        if run_benchmark_only {
+        if !run_mode.streams_market_data() {
             return Ok(());
         }
 
@@ -467,19 +711,37 @@ impl DataProviderExchange for BinanceDataProvider {
         discard_ticks_before: NaiveDateTime,
     ) -> Result<(), GlowError> {
         self.subscribe_to_tick_stream(&mut wss).await?;
+        self.subscribe_to_depth_stream(&mut wss).await?;
 
-        let mut current_staged_kline_minute = discard_ticks_before.time().minute();
+        self.staged_kline_minute = discard_ticks_before.time().minute();
 
         let unique_symbols_len = self.symbols.get_unique_symbols().len();
+        let ws_compression = self.get_ws_compression();
+        // reset on every incoming frame, so a silently dead connection is detected
+        let heartbeat_deadline = sleep(self.ws_heartbeat_timeout);
+        pin!(heartbeat_deadline);
         loop {
-            let message = wss.try_next().await;
+            let message = select! {
+                message = wss.try_next() => message,
+                _ = &mut heartbeat_deadline => {
+                    self.on_listen_ticks_error(current_timestamp());
+                    let error = format!(
+                        "no message was received in the last {} seconds",
+                        self.ws_heartbeat_timeout.as_secs()
+                    );
+                    eprintln!("WebSocket heartbeat timeout: {}", error);
+                    return Err(GlowError::new(
+                        String::from("WebSocket Heartbeat Timeout"),
+                        error,
+                    ));
+                }
+            };
+            heartbeat_deadline
+                .as_mut()
+                .reset(Instant::now() + self.ws_heartbeat_timeout);
+
             if let Err(error) = message {
-                let mut last_error_guard = self
-                    .last_ws_error_ts
-                    .lock()
-                    .expect("handle_websocket -> last_error_guard unwrap");
-                let error_timestamp = current_timestamp();
-                *last_error_guard = Some(error_timestamp);
+                self.on_listen_ticks_error(current_timestamp());
                 eprintln!("WebSocket message error: {:?}", error);
                 return Err(GlowError::from(error));
             }
@@ -489,45 +751,35 @@ impl DataProviderExchange for BinanceDataProvider {
                 continue;
             }
             let message = message.unwrap();
+            let message = match ws_compression.decode_message(message) {
+                Ok(message) => message,
+                Err(error) => {
+                    eprintln!("WebSocket message decompression error: {:?}", error);
+                    continue;
+                }
+            };
             match message {
                 Message::Text(json) => {
-                    let incoming_msg = from_str::<IncomingWsMessage>(&json).unwrap_or_default();
+                    let Some(incoming_msg) = self.parse_ws_message::<IncomingWsMessage>(&json)
+                    else {
+                        continue;
+                    };
                     match incoming_msg {
                         IncomingWsMessage::Tick(tick) => {
                             let tick_data = from_tick_to_tick_data(tick, &self.symbols.get_tuple());
 
-                            let tick_time = tick_data.start_time.time();
-                            let tick_minute = tick_time.minute();
-                            let tick_second = tick_time.second();
-                            // we assume that if the received tick minute is the same as the current staged kline
-                            // the tick still belongs to the kline
-                            if tick_minute == current_staged_kline_minute {
-                                self.staged_ticks
-                                    .entry(tick_second)
-                                    .or_insert(Vec::new())
-                                    .push(tick_data.clone());
-                            } else {
-                                // otherwise, all ticks regarding the staged kline were already provided
-                                // and the ticks must be committed as kline data
-
-                                // commit ticks to kline data
-                                self.ticks_to_commit.next(
-                                    self.staged_ticks
-                                        .values()
-                                        .cloned()
-                                        .into_iter()
-                                        .flat_map(|vec| vec.into_iter())
-                                        .collect(),
-                                );
-
-                                // clear staged ticks
-                                self.staged_ticks.clear();
-
-                                // insert the new tick data at respective map second
-                                self.staged_ticks
-                                    .insert(tick_second, vec![tick_data.clone()]);
-                                // and update current committed kline minute
-                                current_staged_kline_minute = tick_minute;
+                            let tick_second = tick_data.start_time.time().second();
+                            if let Some(committed_ticks) = self.stage_tick(tick_data) {
+                                // all ticks regarding the staged kline were already provided,
+                                // so they must be committed as kline data
+                                let committed_minute = committed_ticks[0]
+                                    .start_time
+                                    .with_second(0)
+                                    .unwrap()
+                                    .with_nanosecond(0)
+                                    .unwrap();
+                                self.ticks_to_commit.next(committed_ticks);
+                                self.set_last_committed_minute(committed_minute);
                             }
 
                             let second_staged_ticks = self.staged_ticks.get(&tick_second).unwrap();
@@ -535,18 +787,15 @@ impl DataProviderExchange for BinanceDataProvider {
                                 print!("{}", LogKlines(second_staged_ticks.to_vec()));
                             }
                         }
-                        fallback => {
-                            println!(
-                                "fallback incoming msg from binance data provider {:?}",
-                                fallback
-                            );
+                        IncomingWsMessage::Depth(depth) => {
+                            let depth = from_depth_to_order_book_depth(depth);
+                            self.depth_emitter.next(Some(depth));
                         }
+                        unhandled => self.drop_unhandled_ws_message(unhandled),
                     }
                 }
                 Message::Ping(_) => wss.send(Message::Pong(vec![])).await?,
-                fallback => {
-                    println!("fallback msg from binance data provider {:?}", fallback);
-                }
+                unhandled => self.drop_unhandled_ws_message(unhandled),
             }
         }
     }
@@ -575,33 +824,62 @@ impl DataProviderExchange for BinanceDataProvider {
         let subscription_message = Message::Text(subscribe_json_str);
         wss.send(subscription_message)
             .await
-            .map_err(|err| GlowError::from(err))
+            .map_err(GlowError::from)?;
+        self.await_subscription_response(wss, subscribe_message.id as i64)
+            .await
+    }
+
+    /// Subscribes to top 5 levels of each symbol's book, updated every 100ms, only if
+    /// strategy is live only, as no other strategy uses depth.
+    async fn subscribe_to_depth_stream(
+        &mut self,
+        wss: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), GlowError> {
+        if !self.streams_depth {
+            return Ok(());
+        }
+        let depth_params: Vec<String> = self
+            .symbols
+            .get_unique_symbols()
+            .into_iter()
+            .map(|s| format!("{}@depth5@100ms", s.name.to_lowercase()))
+            .collect();
+
+        let subscribe_message = WsOutgoingMessage {
+            method: OutgoingWsMessageMethod::Subscribe,
+            params: depth_params,
+            id: 2,
+        };
+
+        let subscribe_json_str = to_string(&subscribe_message)?;
+        wss.send(Message::Text(subscribe_json_str))
+            .await
+            .map_err(GlowError::from)
     }
 }
 
-fn adjust_benchmark_datetimes(
-    benchmark_start: Option<NaiveDateTime>,
-    benchmark_end: Option<NaiveDateTime>,
+pub(crate) fn adjust_benchmark_datetimes(
+    benchmark_window: BenchmarkWindow,
     kline_duration: Duration,
     minimum_days_for_analysis: Option<i64>,
     minimum_klines_for_benchmarking: i32,
 ) -> Result<(NaiveDateTime, NaiveDateTime), GlowError> {
-    if let (Some(benchmark_start), Some(benchmark_end)) = (benchmark_start, benchmark_end) {
+    if let BenchmarkWindow::Absolute(Some(benchmark_start), Some(benchmark_end)) = benchmark_window
+    {
         assert_or_error!(benchmark_end > benchmark_start);
     }
 
-    let benchmark_end = benchmark_end.unwrap_or_else(|| {
-        let current_datetime = current_datetime();
-        let date = NaiveDate::from_ymd_opt(
-            current_datetime.year(),
-            current_datetime.month(),
-            current_datetime.day(),
-        )
-        .unwrap();
-        let time =
-            NaiveTime::from_hms_opt(current_datetime.hour(), current_datetime.minute(), 0).unwrap();
-        NaiveDateTime::new(date, time)
-    });
+    let current_datetime = current_datetime();
+    let date = NaiveDate::from_ymd_opt(
+        current_datetime.year(),
+        current_datetime.month(),
+        current_datetime.day(),
+    )
+    .unwrap();
+    let time =
+        NaiveTime::from_hms_opt(current_datetime.hour(), current_datetime.minute(), 0).unwrap();
+    let (benchmark_start, benchmark_end) =
+        benchmark_window.resolve(NaiveDateTime::new(date, time), kline_duration);
 
     let benchmark_start = benchmark_start.unwrap_or_else(|| {
         benchmark_end - (Duration::days(minimum_days_for_analysis.unwrap_or(1)))
@@ -614,7 +892,7 @@ fn adjust_benchmark_datetimes(
     Ok((benchmark_start, benchmark_end))
 }
 
-fn set_ws_error_ts(last_ws_error_ts: Arc<Mutex<Option<i64>>>, error: GlowError) {
+pub(crate) fn set_ws_error_ts(last_ws_error_ts: Arc<Mutex<Option<i64>>>, error: GlowError) {
     let mut last_error_guard = last_ws_error_ts
         .lock()
         .expect("init -> last_error_guard unwrap");
@@ -0,0 +1,336 @@
+use super::{
+    enums::IncomingWsMessage,
+    functions::{
+        from_depth_to_order_book_depth, get_binance_fetch_granularity, get_binance_interval,
+    },
+    structs::{adjust_benchmark_datetimes, BinanceDataProvider},
+};
+use chrono::{Duration, NaiveDate, NaiveDateTime, Timelike};
+use common::{
+    enums::{benchmark_window::BenchmarkWindow, granularity::Granularity},
+    r#static::METRICS,
+    structs::{TickData, TradingSettings},
+    traits::exchange::DataProviderExchange,
+};
+use strategy::Strategy;
+use tokio_tungstenite::tungstenite::{Error as TungsteniteError, Message};
+
+fn get_datetime(hour: u32, minute: u32, second: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, 1, 1)
+        .unwrap()
+        .and_hms_opt(hour, minute, second)
+        .unwrap()
+}
+
+fn get_tick(start_time: NaiveDateTime) -> TickData {
+    get_symbol_tick("BTCUSDT", start_time)
+}
+
+fn get_symbol_tick(symbol: &'static str, start_time: NaiveDateTime) -> TickData {
+    TickData {
+        symbol,
+        start_time,
+        open: 1.0,
+        high: 1.0,
+        close: 1.0,
+        low: 1.0,
+    }
+}
+
+#[test]
+fn test_mid_minute_disconnect_backfills_from_last_committed_minute() {
+    let mut data_provider =
+        BinanceDataProvider::new(&TradingSettings::default(), &Strategy::default());
+    data_provider.set_last_committed_minute(get_datetime(12, 0, 0));
+    data_provider.stage_ticks(10, vec![get_tick(get_datetime(12, 1, 10))]);
+    data_provider.stage_ticks(20, vec![get_tick(get_datetime(12, 1, 20))]);
+
+    // socket drops at 12:01:30, reconnects at 12:03:15
+    let error_ts = get_datetime(12, 1, 30).timestamp();
+    data_provider.on_listen_ticks_error(error_ts);
+    assert!(
+        !data_provider.has_staged_ticks(),
+        "partial minute must be discarded, as it is going to be backfilled"
+    );
+
+    let now_ts = get_datetime(12, 3, 15).timestamp_millis();
+    let (start_ms, end_ms) = data_provider.get_backfill_interval(error_ts, now_ts);
+    assert_eq!(start_ms, get_datetime(12, 1, 0).timestamp_millis());
+    assert_eq!(end_ms, get_datetime(12, 3, 59).timestamp_millis() + 999);
+}
+
+#[test]
+fn test_disconnect_without_committed_minute_backfills_from_error_minute() {
+    let mut data_provider =
+        BinanceDataProvider::new(&TradingSettings::default(), &Strategy::default());
+    let error_ts = get_datetime(12, 1, 30).timestamp();
+    data_provider.on_listen_ticks_error(error_ts);
+
+    let now_ts = get_datetime(12, 1, 45).timestamp_millis();
+    let (start_ms, end_ms) = data_provider.get_backfill_interval(error_ts, now_ts);
+    assert_eq!(start_ms, get_datetime(12, 1, 0).timestamp_millis());
+    assert_eq!(end_ms, get_datetime(12, 1, 59).timestamp_millis() + 999);
+}
+
+#[test]
+fn test_staged_ticks_are_committed_in_second_and_symbol_order() {
+    let mut data_provider =
+        BinanceDataProvider::new(&TradingSettings::default(), &Strategy::default());
+    data_provider.stage_ticks(
+        40,
+        vec![
+            get_symbol_tick("ETHUSDT", get_datetime(12, 1, 40)),
+            get_symbol_tick("BTCUSDT", get_datetime(12, 1, 40)),
+        ],
+    );
+    data_provider.stage_ticks(5, vec![get_tick(get_datetime(12, 1, 5))]);
+    data_provider.stage_ticks(20, vec![get_tick(get_datetime(12, 1, 20))]);
+
+    let committed_ticks: Vec<(&str, u32)> = data_provider
+        .get_staged_ticks_to_commit()
+        .iter()
+        .map(|tick| (tick.symbol, tick.start_time.second()))
+        .collect();
+
+    assert_eq!(
+        committed_ticks,
+        vec![
+            ("BTCUSDT", 5),
+            ("BTCUSDT", 20),
+            ("BTCUSDT", 40),
+            ("ETHUSDT", 40)
+        ]
+    );
+}
+
+fn get_tick_summaries(ticks: &[TickData]) -> Vec<(&str, u32, u32)> {
+    ticks
+        .iter()
+        .map(|tick| {
+            let time = tick.start_time.time();
+            (tick.symbol, time.minute(), time.second())
+        })
+        .collect()
+}
+
+#[test]
+fn test_stage_tick_commits_single_symbol_minute_on_rollover() {
+    let mut data_provider =
+        BinanceDataProvider::new(&TradingSettings::default(), &Strategy::default());
+
+    assert_eq!(
+        data_provider.stage_tick(get_tick(get_datetime(12, 1, 0))),
+        None
+    );
+    assert_eq!(
+        data_provider.stage_tick(get_tick(get_datetime(12, 1, 30))),
+        None
+    );
+    assert_eq!(
+        data_provider.stage_tick(get_tick(get_datetime(12, 1, 59))),
+        None
+    );
+
+    let committed_ticks = data_provider
+        .stage_tick(get_tick(get_datetime(12, 2, 0)))
+        .expect("minute rollover must commit staged ticks");
+    assert_eq!(
+        get_tick_summaries(&committed_ticks),
+        vec![("BTCUSDT", 1, 0), ("BTCUSDT", 1, 30), ("BTCUSDT", 1, 59)]
+    );
+
+    // rollover tick starts the next kline
+    let committed_ticks = data_provider
+        .stage_tick(get_tick(get_datetime(12, 3, 0)))
+        .unwrap();
+    assert_eq!(
+        get_tick_summaries(&committed_ticks),
+        vec![("BTCUSDT", 2, 0)]
+    );
+}
+
+#[test]
+fn test_stage_tick_commits_every_symbol_of_multi_symbol_minute() {
+    let mut data_provider =
+        BinanceDataProvider::new(&TradingSettings::default(), &Strategy::default());
+    let staged_ticks = vec![
+        get_symbol_tick("ETHUSDT", get_datetime(12, 1, 0)),
+        get_symbol_tick("BTCUSDT", get_datetime(12, 1, 0)),
+        get_symbol_tick("BTCUSDT", get_datetime(12, 1, 30)),
+        get_symbol_tick("ETHUSDT", get_datetime(12, 1, 30)),
+    ];
+    for tick in staged_ticks {
+        assert_eq!(data_provider.stage_tick(tick), None);
+    }
+
+    // only first symbol of next minute arrived, yet staged minute is complete
+    let committed_ticks = data_provider
+        .stage_tick(get_symbol_tick("ETHUSDT", get_datetime(12, 2, 0)))
+        .unwrap();
+    assert_eq!(
+        get_tick_summaries(&committed_ticks),
+        vec![
+            ("BTCUSDT", 1, 0),
+            ("ETHUSDT", 1, 0),
+            ("BTCUSDT", 1, 30),
+            ("ETHUSDT", 1, 30)
+        ]
+    );
+
+    // other symbol's tick of the same minute is staged along, not committed
+    assert_eq!(
+        data_provider.stage_tick(get_symbol_tick("BTCUSDT", get_datetime(12, 2, 0))),
+        None
+    );
+    let committed_ticks = data_provider
+        .stage_tick(get_tick(get_datetime(12, 3, 0)))
+        .unwrap();
+    assert_eq!(
+        get_tick_summaries(&committed_ticks),
+        vec![("BTCUSDT", 2, 0), ("ETHUSDT", 2, 0)]
+    );
+}
+
+#[test]
+fn test_klines_are_fetched_at_coarsest_interval_dividing_granularity() {
+    assert_eq!(get_binance_interval(Granularity::m5).unwrap(), "5m");
+    assert_eq!(get_binance_interval(Granularity::h2).unwrap(), "2h");
+    assert!(get_binance_interval(Granularity::m10).is_err());
+
+    assert_eq!(
+        get_binance_fetch_granularity(Granularity::m1),
+        Granularity::m1
+    );
+    assert_eq!(
+        get_binance_fetch_granularity(Granularity::m5),
+        Granularity::m5
+    );
+    assert_eq!(
+        get_binance_fetch_granularity(Granularity::m10),
+        Granularity::m5
+    );
+    assert_eq!(
+        get_binance_fetch_granularity(Granularity::h4),
+        Granularity::h4
+    );
+    // klines are cached by day, so coarser ones are fetched daily
+    assert_eq!(
+        get_binance_fetch_granularity(Granularity::M1),
+        Granularity::d1
+    );
+}
+
+#[test]
+fn test_warmup_klines_are_fetched_ahead_of_benchmark_window_shorter_than_lookback() {
+    let warmup_klines = Strategy::default().get_warmup_klines() as i32;
+    let benchmark_start = get_datetime(12, 0, 0);
+    let benchmark_end = get_datetime(12, 2, 0);
+
+    let (fetch_start, fetch_end) = adjust_benchmark_datetimes(
+        BenchmarkWindow::Absolute(Some(benchmark_start), Some(benchmark_end)),
+        Duration::minutes(1),
+        None,
+        warmup_klines,
+    )
+    .unwrap();
+
+    assert!(warmup_klines > 2);
+    assert_eq!(
+        fetch_start,
+        benchmark_start - Duration::minutes(warmup_klines as i64)
+    );
+    assert_eq!(fetch_end, benchmark_end);
+}
+
+#[test]
+fn test_depth_updates_are_parsed_into_order_book_imbalance() {
+    let json = r#"{"e":"depthUpdate","E":1704067200000,"T":1704067199990,"s":"BTCUSDT","U":1,"u":2,"pu":0,"b":[["42000.10","3.0"],["42000.00","1.0"]],"a":[["42000.20","1.0"],["42000.30","invalid"]]}"#;
+
+    let IncomingWsMessage::Depth(depth) = serde_json::from_str(json).unwrap() else {
+        panic!("expected depth message");
+    };
+    let depth = from_depth_to_order_book_depth(depth);
+
+    assert_eq!(depth.symbol, "BTCUSDT");
+    assert_eq!(depth.timestamp, 1704067200000);
+    assert_eq!(depth.bids, vec![(42000.1, 3.0), (42000.0, 1.0)]);
+    // unparseable levels are skipped
+    assert_eq!(depth.asks, vec![(42000.2, 1.0)]);
+    // (4 - 1) / (4 + 1)
+    assert_eq!(depth.get_imbalance(), Some(0.6));
+}
+
+#[test]
+fn test_ws_frames_are_dropped_as_unparseable_or_unhandled() {
+    let data_provider = BinanceDataProvider::new(&TradingSettings::default(), &Strategy::default());
+    let get_dropped_frames = |reason: &str| {
+        METRICS
+            .ws_dropped_frames
+            .with_label_values(&["binance", reason])
+            .get()
+    };
+    let (unparseable, unhandled) = (
+        get_dropped_frames("unparseable"),
+        get_dropped_frames("unhandled"),
+    );
+
+    // subscription acknowledgements are recognized, albeit not handled
+    let ack = data_provider.parse_ws_message::<IncomingWsMessage>(r#"{"result":null,"id":1}"#);
+    assert!(matches!(ack, Some(IncomingWsMessage::Response(_))));
+    data_provider.drop_unhandled_ws_message(ack.unwrap());
+
+    // whereas changed message formats fail to be parsed
+    let changed = r#"{"e":"kline","E":1704067200000,"s":"BTCUSDT","kline":{}}"#;
+    assert!(data_provider
+        .parse_ws_message::<IncomingWsMessage>(changed)
+        .is_none());
+
+    assert_eq!(get_dropped_frames("unparseable"), unparseable + 1);
+    assert_eq!(get_dropped_frames("unhandled"), unhandled + 1);
+}
+
+fn get_ws_frames(
+    jsons: &[&str],
+) -> impl tokio_stream::Stream<Item = Result<Message, TungsteniteError>> {
+    let frames: Vec<_> = jsons
+        .iter()
+        .map(|json| Ok(Message::Text(json.to_string())))
+        .collect();
+    tokio_stream::iter(frames)
+}
+
+#[tokio::test]
+async fn test_subscription_is_acknowledged_by_response_of_its_id() {
+    let data_provider = BinanceDataProvider::new(&TradingSettings::default(), &Strategy::default());
+
+    // responses to other requests are skipped until subscription's own
+    let mut wss = get_ws_frames(&[r#"{"result":null,"id":2}"#, r#"{"result":null,"id":1}"#]);
+    assert!(data_provider
+        .await_subscription_response(&mut wss, 1)
+        .await
+        .is_ok());
+
+    let mut wss = get_ws_frames(&[r#"{"result":null,"id":2}"#]);
+    let error = data_provider
+        .await_subscription_response(&mut wss, 1)
+        .await
+        .unwrap_err();
+    assert_eq!(error.title, "WebSocket Subscription Error");
+}
+
+#[tokio::test]
+async fn test_subscription_rejection_is_returned_as_error() {
+    let data_provider = BinanceDataProvider::new(&TradingSettings::default(), &Strategy::default());
+    let rejection = r#"{"error":{"code":2,"msg":"Invalid request: unknown stream"},"id":1}"#;
+    let mut wss = get_ws_frames(&[rejection, r#"{"result":null,"id":1}"#]);
+
+    let error = data_provider
+        .await_subscription_response(&mut wss, 1)
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.title, "WebSocket Subscription Error");
+    assert!(error
+        .description
+        .contains("Invalid request: unknown stream"));
+}
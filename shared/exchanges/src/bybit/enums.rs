@@ -75,6 +75,21 @@ pub enum CancelType {
     CancelBySmp,
 }
 
+/// How an order was created. Orders closing positions on exchange's behalf, other than upon
+/// user set stops, are told apart from the rest, which are kept as `Other`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum CreateType {
+    #[serde(rename = "CreateByLiq")]
+    Liquidation,
+    #[serde(rename = "CreateByTakeOver_PassThrough")]
+    TakeOver,
+    #[serde(rename = "CreateByAdl_PassThrough")]
+    AutoDeleveraging,
+    #[default]
+    #[serde(other)]
+    Other,
+}
+
 #[derive(Clone, Deserialize, Debug)]
 pub enum RejectReason {
     #[serde(rename = "EC_NoError")]
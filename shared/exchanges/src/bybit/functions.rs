@@ -1,6 +1,13 @@
 use super::enums::AdlRankIndicator;
+use glow_error::GlowError;
+use reqwest::{header::HeaderMap, StatusCode};
 use serde::Deserialize;
 use serde::Deserializer;
+use serde_json::{from_str, Value};
+
+/// Bybit's "too many visits" and "IP rate limit exceeded" return codes
+const RATE_LIMIT_RET_CODES: [i64; 2] = [10006, 10018];
+const RATE_LIMIT_RESET_HEADER: &str = "X-Bapi-Limit-Reset-Timestamp";
 
 pub fn parse_f64_option<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
 where
@@ -39,3 +46,29 @@ where
         ))),
     }
 }
+
+/// Checks whether Bybit response was rejected by rate limits, either by its HTTP status or by
+/// its `retCode`, which Bybit may return alongside a successful status.
+pub fn get_rate_limit_error(
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+) -> Option<GlowError> {
+    let ret_code = from_str::<Value>(body)
+        .ok()
+        .and_then(|value| value.get("retCode")?.as_i64());
+    let is_rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+        || ret_code.is_some_and(|ret_code| RATE_LIMIT_RET_CODES.contains(&ret_code));
+    if !is_rate_limited {
+        return None;
+    }
+    let reset_timestamp = headers
+        .get(RATE_LIMIT_RESET_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("unknown");
+    let description = format!(
+        "status {}, ret code {:?}, limit resets at {}",
+        status, ret_code, reset_timestamp
+    );
+    Some(GlowError::new_rate_limit_error(description))
+}
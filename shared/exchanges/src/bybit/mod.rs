@@ -3,28 +3,31 @@ pub mod functions;
 pub mod structs;
 use self::enums::BybitWsMessage;
 use self::structs::{
-    AmendOrderDto, EmptyObject, ExecutionData, FetchCurrentOrderDto, FetchExecutionsDto,
-    FetchHistoryOrderDto, FetchPositionDto, OrderData, OrderResponse, PositionResponseData,
-    SetLeverageDto, WsRequest,
+    AmendOrderDto, CoinData, EmptyObject, ExecutionData, FetchCurrentOrderDto, FetchExecutionsDto,
+    FetchHistoryOrderDto, FetchInstrumentsInfoDto, FetchPositionDto, FetchTickerDto,
+    InstrumentInfoData, OrderData, OrderResponse, PositionResponseData, SetLeverageDto, TickerData,
+    WsRequest,
 };
 use crate::enums::TraderExchangeId;
 use crate::r#static::TRADER_EXCHANGES_CONTEXT_MAP;
 use crate::{
-    config::{TRADER_EXCHANGES_CONFIG_MAP, WS_RECONNECT_INTERVAL_IN_SECS},
-    structs::{ApiCredentials, ApiEndpoints},
+    config::{get_trader_exchange_config, WS_RECONNECT_INTERVAL_IN_SECS},
+    structs::{ApiCredentials, ApiEndpoints, ExchangeConfig},
 };
-use common::constants::SECONDS_IN_MIN;
+use common::enums::log_level::LogLevel;
 use common::enums::order_action::OrderAction;
 use common::enums::symbol_id::SymbolId;
+use common::enums::trading_data_update::TradingDataUpdate;
 use common::functions::{
     current_datetime, current_timestamp, current_timestamp_ms, timestamp_minute_end,
 };
+use common::r#static::METRICS;
 use common::traits::exchange::{BenchmarkExchange, TraderHelper};
 use common::{
     enums::{
         balance::Balance,
         http_method::HttpMethod,
-        modifiers::{leverage::Leverage, position_lock::PositionLock},
+        modifiers::leverage::Leverage,
         order_stage::OrderStage,
         order_status::OrderStatus,
         order_type::OrderType,
@@ -33,12 +36,17 @@ use common::{
         trade_status::TradeStatus,
     },
     functions::{
-        calculate_hmac, calculate_remainder, count_decimal_places, round_down_nth_decimal,
+        calculate_hmac, calculate_remainder, count_decimal_places, is_at_threshold,
+        round_down_nth_decimal,
+    },
+    structs::{
+        BehaviorSubject, Contract, Execution, LogEvent, Order, OrderAmendment, Ticker, Trade,
+        TradingSettings, TrailingStop,
     },
-    structs::{BehaviorSubject, Contract, Execution, Order, Trade, TradingSettings},
     traits::exchange::TraderExchange,
 };
-use enums::AccountType;
+use enums::{AccountType, BybitOrderStatus};
+use functions::get_rate_limit_error;
 use futures_util::SinkExt;
 use glow_error::GlowError;
 use reqwest::{
@@ -50,26 +58,34 @@ use serde_json::{from_str, to_string as to_json_string};
 use serde_urlencoded::to_string as to_url_string;
 use std::{collections::HashMap, sync::Arc, sync::Mutex, time::Duration};
 use structs::{
-    BybitHttpResponseWrapper, CancelOrderDto, CreateOrderDto, FetchWalletBalanceDto,
-    HttpResultList, PingWsMessage, WalletData,
+    BybitHttpResponseWrapper, CancelAllOrdersDto, CancelOrderDto, CreateOrderDto, FetchWalletBalanceDto,
+    EmptyDto, HttpResultList, PingWsMessage, SetPartialTakeProfitDto, SetStopLossDto, WalletData,
 };
 use tokio::{
     net::TcpStream,
     select,
     time::sleep,
-    time::{interval, sleep_until, Instant, Interval},
+    time::{interval, timeout, Interval},
 };
 use tokio_stream::StreamExt;
 use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
 use url::Url;
 // pub mod tests;
 
+/// Bybit's "reduce-only rule not satisfied" return code, i.e. there's no position to be reduced
+const REDUCE_ONLY_WITHOUT_POSITION_RET_CODE: i32 = 110017;
+/// Bybit's "order not exists or too late to cancel" return code, i.e. order was already finalized
+const ORDER_NOT_EXISTS_OR_TOO_LATE_RET_CODE: i32 = 110001;
+
 #[derive(Clone)]
 pub struct BybitTraderExchange {
     balance_update_emitter: BehaviorSubject<Balance>,
-    pub contracts: &'static HashMap<SymbolId, Contract>,
+    pub contracts: Arc<HashMap<SymbolId, Contract>>,
     credentials: ApiCredentials,
     endpoints: ApiEndpoints,
+    // endpoints and credentials websocket must reconnect with, once environment is switched
+    exchange_config_emitter: BehaviorSubject<ExchangeConfig>,
+    exchange_recovery_emitter: BehaviorSubject<TradingDataUpdate>,
     executions_update_emitter: BehaviorSubject<Vec<Execution>>,
     pub fee_rates: (f64, f64),
     http: Client,
@@ -79,13 +95,19 @@ pub struct BybitTraderExchange {
     pub trading_settings: TradingSettings,
     order_update_emitter: BehaviorSubject<OrderAction>,
     trade_update_emitter: BehaviorSubject<Option<Trade>>,
+    trailing_stop: Arc<Mutex<TrailingStop>>,
 }
 
 impl BybitTraderExchange {
-    pub fn new(trading_settings: &TradingSettings) -> Self {
-        let config = TRADER_EXCHANGES_CONFIG_MAP
-            .get(&TraderExchangeId::Bybit)
-            .expect("Bybit to has Exchange Config");
+    /// Fails if settings' exchange environment config isn't provided, so that trader never
+    /// falls back to a different environment than the selected one.
+    pub fn new(trading_settings: &TradingSettings) -> Result<Self, GlowError> {
+        let config =
+            get_trader_exchange_config(TraderExchangeId::Bybit, trading_settings.environment)?;
+        Ok(Self::from_config(trading_settings, config))
+    }
+
+    pub fn from_config(trading_settings: &TradingSettings, config: ExchangeConfig) -> Self {
         let context = TRADER_EXCHANGES_CONTEXT_MAP
             .get(&TraderExchangeId::Bybit)
             .expect("Bybit to has Exchange Context");
@@ -104,16 +126,19 @@ impl BybitTraderExchange {
         headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
 
         let balance_update_emitter = BehaviorSubject::new(Balance::default());
+        let exchange_recovery_emitter = BehaviorSubject::new(TradingDataUpdate::default());
         let executions_update_emitter = BehaviorSubject::new(vec![]);
         let order_update_emitter = BehaviorSubject::new(OrderAction::default());
         let trade_update_emitter = BehaviorSubject::new(None);
 
         Self {
             balance_update_emitter,
-            contracts: &context.contracts,
+            contracts: Arc::new(context.contracts.clone()),
             credentials: config.credentials,
-            executions_update_emitter,
             endpoints: config.endpoints,
+            exchange_config_emitter: BehaviorSubject::new(config),
+            exchange_recovery_emitter,
+            executions_update_emitter,
             fee_rates: (context.maker_fee, context.taker_fee),
             http: Client::builder()
                 .default_headers(headers)
@@ -125,11 +150,393 @@ impl BybitTraderExchange {
             order_update_emitter,
             trade_update_emitter,
             trading_settings: trading_settings.clone(),
+            trailing_stop: Arc::new(Mutex::new(TrailingStop::default())),
+        }
+    }
+
+    /// Closes `close_order` and emits it, as trade's position was already closed out-of-band
+    /// (e.g. manually or by liquidation), so no order update is going to come from exchange.
+    /// It's closed by the executions that actually closed the position, if exchange reports them,
+    /// otherwise it's flagged as closed out-of-band at `est_price`.
+    async fn close_flat_trade_order(
+        &self,
+        trade: &Trade,
+        close_order: Order,
+        est_price: f64,
+    ) -> Order {
+        let now = current_timestamp_ms() as i64;
+        let mut closing_executions: Vec<Execution> = self
+            .fetch_executions(None, trade.open_order.created_at, now)
+            .await
+            .unwrap_or_else(|error| {
+                println!(
+                    "close_flat_trade_order -> failed to fetch closing executions of trade {}: {:?}",
+                    trade.id, error
+                );
+                vec![]
+            })
+            .into_iter()
+            .filter(|execution| execution.order_uuid != trade.open_order.uuid)
+            .collect();
+        // exchange lists most recent executions first
+        closing_executions.sort_by_key(|execution| execution.timestamp);
+        let close_order = close_order
+            .close_by_executions(closing_executions)
+            .unwrap_or_else(|| close_order.close_out_of_band(est_price, now));
+        self.order_update_emitter.next(OrderAction::Update(close_order.clone()));
+        close_order
+    }
+
+    /// Fetches traded symbol executions between `start_timestamp` and `end_timestamp`,
+    /// optionally only those of order with `order_uuid`.
+    async fn fetch_executions(
+        &self,
+        order_uuid: Option<String>,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<Execution>, GlowError> {
+        let traded_symbol = self.get_traded_symbol();
+        let payload = FetchExecutionsDto {
+            category: "linear".to_string(),
+            order_uuid,
+            symbol: traded_symbol.name.to_string(),
+            start_timestamp,
+            end_timestamp,
+        };
+
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Get, "/v5/execution/list", &payload)?;
+
+        let result = request_builder.send().await;
+
+        let parsed_response = Self::try_parse_response::<
+            BybitHttpResponseWrapper<HttpResultList<ExecutionData>>,
+        >(result)
+        .await?;
+
+        if parsed_response.ret_code != 0 || parsed_response.ret_message != "OK" {
+            return Ok(vec![]);
+        }
+
+        let executions: Vec<Execution> = parsed_response
+            .result
+            .list
+            .into_iter()
+            .map(|execution_response| execution_response.into())
+            .collect();
+        Ok(executions)
+    }
+
+    /// Posts trade's `close_order`. If `reduce_only` and there's no position left to be reduced,
+    /// it's closed right away at `est_price`.
+    async fn post_close_order(
+        &self,
+        trade: &Trade,
+        close_order: Order,
+        est_price: f64,
+        reduce_only: bool,
+    ) -> Result<Order, GlowError> {
+        let mut close_order = close_order;
+        let close_order_id = close_order.id.clone();
+        let mut payload: CreateOrderDto = close_order.clone().into();
+        payload.reduce_only = reduce_only;
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Post, "/v5/order/create", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response =
+            Self::try_parse_response::<BybitHttpResponseWrapper<OrderResponse>>(result).await?;
+        if reduce_only && parsed_response.ret_code == REDUCE_ONLY_WITHOUT_POSITION_RET_CODE {
+            println!(
+                "try_close_position -> no position left to reduce, trade {} is already flat",
+                trade.id
+            );
+            return Ok(self
+                .close_flat_trade_order(trade, close_order, est_price)
+                .await);
+        }
+        if parsed_response.ret_code == 0
+            && parsed_response.ret_message == "OK"
+            && parsed_response.result.order_link_id == close_order_id
+        {
+            close_order.uuid = parsed_response.result.order_id;
+            Ok(close_order)
+        } else {
+            let error = format!(
+                "try_close_position -> parsed response {:?}",
+                parsed_response
+            );
+            Err(GlowError::new(String::from("Close Position Error"), error))
+        }
+    }
+
+    /// Cancels maker close order `close_order_id` if it's still resting, closing trade's units left
+    /// at market instead, by an order that supersedes it as trade's close order. Should market
+    /// close fail, position is flattened by another superseding order, so that it's closed
+    /// regardless. Returns market close order, if it was posted.
+    async fn fall_back_to_market_close(
+        &self,
+        trade: &Trade,
+        close_order_id: String,
+        est_price: f64,
+    ) -> Result<Option<Order>, GlowError> {
+        let parsed_response = self.post_cancel_order(&close_order_id).await?;
+        if parsed_response.ret_code == ORDER_NOT_EXISTS_OR_TOO_LATE_RET_CODE {
+            // maker close order was already filled, or finalized otherwise
+            return Ok(None);
+        }
+        if parsed_response.ret_code != 0 || parsed_response.ret_message != "OK" {
+            let error = format!(
+                "fall_back_to_market_close -> maker close order {} wasn't cancelled {:?}",
+                close_order_id, parsed_response
+            );
+            return Err(GlowError::new(String::from("Close Position Error"), error));
+        }
+        // latest trade accounts for maker close order partial fills
+        let trade = self
+            .trade_update_emitter
+            .value()
+            .filter(|latest_trade| latest_trade.id == trade.id)
+            .unwrap_or_else(|| trade.clone());
+        let mut market_close_order = trade.new_fallback_close_order(est_price)?;
+        market_close_order.units = self
+            .get_traded_contract()
+            .round_qty_to_step(market_close_order.units)?;
+        match self
+            .post_close_order(&trade, market_close_order, est_price, true)
+            .await
+        {
+            Ok(market_close_order) => {
+                if market_close_order.status != OrderStatus::Closed {
+                    // records it as trade's close order, so that its fills relate to trade
+                    self.order_update_emitter
+                        .next(OrderAction::Update(market_close_order.clone()));
+                }
+                Ok(Some(market_close_order))
+            }
+            Err(error) => {
+                println!(
+                    "fall_back_to_market_close -> market close failed, flattening position {:?}",
+                    error
+                );
+                self.cancel_all_orders().await?;
+                let flatten_order_id = trade.get_order_id(OrderStage::Flatten);
+                self.post_flatten_orders(|_| flatten_order_id.clone())
+                    .await?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Resolves once trade `trade_id` is closed, or no longer the current one.
+    async fn wait_for_trade_close(&self, trade_id: &str) {
+        let mut trade_updates = self.trade_update_emitter.subscribe();
+        while let Some(trade) = trade_updates.next().await {
+            let is_closed = match trade {
+                Some(trade) if trade.id == trade_id => trade.close_order.is_some_and(|order| {
+                    matches!(
+                        order.status,
+                        OrderStatus::Closed
+                            | OrderStatus::StoppedBR
+                            | OrderStatus::StoppedSL
+                            | OrderStatus::StoppedTP
+                    )
+                }),
+                _ => true,
+            };
+            if is_closed {
+                return;
+            }
+        }
+    }
+
+    async fn post_cancel_order(
+        &self,
+        order_id: &str,
+    ) -> Result<BybitHttpResponseWrapper<OrderResponse>, GlowError> {
+        let traded_symbol = self.get_traded_symbol();
+        let payload = CancelOrderDto::new(
+            order_id.to_string(),
+            "linear".to_string(),
+            traded_symbol.name.to_string(),
+        );
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Post, "/v5/order/cancel", &payload)?;
+        let result = request_builder.send().await;
+        Self::try_parse_response::<BybitHttpResponseWrapper<OrderResponse>>(result).await
+    }
+
+    /// Closes traded symbol open positions at market by reduce-only orders, identified by
+    /// `get_order_id`.
+    async fn post_flatten_orders(
+        &self,
+        get_order_id: impl Fn(&PositionResponseData) -> String,
+    ) -> Result<(), GlowError> {
+        let traded_symbol = self.get_traded_symbol();
+        let payload = FetchPositionDto {
+            category: "linear".to_string(),
+            symbol: traded_symbol.name.to_string(),
+        };
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Get, "/v5/position/list", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<
+            BybitHttpResponseWrapper<HttpResultList<PositionResponseData>>,
+        >(result)
+        .await?;
+
+        for position in parsed_response.result.list {
+            if position.side == Side::None || position.size == 0.0 {
+                continue;
+            }
+            let close_side = position.side.get_opposite_side()?;
+            let order_id = get_order_id(&position);
+            let payload = CreateOrderDto::new_reduce_only_market_order(
+                order_id.clone(),
+                position.symbol.clone(),
+                close_side,
+                position.size,
+            );
+            let request_builder =
+                self.prepare_request_builder(HttpMethod::Post, "/v5/order/create", &payload)?;
+            let result = request_builder.send().await;
+            let parsed_response =
+                Self::try_parse_response::<BybitHttpResponseWrapper<OrderResponse>>(result)
+                    .await?;
+            if parsed_response.ret_code != 0
+                || parsed_response.ret_message != "OK"
+                || parsed_response.result.order_link_id != order_id
+            {
+                let error = format!("flatten_positions -> parsed response {:?}", parsed_response);
+                return Err(GlowError::new(String::from("Flatten Position Error"), error));
+            }
         }
+
+        Ok(())
     }
 
-    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
+    /// Fetches contract account's `coin` wallet data, if any is held, along with response time.
+    async fn fetch_wallet_coin_data(
+        &self,
+        coin: &str,
+    ) -> Result<(i64, Option<CoinData>), GlowError> {
+        let payload = FetchWalletBalanceDto::new(AccountType::Contract, Some(coin.to_string()));
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Get, "/v5/account/wallet-balance", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<
+            BybitHttpResponseWrapper<HttpResultList<WalletData>>,
+        >(result)
+        .await?;
+
+        let coin_data = parsed_response
+            .result
+            .list
+            .into_iter()
+            .find_map(|wallet_data| {
+                wallet_data
+                    .coin
+                    .into_iter()
+                    .find(|coin_data| coin_data.coin == coin)
+            });
+
+        Ok((parsed_response.time, coin_data))
+    }
+
+    /// Switching exchange environment swaps REST endpoints and credentials, and has websocket
+    /// reconnect to the switched one. Settings are left unchanged if its config isn't provided.
+    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) -> Result<(), GlowError> {
+        if trading_settings.environment != self.trading_settings.environment {
+            let config =
+                get_trader_exchange_config(TraderExchangeId::Bybit, trading_settings.environment)?;
+            self.credentials = config.credentials;
+            self.endpoints = config.endpoints;
+            self.exchange_config_emitter.next(config);
+        }
         self.trading_settings = trading_settings.clone();
+        Ok(())
+    }
+
+    /// Replaces stored contracts by `fetched_contracts`, keeping static ones missing from them.
+    pub fn patch_contracts(&mut self, fetched_contracts: HashMap<String, Contract>) {
+        let mut contracts = self.contracts.as_ref().clone();
+        for contract in fetched_contracts.into_values() {
+            contracts.insert(contract.symbol.id, contract);
+        }
+        self.contracts = Arc::new(contracts);
+    }
+
+    /// Trails stepped trailing take profit ("ttp") by amending position's stop loss, given the most
+    /// favorable `price` trade reached since last call, e.g. last bar's high for long positions.
+    ///
+    /// Stop is only amended once peak returns advance a step, to the price at which position has
+    /// the step's returns, same as benchmark closes stepped trailing take profits. Returns whether
+    /// stop was amended.
+    pub async fn update_trailing_stop(&self, trade: &Trade, price: f64) -> Result<bool, GlowError> {
+        let Some(trailing_take_profit) = self.trading_settings.get_stepped_trailing_take_profit()
+        else {
+            return Ok(false);
+        };
+        let open_order = &trade.open_order;
+        let open_price = open_order.get_executed_avg_price();
+        if open_price <= 0.0 {
+            return Ok(false);
+        }
+        let price_change = match open_order.side {
+            Side::Buy => price - open_price,
+            Side::Sell => open_price - price,
+            Side::None => return Ok(false),
+        };
+        let returns = price_change * self.get_leverage_factor() / open_price;
+        let stop_returns = self
+            .trailing_stop
+            .lock()
+            .expect("update_trailing_stop -> trailing stop deadlock")
+            .next(
+                &trade.id,
+                returns,
+                &trailing_take_profit,
+                self.trading_settings.get_price_level_epsilon(),
+            );
+        let Some(stop_returns) = stop_returns else {
+            return Ok(false);
+        };
+        let Some(stop_loss_price) =
+            self.calculate_returns_price(open_order.side, open_price, stop_returns)
+        else {
+            return Ok(false);
+        };
+
+        let payload = SetStopLossDto::new(
+            "linear".to_string(),
+            self.get_traded_contract().symbol.name.to_string(),
+            stop_loss_price,
+        );
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Post, "/v5/position/trading-stop", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response =
+            Self::try_parse_response::<BybitHttpResponseWrapper<EmptyObject>>(result).await?;
+        if parsed_response.ret_code != 0 || parsed_response.ret_message != "OK" {
+            println!(
+                "update_trailing_stop -> unexpected response {:?}",
+                parsed_response
+            );
+            // lets next call retry amending stop to the same step
+            self.trailing_stop
+                .lock()
+                .expect("update_trailing_stop -> trailing stop deadlock")
+                .stop_returns = None;
+            return Ok(false);
+        }
+        println!(
+            "\n{:?} | 🪜 {:?} position stop trailed to {} price ({} returns)",
+            current_datetime(),
+            open_order.side,
+            stop_loss_price,
+            stop_returns
+        );
+
+        Ok(true)
     }
 
     async fn try_parse_response<T: DeserializeOwned>(
@@ -140,11 +547,19 @@ impl BybitTraderExchange {
         }
 
         let response = result.unwrap();
-        if !response.status().is_success() {
+        let status = response.status();
+        let headers = response.headers().clone();
+        if !status.is_success() {
+            if let Some(error) = get_rate_limit_error(status, &headers, "") {
+                return Err(error);
+            }
             let description = format!("try_response -> unsucessful response {:?}", response);
             return Err(GlowError::new_unsuccessful_response(description));
         }
         let response_text = response.text().await?;
+        if let Some(error) = get_rate_limit_error(status, &headers, &response_text) {
+            return Err(error);
+        }
         let parsed_response = from_str::<T>(&response_text)?;
         Ok(parsed_response)
     }
@@ -199,12 +614,35 @@ impl BybitTraderExchange {
             self.append_request_headers(request_builder, timestamp, signature, recv_window);
         Ok(request_builder)
     }
+
+    /// Calculates ((open fee, close fee), fee rate, is maker) for a given (fee rate, is maker) pair
+    fn calculate_order_fees_at_rate(
+        &self,
+        fee_rate_and_is_maker: (f64, bool),
+        side: Side,
+        units: f64,
+        price: f64,
+    ) -> ((f64, f64), f64, bool) {
+        let (fee_rate, is_maker) = fee_rate_and_is_maker;
+        let trading_settings = self.get_trading_settings();
+        let leverage_factor = trading_settings.leverage.get_factor();
+        // close fee is estimated at bankruptcy price
+        let ((open_fee, close_fee), _) = self.get_traded_contract().kind.calculate_order_cost(
+            side,
+            units,
+            price,
+            leverage_factor,
+            fee_rate,
+        );
+
+        ((open_fee, close_fee), fee_rate, is_maker)
+    }
 }
 
 impl TraderHelper for BybitTraderExchange {
     #[inline]
     fn get_contracts(&self) -> &HashMap<SymbolId, Contract> {
-        self.contracts
+        &self.contracts
     }
     #[inline]
     fn get_maker_fee(&self) -> f64 {
@@ -342,22 +780,8 @@ impl TraderHelper for BybitTraderExchange {
         units: f64,
         price: f64,
     ) -> ((f64, f64), f64, bool) {
-        let trading_settings = self.get_trading_settings();
-        let leverage_factor = trading_settings.leverage.get_factor();
-        let (fee_rate, is_maker) = self.get_order_fee_rate(order_type);
-        let open_fee = units * price * fee_rate;
-        let bankruptcy_price = if side == Side::Sell {
-            // Bankruptcy Price for Short Position = Order Price × ( Leverage + 1) / Leverage
-            price * (leverage_factor + 1.0) / leverage_factor
-        } else if side == Side::Buy {
-            // Bankruptcy Price for Long Position = Order Price × ( Leverage − 1) / Leverage
-            price * (leverage_factor - 1.0) / leverage_factor
-        } else {
-            0.0
-        };
-        let close_fee = units * bankruptcy_price * fee_rate;
-
-        ((open_fee, close_fee), fee_rate, is_maker)
+        let fee_rate_and_is_maker = self.get_order_fee_rate(order_type);
+        self.calculate_order_fees_at_rate(fee_rate_and_is_maker, side, units, price)
     }
 
     fn calculate_order_stop_loss_price(&self, side: Side, price: f64) -> Option<f64> {
@@ -569,6 +993,47 @@ impl TraderExchange for BybitTraderExchange {
 
                 let order_response = order_response.unwrap();
 
+                if order_response.is_liquidation() {
+                    if order_response.order_status != BybitOrderStatus::Filled {
+                        return Ok(());
+                    }
+                    let logger = self.get_trading_settings().get_logger();
+                    let Some(current_trade) = self.trade_update_emitter.value() else {
+                        logger.log(
+                            LogEvent::new(
+                                LogLevel::All,
+                                "liquidation_skipped",
+                                format!(
+                                    "Liquidation order {} received without a current trade",
+                                    order_response.order_id
+                                ),
+                            )
+                            .with_field("order_id", &order_response.order_id),
+                        );
+                        return Ok(());
+                    };
+                    logger.log(
+                        LogEvent::new(
+                            LogLevel::Trades,
+                            "position_liquidated",
+                            format!(
+                                "💀 Position was liquidated by exchange {:?}",
+                                order_response.create_type
+                            ),
+                        )
+                        .with_field("trade_id", &current_trade.id)
+                        .with_field("order_id", &order_response.order_id),
+                    );
+                    let liquidation_order = order_response.new_liquidation_close_order(
+                        &current_trade,
+                        self.get_leverage_factor(),
+                        self.get_taker_fee(),
+                    );
+                    self.order_update_emitter
+                        .next(OrderAction::Stop(liquidation_order));
+                    return Ok(());
+                }
+
                 if order_response.is_cancel() {
                     let cancelled_order = order_response.new_order_from_response_data(
                         self.get_leverage_factor(),
@@ -627,36 +1092,8 @@ impl TraderExchange for BybitTraderExchange {
         start_timestamp: i64,
         end_timestamp: i64,
     ) -> Result<Vec<Execution>, GlowError> {
-        let traded_symbol = self.get_traded_symbol();
-        let payload = FetchExecutionsDto {
-            category: "linear".to_string(),
-            order_uuid,
-            symbol: traded_symbol.name.to_string(),
-            start_timestamp,
-            end_timestamp,
-        };
-
-        let request_builder =
-            self.prepare_request_builder(HttpMethod::Get, "/v5/execution/list", &payload)?;
-
-        let result = request_builder.send().await;
-
-        let parsed_response = Self::try_parse_response::<
-            BybitHttpResponseWrapper<HttpResultList<ExecutionData>>,
-        >(result)
-        .await?;
-
-        if parsed_response.ret_code != 0 || parsed_response.ret_message != String::from("OK") {
-            return Ok(vec![]);
-        }
-
-        let executions: Vec<Execution> = parsed_response
-            .result
-            .list
-            .into_iter()
-            .map(|execution_response| execution_response.into())
-            .collect();
-        Ok(executions)
+        self.fetch_executions(Some(order_uuid), start_timestamp, end_timestamp)
+            .await
     }
 
     async fn fetch_history_order(
@@ -1080,32 +1517,109 @@ impl TraderExchange for BybitTraderExchange {
     }
 
     async fn fetch_current_usdt_balance(&self) -> Result<Balance, GlowError> {
-        let payload = FetchWalletBalanceDto::new(AccountType::Contract, Some("USDT".to_string()));
-        let request_builder =
-            self.prepare_request_builder(HttpMethod::Get, "/v5/account/wallet-balance", &payload)?;
-        let result = request_builder.send().await;
-        let parsed_response = Self::try_parse_response::<
-            BybitHttpResponseWrapper<HttpResultList<WalletData>>,
-        >(result)
-        .await?;
-
-        let usdt_coin_data = parsed_response.result.list.iter().find_map(|wallet_data| {
-            wallet_data
-                .coin
-                .iter()
-                .find(|coin_data| coin_data.coin == "USDT".to_string())
-        });
+        let (time, usdt_coin_data) = self.fetch_wallet_coin_data("USDT").await?;
 
         let usdt_data = usdt_coin_data.expect("get_current_usdt_balance -> missing usdt coin data");
 
         let balance = Balance::new(
-            parsed_response.time,
+            time,
             usdt_data.available_to_withdraw,
             usdt_data.wallet_balance,
         );
         Ok(balance)
     }
 
+    async fn fetch_base_asset_balance(&self) -> Result<f64, GlowError> {
+        let base_asset = self.get_traded_symbol().get_base_asset();
+        let (_, base_asset_data) = self.fetch_wallet_coin_data(base_asset).await?;
+        Ok(base_asset_data
+            .map(|coin_data| coin_data.wallet_balance)
+            .unwrap_or_default())
+    }
+
+    async fn fetch_ticker(&self) -> Result<Ticker, GlowError> {
+        let traded_symbol = self.get_traded_symbol();
+        let payload = FetchTickerDto {
+            category: "linear".to_string(),
+            symbol: traded_symbol.name.to_string(),
+        };
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Get, "/v5/market/tickers", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<
+            BybitHttpResponseWrapper<HttpResultList<TickerData>>,
+        >(result)
+        .await?;
+
+        let ticker_data = parsed_response
+            .result
+            .list
+            .into_iter()
+            .find(|ticker_data| ticker_data.symbol == traded_symbol.name)
+            .ok_or(GlowError::new(
+                String::from("Fetch Ticker Error"),
+                format!("fetch_ticker -> missing {} ticker data", traded_symbol.name),
+            ))?;
+
+        Ok(Ticker::new(
+            parsed_response.time,
+            ticker_data.bid_price,
+            ticker_data.ask_price,
+            ticker_data.last_price,
+        ))
+    }
+
+    async fn fetch_instruments_info(&self) -> Result<HashMap<String, Contract>, GlowError> {
+        let mut contracts = HashMap::new();
+        for symbol in self.get_unique_symbols() {
+            let payload = FetchInstrumentsInfoDto {
+                category: "linear".to_string(),
+                symbol: symbol.name.to_string(),
+            };
+            let request_builder = self.prepare_request_builder(
+                HttpMethod::Get,
+                "/v5/market/instruments-info",
+                &payload,
+            )?;
+            let result = request_builder.send().await;
+            let parsed_response = Self::try_parse_response::<
+                BybitHttpResponseWrapper<HttpResultList<InstrumentInfoData>>,
+            >(result)
+            .await?;
+
+            let contract = parsed_response
+                .result
+                .list
+                .into_iter()
+                .find(|instrument| instrument.symbol == symbol.name)
+                .and_then(|instrument| instrument.to_contract(self.contracts.get(&symbol.id)))
+                .ok_or(GlowError::new(
+                    String::from("Fetch Instruments Info Error"),
+                    format!(
+                        "fetch_instruments_info -> missing {} instrument info",
+                        symbol.name
+                    ),
+                ))?;
+            contracts.insert(symbol.name.to_string(), contract);
+        }
+
+        Ok(contracts)
+    }
+
+    async fn server_time(&self) -> Result<i64, GlowError> {
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Get, "/v5/market/time", &EmptyDto {})?;
+        let result = request_builder.send().await;
+        let parsed_response =
+            Self::try_parse_response::<BybitHttpResponseWrapper<EmptyObject>>(result).await?;
+        if parsed_response.ret_code != 0 {
+            let error = format!("server_time -> unexpected response {:?}", parsed_response);
+            return Err(GlowError::new(String::from("Wrong Response Error"), error));
+        }
+
+        Ok(parsed_response.time)
+    }
+
     async fn open_order(
         &self,
         side: Side,
@@ -1114,8 +1628,8 @@ impl TraderExchange for BybitTraderExchange {
     ) -> Result<Order, GlowError> {
         assert_ne!(side, Side::None, "Invalid Open Order Side!");
         assert!(
-            expected_price <= 0.0,
-            "open_order -> expected price is less than 0!"
+            expected_price > 0.0,
+            "open_order -> expected price must be greater than 0!"
         );
 
         let trading_settings = self.get_trading_settings();
@@ -1139,11 +1653,19 @@ impl TraderExchange for BybitTraderExchange {
                 );
             }
         }
+        let expected_price = traded_contract.round_price_to_tick(expected_price);
         let order_cost = total_balance * trading_settings.allocation_percentage;
 
         let mut order = self.new_open_order(side, order_cost, expected_price)?;
+        order.units = traded_contract.round_qty_to_step(order.units)?;
         let order_id = order.id.clone();
-        let payload: CreateOrderDto = order.clone().into();
+        let mut payload: CreateOrderDto = order.clone().into();
+        if trading_settings.get_take_profit_partial_fraction().is_some()
+            || trading_settings.get_take_profit_ladder().is_some()
+        {
+            // partial take profits are set on the position, once the order is filled
+            payload = payload.without_take_profit();
+        }
         let request_builder =
             self.prepare_request_builder(HttpMethod::Post, "/v5/order/create", &payload)?;
         let result = request_builder.send().await;
@@ -1180,13 +1702,32 @@ impl TraderExchange for BybitTraderExchange {
         updated_stop_loss_price: Option<f64>,
         updated_take_profit_price: Option<f64>,
     ) -> Result<bool, GlowError> {
-        let payload = AmendOrderDto {
-            category: "linear".to_string(),
-            order_id: order_id.clone(),
+        let traded_contract = self.get_traded_contract();
+        let mut amendment = OrderAmendment::new(
+            traded_contract,
             updated_units,
             updated_price,
             updated_stop_loss_price,
             updated_take_profit_price,
+        )?;
+        // exchange rejects amends leaving order unchanged, so only changed values are sent
+        let current_trade = self.trade_update_emitter.value();
+        if let Some(current_order) = current_trade
+            .as_ref()
+            .and_then(|trade| trade.get_order_by_id(&order_id))
+        {
+            amendment = current_order.diff_amendment(amendment, traded_contract);
+        }
+        if amendment.is_empty() {
+            return Ok(true);
+        }
+        let payload = AmendOrderDto {
+            category: "linear".to_string(),
+            order_id: order_id.clone(),
+            updated_units: amendment.units,
+            updated_price: amendment.price,
+            updated_stop_loss_price: amendment.stop_loss_price,
+            updated_take_profit_price: amendment.take_profit_price,
         };
         let request_builder =
             self.prepare_request_builder(HttpMethod::Post, "/v5/order/amend", &payload)?;
@@ -1204,7 +1745,38 @@ impl TraderExchange for BybitTraderExchange {
         Ok(true)
     }
 
-    async fn try_close_position(&self, trade: &Trade, est_price: f64) -> Result<Order, GlowError> {
+    async fn scale_out_position(
+        &self,
+        _trade: &Trade,
+        units: f64,
+        price: f64,
+    ) -> Result<bool, GlowError> {
+        let traded_contract = self.get_traded_contract();
+        let payload = SetPartialTakeProfitDto::new(
+            "linear".to_string(),
+            traded_contract.symbol.name.to_string(),
+            traded_contract.round_price_to_tick(price),
+            traded_contract.round_qty_to_step(units)?,
+        );
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Post, "/v5/position/trading-stop", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response =
+            Self::try_parse_response::<BybitHttpResponseWrapper<EmptyObject>>(result).await?;
+        if parsed_response.ret_code != 0 || parsed_response.ret_message != "OK" {
+            println!("scale_out_position -> unexpected response {:?}", parsed_response);
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    async fn try_close_position(
+        &self,
+        trade: &Trade,
+        est_price: f64,
+        reduce_only: bool,
+    ) -> Result<Order, GlowError> {
         let mut est_price = est_price;
         let traded_contract = self.get_traded_contract();
         let trading_settings = self.get_trading_settings();
@@ -1226,75 +1798,79 @@ impl TraderExchange for BybitTraderExchange {
             self.fee_rates.0
         };
 
-        let mut close_order = trade.new_close_order(close_order_type, est_price)?;
+        let close_order = trade.new_close_order(close_order_type, est_price)?;
 
         // println!("try_close_position -> close order = {:?}", close_order);
 
-        match trading_settings.position_lock_modifier {
-            PositionLock::None => {}
-            PositionLock::Fee => {
-                // since we didn't assign close_order to trade, it makes sense to get unrealized profit and loss
-                // as an estimate of profit and loss, and calculate it against the sum of fees
-                let (profit_and_loss, _) = trade.calculate_unrealized_pnl_and_returns(est_price);
-                let total_fee = trade.open_order.get_executed_order_fee()
-                    + close_order.get_estimate_close_order_fee(est_fee_rate, est_price);
-                if total_fee >= profit_and_loss.abs() {
-                    let error = format!(
-                        "Trade wasn't closed due to PositionLockModifier::Fee -> profit and loss = {}, total fee = {}",
-                        profit_and_loss,
-                        total_fee
-                    );
-                    return Err(GlowError::new(String::from("Close Position Error"), error));
-                }
-            }
-            PositionLock::Loss => {
-                let (profit_and_loss, _returns) =
-                    trade.calculate_unrealized_pnl_and_returns(est_price);
-                if profit_and_loss <= 0.0 {
-                    let error = format!(
-                        "Trade wasn't closed due to PositionLockModifier::Loss -> profit and loss = {}",
-                        profit_and_loss
-                    );
-                    print!("{}", &error);
-                    return Err(GlowError::new(String::from("Close Position Error"), error));
-                }
-            }
+        let position_lock = trading_settings.position_lock_modifier;
+        let gross_profit_and_loss = trade.calculate_gross_pnl(traded_contract.kind, est_price);
+        let total_fee = trade.estimate_total_fee(traded_contract.kind, est_price, est_fee_rate);
+        if position_lock.is_close_locked(gross_profit_and_loss, total_fee) {
+            let error = format!(
+                "Trade wasn't closed due to {:?} position lock -> gross profit and loss = {}, total fee = {}",
+                position_lock, gross_profit_and_loss, total_fee
+            );
+            return Err(GlowError::new(String::from("Close Position Error"), error));
         }
 
-        let close_order_id = close_order.id.clone();
-        let payload: CreateOrderDto = close_order.clone().into();
-        let request_builder =
-            self.prepare_request_builder(HttpMethod::Post, "/v5/order/create", &payload)?;
-        let result = request_builder.send().await;
-        let parsed_response =
-            Self::try_parse_response::<BybitHttpResponseWrapper<OrderResponse>>(result).await?;
-        if parsed_response.ret_code != 0
-            || parsed_response.ret_message != "OK".to_string()
-            || parsed_response.result.order_link_id != close_order_id
+        let maker_close_timeout = trading_settings
+            .maker_close_timeout
+            .filter(|_| close_order_type == OrderType::Limit);
+        let Some(maker_close_timeout) = maker_close_timeout else {
+            return self
+                .post_close_order(trade, close_order, est_price, reduce_only)
+                .await;
+        };
+
+        let touch_price = match self.fetch_ticker().await {
+            // maker close rests at the touch: best ask for sells, best bid for buys
+            Ok(ticker) => match close_order.side {
+                Side::Sell => ticker.ask_price,
+                _ => ticker.bid_price,
+            },
+            Err(error) => {
+                println!(
+                    "try_close_position -> fetch_ticker error, using estimated price {:?}",
+                    error
+                );
+                est_price
+            }
+        };
+        let maker_close_order = trade.new_close_order(
+            OrderType::Limit,
+            traded_contract.round_price_to_tick(touch_price),
+        )?;
+        match self
+            .post_close_order(trade, maker_close_order, touch_price, reduce_only)
+            .await
         {
-            close_order.uuid = parsed_response.result.order_id;
-            Ok(close_order)
-        } else {
-            let error = format!(
-                "try_close_position -> parsed response {:?}",
-                parsed_response
-            );
-            Err(GlowError::new(String::from("Close Position Error"), error))
+            Ok(maker_close_order) => {
+                if maker_close_order.status == OrderStatus::Closed
+                    || timeout(maker_close_timeout, self.wait_for_trade_close(&trade.id))
+                        .await
+                        .is_ok()
+                {
+                    return Ok(maker_close_order);
+                }
+                let market_close_order = self
+                    .fall_back_to_market_close(trade, maker_close_order.id.clone(), touch_price)
+                    .await?;
+                Ok(market_close_order.unwrap_or(maker_close_order))
+            }
+            Err(error) => {
+                println!(
+                    "try_close_position -> maker close failed, closing at market {:?}",
+                    error
+                );
+                let market_close_order = trade.new_close_order(OrderType::Market, est_price)?;
+                self.post_close_order(trade, market_close_order, est_price, reduce_only)
+                    .await
+            }
         }
     }
 
     async fn cancel_order(&self, order_id: String) -> Result<bool, GlowError> {
-        let traded_symbol = self.get_traded_symbol();
-        let payload = CancelOrderDto::new(
-            order_id.clone(),
-            "linear".to_string(),
-            traded_symbol.name.to_string(),
-        );
-        let request_builder =
-            self.prepare_request_builder(HttpMethod::Post, "/v5/order/cancel", &payload)?;
-        let result = request_builder.send().await;
-        let parsed_response =
-            Self::try_parse_response::<BybitHttpResponseWrapper<OrderResponse>>(result).await?;
+        let parsed_response = self.post_cancel_order(&order_id).await?;
         if parsed_response.ret_code != 0
             || parsed_response.ret_message != String::from("OK")
             || parsed_response.result.order_link_id != order_id
@@ -1305,6 +1881,33 @@ impl TraderExchange for BybitTraderExchange {
         Ok(true)
     }
 
+    async fn cancel_all_orders(&self) -> Result<usize, GlowError> {
+        let traded_symbol = self.get_traded_symbol();
+        let payload =
+            CancelAllOrdersDto::new("linear".to_string(), traded_symbol.name.to_string());
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Post, "/v5/order/cancel-all", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<
+            BybitHttpResponseWrapper<HttpResultList<OrderResponse>>,
+        >(result)
+        .await?;
+        if parsed_response.ret_code != 0 || parsed_response.ret_message != "OK" {
+            let error = format!("cancel_all_orders -> parsed response {:?}", parsed_response);
+            return Err(GlowError::new(String::from("Cancel Orders Error"), error));
+        }
+        Ok(parsed_response.result.list.len())
+    }
+
+    async fn flatten_positions(&self) -> Result<(), GlowError> {
+        let cancelled_orders = self.cancel_all_orders().await?;
+        println!("flatten_positions -> {} orders cancelled", cancelled_orders);
+        self.post_flatten_orders(|position| {
+            format!("{}_{}_flatten", position.symbol, current_timestamp_ms())
+        })
+        .await
+    }
+
     async fn set_leverage(&self, leverage: Leverage) -> Result<bool, GlowError> {
         let leverage_factor = leverage.get_factor();
         let traded_contract = self.get_traded_contract();
@@ -1512,14 +2115,30 @@ impl TraderExchange for BybitTraderExchange {
             }
         }
 
+        {
+            let mut last_error_guard = self.last_ws_error_ts.lock().unwrap();
+            *last_error_guard = None;
+        }
+
         Ok(())
     }
 
     async fn init(&mut self) -> Result<(), GlowError> {
-        let url = self.get_ws_url()?;
+        match self.fetch_instruments_info().await {
+            Ok(contracts) => self.patch_contracts(contracts),
+            Err(error) => eprintln!(
+                "Failed to fetch Bybit instruments info, keeping static contracts. Error: {:?}",
+                error
+            ),
+        }
+        let mut has_connected = false;
 
         loop {
-            let connection = connect_async(url.clone()).await;
+            let config = self.exchange_config_emitter.value();
+            self.credentials = config.credentials;
+            self.endpoints = config.endpoints;
+            let url = self.get_ws_url()?;
+            let connection = connect_async(url).await;
             if let Err(error) = connection {
                 eprintln!(
                     "Exchange WebSocket connection failed. \n
@@ -1535,6 +2154,10 @@ impl TraderExchange for BybitTraderExchange {
 
             let (wss, resp) = connection.unwrap();
             eprintln!("Exchange connection stablished. \n Response: {:?}", resp);
+            if has_connected {
+                METRICS.inc_ws_reconnects("bybit");
+            }
+            has_connected = true;
             if let Err(err) = self.listen_messages(wss).await {
                 let mut last_error_guard = self
                     .last_ws_error_ts
@@ -1561,18 +2184,29 @@ impl TraderExchange for BybitTraderExchange {
         let ping_interval = self.get_ws_ping_interval();
         let message = self.get_ws_ping_message()?;
         let exchange_wss_ping_interval_and_message = (ping_interval, message);
+        let ws_compression = self.get_ws_compression();
 
         let mut heartbeat_interval: Interval = interval(Duration::from_secs(
             exchange_wss_ping_interval_and_message.0,
         ));
 
-        let mut sleep_deadline = Instant::now();
-        let mut timeout_executed = false;
+        // positions are synced once subscribed, as updates might have been missed while disconnected
+        self.exchange_recovery_emitter.next(TradingDataUpdate::ExchangeRecovery);
+
+        // current config is skipped, so that only switching environment has websocket reconnect
+        let mut exchange_config_updates = self.exchange_config_emitter.subscribe().skip(1);
 
         loop {
             select! {
                 ws_message = wss.next() => {
                     let message = ws_message.unwrap()?;
+                    let message = match ws_compression.decode_message(message) {
+                        Ok(message) => message,
+                        Err(error) => {
+                            eprintln!("WebSocket message decompression error: {:?}", error);
+                            continue;
+                        }
+                    };
 
                     match message {
                         Message::Text(json) => {
@@ -1588,22 +2222,10 @@ impl TraderExchange for BybitTraderExchange {
                         }
                     }
                 },
-                // TODO: check if it's convenient to separate these in a new thread
-                _ = sleep_until(sleep_deadline) => {
-                    sleep_deadline = Instant::now() + Duration::from_secs(SECONDS_IN_MIN as u64);
-                    if timeout_executed {
-                        continue;
-                    }
-
-                    let _ = self.update_position_data_on_faulty_exchange_ws().await;
-
-                    {
-                        let mut last_error_guard = self.last_ws_error_ts.lock().unwrap();
-                        *last_error_guard = None;
-                    }
-
-                    timeout_executed = true;
-                }
+                _ = exchange_config_updates.next() => {
+                    println!("listen_messages -> exchange environment switched, reconnecting");
+                    return Ok(());
+                },
                 _ = heartbeat_interval.tick() => {
                     let ping_message = exchange_wss_ping_interval_and_message.1.clone();
                     let _send = wss.send(ping_message).await?;
@@ -1631,6 +2253,11 @@ impl TraderExchange for BybitTraderExchange {
     fn get_trade_update_emitter(&self) -> &BehaviorSubject<Option<Trade>> {
         &self.trade_update_emitter
     }
+
+    #[inline]
+    fn get_exchange_recovery_emitter(&self) -> &BehaviorSubject<TradingDataUpdate> {
+        &self.exchange_recovery_emitter
+    }
 }
 
 impl BenchmarkExchange for BybitTraderExchange {
@@ -1644,11 +2271,16 @@ impl BenchmarkExchange for BybitTraderExchange {
         let trading_settings = self.get_trading_settings();
         let leverage_factor = trading_settings.leverage.get_factor();
         let open_order_type = trading_settings.get_open_order_type();
+        let price = side.apply_slippage(price, trading_settings.benchmark_slippage_bps);
         // TODO: allocation comes from trading settings, consider that here
         let (units, balance_remainder) =
             self.calculate_open_order_units_and_balance_remainder(side, order_cost, price)?;
-        let ((open_fee, _), fee_rate, is_maker) =
-            self.calculate_order_fees(open_order_type, side, units, price);
+        let ((open_fee, _), fee_rate, is_maker) = self.calculate_order_fees_at_rate(
+            self.get_benchmark_order_fee_rate(open_order_type),
+            side,
+            units,
+            price,
+        );
         let contract = self.get_traded_contract();
         let id = format!(
             "{}_{}_{}",
@@ -1727,9 +2359,15 @@ impl BenchmarkExchange for BybitTraderExchange {
         };
 
         let close_side = open_order.side.get_opposite_side()?;
+        let close_price =
+            close_side.apply_slippage(close_price, trading_settings.benchmark_slippage_bps);
 
-        let ((_, close_fee), fee_rate, is_maker) =
-            self.calculate_order_fees(close_order_type, close_side, open_order.units, close_price);
+        let ((_, close_fee), fee_rate, is_maker) = self.calculate_order_fees_at_rate(
+            self.get_benchmark_order_fee_rate(close_order_type),
+            close_side,
+            open_order.units,
+            close_price,
+        );
 
         let order_uuid = format!("pending_order_uuid_{}", timestamp);
 
@@ -1787,12 +2425,16 @@ impl BenchmarkExchange for BybitTraderExchange {
     ) -> Result<Trade, GlowError> {
         let stop_loss_price = trade.open_order.stop_loss_price.unwrap_or_default();
         let take_profit_price = trade.open_order.take_profit_price.unwrap_or_default();
-        let bankruptcy_price = trade.open_order.get_bankruptcy_price().unwrap_or_default();
-        let final_status = if binding_price == stop_loss_price {
+        let bankruptcy_price = trade
+            .open_order
+            .get_bankruptcy_price(self.get_traded_contract().kind)
+            .unwrap_or_default();
+        let epsilon = self.trading_settings.get_price_level_epsilon();
+        let final_status = if is_at_threshold(binding_price, stop_loss_price, epsilon) {
             OrderStatus::StoppedSL
-        } else if binding_price == take_profit_price {
+        } else if is_at_threshold(binding_price, take_profit_price, epsilon) {
             OrderStatus::StoppedTP
-        } else if binding_price == bankruptcy_price {
+        } else if is_at_threshold(binding_price, bankruptcy_price, epsilon) {
             OrderStatus::StoppedBR
         } else {
             return Err(GlowError::new(
@@ -4,11 +4,14 @@ use crate::shared::{
 };
 
 use super::{enums::*, functions::*};
+use chrono::{Duration, NaiveDateTime};
 use common::{
     enums::{
-        order_status::OrderStatus, order_type::OrderType, side::Side, time_in_force::TimeInForce,
+        contract_kind::ContractKind, order_stage::OrderStage, order_status::OrderStatus,
+        order_type::OrderType, side::Side, time_in_force::TimeInForce,
     },
-    structs::{Execution, Order},
+    r#static::SYMBOLS_MAP,
+    structs::{Contract, Execution, Order, Trade},
 };
 use serde::{Deserialize, Serialize};
 
@@ -128,6 +131,7 @@ impl From<ExecutionData> for Execution {
             fee_rate: value.fee_rate,
             is_maker: value.is_maker,
             closed_qty: value.closed_size.unwrap_or_default(),
+            is_out_of_band: false,
         }
     }
 }
@@ -148,6 +152,8 @@ pub struct OrderData {
     pub close_on_trigger: bool, // Close on trigger
     #[serde(rename = "createdTime", deserialize_with = "parse_i64")]
     pub created_time: i64, // Order created timestamp (ms)
+    #[serde(rename = "createType", default)]
+    pub create_type: CreateType, // Order create type, e.g. by user, by liquidation or by ADL
     #[serde(rename = "cumExecFee", deserialize_with = "parse_f64")]
     pub cum_exec_fee: f64, // Cumulative executed trading fee. For normal spot, it is the execution fee per single fill
     #[serde(rename = "cumExecQty", deserialize_with = "parse_f64")]
@@ -228,6 +234,15 @@ impl OrderData {
         self.cancel_type != CancelType::Nil
     }
 
+    /// Whether order was created by exchange to close position upon liquidation, either by
+    /// liquidation engine taking it over or by auto-deleveraging.
+    pub fn is_liquidation(&self) -> bool {
+        matches!(
+            self.create_type,
+            CreateType::Liquidation | CreateType::TakeOver | CreateType::AutoDeleveraging
+        )
+    }
+
     pub fn is_trigger_order(&self) -> bool {
         match self.order_status {
             BybitOrderStatus::Untriggered => true,
@@ -244,7 +259,10 @@ impl OrderData {
             } else {
                 if self.stop_order_type != StopOrderType::Empty {
                     is_stop = false;
-                    if self.leaves_qty > 0.0 {
+                    // partial take profit fills only reduce position, which keeps open
+                    if self.leaves_qty > 0.0
+                        || self.stop_order_type == StopOrderType::PartialTakeProfit
+                    {
                         OrderStatus::PartiallyClosed
                     } else {
                         OrderStatus::Closed
@@ -292,6 +310,41 @@ impl OrderData {
             self.order_id.clone(),
         )
     }
+
+    /// Closes `trade` by this liquidation order, as a bankruptcy stop. Its id is trade's close
+    /// order one, if there's any, so that it replaces whatever close order trade was pending on.
+    pub fn new_liquidation_close_order(
+        &self,
+        trade: &Trade,
+        leverage_factor: f64,
+        taker_fee_rate: f64,
+    ) -> Order {
+        let id = match &trade.close_order {
+            Some(close_order) => close_order.id.clone(),
+            None => format!("{}_{}", trade.id, OrderStage::Close.to_string()),
+        };
+        Order::new(
+            self.avg_price,
+            0.0,
+            self.created_time,
+            vec![],
+            id,
+            true,
+            true,
+            leverage_factor,
+            self.order_type,
+            self.side,
+            OrderStatus::StoppedBR,
+            None,
+            self.symbol.clone(),
+            None,
+            taker_fee_rate,
+            self.time_in_force,
+            self.cum_exec_qty,
+            self.updated_time,
+            self.order_id.clone(),
+        )
+    }
 }
 
 #[allow(dead_code)]
@@ -441,6 +494,95 @@ pub struct PositionResponseData {
     pub updated_at: i64,
 }
 
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct TickerData {
+    pub symbol: String,
+    #[serde(rename = "lastPrice", deserialize_with = "parse_f64")]
+    pub last_price: f64,
+    #[serde(rename = "bid1Price", deserialize_with = "parse_f64")]
+    pub bid_price: f64,
+    #[serde(rename = "ask1Price", deserialize_with = "parse_f64")]
+    pub ask_price: f64,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct InstrumentInfoData {
+    pub symbol: String,
+    #[serde(rename = "launchTime", deserialize_with = "parse_i64")]
+    pub launch_time: i64,
+    #[serde(rename = "fundingInterval")]
+    pub funding_interval: i64, // in minutes
+    #[serde(rename = "priceFilter")]
+    pub price_filter: PriceFilterData,
+    #[serde(rename = "leverageFilter")]
+    pub leverage_filter: LeverageFilterData,
+    #[serde(rename = "lotSizeFilter")]
+    pub lot_size_filter: LotSizeFilterData,
+}
+
+impl InstrumentInfoData {
+    /// Builds instrument's contract, taking funding rate and kind from `fallback` contract, as
+    /// instruments endpoint doesn't provide them. Returns `None` for unknown symbols.
+    pub fn to_contract(&self, fallback: Option<&Contract>) -> Option<Contract> {
+        let symbol = SYMBOLS_MAP.get(self.symbol.as_str())?;
+        let available_since = NaiveDateTime::from_timestamp_millis(self.launch_time)
+            .or(fallback.map(|contract| contract.available_since))?;
+        let funding_rate = fallback.map_or(0.0, |contract| contract.funding_rate);
+        let kind = fallback.map_or(ContractKind::default(), |contract| contract.kind);
+        let min_notional = self
+            .lot_size_filter
+            .min_notional_value
+            .or(fallback.and_then(|contract| contract.min_notional));
+        let contract = Contract::new(
+            available_since,
+            Duration::minutes(self.funding_interval),
+            funding_rate,
+            self.leverage_filter.max_leverage,
+            (
+                self.lot_size_filter.max_market_order_qty,
+                self.lot_size_filter.max_order_qty,
+            ),
+            self.lot_size_filter.min_order_qty,
+            None,
+            symbol,
+            self.price_filter.tick_size,
+        )
+        .with_kind(kind)
+        .with_min_notional(min_notional);
+        Some(contract)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PriceFilterData {
+    #[serde(rename = "tickSize", deserialize_with = "parse_f64")]
+    pub tick_size: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LeverageFilterData {
+    #[serde(rename = "maxLeverage", deserialize_with = "parse_f64")]
+    pub max_leverage: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LotSizeFilterData {
+    #[serde(rename = "maxOrderQty", deserialize_with = "parse_f64")]
+    pub max_order_qty: f64,
+    #[serde(rename = "maxMktOrderQty", deserialize_with = "parse_f64")]
+    pub max_market_order_qty: f64,
+    #[serde(rename = "minOrderQty", deserialize_with = "parse_f64")]
+    pub min_order_qty: f64,
+    #[serde(
+        rename = "minNotionalValue",
+        default,
+        deserialize_with = "parse_f64_option"
+    )]
+    pub min_notional_value: Option<f64>,
+}
+
 // TODO: implement tp/sl limit price, with tpslMode
 #[derive(Debug, Clone, Serialize)]
 pub struct CreateOrderDto {
@@ -509,7 +651,7 @@ pub struct FetchCurrentOrderDto {
 pub struct FetchExecutionsDto {
     pub category: String,
     #[serde(rename = "orderId")]
-    pub order_uuid: String,
+    pub order_uuid: Option<String>,
     pub symbol: String,
     #[serde(rename = "startTime")]
     pub start_timestamp: i64,
@@ -532,6 +674,18 @@ pub struct FetchPositionDto {
     pub symbol: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct FetchTickerDto {
+    pub category: String,
+    pub symbol: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FetchInstrumentsInfoDto {
+    pub category: String,
+    pub symbol: String,
+}
+
 impl CreateOrderDto {
     fn new(
         id: String,
@@ -560,6 +714,34 @@ impl CreateOrderDto {
             time_in_force,
         }
     }
+
+    /// Market order that only reduces the position, used for flattening positions not tracked as trades
+    pub fn new_reduce_only_market_order(
+        id: String,
+        symbol: String,
+        side: Side,
+        units: f64,
+    ) -> Self {
+        CreateOrderDto::new(
+            id,
+            "linear".to_string(),
+            symbol,
+            side,
+            true,
+            OrderType::Market,
+            units,
+            None,
+            None,
+            None,
+            TimeInForce::IOC,
+        )
+    }
+
+    /// Drops take profit, so that position isn't fully closed by it, leaving room for a partial take profit
+    pub fn without_take_profit(mut self) -> Self {
+        self.take_profit_price = None;
+        self
+    }
 }
 
 impl From<Order> for CreateOrderDto {
@@ -601,6 +783,75 @@ impl CancelOrderDto {
     }
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct CancelAllOrdersDto {
+    category: String,
+    symbol: String,
+}
+
+impl CancelAllOrdersDto {
+    pub fn new(category: String, symbol: String) -> Self {
+        CancelAllOrdersDto { category, symbol }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SetPartialTakeProfitDto {
+    category: String,
+    symbol: String,
+    #[serde(rename = "takeProfit", serialize_with = "f64_as_string")]
+    take_profit_price: f64,
+    #[serde(rename = "tpSize", serialize_with = "f64_as_string")]
+    take_profit_units: f64,
+    #[serde(rename = "tpslMode")]
+    tpsl_mode: String,
+    #[serde(rename = "positionIdx")]
+    position_idx: i32, // 0 for one-way mode position
+}
+
+impl SetPartialTakeProfitDto {
+    pub fn new(
+        category: String,
+        symbol: String,
+        take_profit_price: f64,
+        take_profit_units: f64,
+    ) -> Self {
+        SetPartialTakeProfitDto {
+            category,
+            symbol,
+            take_profit_price,
+            take_profit_units,
+            tpsl_mode: "Partial".to_string(),
+            position_idx: 0,
+        }
+    }
+}
+
+/// Sets whole position's stop loss, replacing the one set when it was opened.
+#[derive(Debug, Clone, Serialize)]
+pub struct SetStopLossDto {
+    category: String,
+    symbol: String,
+    #[serde(rename = "stopLoss", serialize_with = "f64_as_string")]
+    stop_loss_price: f64,
+    #[serde(rename = "tpslMode")]
+    tpsl_mode: String,
+    #[serde(rename = "positionIdx")]
+    position_idx: i32, // 0 for one-way mode position
+}
+
+impl SetStopLossDto {
+    pub fn new(category: String, symbol: String, stop_loss_price: f64) -> Self {
+        SetStopLossDto {
+            category,
+            symbol,
+            stop_loss_price,
+            tpsl_mode: "Full".to_string(),
+            position_idx: 0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct FetchWalletBalanceDto {
     coin: Option<String>,
@@ -635,6 +886,9 @@ pub struct HttpResultList<T> {
     pub category: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct EmptyDto {}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct EmptyObject {}
 
@@ -661,10 +915,11 @@ impl SetLeverageDto {
         }
     }
 }
+/// Rejected orders' result is an empty object, hence its defaults
 #[derive(Debug, Clone, Deserialize)]
 pub struct OrderResponse {
-    #[serde(rename = "orderId")]
+    #[serde(rename = "orderId", default)]
     pub order_id: String,
-    #[serde(rename = "orderLinkId")]
+    #[serde(rename = "orderLinkId", default)]
     pub order_link_id: String,
 }
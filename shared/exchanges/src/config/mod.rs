@@ -2,56 +2,120 @@ use crate::{
     enums::TraderExchangeId,
     structs::{ApiCredentials, ApiEndpoints, ExchangeConfig},
 };
-use common::constants::{API_KEY_ENV_SUFFIX, API_SECRET_ENV_SUFFIX};
+use common::{
+    constants::{API_KEY_ENV_SUFFIX, API_SECRET_ENV_SUFFIX},
+    enums::exchange_environment::ExchangeEnvironment,
+};
+use glow_error::GlowError;
 // use dotenv::dotenv;
 use std::{collections::HashMap, env::var, sync::LazyLock};
+#[cfg(test)]
+mod tests;
 
 pub static WS_RECONNECT_INTERVAL_IN_SECS: u64 = 2;
 
-pub static TRADER_EXCHANGES_CONFIG_MAP: LazyLock<HashMap<TraderExchangeId, ExchangeConfig>> =
-    LazyLock::new(|| {
-        // dotenv().ok();
-        let mut configs = HashMap::new();
-        {
-            let env = var("ENV_NAME")
-                .expect("ENV_NAME env var to be provided")
-                .to_uppercase();
-            let http_url: String;
-            let ws_url: String;
-            let exchange_title = "Bybit".to_uppercase();
-            let api_key_env_var = format!("{}_{}_{}", exchange_title, API_KEY_ENV_SUFFIX, env);
-            let api_key = var(&api_key_env_var)
-                .expect(&format!("{} env var to be provided", api_key_env_var));
-            let api_secret_env_var =
-                format!("{}_{}_{}", exchange_title, API_SECRET_ENV_SUFFIX, env);
-            let api_secret = var(&api_secret_env_var)
-                .expect(&format!("{} env var to be provided", api_secret_env_var));
-
-            if env == "PROD".to_string() {
-                http_url = var("BYBIT_HTTP_BASE_URL_PROD")
-                    .expect("BYBIT_HTTP_BASE_URL_PROD env var to be provided");
-                ws_url = var("BYBIT_WS_BASE_URL_PROD")
-                    .expect("BYBIT_WS_BASE_URL_PROD env var to be provided");
-            } else {
-                http_url = var("BYBIT_HTTP_BASE_URL_DEV")
-                    .expect("BYBIT_HTTP_BASE_URL_DEV env var to be provided");
-                ws_url = var("BYBIT_WS_BASE_URL_DEV")
-                    .expect("BYBIT_WS_BASE_URL_DEV env var to be provided");
-            }
-
-            let config = ExchangeConfig {
-                credentials: ApiCredentials {
-                    key: Box::leak(api_key.into_boxed_str()),
-                    secret: Box::leak(api_secret.into_boxed_str()),
-                },
-                endpoints: ApiEndpoints {
-                    ws: Box::leak(ws_url.into_boxed_str()),
-                    http: Box::leak(http_url.into_boxed_str()),
-                },
-            };
-
-            configs.insert(TraderExchangeId::Bybit, config);
+/// Data provider REST requests per second budget, shared by its fetch loop and retries.
+/// Overridable via `BINANCE_HTTP_REQUESTS_PER_SECOND` env var, which must be positive.
+pub static BINANCE_HTTP_REQUESTS_PER_SECOND: LazyLock<f64> = LazyLock::new(|| {
+    var("BINANCE_HTTP_REQUESTS_PER_SECOND")
+        .ok()
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .filter(|requests_per_second| *requests_per_second > 0.0)
+        .unwrap_or(10.0)
+});
+
+/// Env vars suffix for each exchange environment
+fn get_env_suffix(environment: ExchangeEnvironment) -> &'static str {
+    match environment {
+        ExchangeEnvironment::Testnet => "DEV",
+        ExchangeEnvironment::Mainnet => "PROD",
+    }
+}
+
+/// Resolves exchange config for `environment`, reading each of its env vars by `get_var`.
+/// Fails listing every missing env var, unless all of them are provided.
+fn resolve_exchange_config(
+    exchange_title: &str,
+    environment: ExchangeEnvironment,
+    get_var: impl Fn(&str) -> Option<String>,
+) -> Result<ExchangeConfig, GlowError> {
+    let exchange_title = exchange_title.to_uppercase();
+    let env_suffix = get_env_suffix(environment);
+    let env_vars = [
+        format!("{}_{}_{}", exchange_title, API_KEY_ENV_SUFFIX, env_suffix),
+        format!(
+            "{}_{}_{}",
+            exchange_title, API_SECRET_ENV_SUFFIX, env_suffix
+        ),
+        format!("{}_HTTP_BASE_URL_{}", exchange_title, env_suffix),
+        format!("{}_WS_BASE_URL_{}", exchange_title, env_suffix),
+    ];
+    let values = env_vars.each_ref().map(|env_var| get_var(env_var));
+    let [Some(api_key), Some(api_secret), Some(http_url), Some(ws_url)] = values.clone() else {
+        let missing_env_vars: Vec<&str> = env_vars
+            .iter()
+            .zip(values.iter())
+            .filter(|(_, value)| value.is_none())
+            .map(|(env_var, _)| env_var.as_str())
+            .collect();
+        let error = format!(
+            "{} {:?} config requires env vars {}",
+            exchange_title,
+            environment,
+            missing_env_vars.join(", ")
+        );
+        return Err(GlowError::new(
+            String::from("Missing exchange config"),
+            error,
+        ));
+    };
+
+    Ok(ExchangeConfig {
+        credentials: ApiCredentials {
+            key: Box::leak(api_key.into_boxed_str()),
+            secret: Box::leak(api_secret.into_boxed_str()),
+        },
+        endpoints: ApiEndpoints {
+            ws: Box::leak(ws_url.into_boxed_str()),
+            http: Box::leak(http_url.into_boxed_str()),
+        },
+    })
+}
+
+/// Configs of each trader exchange environment, or why they couldn't be resolved.
+/// Which one is used is up to `TradingSettings::environment`, checked whenever it's selected.
+pub static TRADER_EXCHANGES_CONFIG_MAP: LazyLock<
+    HashMap<(TraderExchangeId, ExchangeEnvironment), Result<ExchangeConfig, GlowError>>,
+> = LazyLock::new(|| {
+    // dotenv().ok();
+    let mut configs = HashMap::new();
+    for (exchange_id, exchange_title) in [
+        (TraderExchangeId::Bybit, "Bybit"),
+        (TraderExchangeId::Kraken, "Kraken"),
+    ] {
+        for environment in [ExchangeEnvironment::Testnet, ExchangeEnvironment::Mainnet] {
+            let config =
+                resolve_exchange_config(exchange_title, environment, |env_var| var(env_var).ok());
+            configs.insert((exchange_id, environment), config);
         }
+    }
+
+    configs
+});
 
-        configs
-    });
+/// Gets config of trader exchange at `environment`, failing when its env vars weren't provided.
+pub fn get_trader_exchange_config(
+    exchange_id: TraderExchangeId,
+    environment: ExchangeEnvironment,
+) -> Result<ExchangeConfig, GlowError> {
+    TRADER_EXCHANGES_CONFIG_MAP
+        .get(&(exchange_id, environment))
+        .cloned()
+        .unwrap_or_else(|| {
+            let error = format!("{:?} has no {:?} config", exchange_id, environment);
+            Err(GlowError::new(
+                String::from("Missing exchange config"),
+                error,
+            ))
+        })
+}
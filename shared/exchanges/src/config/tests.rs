@@ -0,0 +1,105 @@
+use super::resolve_exchange_config;
+use crate::{
+    enums::TraderExchangeWrapper,
+    kraken::KrakenTraderExchange,
+    structs::{ApiCredentials, ApiEndpoints, ExchangeConfig},
+};
+use common::{
+    enums::exchange_environment::ExchangeEnvironment, structs::TradingSettings,
+    traits::exchange::TraderHelper,
+};
+use std::collections::HashMap;
+
+fn get_test_env_vars(env_suffix: &str) -> HashMap<String, String> {
+    [
+        ("BYBIT_API_KEY", "key"),
+        ("BYBIT_API_SECRET", "secret"),
+        ("BYBIT_HTTP_BASE_URL", "https://api.bybit.com"),
+        ("BYBIT_WS_BASE_URL", "wss://stream.bybit.com"),
+    ]
+    .into_iter()
+    .map(|(env_var, value)| (format!("{}_{}", env_var, env_suffix), value.to_string()))
+    .collect()
+}
+
+#[test]
+fn test_exchange_config_is_resolved_from_environment_env_vars() {
+    let mut env_vars = get_test_env_vars("DEV");
+    env_vars.extend(get_test_env_vars("PROD"));
+    env_vars.insert(
+        String::from("BYBIT_HTTP_BASE_URL_PROD"),
+        String::from("https://api.bybit.com/prod"),
+    );
+
+    let testnet_config =
+        resolve_exchange_config("Bybit", ExchangeEnvironment::Testnet, |env_var| {
+            env_vars.get(env_var).cloned()
+        })
+        .unwrap();
+    let mainnet_config =
+        resolve_exchange_config("Bybit", ExchangeEnvironment::Mainnet, |env_var| {
+            env_vars.get(env_var).cloned()
+        })
+        .unwrap();
+
+    assert_eq!(testnet_config.credentials.key, "key");
+    assert_eq!(testnet_config.credentials.secret, "secret");
+    assert_eq!(testnet_config.endpoints.http, "https://api.bybit.com");
+    assert_eq!(testnet_config.endpoints.ws, "wss://stream.bybit.com");
+    assert_eq!(mainnet_config.endpoints.http, "https://api.bybit.com/prod");
+}
+
+#[test]
+fn test_exchange_config_resolution_lists_missing_env_vars() {
+    let mut env_vars = get_test_env_vars("DEV");
+    env_vars.remove("BYBIT_API_SECRET_DEV");
+    env_vars.remove("BYBIT_WS_BASE_URL_DEV");
+
+    let error = resolve_exchange_config("Bybit", ExchangeEnvironment::Testnet, |env_var| {
+        env_vars.get(env_var).cloned()
+    })
+    .unwrap_err();
+    assert_eq!(error.title, "Missing exchange config");
+    assert!(error.description.contains("BYBIT_API_SECRET_DEV"));
+    assert!(error.description.contains("BYBIT_WS_BASE_URL_DEV"));
+    assert!(!error.description.contains("BYBIT_API_KEY_DEV"));
+
+    // mainnet env vars don't stand in for missing testnet ones
+    let env_vars = get_test_env_vars("PROD");
+    let result = resolve_exchange_config("Bybit", ExchangeEnvironment::Testnet, |env_var| {
+        env_vars.get(env_var).cloned()
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_invalid_trading_settings_are_not_applied() {
+    let config = ExchangeConfig {
+        credentials: ApiCredentials {
+            key: "key",
+            secret: "c2VjcmV0",
+        },
+        endpoints: ApiEndpoints {
+            ws: "wss://127.0.0.1",
+            http: "http://127.0.0.1",
+        },
+    };
+    let trading_settings = TradingSettings::default();
+    let mut trader_exchange =
+        TraderExchangeWrapper::Kraken(KrakenTraderExchange::from_config(&trading_settings, config));
+
+    let mut invalid_trading_settings = trading_settings.clone();
+    invalid_trading_settings.allocation_percentage = 0.0;
+    invalid_trading_settings.environment = ExchangeEnvironment::Mainnet;
+    let error = trader_exchange
+        .patch_settings(&invalid_trading_settings)
+        .unwrap_err();
+
+    assert_eq!(error.title, "Invalid Trading Settings");
+    let current_trading_settings = trader_exchange.get_trading_settings();
+    assert_eq!(current_trading_settings.allocation_percentage, 100.0);
+    assert_eq!(
+        current_trading_settings.environment,
+        ExchangeEnvironment::Testnet
+    );
+}
@@ -1,12 +1,19 @@
-use crate::{binance::structs::BinanceDataProvider, bybit::BybitTraderExchange};
+use crate::{
+    binance::structs::BinanceDataProvider, bybit::BybitTraderExchange,
+    kraken::KrakenTraderExchange, okx::structs::OkxDataProvider,
+    replay::structs::ReplayDataProvider,
+};
 use chrono::NaiveDateTime;
 use common::{
     enums::{
-        balance::Balance, modifiers::leverage::Leverage, order_action::OrderAction,
-        order_status::OrderStatus, order_type::OrderType, side::Side, symbol_id::SymbolId,
-        trade_status::TradeStatus, trading_data_update::TradingDataUpdate,
+        balance::Balance, benchmark_window::BenchmarkWindow, modifiers::leverage::Leverage,
+        order_action::OrderAction, order_status::OrderStatus, order_type::OrderType,
+        run_mode::RunMode, side::Side, symbol_id::SymbolId, trade_status::TradeStatus,
+        trading_data_update::TradingDataUpdate, ws_compression::WsCompression,
+    },
+    structs::{
+        BehaviorSubject, Contract, Execution, Order, OrderBookDepth, Ticker, Trade, TradingSettings,
     },
-    structs::{BehaviorSubject, Contract, Execution, Order, Trade, TradingSettings},
     traits::exchange::{BenchmarkExchange, DataProviderExchange, TraderExchange, TraderHelper},
 };
 use glow_error::GlowError;
@@ -23,11 +30,15 @@ use url::Url;
 pub enum DataProviderExchangeId {
     #[default]
     Binance,
+    Okx,
+    Replay,
 }
 
 #[derive(Clone)]
 pub enum DataProviderExchangeWrapper {
     Binance(BinanceDataProvider),
+    Okx(OkxDataProvider),
+    Replay(ReplayDataProvider),
 }
 
 impl DataProviderExchangeWrapper {
@@ -40,30 +51,87 @@ impl DataProviderExchangeWrapper {
             DataProviderExchangeId::Binance => {
                 Self::Binance(BinanceDataProvider::new(trading_settings, strategy))
             }
+            DataProviderExchangeId::Okx => {
+                Self::Okx(OkxDataProvider::new(trading_settings, strategy))
+            }
+            DataProviderExchangeId::Replay => {
+                Self::Replay(ReplayDataProvider::from_env(trading_settings, strategy))
+            }
         }
     }
 
     pub fn get_selection_list() -> Vec<String> {
-        vec![String::from("Binance")]
+        vec![
+            String::from("Binance"),
+            String::from("OKX"),
+            String::from("Replay"),
+        ]
+    }
+
+    /// Name under which exchange data is stored, e.g. gathered market klines.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Binance(_) => "binance",
+            Self::Okx(_) => "okx",
+            Self::Replay(_) => "replay",
+        }
     }
 
     pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
         match self {
             Self::Binance(ex) => ex.patch_settings(trading_settings),
+            Self::Okx(ex) => ex.patch_settings(trading_settings),
+            Self::Replay(ex) => ex.patch_settings(trading_settings),
         }
     }
 
     pub fn patch_strategy(&mut self, strategy: &Strategy) {
         match self {
             Self::Binance(ex) => ex.patch_strategy(strategy),
+            Self::Okx(ex) => ex.patch_strategy(strategy),
+            Self::Replay(ex) => ex.patch_strategy(strategy),
         }
     }
 }
 
 impl DataProviderExchange for DataProviderExchangeWrapper {
+    fn get_exchange_name(&self) -> &'static str {
+        match self {
+            Self::Binance(ex) => ex.get_exchange_name(),
+            Self::Okx(ex) => ex.get_exchange_name(),
+            Self::Replay(ex) => ex.get_exchange_name(),
+        }
+    }
+
     fn get_kline_data_emitter(&self) -> &BehaviorSubject<TradingDataUpdate> {
         match self {
             Self::Binance(ex) => ex.get_kline_data_emitter(),
+            Self::Okx(ex) => ex.get_kline_data_emitter(),
+            Self::Replay(ex) => ex.get_kline_data_emitter(),
+        }
+    }
+
+    fn get_depth_emitter(&self) -> Option<&BehaviorSubject<Option<OrderBookDepth>>> {
+        match self {
+            Self::Binance(ex) => ex.get_depth_emitter(),
+            Self::Okx(ex) => ex.get_depth_emitter(),
+            Self::Replay(ex) => ex.get_depth_emitter(),
+        }
+    }
+
+    fn get_kline_ack_emitter(&self) -> Option<&BehaviorSubject<Option<usize>>> {
+        match self {
+            Self::Binance(ex) => ex.get_kline_ack_emitter(),
+            Self::Okx(ex) => ex.get_kline_ack_emitter(),
+            Self::Replay(ex) => ex.get_kline_ack_emitter(),
+        }
+    }
+
+    fn get_ws_compression(&self) -> WsCompression {
+        match self {
+            Self::Binance(ex) => ex.get_ws_compression(),
+            Self::Okx(ex) => ex.get_ws_compression(),
+            Self::Replay(ex) => ex.get_ws_compression(),
         }
     }
 
@@ -73,25 +141,40 @@ impl DataProviderExchange for DataProviderExchangeWrapper {
     ) -> Result<(), GlowError> {
         match self {
             Self::Binance(ex) => ex.subscribe_to_tick_stream(wss).await,
+            Self::Okx(ex) => ex.subscribe_to_tick_stream(wss).await,
+            Self::Replay(ex) => ex.subscribe_to_tick_stream(wss).await,
+        }
+    }
+
+    async fn subscribe_to_depth_stream(
+        &mut self,
+        wss: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), GlowError> {
+        match self {
+            Self::Binance(ex) => ex.subscribe_to_depth_stream(wss).await,
+            Self::Okx(ex) => ex.subscribe_to_depth_stream(wss).await,
+            Self::Replay(ex) => ex.subscribe_to_depth_stream(wss).await,
         }
     }
 
     async fn init(
         &mut self,
-        benchmark_start: Option<NaiveDateTime>,
-        benchmark_end: Option<NaiveDateTime>,
-        run_benchmark_only: bool,
+        benchmark_window: BenchmarkWindow,
+        run_mode: RunMode,
         trading_data_schema: Schema,
     ) -> Result<(), GlowError> {
         match self {
             Self::Binance(ex) => {
-                ex.init(
-                    benchmark_start,
-                    benchmark_end,
-                    run_benchmark_only,
-                    trading_data_schema,
-                )
-                .await
+                ex.init(benchmark_window, run_mode, trading_data_schema)
+                    .await
+            }
+            Self::Okx(ex) => {
+                ex.init(benchmark_window, run_mode, trading_data_schema)
+                    .await
+            }
+            Self::Replay(ex) => {
+                ex.init(benchmark_window, run_mode, trading_data_schema)
+                    .await
             }
         }
     }
@@ -103,6 +186,8 @@ impl DataProviderExchange for DataProviderExchangeWrapper {
     ) -> Result<(), GlowError> {
         match self {
             Self::Binance(ex) => ex.listen_ticks(wss, benchmark_end).await,
+            Self::Okx(ex) => ex.listen_ticks(wss, benchmark_end).await,
+            Self::Replay(ex) => ex.listen_ticks(wss, benchmark_end).await,
         }
     }
 
@@ -116,12 +201,22 @@ impl DataProviderExchange for DataProviderExchangeWrapper {
                 ex.handle_committed_ticks_data(benchmark_end, trading_data_schema)
                     .await
             }
+            Self::Okx(ex) => {
+                ex.handle_committed_ticks_data(benchmark_end, trading_data_schema)
+                    .await
+            }
+            Self::Replay(ex) => {
+                ex.handle_committed_ticks_data(benchmark_end, trading_data_schema)
+                    .await
+            }
         }
     }
 
     fn handle_ws_error(&self, trading_data_schema: &Schema) -> Option<NaiveDateTime> {
         match self {
             Self::Binance(ex) => ex.handle_ws_error(trading_data_schema),
+            Self::Okx(ex) => ex.handle_ws_error(trading_data_schema),
+            Self::Replay(ex) => ex.handle_ws_error(trading_data_schema),
         }
     }
 }
@@ -130,27 +225,53 @@ impl DataProviderExchange for DataProviderExchangeWrapper {
 pub enum TraderExchangeId {
     #[default]
     Bybit,
+    Kraken,
 }
 
 #[derive(Clone)]
 pub enum TraderExchangeWrapper {
     Bybit(BybitTraderExchange),
+    Kraken(KrakenTraderExchange),
 }
 
 impl TraderExchangeWrapper {
-    pub fn new(trader_exchange_id: TraderExchangeId, trading_settings: &TradingSettings) -> Self {
-        match trader_exchange_id {
-            TraderExchangeId::Bybit => Self::Bybit(BybitTraderExchange::new(trading_settings)),
-        }
+    pub fn new(
+        trader_exchange_id: TraderExchangeId,
+        trading_settings: &TradingSettings,
+    ) -> Result<Self, GlowError> {
+        let trader_exchange = match trader_exchange_id {
+            TraderExchangeId::Bybit => Self::Bybit(BybitTraderExchange::new(trading_settings)?),
+            TraderExchangeId::Kraken => Self::Kraken(KrakenTraderExchange::new(trading_settings)?),
+        };
+        Ok(trader_exchange)
     }
 
     pub fn get_selection_list() -> Vec<String> {
-        vec![String::from("Bybit")]
+        vec![String::from("Bybit"), String::from("Kraken")]
     }
 
-    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
+    /// Settings are validated against their traded contract before being applied, so that
+    /// invalid ones leave current settings unchanged.
+    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) -> Result<(), GlowError> {
+        let traded_symbol = trading_settings.symbols_pair.traded;
+        let traded_contract = self.get_contracts().get(&traded_symbol.id).ok_or_else(|| {
+            let error = format!("trader exchange has no {} contract", traded_symbol.name);
+            GlowError::new(String::from("Missing Contract"), error)
+        })?;
+        trading_settings.validate(traded_contract)?;
         match self {
             TraderExchangeWrapper::Bybit(ex) => ex.patch_settings(trading_settings),
+            TraderExchangeWrapper::Kraken(ex) => ex.patch_settings(trading_settings),
+        }
+    }
+
+    /// Trails exchange side stop of `trade` position up to its stepped trailing take profit,
+    /// given the most favorable `price` it reached. Returns whether stop was amended.
+    pub async fn update_trailing_stop(&self, trade: &Trade, price: f64) -> Result<bool, GlowError> {
+        match self {
+            TraderExchangeWrapper::Bybit(ex) => ex.update_trailing_stop(trade, price).await,
+            // Kraken stops are standalone trigger orders, which aren't trailed
+            TraderExchangeWrapper::Kraken(_) => Ok(false),
         }
     }
 }
@@ -159,18 +280,21 @@ impl TraderHelper for TraderExchangeWrapper {
     fn get_trading_settings(&self) -> &TradingSettings {
         match self {
             Self::Bybit(ex) => ex.get_trading_settings(),
+            Self::Kraken(ex) => ex.get_trading_settings(),
         }
     }
 
     fn get_taker_fee(&self) -> f64 {
         match self {
             Self::Bybit(ex) => ex.get_taker_fee(),
+            Self::Kraken(ex) => ex.get_taker_fee(),
         }
     }
 
     fn get_maker_fee(&self) -> f64 {
         match self {
             Self::Bybit(ex) => ex.get_maker_fee(),
+            Self::Kraken(ex) => ex.get_maker_fee(),
         }
     }
 
@@ -184,12 +308,16 @@ impl TraderHelper for TraderExchangeWrapper {
             Self::Bybit(ex) => {
                 ex.calculate_open_order_units_and_balance_remainder(side, order_cost, price)
             }
+            Self::Kraken(ex) => {
+                ex.calculate_open_order_units_and_balance_remainder(side, order_cost, price)
+            }
         }
     }
 
     fn get_order_fee_rate(&self, order_type: OrderType) -> (f64, bool) {
         match self {
             Self::Bybit(ex) => ex.get_order_fee_rate(order_type),
+            Self::Kraken(ex) => ex.get_order_fee_rate(order_type),
         }
     }
 
@@ -202,24 +330,28 @@ impl TraderHelper for TraderExchangeWrapper {
     ) -> ((f64, f64), f64, bool) {
         match self {
             Self::Bybit(ex) => ex.calculate_order_fees(order_type, side, units, price),
+            Self::Kraken(ex) => ex.calculate_order_fees(order_type, side, units, price),
         }
     }
 
     fn calculate_order_stop_loss_price(&self, side: Side, price: f64) -> Option<f64> {
         match self {
             Self::Bybit(ex) => ex.calculate_order_stop_loss_price(side, price),
+            Self::Kraken(ex) => ex.calculate_order_stop_loss_price(side, price),
         }
     }
 
     fn calculate_order_take_profit_price(&self, side: Side, price: f64) -> Option<f64> {
         match self {
             Self::Bybit(ex) => ex.calculate_order_take_profit_price(side, price),
+            Self::Kraken(ex) => ex.calculate_order_take_profit_price(side, price),
         }
     }
 
     fn get_contracts(&self) -> &HashMap<SymbolId, Contract> {
         match self {
             Self::Bybit(ex) => ex.get_contracts(),
+            Self::Kraken(ex) => ex.get_contracts(),
         }
     }
 }
@@ -228,12 +360,14 @@ impl TraderExchange for TraderExchangeWrapper {
     fn new_open_order(&self, side: Side, order_cost: f64, price: f64) -> Result<Order, GlowError> {
         match self {
             Self::Bybit(ex) => ex.new_open_order(side, order_cost, price),
+            Self::Kraken(ex) => ex.new_open_order(side, order_cost, price),
         }
     }
 
     fn get_ws_url(&self) -> Result<Url, GlowError> {
         match self {
             Self::Bybit(ex) => ex.get_ws_url(),
+            Self::Kraken(ex) => ex.get_ws_url(),
         }
     }
 
@@ -243,6 +377,7 @@ impl TraderExchange for TraderExchangeWrapper {
     ) -> Result<(), GlowError> {
         match self {
             Self::Bybit(ex) => ex.auth_ws(wss).await,
+            Self::Kraken(ex) => ex.auth_ws(wss).await,
         }
     }
 
@@ -252,6 +387,7 @@ impl TraderExchange for TraderExchangeWrapper {
     ) -> Result<(), GlowError> {
         match self {
             Self::Bybit(ex) => ex.subscribe_ws(wss).await,
+            Self::Kraken(ex) => ex.subscribe_ws(wss).await,
         }
     }
 
@@ -266,6 +402,10 @@ impl TraderExchange for TraderExchangeWrapper {
                 ex.fetch_order_executions(order_uuid, start_timestamp, end_timestamp)
                     .await
             }
+            Self::Kraken(ex) => {
+                ex.fetch_order_executions(order_uuid, start_timestamp, end_timestamp)
+                    .await
+            }
         }
     }
 
@@ -277,6 +417,7 @@ impl TraderExchange for TraderExchangeWrapper {
     ) -> Result<Order, GlowError> {
         match self {
             Self::Bybit(ex) => ex.fetch_history_order(id, side, fetch_executions).await,
+            Self::Kraken(ex) => ex.fetch_history_order(id, side, fetch_executions).await,
         }
     }
 
@@ -287,12 +428,14 @@ impl TraderExchange for TraderExchangeWrapper {
     ) -> Result<Order, GlowError> {
         match self {
             Self::Bybit(ex) => ex.fetch_current_order(order_id, fetch_executions).await,
+            Self::Kraken(ex) => ex.fetch_current_order(order_id, fetch_executions).await,
         }
     }
 
     async fn fetch_current_trade_position(&self) -> Result<Option<Trade>, GlowError> {
         match self {
             Self::Bybit(ex) => ex.fetch_current_trade_position().await,
+            Self::Kraken(ex) => ex.fetch_current_trade_position().await,
         }
     }
 
@@ -303,12 +446,42 @@ impl TraderExchange for TraderExchangeWrapper {
     ) -> Result<Trade, GlowError> {
         match self {
             Self::Bybit(ex) => ex.fetch_trade_state(trade_id, last_status).await,
+            Self::Kraken(ex) => ex.fetch_trade_state(trade_id, last_status).await,
         }
     }
 
     async fn fetch_current_usdt_balance(&self) -> Result<Balance, GlowError> {
         match self {
             Self::Bybit(ex) => ex.fetch_current_usdt_balance().await,
+            Self::Kraken(ex) => ex.fetch_current_usdt_balance().await,
+        }
+    }
+
+    async fn fetch_base_asset_balance(&self) -> Result<f64, GlowError> {
+        match self {
+            Self::Bybit(ex) => ex.fetch_base_asset_balance().await,
+            Self::Kraken(ex) => ex.fetch_base_asset_balance().await,
+        }
+    }
+
+    async fn fetch_ticker(&self) -> Result<Ticker, GlowError> {
+        match self {
+            Self::Bybit(ex) => ex.fetch_ticker().await,
+            Self::Kraken(ex) => ex.fetch_ticker().await,
+        }
+    }
+
+    async fn fetch_instruments_info(&self) -> Result<HashMap<String, Contract>, GlowError> {
+        match self {
+            Self::Bybit(ex) => ex.fetch_instruments_info().await,
+            Self::Kraken(ex) => ex.fetch_instruments_info().await,
+        }
+    }
+
+    async fn server_time(&self) -> Result<i64, GlowError> {
+        match self {
+            Self::Bybit(ex) => ex.server_time().await,
+            Self::Kraken(ex) => ex.server_time().await,
         }
     }
 
@@ -320,6 +493,7 @@ impl TraderExchange for TraderExchangeWrapper {
     ) -> Result<Order, GlowError> {
         match self {
             Self::Bybit(ex) => ex.open_order(side, amount, expected_price).await,
+            Self::Kraken(ex) => ex.open_order(side, amount, expected_price).await,
         }
     }
 
@@ -342,60 +516,117 @@ impl TraderExchange for TraderExchangeWrapper {
                 )
                 .await
             }
+            Self::Kraken(ex) => {
+                ex.amend_order(
+                    order_id,
+                    updated_units,
+                    updated_price,
+                    updated_stop_loss_price,
+                    updated_take_profit_price,
+                )
+                .await
+            }
         }
     }
 
-    async fn try_close_position(&self, trade: &Trade, est_price: f64) -> Result<Order, GlowError> {
+    async fn scale_out_position(
+        &self,
+        trade: &Trade,
+        units: f64,
+        price: f64,
+    ) -> Result<bool, GlowError> {
         match self {
-            Self::Bybit(ex) => ex.try_close_position(trade, est_price).await,
+            Self::Bybit(ex) => ex.scale_out_position(trade, units, price).await,
+            Self::Kraken(ex) => ex.scale_out_position(trade, units, price).await,
+        }
+    }
+
+    async fn try_close_position(
+        &self,
+        trade: &Trade,
+        est_price: f64,
+        reduce_only: bool,
+    ) -> Result<Order, GlowError> {
+        match self {
+            Self::Bybit(ex) => ex.try_close_position(trade, est_price, reduce_only).await,
+            Self::Kraken(ex) => ex.try_close_position(trade, est_price, reduce_only).await,
         }
     }
 
     async fn cancel_order(&self, order_id: String) -> Result<bool, GlowError> {
         match self {
             Self::Bybit(ex) => ex.cancel_order(order_id).await,
+            Self::Kraken(ex) => ex.cancel_order(order_id).await,
+        }
+    }
+
+    async fn cancel_all_orders(&self) -> Result<usize, GlowError> {
+        match self {
+            Self::Bybit(ex) => ex.cancel_all_orders().await,
+            Self::Kraken(ex) => ex.cancel_all_orders().await,
+        }
+    }
+
+    async fn flatten_positions(&self) -> Result<(), GlowError> {
+        match self {
+            Self::Bybit(ex) => ex.flatten_positions().await,
+            Self::Kraken(ex) => ex.flatten_positions().await,
         }
     }
 
     async fn set_leverage(&self, leverage: Leverage) -> Result<bool, GlowError> {
         match self {
             Self::Bybit(ex) => ex.set_leverage(leverage).await,
+            Self::Kraken(ex) => ex.set_leverage(leverage).await,
         }
     }
 
     fn get_http_client(&self) -> &Client {
         match self {
             Self::Bybit(ex) => ex.get_http_client(),
+            Self::Kraken(ex) => ex.get_http_client(),
         }
     }
 
     fn get_ws_ping_interval(&self) -> u64 {
         match self {
             Self::Bybit(ex) => ex.get_ws_ping_interval(),
+            Self::Kraken(ex) => ex.get_ws_ping_interval(),
         }
     }
 
     fn get_ws_ping_message(&self) -> Result<Message, GlowError> {
         match self {
             Self::Bybit(ex) => ex.get_ws_ping_message(),
+            Self::Kraken(ex) => ex.get_ws_ping_message(),
+        }
+    }
+
+    fn get_ws_compression(&self) -> WsCompression {
+        match self {
+            Self::Bybit(ex) => ex.get_ws_compression(),
+            Self::Kraken(ex) => ex.get_ws_compression(),
         }
     }
 
     fn process_ws_message(&self, json: &String) -> Result<(), GlowError> {
         match self {
             Self::Bybit(ex) => ex.process_ws_message(json),
+            Self::Kraken(ex) => ex.process_ws_message(json),
         }
     }
 
     async fn update_position_data_on_faulty_exchange_ws(&self) -> Result<(), GlowError> {
         match self {
             Self::Bybit(ex) => ex.update_position_data_on_faulty_exchange_ws().await,
+            Self::Kraken(ex) => ex.update_position_data_on_faulty_exchange_ws().await,
         }
     }
 
     async fn init(&mut self) -> Result<(), GlowError> {
         match self {
             Self::Bybit(ex) => ex.init().await,
+            Self::Kraken(ex) => ex.init().await,
         }
     }
 
@@ -405,30 +636,42 @@ impl TraderExchange for TraderExchangeWrapper {
     ) -> Result<(), GlowError> {
         match self {
             Self::Bybit(ex) => ex.listen_messages(wss).await,
+            Self::Kraken(ex) => ex.listen_messages(wss).await,
         }
     }
 
     fn get_balance_update_emitter(&self) -> &BehaviorSubject<Balance> {
         match self {
             TraderExchangeWrapper::Bybit(ex) => ex.get_balance_update_emitter(),
+            TraderExchangeWrapper::Kraken(ex) => ex.get_balance_update_emitter(),
         }
     }
 
     fn get_executions_update_emitter(&self) -> &BehaviorSubject<Vec<Execution>> {
         match self {
             TraderExchangeWrapper::Bybit(ex) => ex.get_executions_update_emitter(),
+            TraderExchangeWrapper::Kraken(ex) => ex.get_executions_update_emitter(),
         }
     }
 
     fn get_order_update_emitter(&self) -> &BehaviorSubject<OrderAction> {
         match self {
             TraderExchangeWrapper::Bybit(ex) => ex.get_order_update_emitter(),
+            TraderExchangeWrapper::Kraken(ex) => ex.get_order_update_emitter(),
         }
     }
 
     fn get_trade_update_emitter(&self) -> &BehaviorSubject<Option<Trade>> {
         match self {
             TraderExchangeWrapper::Bybit(ex) => ex.get_trade_update_emitter(),
+            TraderExchangeWrapper::Kraken(ex) => ex.get_trade_update_emitter(),
+        }
+    }
+
+    fn get_exchange_recovery_emitter(&self) -> &BehaviorSubject<TradingDataUpdate> {
+        match self {
+            TraderExchangeWrapper::Bybit(ex) => ex.get_exchange_recovery_emitter(),
+            TraderExchangeWrapper::Kraken(ex) => ex.get_exchange_recovery_emitter(),
         }
     }
 }
@@ -443,6 +686,7 @@ impl BenchmarkExchange for TraderExchangeWrapper {
     ) -> Result<Order, GlowError> {
         match self {
             Self::Bybit(ex) => ex.new_benchmark_open_order(timestamp, side, order_cost, price),
+            Self::Kraken(ex) => ex.new_benchmark_open_order(timestamp, side, order_cost, price),
         }
     }
 
@@ -462,6 +706,13 @@ impl BenchmarkExchange for TraderExchangeWrapper {
                 open_order,
                 final_status,
             ),
+            Self::Kraken(ex) => ex.new_benchmark_close_order(
+                timestamp,
+                trade_id,
+                close_price,
+                open_order,
+                final_status,
+            ),
         }
     }
 
@@ -475,12 +726,16 @@ impl BenchmarkExchange for TraderExchangeWrapper {
             Self::Bybit(ex) => {
                 ex.close_benchmark_trade_on_binding_price(trade, current_timestamp, binding_price)
             }
+            Self::Kraken(ex) => {
+                ex.close_benchmark_trade_on_binding_price(trade, current_timestamp, binding_price)
+            }
         }
     }
 
     fn get_minimum_notional_value(&self) -> Option<f64> {
         match self {
             Self::Bybit(ex) => ex.get_minimum_notional_value(),
+            Self::Kraken(ex) => ex.get_minimum_notional_value(),
         }
     }
 
@@ -0,0 +1,78 @@
+use super::structs::*;
+use common::enums::{order_status::OrderStatus, order_type::OrderType};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(untagged)]
+pub enum KrakenWsMessage {
+    #[default]
+    None,
+    Fills(FillsWsMessage),
+    OpenOrders(OpenOrdersWsMessage),
+    Balances(BalancesWsMessage),
+    Event(EventWsMessage),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KrakenOrderType {
+    #[serde(rename = "lmt", alias = "limit")]
+    Limit,
+    #[serde(rename = "mkt", alias = "market")]
+    Market,
+    #[serde(rename = "stp", alias = "stop")]
+    Stop,
+    #[serde(rename = "take_profit")]
+    TakeProfit,
+    // liquidations, assignments, etc
+    #[serde(other)]
+    Other,
+}
+
+impl KrakenOrderType {
+    pub fn is_trigger(&self) -> bool {
+        matches!(self, KrakenOrderType::Stop | KrakenOrderType::TakeProfit)
+    }
+}
+
+impl From<KrakenOrderType> for OrderType {
+    fn from(value: KrakenOrderType) -> Self {
+        match value {
+            KrakenOrderType::Limit => OrderType::Limit,
+            _ => OrderType::Market,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KrakenFillType {
+    Maker,
+    Taker,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum KrakenOrderStatus {
+    EnteredBook,
+    FullyExecuted,
+    Rejected,
+    Cancelled,
+    TriggerPlaced,
+    TriggerActivated,
+}
+
+impl From<KrakenOrderStatus> for OrderStatus {
+    fn from(value: KrakenOrderStatus) -> Self {
+        match value {
+            KrakenOrderStatus::EnteredBook | KrakenOrderStatus::TriggerPlaced => {
+                OrderStatus::StandBy
+            }
+            KrakenOrderStatus::FullyExecuted | KrakenOrderStatus::TriggerActivated => {
+                OrderStatus::Filled
+            }
+            KrakenOrderStatus::Rejected | KrakenOrderStatus::Cancelled => OrderStatus::Cancelled,
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::DateTime;
+use common::enums::{order_stage::OrderStage, side::Side};
+use glow_error::GlowError;
+use hmac::{Hmac, Mac};
+use serde::{de::Error as DeError, ser::Error as SerError, Deserialize, Deserializer, Serializer};
+use sha2::{Digest, Sha256, Sha512};
+
+/// Maps a symbol name (e.g. `BTCUSDT`) to Kraken's multi-collateral perpetual (e.g. `PF_XBTUSD`)
+pub fn get_kraken_symbol(symbol: &str) -> String {
+    format!("PF_{}USD", get_kraken_base_asset(symbol))
+}
+
+/// Maps a symbol name (e.g. `BTCUSDT`) to Kraken's name of its base asset (e.g. `XBT`)
+pub fn get_kraken_base_asset(symbol: &str) -> &str {
+    let base = symbol.strip_suffix("USDT").unwrap_or(symbol);
+    if base == "BTC" {
+        "XBT"
+    } else {
+        base
+    }
+}
+
+/// Maps a Kraken perpetual (e.g. `PF_XBTUSD`, case insensitive) back to its symbol name (e.g. `BTCUSDT`)
+pub fn get_symbol_from_kraken(instrument: &str) -> String {
+    let instrument = instrument.to_uppercase();
+    let base = instrument.strip_prefix("PF_").unwrap_or(&instrument);
+    let base = base.strip_suffix("USD").unwrap_or(base);
+    let base = if base == "XBT" { "BTC" } else { base };
+    format!("{}USDT", base)
+}
+
+/// Kraken signs SHA256 digests with HMAC-SHA512, keyed by the base64 decoded api secret
+fn sign_digest(api_secret: &str, message: &str) -> Result<String, GlowError> {
+    let decoded_secret = BASE64.decode(api_secret).map_err(|error| {
+        GlowError::new(
+            String::from("Invalid API Secret Error"),
+            format!(
+                "sign_digest -> api secret isn't base64 encoded: {:?}",
+                error
+            ),
+        )
+    })?;
+    let digest = Sha256::digest(message.as_bytes());
+    let mut mac =
+        Hmac::<Sha512>::new_from_slice(&decoded_secret).expect("Invalid API secret length");
+    mac.update(&digest);
+    Ok(BASE64.encode(mac.finalize().into_bytes()))
+}
+
+/// Calculates `Authent` header for REST requests
+pub fn sign_request(
+    api_secret: &str,
+    post_data: &str,
+    nonce: &str,
+    endpoint_path: &str,
+) -> Result<String, GlowError> {
+    let message = format!("{}{}{}", post_data, nonce, endpoint_path);
+    sign_digest(api_secret, &message)
+}
+
+/// Signs the challenge sent by websocket, which private feeds subscriptions must carry
+pub fn sign_challenge(api_secret: &str, challenge: &str) -> Result<String, GlowError> {
+    sign_digest(api_secret, challenge)
+}
+
+/// Orders are identified by `{trade_id}_{stage}`, so anything that isn't an open order reduces the position
+pub fn is_close_order_id(id: &str) -> bool {
+    !id.ends_with(&OrderStage::Open.to_string())
+}
+
+pub fn serialize_side<S>(side: &Side, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match side {
+        Side::Buy => serializer.serialize_str("buy"),
+        Side::Sell => serializer.serialize_str("sell"),
+        Side::None => Err(S::Error::custom("Invalid Side None")),
+    }
+}
+
+pub fn deserialize_side<'de, D>(deserializer: D) -> Result<Side, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: &str = Deserialize::deserialize(deserializer)?;
+    match value {
+        "buy" | "long" => Ok(Side::Buy),
+        "sell" | "short" => Ok(Side::Sell),
+        _ => Err(D::Error::custom(format!("Invalid Side value: {}", value))),
+    }
+}
+
+/// Websocket orders carry their side as direction, 0 being buy and 1 being sell
+pub fn deserialize_direction<'de, D>(deserializer: D) -> Result<Side, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: u8 = Deserialize::deserialize(deserializer)?;
+    match value {
+        0 => Ok(Side::Buy),
+        1 => Ok(Side::Sell),
+        _ => Err(D::Error::custom(format!(
+            "Invalid direction value: {}",
+            value
+        ))),
+    }
+}
+
+/// Parses REST RFC 3339 datetimes (e.g. `2024-01-01T00:00:00.000Z`) into milliseconds timestamps
+pub fn parse_rfc3339_ms<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: &str = Deserialize::deserialize(deserializer)?;
+    let datetime = DateTime::parse_from_rfc3339(value).map_err(D::Error::custom)?;
+    Ok(datetime.timestamp_millis())
+}
@@ -0,0 +1,1786 @@
+pub mod enums;
+pub mod functions;
+pub mod structs;
+#[cfg(test)]
+mod tests;
+use self::enums::{KrakenOrderType, KrakenWsMessage};
+use self::functions::{get_kraken_base_asset, get_kraken_symbol, sign_challenge, sign_request};
+use self::structs::{
+    AccountsData, CancelAllOrdersDto, CancelAllStatusData, CancelOrderDto, CancelStatusData,
+    ChallengeWsRequest, EditOrderDto, EditStatusData, EmptyDto, EmptyObject, EventWsMessage,
+    FetchFillsDto, FetchOrdersStatusDto, FillsData, InstrumentsResponseData,
+    KrakenHttpResponseWrapper, OpenOrderData, OpenPositionsData, OrdersStatusData, RestFillData,
+    SendOrderDto, SendStatusData, SetLeverageDto, SubscribeWsRequest, TickerResponseData,
+    WsChallenge,
+};
+use crate::enums::TraderExchangeId;
+use crate::r#static::TRADER_EXCHANGES_CONTEXT_MAP;
+use crate::{
+    config::{get_trader_exchange_config, WS_RECONNECT_INTERVAL_IN_SECS},
+    structs::{ApiCredentials, ApiEndpoints, ExchangeConfig},
+};
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use common::enums::order_action::OrderAction;
+use common::enums::symbol_id::SymbolId;
+use common::enums::trading_data_update::TradingDataUpdate;
+use common::functions::{
+    current_datetime, current_timestamp_ms, is_at_threshold, timestamp_minute_end,
+};
+use common::r#static::METRICS;
+use common::traits::exchange::{BenchmarkExchange, TraderHelper};
+use common::{
+    enums::{
+        balance::Balance, modifiers::leverage::Leverage, order_stage::OrderStage,
+        order_status::OrderStatus, order_type::OrderType, side::Side, time_in_force::TimeInForce,
+        trade_status::TradeStatus,
+    },
+    functions::{calculate_remainder, count_decimal_places, round_down_nth_decimal},
+    structs::{
+        BehaviorSubject, Contract, Execution, Order, OrderAmendment, Ticker, Trade, TradingSettings,
+    },
+    traits::exchange::TraderExchange,
+};
+use futures_util::SinkExt;
+use glow_error::GlowError;
+use reqwest::{
+    header::{self, HeaderMap, HeaderValue},
+    Client, Error, Method, RequestBuilder, Response,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{from_str, to_string as to_json_string};
+use serde_urlencoded::to_string as to_url_string;
+use std::{cmp::Reverse, collections::HashMap, sync::Arc, sync::Mutex, time::Duration};
+use tokio::{
+    net::TcpStream,
+    select,
+    time::sleep,
+    time::{interval, Interval},
+};
+use tokio_stream::StreamExt;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+const PRIVATE_FEEDS: [&str; 3] = ["fills", "open_orders", "balances"];
+/// Send status of reduce-only orders rejected as there's no position left for them to reduce
+const WOULD_NOT_REDUCE_POSITION_STATUS: &str = "wouldNotReducePosition";
+
+#[derive(Clone)]
+pub struct KrakenTraderExchange {
+    balance_update_emitter: BehaviorSubject<Balance>,
+    pub contracts: Arc<HashMap<SymbolId, Contract>>,
+    credentials: ApiCredentials,
+    endpoints: ApiEndpoints,
+    // endpoints and credentials websocket must reconnect with, once environment is switched
+    exchange_config_emitter: BehaviorSubject<ExchangeConfig>,
+    exchange_recovery_emitter: BehaviorSubject<TradingDataUpdate>,
+    executions_update_emitter: BehaviorSubject<Vec<Execution>>,
+    pub fee_rates: (f64, f64),
+    http: Client,
+    last_ws_error_ts: Arc<Mutex<Option<i64>>>,
+    minimum_notional_value: Option<f64>,
+    pub name: &'static str,
+    // removal messages only carry order ids, so last known order data is kept by order id
+    open_orders: Arc<Mutex<HashMap<String, OpenOrderData>>>,
+    order_update_emitter: BehaviorSubject<OrderAction>,
+    trade_update_emitter: BehaviorSubject<Option<Trade>>,
+    pub trading_settings: TradingSettings,
+    ws_challenge: Arc<Mutex<Option<WsChallenge>>>,
+}
+
+impl KrakenTraderExchange {
+    /// Fails if settings' exchange environment config isn't provided, so that trader never
+    /// falls back to a different environment than the selected one.
+    pub fn new(trading_settings: &TradingSettings) -> Result<Self, GlowError> {
+        let config =
+            get_trader_exchange_config(TraderExchangeId::Kraken, trading_settings.environment)?;
+        Ok(Self::from_config(trading_settings, config))
+    }
+
+    pub fn from_config(trading_settings: &TradingSettings, config: ExchangeConfig) -> Self {
+        let context = TRADER_EXCHANGES_CONTEXT_MAP
+            .get(&TraderExchangeId::Kraken)
+            .expect("Kraken to has Exchange Context");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+        headers.insert(header::CONNECTION, HeaderValue::from_static("keep-alive"));
+        headers.insert(
+            header::ACCEPT_ENCODING,
+            HeaderValue::from_static("gzip, deflate, br"),
+        );
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+
+        let balance_update_emitter = BehaviorSubject::new(Balance::default());
+        let exchange_recovery_emitter = BehaviorSubject::new(TradingDataUpdate::default());
+        let executions_update_emitter = BehaviorSubject::new(vec![]);
+        let order_update_emitter = BehaviorSubject::new(OrderAction::default());
+        let trade_update_emitter = BehaviorSubject::new(None);
+
+        Self {
+            balance_update_emitter,
+            contracts: Arc::new(context.contracts.clone()),
+            credentials: config.credentials,
+            endpoints: config.endpoints,
+            exchange_config_emitter: BehaviorSubject::new(config),
+            exchange_recovery_emitter,
+            executions_update_emitter,
+            fee_rates: (context.maker_fee, context.taker_fee),
+            http: Client::builder()
+                .default_headers(headers)
+                .build()
+                .expect("Reqwest client to build"),
+            last_ws_error_ts: Arc::new(Mutex::new(None)),
+            minimum_notional_value: None,
+            name: "Kraken",
+            open_orders: Arc::new(Mutex::new(HashMap::new())),
+            order_update_emitter,
+            trade_update_emitter,
+            trading_settings: trading_settings.clone(),
+            ws_challenge: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Switching exchange environment swaps REST endpoints and credentials, and has websocket
+    /// reconnect to the switched one. Settings are left unchanged if its config isn't provided.
+    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) -> Result<(), GlowError> {
+        if trading_settings.environment != self.trading_settings.environment {
+            let config =
+                get_trader_exchange_config(TraderExchangeId::Kraken, trading_settings.environment)?;
+            self.credentials = config.credentials;
+            self.endpoints = config.endpoints;
+            self.exchange_config_emitter.next(config);
+        }
+        self.trading_settings = trading_settings.clone();
+        Ok(())
+    }
+
+    /// Replaces stored contracts by `fetched_contracts`, keeping static ones missing from them.
+    pub fn patch_contracts(&mut self, fetched_contracts: HashMap<String, Contract>) {
+        let mut contracts = self.contracts.as_ref().clone();
+        for contract in fetched_contracts.into_values() {
+            contracts.insert(contract.symbol.id, contract);
+        }
+        self.contracts = Arc::new(contracts);
+    }
+
+    fn get_traded_kraken_symbol(&self) -> String {
+        get_kraken_symbol(self.get_traded_symbol().name)
+    }
+
+    async fn try_parse_response<T: DeserializeOwned>(
+        result: Result<Response, Error>,
+    ) -> Result<KrakenHttpResponseWrapper<T>, GlowError> {
+        if let Err(error) = result {
+            return Err(GlowError::from(error));
+        }
+
+        let response = result.unwrap();
+        if !response.status().is_success() {
+            let description = format!("try_response -> unsucessful response {:?}", response);
+            return Err(GlowError::new_unsuccessful_response(description));
+        }
+        let response_text = response.text().await?;
+        match from_str::<KrakenHttpResponseWrapper<T>>(&response_text) {
+            Ok(parsed_response) => Ok(parsed_response),
+            Err(error) => {
+                // failed requests don't carry requested data, so their error is reported instead
+                if let Ok(failed_response) =
+                    from_str::<KrakenHttpResponseWrapper<EmptyObject>>(&response_text)
+                {
+                    if !failed_response.is_success() {
+                        let description = format!(
+                            "try_response -> failed response {:?}",
+                            failed_response.error
+                        );
+                        return Err(GlowError::new_unsuccessful_response(description));
+                    }
+                }
+                Err(GlowError::from(error))
+            }
+        }
+    }
+
+    /// Signs payload as url encoded post data, sent as query for GET requests and as body otherwise
+    fn prepare_request_builder<T: Serialize>(
+        &self,
+        method: Method,
+        endpoint_path: &str,
+        payload: &T,
+    ) -> Result<RequestBuilder, GlowError> {
+        let nonce = current_timestamp_ms().to_string();
+        let post_data = to_url_string(payload)?;
+        let signature = sign_request(self.credentials.secret, &post_data, &nonce, endpoint_path)?;
+        let url = format!("{}/derivatives{}", self.endpoints.http, endpoint_path);
+        let request_builder = if method == Method::GET {
+            self.get_http_client().get(format!("{}?{}", url, post_data))
+        } else {
+            self.get_http_client().request(method, url).body(post_data)
+        };
+        Ok(request_builder
+            .header("APIKey", self.credentials.key)
+            .header("Nonce", nonce)
+            .header("Authent", signature))
+    }
+
+    /// Calculates ((open fee, close fee), fee rate, is maker) for a given (fee rate, is maker) pair
+    fn calculate_order_fees_at_rate(
+        &self,
+        fee_rate_and_is_maker: (f64, bool),
+        units: f64,
+        price: f64,
+    ) -> ((f64, f64), f64, bool) {
+        let (fee_rate, is_maker) = fee_rate_and_is_maker;
+        // Kraken charges fees over notional value, so close fee is estimated at open price
+        let notional = self
+            .get_traded_contract()
+            .kind
+            .calculate_notional(units, price);
+        let fee = notional * fee_rate;
+        ((fee, fee), fee_rate, is_maker)
+    }
+
+    async fn post_send_order(
+        &self,
+        payload: &SendOrderDto,
+    ) -> Result<KrakenHttpResponseWrapper<SendStatusData>, GlowError> {
+        let request_builder =
+            self.prepare_request_builder(Method::POST, "/api/v3/sendorder", payload)?;
+        let result = request_builder.send().await;
+        Self::try_parse_response::<SendStatusData>(result).await
+    }
+
+    async fn send_order(&self, payload: &SendOrderDto) -> Result<String, GlowError> {
+        let parsed_response = self.post_send_order(payload).await?;
+        Self::get_placed_order_id(payload, parsed_response)
+    }
+
+    /// Gets exchange id of order sent by `payload`, erroring unless it was placed.
+    fn get_placed_order_id(
+        payload: &SendOrderDto,
+        parsed_response: KrakenHttpResponseWrapper<SendStatusData>,
+    ) -> Result<String, GlowError> {
+        let is_success = parsed_response.is_success();
+        let send_status = parsed_response.data.send_status;
+        if !is_success || send_status.status != "placed" {
+            let error = format!("send_order -> unexpected response => {:?}", send_status);
+            return Err(GlowError::new(String::from("Wrong Response Error"), error));
+        }
+        if send_status
+            .cli_ord_id
+            .as_ref()
+            .is_some_and(|cli_ord_id| cli_ord_id != &payload.cli_ord_id)
+        {
+            let error = format!(
+                "send_order -> send_status.cli_ord_id != cli_ord_id! => {:?}",
+                send_status
+            );
+            return Err(GlowError::new(
+                String::from("Invalid Order Id Error"),
+                error,
+            ));
+        }
+        Ok(send_status.order_id.unwrap_or_default())
+    }
+
+    async fn edit_order(&self, payload: &EditOrderDto) -> Result<bool, GlowError> {
+        let request_builder =
+            self.prepare_request_builder(Method::POST, "/api/v3/editorder", payload)?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<EditStatusData>(result).await?;
+        if !parsed_response.is_success() || parsed_response.data.edit_status.status != "edited" {
+            println!("edit_order -> unexpected response {:?}", parsed_response);
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Closes `close_order` and emits it, as trade's position was already closed out-of-band
+    /// (e.g. manually or by liquidation), so no order update is going to come from exchange.
+    /// It's closed by the fills that actually closed the position, if exchange reports them,
+    /// otherwise it's flagged as closed out-of-band at `est_price`.
+    async fn close_flat_trade_order(
+        &self,
+        trade: &Trade,
+        close_order: Order,
+        est_price: f64,
+    ) -> Order {
+        let now = current_timestamp_ms() as i64;
+        let fills = self.fetch_fills(None).await.unwrap_or_else(|error| {
+            println!(
+                "close_flat_trade_order -> failed to fetch closing fills of trade {}: {:?}",
+                trade.id, error
+            );
+            vec![]
+        });
+        let mut closing_fills: Vec<_> = fills
+            .into_iter()
+            .filter(|fill| {
+                fill.side != trade.open_order.side
+                    && fill.order_id != trade.open_order.uuid
+                    && fill.fill_time >= trade.open_order.created_at
+            })
+            .collect();
+        closing_fills.sort_by_key(|fill| fill.fill_time);
+        let closing_executions = closing_fills
+            .into_iter()
+            .map(|fill| {
+                let mut execution = fill.new_execution(self.fee_rates);
+                execution.closed_qty = execution.qty;
+                execution
+            })
+            .collect();
+        let close_order = close_order
+            .close_by_executions(closing_executions)
+            .unwrap_or_else(|| close_order.close_out_of_band(est_price, now));
+        self.order_update_emitter
+            .next(OrderAction::Update(close_order.clone()));
+        close_order
+    }
+
+    fn get_price_level_order_id(trade_id: &str, price_level: &str) -> String {
+        format!("{}_{}", trade_id, price_level)
+    }
+
+    /// Places stop loss and take profit trigger orders for an open order, if any is set
+    async fn place_price_level_orders(&self, order: &Order) -> Result<(), GlowError> {
+        let trade_id = order
+            .id
+            .strip_suffix(&format!("_{}", OrderStage::Open.to_string()))
+            .unwrap_or(&order.id);
+        let close_side = order.side.get_opposite_side()?;
+        let price_levels = [
+            ("sl", KrakenOrderType::Stop, order.stop_loss_price),
+            ("tp", KrakenOrderType::TakeProfit, order.take_profit_price),
+        ];
+        for (price_level, order_type, price) in price_levels {
+            if let Some(price) = price {
+                let payload = SendOrderDto::new_trigger_order(
+                    Self::get_price_level_order_id(trade_id, price_level),
+                    order_type,
+                    &order.symbol,
+                    close_side,
+                    order.units,
+                    price,
+                );
+                self.send_order(&payload).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Cancels price level trigger orders left behind by closed positions, ignoring missing ones
+    async fn cancel_price_level_orders(&self, trade_id: &str) {
+        for price_level in ["sl", "tp"] {
+            let order_id = Self::get_price_level_order_id(trade_id, price_level);
+            if let Err(error) = self.cancel_order(order_id).await {
+                println!("cancel_price_level_orders -> error {:?}", error);
+            }
+        }
+    }
+
+    async fn fetch_fills(
+        &self,
+        last_fill_time: Option<i64>,
+    ) -> Result<Vec<RestFillData>, GlowError> {
+        let last_fill_time = last_fill_time
+            .and_then(|timestamp| Utc.timestamp_millis_opt(timestamp).single())
+            .map(|datetime| datetime.to_rfc3339_opts(SecondsFormat::Millis, true));
+        let payload = FetchFillsDto { last_fill_time };
+        let request_builder =
+            self.prepare_request_builder(Method::GET, "/api/v3/fills", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<FillsData>(result).await?;
+        let traded_symbol = self.get_traded_kraken_symbol();
+        let fills = parsed_response
+            .data
+            .fills
+            .into_iter()
+            .filter(|fill| fill.symbol.to_uppercase() == traded_symbol)
+            .collect();
+        Ok(fills)
+    }
+
+    fn process_open_orders_message(
+        &self,
+        order_data: Option<OpenOrderData>,
+        order_id: Option<String>,
+        is_cancel: bool,
+    ) -> Option<OrderAction> {
+        let leverage_factor = self.get_leverage_factor();
+        let taker_fee = self.get_taker_fee();
+        let mut open_orders_guard = self.open_orders.lock().unwrap();
+
+        if let Some(order_data) = order_data {
+            if order_data.instrument.to_uppercase() != self.get_traded_kraken_symbol() {
+                return None;
+            }
+            open_orders_guard.insert(order_data.order_id.clone(), order_data.clone());
+            // resting stop loss/take profit orders are only relevant once triggered
+            if order_data.is_trigger_order() {
+                return None;
+            }
+            let order = order_data.new_order_from_response_data(leverage_factor, taker_fee);
+            return Some(OrderAction::Update(order));
+        }
+
+        let mut order_data = open_orders_guard.remove(&order_id?)?;
+        if is_cancel {
+            if order_data.is_trigger_order() {
+                return None;
+            }
+            let order = order_data.new_order_from_response_data(leverage_factor, taker_fee);
+            return Some(OrderAction::Cancel(order));
+        }
+        order_data.filled = order_data.qty;
+        let order = order_data.new_order_from_response_data(leverage_factor, taker_fee);
+        if order.is_stop {
+            return Some(OrderAction::Stop(order));
+        }
+        Some(OrderAction::Update(order))
+    }
+}
+
+impl TraderHelper for KrakenTraderExchange {
+    #[inline]
+    fn get_contracts(&self) -> &HashMap<SymbolId, Contract> {
+        &self.contracts
+    }
+    #[inline]
+    fn get_maker_fee(&self) -> f64 {
+        self.fee_rates.0
+    }
+    #[inline]
+    fn get_taker_fee(&self) -> f64 {
+        self.fee_rates.1
+    }
+    #[inline]
+    fn get_trading_settings(&self) -> &TradingSettings {
+        &self.trading_settings
+    }
+
+    fn calculate_open_order_units_and_balance_remainder(
+        &self,
+        side: Side,
+        order_cost: f64,
+        price: f64,
+    ) -> Result<(f64, f64), GlowError> {
+        if side == Side::None {
+            let error = format!(
+                "calculate_order_units_and_balance_remainder -> Invalid side {:?}",
+                side
+            );
+            return Err(GlowError::new(String::from("Invalid Side Error"), error));
+        }
+        let trading_settings = self.get_trading_settings();
+        let leverage_factor = trading_settings.leverage.get_factor();
+        // Order Cost = Initial Margin + Fee to Open Position + Fee to Close Position
+        // Initial Margin = (Order Price × Order Quantity) / Leverage
+        // Fee to Open/Close Position = Order Quantity × Order Price × Taker Fee Rate
+        let contract = self.get_traded_contract();
+        let taker_fee_rate = self.get_taker_fee();
+        let maximum_order_sizes = contract.maximum_order_sizes;
+
+        let mut units =
+            order_cost * leverage_factor / (price * (1.0 + 2.0 * taker_fee_rate * leverage_factor));
+
+        let fract_units = calculate_remainder(units, contract.minimum_order_size);
+        let size_decimals = count_decimal_places(contract.minimum_order_size);
+        units = round_down_nth_decimal(units - fract_units, size_decimals);
+
+        let open_order_type = trading_settings.order_types.0;
+        let maximum_order_size = if open_order_type == OrderType::Market {
+            maximum_order_sizes.0
+        } else {
+            maximum_order_sizes.1
+        };
+
+        let error = if units == 0.0 || units < contract.minimum_order_size {
+            Some(format!(
+                "units < contract.minimum_order_size | units = {}, minimum order size = {}",
+                units, contract.minimum_order_size
+            ))
+        } else if units > maximum_order_size {
+            Some(format!(
+                "units > contract.maximum_order_size | units = {}, maximum order size = {}",
+                units, maximum_order_size
+            ))
+        } else if leverage_factor > contract.max_leverage {
+            Some(format!(
+                "leverage_factor > contract.max_leverage | leverage_factor = {}, max_leverage = {}",
+                leverage_factor, contract.max_leverage
+            ))
+        } else {
+            None
+        };
+
+        if let Some(error) = error {
+            println!("Some contract constraints stopped the order from being placed");
+            let error = format!(
+                "calculate_order_units -> Invalid side {:?}, error {}",
+                side, error
+            );
+            return Err(GlowError::new(String::from("Invalid Side Error"), error));
+        }
+
+        let balance_remainder = fract_units * price / leverage_factor;
+        Ok((units, balance_remainder))
+    }
+
+    fn get_order_fee_rate(&self, order_type: OrderType) -> (f64, bool) {
+        if order_type == OrderType::Limit {
+            (self.get_maker_fee(), true)
+        } else {
+            (self.get_taker_fee(), false)
+        }
+    }
+
+    fn calculate_order_fees(
+        &self,
+        order_type: OrderType,
+        _side: Side,
+        units: f64,
+        price: f64,
+    ) -> ((f64, f64), f64, bool) {
+        let fee_rate_and_is_maker = self.get_order_fee_rate(order_type);
+        self.calculate_order_fees_at_rate(fee_rate_and_is_maker, units, price)
+    }
+
+    fn calculate_order_stop_loss_price(&self, side: Side, price: f64) -> Option<f64> {
+        let trading_settings = self.get_trading_settings();
+        let stop_loss = trading_settings.price_level_modifier_map.get("sl")?;
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let stop_loss_percentage = stop_loss.get_percentage();
+        let position_mod = match side {
+            Side::Sell => leverage_factor + stop_loss_percentage,
+            Side::Buy => leverage_factor - stop_loss_percentage,
+            Side::None => return None,
+        };
+        let contract = self.get_traded_contract();
+        Some(contract.round_price_to_tick(price * position_mod / leverage_factor))
+    }
+
+    fn calculate_order_take_profit_price(&self, side: Side, price: f64) -> Option<f64> {
+        let trading_settings = self.get_trading_settings();
+        let take_profit = trading_settings.price_level_modifier_map.get("tp")?;
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let take_profit_percentage = take_profit.get_percentage();
+        let position_mod = match side {
+            Side::Sell => leverage_factor - take_profit_percentage,
+            Side::Buy => leverage_factor + take_profit_percentage,
+            Side::None => return None,
+        };
+        let contract = self.get_traded_contract();
+        Some(contract.round_price_to_tick(price * position_mod / leverage_factor))
+    }
+}
+
+impl TraderExchange for KrakenTraderExchange {
+    #[inline]
+    fn get_http_client(&self) -> &Client {
+        &self.http
+    }
+    #[inline]
+    fn get_ws_url(&self) -> Result<Url, GlowError> {
+        let url = Url::parse(&format!("{}/ws/v1", self.endpoints.ws))?;
+        Ok(url)
+    }
+
+    /// Unlike a static signed payload, Kraken sends a challenge that must be signed and carried by
+    /// every private feed subscription
+    async fn auth_ws(
+        &self,
+        wss: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), GlowError> {
+        let challenge_request = ChallengeWsRequest::new(self.credentials.key.to_string());
+        let challenge_json_str = to_json_string(&challenge_request)?;
+        wss.send(Message::Text(challenge_json_str)).await?;
+
+        while let Some(message) = wss.next().await {
+            let json = match message? {
+                Message::Text(json) => json,
+                _ => continue,
+            };
+            let event = match from_str::<EventWsMessage>(&json) {
+                Ok(event) => event,
+                Err(_) => continue,
+            };
+            match (event.event.as_str(), event.message) {
+                ("challenge", Some(original)) => {
+                    let signed = sign_challenge(self.credentials.secret, &original)?;
+                    let mut challenge_guard = self.ws_challenge.lock().unwrap();
+                    *challenge_guard = Some(WsChallenge { original, signed });
+                    println!(
+                        "{:?} | Trading Exchange websocket challenge received",
+                        current_datetime()
+                    );
+                    return Ok(());
+                }
+                ("error", message) => {
+                    let error = format!("auth_ws -> challenge request error {:?}", message);
+                    return Err(GlowError::new(String::from("WebSocket Auth Error"), error));
+                }
+                _ => {}
+            }
+        }
+
+        let error = String::from("auth_ws -> websocket closed before challenge was received");
+        Err(GlowError::new(String::from("WebSocket Auth Error"), error))
+    }
+
+    async fn subscribe_ws(
+        &self,
+        wss: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), GlowError> {
+        let challenge = self.ws_challenge.lock().unwrap().clone();
+        let challenge = challenge.ok_or(GlowError::new(
+            String::from("WebSocket Auth Error"),
+            String::from("subscribe_ws -> websocket challenge wasn't signed"),
+        ))?;
+
+        for feed in PRIVATE_FEEDS {
+            let subscribe_request = SubscribeWsRequest::new(
+                feed.to_string(),
+                self.credentials.key.to_string(),
+                &challenge,
+            );
+            let subscription_json = to_json_string(&subscribe_request)?;
+            wss.send(Message::Text(subscription_json)).await?;
+        }
+        Ok(())
+    }
+
+    fn process_ws_message(&self, json: &String) -> Result<(), GlowError> {
+        let response: KrakenWsMessage = from_str::<KrakenWsMessage>(json).unwrap_or_default();
+
+        match response {
+            KrakenWsMessage::None => Ok(()),
+            KrakenWsMessage::Event(message) => {
+                println!(
+                    "{:?} | Trading Exchange {} event: {:?} {:?}",
+                    current_datetime(),
+                    message.event,
+                    message.feed,
+                    message.message
+                );
+                Ok(())
+            }
+            KrakenWsMessage::Fills(message) => {
+                // snapshot carries past fills, which are fetched through REST when needed
+                if message.feed != "fills" {
+                    return Ok(());
+                }
+                let traded_symbol = self.get_traded_kraken_symbol();
+                let executions: Vec<Execution> = message
+                    .fills
+                    .iter()
+                    .filter(|fill| fill.instrument.to_uppercase() == traded_symbol)
+                    .map(|fill| fill.new_execution(self.fee_rates))
+                    .collect();
+                if !executions.is_empty() {
+                    self.executions_update_emitter.next(executions);
+                }
+                Ok(())
+            }
+            KrakenWsMessage::OpenOrders(message) => {
+                let order_id = message.order_id.clone();
+                if let Some(order_action) =
+                    self.process_open_orders_message(message.order, order_id, message.is_cancel)
+                {
+                    self.order_update_emitter.next(order_action);
+                }
+                Ok(())
+            }
+            KrakenWsMessage::Balances(message) => {
+                let usdt_data =
+                    message.flex_futures.currencies.get("USDT").expect(
+                        "process_ws_message error -> USDT is missing in ws balances message",
+                    );
+                let balance =
+                    Balance::new(message.timestamp, usdt_data.available, usdt_data.quantity);
+                self.balance_update_emitter.next(balance);
+                Ok(())
+            }
+        }
+    }
+
+    /// Kraken only serves fills before a given time, so those are filtered by order and time range
+    async fn fetch_order_executions(
+        &self,
+        order_uuid: String,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<Execution>, GlowError> {
+        let fills = self.fetch_fills(Some(end_timestamp)).await?;
+        let executions = fills
+            .into_iter()
+            .filter(|fill| {
+                fill.order_id == order_uuid
+                    && fill.fill_time >= start_timestamp
+                    && fill.fill_time <= end_timestamp
+            })
+            .map(|fill| fill.new_execution(self.fee_rates))
+            .collect();
+        Ok(executions)
+    }
+
+    /// Kraken doesn't keep closed orders queryable, so these are rebuilt from their fills
+    async fn fetch_history_order(
+        &self,
+        id: Option<String>,
+        side: Option<Side>,
+        fetch_executions: bool,
+    ) -> Result<Order, GlowError> {
+        let mut fills = self.fetch_fills(None).await?;
+        fills.sort_by_key(|fill| Reverse(fill.fill_time));
+
+        let latest_fill = fills.iter().find(|fill| {
+            id.as_ref()
+                .is_none_or(|id| fill.cli_ord_id.as_ref() == Some(id))
+                && side.is_none_or(|side| fill.side == side)
+        });
+
+        if latest_fill.is_none() {
+            let error = "fetch_history_order -> no filled order was found".to_string();
+            return Err(GlowError::new(String::from("Failed Query"), error));
+        }
+        let latest_fill = latest_fill.unwrap().clone();
+
+        let order_fills: Vec<&RestFillData> = fills
+            .iter()
+            .filter(|fill| fill.order_id == latest_fill.order_id)
+            .collect();
+        let units: f64 = order_fills.iter().map(|fill| fill.size).sum();
+        let avg_price = order_fills
+            .iter()
+            .map(|fill| fill.price * fill.size)
+            .sum::<f64>()
+            / units;
+        let created_at = order_fills
+            .last()
+            .map(|fill| fill.fill_time)
+            .unwrap_or_default();
+
+        let id = latest_fill
+            .cli_ord_id
+            .clone()
+            .unwrap_or(latest_fill.order_id.clone());
+        let is_close = functions::is_close_order_id(&id);
+        let status = if id.ends_with("_sl") {
+            OrderStatus::StoppedSL
+        } else if id.ends_with("_tp") {
+            OrderStatus::StoppedTP
+        } else if is_close {
+            OrderStatus::Closed
+        } else {
+            OrderStatus::Filled
+        };
+        let execution = latest_fill.new_execution(self.fee_rates);
+
+        let order = Order::new(
+            Some(avg_price),
+            0.0,
+            created_at,
+            vec![],
+            id,
+            is_close,
+            status == OrderStatus::StoppedSL || status == OrderStatus::StoppedTP,
+            self.get_leverage_factor(),
+            execution.order_type,
+            latest_fill.side,
+            status,
+            None,
+            functions::get_symbol_from_kraken(&latest_fill.symbol),
+            None,
+            self.get_taker_fee(),
+            if execution.is_maker {
+                TimeInForce::GTC
+            } else {
+                TimeInForce::IOC
+            },
+            units,
+            latest_fill.fill_time,
+            latest_fill.order_id.clone(),
+        );
+
+        if !fetch_executions {
+            return Ok(order);
+        }
+        let executions = order_fills
+            .into_iter()
+            .map(|fill| fill.new_execution(self.fee_rates))
+            .collect();
+        Ok(order.push_executions_if_new(executions))
+    }
+
+    async fn fetch_current_order(
+        &self,
+        order_id: String,
+        fetch_executions: bool,
+    ) -> Result<Order, GlowError> {
+        let payload = FetchOrdersStatusDto {
+            cli_ord_id: order_id.clone(),
+        };
+        let request_builder =
+            self.prepare_request_builder(Method::POST, "/api/v3/orders/status", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<OrdersStatusData>(result).await?;
+
+        let order_response = parsed_response.data.orders.into_iter().find(|order_data| {
+            order_data.order.cli_ord_id.as_ref() == Some(&order_id)
+                && !order_data.is_trigger_order()
+        });
+
+        // filled orders leave orders status shortly after, so they're looked up in fills
+        if order_response.is_none() {
+            return self
+                .fetch_history_order(Some(order_id), None, fetch_executions)
+                .await;
+        }
+        let order_response = order_response.unwrap();
+
+        let executed_qty = order_response.order.filled;
+        let mut order = order_response
+            .new_order_from_response_data(self.get_leverage_factor(), self.get_taker_fee());
+
+        if fetch_executions && executed_qty > 0.0 {
+            let executions = self
+                .fetch_order_executions(
+                    order.uuid.clone(),
+                    order.created_at,
+                    current_timestamp_ms(),
+                )
+                .await?;
+            order = order.push_executions_if_new(executions);
+        }
+        Ok(order)
+    }
+
+    async fn fetch_current_trade_position(&self) -> Result<Option<Trade>, GlowError> {
+        let request_builder =
+            self.prepare_request_builder(Method::GET, "/api/v3/openpositions", &EmptyDto {})?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<OpenPositionsData>(result).await?;
+
+        let traded_symbol = self.get_traded_kraken_symbol();
+        let position_response = parsed_response
+            .data
+            .open_positions
+            .into_iter()
+            .find(|position| position.symbol.to_uppercase() == traded_symbol);
+
+        if position_response.is_none() {
+            return Ok(None);
+        }
+        let position_response = position_response.unwrap();
+
+        let latest_order = self
+            .fetch_history_order(None, Some(position_response.side), true)
+            .await?;
+
+        let open_order;
+        let close_order: Option<Order>;
+
+        if latest_order.is_close {
+            let open_order_id = latest_order.id.replace("close", "open");
+            close_order = Some(latest_order);
+            open_order = self
+                .fetch_history_order(Some(open_order_id), None, true)
+                .await?;
+        } else {
+            let close_order_id = latest_order.id.replace("open", "close");
+            open_order = latest_order;
+            close_order = self.fetch_current_order(close_order_id, true).await.ok();
+        }
+
+        let trade = Trade::new(open_order, close_order);
+
+        Ok(Some(trade))
+    }
+
+    async fn fetch_trade_state(
+        &self,
+        trade_id: String,
+        last_status: TradeStatus,
+    ) -> Result<Trade, GlowError> {
+        let open_order_id = format!("{}_{}", trade_id, OrderStage::Open.to_string());
+        let close_order_id = format!("{}_{}", trade_id, OrderStage::Close.to_string());
+
+        let open_order = self.fetch_current_order(open_order_id, true).await?;
+        let close_order = match last_status {
+            TradeStatus::Cancelled => None,
+            TradeStatus::New | TradeStatus::PartiallyOpen | TradeStatus::PendingCloseOrder => {
+                if open_order.status == OrderStatus::Filled {
+                    self.fetch_current_order(close_order_id, true).await.ok()
+                } else {
+                    None
+                }
+            }
+            TradeStatus::CloseOrderStandBy | TradeStatus::PartiallyClosed | TradeStatus::Closed => {
+                Some(self.fetch_current_order(close_order_id, true).await?)
+            }
+        };
+
+        Ok(Trade::new(open_order, close_order))
+    }
+
+    async fn fetch_current_usdt_balance(&self) -> Result<Balance, GlowError> {
+        let request_builder =
+            self.prepare_request_builder(Method::GET, "/api/v3/accounts", &EmptyDto {})?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<AccountsData>(result).await?;
+
+        let usdt_data = parsed_response
+            .data
+            .accounts
+            .flex
+            .currencies
+            .get("USDT")
+            .cloned()
+            .expect("get_current_usdt_balance -> missing usdt currency data");
+
+        let balance = Balance::new(
+            current_timestamp_ms(),
+            usdt_data.available,
+            usdt_data.quantity,
+        );
+        Ok(balance)
+    }
+
+    async fn fetch_base_asset_balance(&self) -> Result<f64, GlowError> {
+        let request_builder =
+            self.prepare_request_builder(Method::GET, "/api/v3/accounts", &EmptyDto {})?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<AccountsData>(result).await?;
+
+        let base_asset = get_kraken_base_asset(self.get_traded_symbol().name);
+        Ok(parsed_response
+            .data
+            .accounts
+            .flex
+            .currencies
+            .get(base_asset)
+            .map(|currency_data| currency_data.quantity)
+            .unwrap_or_default())
+    }
+
+    async fn fetch_ticker(&self) -> Result<Ticker, GlowError> {
+        let endpoint_path = format!("/api/v3/tickers/{}", self.get_traded_kraken_symbol());
+        let request_builder =
+            self.prepare_request_builder(Method::GET, &endpoint_path, &EmptyDto {})?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<TickerResponseData>(result).await?;
+
+        let ticker_data = parsed_response.data.ticker;
+        Ok(Ticker::new(
+            current_timestamp_ms(),
+            ticker_data.bid,
+            ticker_data.ask,
+            ticker_data.last,
+        ))
+    }
+
+    async fn fetch_instruments_info(&self) -> Result<HashMap<String, Contract>, GlowError> {
+        let request_builder =
+            self.prepare_request_builder(Method::GET, "/api/v3/instruments", &EmptyDto {})?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<InstrumentsResponseData>(result).await?;
+
+        let mut contracts = HashMap::new();
+        for symbol in self.get_unique_symbols() {
+            let kraken_symbol = get_kraken_symbol(symbol.name);
+            let contract = parsed_response
+                .data
+                .instruments
+                .iter()
+                .find(|instrument| instrument.symbol.eq_ignore_ascii_case(&kraken_symbol))
+                .and_then(|instrument| instrument.to_contract(self.contracts.get(&symbol.id)))
+                .ok_or(GlowError::new(
+                    String::from("Fetch Instruments Info Error"),
+                    format!(
+                        "fetch_instruments_info -> missing {} instrument info",
+                        kraken_symbol
+                    ),
+                ))?;
+            contracts.insert(symbol.name.to_string(), contract);
+        }
+
+        Ok(contracts)
+    }
+
+    /// Kraken has no server time endpoint, so it's read from traded symbol's ticker response
+    async fn server_time(&self) -> Result<i64, GlowError> {
+        let endpoint_path = format!("/api/v3/tickers/{}", self.get_traded_kraken_symbol());
+        let request_builder =
+            self.prepare_request_builder(Method::GET, &endpoint_path, &EmptyDto {})?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<EmptyObject>(result).await?;
+        let server_time = parsed_response.server_time.ok_or_else(|| {
+            GlowError::new_str(
+                "Missing Server Time",
+                "server_time -> response has no server time",
+            )
+        })?;
+        let server_datetime = DateTime::parse_from_rfc3339(&server_time).map_err(|error| {
+            GlowError::new(
+                String::from("Invalid Server Time"),
+                format!(
+                    "server_time -> {} couldn't be parsed: {}",
+                    server_time, error
+                ),
+            )
+        })?;
+
+        Ok(server_datetime.timestamp_millis())
+    }
+
+    async fn open_order(
+        &self,
+        side: Side,
+        total_balance: f64,
+        expected_price: f64,
+    ) -> Result<Order, GlowError> {
+        assert_ne!(side, Side::None, "Invalid Open Order Side!");
+        assert!(
+            expected_price > 0.0,
+            "open_order -> expected price must be greater than 0!"
+        );
+
+        let trading_settings = self.get_trading_settings();
+        let traded_contract = self.get_traded_contract();
+
+        let mut expected_price = expected_price;
+        if trading_settings.get_open_order_type() == OrderType::Limit {
+            // move last price marginally away from book in order to realize maker_fee
+            if side == Side::Sell {
+                expected_price += traded_contract.tick_size;
+            } else {
+                expected_price -= traded_contract.tick_size;
+            }
+        }
+        let expected_price = traded_contract.round_price_to_tick(expected_price);
+        let order_cost = total_balance * trading_settings.allocation_percentage;
+
+        let mut order = self.new_open_order(side, order_cost, expected_price)?;
+        order.units = traded_contract.round_qty_to_step(order.units)?;
+        let payload: SendOrderDto = order.clone().into();
+        order.uuid = self.send_order(&payload).await?;
+
+        if let Err(error) = self.place_price_level_orders(&order).await {
+            println!(
+                "open_order -> failed to place price level orders {:?}",
+                error
+            );
+        }
+        Ok(order)
+    }
+
+    async fn amend_order(
+        &self,
+        order_id: String,
+        updated_units: Option<f64>,
+        updated_price: Option<f64>,
+        updated_stop_loss_price: Option<f64>,
+        updated_take_profit_price: Option<f64>,
+    ) -> Result<bool, GlowError> {
+        let traded_contract = self.get_traded_contract();
+        let mut amendment = OrderAmendment::new(
+            traded_contract,
+            updated_units,
+            updated_price,
+            updated_stop_loss_price,
+            updated_take_profit_price,
+        )?;
+        // exchange rejects amends leaving order unchanged, so only changed values are sent
+        let current_trade = self.trade_update_emitter.value();
+        if let Some(current_order) = current_trade
+            .as_ref()
+            .and_then(|trade| trade.get_order_by_id(&order_id))
+        {
+            amendment = current_order.diff_amendment(amendment, traded_contract);
+        }
+        if amendment.is_empty() {
+            return Ok(true);
+        }
+
+        let mut amended = true;
+        if amendment.units.is_some() || amendment.price.is_some() {
+            let payload = EditOrderDto {
+                cli_ord_id: order_id.clone(),
+                size: amendment.units,
+                limit_price: amendment.price,
+                stop_price: None,
+            };
+            amended &= self.edit_order(&payload).await?;
+        }
+
+        // stop loss and take profit live in their own trigger orders
+        let trade_id = order_id
+            .rsplit_once('_')
+            .map_or(order_id.as_str(), |(trade_id, _)| trade_id);
+        let price_levels = [
+            ("sl", amendment.stop_loss_price),
+            ("tp", amendment.take_profit_price),
+        ];
+        for (price_level, price) in price_levels {
+            if let Some(price) = price {
+                let payload = EditOrderDto {
+                    cli_ord_id: Self::get_price_level_order_id(trade_id, price_level),
+                    size: amendment.units,
+                    limit_price: None,
+                    stop_price: Some(price),
+                };
+                amended &= self.edit_order(&payload).await?;
+            }
+        }
+
+        Ok(amended)
+    }
+
+    async fn scale_out_position(
+        &self,
+        trade: &Trade,
+        units: f64,
+        price: f64,
+    ) -> Result<bool, GlowError> {
+        // take profit trigger order is already placed for the whole position, so it's shrunk to `units`
+        let traded_contract = self.get_traded_contract();
+        let payload = EditOrderDto {
+            cli_ord_id: Self::get_price_level_order_id(&trade.id, "tp"),
+            size: Some(traded_contract.round_qty_to_step(units)?),
+            limit_price: None,
+            stop_price: Some(traded_contract.round_price_to_tick(price)),
+        };
+        self.edit_order(&payload).await
+    }
+
+    async fn try_close_position(
+        &self,
+        trade: &Trade,
+        est_price: f64,
+        reduce_only: bool,
+    ) -> Result<Order, GlowError> {
+        let mut est_price = est_price;
+        let traded_contract = self.get_traded_contract();
+        let trading_settings = self.get_trading_settings();
+        let close_order_type = trading_settings.get_close_order_type();
+
+        if close_order_type == OrderType::Limit {
+            // close order will have open order opposite side, move price marginally away from book in order to realize maker_fee
+            if trade.open_order.side == Side::Sell {
+                est_price -= traded_contract.tick_size;
+            } else if trade.open_order.side == Side::Buy {
+                est_price += traded_contract.tick_size;
+            }
+            est_price = traded_contract.round_price_to_tick(est_price);
+        }
+
+        let (est_fee_rate, _) = self.get_order_fee_rate(close_order_type);
+        let mut close_order = trade.new_close_order(close_order_type, est_price)?;
+
+        let position_lock = trading_settings.position_lock_modifier;
+        let gross_profit_and_loss = trade.calculate_gross_pnl(traded_contract.kind, est_price);
+        let total_fee = trade.estimate_total_fee(traded_contract.kind, est_price, est_fee_rate);
+        if position_lock.is_close_locked(gross_profit_and_loss, total_fee) {
+            let error = format!(
+                "Trade wasn't closed due to {:?} position lock -> gross profit and loss = {}, total fee = {}",
+                position_lock, gross_profit_and_loss, total_fee
+            );
+            return Err(GlowError::new(String::from("Close Position Error"), error));
+        }
+
+        let payload = SendOrderDto::from(close_order.clone()).with_reduce_only(reduce_only);
+        let parsed_response = self.post_send_order(&payload).await?;
+        if reduce_only
+            && parsed_response.is_success()
+            && parsed_response.data.send_status.status == WOULD_NOT_REDUCE_POSITION_STATUS
+        {
+            println!(
+                "try_close_position -> no position left to reduce, trade {} is already flat",
+                trade.id
+            );
+            self.cancel_price_level_orders(&trade.id).await;
+            return Ok(self
+                .close_flat_trade_order(trade, close_order, est_price)
+                .await);
+        }
+        close_order.uuid = Self::get_placed_order_id(&payload, parsed_response)?;
+        self.cancel_price_level_orders(&trade.id).await;
+        Ok(close_order)
+    }
+
+    async fn cancel_order(&self, order_id: String) -> Result<bool, GlowError> {
+        let payload = CancelOrderDto {
+            cli_ord_id: order_id,
+        };
+        let request_builder =
+            self.prepare_request_builder(Method::POST, "/api/v3/cancelorder", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<CancelStatusData>(result).await?;
+        if !parsed_response.is_success() || parsed_response.data.cancel_status.status != "cancelled"
+        {
+            println!("cancel_order -> parsed response {:?}", parsed_response);
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    async fn cancel_all_orders(&self) -> Result<usize, GlowError> {
+        let payload = CancelAllOrdersDto {
+            symbol: self.get_traded_kraken_symbol(),
+        };
+        let request_builder =
+            self.prepare_request_builder(Method::POST, "/api/v3/cancelallorders", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<CancelAllStatusData>(result).await?;
+        let is_success = parsed_response.is_success();
+        let cancel_status = parsed_response.data.cancel_status;
+        if !is_success
+            || !["cancelled", "noOrdersToCancel"].contains(&cancel_status.status.as_str())
+        {
+            let error = format!(
+                "cancel_all_orders -> unexpected response {:?}",
+                cancel_status
+            );
+            return Err(GlowError::new(String::from("Cancel Orders Error"), error));
+        }
+        Ok(cancel_status.cancelled_orders.len())
+    }
+
+    async fn flatten_positions(&self) -> Result<(), GlowError> {
+        let cancelled_orders = self.cancel_all_orders().await?;
+        println!("flatten_positions -> {} orders cancelled", cancelled_orders);
+
+        let request_builder =
+            self.prepare_request_builder(Method::GET, "/api/v3/openpositions", &EmptyDto {})?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<OpenPositionsData>(result).await?;
+
+        let traded_symbol = self.get_traded_kraken_symbol();
+        let positions = parsed_response
+            .data
+            .open_positions
+            .into_iter()
+            .filter(|position| position.symbol.to_uppercase() == traded_symbol);
+
+        for position in positions {
+            if position.size == 0.0 {
+                continue;
+            }
+            let close_side = position.side.get_opposite_side()?;
+            let cli_ord_id = format!(
+                "{}_{}_flatten",
+                self.get_traded_symbol().name,
+                current_timestamp_ms()
+            );
+            let payload = SendOrderDto::new_reduce_only_market_order(
+                cli_ord_id,
+                traded_symbol.clone(),
+                close_side,
+                position.size,
+            );
+            self.send_order(&payload).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn set_leverage(&self, leverage: Leverage) -> Result<bool, GlowError> {
+        let leverage_factor = leverage.get_factor();
+        let traded_contract = self.get_traded_contract();
+        if leverage_factor > traded_contract.max_leverage {
+            let error = format!(
+                "symbol {} only allows for max {} leverage, {} was sent",
+                traded_contract.symbol.name, traded_contract.max_leverage, leverage_factor
+            );
+            return Err(GlowError::new(
+                String::from("Invalid Leverage Error"),
+                error,
+            ));
+        }
+
+        let payload = SetLeverageDto {
+            symbol: self.get_traded_kraken_symbol(),
+            max_leverage: leverage_factor,
+        };
+        let request_builder =
+            self.prepare_request_builder(Method::PUT, "/api/v3/leveragepreferences", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<EmptyObject>(result).await?;
+        Ok(parsed_response.is_success())
+    }
+
+    fn new_open_order(&self, side: Side, order_cost: f64, price: f64) -> Result<Order, GlowError> {
+        let trading_settings = self.get_trading_settings();
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let open_order_type = trading_settings.get_open_order_type();
+        let (units, balance_remainder) =
+            self.calculate_open_order_units_and_balance_remainder(side, order_cost, price)?;
+
+        let contract = self.get_traded_contract();
+
+        let timestamp = current_timestamp_ms();
+        let id = format!(
+            "{}_{}_{}",
+            &contract.symbol.name,
+            timestamp,
+            OrderStage::Open.to_string()
+        );
+        let (avg_price, time_in_force) = if open_order_type == OrderType::Limit {
+            (Some(price), TimeInForce::GTC)
+        } else {
+            (None, TimeInForce::IOC)
+        };
+        let stop_loss_price = self.calculate_order_stop_loss_price(side, price);
+        let take_profit_price = self.calculate_order_take_profit_price(side, price);
+
+        let order = Order::new(
+            avg_price,
+            balance_remainder,
+            timestamp,
+            vec![],
+            id,
+            false,
+            false,
+            leverage_factor,
+            open_order_type,
+            side,
+            OrderStatus::StandBy,
+            stop_loss_price,
+            contract.symbol.name.to_string(),
+            take_profit_price,
+            self.get_taker_fee(),
+            time_in_force,
+            units,
+            timestamp,
+            "".to_string(),
+        );
+        Ok(order)
+    }
+
+    /// Kraken drops connections without pings within 60 seconds
+    fn get_ws_ping_interval(&self) -> u64 {
+        30
+    }
+
+    fn get_ws_ping_message(&self) -> Result<Message, GlowError> {
+        Ok(Message::Ping(vec![]))
+    }
+
+    async fn update_position_data_on_faulty_exchange_ws(&self) -> Result<(), GlowError> {
+        let balance = self
+            .fetch_current_usdt_balance()
+            .await
+            .expect("set_current_balance_handle -> get_current_usdt_balance error");
+        self.balance_update_emitter.next(balance);
+
+        let last_error_ts = *self.last_ws_error_ts.lock().unwrap();
+
+        if last_error_ts.is_none() {
+            let trade = self.fetch_current_trade_position().await?;
+            if trade.is_some() {
+                println!(
+                    "{:?} | A initial trade was found! {:?}",
+                    current_datetime(),
+                    trade.clone().unwrap()
+                );
+            }
+            self.trade_update_emitter.next(trade);
+            return Ok(());
+        }
+        let last_error_ts = last_error_ts.unwrap();
+        println!(
+            "{} | update_position_data_on_faulty_exchange_ws -> last error ts = {}",
+            current_timestamp_ms(),
+            last_error_ts
+        );
+
+        let current_trade = self.trade_update_emitter.value();
+        if let Some(current_trade) = current_trade {
+            let current_trade_status = current_trade.status();
+            match current_trade_status {
+                TradeStatus::PendingCloseOrder | TradeStatus::Closed | TradeStatus::Cancelled => {
+                    // no order can have been updated meanwhile
+                }
+                _ => {
+                    let current_order_uuid = current_trade.get_active_order_uuid()
+                            .unwrap_or_else(|| panic!("update_position_data_on_faulty_exchange_ws -> missing current order uuid. trade = {:?}", &current_trade));
+                    let current_minute_end_timestamp = timestamp_minute_end(true, None);
+
+                    let interim_executions = match self
+                        .fetch_order_executions(
+                            current_order_uuid,
+                            last_error_ts,
+                            current_minute_end_timestamp,
+                        )
+                        .await
+                    {
+                        Ok(executions) => executions,
+                        Err(error) => {
+                            println!("update_position_data_on_faulty_exchange_ws -> executions error {:?}", error);
+                            vec![]
+                        }
+                    };
+
+                    if !interim_executions.is_empty() || current_trade_status == TradeStatus::New {
+                        if !interim_executions.is_empty() {
+                            self.executions_update_emitter.next(interim_executions);
+                        }
+                        let current_order_id = current_trade.get_active_order_id()
+                                .unwrap_or_else(|| panic!("update_position_data_on_faulty_exchange_ws -> missing current order id. trade = {:?}", &current_trade));
+
+                        match self.fetch_current_order(current_order_id, false).await {
+                            Ok(updated_order) => {
+                                let order_action = if updated_order.is_cancel_order() {
+                                    OrderAction::Cancel(updated_order)
+                                } else {
+                                    OrderAction::Update(updated_order)
+                                };
+                                self.order_update_emitter.next(order_action);
+                            }
+                            Err(error) => {
+                                println!("handle_exchange_websocket -> fetch_opened_order failed {:?}. Error = {:?}", current_trade_status, error);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        {
+            let mut last_error_guard = self.last_ws_error_ts.lock().unwrap();
+            *last_error_guard = None;
+        }
+
+        Ok(())
+    }
+
+    async fn init(&mut self) -> Result<(), GlowError> {
+        match self.fetch_instruments_info().await {
+            Ok(contracts) => self.patch_contracts(contracts),
+            Err(error) => eprintln!(
+                "Failed to fetch Kraken instruments info, keeping static contracts. Error: {:?}",
+                error
+            ),
+        }
+        let mut has_connected = false;
+
+        loop {
+            let config = self.exchange_config_emitter.value();
+            self.credentials = config.credentials;
+            self.endpoints = config.endpoints;
+            let url = self.get_ws_url()?;
+            let connection = connect_async(url).await;
+            if let Err(error) = connection {
+                eprintln!(
+                    "Exchange WebSocket connection failed. \n
+                    Error: {} \n
+                    Retrying in {} seconds...",
+                    error, WS_RECONNECT_INTERVAL_IN_SECS
+                );
+
+                sleep(Duration::from_secs(WS_RECONNECT_INTERVAL_IN_SECS)).await;
+                continue;
+            }
+
+            let (wss, resp) = connection.unwrap();
+            eprintln!("Exchange connection stablished. \n Response: {:?}", resp);
+            if has_connected {
+                METRICS.inc_ws_reconnects("kraken");
+            }
+            has_connected = true;
+            if let Err(err) = self.listen_messages(wss).await {
+                let mut last_error_guard = self
+                    .last_ws_error_ts
+                    .lock()
+                    .expect("handle_websocket -> last_error_guard unwrap");
+                *last_error_guard = Some(current_timestamp_ms());
+
+                eprintln!(
+                    "{:?} | Exchange websocket connection error: {:?}. Retrying...",
+                    current_timestamp_ms(),
+                    err
+                );
+            }
+        }
+    }
+
+    async fn listen_messages(
+        &mut self,
+        mut wss: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), GlowError> {
+        self.auth_ws(&mut wss).await?;
+        self.subscribe_ws(&mut wss).await?;
+        let ping_message = self.get_ws_ping_message()?;
+        let mut heartbeat_interval: Interval =
+            interval(Duration::from_secs(self.get_ws_ping_interval()));
+        let ws_compression = self.get_ws_compression();
+
+        // positions are synced once subscribed, as updates might have been missed while disconnected
+        self.exchange_recovery_emitter
+            .next(TradingDataUpdate::ExchangeRecovery);
+
+        // current config is skipped, so that only switching environment has websocket reconnect
+        let mut exchange_config_updates = self.exchange_config_emitter.subscribe().skip(1);
+
+        loop {
+            select! {
+                ws_message = wss.next() => {
+                    let message = ws_message.ok_or(GlowError::new(
+                        String::from("WebSocket Closed Error"),
+                        String::from("listen_messages -> exchange websocket stream ended"),
+                    ))??;
+                    let message = match ws_compression.decode_message(message) {
+                        Ok(message) => message,
+                        Err(error) => {
+                            eprintln!("WebSocket message decompression error: {:?}", error);
+                            continue;
+                        }
+                    };
+
+                    match message {
+                        Message::Text(json) => {
+                            let _ = self.process_ws_message(&json);
+                        },
+                        Message::Ping(_) => {
+                            wss.send(Message::Pong(vec![])).await?
+                        },
+                        Message::Pong(_) => {},
+                        fallback => {
+                            println!("exchange fallback message {:?}", fallback);
+                        }
+                    }
+                },
+                _ = exchange_config_updates.next() => {
+                    println!("listen_messages -> exchange environment switched, reconnecting");
+                    return Ok(());
+                },
+                _ = heartbeat_interval.tick() => {
+                    wss.send(ping_message.clone()).await?;
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_balance_update_emitter(&self) -> &BehaviorSubject<Balance> {
+        &self.balance_update_emitter
+    }
+
+    #[inline]
+    fn get_executions_update_emitter(&self) -> &BehaviorSubject<Vec<Execution>> {
+        &self.executions_update_emitter
+    }
+
+    #[inline]
+    fn get_order_update_emitter(&self) -> &BehaviorSubject<OrderAction> {
+        &self.order_update_emitter
+    }
+
+    #[inline]
+    fn get_trade_update_emitter(&self) -> &BehaviorSubject<Option<Trade>> {
+        &self.trade_update_emitter
+    }
+
+    #[inline]
+    fn get_exchange_recovery_emitter(&self) -> &BehaviorSubject<TradingDataUpdate> {
+        &self.exchange_recovery_emitter
+    }
+}
+
+impl BenchmarkExchange for KrakenTraderExchange {
+    fn new_benchmark_open_order(
+        &self,
+        timestamp: i64,
+        side: Side,
+        order_cost: f64,
+        price: f64,
+    ) -> Result<Order, GlowError> {
+        let trading_settings = self.get_trading_settings();
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let open_order_type = trading_settings.get_open_order_type();
+        let price = side.apply_slippage(price, trading_settings.benchmark_slippage_bps);
+        let (units, balance_remainder) =
+            self.calculate_open_order_units_and_balance_remainder(side, order_cost, price)?;
+        let ((open_fee, _), fee_rate, is_maker) = self.calculate_order_fees_at_rate(
+            self.get_benchmark_order_fee_rate(open_order_type),
+            units,
+            price,
+        );
+        let contract = self.get_traded_contract();
+        let id = format!(
+            "{}_{}_{}",
+            &contract.symbol.name,
+            timestamp,
+            OrderStage::Open.to_string()
+        );
+        let stop_loss_price = self.calculate_order_stop_loss_price(side, price);
+        let take_profit_price = self.calculate_order_take_profit_price(side, price);
+        let time_in_force = if open_order_type == OrderType::Limit {
+            TimeInForce::GTC
+        } else {
+            TimeInForce::IOC
+        };
+
+        let order_uuid = format!("benchmark_open_order_{}", timestamp);
+
+        let benchmark_execution = Execution::new(
+            id.clone(),
+            order_uuid.clone(),
+            open_order_type,
+            timestamp,
+            price,
+            units,
+            open_fee,
+            fee_rate,
+            is_maker,
+            0.0,
+        );
+
+        let order = Order::new(
+            Some(price),
+            balance_remainder,
+            timestamp,
+            vec![benchmark_execution],
+            id,
+            false,
+            false,
+            leverage_factor,
+            open_order_type,
+            side,
+            OrderStatus::Filled,
+            stop_loss_price,
+            contract.symbol.name.to_string(),
+            take_profit_price,
+            self.get_taker_fee(),
+            time_in_force,
+            units,
+            timestamp,
+            order_uuid,
+        );
+        Ok(order)
+    }
+
+    fn new_benchmark_close_order(
+        &self,
+        timestamp: i64,
+        trade_id: &String,
+        close_price: f64,
+        open_order: Order,
+        final_status: OrderStatus,
+    ) -> Result<Order, GlowError> {
+        let trading_settings = self.get_trading_settings();
+        let close_order_type = match final_status {
+            OrderStatus::StoppedBR | OrderStatus::StoppedSL | OrderStatus::StoppedTP => {
+                trading_settings.get_close_order_type()
+            }
+            _ => OrderType::Market,
+        };
+        let id = format!("{}_{}", trade_id, OrderStage::Close.to_string());
+        let time_in_force = if close_order_type == OrderType::Market {
+            TimeInForce::IOC
+        } else {
+            TimeInForce::GTC
+        };
+
+        let close_side = open_order.side.get_opposite_side()?;
+        let close_price =
+            close_side.apply_slippage(close_price, trading_settings.benchmark_slippage_bps);
+
+        let ((_, close_fee), fee_rate, is_maker) = self.calculate_order_fees_at_rate(
+            self.get_benchmark_order_fee_rate(close_order_type),
+            open_order.units,
+            close_price,
+        );
+
+        let order_uuid = format!("pending_order_uuid_{}", timestamp);
+
+        let benchmark_execution = Execution::new(
+            id.clone(),
+            order_uuid.clone(),
+            close_order_type,
+            timestamp,
+            close_price,
+            open_order.units,
+            close_fee,
+            fee_rate,
+            is_maker,
+            open_order.units,
+        );
+
+        let is_stop = final_status == OrderStatus::StoppedBR
+            || final_status == OrderStatus::StoppedSL
+            || final_status == OrderStatus::StoppedTP;
+
+        let order = Order::new(
+            Some(close_price),
+            0.0,
+            timestamp,
+            vec![benchmark_execution],
+            id,
+            true,
+            is_stop,
+            open_order.leverage_factor,
+            close_order_type,
+            close_side,
+            final_status,
+            None,
+            open_order.symbol.clone(),
+            None,
+            0.0,
+            time_in_force,
+            open_order.units,
+            timestamp,
+            order_uuid,
+        );
+
+        Ok(order)
+    }
+
+    fn close_benchmark_trade_on_binding_price(
+        &self,
+        trade: &Trade,
+        current_timestamp: i64,
+        binding_price: f64,
+    ) -> Result<Trade, GlowError> {
+        let stop_loss_price = trade.open_order.stop_loss_price.unwrap_or_default();
+        let take_profit_price = trade.open_order.take_profit_price.unwrap_or_default();
+        let bankruptcy_price = trade
+            .open_order
+            .get_bankruptcy_price(self.get_traded_contract().kind)
+            .unwrap_or_default();
+        let epsilon = self.trading_settings.get_price_level_epsilon();
+        let final_status = if is_at_threshold(binding_price, stop_loss_price, epsilon) {
+            OrderStatus::StoppedSL
+        } else if is_at_threshold(binding_price, take_profit_price, epsilon) {
+            OrderStatus::StoppedTP
+        } else if is_at_threshold(binding_price, bankruptcy_price, epsilon) {
+            OrderStatus::StoppedBR
+        } else {
+            return Err(GlowError::new(
+                "Invalid binding price".to_owned(),
+                format!(
+                    "binding price = {:?}, SL price = {:?}, TP price = {:?} BR price = {:?}",
+                    binding_price, stop_loss_price, take_profit_price, bankruptcy_price
+                ),
+            ));
+        };
+
+        let close_order = self.new_benchmark_close_order(
+            current_timestamp,
+            &trade.id,
+            binding_price,
+            trade.open_order.clone(),
+            final_status,
+        )?;
+
+        let closed_trade = trade.update_trade(close_order)?;
+        Ok(closed_trade)
+    }
+
+    fn get_minimum_notional_value(&self) -> Option<f64> {
+        self.minimum_notional_value
+    }
+}
@@ -0,0 +1,680 @@
+use super::{enums::*, functions::*};
+use chrono::{DateTime, Duration};
+use common::{
+    enums::{
+        contract_kind::ContractKind, order_status::OrderStatus, order_type::OrderType, side::Side,
+        time_in_force::TimeInForce,
+    },
+    r#static::SYMBOLS_MAP,
+    structs::{Contract, Execution, Order},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize)]
+pub struct ChallengeWsRequest {
+    event: String,
+    api_key: String,
+}
+
+impl ChallengeWsRequest {
+    pub fn new(api_key: String) -> Self {
+        ChallengeWsRequest {
+            event: "challenge".to_string(),
+            api_key,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubscribeWsRequest {
+    event: String,
+    feed: String,
+    api_key: String,
+    original_challenge: String,
+    signed_challenge: String,
+}
+
+impl SubscribeWsRequest {
+    pub fn new(feed: String, api_key: String, challenge: &WsChallenge) -> Self {
+        SubscribeWsRequest {
+            event: "subscribe".to_string(),
+            feed,
+            api_key,
+            original_challenge: challenge.original.clone(),
+            signed_challenge: challenge.signed.clone(),
+        }
+    }
+}
+
+/// Challenge sent by Kraken after authentication request, alongside its signature
+#[derive(Clone, Debug)]
+pub struct WsChallenge {
+    pub original: String,
+    pub signed: String,
+}
+
+/// Covers `info`, `challenge`, `subscribed` and `error` events
+#[derive(Debug, Deserialize)]
+pub struct EventWsMessage {
+    pub event: String,
+    pub feed: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FillsWsMessage {
+    pub feed: String,
+    pub fills: Vec<FillData>,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug, Deserialize)]
+pub struct FillData {
+    pub instrument: String,
+    pub time: i64,
+    pub price: f64,
+    pub buy: bool,
+    pub qty: f64,
+    pub remaining_order_qty: f64,
+    pub order_id: String,
+    pub cli_ord_id: Option<String>,
+    pub fill_id: String,
+    pub fill_type: KrakenFillType,
+    pub fee_paid: f64,
+    pub order_type: KrakenOrderType,
+}
+
+impl FillData {
+    /// Kraken doesn't provide fill fee rate, so it's inferred from maker/taker fee rates
+    pub fn new_execution(&self, fee_rates: (f64, f64)) -> Execution {
+        let is_maker = self.fill_type == KrakenFillType::Maker;
+        let fee_rate = if is_maker { fee_rates.0 } else { fee_rates.1 };
+        let is_close = self.cli_ord_id.as_deref().is_some_and(is_close_order_id);
+        Execution::new(
+            self.fill_id.clone(),
+            self.order_id.clone(),
+            self.order_type.into(),
+            self.time,
+            self.price,
+            self.qty,
+            self.fee_paid,
+            fee_rate,
+            is_maker,
+            if is_close { self.qty } else { 0.0 },
+        )
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenOrdersWsMessage {
+    pub feed: String,
+    // updates carry order data, while removals (fills and cancels) only carry its ids
+    pub order: Option<OpenOrderData>,
+    pub order_id: Option<String>,
+    pub cli_ord_id: Option<String>,
+    pub is_cancel: bool,
+    pub reason: String,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug, Deserialize)]
+pub struct OpenOrderData {
+    pub instrument: String,
+    pub time: i64,
+    pub last_update_time: i64,
+    pub qty: f64,
+    pub filled: f64,
+    pub limit_price: f64,
+    pub stop_price: f64,
+    #[serde(rename = "type")]
+    pub order_type: KrakenOrderType,
+    pub order_id: String,
+    pub cli_ord_id: Option<String>,
+    #[serde(deserialize_with = "deserialize_direction")]
+    pub direction: Side,
+    pub reduce_only: bool,
+}
+
+impl OpenOrderData {
+    pub fn is_trigger_order(&self) -> bool {
+        self.order_type.is_trigger()
+    }
+
+    pub fn new_order_from_response_data(&self, leverage_factor: f64, taker_fee_rate: f64) -> Order {
+        let is_stop = self.is_trigger_order();
+        let status = if is_stop {
+            if self.order_type == KrakenOrderType::TakeProfit {
+                OrderStatus::StoppedTP
+            } else {
+                OrderStatus::StoppedSL
+            }
+        } else if self.filled == 0.0 {
+            OrderStatus::StandBy
+        } else if self.filled < self.qty {
+            if self.reduce_only {
+                OrderStatus::PartiallyClosed
+            } else {
+                OrderStatus::PartiallyFilled
+            }
+        } else if self.reduce_only {
+            OrderStatus::Closed
+        } else {
+            OrderStatus::Filled
+        };
+        let order_type: OrderType = self.order_type.into();
+        let time_in_force = if order_type == OrderType::Limit {
+            TimeInForce::GTC
+        } else {
+            TimeInForce::IOC
+        };
+        let avg_price = if self.limit_price > 0.0 {
+            Some(self.limit_price)
+        } else {
+            None
+        };
+
+        Order::new(
+            avg_price,
+            0.0,
+            self.time,
+            vec![],
+            self.cli_ord_id.clone().unwrap_or(self.order_id.clone()),
+            self.reduce_only,
+            is_stop,
+            leverage_factor,
+            order_type,
+            self.direction,
+            status,
+            None,
+            get_symbol_from_kraken(&self.instrument),
+            None,
+            taker_fee_rate,
+            time_in_force,
+            self.qty,
+            self.last_update_time,
+            self.order_id.clone(),
+        )
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct BalancesWsMessage {
+    pub feed: String,
+    pub flex_futures: FlexFuturesData,
+    pub timestamp: i64,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct FlexFuturesData {
+    pub currencies: HashMap<String, FlexCurrencyData>,
+    pub balance_value: f64,
+    pub available_margin: f64,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug, Deserialize)]
+pub struct FlexCurrencyData {
+    pub quantity: f64,
+    pub value: f64,
+    pub available: f64,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct KrakenHttpResponseWrapper<T> {
+    pub result: String,
+    #[serde(rename = "serverTime")]
+    pub server_time: Option<String>,
+    pub error: Option<String>,
+    #[serde(flatten)]
+    pub data: T,
+}
+
+impl<T> KrakenHttpResponseWrapper<T> {
+    pub fn is_success(&self) -> bool {
+        self.result == "success"
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SendOrderDto {
+    #[serde(rename = "orderType")]
+    order_type: KrakenOrderType,
+    symbol: String,
+    #[serde(serialize_with = "serialize_side")]
+    side: Side,
+    size: f64,
+    #[serde(rename = "limitPrice")]
+    limit_price: Option<f64>,
+    #[serde(rename = "stopPrice")]
+    stop_price: Option<f64>,
+    #[serde(rename = "triggerSignal")]
+    trigger_signal: Option<String>,
+    #[serde(rename = "cliOrdId")]
+    pub cli_ord_id: String,
+    #[serde(rename = "reduceOnly")]
+    reduce_only: bool,
+}
+
+impl SendOrderDto {
+    /// Kraken doesn't attach stop loss/take profit to orders, so they're placed as reduce only trigger orders
+    pub fn new_trigger_order(
+        cli_ord_id: String,
+        order_type: KrakenOrderType,
+        symbol: &str,
+        side: Side,
+        size: f64,
+        stop_price: f64,
+    ) -> Self {
+        SendOrderDto {
+            order_type,
+            symbol: get_kraken_symbol(symbol),
+            side,
+            size,
+            limit_price: None,
+            stop_price: Some(stop_price),
+            trigger_signal: Some("mark".to_string()),
+            cli_ord_id,
+            reduce_only: true,
+        }
+    }
+
+    /// Market order that only reduces the position, used for flattening positions not tracked as trades
+    pub fn new_reduce_only_market_order(
+        cli_ord_id: String,
+        symbol: String,
+        side: Side,
+        size: f64,
+    ) -> Self {
+        SendOrderDto {
+            order_type: KrakenOrderType::Market,
+            symbol,
+            side,
+            size,
+            limit_price: None,
+            stop_price: None,
+            trigger_signal: None,
+            cli_ord_id,
+            reduce_only: true,
+        }
+    }
+}
+
+impl SendOrderDto {
+    pub fn with_reduce_only(mut self, reduce_only: bool) -> Self {
+        self.reduce_only = reduce_only;
+        self
+    }
+}
+
+impl From<Order> for SendOrderDto {
+    fn from(order: Order) -> Self {
+        let order_type = if order.order_type == OrderType::Limit {
+            KrakenOrderType::Limit
+        } else {
+            KrakenOrderType::Market
+        };
+        let limit_price = if order_type == KrakenOrderType::Limit {
+            order.avg_price
+        } else {
+            None
+        };
+        SendOrderDto {
+            order_type,
+            symbol: get_kraken_symbol(&order.symbol),
+            side: order.side,
+            size: order.units,
+            limit_price,
+            stop_price: None,
+            trigger_signal: None,
+            cli_ord_id: order.id,
+            reduce_only: order.is_close,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EditOrderDto {
+    #[serde(rename = "cliOrdId")]
+    pub cli_ord_id: String,
+    pub size: Option<f64>,
+    #[serde(rename = "limitPrice")]
+    pub limit_price: Option<f64>,
+    #[serde(rename = "stopPrice")]
+    pub stop_price: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CancelOrderDto {
+    #[serde(rename = "cliOrdId")]
+    pub cli_ord_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CancelAllOrdersDto {
+    pub symbol: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FetchOrdersStatusDto {
+    #[serde(rename = "cliOrdIds")]
+    pub cli_ord_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FetchFillsDto {
+    #[serde(rename = "lastFillTime")]
+    pub last_fill_time: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SetLeverageDto {
+    pub symbol: String,
+    #[serde(rename = "maxLeverage")]
+    pub max_leverage: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmptyDto {}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmptyObject {}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderStatusResponse {
+    pub status: String,
+    #[serde(alias = "orderId")]
+    pub order_id: Option<String>,
+    #[serde(rename = "cliOrdId")]
+    pub cli_ord_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SendStatusData {
+    #[serde(rename = "sendStatus")]
+    pub send_status: OrderStatusResponse,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EditStatusData {
+    #[serde(rename = "editStatus")]
+    pub edit_status: OrderStatusResponse,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CancelStatusData {
+    #[serde(rename = "cancelStatus")]
+    pub cancel_status: OrderStatusResponse,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CancelAllStatusData {
+    #[serde(rename = "cancelStatus")]
+    pub cancel_status: CancelAllStatusResponse,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CancelAllStatusResponse {
+    pub status: String,
+    #[serde(rename = "cancelledOrders", default)]
+    pub cancelled_orders: Vec<CancelledOrderData>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct CancelledOrderData {
+    #[serde(alias = "orderId")]
+    pub order_id: String,
+    #[serde(rename = "cliOrdId")]
+    pub cli_ord_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrdersStatusData {
+    pub orders: Vec<OrderStatusData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderStatusData {
+    pub order: OrderData,
+    pub status: KrakenOrderStatus,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderData {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(rename = "orderId")]
+    pub order_id: String,
+    #[serde(rename = "cliOrdId")]
+    pub cli_ord_id: Option<String>,
+    pub symbol: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub side: Side,
+    pub quantity: f64,
+    pub filled: f64,
+    #[serde(rename = "limitPrice")]
+    pub limit_price: Option<f64>,
+    #[serde(rename = "reduceOnly")]
+    pub reduce_only: bool,
+    #[serde(deserialize_with = "parse_rfc3339_ms")]
+    pub timestamp: i64,
+    #[serde(rename = "lastUpdateTimestamp", deserialize_with = "parse_rfc3339_ms")]
+    pub last_update_timestamp: i64,
+}
+
+impl OrderStatusData {
+    pub fn is_trigger_order(&self) -> bool {
+        self.order.kind == "TRIGGER_ORDER"
+    }
+
+    pub fn new_order_from_response_data(&self, leverage_factor: f64, taker_fee_rate: f64) -> Order {
+        let order_data = &self.order;
+        let status = match OrderStatus::from(self.status) {
+            OrderStatus::StandBy if order_data.filled > 0.0 => {
+                if order_data.reduce_only {
+                    OrderStatus::PartiallyClosed
+                } else {
+                    OrderStatus::PartiallyFilled
+                }
+            }
+            OrderStatus::Filled if order_data.reduce_only => OrderStatus::Closed,
+            status => status,
+        };
+        let (order_type, time_in_force) = if order_data.limit_price.is_some() {
+            (OrderType::Limit, TimeInForce::GTC)
+        } else {
+            (OrderType::Market, TimeInForce::IOC)
+        };
+
+        Order::new(
+            order_data.limit_price,
+            0.0,
+            order_data.timestamp,
+            vec![],
+            order_data
+                .cli_ord_id
+                .clone()
+                .unwrap_or(order_data.order_id.clone()),
+            order_data.reduce_only,
+            false,
+            leverage_factor,
+            order_type,
+            order_data.side,
+            status,
+            None,
+            get_symbol_from_kraken(&order_data.symbol),
+            None,
+            taker_fee_rate,
+            time_in_force,
+            order_data.quantity,
+            order_data.last_update_timestamp,
+            order_data.order_id.clone(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FillsData {
+    pub fills: Vec<RestFillData>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct RestFillData {
+    pub fill_id: String,
+    pub symbol: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub side: Side,
+    pub order_id: String,
+    #[serde(rename = "cliOrdId")]
+    pub cli_ord_id: Option<String>,
+    pub size: f64,
+    pub price: f64,
+    #[serde(rename = "fillTime", deserialize_with = "parse_rfc3339_ms")]
+    pub fill_time: i64,
+    #[serde(rename = "fillType")]
+    pub fill_type: KrakenFillType,
+}
+
+impl RestFillData {
+    /// REST fills carry neither fees nor order type, so these are inferred from fill type
+    pub fn new_execution(&self, fee_rates: (f64, f64)) -> Execution {
+        let is_maker = self.fill_type == KrakenFillType::Maker;
+        let (order_type, fee_rate) = if is_maker {
+            (OrderType::Limit, fee_rates.0)
+        } else {
+            (OrderType::Market, fee_rates.1)
+        };
+        let is_close = self.cli_ord_id.as_deref().is_some_and(is_close_order_id);
+        Execution::new(
+            self.fill_id.clone(),
+            self.order_id.clone(),
+            order_type,
+            self.fill_time,
+            self.price,
+            self.size,
+            self.size * self.price * fee_rate,
+            fee_rate,
+            is_maker,
+            if is_close { self.size } else { 0.0 },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenPositionsData {
+    #[serde(rename = "openPositions")]
+    pub open_positions: Vec<PositionData>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct PositionData {
+    #[serde(deserialize_with = "deserialize_side")]
+    pub side: Side,
+    pub symbol: String,
+    pub price: f64,
+    pub size: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TickerResponseData {
+    pub ticker: TickerData,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct TickerData {
+    pub symbol: String,
+    pub bid: f64,
+    pub ask: f64,
+    pub last: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InstrumentsResponseData {
+    pub instruments: Vec<InstrumentData>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct InstrumentData {
+    pub symbol: String,
+    #[serde(rename = "tickSize")]
+    pub tick_size: Option<f64>,
+    #[serde(rename = "contractValueTradePrecision")]
+    pub contract_value_trade_precision: Option<i32>,
+    #[serde(rename = "maxPositionSize")]
+    pub max_position_size: Option<f64>,
+    #[serde(rename = "openingDate")]
+    pub opening_date: Option<String>,
+    #[serde(rename = "marginLevels", default)]
+    pub margin_levels: Vec<MarginLevelData>,
+}
+
+impl InstrumentData {
+    /// Builds perpetual's contract, taking funding interval, funding rate and kind from `fallback`
+    /// contract, as instruments endpoint doesn't provide them. Returns `None` for unknown symbols
+    /// or instruments missing their specs.
+    pub fn to_contract(&self, fallback: Option<&Contract>) -> Option<Contract> {
+        let symbol = SYMBOLS_MAP.get(get_symbol_from_kraken(&self.symbol).as_str())?;
+        let available_since = self
+            .opening_date
+            .as_ref()
+            .and_then(|opening_date| DateTime::parse_from_rfc3339(opening_date).ok())
+            .map(|opening_date| opening_date.naive_utc())
+            .or(fallback.map(|contract| contract.available_since))?;
+        // first margin level is the least restrictive one
+        let initial_margin = self.margin_levels.first()?.initial_margin;
+        if initial_margin <= 0.0 {
+            return None;
+        }
+        let minimum_order_size = 10.0_f64.powi(-self.contract_value_trade_precision?);
+        let max_position_size = self.max_position_size?;
+        let funding_interval =
+            fallback.map_or(Duration::hours(1), |contract| contract.funding_interval);
+        let funding_rate = fallback.map_or(0.0, |contract| contract.funding_rate);
+        let kind = fallback.map_or(ContractKind::default(), |contract| contract.kind);
+        let contract = Contract::new(
+            available_since,
+            funding_interval,
+            funding_rate,
+            1.0 / initial_margin,
+            (max_position_size, max_position_size),
+            minimum_order_size,
+            None,
+            symbol,
+            self.tick_size?,
+        )
+        .with_kind(kind)
+        .with_min_notional(fallback.and_then(|contract| contract.min_notional));
+        Some(contract)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarginLevelData {
+    #[serde(rename = "initialMargin")]
+    pub initial_margin: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountsData {
+    pub accounts: KrakenAccounts,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct KrakenAccounts {
+    pub flex: FlexAccountData,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FlexAccountData {
+    pub currencies: HashMap<String, FlexCurrencyData>,
+}
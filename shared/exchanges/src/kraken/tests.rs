@@ -0,0 +1,206 @@
+use super::{
+    enums::KrakenWsMessage,
+    functions::{
+        get_kraken_base_asset, get_kraken_symbol, get_symbol_from_kraken, sign_challenge,
+        sign_request,
+    },
+    structs::{
+        CancelAllStatusData, InstrumentsResponseData, KrakenHttpResponseWrapper, SendOrderDto,
+        SendStatusData, TickerResponseData,
+    },
+    KrakenTraderExchange, WOULD_NOT_REDUCE_POSITION_STATUS,
+};
+use common::{
+    enums::{
+        order_status::OrderStatus, order_type::OrderType, side::Side, time_in_force::TimeInForce,
+    },
+    structs::Order,
+};
+use serde_json::from_str;
+use serde_urlencoded::to_string as to_url_string;
+
+// "secret" base64 encoded
+const API_SECRET: &str = "c2VjcmV0";
+
+#[test]
+fn test_symbol_to_kraken_symbol_round_trip() {
+    assert_eq!(get_kraken_symbol("BTCUSDT"), "PF_XBTUSD");
+    assert_eq!(get_kraken_symbol("ETHUSDT"), "PF_ETHUSD");
+    assert_eq!(get_kraken_base_asset("BTCUSDT"), "XBT");
+    assert_eq!(get_kraken_base_asset("ETHUSDT"), "ETH");
+    assert_eq!(get_symbol_from_kraken("PF_XBTUSD"), "BTCUSDT");
+    assert_eq!(get_symbol_from_kraken("pf_solusd"), "SOLUSDT");
+}
+
+#[test]
+fn test_challenge_signature_is_deterministic_base64_sha512() {
+    let challenge = "c100b894-1729-464d-ace1-52dbce11db42";
+    let signature = sign_challenge(API_SECRET, challenge).unwrap();
+    assert_eq!(signature, sign_challenge(API_SECRET, challenge).unwrap());
+    // 64 bytes HMAC-SHA512 output is 88 base64 chars long
+    assert_eq!(signature.len(), 88);
+    assert_ne!(signature, sign_challenge(API_SECRET, "other").unwrap());
+    assert!(sign_challenge("not base64!", challenge).is_err());
+}
+
+#[test]
+fn test_request_signature_covers_payload_nonce_and_path() {
+    let signature = sign_request(API_SECRET, "symbol=PF_XBTUSD", "1", "/api/v3/sendorder").unwrap();
+    assert_ne!(
+        signature,
+        sign_request(API_SECRET, "symbol=PF_XBTUSD", "2", "/api/v3/sendorder").unwrap()
+    );
+    assert_ne!(
+        signature,
+        sign_request(API_SECRET, "symbol=PF_XBTUSD", "1", "/api/v3/editorder").unwrap()
+    );
+}
+
+#[test]
+fn test_fills_message_is_parsed_into_executions() {
+    let json = r#"{"feed":"fills","username":"user","fills":[{"instrument":"PF_XBTUSD","time":1704067200000,"price":42000.0,"seq":1,"buy":false,"qty":0.01,"remaining_order_qty":0.0,"order_id":"uuid","cli_ord_id":"BTCUSDT_1704067100000_close","fill_id":"fill","fill_type":"taker","fee_paid":0.21,"fee_currency":"USD","taker_order_type":"market","order_type":"market"}]}"#;
+    let message = from_str::<KrakenWsMessage>(json).unwrap();
+    let KrakenWsMessage::Fills(message) = message else {
+        panic!("fills message parsed as {:?}", message);
+    };
+    let execution = message.fills[0].new_execution((0.0002, 0.0005));
+    assert_eq!(execution.order_uuid, "uuid");
+    assert_eq!(execution.order_type, OrderType::Market);
+    assert_eq!(execution.timestamp, 1_704_067_200_000);
+    assert_eq!(execution.fee, 0.21);
+    assert_eq!(execution.fee_rate, 0.0005);
+    assert!(!execution.is_maker);
+    assert_eq!(execution.closed_qty, 0.01);
+}
+
+#[test]
+fn test_open_orders_message_is_parsed_into_order() {
+    let json = r#"{"feed":"open_orders","order":{"instrument":"PF_XBTUSD","time":1704067200000,"last_update_time":1704067201000,"qty":0.02,"filled":0.01,"limit_price":42000.0,"stop_price":0.0,"type":"limit","order_id":"uuid","cli_ord_id":"BTCUSDT_1704067200000_open","direction":1,"reduce_only":false},"is_cancel":false,"reason":"partial_fill"}"#;
+    let message = from_str::<KrakenWsMessage>(json).unwrap();
+    let KrakenWsMessage::OpenOrders(message) = message else {
+        panic!("open orders message parsed as {:?}", message);
+    };
+    let order = message
+        .order
+        .unwrap()
+        .new_order_from_response_data(10.0, 0.0005);
+    assert_eq!(order.id, "BTCUSDT_1704067200000_open");
+    assert_eq!(order.uuid, "uuid");
+    assert_eq!(order.symbol, "BTCUSDT");
+    assert_eq!(order.side, Side::Sell);
+    assert_eq!(order.status, OrderStatus::PartiallyFilled);
+    assert_eq!(order.avg_price, Some(42000.0));
+    assert!(!order.is_close);
+    assert!(!order.is_stop);
+}
+
+#[test]
+fn test_removed_open_order_message_has_no_order_data() {
+    let json = r#"{"feed":"open_orders","order_id":"uuid","cli_ord_id":"BTCUSDT_1704067200000_open","is_cancel":true,"reason":"cancelled_by_user"}"#;
+    let message = from_str::<KrakenWsMessage>(json).unwrap();
+    let KrakenWsMessage::OpenOrders(message) = message else {
+        panic!("open orders message parsed as {:?}", message);
+    };
+    assert!(message.order.is_none());
+    assert!(message.is_cancel);
+    assert_eq!(message.order_id.as_deref(), Some("uuid"));
+}
+
+#[test]
+fn test_close_order_is_sent_as_reduce_only_kraken_order() {
+    let order = Order::new(
+        Some(42000.0),
+        0.0,
+        1_704_067_200_000,
+        vec![],
+        String::from("BTCUSDT_1704067100000_close"),
+        true,
+        false,
+        10.0,
+        OrderType::Limit,
+        Side::Buy,
+        OrderStatus::StandBy,
+        None,
+        String::from("BTCUSDT"),
+        None,
+        0.0005,
+        TimeInForce::GTC,
+        0.01,
+        1_704_067_200_000,
+        String::new(),
+    );
+    let payload: SendOrderDto = order.into();
+    assert_eq!(
+        to_url_string(&payload).unwrap(),
+        "orderType=lmt&symbol=PF_XBTUSD&side=buy&size=0.01&limitPrice=42000.0&cliOrdId=BTCUSDT_1704067100000_close&reduceOnly=true"
+    );
+}
+
+#[test]
+fn test_send_order_response_tells_reduce_only_order_had_no_position_left() {
+    let payload: SendOrderDto = Order {
+        id: String::from("BTCUSDT_1704067100000_close"),
+        is_close: true,
+        symbol: String::from("BTCUSDT"),
+        units: 0.01,
+        ..Default::default()
+    }
+    .into();
+    let json = r#"{"result":"success","sendStatus":{"receivedTime":"2024-01-01T00:00:00.000Z","status":"placed","order_id":"uuid","cliOrdId":"BTCUSDT_1704067100000_close"},"serverTime":"2024-01-01T00:00:00.000Z"}"#;
+    let response = from_str::<KrakenHttpResponseWrapper<SendStatusData>>(json).unwrap();
+    assert_eq!(
+        KrakenTraderExchange::get_placed_order_id(&payload, response).unwrap(),
+        "uuid"
+    );
+
+    let json = r#"{"result":"success","sendStatus":{"receivedTime":"2024-01-01T00:00:00.000Z","status":"wouldNotReducePosition","cliOrdId":"BTCUSDT_1704067100000_close"},"serverTime":"2024-01-01T00:00:00.000Z"}"#;
+    let response = from_str::<KrakenHttpResponseWrapper<SendStatusData>>(json).unwrap();
+    assert!(response.is_success());
+    assert_eq!(
+        response.data.send_status.status,
+        WOULD_NOT_REDUCE_POSITION_STATUS
+    );
+    // left unhandled, it's a wrong response, as order wasn't placed
+    let error = KrakenTraderExchange::get_placed_order_id(&payload, response).unwrap_err();
+    assert_eq!(error.title, "Wrong Response Error");
+}
+
+#[test]
+fn test_cancel_all_orders_response_counts_cancelled_orders() {
+    let json = r#"{"result":"success","cancelStatus":{"receivedTime":"2024-01-01T00:00:00.000Z","cancelOnly":"PF_XBTUSD","status":"cancelled","cancelledOrders":[{"order_id":"uuid-1","cliOrdId":"BTCUSDT_1704067200000_sl"},{"order_id":"uuid-2"}]},"serverTime":"2024-01-01T00:00:00.000Z"}"#;
+    let response = from_str::<KrakenHttpResponseWrapper<CancelAllStatusData>>(json).unwrap();
+    assert!(response.is_success());
+    assert_eq!(response.data.cancel_status.cancelled_orders.len(), 2);
+
+    let json = r#"{"result":"success","cancelStatus":{"receivedTime":"2024-01-01T00:00:00.000Z","cancelOnly":"PF_XBTUSD","status":"noOrdersToCancel"},"serverTime":"2024-01-01T00:00:00.000Z"}"#;
+    let response = from_str::<KrakenHttpResponseWrapper<CancelAllStatusData>>(json).unwrap();
+    assert!(response.data.cancel_status.cancelled_orders.is_empty());
+}
+
+#[test]
+fn test_ticker_response_is_parsed_into_best_bid_and_ask() {
+    let json = r#"{"result":"success","ticker":{"tag":"perpetual","pair":"XBT:USD","symbol":"PF_XBTUSD","markPrice":42001.5,"bid":42000.0,"bidSize":1.2,"ask":42001.0,"askSize":0.8,"vol24h":1234.5,"volumeQuote":51840000.0,"openInterest":250.0,"open24h":41500.0,"high24h":42500.0,"low24h":41200.0,"last":42000.5,"lastTime":"2024-01-01T00:00:00.000Z","lastSize":0.01,"suspended":false,"fundingRate":0.0001,"fundingRatePrediction":0.0001,"postOnly":false},"serverTime":"2024-01-01T00:00:00.000Z"}"#;
+    let response = from_str::<KrakenHttpResponseWrapper<TickerResponseData>>(json).unwrap();
+    assert!(response.is_success());
+    assert_eq!(response.data.ticker.symbol, "PF_XBTUSD");
+    assert_eq!(response.data.ticker.bid, 42000.0);
+    assert_eq!(response.data.ticker.ask, 42001.0);
+    assert_eq!(response.data.ticker.last, 42000.5);
+}
+
+#[test]
+fn test_instruments_response_is_parsed_into_contract() {
+    let json = r#"{"result":"success","instruments":[{"symbol":"PF_XBTUSD","type":"flexible_futures","tradeable":true,"tickSize":1,"contractSize":1,"impactMidSize":1,"maxPositionSize":1000000,"openingDate":"2022-06-28T09:00:00.000Z","marginLevels":[{"contracts":0,"initialMargin":0.02,"maintenanceMargin":0.01},{"contracts":500000,"initialMargin":0.04,"maintenanceMargin":0.02}],"contractValueTradePrecision":4,"postOnly":false},{"symbol":"in_xbtusd","type":"spot index","tradeable":false}],"serverTime":"2024-01-01T00:00:00.000Z"}"#;
+    let response = from_str::<KrakenHttpResponseWrapper<InstrumentsResponseData>>(json).unwrap();
+    assert!(response.is_success());
+    assert_eq!(response.data.instruments.len(), 2);
+    assert!(response.data.instruments[1].to_contract(None).is_none());
+
+    let contract = response.data.instruments[0].to_contract(None).unwrap();
+    assert_eq!(contract.symbol.name, "BTCUSDT");
+    assert_eq!(contract.tick_size, 1.0);
+    assert_eq!(contract.minimum_order_size, 0.0001);
+    assert_eq!(contract.maximum_order_sizes, (1000000.0, 1000000.0));
+    assert_eq!(contract.max_leverage, 50.0);
+    assert_eq!(contract.available_since.to_string(), "2022-06-28 09:00:00");
+}
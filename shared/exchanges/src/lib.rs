@@ -2,6 +2,9 @@ pub mod binance;
 pub mod bybit;
 pub mod config;
 pub mod enums;
+pub mod kraken;
+pub mod okx;
+pub mod replay;
 pub mod shared;
 pub mod structs;
 pub mod r#static;
\ No newline at end of file
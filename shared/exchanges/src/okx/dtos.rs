@@ -0,0 +1,55 @@
+pub mod http {
+    pub mod response {
+        use serde::Deserialize;
+
+        #[allow(dead_code)]
+        #[derive(Debug, Deserialize)]
+        pub struct OkxHttpResponse<T> {
+            pub code: String,
+            pub msg: String,
+            pub data: T,
+        }
+
+        /// Candles are provided as string arrays:
+        /// [ts, open, high, low, close, vol, volCcy, volCcyQuote, confirm]
+        pub type OkxHttpCandleResponse = Vec<String>;
+    }
+}
+
+pub mod ws {
+    pub mod outgoing {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Debug, Serialize, Deserialize)]
+        pub struct WsOutgoingMessage {
+            pub op: String,
+            pub args: Vec<WsChannelArg>,
+        }
+
+        #[derive(Debug, Serialize, Deserialize)]
+        pub struct WsChannelArg {
+            pub channel: String,
+            #[serde(rename = "instId")]
+            pub inst_id: String,
+        }
+    }
+
+    pub mod incoming {
+        use super::outgoing::WsChannelArg;
+        use serde::Deserialize;
+
+        #[derive(Debug, Deserialize)]
+        pub struct CandleMessage {
+            pub arg: WsChannelArg,
+            pub data: Vec<Vec<String>>,
+        }
+
+        #[allow(dead_code)]
+        #[derive(Debug, Deserialize)]
+        pub struct EventMessage {
+            pub event: String,
+            pub code: Option<String>,
+            pub msg: Option<String>,
+        }
+    }
+}
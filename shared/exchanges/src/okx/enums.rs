@@ -0,0 +1,11 @@
+use super::dtos::ws::incoming::{CandleMessage, EventMessage};
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(untagged)]
+pub enum IncomingWsMessage {
+    #[default]
+    None,
+    Candle(CandleMessage),
+    Event(EventMessage),
+}
@@ -0,0 +1,90 @@
+use chrono::NaiveDateTime;
+use common::{enums::granularity::Granularity, structs::TickData};
+use glow_error::GlowError;
+
+/// Maps granularity to OKX candle bar code.
+///
+/// Bars from 6 hours on are requested UTC-aligned, as klines are downsampled in UTC.
+/// OKX doesn't provide 10 minutes candles.
+pub fn get_okx_bar(granularity: Granularity) -> Result<&'static str, GlowError> {
+    let bar = match granularity {
+        Granularity::m1 => "1m",
+        Granularity::m3 => "3m",
+        Granularity::m5 => "5m",
+        Granularity::m10 => {
+            return Err(GlowError::new(
+                String::from("Invalid OKX bar"),
+                format!("OKX doesn't provide {:?} candles", granularity),
+            ))
+        }
+        Granularity::m15 => "15m",
+        Granularity::m30 => "30m",
+        Granularity::h1 => "1H",
+        Granularity::h2 => "2H",
+        Granularity::h4 => "4H",
+        Granularity::h6 => "6Hutc",
+        Granularity::h12 => "12Hutc",
+        Granularity::d1 => "1Dutc",
+        Granularity::w1 => "1Wutc",
+        Granularity::M1 => "1Mutc",
+    };
+    Ok(bar)
+}
+
+/// Gets granularity at which candles are fetched for benchmarking at `granularity`, being the
+/// coarsest OKX bar it's a multiple of. Candles are cached by day, so fetched ones are at most daily.
+pub fn get_okx_fetch_granularity(granularity: Granularity) -> Granularity {
+    granularity.get_coarsest_divisor(|divisor| {
+        divisor.get_granularity_in_secs() <= Granularity::d1.get_granularity_in_secs()
+            && get_okx_bar(divisor).is_ok()
+    })
+}
+
+/// Gets OKX spot instrument id from symbol name, e.g. `BTCUSDT` -> `BTC-USDT`
+pub fn get_okx_inst_id(symbol_name: &str) -> String {
+    match symbol_name.strip_suffix("USDT") {
+        Some(base) => format!("{}-USDT", base),
+        None => symbol_name.to_string(),
+    }
+}
+
+pub fn from_candle_to_tick_data(
+    symbol: &'static str,
+    candle: &[String],
+) -> Result<TickData, GlowError> {
+    if candle.len() < 5 {
+        return Err(GlowError::new(
+            String::from("Invalid OKX candle"),
+            format!("Candle {:?} has less than 5 fields", candle),
+        ));
+    }
+    let timestamp = candle[0].parse::<i64>()?;
+    let start_time = NaiveDateTime::from_timestamp_millis(timestamp).ok_or(GlowError::new(
+        String::from("Invalid OKX candle"),
+        format!("Invalid candle timestamp {}", timestamp),
+    ))?;
+    let open = candle[1].parse::<f64>()?;
+    let high = candle[2].parse::<f64>()?;
+    let low = candle[3].parse::<f64>()?;
+    let close = candle[4].parse::<f64>()?;
+    Ok(TickData::new_from_string(
+        symbol, start_time, open, high, close, low,
+    ))
+}
+
+/// OKX returns candles in descending time order, so they're reversed into ascending order
+pub fn map_candles_to_ticks_data(
+    symbol: &'static str,
+    candles: &[Vec<String>],
+) -> Result<Vec<TickData>, GlowError> {
+    candles
+        .iter()
+        .rev()
+        .map(|candle| from_candle_to_tick_data(symbol, candle))
+        .collect()
+}
+
+/// Whether OKX candle is completed (`confirm` field equals "1")
+pub fn is_candle_confirmed(candle: &[String]) -> bool {
+    candle.get(8).map(|confirm| confirm == "1").unwrap_or(false)
+}
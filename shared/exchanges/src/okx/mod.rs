@@ -0,0 +1,6 @@
+mod dtos;
+pub mod enums;
+pub mod functions;
+pub mod structs;
+#[cfg(test)]
+mod tests;
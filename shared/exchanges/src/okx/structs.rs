@@ -0,0 +1,652 @@
+use super::{
+    dtos::{
+        http::response::{OkxHttpCandleResponse, OkxHttpResponse},
+        ws::outgoing::{WsChannelArg, WsOutgoingMessage},
+    },
+    enums::IncomingWsMessage,
+    functions::{
+        get_okx_bar, get_okx_fetch_granularity, get_okx_inst_id, is_candle_confirmed,
+        map_candles_to_ticks_data,
+    },
+};
+use crate::{
+    binance::structs::{adjust_benchmark_datetimes, set_ws_error_ts},
+    config::WS_RECONNECT_INTERVAL_IN_SECS,
+};
+use chrono::{Duration, NaiveDateTime};
+use common::{
+    enums::{
+        benchmark_window::BenchmarkWindow, granularity::Granularity, run_mode::RunMode,
+        trading_data_update::TradingDataUpdate,
+    },
+    functions::{
+        coerce_df_to_schema,
+        csv::{load_interval_tick_dataframe, save_kline_df_to_csv},
+        current_datetime, current_timestamp, current_timestamp_ms,
+        downsample_tick_lf_to_kline_duration, filter_df_timestamps_to_lf,
+        get_date_start_and_end_timestamps, map_ticks_data_to_df, timestamp_minute_end,
+        timestamp_minute_start,
+    },
+    structs::{BehaviorSubject, LogKlines, SymbolsPair, TickData, TradingSettings},
+    traits::exchange::DataProviderExchange,
+};
+use futures_util::SinkExt;
+use glow_error::GlowError;
+use polars::{
+    frame::DataFrame,
+    prelude::{IntoLazy, Schema},
+    time::ClosedWindow,
+};
+use reqwest::Client;
+use serde_json::to_string;
+use std::{
+    collections::HashMap,
+    env::var as env_var,
+    sync::{Arc, Mutex},
+    time::Duration as StdDuration,
+};
+use strategy::Strategy;
+use tokio::{
+    net::TcpStream,
+    select, spawn,
+    time::{interval, sleep, sleep_until, Instant},
+};
+use tokio_stream::StreamExt;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+const OKX_HTTP_BASE_URL: &str = "https://www.okx.com";
+const OKX_NAME: &str = "okx";
+/// OKX history candles endpoint returns at most 100 candles per call
+const OKX_CANDLES_LIMIT: i64 = 100;
+/// OKX closes connections without any message within 30 seconds
+const OKX_WS_PING_INTERVAL_IN_SECS: u64 = 25;
+
+#[derive(Clone)]
+pub struct OkxDataProvider {
+    fetch_granularity: Granularity, // granularity of candles fetched for benchmarking
+    fetch_leeway: StdDuration,
+    http: Client,
+    kline_duration: Duration,
+    last_committed_minute: Arc<Mutex<Option<NaiveDateTime>>>, // start of the last kline minute committed or backfilled
+    last_ws_error_ts: Arc<Mutex<Option<i64>>>,
+    minimum_klines_for_benchmarking: u32,
+    staged_ticks: HashMap<&'static str, TickData>, // latest 1m candle by symbol, for the staged minute
+    symbols: SymbolsPair,
+    ticks_to_commit: BehaviorSubject<Vec<TickData>>,
+    klines_data_update_emitter: BehaviorSubject<TradingDataUpdate>,
+}
+
+impl OkxDataProvider {
+    pub fn new(trading_settings: &TradingSettings, strategy: &Strategy) -> Self {
+        let symbols = trading_settings.symbols_pair;
+        let kline_duration = trading_settings.granularity.get_chrono_duration();
+        let minimum_klines_for_benchmarking = strategy.get_warmup_klines();
+        let klines_data_update_emitter = BehaviorSubject::new(TradingDataUpdate::default());
+        Self {
+            fetch_granularity: get_okx_fetch_granularity(trading_settings.granularity),
+            fetch_leeway: StdDuration::from_secs(5),
+            http: Client::new(),
+            kline_duration,
+            last_committed_minute: Arc::new(Mutex::new(None)),
+            last_ws_error_ts: Arc::new(Mutex::new(None)),
+            minimum_klines_for_benchmarking,
+            staged_ticks: HashMap::new(),
+            symbols,
+            ticks_to_commit: BehaviorSubject::new(vec![]),
+            klines_data_update_emitter,
+        }
+    }
+
+    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
+        self.symbols = trading_settings.symbols_pair;
+        self.kline_duration = trading_settings.granularity.get_chrono_duration();
+        self.fetch_granularity = get_okx_fetch_granularity(trading_settings.granularity);
+    }
+
+    /// Gets name candles fetched for benchmarking are cached under. Minute candles keep being
+    /// cached under exchange name, whereas coarser ones are suffixed by their duration, e.g. `okx_5m`.
+    fn get_cache_name(&self) -> String {
+        match self.fetch_granularity {
+            Granularity::m1 => String::from(OKX_NAME),
+            granularity => format!("{}_{}m", OKX_NAME, granularity.get_granularity_in_mins()),
+        }
+    }
+
+    pub fn patch_strategy(&mut self, strategy: &Strategy) {
+        self.minimum_klines_for_benchmarking = strategy.get_warmup_klines();
+    }
+
+    fn set_last_committed_minute(&self, minute_start: NaiveDateTime) {
+        let mut last_committed_minute_guard = self
+            .last_committed_minute
+            .lock()
+            .expect("set_last_committed_minute -> last_committed_minute_guard unwrap");
+        *last_committed_minute_guard = Some(minute_start);
+    }
+
+    /// Gets (start, end) timestamps in milliseconds for the klines missed during a websocket outage.
+    fn get_backfill_interval(&self, last_error_ts: i64, now_ts: i64) -> (i64, i64) {
+        let last_committed_minute = {
+            let last_committed_minute_guard = self
+                .last_committed_minute
+                .lock()
+                .expect("get_backfill_interval -> last_committed_minute_guard unwrap");
+            *last_committed_minute_guard
+        };
+        let start_ms = match last_committed_minute {
+            Some(minute_start) => (minute_start + Duration::minutes(1)).timestamp_millis(),
+            None => timestamp_minute_start(false, Some(last_error_ts)) * 1000,
+        };
+        let end_ms = timestamp_minute_end(false, Some(now_ts / 1000)) * 1000 + 999;
+        (start_ms, end_ms)
+    }
+
+    /// Commits the staged candles, if any, and clears them.
+    fn commit_staged_ticks(&mut self) {
+        if self.staged_ticks.is_empty() {
+            return;
+        }
+        let mut committed_ticks: Vec<TickData> = self.staged_ticks.values().cloned().collect();
+        committed_ticks.sort_by(|a, b| a.symbol.cmp(b.symbol));
+        let committed_minute = committed_ticks[0].start_time;
+        print!("{}", LogKlines(committed_ticks.clone()));
+        self.ticks_to_commit.next(committed_ticks);
+        self.set_last_committed_minute(committed_minute);
+        self.staged_ticks.clear();
+    }
+
+    /// Loads cached candles between `start_datetime` and `end_datetime`, fetching missing days at
+    /// fetch granularity, then downsamples them to kline duration.
+    async fn load_or_fetch_kline_data(
+        &self,
+        trading_data_schema: &Schema,
+        start_datetime: NaiveDateTime,
+        end_datetime: NaiveDateTime,
+    ) -> Result<DataFrame, GlowError> {
+        let cache_name = self.get_cache_name();
+        let mut kline_df = DataFrame::from(trading_data_schema);
+        for symbol in &self.symbols.get_unique_symbols() {
+            let (loaded_data_df, not_loaded_dates) =
+                load_interval_tick_dataframe(start_datetime, end_datetime, symbol, &cache_name)?;
+
+            let mut result_df =
+                loaded_data_df.unwrap_or_else(|| DataFrame::from(trading_data_schema));
+
+            if result_df.schema().len() != trading_data_schema.len() {
+                result_df = coerce_df_to_schema(result_df, trading_data_schema)?;
+            }
+
+            for (i, date) in not_loaded_dates.into_iter().enumerate() {
+                let datetimes = get_date_start_and_end_timestamps(date);
+                let mut day_ticks_data = vec![];
+                if i > 0 {
+                    // avoid spamming API
+                    sleep(StdDuration::from_secs(1)).await;
+                }
+                for (start_timestamp_ms, end_timestamp_ms) in datetimes {
+                    let fetched_ticks = self
+                        .fetch_tick_data(
+                            symbol.name,
+                            start_timestamp_ms,
+                            end_timestamp_ms,
+                            self.fetch_granularity,
+                        )
+                        .await?;
+                    day_ticks_data.extend(fetched_ticks);
+                }
+                let fetched_data_df = map_ticks_data_to_df(&day_ticks_data)?;
+
+                let total_klines = fetched_data_df.height() as i64;
+                let daily_klines = Duration::days(1).num_seconds()
+                    / self.fetch_granularity.get_granularity_in_secs() as i64;
+
+                if total_klines == daily_klines {
+                    save_kline_df_to_csv(&fetched_data_df, date, &cache_name, symbol.name)?;
+                }
+                let fetched_data_df = coerce_df_to_schema(fetched_data_df, trading_data_schema)?;
+                result_df =
+                    result_df
+                        .vstack(&fetched_data_df)?
+                        .sort(["start_time"], false, false)?;
+            }
+            result_df = coerce_df_to_schema(result_df, trading_data_schema)?;
+            kline_df = kline_df.vstack(&result_df)?;
+        }
+
+        let mut kline_df = kline_df.sort(["start_time"], false, false)?;
+
+        kline_df.align_chunks();
+        let kline_lf = filter_df_timestamps_to_lf(kline_df, start_datetime, end_datetime)?;
+        let kline_lf = downsample_tick_lf_to_kline_duration(
+            &self.symbols.get_unique_symbols(),
+            self.kline_duration,
+            kline_lf,
+            ClosedWindow::Left,
+            Some(trading_data_schema),
+        )?;
+
+        Ok(kline_lf.collect()?)
+    }
+
+    /// Fetches `granularity` candles between `start_timestamp_ms` and `end_timestamp_ms` (both inclusive).
+    ///
+    /// As OKX paginates history candles from newest to oldest, pages are requested backwards
+    /// from interval end, and the result is reversed to ascending order.
+    async fn fetch_tick_data(
+        &self,
+        symbol: &'static str,
+        start_timestamp_ms: i64,
+        end_timestamp_ms: i64,
+        granularity: Granularity,
+    ) -> Result<Vec<TickData>, GlowError> {
+        let inst_id = get_okx_inst_id(symbol);
+        let bar = get_okx_bar(granularity)?;
+
+        println!(
+            "{:?} | 🦴 Fetching {} data for interval between {} and {}",
+            current_datetime(),
+            symbol,
+            NaiveDateTime::from_timestamp_millis(start_timestamp_ms).unwrap(),
+            NaiveDateTime::from_timestamp_millis(end_timestamp_ms).unwrap()
+        );
+
+        let mut candles: Vec<OkxHttpCandleResponse> = vec![];
+        // `after` and `before` are exclusive bounds
+        let mut after = end_timestamp_ms + 1;
+        loop {
+            let url = format!(
+                "{}/api/v5/market/history-candles?instId={}&bar={}&after={}&before={}&limit={}",
+                OKX_HTTP_BASE_URL,
+                inst_id,
+                bar,
+                after,
+                start_timestamp_ms - 1,
+                OKX_CANDLES_LIMIT
+            );
+            let response: OkxHttpResponse<Vec<OkxHttpCandleResponse>> =
+                self.http.get(url).send().await?.json().await?;
+            if response.code != "0" {
+                return Err(GlowError::new_unsuccessful_response(format!(
+                    "OKX history candles error {}: {}",
+                    response.code, response.msg
+                )));
+            }
+            let page_len = response.data.len() as i64;
+            let oldest_timestamp = match response.data.last() {
+                Some(candle) => candle[0].parse::<i64>()?,
+                None => break,
+            };
+            candles.extend(response.data);
+            if page_len < OKX_CANDLES_LIMIT || oldest_timestamp <= start_timestamp_ms {
+                break;
+            }
+            after = oldest_timestamp;
+            // avoid hitting OKX rate limit (20 requests / 2 seconds)
+            sleep(StdDuration::from_millis(100)).await;
+        }
+
+        map_candles_to_ticks_data(symbol, &candles)
+    }
+
+    async fn fetch_data_after_waiting(
+        &self,
+        wait_until: Instant,
+        start_timestamp_ms: i64,
+        end_timestamp_ms: i64,
+        trading_data_schema: &Schema,
+    ) -> Result<DataFrame, GlowError> {
+        sleep_until(wait_until).await;
+
+        let mut ticks_data = Vec::new();
+        for symbol in &self.symbols.get_unique_symbols() {
+            let symbol_kline_data = self
+                .fetch_tick_data(
+                    symbol.name,
+                    start_timestamp_ms,
+                    end_timestamp_ms,
+                    Granularity::m1,
+                )
+                .await?;
+            ticks_data.extend(symbol_kline_data);
+        }
+
+        let kline_data_df = map_ticks_data_to_df(&ticks_data)?;
+        let kline_data_df = coerce_df_to_schema(kline_data_df, trading_data_schema)?;
+
+        Ok(kline_data_df)
+    }
+
+    async fn handle_initial_klines_fetch(
+        &self,
+        benchmark_start: NaiveDateTime,
+        benchmark_end: NaiveDateTime,
+        trading_data_schema: &Schema,
+    ) -> Result<(), GlowError> {
+        let initial_kline_data_df = self
+            .load_or_fetch_kline_data(trading_data_schema, benchmark_start, benchmark_end)
+            .await?;
+
+        let current_datetime = current_datetime();
+        let is_last_kline_available = current_datetime > benchmark_end;
+
+        if is_last_kline_available {
+            let initial_data = TradingDataUpdate::Initial(initial_kline_data_df);
+            self.klines_data_update_emitter.next(initial_data);
+            return Ok(());
+        }
+
+        let current_timestamp = current_timestamp();
+        let seconds_until_pending_kline_available = benchmark_end.timestamp() - current_timestamp;
+        let duration_until_pending_kline_available =
+            StdDuration::from_secs(seconds_until_pending_kline_available.max(0) as u64);
+        let pending_kline_available_at = Instant::now() + duration_until_pending_kline_available;
+
+        let remaining_seconds_from_current_ts = current_timestamp % 60;
+        let start_ms = (current_timestamp - remaining_seconds_from_current_ts) * 1000;
+        let end_ms = benchmark_end.timestamp_millis();
+
+        let pending_kline_df = self
+            .fetch_data_after_waiting(
+                pending_kline_available_at,
+                start_ms,
+                end_ms,
+                trading_data_schema,
+            )
+            .await?;
+
+        let initial_kline_data_df = initial_kline_data_df.vstack(&pending_kline_df)?;
+        let initial_data = TradingDataUpdate::Initial(initial_kline_data_df);
+        self.klines_data_update_emitter.next(initial_data);
+        Ok(())
+    }
+}
+
+impl DataProviderExchange for OkxDataProvider {
+    fn get_exchange_name(&self) -> &'static str {
+        "okx"
+    }
+
+    #[inline]
+    fn get_kline_data_emitter(&self) -> &BehaviorSubject<TradingDataUpdate> {
+        &self.klines_data_update_emitter
+    }
+
+    async fn handle_committed_ticks_data(
+        &self,
+        discard_ticks_before: NaiveDateTime,
+        trading_data_schema: &Schema,
+    ) -> Result<(), GlowError> {
+        let mut ticks_to_commit_subscription = self.ticks_to_commit.subscribe();
+        let discard_ticks_before = discard_ticks_before - Duration::nanoseconds(1);
+        let unique_symbols = self.symbols.get_unique_symbols();
+
+        while let Some(committed_ticks) = ticks_to_commit_subscription.next().await {
+            let mut committed_ticks: Vec<TickData> = committed_ticks
+                .into_iter()
+                .filter(|tick| tick.start_time > discard_ticks_before)
+                .collect();
+            if committed_ticks.is_empty() {
+                continue;
+            }
+
+            committed_ticks.sort_by_key(|tick| tick.start_time);
+
+            let committed_kline_df = map_ticks_data_to_df(&committed_ticks)?;
+            let committed_kline_lf =
+                coerce_df_to_schema(committed_kline_df, trading_data_schema)?.lazy();
+
+            let committed_kline_lf = downsample_tick_lf_to_kline_duration(
+                &unique_symbols,
+                self.kline_duration,
+                committed_kline_lf,
+                ClosedWindow::Left,
+                None,
+            )?;
+
+            let committed_kline_df = committed_kline_lf.collect()?;
+
+            let market_data = TradingDataUpdate::Market(committed_kline_df);
+            self.klines_data_update_emitter.next(market_data);
+        }
+        Ok(())
+    }
+
+    fn handle_ws_error(&self, trading_data_schema: &Schema) -> Option<NaiveDateTime> {
+        let schema = trading_data_schema.clone();
+        let last_error_ts = {
+            let last_error_guard = self
+                .last_ws_error_ts
+                .lock()
+                .expect("handle_ws_error -> last_error_guard unwrap");
+            (*last_error_guard)?
+        };
+        let now = Instant::now();
+        let now_ts = current_timestamp_ms();
+
+        let (start_ms, end_ms) = self.get_backfill_interval(last_error_ts, now_ts);
+        let result = NaiveDateTime::from_timestamp_millis(end_ms).unwrap();
+        println!(
+            "{:?} | 🩹 Backfilling klines between {} and {}",
+            current_datetime(),
+            NaiveDateTime::from_timestamp_millis(start_ms).unwrap(),
+            result
+        );
+        let data_provider = self.clone();
+        spawn(async move {
+            let duration_until_available =
+                StdDuration::from_millis((end_ms - now_ts).max(0) as u64);
+
+            let wait_until = now + duration_until_available + data_provider.fetch_leeway;
+            let pending_kline_df = data_provider
+                .fetch_data_after_waiting(wait_until, start_ms, end_ms, &schema)
+                .await
+                .expect("pending kline df");
+            let market_data = TradingDataUpdate::Market(pending_kline_df);
+            data_provider.klines_data_update_emitter.next(market_data);
+            data_provider.set_last_committed_minute(
+                NaiveDateTime::from_timestamp_millis(end_ms - 59_999).unwrap(),
+            );
+
+            {
+                let mut last_error_guard = data_provider.last_ws_error_ts.lock().unwrap();
+                *last_error_guard = None;
+            }
+        });
+        Some(result)
+    }
+
+    async fn init(
+        &mut self,
+        benchmark_window: BenchmarkWindow,
+        run_mode: RunMode,
+        trading_data_schema: Schema,
+    ) -> Result<(), GlowError> {
+        let (benchmark_start, benchmark_end) = adjust_benchmark_datetimes(
+            benchmark_window,
+            self.kline_duration,
+            Some(1),
+            self.minimum_klines_for_benchmarking as i32,
+        )?;
+
+        self.handle_initial_klines_fetch(benchmark_start, benchmark_end, &trading_data_schema)
+            .await?;
+
+        if !run_mode.streams_market_data() {
+            return Ok(());
+        }
+
+        println!(
+            "{} | 💹 Initializing DataFeed -> trades might be open after {}",
+            current_datetime(),
+            benchmark_end
+        );
+
+        let okx_ws_base_url = env_var("OKX_WS_BASE_URL")?;
+        let url = Url::parse(&format!("{}/ws/v5/business", okx_ws_base_url))?;
+
+        loop {
+            match connect_async(url.clone()).await {
+                Ok((wss, resp)) => {
+                    eprintln!(
+                        "Data provider connection stablished. \n Response: {:?}",
+                        resp
+                    );
+                    let discard_ticks_before = self
+                        .handle_ws_error(&trading_data_schema)
+                        .unwrap_or(benchmark_end);
+                    let committer = self.clone();
+                    let result = select! {
+                        result = committer.handle_committed_ticks_data(
+                            discard_ticks_before,
+                            &trading_data_schema,
+                        ) => result,
+                        result = self.listen_ticks(wss, discard_ticks_before) => result,
+                    };
+                    if let Err(error) = result {
+                        set_ws_error_ts(self.last_ws_error_ts.clone(), error);
+                        sleep(StdDuration::from_secs(WS_RECONNECT_INTERVAL_IN_SECS)).await;
+                    }
+                }
+                Err(error) => {
+                    set_ws_error_ts(self.last_ws_error_ts.clone(), error.into());
+                    sleep(StdDuration::from_secs(WS_RECONNECT_INTERVAL_IN_SECS)).await;
+                }
+            }
+        }
+    }
+
+    /// Stages the latest 1 minute candle of each symbol, committing them as soon as
+    /// every symbol candle is confirmed, or a newer minute candle arrives.
+    async fn listen_ticks(
+        &mut self,
+        mut wss: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        discard_ticks_before: NaiveDateTime,
+    ) -> Result<(), GlowError> {
+        self.subscribe_to_tick_stream(&mut wss).await?;
+
+        let unique_symbols = self.symbols.get_unique_symbols();
+        let mut heartbeat_interval = interval(StdDuration::from_secs(OKX_WS_PING_INTERVAL_IN_SECS));
+        let mut confirmed_symbols: Vec<&'static str> = vec![];
+        let ws_compression = self.get_ws_compression();
+
+        loop {
+            select! {
+                message = wss.next() => {
+                    let message = match message {
+                        Some(Ok(message)) => message,
+                        Some(Err(error)) => {
+                            self.staged_ticks.clear();
+                            eprintln!("WebSocket message error: {:?}", error);
+                            return Err(GlowError::from(error));
+                        }
+                        None => {
+                            self.staged_ticks.clear();
+                            return Err(GlowError::new_str(
+                                "OKX WebSocket closed",
+                                "OKX data provider stream ended",
+                            ));
+                        }
+                    };
+                    let message = match ws_compression.decode_message(message) {
+                        Ok(message) => message,
+                        Err(error) => {
+                            eprintln!("WebSocket message decompression error: {:?}", error);
+                            continue;
+                        }
+                    };
+                    match message {
+                        Message::Text(json) => {
+                            if json == "pong" {
+                                continue;
+                            }
+                            let Some(incoming_msg) =
+                                self.parse_ws_message::<IncomingWsMessage>(&json)
+                            else {
+                                continue;
+                            };
+                            match incoming_msg {
+                                IncomingWsMessage::Candle(candle_message) => {
+                                    let symbol = match unique_symbols.iter().find(|symbol| {
+                                        get_okx_inst_id(symbol.name) == candle_message.arg.inst_id
+                                    }) {
+                                        Some(symbol) => symbol.name,
+                                        None => continue,
+                                    };
+                                    for candle in candle_message.data {
+                                        let is_confirmed = is_candle_confirmed(&candle);
+                                        let tick_data = map_candles_to_ticks_data(symbol, &[candle])?
+                                            .pop()
+                                            .expect("a tick data per candle");
+                                        if tick_data.start_time < discard_ticks_before {
+                                            continue;
+                                        }
+                                        let staged_minute = self
+                                            .staged_ticks
+                                            .values()
+                                            .next()
+                                            .map(|tick| tick.start_time);
+                                        if let Some(staged_minute) = staged_minute {
+                                            if tick_data.start_time > staged_minute {
+                                                self.commit_staged_ticks();
+                                                confirmed_symbols.clear();
+                                            } else if tick_data.start_time < staged_minute {
+                                                continue;
+                                            }
+                                        }
+                                        self.staged_ticks.insert(symbol, tick_data);
+                                        if is_confirmed && !confirmed_symbols.contains(&symbol) {
+                                            confirmed_symbols.push(symbol);
+                                        }
+                                        if confirmed_symbols.len() == unique_symbols.len() {
+                                            self.commit_staged_ticks();
+                                            confirmed_symbols.clear();
+                                        }
+                                    }
+                                }
+                                IncomingWsMessage::Event(event) => {
+                                    if event.event == "error" {
+                                        eprintln!("OKX data provider error event {:?}", event);
+                                    }
+                                }
+                                IncomingWsMessage::None => self.drop_unhandled_ws_message(json),
+                            }
+                        }
+                        Message::Ping(_) => wss.send(Message::Pong(vec![])).await?,
+                        unhandled => self.drop_unhandled_ws_message(unhandled),
+                    }
+                }
+                _ = heartbeat_interval.tick() => {
+                    wss.send(Message::Text(String::from("ping"))).await?;
+                }
+            }
+        }
+    }
+
+    async fn subscribe_to_tick_stream(
+        &mut self,
+        wss: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), GlowError> {
+        let channel = format!("candle{}", get_okx_bar(Granularity::m1)?);
+        let args = self
+            .symbols
+            .get_unique_symbols()
+            .into_iter()
+            .map(|symbol| WsChannelArg {
+                channel: channel.clone(),
+                inst_id: get_okx_inst_id(symbol.name),
+            })
+            .collect();
+
+        let subscribe_message = WsOutgoingMessage {
+            op: String::from("subscribe"),
+            args,
+        };
+
+        let subscribe_json_str = to_string(&subscribe_message)?;
+
+        wss.send(Message::Text(subscribe_json_str))
+            .await
+            .map_err(GlowError::from)
+    }
+}
@@ -0,0 +1,63 @@
+use super::functions::{
+    get_okx_bar, get_okx_fetch_granularity, get_okx_inst_id, map_candles_to_ticks_data,
+};
+use chrono::NaiveDateTime;
+use common::enums::granularity::Granularity;
+
+fn get_candle(timestamp: i64, close: &str) -> Vec<String> {
+    vec![
+        timestamp.to_string(),
+        String::from("1.0"),
+        String::from("2.0"),
+        String::from("0.5"),
+        close.to_string(),
+        String::from("10"),
+        String::from("10"),
+        String::from("10"),
+        String::from("1"),
+    ]
+}
+
+#[test]
+fn test_candles_are_mapped_to_ascending_ticks() {
+    // OKX returns newest candles first
+    let candles = vec![
+        get_candle(1_704_067_320_000, "1.3"),
+        get_candle(1_704_067_260_000, "1.2"),
+        get_candle(1_704_067_200_000, "1.1"),
+    ];
+    let ticks = map_candles_to_ticks_data("BTCUSDT", &candles).unwrap();
+
+    let start_times: Vec<NaiveDateTime> = ticks.iter().map(|tick| tick.start_time).collect();
+    let mut sorted_start_times = start_times.clone();
+    sorted_start_times.sort();
+    assert_eq!(start_times, sorted_start_times);
+    assert_eq!(ticks[0].close, 1.1);
+    assert_eq!(ticks[2].close, 1.3);
+    assert_eq!(ticks[0].high, 2.0);
+    assert_eq!(ticks[0].low, 0.5);
+}
+
+#[test]
+fn test_granularity_to_okx_bar() {
+    assert_eq!(get_okx_bar(Granularity::m1).unwrap(), "1m");
+    assert_eq!(get_okx_bar(Granularity::m5).unwrap(), "5m");
+    assert_eq!(get_okx_bar(Granularity::h1).unwrap(), "1H");
+    assert_eq!(get_okx_bar(Granularity::d1).unwrap(), "1Dutc");
+    assert!(get_okx_bar(Granularity::m10).is_err());
+}
+
+#[test]
+fn test_candles_are_fetched_at_coarsest_bar_dividing_granularity() {
+    assert_eq!(get_okx_fetch_granularity(Granularity::m1), Granularity::m1);
+    assert_eq!(get_okx_fetch_granularity(Granularity::m5), Granularity::m5);
+    assert_eq!(get_okx_fetch_granularity(Granularity::m10), Granularity::m5);
+    assert_eq!(get_okx_fetch_granularity(Granularity::h2), Granularity::h2);
+    assert_eq!(get_okx_fetch_granularity(Granularity::w1), Granularity::d1);
+}
+
+#[test]
+fn test_symbol_to_okx_inst_id() {
+    assert_eq!(get_okx_inst_id("BTCUSDT"), "BTC-USDT");
+    assert_eq!(get_okx_inst_id("LINKUSDT"), "LINK-USDT");
+}
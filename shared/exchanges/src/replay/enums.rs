@@ -0,0 +1,23 @@
+use std::str::FromStr;
+
+/// Pace at which recorded klines are replayed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ReplaySpeed {
+    /// emits next kline as soon as runtime gets a chance to process the previous one
+    #[default]
+    AsFastAsPossible,
+    /// waits a kline duration between emissions, as a live data provider would
+    RealTime,
+}
+
+impl FromStr for ReplaySpeed {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fast" | "as_fast_as_possible" => Ok(Self::AsFastAsPossible),
+            "realtime" | "real_time" => Ok(Self::RealTime),
+            _ => Err(format!("invalid replay speed {}", s)),
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod enums;
+pub mod structs;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,234 @@
+use super::enums::ReplaySpeed;
+use chrono::{Duration, NaiveDateTime};
+use common::{
+    enums::{
+        benchmark_window::BenchmarkWindow, run_mode::RunMode,
+        trading_data_update::TradingDataUpdate,
+    },
+    functions::{coerce_df_to_schema, csv::load_csv, current_datetime},
+    structs::{BehaviorSubject, SymbolsPair, TradingSettings},
+    traits::exchange::DataProviderExchange,
+};
+use glow_error::GlowError;
+use polars::prelude::{DataFrame, DataType, Schema, TimeUnit};
+use std::{env::var as env_var, path::PathBuf, str::FromStr};
+use strategy::Strategy;
+use tokio::{net::TcpStream, task::yield_now, time::sleep};
+use tokio_stream::StreamExt;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+/// Data provider that replays recorded klines from a .csv file through the same
+/// emitter live data providers use, so that strategies can be dry-run deterministically.
+#[derive(Clone)]
+pub struct ReplayDataProvider {
+    file_path: PathBuf,
+    kline_ack_emitter: BehaviorSubject<Option<usize>>,
+    kline_duration: Duration,
+    klines_data_update_emitter: BehaviorSubject<TradingDataUpdate>,
+    minimum_klines_for_benchmarking: u32,
+    speed: ReplaySpeed,
+    symbols: SymbolsPair,
+}
+
+impl ReplayDataProvider {
+    pub fn new(
+        trading_settings: &TradingSettings,
+        strategy: &Strategy,
+        file_path: PathBuf,
+        speed: ReplaySpeed,
+    ) -> Self {
+        let symbols = trading_settings.symbols_pair;
+        let kline_duration = trading_settings.granularity.get_chrono_duration();
+        let minimum_klines_for_benchmarking = strategy.get_warmup_klines();
+        let klines_data_update_emitter = BehaviorSubject::new(TradingDataUpdate::default());
+        Self {
+            file_path,
+            kline_ack_emitter: BehaviorSubject::new(None),
+            kline_duration,
+            klines_data_update_emitter,
+            minimum_klines_for_benchmarking,
+            speed,
+            symbols,
+        }
+    }
+
+    /// Builds provider from `REPLAY_DATA_PATH` and `REPLAY_SPEED` env vars.
+    /// Speed defaults to as fast as possible when unset or invalid.
+    pub fn from_env(trading_settings: &TradingSettings, strategy: &Strategy) -> Self {
+        let file_path = PathBuf::from(env_var("REPLAY_DATA_PATH").unwrap_or_default());
+        let speed = env_var("REPLAY_SPEED")
+            .ok()
+            .and_then(|speed| ReplaySpeed::from_str(&speed).ok())
+            .unwrap_or_default();
+        Self::new(trading_settings, strategy, file_path, speed)
+    }
+
+    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
+        self.symbols = trading_settings.symbols_pair;
+        self.kline_duration = trading_settings.granularity.get_chrono_duration();
+    }
+
+    pub fn patch_strategy(&mut self, strategy: &Strategy) {
+        self.minimum_klines_for_benchmarking = strategy.get_warmup_klines();
+    }
+
+    fn get_klines_schema(&self) -> Schema {
+        let mut schema = Schema::new();
+        let _ = schema.insert_at_index(
+            0,
+            "start_time".into(),
+            DataType::Datetime(TimeUnit::Milliseconds, None),
+        );
+        for symbol in self.symbols.get_unique_symbols() {
+            let (open, high, low, close) = symbol.get_ohlc_cols();
+            for col in [open, high, low, close] {
+                let _ = schema.insert_at_index(schema.len(), col.into(), DataType::Float64);
+            }
+        }
+        schema
+    }
+
+    fn load_klines(&self, trading_data_schema: &Schema) -> Result<DataFrame, GlowError> {
+        if !self.file_path.is_file() {
+            return Err(GlowError::new(
+                String::from("Replay data error"),
+                format!("Replay file {:?} doesn't exist", self.file_path),
+            ));
+        }
+        let klines_df = load_csv(self.file_path.clone(), &self.get_klines_schema())?;
+        let klines_df = klines_df.sort(["start_time"], false, false)?;
+        coerce_df_to_schema(klines_df, trading_data_schema)
+    }
+
+    /// Gets how many leading klines are emitted as initial data. When benchmark end is set,
+    /// every kline starting up to it is initial, otherwise just enough for calculations.
+    /// Either way, initial data holds strategy warmup klines, so that first replayed market
+    /// kline has indicators' whole lookback even for benchmark windows shorter than it.
+    fn get_initial_klines_count(
+        &self,
+        klines_df: &DataFrame,
+        benchmark_end: Option<NaiveDateTime>,
+    ) -> Result<usize, GlowError> {
+        let count = match benchmark_end {
+            Some(benchmark_end) => {
+                let benchmark_end_ms = benchmark_end.timestamp_millis();
+                klines_df
+                    .column("start_time")?
+                    .datetime()?
+                    .into_iter()
+                    .filter(|start_time| start_time.is_some_and(|ts| ts <= benchmark_end_ms))
+                    .count()
+                    .max(self.minimum_klines_for_benchmarking as usize)
+            }
+            None => self.minimum_klines_for_benchmarking.max(1) as usize,
+        };
+        Ok(count.min(klines_df.height()))
+    }
+
+    /// Waits before emitting next market kline, after `emitted_updates` klines updates were.
+    /// Replaying as fast as possible waits for them to be acknowledged, as emitter only keeps
+    /// latest update.
+    async fn wait_for_next_kline(&self, emitted_updates: usize) {
+        match self.speed {
+            ReplaySpeed::AsFastAsPossible => self.wait_for_kline_ack(emitted_updates).await,
+            ReplaySpeed::RealTime => {
+                let kline_duration = self.kline_duration.to_std().unwrap_or_default();
+                sleep(kline_duration).await
+            }
+        }
+    }
+
+    async fn wait_for_kline_ack(&self, emitted_updates: usize) {
+        if self.kline_ack_emitter.value().is_none() {
+            // no consumer acknowledges klines
+            yield_now().await;
+            return;
+        }
+        let mut subscription = self.kline_ack_emitter.subscribe();
+        while let Some(acked_updates) = subscription.next().await {
+            if acked_updates.is_some_and(|acked_updates| acked_updates >= emitted_updates) {
+                return;
+            }
+        }
+    }
+}
+
+impl DataProviderExchange for ReplayDataProvider {
+    fn get_exchange_name(&self) -> &'static str {
+        "replay"
+    }
+
+    #[inline]
+    fn get_kline_data_emitter(&self) -> &BehaviorSubject<TradingDataUpdate> {
+        &self.klines_data_update_emitter
+    }
+
+    fn get_kline_ack_emitter(&self) -> Option<&BehaviorSubject<Option<usize>>> {
+        Some(&self.kline_ack_emitter)
+    }
+
+    async fn handle_committed_ticks_data(
+        &self,
+        _discard_ticks_before: NaiveDateTime,
+        _trading_data_schema: &Schema,
+    ) -> Result<(), GlowError> {
+        Ok(())
+    }
+
+    fn handle_ws_error(&self, _trading_data_schema: &Schema) -> Option<NaiveDateTime> {
+        None
+    }
+
+    async fn init(
+        &mut self,
+        benchmark_window: BenchmarkWindow,
+        run_mode: RunMode,
+        trading_data_schema: Schema,
+    ) -> Result<(), GlowError> {
+        // recorded klines aren't relative to now, so only absolute ends are honoured
+        let benchmark_end = match benchmark_window {
+            BenchmarkWindow::Absolute(_, benchmark_end) => benchmark_end,
+            _ => None,
+        };
+        let klines_df = self.load_klines(&trading_data_schema)?;
+        let initial_klines_count = self.get_initial_klines_count(&klines_df, benchmark_end)?;
+
+        let initial_data = TradingDataUpdate::Initial(klines_df.head(Some(initial_klines_count)));
+        self.klines_data_update_emitter.next(initial_data);
+
+        if !run_mode.streams_market_data() {
+            return Ok(());
+        }
+
+        println!(
+            "{} | ⏪ Replaying {} klines from {:?}",
+            current_datetime(),
+            klines_df.height() - initial_klines_count,
+            self.file_path
+        );
+
+        for (market_klines, row) in (initial_klines_count..klines_df.height()).enumerate() {
+            // initial klines update included
+            self.wait_for_next_kline(market_klines + 1).await;
+            let market_data = TradingDataUpdate::Market(klines_df.slice(row as i64, 1));
+            self.klines_data_update_emitter.next(market_data);
+        }
+
+        Ok(())
+    }
+
+    async fn listen_ticks(
+        &mut self,
+        _wss: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        _discard_ticks_before: NaiveDateTime,
+    ) -> Result<(), GlowError> {
+        Ok(())
+    }
+
+    async fn subscribe_to_tick_stream(
+        &mut self,
+        _wss: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), GlowError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+use super::{enums::ReplaySpeed, structs::ReplayDataProvider};
+use chrono::{Duration, NaiveDateTime};
+use common::{
+    enums::{
+        benchmark_window::BenchmarkWindow, run_mode::RunMode,
+        trading_data_update::TradingDataUpdate,
+    },
+    structs::{SymbolsPair, TradingSettings},
+    traits::exchange::DataProviderExchange,
+};
+use polars::prelude::{DataFrame, Schema};
+use std::{env::temp_dir, fs::write, path::PathBuf, time::Duration as StdDuration};
+use strategy::{
+    params::{NumberParamConfig, Param, ParamId},
+    Strategy,
+};
+use tokio::{
+    spawn,
+    time::{sleep, timeout},
+};
+use tokio_stream::StreamExt;
+
+fn get_schema() -> Schema {
+    SymbolsPair::default()
+        .traded
+        .derive_symbol_tick_data_schema()
+}
+
+const KLINES_COUNT: i64 = 60;
+
+fn get_start_time(minute: i64) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str("2024-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap()
+        + Duration::minutes(minute)
+}
+
+fn get_benchmark_end() -> Option<NaiveDateTime> {
+    Some(get_start_time(KLINES_COUNT - 5))
+}
+
+fn get_strategy() -> Strategy {
+    let slow_span_config = NumberParamConfig::new(100, Some(50), Some(200));
+    Strategy::default()
+        .patch_param(ParamId::SlowSpan, Param::UInt32(50, slow_span_config))
+        .unwrap()
+}
+
+/// Writes one minute klines closing at their minute index, e.g. 3.0 for 00:03:00.
+fn get_replay_provider(file_name: &str) -> ReplayDataProvider {
+    let symbols = SymbolsPair::default();
+    let (open, high, low, close) = symbols.traded.get_ohlc_cols();
+    let mut csv = format!("start_time,{},{},{},{}\n", open, high, low, close);
+    for minute in 0..KLINES_COUNT {
+        csv.push_str(&format!(
+            "{},1.0,2.0,0.5,{}.0\n",
+            get_start_time(minute).format("%Y-%m-%d %H:%M:%S"),
+            minute
+        ));
+    }
+    let file_path: PathBuf = temp_dir().join(file_name);
+    write(&file_path, csv).unwrap();
+
+    ReplayDataProvider::new(
+        &TradingSettings::default(),
+        &get_strategy(),
+        file_path,
+        ReplaySpeed::AsFastAsPossible,
+    )
+}
+
+fn get_closes(df: &DataFrame) -> Vec<Option<f64>> {
+    let close_col = SymbolsPair::default().traded.get_ohlc_cols().3;
+    df.column(close_col)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect()
+}
+
+#[tokio::test]
+async fn test_replay_emits_only_initial_klines_when_running_benchmark_only() {
+    let mut provider = get_replay_provider("glow_replay_benchmark_only.csv");
+    let schema = get_schema();
+
+    provider
+        .init(
+            BenchmarkWindow::Absolute(None, get_benchmark_end()),
+            RunMode::BenchmarkOnly,
+            schema,
+        )
+        .await
+        .unwrap();
+
+    match provider.get_kline_data_emitter().value() {
+        TradingDataUpdate::Initial(df) => assert_eq!(df.height(), KLINES_COUNT as usize - 4),
+        _ => panic!("expected initial klines"),
+    }
+}
+
+#[tokio::test]
+async fn test_replay_initial_klines_cover_warmup_when_benchmark_window_is_shorter() {
+    let mut provider = get_replay_provider("glow_replay_short_window.csv");
+    let schema = get_schema();
+    let warmup_klines = get_strategy().get_warmup_klines() as usize;
+    // benchmark window ends at second kline, way before lookback is covered
+    let benchmark_end = Some(get_start_time(1));
+    assert!(warmup_klines > 2);
+
+    provider
+        .init(
+            BenchmarkWindow::Absolute(None, benchmark_end),
+            RunMode::BenchmarkOnly,
+            schema,
+        )
+        .await
+        .unwrap();
+
+    match provider.get_kline_data_emitter().value() {
+        TradingDataUpdate::Initial(df) => {
+            let expected_closes: Vec<_> = (0..warmup_klines)
+                .map(|minute| Some(minute as f64))
+                .collect();
+            assert_eq!(get_closes(&df), expected_closes);
+        }
+        _ => panic!("expected initial klines"),
+    }
+}
+
+#[tokio::test]
+async fn test_replay_emits_remaining_klines_as_market_data() {
+    let mut provider = get_replay_provider("glow_replay_market_data.csv");
+    let schema = get_schema();
+
+    provider
+        .init(
+            BenchmarkWindow::Absolute(None, get_benchmark_end()),
+            RunMode::Live,
+            schema,
+        )
+        .await
+        .unwrap();
+
+    match provider.get_kline_data_emitter().value() {
+        TradingDataUpdate::Market(df) => {
+            assert_eq!(get_closes(&df), vec![Some((KLINES_COUNT - 1) as f64)]);
+        }
+        _ => panic!("expected market klines"),
+    }
+}
+
+#[tokio::test]
+async fn test_replay_streams_market_klines_when_gathering_data_only() {
+    let mut provider = get_replay_provider("glow_replay_data_gather_only.csv");
+    let schema = get_schema();
+
+    provider
+        .init(
+            BenchmarkWindow::Absolute(None, get_benchmark_end()),
+            RunMode::DataGatherOnly,
+            schema,
+        )
+        .await
+        .unwrap();
+
+    match provider.get_kline_data_emitter().value() {
+        TradingDataUpdate::Market(df) => assert_eq!(df.height(), 1),
+        _ => panic!("expected market klines"),
+    }
+}
+
+#[tokio::test]
+async fn test_replay_as_fast_as_possible_emits_every_acknowledged_kline_in_order() {
+    let mut provider = get_replay_provider("glow_replay_acknowledged.csv");
+    let schema = get_schema();
+    let kline_ack_emitter = provider.get_kline_ack_emitter().unwrap().clone();
+    kline_ack_emitter.next(Some(0));
+
+    let mut subscription = provider.get_kline_data_emitter().subscribe();
+    let consumer = spawn(async move {
+        let mut handled_updates = 0;
+        let mut closes = vec![];
+        while let Some(klines_data) = subscription.next().await {
+            let df = match klines_data {
+                TradingDataUpdate::Initial(df) | TradingDataUpdate::Market(df) => df,
+                _ => continue,
+            };
+            // slow consumer, which would miss klines not waited for
+            sleep(StdDuration::from_millis(5)).await;
+            closes.extend(get_closes(&df));
+            handled_updates += 1;
+            kline_ack_emitter.next(Some(handled_updates));
+            if closes.len() == KLINES_COUNT as usize {
+                break;
+            }
+        }
+        closes
+    });
+
+    provider
+        .init(
+            BenchmarkWindow::Absolute(None, get_benchmark_end()),
+            RunMode::Live,
+            schema,
+        )
+        .await
+        .unwrap();
+
+    let closes = timeout(StdDuration::from_secs(1), consumer)
+        .await
+        .unwrap()
+        .unwrap();
+    let expected_closes: Vec<_> = (0..KLINES_COUNT)
+        .map(|minute| Some(minute as f64))
+        .collect();
+    assert_eq!(closes, expected_closes);
+}
+
+#[test]
+fn test_replay_speed_is_parsed_from_str() {
+    assert_eq!("realtime".parse(), Ok(ReplaySpeed::RealTime));
+    assert_eq!("fast".parse(), Ok(ReplaySpeed::AsFastAsPossible));
+    assert!("slow".parse::<ReplaySpeed>().is_err());
+}
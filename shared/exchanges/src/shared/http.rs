@@ -0,0 +1,63 @@
+use crate::structs::{HttpRateLimiter, HttpRetryPolicy};
+use glow_error::GlowError;
+use reqwest::{header::RETRY_AFTER, Client, Response, StatusCode};
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// Sends GET request to `url`, retrying it according to `retry_policy` on transient errors.
+///
+/// Rate limited responses wait for as many seconds as their `Retry-After` header, if any,
+/// while server errors, timeouts and connection failures back off exponentially.
+/// Other responses are returned as they are, for callers to handle.
+///
+/// Every attempt, retries included, waits for a token from `rate_limiter` before being sent.
+pub async fn send_with_retry(
+    http: &Client,
+    url: &str,
+    retry_policy: &HttpRetryPolicy,
+    rate_limiter: &HttpRateLimiter,
+) -> Result<Response, GlowError> {
+    let mut retry = 0;
+    loop {
+        rate_limiter.acquire().await;
+        let (delay, error) = match http.get(url).send().await {
+            Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                let delay = get_retry_after(&response)
+                    .unwrap_or_else(|| retry_policy.get_backoff_delay(retry));
+                (delay, format!("{} responded {}", url, response.status()))
+            }
+            Ok(response) if response.status().is_server_error() => (
+                retry_policy.get_backoff_delay(retry),
+                format!("{} responded {}", url, response.status()),
+            ),
+            Ok(response) => return Ok(response),
+            Err(error) if error.is_timeout() || error.is_connect() => {
+                (retry_policy.get_backoff_delay(retry), error.to_string())
+            }
+            Err(error) => return Err(error.into()),
+        };
+        if retry >= retry_policy.max_retries {
+            let error = format!("{} (after {} retries)", error, retry);
+            return Err(GlowError::new(
+                String::from("Http Retries Exhausted"),
+                error,
+            ));
+        }
+        println!(
+            "send_with_retry -> {}, retrying in {:?} ({}/{})",
+            error,
+            delay,
+            retry + 1,
+            retry_policy.max_retries
+        );
+        sleep(delay).await;
+        retry += 1;
+    }
+}
+
+/// Parses `Retry-After` header, when given in seconds.
+fn get_retry_after(response: &Response) -> Option<Duration> {
+    let retry_after = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    let seconds = retry_after.trim().parse::<u64>().ok()?;
+    Some(Duration::from_secs(seconds))
+}
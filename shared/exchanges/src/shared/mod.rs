@@ -1,2 +1,5 @@
 pub mod deserializers;
+pub mod http;
 pub mod serializers;
+#[cfg(test)]
+mod tests;
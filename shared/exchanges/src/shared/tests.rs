@@ -0,0 +1,117 @@
+use super::http::send_with_retry;
+use crate::structs::{HttpRateLimiter, HttpRetryPolicy};
+use reqwest::{Client, StatusCode};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    spawn,
+};
+
+/// Serves each of `responses` to a single request, in order, counting received requests.
+async fn serve_responses(responses: Vec<&'static str>) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/api/v3/klines", listener.local_addr().unwrap());
+    let requests_count = Arc::new(AtomicUsize::new(0));
+    let server_requests_count = requests_count.clone();
+    spawn(async move {
+        for response in responses {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = [0; 1024];
+            let _ = stream.read(&mut buffer).await.unwrap();
+            server_requests_count.fetch_add(1, Ordering::SeqCst);
+            stream.write_all(response.as_bytes()).await.unwrap();
+            stream.shutdown().await.unwrap();
+        }
+    });
+    (url, requests_count)
+}
+
+fn get_test_retry_policy(max_retries: u32) -> HttpRetryPolicy {
+    HttpRetryPolicy {
+        max_retries,
+        base_delay: Duration::from_millis(1),
+    }
+}
+
+fn get_test_rate_limiter() -> HttpRateLimiter {
+    HttpRateLimiter::new(1_000.0, 1_000)
+}
+
+const RATE_LIMITED_RESPONSE: &str = "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const SERVER_ERROR_RESPONSE: &str =
+    "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const OK_RESPONSE: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n[]";
+
+#[tokio::test]
+async fn test_send_with_retry_retries_rate_limited_request() {
+    let (url, requests_count) = serve_responses(vec![RATE_LIMITED_RESPONSE, OK_RESPONSE]).await;
+
+    let response = send_with_retry(
+        &Client::new(),
+        &url,
+        &get_test_retry_policy(3),
+        &get_test_rate_limiter(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Vec<serde_json::Value> = response.json().await.unwrap();
+    assert!(body.is_empty());
+    assert_eq!(requests_count.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_send_with_retry_fails_once_retries_are_exhausted() {
+    let (url, requests_count) =
+        serve_responses(vec![SERVER_ERROR_RESPONSE, SERVER_ERROR_RESPONSE]).await;
+
+    let result = send_with_retry(
+        &Client::new(),
+        &url,
+        &get_test_retry_policy(1),
+        &get_test_rate_limiter(),
+    )
+    .await;
+
+    let error = result.unwrap_err();
+    assert_eq!(error.title, "Http Retries Exhausted");
+    assert_eq!(requests_count.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_send_with_retry_counts_retries_against_rate_limit() {
+    let (url, requests_count) = serve_responses(vec![
+        RATE_LIMITED_RESPONSE,
+        SERVER_ERROR_RESPONSE,
+        OK_RESPONSE,
+    ])
+    .await;
+    let rate_limiter = HttpRateLimiter::new(10.0, 1);
+    let started_at = Instant::now();
+
+    let response = send_with_retry(
+        &Client::new(),
+        &url,
+        &get_test_retry_policy(3),
+        &rate_limiter.clone(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(requests_count.load(Ordering::SeqCst), 3);
+    // first attempt takes the single burst token, each retry waits 100ms for another
+    assert!(started_at.elapsed() >= Duration::from_millis(190));
+
+    let started_at = Instant::now();
+    rate_limiter.acquire().await;
+    assert!(started_at.elapsed() >= Duration::from_millis(90));
+}
@@ -99,5 +99,65 @@ pub static TRADER_EXCHANGES_CONTEXT_MAP: LazyLock<HashMap<TraderExchangeId, Exch
             exchanges_contexts.insert(TraderExchangeId::Bybit, context);
         }
 
+        {
+            let btcusdt_contract = Contract::new(
+                NaiveDateTime::new(
+                    NaiveDate::from_ymd_opt(2022, 9, 1).unwrap(),
+                    NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+                ),
+                Duration::hours(1),
+                0.000001,
+                50.0,
+                (100.0, 1000.0),
+                0.0001,
+                None,
+                SYMBOLS_MAP.get("BTCUSDT").unwrap(),
+                1.0,
+            );
+
+            let ethusdt_contract = Contract::new(
+                NaiveDateTime::new(
+                    NaiveDate::from_ymd_opt(2022, 9, 1).unwrap(),
+                    NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+                ),
+                Duration::hours(1),
+                0.000001,
+                50.0,
+                (1000.0, 10000.0),
+                0.001,
+                None,
+                SYMBOLS_MAP.get("ETHUSDT").unwrap(),
+                0.1,
+            );
+
+            let solusdt_contract = Contract::new(
+                NaiveDateTime::new(
+                    NaiveDate::from_ymd_opt(2022, 9, 1).unwrap(),
+                    NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+                ),
+                Duration::hours(1),
+                0.000001,
+                50.0,
+                (50000.0, 500000.0),
+                0.01,
+                None,
+                SYMBOLS_MAP.get("SOLUSDT").unwrap(),
+                0.001,
+            );
+
+            let mut contracts = HashMap::new();
+            contracts.insert(btcusdt_contract.symbol.id, btcusdt_contract);
+            contracts.insert(ethusdt_contract.symbol.id, ethusdt_contract);
+            contracts.insert(solusdt_contract.symbol.id, solusdt_contract);
+
+            let context = ExchangeContext {
+                taker_fee: 0.0005,
+                maker_fee: 0.0002,
+                contracts,
+            };
+
+            exchanges_contexts.insert(TraderExchangeId::Kraken, context);
+        }
+
         exchanges_contexts
     });
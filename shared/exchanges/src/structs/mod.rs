@@ -1,6 +1,11 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use common::{enums::symbol_id::SymbolId, structs::Contract};
+use tokio::time::{sleep, Instant};
 
 #[derive(Debug, Clone, Copy)]
 pub struct ApiCredentials {
@@ -26,3 +31,78 @@ pub struct ExchangeContext {
     pub maker_fee: f64,
     pub contracts: HashMap<SymbolId, Contract>,
 }
+
+/// Bounded retries for transient HTTP errors, i.e. rate limits, server errors and timeouts.
+/// Delay doubles after each retry, unless server tells how long to wait for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpRetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+}
+
+impl Default for HttpRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+impl HttpRetryPolicy {
+    pub fn get_backoff_delay(&self, retry: u32) -> Duration {
+        self.base_delay.saturating_mul(2_u32.saturating_pow(retry))
+    }
+}
+
+/// Token bucket throttling HTTP requests to `requests_per_second`, allowing bursts of up to
+/// `burst` requests. Clones share the same bucket, so that every request sent through any of
+/// them, retries included, counts against the same budget.
+#[derive(Debug, Clone)]
+pub struct HttpRateLimiter {
+    requests_per_second: f64,
+    burst: f64,
+    bucket: Arc<Mutex<(f64, Instant)>>, // (available tokens, last refill)
+}
+
+impl HttpRateLimiter {
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        assert!(
+            requests_per_second > 0.0,
+            "Requests per second must be greater than 0"
+        );
+        let burst = burst.max(1) as f64;
+        Self {
+            requests_per_second,
+            burst,
+            bucket: Arc::new(Mutex::new((burst, Instant::now()))),
+        }
+    }
+
+    pub fn get_requests_per_second(&self) -> f64 {
+        self.requests_per_second
+    }
+
+    /// Waits until a token is available, taking it.
+    pub async fn acquire(&self) {
+        let delay = self.reserve();
+        if !delay.is_zero() {
+            sleep(delay).await;
+        }
+    }
+
+    /// Takes a token, returning how long to wait for it. Tokens are taken even if not yet
+    /// refilled, so that concurrent callers queue up in order instead of competing for them.
+    fn reserve(&self) -> Duration {
+        let mut bucket_guard = self.bucket.lock().expect("reserve -> bucket_guard unwrap");
+        let (tokens, last_refill) = *bucket_guard;
+        let now = Instant::now();
+        let refilled = (now - last_refill).as_secs_f64() * self.requests_per_second;
+        let tokens = (tokens + refilled).min(self.burst) - 1.0;
+        *bucket_guard = (tokens, now);
+        if tokens >= 0.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(-tokens / self.requests_per_second)
+    }
+}
@@ -1,4 +1,5 @@
 use glow_error::GlowError;
+use polars::prelude::DataFrame;
 
 pub fn calculate_span_alpha(span: f64) -> Result<f64, GlowError> {
     if span < 1.0 {
@@ -45,3 +46,16 @@ pub fn get_calculation_minutes(initial_minute: u32, window: &u32) -> Vec<u32> {
 
 //     result.clone()
 // }
+
+/// Returns the index of the last non-null value at `column`, if the column exists and has any.
+pub fn get_last_valid_index(df: &DataFrame, column: &str) -> Result<Option<usize>, GlowError> {
+    let series = match df.column(column) {
+        Ok(series) => series,
+        Err(_) => return Ok(None),
+    };
+    let last_valid_index = series
+        .is_not_null()
+        .into_iter()
+        .rposition(|is_valid| is_valid.unwrap_or(false));
+    Ok(last_valid_index)
+}
@@ -0,0 +1,311 @@
+use super::IndicatorWrapper;
+use crate::functions::get_last_valid_index;
+use common::{structs::SymbolsPair, traits::indicator::Indicator};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+const NAME: &str = "ADX";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AdxParams {
+    pub period: usize,
+}
+
+impl Default for AdxParams {
+    fn default() -> Self {
+        Self { period: 14 }
+    }
+}
+
+/// Average directional index over anchor's highs, lows and closes, emitted at `{anchor}_adx`,
+/// alongside directional indicators at `{anchor}_plus_di` and `{anchor}_minus_di`.
+///
+/// True range and directional movements are Wilder smoothed over `period` bars, so that DIs are
+/// available from bar `period` on, and ADX, which smooths DX over `period` bars as well, from
+/// bar `2 * period - 1` on. Previous rows are null.
+#[derive(Clone, Debug)]
+pub struct AdxIndicator {
+    pub name: &'static str,
+    pub period: usize,
+    pub high_col: String,
+    pub low_col: String,
+    pub close_col: String,
+    pub adx_col: String,
+    pub plus_di_col: String,
+    pub minus_di_col: String,
+    columns: Vec<(String, DataType)>,
+}
+
+impl AdxIndicator {
+    pub fn new(symbols_pair: SymbolsPair, period: usize) -> Self {
+        let anchor = symbols_pair.anchor;
+        let (_, high_col, low_col, close_col) = anchor.get_ohlc_cols();
+        let adx_col = get_adx_col(anchor.name);
+        let plus_di_col = get_plus_di_col(anchor.name);
+        let minus_di_col = get_minus_di_col(anchor.name);
+        let columns = vec![
+            (adx_col.clone(), DataType::Float64),
+            (plus_di_col.clone(), DataType::Float64),
+            (minus_di_col.clone(), DataType::Float64),
+        ];
+        Self {
+            name: NAME,
+            period,
+            high_col: high_col.to_string(),
+            low_col: low_col.to_string(),
+            close_col: close_col.to_string(),
+            adx_col,
+            plus_di_col,
+            minus_di_col,
+            columns,
+        }
+    }
+
+    /// Keeps ADX and DIs values before `first_pending_index`, calculating the remaining ones.
+    ///
+    /// Emitted DIs are ratios between smoothed values, which can't be recovered from them, so
+    /// smoothed TR/DM and ADX are replayed over the kept rows before being carried forward.
+    fn calculate_columns(
+        &self,
+        df: &DataFrame,
+        first_pending_index: usize,
+    ) -> Result<[Series; 3], GlowError> {
+        let highs = get_source_values(df, &self.high_col)?;
+        let lows = get_source_values(df, &self.low_col)?;
+        let closes = get_source_values(df, &self.close_col)?;
+        let mut adx_values = get_kept_values(df, &self.adx_col, first_pending_index)?;
+        let mut plus_di_values = get_kept_values(df, &self.plus_di_col, first_pending_index)?;
+        let mut minus_di_values = get_kept_values(df, &self.minus_di_col, first_pending_index)?;
+
+        let mut state = AdxState::new(self.period);
+        for index in 0..df.height() {
+            let bar = match (highs[index], lows[index], closes[index]) {
+                (Some(high), Some(low), Some(close)) => Some((high, low, close)),
+                _ => None,
+            };
+            let (adx, plus_di, minus_di) = match bar {
+                Some(bar) => state.next(bar),
+                None => (None, None, None),
+            };
+            if index < first_pending_index {
+                continue;
+            }
+            adx_values.push(adx);
+            plus_di_values.push(plus_di);
+            minus_di_values.push(minus_di);
+        }
+
+        Ok([
+            Series::new(&self.adx_col, adx_values),
+            Series::new(&self.plus_di_col, plus_di_values),
+            Series::new(&self.minus_di_col, minus_di_values),
+        ])
+    }
+}
+
+pub fn get_adx_col(symbol: &str) -> String {
+    format!("{}_adx", symbol)
+}
+
+pub fn get_plus_di_col(symbol: &str) -> String {
+    format!("{}_plus_di", symbol)
+}
+
+pub fn get_minus_di_col(symbol: &str) -> String {
+    format!("{}_minus_di", symbol)
+}
+
+fn get_source_values(df: &DataFrame, column: &str) -> Result<Vec<Option<f64>>, GlowError> {
+    let series = df.column(column)?.cast(&DataType::Float64)?;
+    let values = series.f64()?.into_iter().collect();
+    Ok(values)
+}
+
+fn get_kept_values(
+    df: &DataFrame,
+    column: &str,
+    length: usize,
+) -> Result<Vec<Option<f64>>, GlowError> {
+    if length == 0 {
+        return Ok(vec![]);
+    }
+    let values = df.column(column)?.f64()?.into_iter().take(length).collect();
+    Ok(values)
+}
+
+/// Wilder smoothing of `period` values: their mean while accumulating, then
+/// `previous + (value - previous) / period`.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct WilderAverage {
+    period: usize,
+    count: usize,
+    value: f64,
+}
+
+impl WilderAverage {
+    pub(super) fn new(period: usize) -> Self {
+        Self {
+            period,
+            count: 0,
+            value: 0.0,
+        }
+    }
+
+    /// Resumes smoothing from an already emitted `value`, as if `period` values were accumulated.
+    pub(super) fn resume(period: usize, value: f64) -> Self {
+        Self {
+            period,
+            count: period,
+            value,
+        }
+    }
+
+    pub(super) fn next(&mut self, value: f64) -> Option<f64> {
+        if self.count < self.period {
+            self.count += 1;
+            self.value += (value - self.value) / self.count as f64;
+        } else {
+            self.value += (value - self.value) / self.period as f64;
+        }
+        (self.count == self.period).then_some(self.value)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct AdxState {
+    previous_bar: Option<(f64, f64, f64)>,
+    true_range: WilderAverage,
+    plus_dm: WilderAverage,
+    minus_dm: WilderAverage,
+    dx: WilderAverage,
+}
+
+impl AdxState {
+    fn new(period: usize) -> Self {
+        Self {
+            previous_bar: None,
+            true_range: WilderAverage::new(period),
+            plus_dm: WilderAverage::new(period),
+            minus_dm: WilderAverage::new(period),
+            dx: WilderAverage::new(period),
+        }
+    }
+
+    /// Takes bar's (high, low, close), returning its (ADX, +DI, -DI), if already available.
+    fn next(&mut self, bar: (f64, f64, f64)) -> (Option<f64>, Option<f64>, Option<f64>) {
+        let (high, low, _) = bar;
+        let Some((previous_high, previous_low, previous_close)) = self.previous_bar.replace(bar)
+        else {
+            return (None, None, None);
+        };
+        let true_range = (high - low)
+            .max((high - previous_close).abs())
+            .max((low - previous_close).abs());
+        let up_move = high - previous_high;
+        let down_move = previous_low - low;
+        let plus_dm = if up_move > down_move && up_move > 0.0 {
+            up_move
+        } else {
+            0.0
+        };
+        let minus_dm = if down_move > up_move && down_move > 0.0 {
+            down_move
+        } else {
+            0.0
+        };
+
+        let smoothed_true_range = self.true_range.next(true_range);
+        let smoothed_plus_dm = self.plus_dm.next(plus_dm);
+        let smoothed_minus_dm = self.minus_dm.next(minus_dm);
+        let (Some(smoothed_true_range), Some(smoothed_plus_dm), Some(smoothed_minus_dm)) =
+            (smoothed_true_range, smoothed_plus_dm, smoothed_minus_dm)
+        else {
+            return (None, None, None);
+        };
+
+        let (plus_di, minus_di) = if smoothed_true_range > 0.0 {
+            (
+                100.0 * smoothed_plus_dm / smoothed_true_range,
+                100.0 * smoothed_minus_dm / smoothed_true_range,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+        let di_sum = plus_di + minus_di;
+        let dx = if di_sum > 0.0 {
+            100.0 * (plus_di - minus_di).abs() / di_sum
+        } else {
+            0.0
+        };
+        let adx = self.dx.next(dx);
+
+        (adx, Some(plus_di), Some(minus_di))
+    }
+}
+
+impl Indicator for AdxIndicator {
+    type Params = AdxParams;
+    type Wrapper = IndicatorWrapper;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_indicator_columns(&self) -> &Vec<(String, DataType)> {
+        &self.columns
+    }
+
+    fn set_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        if self.period == 0 {
+            return Err(GlowError::new(
+                String::from("Invalid ADX Period"),
+                String::from("adx period must be at least 1"),
+            ));
+        }
+        let mut df = lf.collect()?;
+        for series in self.calculate_columns(&df, 0)? {
+            df.with_column(series)?;
+        }
+
+        Ok(df.lazy())
+    }
+
+    /// Calculates only rows appended after the last computed ADX, carrying smoothed TR/DM and
+    /// ADX values over them. If no prior value exists, whole columns are recomputed.
+    fn update_indicator_columns(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        let last_valid_index = get_last_valid_index(df, &self.adx_col)?;
+        if last_valid_index.is_none() {
+            let result_df = self.set_indicator_columns(df.clone().lazy())?.collect()?;
+            return Ok(result_df);
+        }
+        let first_pending_index = last_valid_index.unwrap() + 1;
+        if first_pending_index >= df.height() {
+            return Ok(df.clone());
+        }
+
+        let mut result_df = df.clone();
+        for series in self.calculate_columns(df, first_pending_index)? {
+            result_df.with_column(series)?;
+        }
+
+        Ok(result_df)
+    }
+
+    fn get_minimum_klines_for_benchmarking(&self) -> u32 {
+        (self.period * 2) as u32
+    }
+
+    fn patch_params(&self, params: Self::Params) -> Result<Self::Wrapper, GlowError> {
+        let mut updated = self.clone();
+        updated.period = params.period;
+        Ok(updated.into())
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let updated = Self::new(updated_symbols_pair, self.period);
+        Ok(updated.into())
+    }
+}
@@ -0,0 +1,142 @@
+use super::IndicatorWrapper;
+use common::traits::indicator::Indicator;
+use glow_error::GlowError;
+use polars::prelude::*;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+    hash::{Hash, Hasher},
+};
+
+/// In memory cache of indicators' computed columns, keyed by `get_indicator_cache_key`, so
+/// that variants sharing an indicator configuration, as over parameter sweeps where only signal
+/// thresholds change, don't recompute its columns.
+///
+/// Input columns are kept alongside computed ones, so that a hit is only taken if they're
+/// equal to the requested inputs, as different inputs may share the same key.
+///
+/// It's bounded by `max_rows` cached rows, evicting least recently used column sets beyond it.
+#[derive(Debug)]
+pub struct IndicatorCache {
+    max_rows: usize,
+    rows: usize,
+    entries: HashMap<u64, IndicatorCacheEntry>,
+    /// keys, from least to most recently used.
+    recency: VecDeque<u64>,
+}
+
+impl IndicatorCache {
+    pub fn new(max_rows: usize) -> Self {
+        Self {
+            max_rows,
+            rows: 0,
+            entries: HashMap::new(),
+            recency: VecDeque::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Rows of cached column sets.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Gets column set cached at `key`, if any was computed over `inputs_df`, marking it as
+    /// the most recently used.
+    pub fn get(&mut self, key: u64, inputs_df: &DataFrame) -> Option<DataFrame> {
+        let entry = self.entries.get(&key)?;
+        if entry.inputs_df.height() != inputs_df.height()
+            || !entry.inputs_df.frame_equal_missing(inputs_df)
+        {
+            return None;
+        }
+        let columns_df = entry.columns_df.clone();
+        self.touch(key);
+        Some(columns_df)
+    }
+
+    /// Caches `columns_df`, computed over `inputs_df`, at `key`, evicting least recently used
+    /// column sets beyond max rows. Column sets taller than max rows aren't cached.
+    pub fn insert(&mut self, key: u64, inputs_df: DataFrame, columns_df: DataFrame) {
+        let height = columns_df.height();
+        if height > self.max_rows {
+            return;
+        }
+        let entry = IndicatorCacheEntry {
+            inputs_df,
+            columns_df,
+        };
+        self.rows += height;
+        match self.entries.insert(key, entry) {
+            Some(replaced_entry) => {
+                self.rows -= replaced_entry.columns_df.height();
+                self.touch(key);
+            }
+            None => self.recency.push_back(key),
+        }
+        while self.rows > self.max_rows {
+            let Some(evicted_key) = self.recency.pop_front() else {
+                break;
+            };
+            if let Some(evicted_entry) = self.entries.remove(&evicted_key) {
+                self.rows -= evicted_entry.columns_df.height();
+            }
+        }
+    }
+
+    fn touch(&mut self, key: u64) {
+        if let Some(position) = self.recency.iter().position(|other| *other == key) {
+            self.recency.remove(position);
+        }
+        self.recency.push_back(key);
+    }
+}
+
+#[derive(Debug)]
+struct IndicatorCacheEntry {
+    inputs_df: DataFrame,
+    columns_df: DataFrame,
+}
+
+/// Selects, out of `df`, the columns `indicator` reads, see `IndicatorWrapper::get_input_columns`.
+/// Those missing from `df` are left out.
+pub fn get_indicator_inputs_df(
+    indicator: &IndicatorWrapper,
+    df: &DataFrame,
+) -> Result<DataFrame, GlowError> {
+    let schema = df.schema();
+    let input_columns: Vec<String> = indicator
+        .get_input_columns()
+        .into_iter()
+        .filter(|column| schema.contains(column))
+        .collect();
+    Ok(df.select(input_columns)?)
+}
+
+/// Hashes `indicator` type and configuration, params and columns included, alongside a
+/// fingerprint of `df`, the frame its columns are set over: its height and first and last
+/// `start_time`, if any. Values aren't hashed, as cache hits compare inputs anyway.
+pub fn get_indicator_cache_key(
+    indicator: &IndicatorWrapper,
+    df: &DataFrame,
+) -> Result<u64, GlowError> {
+    let mut hasher = DefaultHasher::new();
+    indicator.name().hash(&mut hasher);
+    format!("{:?}", indicator).hash(&mut hasher);
+    df.height().hash(&mut hasher);
+    if let Ok(start_times) = df.column("start_time") {
+        let start_times = start_times.to_physical_repr().cast(&DataType::Int64)?;
+        let start_times = start_times.i64()?;
+        start_times.get(0).hash(&mut hasher);
+        start_times
+            .get(start_times.len().saturating_sub(1))
+            .hash(&mut hasher);
+    }
+    Ok(hasher.finish())
+}
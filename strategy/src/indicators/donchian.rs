@@ -0,0 +1,219 @@
+use super::IndicatorWrapper;
+use crate::functions::get_last_valid_index;
+use common::{structs::SymbolsPair, traits::indicator::Indicator};
+use glow_error::GlowError;
+use polars::prelude::*;
+use std::collections::VecDeque;
+
+const NAME: &str = "Donchian";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DonchianParams {
+    pub lookback: usize,
+}
+
+impl Default for DonchianParams {
+    fn default() -> Self {
+        Self { lookback: 20 }
+    }
+}
+
+/// Donchian channel over anchor's highs and lows: highest high and lowest low of the last
+/// `lookback` bars, current one included, emitted at `{anchor}_donchian_high` and
+/// `{anchor}_donchian_low`.
+///
+/// Rows lacking a full window are null.
+#[derive(Clone, Debug)]
+pub struct DonchianIndicator {
+    pub name: &'static str,
+    pub lookback: usize,
+    pub high_col: String,
+    pub low_col: String,
+    pub upper_col: String,
+    pub lower_col: String,
+    columns: Vec<(String, DataType)>,
+}
+
+impl DonchianIndicator {
+    pub fn new(symbols_pair: SymbolsPair, lookback: usize) -> Self {
+        let anchor = symbols_pair.anchor;
+        let upper_col = get_donchian_high_col(anchor.name);
+        let lower_col = get_donchian_low_col(anchor.name);
+        let columns = vec![
+            (upper_col.clone(), DataType::Float64),
+            (lower_col.clone(), DataType::Float64),
+        ];
+        Self {
+            name: NAME,
+            lookback,
+            high_col: anchor.get_high_col().to_string(),
+            low_col: anchor.get_low_col().to_string(),
+            upper_col,
+            lower_col,
+            columns,
+        }
+    }
+
+    fn get_rolling_options(&self) -> RollingOptions {
+        RollingOptions {
+            window_size: Duration::parse(&format!("{}i", self.lookback)),
+            min_periods: self.lookback,
+            center: false,
+            by: None,
+            weights: None,
+            closed_window: None,
+            fn_params: None,
+        }
+    }
+
+    /// Extends `output_col` values from `first_pending_index` on, keeping a monotonic deque
+    /// of window candidates, so that the bar leaving the window is dropped as soon as it does.
+    fn update_rolling_extreme(
+        &self,
+        df: &DataFrame,
+        source_col: &str,
+        output_col: &str,
+        first_pending_index: usize,
+        is_max: bool,
+    ) -> Result<Series, GlowError> {
+        let source_series = df.column(source_col)?.cast(&DataType::Float64)?;
+        let source_values: Vec<Option<f64>> = source_series.f64()?.into_iter().collect();
+        let mut updated_values: Vec<Option<f64>> = df
+            .column(output_col)?
+            .f64()?
+            .into_iter()
+            .take(first_pending_index)
+            .collect();
+
+        let offset = (first_pending_index + 1).saturating_sub(self.lookback);
+        let mut candidates: VecDeque<(usize, f64)> = VecDeque::new();
+        let mut last_null_index: Option<usize> = None;
+        for (index, value) in source_values.iter().enumerate().skip(offset) {
+            while candidates
+                .front()
+                .is_some_and(|(candidate_index, _)| candidate_index + self.lookback <= index)
+            {
+                candidates.pop_front();
+            }
+            match value {
+                Some(value) => {
+                    while candidates.back().is_some_and(|(_, candidate)| {
+                        if is_max {
+                            candidate <= value
+                        } else {
+                            candidate >= value
+                        }
+                    }) {
+                        candidates.pop_back();
+                    }
+                    candidates.push_back((index, *value));
+                }
+                None => last_null_index = Some(index),
+            }
+            if index < first_pending_index {
+                continue;
+            }
+            let has_full_window = index + 1 >= self.lookback
+                && last_null_index.is_none_or(|null_index| null_index + self.lookback <= index);
+            let extreme = candidates.front().map(|(_, candidate)| *candidate);
+            updated_values.push(extreme.filter(|_| has_full_window));
+        }
+
+        Ok(Series::new(output_col, updated_values))
+    }
+}
+
+pub fn get_donchian_high_col(symbol: &str) -> String {
+    format!("{}_donchian_high", symbol)
+}
+
+pub fn get_donchian_low_col(symbol: &str) -> String {
+    format!("{}_donchian_low", symbol)
+}
+
+impl Indicator for DonchianIndicator {
+    type Params = DonchianParams;
+    type Wrapper = IndicatorWrapper;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_indicator_columns(&self) -> &Vec<(String, DataType)> {
+        &self.columns
+    }
+
+    fn set_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        if self.lookback == 0 {
+            return Err(GlowError::new(
+                String::from("Invalid Donchian Lookback"),
+                String::from("donchian lookback must be at least 1"),
+            ));
+        }
+        let lf = lf.with_columns([
+            col(&self.high_col)
+                .cast(DataType::Float64)
+                .rolling_max(self.get_rolling_options())
+                .alias(&self.upper_col),
+            col(&self.low_col)
+                .cast(DataType::Float64)
+                .rolling_min(self.get_rolling_options())
+                .alias(&self.lower_col),
+        ]);
+
+        Ok(lf)
+    }
+
+    /// Calculates only rows appended after the last computed channel, sliding the window over
+    /// the `lookback` bars preceding them. If no prior value exists, whole columns are recomputed.
+    fn update_indicator_columns(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        let last_valid_index = get_last_valid_index(df, &self.upper_col)?;
+        if last_valid_index.is_none() {
+            let result_df = self.set_indicator_columns(df.clone().lazy())?.collect()?;
+            return Ok(result_df);
+        }
+        let first_pending_index = last_valid_index.unwrap() + 1;
+        if first_pending_index >= df.height() {
+            return Ok(df.clone());
+        }
+
+        let upper_series = self.update_rolling_extreme(
+            df,
+            &self.high_col,
+            &self.upper_col,
+            first_pending_index,
+            true,
+        )?;
+        let lower_series = self.update_rolling_extreme(
+            df,
+            &self.low_col,
+            &self.lower_col,
+            first_pending_index,
+            false,
+        )?;
+
+        let mut result_df = df.clone();
+        result_df.with_column(upper_series)?;
+        result_df.with_column(lower_series)?;
+
+        Ok(result_df)
+    }
+
+    fn get_minimum_klines_for_benchmarking(&self) -> u32 {
+        self.lookback as u32
+    }
+
+    fn patch_params(&self, params: Self::Params) -> Result<Self::Wrapper, GlowError> {
+        let mut updated = self.clone();
+        updated.lookback = params.lookback;
+        Ok(updated.into())
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let updated = Self::new(updated_symbols_pair, self.lookback);
+        Ok(updated.into())
+    }
+}
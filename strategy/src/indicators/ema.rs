@@ -0,0 +1,184 @@
+use super::IndicatorWrapper;
+use crate::functions::{calculate_span_alpha, get_last_valid_index};
+use common::{structs::SymbolsPair, traits::indicator::Indicator};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+const NAME: &str = "EMA";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EmaParams {
+    pub period: usize,
+}
+
+impl Default for EmaParams {
+    fn default() -> Self {
+        Self { period: 20 }
+    }
+}
+
+/// Exponential moving average over an arbitrary numeric `source_col`.
+///
+/// Columns may be named after the strategy symbols (e.g. `BTCUSDT_close` -> `BTCUSDT_fast_ema`),
+/// in which case `patch_symbols_pair` renames them accordingly.
+///
+/// Unless `ignore_nulls` is set, null source values (e.g. gap bars) still decay previous value's
+/// weight, as if time had passed over them.
+#[derive(Clone, Debug)]
+pub struct EmaIndicator {
+    pub name: &'static str,
+    pub period: usize,
+    pub source_col: String,
+    pub output_col: String,
+    pub ignore_nulls: bool,
+    symbols_pair: SymbolsPair,
+    columns: Vec<(String, DataType)>,
+}
+
+impl EmaIndicator {
+    pub fn new(
+        symbols_pair: SymbolsPair,
+        period: usize,
+        source_col: String,
+        output_col: String,
+        ignore_nulls: bool,
+    ) -> Self {
+        let columns = vec![(output_col.clone(), DataType::Float64)];
+        Self {
+            name: NAME,
+            period,
+            source_col,
+            output_col,
+            ignore_nulls,
+            symbols_pair,
+            columns,
+        }
+    }
+
+    /// Builds an EMA over anchor's close, named `{anchor}_{suffix}` (e.g. `fast_ema`), which
+    /// doesn't ignore null closes
+    pub fn from_anchor_close(symbols_pair: SymbolsPair, period: usize, suffix: &str) -> Self {
+        let source_col = symbols_pair.anchor.get_close_col().to_string();
+        let output_col = format!("{}_{}", symbols_pair.anchor.name, suffix);
+        Self::new(symbols_pair, period, source_col, output_col, false)
+    }
+
+    fn get_alpha(&self) -> Result<f64, GlowError> {
+        calculate_span_alpha(self.period as f64)
+    }
+}
+
+impl Indicator for EmaIndicator {
+    type Params = EmaParams;
+    type Wrapper = IndicatorWrapper;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_indicator_columns(&self) -> &Vec<(String, DataType)> {
+        &self.columns
+    }
+
+    fn set_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        let opts = EWMOptions {
+            alpha: self.get_alpha()?,
+            adjust: false,
+            bias: false,
+            min_periods: 1,
+            ignore_nulls: self.ignore_nulls,
+        };
+
+        let lf = lf.with_column(
+            col(&self.source_col)
+                .cast(DataType::Float64)
+                .ewm_mean(opts)
+                .alias(&self.output_col),
+        );
+
+        Ok(lf)
+    }
+
+    /// Seeds from the last computed EMA value, so only rows appended after it are calculated.
+    /// If no prior value exists, the whole column is recomputed. As in `ewm_mean`, null source
+    /// rows carry previous value over.
+    fn update_indicator_columns(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        let last_valid_index = get_last_valid_index(df, &self.output_col)?;
+        if last_valid_index.is_none() {
+            let result_df = self.set_indicator_columns(df.clone().lazy())?.collect()?;
+            return Ok(result_df);
+        }
+        let last_valid_index = last_valid_index.unwrap();
+
+        let alpha = self.get_alpha()?;
+        let source_series = df.column(&self.source_col)?.cast(&DataType::Float64)?;
+        let source_values = source_series.f64()?;
+        let output_values = df.column(&self.output_col)?.f64()?;
+
+        let mut updated_values: Vec<Option<f64>> = output_values
+            .into_iter()
+            .take(last_valid_index + 1)
+            .collect();
+        let mut previous_ema = updated_values[last_valid_index].unwrap();
+        let decay = 1.0 - alpha;
+        // nulls since last source value keep decaying previous value's weight
+        let mut previous_weight = if self.ignore_nulls {
+            1.0
+        } else {
+            let trailing_nulls = source_values
+                .into_iter()
+                .take(last_valid_index + 1)
+                .rev()
+                .take_while(|value| value.is_none())
+                .count();
+            decay.powi(trailing_nulls as i32)
+        };
+
+        for value in source_values.into_iter().skip(last_valid_index + 1) {
+            match value {
+                Some(value) => {
+                    previous_weight *= decay;
+                    previous_ema = (previous_weight * previous_ema + alpha * value)
+                        / (previous_weight + alpha);
+                    previous_weight = 1.0;
+                }
+                None if !self.ignore_nulls => previous_weight *= decay,
+                None => {}
+            }
+            updated_values.push(Some(previous_ema));
+        }
+
+        let mut result_df = df.clone();
+        result_df.with_column(Series::new(&self.output_col, updated_values))?;
+
+        Ok(result_df)
+    }
+
+    fn get_minimum_klines_for_benchmarking(&self) -> u32 {
+        self.period as u32
+    }
+
+    fn patch_params(&self, params: Self::Params) -> Result<Self::Wrapper, GlowError> {
+        let mut updated = self.clone();
+        updated.period = params.period;
+        Ok(updated.into())
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let previous_anchor = self.symbols_pair.anchor.name;
+        let updated_anchor = updated_symbols_pair.anchor.name;
+        let source_col = self.source_col.replacen(previous_anchor, updated_anchor, 1);
+        let output_col = self.output_col.replacen(previous_anchor, updated_anchor, 1);
+        let updated = Self::new(
+            updated_symbols_pair,
+            self.period,
+            source_col,
+            output_col,
+            self.ignore_nulls,
+        );
+        Ok(updated.into())
+    }
+}
@@ -0,0 +1,204 @@
+use super::IndicatorWrapper;
+use crate::functions::get_last_valid_index;
+use common::{structs::SymbolsPair, traits::indicator::Indicator};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+const NAME: &str = "HeikinAshi";
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct HeikinAshiParams {}
+
+/// Heikin-Ashi candles over anchor's OHLC, emitted at `{anchor}_ha_open`, `{anchor}_ha_high`,
+/// `{anchor}_ha_low` and `{anchor}_ha_close`.
+///
+/// HA close is the mean of bar's OHLC, while HA open is the mean of previous HA open and close,
+/// seeded by the mean of first bar's open and close. HA high and low extend bar's high and low
+/// to HA open and close. Bars with missing OHLC are null, and don't break the recursion.
+#[derive(Clone, Debug)]
+pub struct HeikinAshiIndicator {
+    pub name: &'static str,
+    pub open_col: String,
+    pub high_col: String,
+    pub low_col: String,
+    pub close_col: String,
+    pub ha_open_col: String,
+    pub ha_high_col: String,
+    pub ha_low_col: String,
+    pub ha_close_col: String,
+    columns: Vec<(String, DataType)>,
+}
+
+impl HeikinAshiIndicator {
+    pub fn new(symbols_pair: SymbolsPair) -> Self {
+        let anchor = symbols_pair.anchor;
+        let (open_col, high_col, low_col, close_col) = anchor.get_ohlc_cols();
+        let ha_open_col = get_ha_open_col(anchor.name);
+        let ha_high_col = get_ha_high_col(anchor.name);
+        let ha_low_col = get_ha_low_col(anchor.name);
+        let ha_close_col = get_ha_close_col(anchor.name);
+        let columns = vec![
+            (ha_open_col.clone(), DataType::Float64),
+            (ha_high_col.clone(), DataType::Float64),
+            (ha_low_col.clone(), DataType::Float64),
+            (ha_close_col.clone(), DataType::Float64),
+        ];
+        Self {
+            name: NAME,
+            open_col: open_col.to_string(),
+            high_col: high_col.to_string(),
+            low_col: low_col.to_string(),
+            close_col: close_col.to_string(),
+            ha_open_col,
+            ha_high_col,
+            ha_low_col,
+            ha_close_col,
+            columns,
+        }
+    }
+
+    /// Keeps HA values before `first_pending_index`, calculating the remaining ones from the
+    /// last kept HA open and close.
+    fn calculate_columns(
+        &self,
+        df: &DataFrame,
+        first_pending_index: usize,
+    ) -> Result<[Series; 4], GlowError> {
+        let opens = get_source_values(df, &self.open_col)?;
+        let highs = get_source_values(df, &self.high_col)?;
+        let lows = get_source_values(df, &self.low_col)?;
+        let closes = get_source_values(df, &self.close_col)?;
+        let mut ha_open_values = get_kept_values(df, &self.ha_open_col, first_pending_index)?;
+        let mut ha_high_values = get_kept_values(df, &self.ha_high_col, first_pending_index)?;
+        let mut ha_low_values = get_kept_values(df, &self.ha_low_col, first_pending_index)?;
+        let mut ha_close_values = get_kept_values(df, &self.ha_close_col, first_pending_index)?;
+
+        let mut previous_candle = ha_open_values
+            .iter()
+            .zip(ha_close_values.iter())
+            .rev()
+            .find_map(|(ha_open, ha_close)| ha_open.zip(*ha_close));
+        for index in first_pending_index..df.height() {
+            let (Some(open), Some(high), Some(low), Some(close)) =
+                (opens[index], highs[index], lows[index], closes[index])
+            else {
+                ha_open_values.push(None);
+                ha_high_values.push(None);
+                ha_low_values.push(None);
+                ha_close_values.push(None);
+                continue;
+            };
+            let ha_close = (open + high + low + close) / 4.0;
+            let ha_open = match previous_candle {
+                Some((previous_ha_open, previous_ha_close)) => {
+                    (previous_ha_open + previous_ha_close) / 2.0
+                }
+                None => (open + close) / 2.0,
+            };
+            ha_open_values.push(Some(ha_open));
+            ha_high_values.push(Some(high.max(ha_open).max(ha_close)));
+            ha_low_values.push(Some(low.min(ha_open).min(ha_close)));
+            ha_close_values.push(Some(ha_close));
+            previous_candle = Some((ha_open, ha_close));
+        }
+
+        Ok([
+            Series::new(&self.ha_open_col, ha_open_values),
+            Series::new(&self.ha_high_col, ha_high_values),
+            Series::new(&self.ha_low_col, ha_low_values),
+            Series::new(&self.ha_close_col, ha_close_values),
+        ])
+    }
+}
+
+pub fn get_ha_open_col(symbol: &str) -> String {
+    format!("{}_ha_open", symbol)
+}
+
+pub fn get_ha_high_col(symbol: &str) -> String {
+    format!("{}_ha_high", symbol)
+}
+
+pub fn get_ha_low_col(symbol: &str) -> String {
+    format!("{}_ha_low", symbol)
+}
+
+pub fn get_ha_close_col(symbol: &str) -> String {
+    format!("{}_ha_close", symbol)
+}
+
+fn get_source_values(df: &DataFrame, column: &str) -> Result<Vec<Option<f64>>, GlowError> {
+    let series = df.column(column)?.cast(&DataType::Float64)?;
+    let values = series.f64()?.into_iter().collect();
+    Ok(values)
+}
+
+fn get_kept_values(
+    df: &DataFrame,
+    column: &str,
+    length: usize,
+) -> Result<Vec<Option<f64>>, GlowError> {
+    if length == 0 {
+        return Ok(vec![]);
+    }
+    let values = df.column(column)?.f64()?.into_iter().take(length).collect();
+    Ok(values)
+}
+
+impl Indicator for HeikinAshiIndicator {
+    type Params = HeikinAshiParams;
+    type Wrapper = IndicatorWrapper;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_indicator_columns(&self) -> &Vec<(String, DataType)> {
+        &self.columns
+    }
+
+    fn set_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        let mut df = lf.collect()?;
+        for series in self.calculate_columns(&df, 0)? {
+            df.with_column(series)?;
+        }
+
+        Ok(df.lazy())
+    }
+
+    /// Carries the last computed HA open and close forward, so only rows appended after them are
+    /// calculated. If no prior value exists, whole columns are recomputed.
+    fn update_indicator_columns(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        let last_valid_index = get_last_valid_index(df, &self.ha_open_col)?;
+        if last_valid_index.is_none() {
+            let result_df = self.set_indicator_columns(df.clone().lazy())?.collect()?;
+            return Ok(result_df);
+        }
+        let first_pending_index = last_valid_index.unwrap() + 1;
+        if first_pending_index >= df.height() {
+            return Ok(df.clone());
+        }
+
+        let mut result_df = df.clone();
+        for series in self.calculate_columns(df, first_pending_index)? {
+            result_df.with_column(series)?;
+        }
+
+        Ok(result_df)
+    }
+
+    fn get_minimum_klines_for_benchmarking(&self) -> u32 {
+        2
+    }
+
+    fn patch_params(&self, _params: Self::Params) -> Result<Self::Wrapper, GlowError> {
+        Ok(self.clone().into())
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        Ok(Self::new(updated_symbols_pair).into())
+    }
+}
@@ -0,0 +1,292 @@
+use super::IndicatorWrapper;
+use crate::functions::get_last_valid_index;
+use common::{enums::granularity::Granularity, structs::SymbolsPair, traits::indicator::Indicator};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+const NAME: &str = "Higher Timeframe";
+const HTF_START_TIME_COL: &str = "htf_start_time";
+
+#[derive(Clone, Copy, Debug)]
+pub struct HigherTimeframeParams {
+    pub granularity: Granularity,
+}
+
+/// Runs `indicator` over klines resampled from `base_granularity` to the coarser `granularity`,
+/// emitting each of its columns at `{column}_{granularity in minutes}m` (e.g. `BTCUSDT_ema_60m`).
+///
+/// Klines are bucketed by `start_time`, aggregating each symbol's open, high, low, close and, if
+/// present, volume. A higher timeframe value only becomes available at the base bar closing its
+/// bucket, being carried over following base bars until the next bucket closes, so no base bar
+/// sees a value from a bucket still forming.
+#[derive(Clone, Debug)]
+pub struct HigherTimeframeIndicator {
+    pub name: &'static str,
+    pub base_granularity: Granularity,
+    pub granularity: Granularity,
+    pub indicator: Box<IndicatorWrapper>,
+    symbols_pair: SymbolsPair,
+    columns: Vec<(String, DataType)>,
+}
+
+impl HigherTimeframeIndicator {
+    pub fn new(
+        symbols_pair: SymbolsPair,
+        base_granularity: Granularity,
+        granularity: Granularity,
+        indicator: IndicatorWrapper,
+    ) -> Self {
+        let columns = indicator
+            .get_indicator_columns()
+            .iter()
+            .map(|(column, dtype)| (get_higher_timeframe_col(column, granularity), dtype.clone()))
+            .collect();
+        Self {
+            name: NAME,
+            base_granularity,
+            granularity,
+            indicator: Box::new(indicator),
+            symbols_pair,
+            columns,
+        }
+    }
+
+    fn get_bucket_duration_ms(&self) -> Result<i64, GlowError> {
+        let base_duration_ms = self.base_granularity.get_granularity_in_secs() as i64 * 1000;
+        let bucket_duration_ms = self.granularity.get_granularity_in_secs() as i64 * 1000;
+        if bucket_duration_ms <= base_duration_ms || bucket_duration_ms % base_duration_ms != 0 {
+            let error = format!(
+                "{:?} granularity isn't a coarser multiple of {:?} base granularity",
+                self.granularity, self.base_granularity
+            );
+            return Err(GlowError::new(
+                String::from("Invalid Higher Timeframe Granularity"),
+                error,
+            ));
+        }
+        Ok(bucket_duration_ms)
+    }
+
+    /// Columns read while resampling, i.e. `start_time` along with every symbol's OHLC and volume.
+    pub fn get_input_columns(&self) -> Vec<String> {
+        let mut input_columns = vec![String::from("start_time")];
+        for symbol in self.symbols_pair.get_unique_symbols() {
+            let (open_col, high_col, low_col, close_col) = symbol.get_ohlc_cols();
+            input_columns.extend([open_col, high_col, low_col, close_col].map(String::from));
+            input_columns.push(format!("{}_volume", symbol.name));
+        }
+        input_columns
+    }
+
+    fn get_start_timestamps(df: &DataFrame) -> Result<Vec<Option<i64>>, GlowError> {
+        let start_timestamps = df
+            .column("start_time")?
+            .timestamp(TimeUnit::Milliseconds)?
+            .into_iter()
+            .collect();
+        Ok(start_timestamps)
+    }
+
+    /// Gets, for each base bar, start of the latest bucket closed by the end of that bar.
+    fn get_available_bucket_starts(&self, df: &DataFrame) -> Result<Vec<Option<i64>>, GlowError> {
+        let bucket_duration_ms = self.get_bucket_duration_ms()?;
+        let base_duration_ms = self.base_granularity.get_granularity_in_secs() as i64 * 1000;
+        let bucket_starts = Self::get_start_timestamps(df)?
+            .into_iter()
+            .map(|start_timestamp| {
+                start_timestamp.map(|start_timestamp| {
+                    let end_timestamp = start_timestamp + base_duration_ms;
+                    end_timestamp.div_euclid(bucket_duration_ms) * bucket_duration_ms
+                        - bucket_duration_ms
+                })
+            })
+            .collect();
+        Ok(bucket_starts)
+    }
+
+    /// Aggregates `df` klines into `granularity` buckets, keyed by their start at `htf_start_time`.
+    fn resample(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        let bucket_duration_ms = self.get_bucket_duration_ms()?;
+        let bucket_starts: Vec<Option<i64>> = Self::get_start_timestamps(df)?
+            .into_iter()
+            .map(|start_timestamp| {
+                start_timestamp.map(|start_timestamp| {
+                    start_timestamp.div_euclid(bucket_duration_ms) * bucket_duration_ms
+                })
+            })
+            .collect();
+
+        let mut agg_expressions = vec![];
+        for symbol in self.symbols_pair.get_unique_symbols() {
+            let (open_col, high_col, low_col, close_col) = symbol.get_ohlc_cols();
+            agg_expressions.push(col(open_col).drop_nulls().first().alias(open_col));
+            agg_expressions.push(col(high_col).max().alias(high_col));
+            agg_expressions.push(col(low_col).min().alias(low_col));
+            agg_expressions.push(col(close_col).drop_nulls().last().alias(close_col));
+            let volume_col = format!("{}_volume", symbol.name);
+            if df.schema().contains(&volume_col) {
+                agg_expressions.push(col(&volume_col).sum().alias(&volume_col));
+            }
+        }
+
+        let mut base_df = df.clone();
+        base_df.with_column(Series::new(HTF_START_TIME_COL, bucket_starts))?;
+        let resampled_df = base_df
+            .lazy()
+            .filter(col(HTF_START_TIME_COL).is_not_null())
+            .group_by_stable([col(HTF_START_TIME_COL)])
+            .agg(agg_expressions)
+            .sort(HTF_START_TIME_COL, SortOptions::default())
+            .with_column(
+                col(HTF_START_TIME_COL)
+                    .cast(DataType::Datetime(TimeUnit::Milliseconds, None))
+                    .alias("start_time"),
+            )
+            .collect()?;
+
+        Ok(resampled_df)
+    }
+
+    /// Calculates higher timeframe columns over whole `df`, each aligned to its base bars.
+    fn calculate_columns(&self, df: &DataFrame) -> Result<Vec<Series>, GlowError> {
+        let resampled_df = self.resample(df)?;
+        let indicator_df = self
+            .indicator
+            .set_indicator_columns(resampled_df.lazy())?
+            .collect()?;
+        let mut selected_cols = vec![col(HTF_START_TIME_COL)];
+        for ((indicator_col, _), (output_col, _)) in self
+            .indicator
+            .get_indicator_columns()
+            .iter()
+            .zip(self.columns.iter())
+        {
+            selected_cols.push(col(indicator_col).alias(output_col));
+        }
+        let indicator_lf = indicator_df.lazy().select(selected_cols);
+
+        let available_bucket_starts = self.get_available_bucket_starts(df)?;
+        let aligned_df = DataFrame::new(vec![Series::new(
+            HTF_START_TIME_COL,
+            available_bucket_starts,
+        )])?
+        .lazy()
+        .left_join(indicator_lf, HTF_START_TIME_COL, HTF_START_TIME_COL)
+        .collect()?;
+
+        let mut series = vec![];
+        for (output_col, dtype) in &self.columns {
+            series.push(aligned_df.column(output_col)?.cast(dtype)?);
+        }
+        Ok(series)
+    }
+}
+
+pub fn get_higher_timeframe_col(column: &str, granularity: Granularity) -> String {
+    format!("{}_{}m", column, granularity.get_granularity_in_mins())
+}
+
+impl Indicator for HigherTimeframeIndicator {
+    type Params = HigherTimeframeParams;
+    type Wrapper = IndicatorWrapper;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_indicator_columns(&self) -> &Vec<(String, DataType)> {
+        &self.columns
+    }
+
+    fn set_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        let mut df = lf.collect()?;
+        for series in self.calculate_columns(&df)? {
+            df.with_column(series)?;
+        }
+
+        Ok(df.lazy())
+    }
+
+    /// Carries last known higher timeframe values over rows appended within the same bucket.
+    /// Once an appended row closes a new bucket, columns are recomputed, keeping prior values.
+    fn update_indicator_columns(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        let Some((first_output_col, _)) = self.columns.first() else {
+            return Ok(df.clone());
+        };
+        let last_valid_index = get_last_valid_index(df, first_output_col)?;
+        if last_valid_index.is_none() {
+            let result_df = self.set_indicator_columns(df.clone().lazy())?.collect()?;
+            return Ok(result_df);
+        }
+        let last_valid_index = last_valid_index.unwrap();
+        let first_pending_index = last_valid_index + 1;
+        if first_pending_index >= df.height() {
+            return Ok(df.clone());
+        }
+
+        let available_bucket_starts = self.get_available_bucket_starts(df)?;
+        let last_bucket_start = available_bucket_starts[last_valid_index];
+        let is_same_bucket = available_bucket_starts
+            .iter()
+            .skip(first_pending_index)
+            .all(|bucket_start| bucket_start == &last_bucket_start);
+
+        let mut result_df = df.clone();
+        if is_same_bucket {
+            let pending_rows = df.height() - first_pending_index;
+            for (output_col, _) in &self.columns {
+                let series = df.column(output_col)?;
+                let carried_values = series
+                    .slice(last_valid_index as i64, 1)
+                    .new_from_index(0, pending_rows);
+                let mut updated_series = series.slice(0, first_pending_index);
+                updated_series.append(&carried_values)?;
+                result_df.with_column(updated_series)?;
+            }
+            return Ok(result_df);
+        }
+
+        for series in self.calculate_columns(df)? {
+            let mut updated_series = df.column(series.name())?.slice(0, first_pending_index);
+            updated_series.append(&series.slice(
+                first_pending_index as i64,
+                df.height() - first_pending_index,
+            ))?;
+            result_df.with_column(updated_series)?;
+        }
+
+        Ok(result_df)
+    }
+
+    /// Counts base klines spanning as many buckets as child indicator requires, plus the one
+    /// still forming.
+    fn get_minimum_klines_for_benchmarking(&self) -> u32 {
+        let klines_per_bucket = self.granularity.get_granularity_in_secs()
+            / self.base_granularity.get_granularity_in_secs().max(1);
+        (self.indicator.get_minimum_klines_for_benchmarking() + 1) * klines_per_bucket
+    }
+
+    fn patch_params(&self, params: Self::Params) -> Result<Self::Wrapper, GlowError> {
+        let updated = Self::new(
+            self.symbols_pair,
+            self.base_granularity,
+            params.granularity,
+            *self.indicator.clone(),
+        );
+        Ok(updated.into())
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let indicator = self.indicator.patch_symbols_pair(updated_symbols_pair)?;
+        let updated = Self::new(
+            updated_symbols_pair,
+            self.base_granularity,
+            self.granularity,
+            indicator,
+        );
+        Ok(updated.into())
+    }
+}
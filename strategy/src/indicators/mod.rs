@@ -0,0 +1,434 @@
+use crate::r#static::INDICATORS_CACHE;
+use common::{structs::SymbolsPair, traits::indicator::Indicator};
+use glow_error::GlowError;
+use polars::prelude::*;
+pub mod adx;
+pub mod cache;
+pub mod donchian;
+pub mod ema;
+pub mod heikin_ashi;
+pub mod higher_timeframe;
+pub mod obv;
+pub mod order_book_imbalance;
+pub mod realized_volatility;
+pub mod rolling_correlation;
+pub mod rsi;
+pub mod smoothing;
+pub mod spread;
+pub mod supertrend;
+pub mod zscore;
+use adx::{AdxIndicator, AdxParams};
+use cache::{get_indicator_cache_key, get_indicator_inputs_df};
+use donchian::{DonchianIndicator, DonchianParams};
+use ema::{EmaIndicator, EmaParams};
+use heikin_ashi::{HeikinAshiIndicator, HeikinAshiParams};
+use higher_timeframe::{HigherTimeframeIndicator, HigherTimeframeParams};
+use obv::{ObvIndicator, ObvParams};
+use order_book_imbalance::{OrderBookImbalanceIndicator, OrderBookImbalanceParams};
+use realized_volatility::{RealizedVolatilityIndicator, RealizedVolatilityParams};
+use rolling_correlation::{RollingCorrelationIndicator, RollingCorrelationParams};
+use rsi::{RsiIndicator, RsiParams};
+use smoothing::{SmoothingParams, SmoothingPreIndicator};
+use spread::{SpreadIndicator, SpreadParams};
+use supertrend::{SupertrendIndicator, SupertrendParams};
+use zscore::{ZScoreIndicator, ZScoreParams};
+#[cfg(test)]
+mod tests;
+
+#[derive(Clone, Debug)]
+pub enum IndicatorWrapper {
+    Adx(AdxIndicator),
+    Donchian(DonchianIndicator),
+    Ema(EmaIndicator),
+    HeikinAshi(HeikinAshiIndicator),
+    HigherTimeframe(HigherTimeframeIndicator),
+    Obv(ObvIndicator),
+    OrderBookImbalance(OrderBookImbalanceIndicator),
+    RealizedVolatility(RealizedVolatilityIndicator),
+    RollingCorrelation(RollingCorrelationIndicator),
+    Rsi(RsiIndicator),
+    Smoothing(SmoothingPreIndicator),
+    Spread(SpreadIndicator),
+    Supertrend(SupertrendIndicator),
+    ZScore(ZScoreIndicator),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum IndicatorParamsWrapper {
+    Adx(AdxParams),
+    Donchian(DonchianParams),
+    Ema(EmaParams),
+    HeikinAshi(HeikinAshiParams),
+    HigherTimeframe(HigherTimeframeParams),
+    Obv(ObvParams),
+    OrderBookImbalance(OrderBookImbalanceParams),
+    RealizedVolatility(RealizedVolatilityParams),
+    RollingCorrelation(RollingCorrelationParams),
+    Rsi(RsiParams),
+    Smoothing(SmoothingParams),
+    Spread(SpreadParams),
+    Supertrend(SupertrendParams),
+    ZScore(ZScoreParams),
+}
+
+impl IndicatorWrapper {
+    /// Whether indicator only produces values live, from data klines don't carry, such as
+    /// order book depth, being null over benchmarks.
+    pub fn is_live_only(&self) -> bool {
+        match self {
+            Self::OrderBookImbalance(_) => true,
+            Self::HigherTimeframe(indicator) => indicator.indicator.is_live_only(),
+            _ => false,
+        }
+    }
+
+    /// Columns indicator reads in order to set its own ones.
+    pub fn get_input_columns(&self) -> Vec<String> {
+        match self {
+            Self::Adx(indicator) => vec![
+                indicator.high_col.clone(),
+                indicator.low_col.clone(),
+                indicator.close_col.clone(),
+            ],
+            Self::Donchian(indicator) => {
+                vec![indicator.high_col.clone(), indicator.low_col.clone()]
+            }
+            Self::Ema(indicator) => vec![indicator.source_col.clone()],
+            Self::HeikinAshi(indicator) => vec![
+                indicator.open_col.clone(),
+                indicator.high_col.clone(),
+                indicator.low_col.clone(),
+                indicator.close_col.clone(),
+            ],
+            Self::HigherTimeframe(indicator) => indicator.get_input_columns(),
+            Self::Obv(indicator) => vec![indicator.close_col.clone(), indicator.volume_col.clone()],
+            Self::OrderBookImbalance(_) => vec![],
+            Self::RealizedVolatility(indicator) => vec![indicator.close_col.clone()],
+            Self::RollingCorrelation(indicator) => vec![
+                indicator.anchor_close_col.clone(),
+                indicator.traded_close_col.clone(),
+            ],
+            Self::Rsi(indicator) => vec![indicator.close_col.clone()],
+            Self::Smoothing(indicator) => vec![indicator.source_col.clone()],
+            Self::Spread(indicator) => vec![
+                indicator.anchor_close_col.clone(),
+                indicator.traded_close_col.clone(),
+            ],
+            Self::Supertrend(indicator) => vec![
+                indicator.high_col.clone(),
+                indicator.low_col.clone(),
+                indicator.close_col.clone(),
+            ],
+            Self::ZScore(indicator) => vec![indicator.source_col.clone()],
+        }
+    }
+
+    /// Sets indicator columns as `set_indicator_columns` does, but takes them from
+    /// `INDICATORS_CACHE` if this same indicator configuration was already set over the same
+    /// input columns. Live only indicators aren't cached, as they read more than `lf`.
+    pub fn set_cached_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        if self.is_live_only() {
+            return self.set_indicator_columns(lf);
+        }
+        let mut df = lf.collect()?;
+        let inputs_df = get_indicator_inputs_df(self, &df)?;
+        let key = get_indicator_cache_key(self, &df)?;
+        let cached_columns_df = INDICATORS_CACHE.lock()?.get(key, &inputs_df);
+        let columns_df = match cached_columns_df {
+            Some(columns_df) => columns_df,
+            None => {
+                let result_df = self.set_indicator_columns(df.clone().lazy())?.collect()?;
+                let columns: Vec<&str> = self
+                    .get_indicator_columns()
+                    .iter()
+                    .map(|(column, _)| column.as_str())
+                    .collect();
+                let columns_df = result_df.select(columns)?;
+                INDICATORS_CACHE
+                    .lock()?
+                    .insert(key, inputs_df, columns_df.clone());
+                columns_df
+            }
+        };
+        for series in columns_df.get_columns() {
+            df.with_column(series.clone())?;
+        }
+
+        Ok(df.lazy())
+    }
+}
+
+/// Indicators are defined as such:
+/// They provide data in order to signals be set.
+impl Indicator for IndicatorWrapper {
+    type Params = IndicatorParamsWrapper;
+    type Wrapper = Self;
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Adx(indicator) => indicator.name(),
+            Self::Donchian(indicator) => indicator.name(),
+            Self::Ema(indicator) => indicator.name(),
+            Self::HeikinAshi(indicator) => indicator.name(),
+            Self::HigherTimeframe(indicator) => indicator.name(),
+            Self::Obv(indicator) => indicator.name(),
+            Self::OrderBookImbalance(indicator) => indicator.name(),
+            Self::RealizedVolatility(indicator) => indicator.name(),
+            Self::RollingCorrelation(indicator) => indicator.name(),
+            Self::Rsi(indicator) => indicator.name(),
+            Self::Smoothing(indicator) => indicator.name(),
+            Self::Spread(indicator) => indicator.name(),
+            Self::Supertrend(indicator) => indicator.name(),
+            Self::ZScore(indicator) => indicator.name(),
+        }
+    }
+
+    fn get_indicator_columns(&self) -> &Vec<(String, DataType)> {
+        match self {
+            Self::Adx(indicator) => indicator.get_indicator_columns(),
+            Self::Donchian(indicator) => indicator.get_indicator_columns(),
+            Self::Ema(indicator) => indicator.get_indicator_columns(),
+            Self::HeikinAshi(indicator) => indicator.get_indicator_columns(),
+            Self::HigherTimeframe(indicator) => indicator.get_indicator_columns(),
+            Self::Obv(indicator) => indicator.get_indicator_columns(),
+            Self::OrderBookImbalance(indicator) => indicator.get_indicator_columns(),
+            Self::RealizedVolatility(indicator) => indicator.get_indicator_columns(),
+            Self::RollingCorrelation(indicator) => indicator.get_indicator_columns(),
+            Self::Rsi(indicator) => indicator.get_indicator_columns(),
+            Self::Smoothing(indicator) => indicator.get_indicator_columns(),
+            Self::Spread(indicator) => indicator.get_indicator_columns(),
+            Self::Supertrend(indicator) => indicator.get_indicator_columns(),
+            Self::ZScore(indicator) => indicator.get_indicator_columns(),
+        }
+    }
+
+    fn set_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        match self {
+            Self::Adx(indicator) => indicator.set_indicator_columns(lf),
+            Self::Donchian(indicator) => indicator.set_indicator_columns(lf),
+            Self::Ema(indicator) => indicator.set_indicator_columns(lf),
+            Self::HeikinAshi(indicator) => indicator.set_indicator_columns(lf),
+            Self::HigherTimeframe(indicator) => indicator.set_indicator_columns(lf),
+            Self::Obv(indicator) => indicator.set_indicator_columns(lf),
+            Self::OrderBookImbalance(indicator) => indicator.set_indicator_columns(lf),
+            Self::RealizedVolatility(indicator) => indicator.set_indicator_columns(lf),
+            Self::RollingCorrelation(indicator) => indicator.set_indicator_columns(lf),
+            Self::Rsi(indicator) => indicator.set_indicator_columns(lf),
+            Self::Smoothing(indicator) => indicator.set_indicator_columns(lf),
+            Self::Spread(indicator) => indicator.set_indicator_columns(lf),
+            Self::Supertrend(indicator) => indicator.set_indicator_columns(lf),
+            Self::ZScore(indicator) => indicator.set_indicator_columns(lf),
+        }
+    }
+
+    fn update_indicator_columns(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        match self {
+            Self::Adx(indicator) => indicator.update_indicator_columns(df),
+            Self::Donchian(indicator) => indicator.update_indicator_columns(df),
+            Self::Ema(indicator) => indicator.update_indicator_columns(df),
+            Self::HeikinAshi(indicator) => indicator.update_indicator_columns(df),
+            Self::HigherTimeframe(indicator) => indicator.update_indicator_columns(df),
+            Self::Obv(indicator) => indicator.update_indicator_columns(df),
+            Self::OrderBookImbalance(indicator) => indicator.update_indicator_columns(df),
+            Self::RealizedVolatility(indicator) => indicator.update_indicator_columns(df),
+            Self::RollingCorrelation(indicator) => indicator.update_indicator_columns(df),
+            Self::Rsi(indicator) => indicator.update_indicator_columns(df),
+            Self::Smoothing(indicator) => indicator.update_indicator_columns(df),
+            Self::Spread(indicator) => indicator.update_indicator_columns(df),
+            Self::Supertrend(indicator) => indicator.update_indicator_columns(df),
+            Self::ZScore(indicator) => indicator.update_indicator_columns(df),
+        }
+    }
+
+    fn get_minimum_klines_for_benchmarking(&self) -> u32 {
+        match self {
+            Self::Adx(indicator) => indicator.get_minimum_klines_for_benchmarking(),
+            Self::Donchian(indicator) => indicator.get_minimum_klines_for_benchmarking(),
+            Self::Ema(indicator) => indicator.get_minimum_klines_for_benchmarking(),
+            Self::HeikinAshi(indicator) => indicator.get_minimum_klines_for_benchmarking(),
+            Self::HigherTimeframe(indicator) => indicator.get_minimum_klines_for_benchmarking(),
+            Self::Obv(indicator) => indicator.get_minimum_klines_for_benchmarking(),
+            Self::OrderBookImbalance(indicator) => indicator.get_minimum_klines_for_benchmarking(),
+            Self::RealizedVolatility(indicator) => indicator.get_minimum_klines_for_benchmarking(),
+            Self::RollingCorrelation(indicator) => indicator.get_minimum_klines_for_benchmarking(),
+            Self::Rsi(indicator) => indicator.get_minimum_klines_for_benchmarking(),
+            Self::Smoothing(indicator) => indicator.get_minimum_klines_for_benchmarking(),
+            Self::Spread(indicator) => indicator.get_minimum_klines_for_benchmarking(),
+            Self::Supertrend(indicator) => indicator.get_minimum_klines_for_benchmarking(),
+            Self::ZScore(indicator) => indicator.get_minimum_klines_for_benchmarking(),
+        }
+    }
+
+    fn patch_params(&self, params: Self::Params) -> Result<Self::Wrapper, GlowError> {
+        match (self, params) {
+            (Self::Adx(indicator), IndicatorParamsWrapper::Adx(params)) => {
+                indicator.patch_params(params)
+            }
+            (Self::Donchian(indicator), IndicatorParamsWrapper::Donchian(params)) => {
+                indicator.patch_params(params)
+            }
+            (Self::Ema(indicator), IndicatorParamsWrapper::Ema(params)) => {
+                indicator.patch_params(params)
+            }
+            (Self::HeikinAshi(indicator), IndicatorParamsWrapper::HeikinAshi(params)) => {
+                indicator.patch_params(params)
+            }
+            (Self::HigherTimeframe(indicator), IndicatorParamsWrapper::HigherTimeframe(params)) => {
+                indicator.patch_params(params)
+            }
+            (Self::Obv(indicator), IndicatorParamsWrapper::Obv(params)) => {
+                indicator.patch_params(params)
+            }
+            (
+                Self::OrderBookImbalance(indicator),
+                IndicatorParamsWrapper::OrderBookImbalance(params),
+            ) => indicator.patch_params(params),
+            (
+                Self::RealizedVolatility(indicator),
+                IndicatorParamsWrapper::RealizedVolatility(params),
+            ) => indicator.patch_params(params),
+            (
+                Self::RollingCorrelation(indicator),
+                IndicatorParamsWrapper::RollingCorrelation(params),
+            ) => indicator.patch_params(params),
+            (Self::Rsi(indicator), IndicatorParamsWrapper::Rsi(params)) => {
+                indicator.patch_params(params)
+            }
+            (Self::Smoothing(indicator), IndicatorParamsWrapper::Smoothing(params)) => {
+                indicator.patch_params(params)
+            }
+            (Self::Spread(indicator), IndicatorParamsWrapper::Spread(params)) => {
+                indicator.patch_params(params)
+            }
+            (Self::Supertrend(indicator), IndicatorParamsWrapper::Supertrend(params)) => {
+                indicator.patch_params(params)
+            }
+            (Self::ZScore(indicator), IndicatorParamsWrapper::ZScore(params)) => {
+                indicator.patch_params(params)
+            }
+            (indicator, params) => {
+                let error = format!(
+                    "params {:?} don't match {} indicator",
+                    params,
+                    indicator.name()
+                );
+                Err(GlowError::new(
+                    String::from("Invalid Indicator Params"),
+                    error,
+                ))
+            }
+        }
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        match self {
+            Self::Adx(indicator) => indicator.patch_symbols_pair(updated_symbols_pair),
+            Self::Donchian(indicator) => indicator.patch_symbols_pair(updated_symbols_pair),
+            Self::Ema(indicator) => indicator.patch_symbols_pair(updated_symbols_pair),
+            Self::HeikinAshi(indicator) => indicator.patch_symbols_pair(updated_symbols_pair),
+            Self::HigherTimeframe(indicator) => indicator.patch_symbols_pair(updated_symbols_pair),
+            Self::Obv(indicator) => indicator.patch_symbols_pair(updated_symbols_pair),
+            Self::OrderBookImbalance(indicator) => {
+                indicator.patch_symbols_pair(updated_symbols_pair)
+            }
+            Self::RealizedVolatility(indicator) => {
+                indicator.patch_symbols_pair(updated_symbols_pair)
+            }
+            Self::RollingCorrelation(indicator) => {
+                indicator.patch_symbols_pair(updated_symbols_pair)
+            }
+            Self::Rsi(indicator) => indicator.patch_symbols_pair(updated_symbols_pair),
+            Self::Smoothing(indicator) => indicator.patch_symbols_pair(updated_symbols_pair),
+            Self::Spread(indicator) => indicator.patch_symbols_pair(updated_symbols_pair),
+            Self::Supertrend(indicator) => indicator.patch_symbols_pair(updated_symbols_pair),
+            Self::ZScore(indicator) => indicator.patch_symbols_pair(updated_symbols_pair),
+        }
+    }
+}
+
+impl From<AdxIndicator> for IndicatorWrapper {
+    fn from(value: AdxIndicator) -> Self {
+        Self::Adx(value)
+    }
+}
+
+impl From<DonchianIndicator> for IndicatorWrapper {
+    fn from(value: DonchianIndicator) -> Self {
+        Self::Donchian(value)
+    }
+}
+
+impl From<EmaIndicator> for IndicatorWrapper {
+    fn from(value: EmaIndicator) -> Self {
+        Self::Ema(value)
+    }
+}
+
+impl From<HeikinAshiIndicator> for IndicatorWrapper {
+    fn from(value: HeikinAshiIndicator) -> Self {
+        Self::HeikinAshi(value)
+    }
+}
+
+impl From<HigherTimeframeIndicator> for IndicatorWrapper {
+    fn from(value: HigherTimeframeIndicator) -> Self {
+        Self::HigherTimeframe(value)
+    }
+}
+
+impl From<ObvIndicator> for IndicatorWrapper {
+    fn from(value: ObvIndicator) -> Self {
+        Self::Obv(value)
+    }
+}
+
+impl From<OrderBookImbalanceIndicator> for IndicatorWrapper {
+    fn from(value: OrderBookImbalanceIndicator) -> Self {
+        Self::OrderBookImbalance(value)
+    }
+}
+
+impl From<RealizedVolatilityIndicator> for IndicatorWrapper {
+    fn from(value: RealizedVolatilityIndicator) -> Self {
+        Self::RealizedVolatility(value)
+    }
+}
+
+impl From<RollingCorrelationIndicator> for IndicatorWrapper {
+    fn from(value: RollingCorrelationIndicator) -> Self {
+        Self::RollingCorrelation(value)
+    }
+}
+
+impl From<RsiIndicator> for IndicatorWrapper {
+    fn from(value: RsiIndicator) -> Self {
+        Self::Rsi(value)
+    }
+}
+
+impl From<SmoothingPreIndicator> for IndicatorWrapper {
+    fn from(value: SmoothingPreIndicator) -> Self {
+        Self::Smoothing(value)
+    }
+}
+
+impl From<SpreadIndicator> for IndicatorWrapper {
+    fn from(value: SpreadIndicator) -> Self {
+        Self::Spread(value)
+    }
+}
+
+impl From<SupertrendIndicator> for IndicatorWrapper {
+    fn from(value: SupertrendIndicator) -> Self {
+        Self::Supertrend(value)
+    }
+}
+
+impl From<ZScoreIndicator> for IndicatorWrapper {
+    fn from(value: ZScoreIndicator) -> Self {
+        Self::ZScore(value)
+    }
+}
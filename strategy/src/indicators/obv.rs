@@ -0,0 +1,143 @@
+use super::IndicatorWrapper;
+use crate::functions::get_last_valid_index;
+use common::{structs::SymbolsPair, traits::indicator::Indicator};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+const NAME: &str = "OBV";
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ObvParams {}
+
+/// On-balance volume over anchor symbol, emitted at `{anchor}_obv`.
+///
+/// Each bar adds its volume when close rises from previous bar's close, subtracts it when it falls,
+/// and keeps the running total otherwise. Requires `{anchor}_volume` to be present.
+#[derive(Clone, Debug)]
+pub struct ObvIndicator {
+    pub name: &'static str,
+    pub close_col: String,
+    pub volume_col: String,
+    pub output_col: String,
+    columns: Vec<(String, DataType)>,
+}
+
+impl ObvIndicator {
+    pub fn new(symbols_pair: SymbolsPair) -> Self {
+        let anchor = symbols_pair.anchor;
+        let close_col = anchor.get_close_col().to_string();
+        let volume_col = format!("{}_volume", anchor.name);
+        let output_col = format!("{}_obv", anchor.name);
+        let columns = vec![(output_col.clone(), DataType::Float64)];
+        Self {
+            name: NAME,
+            close_col,
+            volume_col,
+            output_col,
+            columns,
+        }
+    }
+
+    fn get_missing_volume_error(&self) -> GlowError {
+        let error = format!(
+            "{} indicator requires {} column, which is missing from data",
+            self.name, self.volume_col
+        );
+        GlowError::new(String::from("Missing Volume Column"), error)
+    }
+}
+
+impl Indicator for ObvIndicator {
+    type Params = ObvParams;
+    type Wrapper = IndicatorWrapper;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_indicator_columns(&self) -> &Vec<(String, DataType)> {
+        &self.columns
+    }
+
+    fn set_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        let schema = lf.schema()?;
+        if !schema.contains(&self.volume_col) {
+            return Err(self.get_missing_volume_error());
+        }
+        let close = col(&self.close_col).cast(DataType::Float64);
+        let volume = col(&self.volume_col).cast(DataType::Float64);
+
+        let lf = lf.with_column(
+            ((close.clone() - close.shift(1)).sign() * volume)
+                .fill_null(lit(0.0))
+                .cumsum(false)
+                .alias(&self.output_col),
+        );
+
+        Ok(lf)
+    }
+
+    /// Carries the running total from the last computed OBV value, so only rows appended after it
+    /// are calculated. If no prior value exists, the whole column is recomputed.
+    fn update_indicator_columns(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        if !df.schema().contains(&self.volume_col) {
+            return Err(self.get_missing_volume_error());
+        }
+        let last_valid_index = get_last_valid_index(df, &self.output_col)?;
+        if last_valid_index.is_none() {
+            let result_df = self.set_indicator_columns(df.clone().lazy())?.collect()?;
+            return Ok(result_df);
+        }
+        let last_valid_index = last_valid_index.unwrap();
+
+        let close_series = df.column(&self.close_col)?.cast(&DataType::Float64)?;
+        let close_values: Vec<Option<f64>> = close_series.f64()?.into_iter().collect();
+        let volume_series = df.column(&self.volume_col)?.cast(&DataType::Float64)?;
+        let volume_values = volume_series.f64()?;
+        let output_values = df.column(&self.output_col)?.f64()?;
+
+        let mut updated_values: Vec<Option<f64>> = output_values
+            .into_iter()
+            .take(last_valid_index + 1)
+            .collect();
+        let mut previous_obv = updated_values[last_valid_index].unwrap();
+
+        for (index, volume) in volume_values
+            .into_iter()
+            .enumerate()
+            .skip(last_valid_index + 1)
+        {
+            let close_change = match (close_values[index - 1], close_values[index]) {
+                (Some(previous_close), Some(close)) => close - previous_close,
+                _ => 0.0,
+            };
+            let volume = volume.unwrap_or_default();
+            if close_change > 0.0 {
+                previous_obv += volume;
+            } else if close_change < 0.0 {
+                previous_obv -= volume;
+            }
+            updated_values.push(Some(previous_obv));
+        }
+
+        let mut result_df = df.clone();
+        result_df.with_column(Series::new(&self.output_col, updated_values))?;
+
+        Ok(result_df)
+    }
+
+    fn get_minimum_klines_for_benchmarking(&self) -> u32 {
+        2
+    }
+
+    fn patch_params(&self, _params: Self::Params) -> Result<Self::Wrapper, GlowError> {
+        Ok(self.clone().into())
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        Ok(Self::new(updated_symbols_pair).into())
+    }
+}
@@ -0,0 +1,115 @@
+use super::IndicatorWrapper;
+use common::{r#static::ORDER_BOOK_DEPTHS, structs::SymbolsPair, traits::indicator::Indicator};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+const NAME: &str = "Order Book Imbalance";
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct OrderBookImbalanceParams {}
+
+/// Imbalance between anchor's order book bid and ask quantities, emitted at
+/// `{anchor}_depth_imbalance`, ranging from -1, when there are only asks, to 1, when there are
+/// only bids. See `OrderBookDepth::get_imbalance`.
+///
+/// This indicator is live only: historical klines carry no depth, so it's null over benchmarks.
+/// Live, each appended bar takes the latest depth streamed by data provider, at `ORDER_BOOK_DEPTHS`,
+/// meaning schemas using it must be flagged by `Schema::is_live_only` and update it by
+/// `update_indicator_columns`, as recomputing it by `set_indicator_columns` only keeps prior values.
+#[derive(Clone, Debug)]
+pub struct OrderBookImbalanceIndicator {
+    pub name: &'static str,
+    pub symbol: &'static str,
+    pub output_col: String,
+    columns: Vec<(String, DataType)>,
+}
+
+impl OrderBookImbalanceIndicator {
+    pub fn new(symbols_pair: SymbolsPair) -> Self {
+        let symbol = symbols_pair.anchor.name;
+        let output_col = get_depth_imbalance_col(symbol);
+        let columns = vec![(output_col.clone(), DataType::Float64)];
+        Self {
+            name: NAME,
+            symbol,
+            output_col,
+            columns,
+        }
+    }
+
+    /// Gets values already set at `df`, or nulls if column is absent.
+    fn get_kept_values(&self, df: &DataFrame) -> Result<Vec<Option<f64>>, GlowError> {
+        let values = match df.column(&self.output_col) {
+            Ok(series) => series
+                .cast(&DataType::Float64)?
+                .f64()?
+                .into_iter()
+                .collect(),
+            Err(_) => vec![None; df.height()],
+        };
+        Ok(values)
+    }
+
+    fn get_latest_imbalance(&self) -> Result<Option<f64>, GlowError> {
+        let depths = ORDER_BOOK_DEPTHS.read()?;
+        let imbalance = depths
+            .get(self.symbol)
+            .and_then(|depth| depth.get_imbalance());
+        Ok(imbalance)
+    }
+}
+
+pub fn get_depth_imbalance_col(symbol: &str) -> String {
+    format!("{}_depth_imbalance", symbol)
+}
+
+impl Indicator for OrderBookImbalanceIndicator {
+    type Params = OrderBookImbalanceParams;
+    type Wrapper = IndicatorWrapper;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_indicator_columns(&self) -> &Vec<(String, DataType)> {
+        &self.columns
+    }
+
+    /// Depth can't be derived from klines, so rows keep their prior values, if any, or are null.
+    fn set_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        let mut df = lf.collect()?;
+        let values = self.get_kept_values(&df)?;
+        df.with_column(Series::new(&self.output_col, values))?;
+
+        Ok(df.lazy())
+    }
+
+    /// Sets last row, i.e. the bar just appended live, to the latest streamed imbalance,
+    /// keeping prior rows. Without any streamed depth, it's null.
+    fn update_indicator_columns(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        let mut values = self.get_kept_values(df)?;
+        if let Some(last_value) = values.last_mut() {
+            *last_value = self.get_latest_imbalance()?;
+        }
+        let mut result_df = df.clone();
+        result_df.with_column(Series::new(&self.output_col, values))?;
+
+        Ok(result_df)
+    }
+
+    fn get_minimum_klines_for_benchmarking(&self) -> u32 {
+        0
+    }
+
+    fn patch_params(&self, _params: Self::Params) -> Result<Self::Wrapper, GlowError> {
+        Ok(self.clone().into())
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let updated = Self::new(updated_symbols_pair);
+        Ok(updated.into())
+    }
+}
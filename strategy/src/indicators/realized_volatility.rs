@@ -0,0 +1,157 @@
+use super::IndicatorWrapper;
+use crate::functions::get_last_valid_index;
+use common::{enums::granularity::Granularity, structs::SymbolsPair, traits::indicator::Indicator};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+const NAME: &str = "Realized Volatility";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RealizedVolatilityParams {
+    pub window: usize,
+}
+
+impl Default for RealizedVolatilityParams {
+    fn default() -> Self {
+        Self { window: 20 }
+    }
+}
+
+/// Annualized realized volatility of anchor's closes, emitted at `{anchor}_rv`, as the rolling
+/// sample std of log returns over `window` bars, scaled by the square root of `granularity`'s
+/// klines per year.
+///
+/// First `window` rows lack a full window of returns, so they're null.
+#[derive(Clone, Debug)]
+pub struct RealizedVolatilityIndicator {
+    pub name: &'static str,
+    pub window: usize,
+    pub granularity: Granularity,
+    pub close_col: String,
+    pub output_col: String,
+    columns: Vec<(String, DataType)>,
+}
+
+impl RealizedVolatilityIndicator {
+    pub fn new(symbols_pair: SymbolsPair, window: usize, granularity: Granularity) -> Self {
+        let anchor = symbols_pair.anchor;
+        let output_col = get_realized_volatility_col(anchor.name);
+        let columns = vec![(output_col.clone(), DataType::Float64)];
+        Self {
+            name: NAME,
+            window,
+            granularity,
+            close_col: anchor.get_close_col().to_string(),
+            output_col,
+            columns,
+        }
+    }
+
+    fn get_rolling_options(&self) -> RollingOptions {
+        RollingOptions {
+            window_size: Duration::parse(&format!("{}i", self.window)),
+            min_periods: self.window,
+            center: false,
+            by: None,
+            weights: None,
+            closed_window: None,
+            fn_params: None,
+        }
+    }
+}
+
+pub fn get_realized_volatility_col(symbol: &str) -> String {
+    format!("{}_rv", symbol)
+}
+
+impl Indicator for RealizedVolatilityIndicator {
+    type Params = RealizedVolatilityParams;
+    type Wrapper = IndicatorWrapper;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_indicator_columns(&self) -> &Vec<(String, DataType)> {
+        &self.columns
+    }
+
+    fn set_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        if self.window < 2 {
+            let error = format!(
+                "realized volatility window must be at least 2, got {}",
+                self.window
+            );
+            return Err(GlowError::new(
+                String::from("Invalid Realized Volatility Window"),
+                error,
+            ));
+        }
+        let close = col(&self.close_col).cast(DataType::Float64);
+        let log_returns = (close.clone() / close.shift(1)).log(std::f64::consts::E);
+        let annualization_factor = self.granularity.get_periods_per_year().sqrt();
+
+        let lf = lf.with_column(
+            (log_returns.rolling_std(self.get_rolling_options()) * lit(annualization_factor))
+                .alias(&self.output_col),
+        );
+
+        Ok(lf)
+    }
+
+    /// Recomputes only rows appended after the last calculated volatility, alongside the closes
+    /// their returns' window spans. If no prior value exists, the whole column is recomputed.
+    fn update_indicator_columns(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        let last_valid_index = get_last_valid_index(df, &self.output_col)?;
+        if last_valid_index.is_none() {
+            let result_df = self.set_indicator_columns(df.clone().lazy())?.collect()?;
+            return Ok(result_df);
+        }
+        let first_pending_index = last_valid_index.unwrap() + 1;
+        if first_pending_index >= df.height() {
+            return Ok(df.clone());
+        }
+
+        let offset = first_pending_index.saturating_sub(self.window);
+        let window_df = df
+            .select([&self.close_col])?
+            .slice(offset as i64, df.height() - offset);
+        let window_df = self.set_indicator_columns(window_df.lazy())?.collect()?;
+        let pending_values = window_df.column(&self.output_col)?.f64()?;
+
+        let mut updated_values: Vec<Option<f64>> = df
+            .column(&self.output_col)?
+            .f64()?
+            .into_iter()
+            .take(first_pending_index)
+            .collect();
+        updated_values.extend(
+            pending_values
+                .into_iter()
+                .skip(first_pending_index - offset),
+        );
+
+        let mut result_df = df.clone();
+        result_df.with_column(Series::new(&self.output_col, updated_values))?;
+
+        Ok(result_df)
+    }
+
+    fn get_minimum_klines_for_benchmarking(&self) -> u32 {
+        (self.window + 1) as u32
+    }
+
+    fn patch_params(&self, params: Self::Params) -> Result<Self::Wrapper, GlowError> {
+        let mut updated = self.clone();
+        updated.window = params.window;
+        Ok(updated.into())
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let updated = Self::new(updated_symbols_pair, self.window, self.granularity);
+        Ok(updated.into())
+    }
+}
@@ -0,0 +1,180 @@
+use super::IndicatorWrapper;
+use crate::functions::get_last_valid_index;
+use common::{structs::SymbolsPair, traits::indicator::Indicator};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+const NAME: &str = "Rolling Correlation";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RollingCorrelationParams {
+    pub window: usize,
+}
+
+impl Default for RollingCorrelationParams {
+    fn default() -> Self {
+        Self { window: 20 }
+    }
+}
+
+/// Pearson correlation between anchor's and traded symbol's close to close returns, over
+/// rolling `window` bars, emitted at `{anchor}_{traded}_corr`.
+///
+/// First `window` rows lack a full window of returns, so they're null, as are rows whose window
+/// lacks any close or has either symbol's returns flat, as their correlation is undefined.
+#[derive(Clone, Debug)]
+pub struct RollingCorrelationIndicator {
+    pub name: &'static str,
+    pub window: usize,
+    pub anchor_close_col: String,
+    pub traded_close_col: String,
+    pub output_col: String,
+    columns: Vec<(String, DataType)>,
+}
+
+impl RollingCorrelationIndicator {
+    pub fn new(symbols_pair: SymbolsPair, window: usize) -> Self {
+        let output_col = get_rolling_correlation_col(symbols_pair);
+        let columns = vec![(output_col.clone(), DataType::Float64)];
+        Self {
+            name: NAME,
+            window,
+            anchor_close_col: symbols_pair.anchor.get_close_col().to_string(),
+            traded_close_col: symbols_pair.traded.get_close_col().to_string(),
+            output_col,
+            columns,
+        }
+    }
+
+    fn get_rolling_options(&self) -> RollingOptions {
+        RollingOptions {
+            window_size: Duration::parse(&format!("{}i", self.window)),
+            min_periods: self.window,
+            center: false,
+            by: None,
+            weights: None,
+            closed_window: None,
+            fn_params: None,
+        }
+    }
+
+    fn get_returns_expr(close_col: &str) -> Expr {
+        let close = col(close_col).cast(DataType::Float64);
+        close.clone() / close.shift(1) - lit(1.0)
+    }
+
+    /// Correlation as windowed covariance over the product of windowed stds, each derived from
+    /// windowed means, as `E[xy] - E[x]E[y]`.
+    fn get_correlation_expr(&self) -> Expr {
+        let anchor_returns = Self::get_returns_expr(&self.anchor_close_col);
+        let traded_returns = Self::get_returns_expr(&self.traded_close_col);
+        let rolling_mean = |expr: Expr| expr.rolling_mean(self.get_rolling_options());
+
+        let anchor_mean = rolling_mean(anchor_returns.clone());
+        let traded_mean = rolling_mean(traded_returns.clone());
+        let covariance = rolling_mean(anchor_returns.clone() * traded_returns.clone())
+            - anchor_mean.clone() * traded_mean.clone();
+        let anchor_variance = rolling_mean(anchor_returns.clone() * anchor_returns)
+            - anchor_mean.clone() * anchor_mean;
+        let traded_variance = rolling_mean(traded_returns.clone() * traded_returns)
+            - traded_mean.clone() * traded_mean;
+        let variances_product = anchor_variance * traded_variance;
+
+        when(variances_product.clone().gt(lit(0.0)))
+            .then(covariance / variances_product.sqrt())
+            .otherwise(lit(NULL).cast(DataType::Float64))
+            .alias(&self.output_col)
+    }
+}
+
+pub fn get_rolling_correlation_col(symbols_pair: SymbolsPair) -> String {
+    format!(
+        "{}_{}_corr",
+        symbols_pair.anchor.name, symbols_pair.traded.name
+    )
+}
+
+impl Indicator for RollingCorrelationIndicator {
+    type Params = RollingCorrelationParams;
+    type Wrapper = IndicatorWrapper;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_indicator_columns(&self) -> &Vec<(String, DataType)> {
+        &self.columns
+    }
+
+    fn set_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        if self.window < 2 {
+            let error = format!(
+                "rolling correlation window must be at least 2, got {}",
+                self.window
+            );
+            return Err(GlowError::new(
+                String::from("Invalid Rolling Correlation Window"),
+                error,
+            ));
+        }
+        let lf = lf.with_column(self.get_correlation_expr());
+
+        Ok(lf)
+    }
+
+    /// Recomputes only rows appended after the last calculated correlation, alongside the closes
+    /// their returns' window spans. If no prior value exists, the whole column is recomputed.
+    fn update_indicator_columns(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        let last_valid_index = get_last_valid_index(df, &self.output_col)?;
+        if last_valid_index.is_none() {
+            let result_df = self.set_indicator_columns(df.clone().lazy())?.collect()?;
+            return Ok(result_df);
+        }
+        let first_pending_index = last_valid_index.unwrap() + 1;
+        if first_pending_index >= df.height() {
+            return Ok(df.clone());
+        }
+
+        let offset = first_pending_index.saturating_sub(self.window);
+        let window_df = df
+            .select([&self.anchor_close_col, &self.traded_close_col])?
+            .slice(offset as i64, df.height() - offset);
+        let window_df = self.set_indicator_columns(window_df.lazy())?.collect()?;
+        let pending_values = window_df.column(&self.output_col)?.f64()?;
+
+        let mut updated_values: Vec<Option<f64>> = df
+            .column(&self.output_col)?
+            .f64()?
+            .into_iter()
+            .take(first_pending_index)
+            .collect();
+        updated_values.extend(
+            pending_values
+                .into_iter()
+                .skip(first_pending_index - offset),
+        );
+
+        let mut result_df = df.clone();
+        result_df.with_column(Series::new(&self.output_col, updated_values))?;
+
+        Ok(result_df)
+    }
+
+    fn get_minimum_klines_for_benchmarking(&self) -> u32 {
+        (self.window + 1) as u32
+    }
+
+    fn patch_params(&self, params: Self::Params) -> Result<Self::Wrapper, GlowError> {
+        let mut updated = self.clone();
+        updated.window = params.window;
+        Ok(updated.into())
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let updated = Self::new(updated_symbols_pair, self.window);
+        Ok(updated.into())
+    }
+}
@@ -0,0 +1,234 @@
+use super::{adx::WilderAverage, IndicatorWrapper};
+use crate::functions::get_last_valid_index;
+use common::{structs::SymbolsPair, traits::indicator::Indicator};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+const NAME: &str = "RSI";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RsiParams {
+    pub period: usize,
+}
+
+impl Default for RsiParams {
+    fn default() -> Self {
+        Self { period: 14 }
+    }
+}
+
+/// Relative strength index over anchor's closes, emitted at `{anchor}_rsi`, ranging from 0 to 100.
+///
+/// Gains and losses between consecutive closes are Wilder smoothed over `period` bars, so RSI is
+/// available from bar `period` on, previous rows being null. Smoothed averages are emitted as well,
+/// at `{anchor}_rsi_avg_gain` and `{anchor}_rsi_avg_loss`, so that appended rows carry them forward
+/// instead of replaying whole history. Bars with neither gains nor losses have an RSI of 50.
+#[derive(Clone, Debug)]
+pub struct RsiIndicator {
+    pub name: &'static str,
+    pub period: usize,
+    pub close_col: String,
+    pub rsi_col: String,
+    pub avg_gain_col: String,
+    pub avg_loss_col: String,
+    columns: Vec<(String, DataType)>,
+}
+
+impl RsiIndicator {
+    pub fn new(symbols_pair: SymbolsPair, period: usize) -> Self {
+        let anchor = symbols_pair.anchor;
+        let rsi_col = get_rsi_col(anchor.name);
+        let avg_gain_col = format!("{}_avg_gain", rsi_col);
+        let avg_loss_col = format!("{}_avg_loss", rsi_col);
+        let columns = vec![
+            (rsi_col.clone(), DataType::Float64),
+            (avg_gain_col.clone(), DataType::Float64),
+            (avg_loss_col.clone(), DataType::Float64),
+        ];
+        Self {
+            name: NAME,
+            period,
+            close_col: anchor.get_close_col().to_string(),
+            rsi_col,
+            avg_gain_col,
+            avg_loss_col,
+            columns,
+        }
+    }
+
+    /// Keeps RSI and averages values up to `last_valid_index`, resuming smoothing from the kept
+    /// averages over the remaining rows. If `last_valid_index` is `None`, all rows are calculated.
+    fn calculate_columns(
+        &self,
+        df: &DataFrame,
+        last_valid_index: Option<usize>,
+    ) -> Result<[Series; 3], GlowError> {
+        let close_series = df.column(&self.close_col)?.cast(&DataType::Float64)?;
+        let closes: Vec<Option<f64>> = close_series.f64()?.into_iter().collect();
+
+        let (mut state, first_pending_index) = match last_valid_index {
+            Some(last_valid_index) => {
+                let avg_gain = df.column(&self.avg_gain_col)?.f64()?.get(last_valid_index);
+                let avg_loss = df.column(&self.avg_loss_col)?.f64()?.get(last_valid_index);
+                let (Some(avg_gain), Some(avg_loss), Some(previous_close)) =
+                    (avg_gain, avg_loss, closes[last_valid_index])
+                else {
+                    let error = format!(
+                        "{} row {} has RSI, but lacks its close or smoothed averages",
+                        self.rsi_col, last_valid_index
+                    );
+                    return Err(GlowError::new(String::from("Invalid RSI State"), error));
+                };
+                let state = RsiState::resume(self.period, previous_close, avg_gain, avg_loss);
+                (state, last_valid_index + 1)
+            }
+            None => (RsiState::new(self.period), 0),
+        };
+
+        let mut rsi_values = get_kept_values(df, &self.rsi_col, first_pending_index)?;
+        let mut avg_gain_values = get_kept_values(df, &self.avg_gain_col, first_pending_index)?;
+        let mut avg_loss_values = get_kept_values(df, &self.avg_loss_col, first_pending_index)?;
+        for close in closes.into_iter().skip(first_pending_index) {
+            let (rsi, avg_gain, avg_loss) = match close.and_then(|close| state.next(close)) {
+                Some((rsi, avg_gain, avg_loss)) => (Some(rsi), Some(avg_gain), Some(avg_loss)),
+                None => (None, None, None),
+            };
+            rsi_values.push(rsi);
+            avg_gain_values.push(avg_gain);
+            avg_loss_values.push(avg_loss);
+        }
+
+        Ok([
+            Series::new(&self.rsi_col, rsi_values),
+            Series::new(&self.avg_gain_col, avg_gain_values),
+            Series::new(&self.avg_loss_col, avg_loss_values),
+        ])
+    }
+}
+
+pub fn get_rsi_col(symbol: &str) -> String {
+    format!("{}_rsi", symbol)
+}
+
+fn get_kept_values(
+    df: &DataFrame,
+    column: &str,
+    length: usize,
+) -> Result<Vec<Option<f64>>, GlowError> {
+    if length == 0 {
+        return Ok(vec![]);
+    }
+    let values = df.column(column)?.f64()?.into_iter().take(length).collect();
+    Ok(values)
+}
+
+#[derive(Clone, Copy, Debug)]
+struct RsiState {
+    previous_close: Option<f64>,
+    avg_gain: WilderAverage,
+    avg_loss: WilderAverage,
+}
+
+impl RsiState {
+    fn new(period: usize) -> Self {
+        Self {
+            previous_close: None,
+            avg_gain: WilderAverage::new(period),
+            avg_loss: WilderAverage::new(period),
+        }
+    }
+
+    fn resume(period: usize, previous_close: f64, avg_gain: f64, avg_loss: f64) -> Self {
+        Self {
+            previous_close: Some(previous_close),
+            avg_gain: WilderAverage::resume(period, avg_gain),
+            avg_loss: WilderAverage::resume(period, avg_loss),
+        }
+    }
+
+    /// Takes bar's close, returning its (RSI, average gain, average loss), if already available.
+    fn next(&mut self, close: f64) -> Option<(f64, f64, f64)> {
+        let previous_close = self.previous_close.replace(close)?;
+        let change = close - previous_close;
+        let avg_gain = self.avg_gain.next(change.max(0.0));
+        let avg_loss = self.avg_loss.next((-change).max(0.0));
+        let (Some(avg_gain), Some(avg_loss)) = (avg_gain, avg_loss) else {
+            return None;
+        };
+
+        let rsi = if avg_loss > 0.0 {
+            100.0 - 100.0 / (1.0 + avg_gain / avg_loss)
+        } else if avg_gain > 0.0 {
+            100.0
+        } else {
+            50.0
+        };
+        Some((rsi, avg_gain, avg_loss))
+    }
+}
+
+impl Indicator for RsiIndicator {
+    type Params = RsiParams;
+    type Wrapper = IndicatorWrapper;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_indicator_columns(&self) -> &Vec<(String, DataType)> {
+        &self.columns
+    }
+
+    fn set_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        if self.period == 0 {
+            return Err(GlowError::new(
+                String::from("Invalid RSI Period"),
+                String::from("rsi period must be at least 1"),
+            ));
+        }
+        let mut df = lf.collect()?;
+        for series in self.calculate_columns(&df, None)? {
+            df.with_column(series)?;
+        }
+
+        Ok(df.lazy())
+    }
+
+    /// Calculates only rows appended after the last computed RSI, carrying its smoothed average
+    /// gain and loss over them. If no prior value exists, whole columns are recomputed.
+    fn update_indicator_columns(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        let last_valid_index = get_last_valid_index(df, &self.rsi_col)?;
+        if last_valid_index.is_none() {
+            let result_df = self.set_indicator_columns(df.clone().lazy())?.collect()?;
+            return Ok(result_df);
+        }
+        if last_valid_index.unwrap() + 1 >= df.height() {
+            return Ok(df.clone());
+        }
+
+        let mut result_df = df.clone();
+        for series in self.calculate_columns(df, last_valid_index)? {
+            result_df.with_column(series)?;
+        }
+
+        Ok(result_df)
+    }
+
+    fn get_minimum_klines_for_benchmarking(&self) -> u32 {
+        (self.period + 1) as u32
+    }
+
+    fn patch_params(&self, params: Self::Params) -> Result<Self::Wrapper, GlowError> {
+        let mut updated = self.clone();
+        updated.period = params.period;
+        Ok(updated.into())
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let updated = Self::new(updated_symbols_pair, self.period);
+        Ok(updated.into())
+    }
+}
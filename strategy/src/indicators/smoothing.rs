@@ -0,0 +1,193 @@
+use super::{ema::EmaIndicator, IndicatorWrapper};
+use crate::functions::get_last_valid_index;
+use common::{structs::SymbolsPair, traits::indicator::Indicator};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+const NAME: &str = "Smoothing";
+
+/// Filter `SmoothingPreIndicator` denoises closes with.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SmoothingMethod {
+    /// simple moving average over the last `window` closes.
+    #[default]
+    Sma,
+    /// exponential moving average, of `window` span.
+    Ema,
+    /// median of the last `window` closes, which, unlike averages, disregards outlier spikes.
+    Median,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SmoothingParams {
+    pub method: SmoothingMethod,
+    pub window: usize,
+}
+
+impl Default for SmoothingParams {
+    fn default() -> Self {
+        Self {
+            method: SmoothingMethod::default(),
+            window: 5,
+        }
+    }
+}
+
+/// Anchor's close smoothed by `method`, emitted at `{anchor}_smoothed_close`, as a denoised
+/// source for other indicators.
+///
+/// It's a pre-indicator in that indicators taking a source column read it instead of the raw
+/// close, e.g. `EmaIndicator::new(symbols_pair, period, get_smoothed_close_col(anchor), ..)`.
+/// Indicators' columns are set in the order indicators are listed, so it must be listed before
+/// any indicator reading its column. Moving averages are null over their first `window - 1`
+/// rows, while EMA is set from the first close on.
+#[derive(Clone, Debug)]
+pub struct SmoothingPreIndicator {
+    pub name: &'static str,
+    pub method: SmoothingMethod,
+    pub window: usize,
+    pub source_col: String,
+    pub output_col: String,
+    symbols_pair: SymbolsPair,
+    columns: Vec<(String, DataType)>,
+}
+
+impl SmoothingPreIndicator {
+    pub fn new(symbols_pair: SymbolsPair, method: SmoothingMethod, window: usize) -> Self {
+        let output_col = get_smoothed_close_col(symbols_pair.anchor.name);
+        let columns = vec![(output_col.clone(), DataType::Float64)];
+        Self {
+            name: NAME,
+            method,
+            window,
+            source_col: symbols_pair.anchor.get_close_col().to_string(),
+            output_col,
+            symbols_pair,
+            columns,
+        }
+    }
+
+    fn get_ema_indicator(&self) -> EmaIndicator {
+        EmaIndicator::new(
+            self.symbols_pair,
+            self.window,
+            self.source_col.clone(),
+            self.output_col.clone(),
+            true,
+        )
+    }
+
+    fn get_rolling_options(&self) -> RollingOptions {
+        RollingOptions {
+            window_size: Duration::parse(&format!("{}i", self.window)),
+            min_periods: self.window,
+            center: false,
+            by: None,
+            weights: None,
+            closed_window: None,
+            fn_params: None,
+        }
+    }
+
+    fn get_rolling_expr(&self) -> Expr {
+        let source = col(&self.source_col).cast(DataType::Float64);
+        let smoothed = match self.method {
+            SmoothingMethod::Median => source.rolling_median(self.get_rolling_options()),
+            _ => source.rolling_mean(self.get_rolling_options()),
+        };
+        smoothed.alias(&self.output_col)
+    }
+}
+
+pub fn get_smoothed_close_col(symbol: &str) -> String {
+    format!("{}_smoothed_close", symbol)
+}
+
+impl Indicator for SmoothingPreIndicator {
+    type Params = SmoothingParams;
+    type Wrapper = IndicatorWrapper;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_indicator_columns(&self) -> &Vec<(String, DataType)> {
+        &self.columns
+    }
+
+    fn set_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        if self.window == 0 {
+            return Err(GlowError::new(
+                String::from("Invalid Smoothing Window"),
+                String::from("smoothing window must be at least 1"),
+            ));
+        }
+        if self.method == SmoothingMethod::Ema {
+            return self.get_ema_indicator().set_indicator_columns(lf);
+        }
+        let lf = lf.with_column(self.get_rolling_expr());
+
+        Ok(lf)
+    }
+
+    /// EMA is seeded from its last value, see `EmaIndicator`, while moving averages only
+    /// recompute rows appended after the last smoothed value, alongside the closes their
+    /// window spans. If no prior value exists, the whole column is recomputed.
+    fn update_indicator_columns(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        if self.method == SmoothingMethod::Ema {
+            return self.get_ema_indicator().update_indicator_columns(df);
+        }
+        let last_valid_index = get_last_valid_index(df, &self.output_col)?;
+        if last_valid_index.is_none() {
+            let result_df = self.set_indicator_columns(df.clone().lazy())?.collect()?;
+            return Ok(result_df);
+        }
+        let first_pending_index = last_valid_index.unwrap() + 1;
+        if first_pending_index >= df.height() {
+            return Ok(df.clone());
+        }
+
+        let offset = first_pending_index.saturating_sub(self.window - 1);
+        let window_df = df
+            .select([&self.source_col])?
+            .slice(offset as i64, df.height() - offset);
+        let window_df = self.set_indicator_columns(window_df.lazy())?.collect()?;
+        let pending_values = window_df.column(&self.output_col)?.f64()?;
+
+        let mut updated_values: Vec<Option<f64>> = df
+            .column(&self.output_col)?
+            .f64()?
+            .into_iter()
+            .take(first_pending_index)
+            .collect();
+        updated_values.extend(
+            pending_values
+                .into_iter()
+                .skip(first_pending_index - offset),
+        );
+
+        let mut result_df = df.clone();
+        result_df.with_column(Series::new(&self.output_col, updated_values))?;
+
+        Ok(result_df)
+    }
+
+    fn get_minimum_klines_for_benchmarking(&self) -> u32 {
+        self.window as u32
+    }
+
+    fn patch_params(&self, params: Self::Params) -> Result<Self::Wrapper, GlowError> {
+        let mut updated = self.clone();
+        updated.method = params.method;
+        updated.window = params.window;
+        Ok(updated.into())
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let updated = Self::new(updated_symbols_pair, self.method, self.window);
+        Ok(updated.into())
+    }
+}
@@ -0,0 +1,138 @@
+use super::IndicatorWrapper;
+use crate::functions::get_last_valid_index;
+use common::{structs::SymbolsPair, traits::indicator::Indicator};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+const NAME: &str = "Spread";
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SpreadKind {
+    #[default]
+    Difference,
+    Ratio,
+    LogRatio,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SpreadParams {
+    pub kind: SpreadKind,
+}
+
+/// Spread between anchor's and traded symbol's closes, emitted at `{anchor}_{traded}_spread`,
+/// as their difference, ratio or log-ratio, according to `kind`.
+///
+/// Rows lacking either close are null.
+#[derive(Clone, Debug)]
+pub struct SpreadIndicator {
+    pub name: &'static str,
+    pub kind: SpreadKind,
+    pub anchor_close_col: String,
+    pub traded_close_col: String,
+    pub output_col: String,
+    columns: Vec<(String, DataType)>,
+}
+
+impl SpreadIndicator {
+    pub fn new(symbols_pair: SymbolsPair, kind: SpreadKind) -> Self {
+        let output_col = get_spread_col(symbols_pair);
+        let columns = vec![(output_col.clone(), DataType::Float64)];
+        Self {
+            name: NAME,
+            kind,
+            anchor_close_col: symbols_pair.anchor.get_close_col().to_string(),
+            traded_close_col: symbols_pair.traded.get_close_col().to_string(),
+            output_col,
+            columns,
+        }
+    }
+
+    fn get_spread_expr(&self) -> Expr {
+        let anchor_close = col(&self.anchor_close_col).cast(DataType::Float64);
+        let traded_close = col(&self.traded_close_col).cast(DataType::Float64);
+        let spread = match self.kind {
+            SpreadKind::Difference => anchor_close - traded_close,
+            SpreadKind::Ratio => anchor_close / traded_close,
+            SpreadKind::LogRatio => (anchor_close / traded_close).log(std::f64::consts::E),
+        };
+        spread.alias(&self.output_col)
+    }
+}
+
+pub fn get_spread_col(symbols_pair: SymbolsPair) -> String {
+    format!(
+        "{}_{}_spread",
+        symbols_pair.anchor.name, symbols_pair.traded.name
+    )
+}
+
+impl Indicator for SpreadIndicator {
+    type Params = SpreadParams;
+    type Wrapper = IndicatorWrapper;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_indicator_columns(&self) -> &Vec<(String, DataType)> {
+        &self.columns
+    }
+
+    fn set_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        let lf = lf.with_column(self.get_spread_expr());
+        Ok(lf)
+    }
+
+    /// As spread only depends on each row's closes, only rows appended after the last computed
+    /// spread are calculated. If no prior value exists, the whole column is recomputed.
+    fn update_indicator_columns(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        let last_valid_index = get_last_valid_index(df, &self.output_col)?;
+        if last_valid_index.is_none() {
+            let result_df = self.set_indicator_columns(df.clone().lazy())?.collect()?;
+            return Ok(result_df);
+        }
+        let first_pending_index = last_valid_index.unwrap() + 1;
+        if first_pending_index >= df.height() {
+            return Ok(df.clone());
+        }
+
+        let pending_df = df
+            .slice(
+                first_pending_index as i64,
+                df.height() - first_pending_index,
+            )
+            .lazy()
+            .select([self.get_spread_expr()])
+            .collect()?;
+        let mut updated_values: Vec<Option<f64>> = df
+            .column(&self.output_col)?
+            .f64()?
+            .into_iter()
+            .take(first_pending_index)
+            .collect();
+        updated_values.extend(pending_df.column(&self.output_col)?.f64()?);
+
+        let mut result_df = df.clone();
+        result_df.with_column(Series::new(&self.output_col, updated_values))?;
+
+        Ok(result_df)
+    }
+
+    fn get_minimum_klines_for_benchmarking(&self) -> u32 {
+        1
+    }
+
+    fn patch_params(&self, params: Self::Params) -> Result<Self::Wrapper, GlowError> {
+        let mut updated = self.clone();
+        updated.kind = params.kind;
+        Ok(updated.into())
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let updated = Self::new(updated_symbols_pair, self.kind);
+        Ok(updated.into())
+    }
+}
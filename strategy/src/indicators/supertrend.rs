@@ -0,0 +1,296 @@
+use super::{adx::WilderAverage, IndicatorWrapper};
+use crate::functions::get_last_valid_index;
+use common::{structs::SymbolsPair, traits::indicator::Indicator};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+const NAME: &str = "Supertrend";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SupertrendParams {
+    pub period: usize,
+    pub multiplier: f64,
+}
+
+impl Default for SupertrendParams {
+    fn default() -> Self {
+        Self {
+            period: 10,
+            multiplier: 3.0,
+        }
+    }
+}
+
+/// Supertrend over anchor's highs, lows and closes, emitted at `{anchor}_supertrend`, alongside
+/// its direction at `{anchor}_supertrend_dir`, being 1 on uptrends and -1 on downtrends.
+///
+/// Bands are offset from bar's median price by `multiplier` times ATR, Wilder smoothed over
+/// `period` bars, and only tighten while close stays within them. Supertrend follows lower band
+/// on uptrends and upper band on downtrends, flipping once close crosses the followed band.
+/// Direction starts off as a downtrend, at bar `period - 1`, previous rows being null.
+#[derive(Clone, Debug)]
+pub struct SupertrendIndicator {
+    pub name: &'static str,
+    pub period: usize,
+    pub multiplier: f64,
+    pub high_col: String,
+    pub low_col: String,
+    pub close_col: String,
+    pub supertrend_col: String,
+    pub direction_col: String,
+    columns: Vec<(String, DataType)>,
+}
+
+impl SupertrendIndicator {
+    pub fn new(symbols_pair: SymbolsPair, period: usize, multiplier: f64) -> Self {
+        let anchor = symbols_pair.anchor;
+        let (_, high_col, low_col, close_col) = anchor.get_ohlc_cols();
+        let supertrend_col = get_supertrend_col(anchor.name);
+        let direction_col = get_supertrend_dir_col(anchor.name);
+        let columns = vec![
+            (supertrend_col.clone(), DataType::Float64),
+            (direction_col.clone(), DataType::Int32),
+        ];
+        Self {
+            name: NAME,
+            period,
+            multiplier,
+            high_col: high_col.to_string(),
+            low_col: low_col.to_string(),
+            close_col: close_col.to_string(),
+            supertrend_col,
+            direction_col,
+            columns,
+        }
+    }
+
+    /// Keeps supertrend and direction values before `first_pending_index`, calculating the
+    /// remaining ones.
+    ///
+    /// ATR and the band not being followed aren't emitted, so they're replayed over the kept rows,
+    /// while kept supertrend and direction are carried forward as the prior band and direction.
+    fn calculate_columns(
+        &self,
+        df: &DataFrame,
+        first_pending_index: usize,
+    ) -> Result<[Series; 2], GlowError> {
+        let highs = get_source_values(df, &self.high_col)?;
+        let lows = get_source_values(df, &self.low_col)?;
+        let closes = get_source_values(df, &self.close_col)?;
+        let kept_supertrends: Vec<Option<f64>> = if first_pending_index == 0 {
+            vec![]
+        } else {
+            df.column(&self.supertrend_col)?
+                .f64()?
+                .into_iter()
+                .take(first_pending_index)
+                .collect()
+        };
+        let kept_directions: Vec<Option<i32>> = if first_pending_index == 0 {
+            vec![]
+        } else {
+            df.column(&self.direction_col)?
+                .cast(&DataType::Int32)?
+                .i32()?
+                .into_iter()
+                .take(first_pending_index)
+                .collect()
+        };
+        let mut supertrend_values = kept_supertrends.clone();
+        let mut direction_values = kept_directions.clone();
+
+        let mut state = SupertrendState::new(self.period, self.multiplier);
+        for index in 0..df.height() {
+            let (Some(high), Some(low), Some(close)) = (highs[index], lows[index], closes[index])
+            else {
+                if index >= first_pending_index {
+                    supertrend_values.push(None);
+                    direction_values.push(None);
+                }
+                continue;
+            };
+            let result = state.next((high, low, close));
+            if index < first_pending_index {
+                if let (Some(supertrend), Some(direction)) =
+                    (kept_supertrends[index], kept_directions[index])
+                {
+                    state.carry(supertrend, direction);
+                }
+                continue;
+            }
+            let (supertrend, direction) = match result {
+                Some((supertrend, direction)) => (Some(supertrend), Some(direction)),
+                None => (None, None),
+            };
+            supertrend_values.push(supertrend);
+            direction_values.push(direction);
+        }
+
+        Ok([
+            Series::new(&self.supertrend_col, supertrend_values),
+            Series::new(&self.direction_col, direction_values),
+        ])
+    }
+}
+
+pub fn get_supertrend_col(symbol: &str) -> String {
+    format!("{}_supertrend", symbol)
+}
+
+pub fn get_supertrend_dir_col(symbol: &str) -> String {
+    format!("{}_supertrend_dir", symbol)
+}
+
+fn get_source_values(df: &DataFrame, column: &str) -> Result<Vec<Option<f64>>, GlowError> {
+    let series = df.column(column)?.cast(&DataType::Float64)?;
+    let values = series.f64()?.into_iter().collect();
+    Ok(values)
+}
+
+#[derive(Clone, Copy, Debug)]
+struct SupertrendState {
+    multiplier: f64,
+    previous_close: Option<f64>,
+    true_range: WilderAverage,
+    bands: Option<(f64, f64)>, // (final upper band, final lower band)
+    direction: i32,
+}
+
+impl SupertrendState {
+    fn new(period: usize, multiplier: f64) -> Self {
+        Self {
+            multiplier,
+            previous_close: None,
+            true_range: WilderAverage::new(period),
+            bands: None,
+            direction: -1,
+        }
+    }
+
+    /// Takes bar's (high, low, close), returning its (supertrend, direction), if already available.
+    fn next(&mut self, bar: (f64, f64, f64)) -> Option<(f64, i32)> {
+        let (high, low, close) = bar;
+        let true_range = match self.previous_close {
+            Some(previous_close) => (high - low)
+                .max((high - previous_close).abs())
+                .max((low - previous_close).abs()),
+            None => high - low,
+        };
+        let previous_close = self.previous_close.replace(close);
+        let atr = self.true_range.next(true_range)?;
+
+        let median_price = (high + low) / 2.0;
+        let basic_upper_band = median_price + self.multiplier * atr;
+        let basic_lower_band = median_price - self.multiplier * atr;
+        let (upper_band, lower_band) = match (self.bands, previous_close) {
+            (Some((previous_upper_band, previous_lower_band)), Some(previous_close)) => (
+                if basic_upper_band < previous_upper_band || previous_close > previous_upper_band {
+                    basic_upper_band
+                } else {
+                    previous_upper_band
+                },
+                if basic_lower_band > previous_lower_band || previous_close < previous_lower_band {
+                    basic_lower_band
+                } else {
+                    previous_lower_band
+                },
+            ),
+            _ => (basic_upper_band, basic_lower_band),
+        };
+        if self.bands.is_some() {
+            self.direction = match self.direction {
+                -1 if close > upper_band => 1,
+                1 if close < lower_band => -1,
+                direction => direction,
+            };
+        }
+        self.bands = Some((upper_band, lower_band));
+
+        let supertrend = if self.direction == 1 {
+            lower_band
+        } else {
+            upper_band
+        };
+        Some((supertrend, self.direction))
+    }
+
+    /// Overrides direction and followed band by already emitted `supertrend` and `direction`.
+    fn carry(&mut self, supertrend: f64, direction: i32) {
+        self.direction = direction;
+        self.bands = self.bands.map(|(upper_band, lower_band)| {
+            if direction == 1 {
+                (upper_band, supertrend)
+            } else {
+                (supertrend, lower_band)
+            }
+        });
+    }
+}
+
+impl Indicator for SupertrendIndicator {
+    type Params = SupertrendParams;
+    type Wrapper = IndicatorWrapper;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_indicator_columns(&self) -> &Vec<(String, DataType)> {
+        &self.columns
+    }
+
+    fn set_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        if self.period == 0 {
+            return Err(GlowError::new(
+                String::from("Invalid Supertrend Period"),
+                String::from("supertrend period must be at least 1"),
+            ));
+        }
+        let mut df = lf.collect()?;
+        for series in self.calculate_columns(&df, 0)? {
+            df.with_column(series)?;
+        }
+
+        Ok(df.lazy())
+    }
+
+    /// Calculates only rows appended after the last computed supertrend, carrying prior band and
+    /// direction over them. If no prior value exists, whole columns are recomputed.
+    fn update_indicator_columns(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        let last_valid_index = get_last_valid_index(df, &self.supertrend_col)?;
+        if last_valid_index.is_none() {
+            let result_df = self.set_indicator_columns(df.clone().lazy())?.collect()?;
+            return Ok(result_df);
+        }
+        let first_pending_index = last_valid_index.unwrap() + 1;
+        if first_pending_index >= df.height() {
+            return Ok(df.clone());
+        }
+
+        let mut result_df = df.clone();
+        for series in self.calculate_columns(df, first_pending_index)? {
+            result_df.with_column(series)?;
+        }
+
+        Ok(result_df)
+    }
+
+    fn get_minimum_klines_for_benchmarking(&self) -> u32 {
+        (self.period + 1) as u32
+    }
+
+    fn patch_params(&self, params: Self::Params) -> Result<Self::Wrapper, GlowError> {
+        let mut updated = self.clone();
+        updated.period = params.period;
+        updated.multiplier = params.multiplier;
+        Ok(updated.into())
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let updated = Self::new(updated_symbols_pair, self.period, self.multiplier);
+        Ok(updated.into())
+    }
+}
@@ -0,0 +1,1209 @@
+use super::{
+    adx::AdxIndicator,
+    cache::{get_indicator_cache_key, get_indicator_inputs_df, IndicatorCache},
+    donchian::DonchianIndicator,
+    ema::EmaIndicator,
+    heikin_ashi::HeikinAshiIndicator,
+    higher_timeframe::HigherTimeframeIndicator,
+    obv::ObvIndicator,
+    order_book_imbalance::OrderBookImbalanceIndicator,
+    realized_volatility::RealizedVolatilityIndicator,
+    rolling_correlation::RollingCorrelationIndicator,
+    rsi::RsiIndicator,
+    smoothing::{get_smoothed_close_col, SmoothingMethod, SmoothingPreIndicator},
+    spread::{SpreadIndicator, SpreadKind},
+    supertrend::SupertrendIndicator,
+    zscore::ZScoreIndicator,
+    IndicatorWrapper,
+};
+use crate::{r#static::INDICATORS_CACHE, signals::supertrend::SupertrendSignal};
+use common::{
+    enums::{granularity::Granularity, signal_category::SignalCategory, symbol_id::SymbolId},
+    r#static::ORDER_BOOK_DEPTHS,
+    structs::{OrderBookDepth, SymbolsPair},
+    traits::{indicator::Indicator, signal::Signal},
+};
+use polars::prelude::*;
+
+const TOLERANCE: f64 = 1e-9;
+
+fn get_test_closes(length: usize) -> Vec<f64> {
+    (0..length)
+        .map(|index| {
+            let index = index as f64;
+            100.0 + (index * 0.7).sin() * 5.0 + index * 0.1
+        })
+        .collect()
+}
+
+fn assert_columns_match(left: &DataFrame, right: &DataFrame, column: &str) {
+    let left_values = left.column(column).unwrap().f64().unwrap();
+    let right_values = right.column(column).unwrap().f64().unwrap();
+    assert_eq!(left_values.len(), right_values.len());
+    for (index, (left_value, right_value)) in left_values
+        .into_iter()
+        .zip(right_values.into_iter())
+        .enumerate()
+    {
+        match (left_value, right_value) {
+            (Some(left_value), Some(right_value)) => assert!(
+                (left_value - right_value).abs() < TOLERANCE,
+                "{} differs at index {}: {} != {}",
+                column,
+                index,
+                left_value,
+                right_value
+            ),
+            (None, None) => {}
+            _ => panic!("{} nullability differs at index {}", column, index),
+        }
+    }
+}
+
+/// Runs full calculation over the first `initial_length` rows, appends the remainder with
+/// nulls at indicator columns, and calls `update_indicator_columns` on the stacked frame.
+fn calculate_incrementally<I: Indicator>(
+    indicator: &I,
+    df: &DataFrame,
+    initial_length: usize,
+) -> DataFrame {
+    let initial_df = indicator
+        .set_indicator_columns(df.slice(0, initial_length).lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+    let mut appended_df = df.slice(initial_length as i64, df.height() - initial_length);
+    for (column, dtype) in indicator.get_indicator_columns() {
+        let nulls = Series::full_null(column, appended_df.height(), dtype);
+        appended_df.with_column(nulls).unwrap();
+    }
+    let stacked_df = initial_df.vstack(&appended_df).unwrap();
+    indicator.update_indicator_columns(&stacked_df).unwrap()
+}
+
+#[test]
+fn test_ema_incremental_update_matches_full_recompute() {
+    let symbols_pair = SymbolsPair::default();
+    let close_col = symbols_pair.anchor.get_close_col();
+    let df = df!(close_col => get_test_closes(120)).unwrap();
+
+    let indicator = EmaIndicator::from_anchor_close(symbols_pair, 21, "fast_ema");
+    let full_df = indicator
+        .set_indicator_columns(df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+
+    for initial_length in [1, 50, 119] {
+        let updated_df = calculate_incrementally(&indicator, &df, initial_length);
+        assert_columns_match(&full_df, &updated_df, &indicator.output_col);
+    }
+}
+
+#[test]
+fn test_ema_null_bars_decay_weight_unless_ignored() {
+    let symbols_pair = SymbolsPair::default();
+    let close_col = symbols_pair.anchor.get_close_col();
+    let closes = [
+        Some(10.0),
+        None,
+        None,
+        Some(20.0),
+        Some(30.0),
+        None,
+        Some(10.0),
+    ];
+    let df = df!(close_col => closes).unwrap();
+    // alpha = 2 / (3 + 1) = 0.5, so each null bar halves previous value's weight
+    let decayed = EmaIndicator::from_anchor_close(symbols_pair, 3, "ema");
+    assert!(!decayed.ignore_nulls);
+    let ignoring = EmaIndicator::new(
+        symbols_pair,
+        3,
+        close_col.to_string(),
+        decayed.output_col.clone(),
+        true,
+    );
+    let cases = [
+        (decayed, 10.0 + (20.0 - 10.0) * 0.5 / (0.125 + 0.5)),
+        (ignoring, 15.0),
+    ];
+
+    for (indicator, expected_after_gap) in cases {
+        let full_df = indicator
+            .set_indicator_columns(df.clone().lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+        let values = full_df
+            .column(&indicator.output_col)
+            .unwrap()
+            .f64()
+            .unwrap();
+        // null bars carry previous value over
+        assert_eq!(values.get(2), Some(10.0));
+        assert!((values.get(3).unwrap() - expected_after_gap).abs() < TOLERANCE);
+
+        for initial_length in 1..df.height() {
+            let updated_df = calculate_incrementally(&indicator, &df, initial_length);
+            assert_columns_match(&full_df, &updated_df, &indicator.output_col);
+        }
+    }
+}
+
+#[test]
+fn test_ema_over_custom_source_column() {
+    let symbols_pair = SymbolsPair::default();
+    let rsi_col = format!("{}_rsi", symbols_pair.anchor.name);
+    let rsi_values = vec![50.0, 55.0, 60.0, 40.0];
+    let df = df!(&rsi_col => rsi_values).unwrap();
+
+    let output_col = format!("{}_rsi_ema", symbols_pair.anchor.name);
+    let indicator = EmaIndicator::new(symbols_pair, 3, rsi_col, output_col.clone(), true);
+    let result_df = indicator
+        .set_indicator_columns(df.lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+
+    // alpha = 2 / (3 + 1) = 0.5
+    let expected = [50.0, 52.5, 56.25, 48.125];
+    let result: Vec<f64> = result_df
+        .column(&output_col)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+    for (value, expected) in result.iter().zip(expected.iter()) {
+        assert!((value - expected).abs() < TOLERANCE);
+    }
+}
+
+#[test]
+fn test_zscore_warmup_and_flat_windows_are_null() {
+    let symbols_pair = SymbolsPair::default();
+    let volume_col = format!("{}_volume", symbols_pair.anchor.name);
+    let volumes: Vec<i64> = vec![1, 2, 3, 4, 4, 4, 4, 10];
+    let df = df!(&volume_col => volumes).unwrap();
+
+    let indicator = ZScoreIndicator::new(symbols_pair, 3, volume_col.clone());
+    assert_eq!(indicator.output_col, format!("{}_zscore", volume_col));
+    let result_df = indicator
+        .set_indicator_columns(df.lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+    let result: Vec<Option<f64>> = result_df
+        .column(&indicator.output_col)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect();
+
+    // windows [1, 2, 3], [2, 3, 4] and [3, 4, 4] yield 1.0, 1.0 and 1 / sqrt(3)
+    // [4, 4, 4] has no deviation, [4, 4, 10] yields 2 / sqrt(3)
+    let expected = [
+        None,
+        None,
+        Some(1.0),
+        Some(1.0),
+        Some(1.0 / 3.0_f64.sqrt()),
+        None,
+        None,
+        Some(2.0 / 3.0_f64.sqrt()),
+    ];
+    assert_eq!(result.len(), expected.len());
+    for (index, (value, expected)) in result.iter().zip(expected.iter()).enumerate() {
+        match (value, expected) {
+            (Some(value), Some(expected)) => assert!(
+                (value - expected).abs() < TOLERANCE,
+                "z-score differs at index {}: {} != {}",
+                index,
+                value,
+                expected
+            ),
+            (None, None) => {}
+            _ => panic!(
+                "z-score nullability differs at index {}: {:?}",
+                index, value
+            ),
+        }
+    }
+}
+
+#[test]
+fn test_zscore_incremental_update_matches_full_recompute() {
+    let symbols_pair = SymbolsPair::default();
+    let close_col = symbols_pair.anchor.get_close_col();
+    let df = df!(&close_col => get_test_closes(120)).unwrap();
+
+    let indicator = ZScoreIndicator::new(symbols_pair, 20, close_col.to_string());
+    let full_df = indicator
+        .set_indicator_columns(df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+
+    for initial_length in [1, 10, 50, 119] {
+        let updated_df = calculate_incrementally(&indicator, &df, initial_length);
+        assert_columns_match(&full_df, &updated_df, &indicator.output_col);
+    }
+}
+
+#[test]
+fn test_zscore_rejects_window_shorter_than_two() {
+    let symbols_pair = SymbolsPair::default();
+    let close_col = symbols_pair.anchor.get_close_col();
+    let df = df!(&close_col => get_test_closes(5)).unwrap();
+
+    let indicator = ZScoreIndicator::new(symbols_pair, 1, close_col.to_string());
+    assert!(indicator.set_indicator_columns(df.lazy()).is_err());
+}
+
+#[test]
+fn test_obv_accumulates_volume_signed_by_close_direction() {
+    let symbols_pair = SymbolsPair::default();
+    let close_col = symbols_pair.anchor.get_close_col();
+    let volume_col = format!("{}_volume", symbols_pair.anchor.name);
+    let df = df!(
+        close_col => [10.0, 11.0, 11.0, 9.0, 12.0],
+        &volume_col => [5.0, 3.0, 4.0, 2.0, 6.0]
+    )
+    .unwrap();
+
+    let indicator = ObvIndicator::new(symbols_pair);
+    let result_df = indicator
+        .set_indicator_columns(df.lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+    let result: Vec<f64> = result_df
+        .column(&indicator.output_col)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+
+    assert_eq!(result, vec![0.0, 3.0, 3.0, 1.0, 7.0]);
+}
+
+#[test]
+fn test_obv_incremental_update_matches_full_recompute() {
+    let symbols_pair = SymbolsPair::default();
+    let close_col = symbols_pair.anchor.get_close_col();
+    let volume_col = format!("{}_volume", symbols_pair.anchor.name);
+    let volumes: Vec<f64> = (0..120).map(|index| 100.0 + (index % 7) as f64).collect();
+    let df = df!(close_col => get_test_closes(120), &volume_col => volumes).unwrap();
+
+    let indicator = ObvIndicator::new(symbols_pair);
+    let full_df = indicator
+        .set_indicator_columns(df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+
+    for initial_length in [1, 50, 119] {
+        let updated_df = calculate_incrementally(&indicator, &df, initial_length);
+        assert_columns_match(&full_df, &updated_df, &indicator.output_col);
+    }
+}
+
+#[test]
+fn test_obv_errors_without_volume_column() {
+    let symbols_pair = SymbolsPair::default();
+    let close_col = symbols_pair.anchor.get_close_col();
+    let df = df!(close_col => get_test_closes(5)).unwrap();
+
+    let indicator = ObvIndicator::new(symbols_pair);
+    assert!(indicator.set_indicator_columns(df.clone().lazy()).is_err());
+    assert!(indicator.update_indicator_columns(&df).is_err());
+}
+
+#[test]
+fn test_donchian_incremental_update_drops_bars_leaving_window() {
+    let symbols_pair = SymbolsPair::default();
+    let (_, high_col, low_col, _) = symbols_pair.anchor.get_ohlc_cols();
+    let df = df!(
+        high_col => [5.0, 1.0, 2.0, 1.0, 1.0, 3.0],
+        low_col => [0.5, 0.9, 1.5, 0.8, 0.9, 2.0]
+    )
+    .unwrap();
+
+    let indicator = DonchianIndicator::new(symbols_pair, 3);
+    let updated_df = calculate_incrementally(&indicator, &df, 3);
+    let upper: Vec<Option<f64>> = updated_df
+        .column(&indicator.upper_col)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect();
+    let lower: Vec<Option<f64>> = updated_df
+        .column(&indicator.lower_col)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect();
+
+    // 5.0 high and 0.5 low leave the window at fourth bar
+    assert_eq!(
+        upper,
+        vec![None, None, Some(5.0), Some(2.0), Some(2.0), Some(3.0)]
+    );
+    assert_eq!(
+        lower,
+        vec![None, None, Some(0.5), Some(0.8), Some(0.8), Some(0.8)]
+    );
+}
+
+#[test]
+fn test_donchian_incremental_update_matches_full_recompute() {
+    let symbols_pair = SymbolsPair::default();
+    let (_, high_col, low_col, _) = symbols_pair.anchor.get_ohlc_cols();
+    let closes = get_test_closes(120);
+    let highs: Vec<f64> = closes.iter().map(|close| close + 1.0).collect();
+    let lows: Vec<f64> = closes.iter().map(|close| close - 1.0).collect();
+    let df = df!(high_col => highs, low_col => lows).unwrap();
+
+    let indicator = DonchianIndicator::new(symbols_pair, 20);
+    let full_df = indicator
+        .set_indicator_columns(df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+
+    for initial_length in [1, 25, 50, 119] {
+        let updated_df = calculate_incrementally(&indicator, &df, initial_length);
+        assert_columns_match(&full_df, &updated_df, &indicator.upper_col);
+        assert_columns_match(&full_df, &updated_df, &indicator.lower_col);
+    }
+}
+
+#[test]
+fn test_realized_volatility_annualizes_log_returns_std() {
+    let symbols_pair = SymbolsPair::default();
+    let close_col = symbols_pair.anchor.get_close_col();
+    // log returns alternate between 0.01 and -0.01
+    let closes: Vec<f64> = (0..8)
+        .map(|index| 100.0 * if index % 2 == 0 { 1.0 } else { 0.01_f64.exp() })
+        .collect();
+    let df = df!(close_col => closes).unwrap();
+
+    let indicator = RealizedVolatilityIndicator::new(symbols_pair, 4, Granularity::d1);
+    assert_eq!(
+        indicator.output_col,
+        format!("{}_rv", symbols_pair.anchor.name)
+    );
+    let result_df = indicator
+        .set_indicator_columns(df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+    let result: Vec<Option<f64>> = result_df
+        .column(&indicator.output_col)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect();
+
+    // returns [0.01, -0.01, 0.01, -0.01] have sample std 0.02 / sqrt(3), annualized by sqrt(365)
+    let expected = 0.02 / 3.0_f64.sqrt() * 365.0_f64.sqrt();
+    assert!(result.iter().take(4).all(|value| value.is_none()));
+    for value in result.iter().skip(4) {
+        assert!((value.unwrap() - expected).abs() < TOLERANCE);
+    }
+
+    for initial_length in [1, 5, 7] {
+        let updated_df = calculate_incrementally(&indicator, &df, initial_length);
+        assert_columns_match(&result_df, &updated_df, &indicator.output_col);
+    }
+}
+
+#[test]
+fn test_higher_timeframe_values_are_carried_until_next_bucket_closes() {
+    let symbols_pair = SymbolsPair::default();
+    let (open_col, high_col, low_col, close_col) = symbols_pair.anchor.get_ohlc_cols();
+    let length = 150;
+    let start_times: Vec<i64> = (0..length)
+        .map(|index| 1_704_067_200_000 + index as i64 * 60_000)
+        .collect();
+    let closes = get_test_closes(length);
+    let df = df!(
+        "start_time" => start_times,
+        open_col => &closes,
+        high_col => &closes,
+        low_col => &closes,
+        close_col => &closes
+    )
+    .unwrap()
+    .lazy()
+    .with_column(col("start_time").cast(DataType::Datetime(TimeUnit::Milliseconds, None)))
+    .collect()
+    .unwrap();
+
+    // a single period EMA equals each hourly bucket's close
+    let ema = EmaIndicator::from_anchor_close(symbols_pair, 1, "ema");
+    let indicator =
+        HigherTimeframeIndicator::new(symbols_pair, Granularity::m1, Granularity::h1, ema.into());
+    let output_col = format!("{}_ema_60m", symbols_pair.anchor.name);
+    assert_eq!(indicator.get_indicator_columns()[0].0, output_col);
+    let full_df = indicator
+        .set_indicator_columns(df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+    let values: Vec<Option<f64>> = full_df
+        .column(&output_col)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect();
+
+    // first bucket closes with the bar starting at minute 59, second one at minute 119
+    assert!(values.iter().take(59).all(|value| value.is_none()));
+    for (index, value) in values.iter().enumerate().skip(59) {
+        let expected = if index < 119 { closes[59] } else { closes[119] };
+        assert!((value.unwrap() - expected).abs() < TOLERANCE);
+    }
+
+    for initial_length in [30, 60, 100, 119, 120] {
+        let updated_df = calculate_incrementally(&indicator, &df, initial_length);
+        assert_columns_match(&full_df, &updated_df, &output_col);
+    }
+    // bars appended within the same bucket carry its value over
+    let updated_df = calculate_incrementally(&indicator, &df.slice(0, 110), 70);
+    assert_columns_match(&full_df.slice(0, 110), &updated_df, &output_col);
+}
+
+fn get_spread_test_df(symbols_pair: SymbolsPair, length: usize) -> DataFrame {
+    let anchor_closes = get_test_closes(length);
+    let traded_closes: Vec<f64> = anchor_closes
+        .iter()
+        .map(|close| close / 2.0 + 1.0)
+        .collect();
+    df!(
+        symbols_pair.anchor.get_close_col() => anchor_closes,
+        symbols_pair.traded.get_close_col() => traded_closes
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_spread_kinds_compare_anchor_and_traded_closes() {
+    let symbols_pair = SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Ethereum);
+    let df = df!(
+        symbols_pair.anchor.get_close_col() => [Some(100.0), Some(90.0), None],
+        symbols_pair.traded.get_close_col() => [Some(50.0), Some(60.0), Some(70.0)]
+    )
+    .unwrap();
+
+    for (kind, expected) in [
+        (SpreadKind::Difference, [50.0, 30.0]),
+        (SpreadKind::Ratio, [2.0, 1.5]),
+        (SpreadKind::LogRatio, [2.0_f64.ln(), 1.5_f64.ln()]),
+    ] {
+        let indicator = SpreadIndicator::new(symbols_pair, kind);
+        assert_eq!(indicator.output_col, "BTCUSDT_ETHUSDT_spread");
+        let result_df = indicator
+            .set_indicator_columns(df.clone().lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+        let values: Vec<Option<f64>> = result_df
+            .column(&indicator.output_col)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert!((values[0].unwrap() - expected[0]).abs() < TOLERANCE);
+        assert!((values[1].unwrap() - expected[1]).abs() < TOLERANCE);
+        assert_eq!(values[2], None);
+    }
+}
+
+#[test]
+fn test_spread_incremental_update_matches_full_recompute() {
+    let symbols_pair = SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Ethereum);
+    let df = get_spread_test_df(symbols_pair, 60);
+
+    let indicator = SpreadIndicator::new(symbols_pair, SpreadKind::LogRatio);
+    let full_df = indicator
+        .set_indicator_columns(df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+
+    for initial_length in [1, 30, 59] {
+        let updated_df = calculate_incrementally(&indicator, &df, initial_length);
+        assert_columns_match(&full_df, &updated_df, &indicator.output_col);
+    }
+}
+
+/// Closes compounding `returns` off 100, so that their close to close returns are exactly them.
+fn get_compounded_closes(returns: &[f64]) -> Vec<f64> {
+    let mut close = 100.0;
+    let mut closes = vec![close];
+    for value in returns {
+        close *= 1.0 + value;
+        closes.push(close);
+    }
+    closes
+}
+
+#[test]
+fn test_rolling_correlation_of_linearly_related_returns() {
+    let symbols_pair = SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Ethereum);
+    let window = 10;
+    let indicator = RollingCorrelationIndicator::new(symbols_pair, window);
+    assert_eq!(indicator.output_col, "BTCUSDT_ETHUSDT_corr");
+    let anchor_returns: Vec<f64> = (0..49)
+        .map(|index| 0.01 * (index as f64 * 0.9).sin())
+        .collect();
+    let get_correlations = |traded_returns: Vec<f64>| -> Vec<Option<f64>> {
+        let df = df!(
+            symbols_pair.anchor.get_close_col() => get_compounded_closes(&anchor_returns),
+            symbols_pair.traded.get_close_col() => get_compounded_closes(&traded_returns)
+        )
+        .unwrap();
+        indicator
+            .set_indicator_columns(df.lazy())
+            .unwrap()
+            .collect()
+            .unwrap()
+            .column(&indicator.output_col)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect()
+    };
+
+    for (traded_returns, expected) in [
+        (
+            anchor_returns
+                .iter()
+                .map(|value| 0.5 * value + 0.001)
+                .collect(),
+            1.0,
+        ),
+        (
+            anchor_returns.iter().map(|value| -2.0 * value).collect(),
+            -1.0,
+        ),
+    ] {
+        let correlations = get_correlations(traded_returns);
+        assert_eq!(correlations.len(), 50);
+        // first window rows lack a full window of returns
+        assert!(correlations[..window].iter().all(|value| value.is_none()));
+        for value in correlations[window..].iter() {
+            assert!((value.unwrap() - expected).abs() < 1e-6, "{:?}", value);
+        }
+    }
+
+    // flat traded closes have no returns' variance, so their correlation is undefined
+    let correlations = get_correlations(vec![0.0; 49]);
+    assert!(correlations.iter().all(|value| value.is_none()));
+}
+
+#[test]
+fn test_rolling_correlation_incremental_update_matches_full_recompute() {
+    let symbols_pair = SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Ethereum);
+    let length = 60;
+    let anchor_closes = get_test_closes(length);
+    let traded_closes: Vec<f64> = (0..length)
+        .map(|index| 50.0 + (index as f64 * 0.3).cos() * 2.0)
+        .collect();
+    let df = df!(
+        symbols_pair.anchor.get_close_col() => anchor_closes,
+        symbols_pair.traded.get_close_col() => traded_closes
+    )
+    .unwrap();
+
+    let indicator = RollingCorrelationIndicator::new(symbols_pair, 14);
+    let full_df = indicator
+        .set_indicator_columns(df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+
+    for initial_length in [1, 15, 30, 59] {
+        let updated_df = calculate_incrementally(&indicator, &df, initial_length);
+        assert_columns_match(&full_df, &updated_df, &indicator.output_col);
+    }
+}
+
+fn get_adx_test_df(symbols_pair: SymbolsPair, length: usize) -> DataFrame {
+    let (_, high_col, low_col, close_col) = symbols_pair.anchor.get_ohlc_cols();
+    let closes = get_test_closes(length);
+    let highs: Vec<f64> = closes
+        .iter()
+        .enumerate()
+        .map(|(index, close)| close + 1.0 + (index % 3) as f64 * 0.2)
+        .collect();
+    let lows: Vec<f64> = closes
+        .iter()
+        .enumerate()
+        .map(|(index, close)| close - 1.0 - (index % 4) as f64 * 0.3)
+        .collect();
+    df!(high_col => highs, low_col => lows, close_col => closes).unwrap()
+}
+
+#[test]
+fn test_adx_incremental_update_matches_full_recompute() {
+    let symbols_pair = SymbolsPair::default();
+    let df = get_adx_test_df(symbols_pair, 150);
+
+    let indicator = AdxIndicator::new(symbols_pair, 14);
+    let full_df = indicator
+        .set_indicator_columns(df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+
+    for initial_length in [1, 14, 27, 28, 80, 149] {
+        let updated_df = calculate_incrementally(&indicator, &df, initial_length);
+        assert_columns_match(&full_df, &updated_df, &indicator.adx_col);
+        assert_columns_match(&full_df, &updated_df, &indicator.plus_di_col);
+        assert_columns_match(&full_df, &updated_df, &indicator.minus_di_col);
+    }
+}
+
+#[test]
+fn test_adx_rises_with_steady_trend() {
+    let symbols_pair = SymbolsPair::default();
+    let (_, high_col, low_col, close_col) = symbols_pair.anchor.get_ohlc_cols();
+    let closes: Vec<f64> = (0..10).map(|index| 100.0 + index as f64).collect();
+    let highs: Vec<f64> = closes.iter().map(|close| close + 0.5).collect();
+    let lows: Vec<f64> = closes.iter().map(|close| close - 0.5).collect();
+    let df = df!(high_col => highs, low_col => lows, close_col => closes).unwrap();
+
+    let indicator = AdxIndicator::new(symbols_pair, 3);
+    let result_df = indicator
+        .set_indicator_columns(df.lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+    let adx = result_df.column(&indicator.adx_col).unwrap().f64().unwrap();
+    let plus_di = result_df
+        .column(&indicator.plus_di_col)
+        .unwrap()
+        .f64()
+        .unwrap();
+    let minus_di = result_df
+        .column(&indicator.minus_di_col)
+        .unwrap()
+        .f64()
+        .unwrap();
+
+    // DIs take `period` bars after the first one, ADX another `period - 1`
+    assert_eq!(plus_di.get(2), None);
+    assert!(plus_di.get(3).is_some());
+    assert_eq!(adx.get(4), None);
+    // every bar moves up by 1.0 over a 1.5 true range, with no downward movement
+    let expected_plus_di = 100.0 / 1.5;
+    for index in 5..10 {
+        assert!((plus_di.get(index).unwrap() - expected_plus_di).abs() < TOLERANCE);
+        assert_eq!(minus_di.get(index), Some(0.0));
+        assert!((adx.get(index).unwrap() - 100.0).abs() < TOLERANCE);
+    }
+}
+
+#[test]
+fn test_heikin_ashi_incremental_update_carries_previous_candle() {
+    let symbols_pair = SymbolsPair::default();
+    let (open_col, high_col, low_col, _) = symbols_pair.anchor.get_ohlc_cols();
+    let mut df = get_adx_test_df(symbols_pair, 60);
+    let closes = get_test_closes(60);
+    let opens: Vec<f64> = (0..60)
+        .map(|index| if index == 0 { 99.0 } else { closes[index - 1] })
+        .collect();
+    df.with_column(Series::new(open_col, opens.clone()))
+        .unwrap();
+
+    let indicator = HeikinAshiIndicator::new(symbols_pair);
+    let full_df = indicator
+        .set_indicator_columns(df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+
+    let ha_open = full_df
+        .column(&indicator.ha_open_col)
+        .unwrap()
+        .f64()
+        .unwrap();
+    let ha_close = full_df
+        .column(&indicator.ha_close_col)
+        .unwrap()
+        .f64()
+        .unwrap();
+    let highs = df.column(high_col).unwrap().f64().unwrap();
+    let lows = df.column(low_col).unwrap().f64().unwrap();
+    // first HA open is seeded by first bar's open and close, the next ones by previous HA candle
+    assert!((ha_open.get(0).unwrap() - (opens[0] + closes[0]) / 2.0).abs() < TOLERANCE);
+    for index in 1..60 {
+        let expected_ha_open =
+            (ha_open.get(index - 1).unwrap() + ha_close.get(index - 1).unwrap()) / 2.0;
+        assert!((ha_open.get(index).unwrap() - expected_ha_open).abs() < TOLERANCE);
+        let expected_ha_close =
+            (opens[index] + highs.get(index).unwrap() + lows.get(index).unwrap() + closes[index])
+                / 4.0;
+        assert!((ha_close.get(index).unwrap() - expected_ha_close).abs() < TOLERANCE);
+    }
+
+    for initial_length in [1, 2, 30, 59] {
+        let updated_df = calculate_incrementally(&indicator, &df, initial_length);
+        for (column, _) in indicator.get_indicator_columns() {
+            assert_columns_match(&full_df, &updated_df, column);
+        }
+    }
+}
+
+#[test]
+fn test_supertrend_flips_direction_on_appended_bars() {
+    let symbols_pair = SymbolsPair::default();
+    let (_, high_col, low_col, close_col) = symbols_pair.anchor.get_ohlc_cols();
+    // prices rise, fall and then rise again
+    let closes: Vec<f64> = (0..90)
+        .map(|index| match index {
+            0..=29 => 100.0 + index as f64,
+            30..=59 => 129.0 - (index - 29) as f64 * 1.5,
+            _ => 84.0 + (index - 59) as f64 * 2.0,
+        })
+        .collect();
+    let highs: Vec<f64> = closes.iter().map(|close| close + 1.0).collect();
+    let lows: Vec<f64> = closes.iter().map(|close| close - 1.0).collect();
+    let df = df!(high_col => highs, low_col => lows, close_col => closes).unwrap();
+
+    let indicator = SupertrendIndicator::new(symbols_pair, 5, 2.0);
+    let full_df = indicator
+        .set_indicator_columns(df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+    let full_directions: Vec<Option<i32>> = full_df
+        .column(&indicator.direction_col)
+        .unwrap()
+        .i32()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(full_directions[3], None);
+    assert_eq!(full_directions[4], Some(-1));
+    let up_flips: Vec<usize> = (1..90)
+        .filter(|index| {
+            full_directions[index - 1] == Some(-1) && full_directions[*index] == Some(1)
+        })
+        .collect();
+    let down_flips: Vec<usize> = (1..90)
+        .filter(|index| {
+            full_directions[index - 1] == Some(1) && full_directions[*index] == Some(-1)
+        })
+        .collect();
+    assert_eq!(up_flips.len(), 2);
+    assert_eq!(down_flips.len(), 1);
+    assert!(up_flips[0] < down_flips[0] && down_flips[0] < up_flips[1]);
+
+    // last flip happens on bars appended after initial calculation
+    let last_flip = up_flips[1];
+    for initial_length in [1, 5, down_flips[0], last_flip - 1, last_flip] {
+        let updated_df = calculate_incrementally(&indicator, &df, initial_length);
+        assert_columns_match(&full_df, &updated_df, &indicator.supertrend_col);
+        let updated_directions: Vec<Option<i32>> = updated_df
+            .column(&indicator.direction_col)
+            .unwrap()
+            .i32()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(updated_directions, full_directions);
+
+        let signal = SupertrendSignal::new(symbols_pair, SignalCategory::GoLong).unwrap();
+        let signal_df = signal.update_signal_column(&updated_df).unwrap();
+        let go_longs: Vec<usize> = signal_df
+            .column(SignalCategory::GoLong.get_column())
+            .unwrap()
+            .i32()
+            .unwrap()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, value)| (value == Some(1)).then_some(index))
+            .collect();
+        assert_eq!(go_longs, up_flips);
+    }
+}
+
+#[test]
+fn test_rsi_incremental_update_matches_full_recompute() {
+    let symbols_pair = SymbolsPair::default();
+    let close_col = symbols_pair.anchor.get_close_col();
+    let df = df!(close_col => get_test_closes(120)).unwrap();
+
+    let indicator = RsiIndicator::new(symbols_pair, 14);
+    let full_df = indicator
+        .set_indicator_columns(df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+
+    for initial_length in [1, 14, 15, 50, 119] {
+        let updated_df = calculate_incrementally(&indicator, &df, initial_length);
+        for (column, _) in indicator.get_indicator_columns() {
+            assert_columns_match(&full_df, &updated_df, column);
+        }
+    }
+
+    // streaming rows one at a time keeps matching, as averages are carried from previous update
+    let mut streamed_df = indicator
+        .set_indicator_columns(df.slice(0, 30).lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+    for index in 30..df.height() {
+        let mut appended_df = df.slice(index as i64, 1);
+        for (column, dtype) in indicator.get_indicator_columns() {
+            appended_df
+                .with_column(Series::full_null(column, 1, dtype))
+                .unwrap();
+        }
+        let stacked_df = streamed_df.vstack(&appended_df).unwrap();
+        streamed_df = indicator.update_indicator_columns(&stacked_df).unwrap();
+    }
+    assert_columns_match(&full_df, &streamed_df, &indicator.rsi_col);
+}
+
+#[test]
+fn test_rsi_warmup_and_one_sided_moves() {
+    let symbols_pair = SymbolsPair::default();
+    let close_col = symbols_pair.anchor.get_close_col();
+    let df = df!(close_col => [1.0, 2.0, 3.0, 4.0, 3.0, 3.0]).unwrap();
+
+    let indicator = RsiIndicator::new(symbols_pair, 3);
+    let result_df = indicator
+        .set_indicator_columns(df.lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+    let rsi = result_df.column(&indicator.rsi_col).unwrap().f64().unwrap();
+
+    // first `period` bars lack enough changes
+    for index in 0..3 {
+        assert_eq!(rsi.get(index), None);
+    }
+    assert!((rsi.get(3).unwrap() - 100.0).abs() < TOLERANCE);
+    // avg gain 1 * 2 / 3, avg loss 1 / 3, so RS is 2
+    let expected_rsi = 100.0 - 100.0 / 3.0;
+    assert!((rsi.get(4).unwrap() - expected_rsi).abs() < TOLERANCE);
+    // flat bar decays both averages equally, keeping RSI
+    assert!((rsi.get(5).unwrap() - expected_rsi).abs() < TOLERANCE);
+}
+
+#[test]
+fn test_order_book_imbalance_is_null_over_benchmark_and_set_live() {
+    // symbol isn't used by other tests, so that its depth isn't overwritten
+    let symbols_pair = SymbolsPair::new(&SymbolId::Chainlink, &SymbolId::Chainlink);
+    let indicator = OrderBookImbalanceIndicator::new(symbols_pair);
+    assert!(IndicatorWrapper::from(indicator.clone()).is_live_only());
+    let closes = get_test_closes(3);
+    let df = df!(symbols_pair.anchor.get_close_col() => closes).unwrap();
+
+    let benchmark_df = indicator
+        .set_indicator_columns(df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+    let output_series = benchmark_df.column(&indicator.output_col).unwrap();
+    assert_eq!(output_series.null_count(), 3);
+
+    ORDER_BOOK_DEPTHS.write().unwrap().insert(
+        String::from("LINKUSDT"),
+        OrderBookDepth::new(
+            String::from("LINKUSDT"),
+            0,
+            vec![(15.0, 2.0)],
+            vec![(15.1, 6.0)],
+        ),
+    );
+    let live_df = indicator.update_indicator_columns(&benchmark_df).unwrap();
+    let imbalances: Vec<Option<f64>> = live_df
+        .column(&indicator.output_col)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect();
+    // only the bar just appended takes the latest depth, (2 - 6) / (2 + 6)
+    assert_eq!(imbalances, vec![None, None, Some(-0.5)]);
+
+    // recomputing, e.g. once strategy is patched, keeps live values
+    let recomputed_df = indicator
+        .set_indicator_columns(live_df.lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+    assert_eq!(
+        recomputed_df
+            .column(&indicator.output_col)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(2),
+        Some(-0.5)
+    );
+}
+
+#[test]
+fn test_smoothing_methods_denoise_close_spike() {
+    let symbols_pair = SymbolsPair::default();
+    let close_col = symbols_pair.anchor.get_close_col();
+    let df = df!(close_col => [10.0, 10.0, 40.0, 10.0, 10.0]).unwrap();
+    let output_col = get_smoothed_close_col(symbols_pair.anchor.name);
+
+    let expected_by_method = [
+        (
+            SmoothingMethod::Sma,
+            [None, None, Some(20.0), Some(20.0), Some(20.0)],
+        ),
+        (
+            SmoothingMethod::Median,
+            [None, None, Some(10.0), Some(10.0), Some(10.0)],
+        ),
+        // alpha = 2 / (3 + 1) = 0.5
+        (
+            SmoothingMethod::Ema,
+            [Some(10.0), Some(10.0), Some(25.0), Some(17.5), Some(13.75)],
+        ),
+    ];
+    for (method, expected) in expected_by_method {
+        let indicator = SmoothingPreIndicator::new(symbols_pair, method, 3);
+        let result_df = indicator
+            .set_indicator_columns(df.clone().lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+        let result: Vec<Option<f64>> = result_df
+            .column(&output_col)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(result.len(), expected.len());
+        for (value, expected) in result.iter().zip(expected.iter()) {
+            match (value, expected) {
+                (Some(value), Some(expected)) => {
+                    assert!((value - expected).abs() < TOLERANCE, "{:?}", method)
+                }
+                (None, None) => {}
+                _ => panic!("{:?} nullability differs: {:?}", method, result),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_smoothing_incremental_update_matches_full_recompute() {
+    let symbols_pair = SymbolsPair::default();
+    let close_col = symbols_pair.anchor.get_close_col();
+    let df = df!(close_col => get_test_closes(120)).unwrap();
+
+    for method in [
+        SmoothingMethod::Sma,
+        SmoothingMethod::Ema,
+        SmoothingMethod::Median,
+    ] {
+        let indicator = SmoothingPreIndicator::new(symbols_pair, method, 5);
+        let full_df = indicator
+            .set_indicator_columns(df.clone().lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+
+        for initial_length in [1, 50, 119] {
+            let updated_df = calculate_incrementally(&indicator, &df, initial_length);
+            assert_columns_match(&full_df, &updated_df, &indicator.output_col);
+        }
+    }
+}
+
+#[test]
+fn test_ema_over_smoothed_close() {
+    let symbols_pair = SymbolsPair::default();
+    let close_col = symbols_pair.anchor.get_close_col();
+    let df = df!(close_col => get_test_closes(60)).unwrap();
+
+    let smoothing = SmoothingPreIndicator::new(symbols_pair, SmoothingMethod::Sma, 5);
+    let ema = EmaIndicator::new(
+        symbols_pair,
+        9,
+        smoothing.output_col.clone(),
+        String::from("smoothed_ema"),
+        true,
+    );
+    let smoothed_lf = smoothing.set_indicator_columns(df.lazy()).unwrap();
+    let result_df = ema
+        .set_indicator_columns(smoothed_lf)
+        .unwrap()
+        .collect()
+        .unwrap();
+
+    let ema_values = result_df.column("smoothed_ema").unwrap().f64().unwrap();
+    // EMA is seeded by smoothed close's first value, set once its window is full
+    assert!(ema_values.get(3).is_none());
+    assert_eq!(
+        ema_values.get(4),
+        result_df
+            .column(&smoothing.output_col)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(4)
+    );
+    assert_eq!(ema_values.null_count(), 4);
+}
+
+#[test]
+fn test_indicator_cache_evicts_least_recently_used() {
+    let inputs_df = df!("close" => [1.0]).unwrap();
+    let columns_df = df!("value" => [1.0]).unwrap();
+    let mut cache = IndicatorCache::new(2);
+    cache.insert(1, inputs_df.clone(), columns_df.clone());
+    cache.insert(2, inputs_df.clone(), columns_df.clone());
+    assert!(cache.get(1, &inputs_df).is_some());
+
+    cache.insert(3, inputs_df.clone(), columns_df);
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get(2, &inputs_df).is_none());
+    assert!(cache.get(1, &inputs_df).is_some());
+    assert!(cache.get(3, &inputs_df).is_some());
+}
+
+#[test]
+fn test_indicator_cache_is_bounded_by_rows() {
+    let get_df = |column: &str, height: usize| df!(column => vec![1.0; height]).unwrap();
+    let mut cache = IndicatorCache::new(5);
+    cache.insert(1, get_df("close", 2), get_df("value", 2));
+    cache.insert(2, get_df("close", 2), get_df("value", 2));
+    assert_eq!(cache.rows(), 4);
+
+    // least recently used column set is evicted, until rows fit again
+    cache.insert(3, get_df("close", 3), get_df("value", 3));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.rows(), 5);
+    assert!(cache.get(1, &get_df("close", 2)).is_none());
+    assert!(cache.get(3, &get_df("close", 3)).is_some());
+
+    // replacing a column set doesn't count its previous rows
+    cache.insert(3, get_df("close", 1), get_df("value", 1));
+    assert_eq!(cache.rows(), 3);
+    // column sets taller than max rows aren't cached
+    cache.insert(4, get_df("close", 6), get_df("value", 6));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.rows(), 3);
+}
+
+#[test]
+fn test_indicator_cache_misses_colliding_key_over_different_inputs() {
+    let inputs_df = df!("close" => [1.0, 2.0]).unwrap();
+    let mut cache = IndicatorCache::new(2);
+    cache.insert(1, inputs_df.clone(), df!("value" => [1.0, 2.0]).unwrap());
+
+    assert!(cache.get(1, &inputs_df).is_some());
+    assert!(cache.get(1, &df!("close" => [1.0, 3.0]).unwrap()).is_none());
+    assert!(cache
+        .get(1, &df!("close" => [1.0, 2.0, 3.0]).unwrap())
+        .is_none());
+}
+
+#[test]
+fn test_cached_indicator_columns_are_keyed_by_config_and_data() {
+    let symbols_pair = SymbolsPair::default();
+    let close_col = symbols_pair.anchor.get_close_col();
+    let df = df!(close_col => get_test_closes(90)).unwrap();
+    let indicator: IndicatorWrapper =
+        EmaIndicator::from_anchor_close(symbols_pair, 13, "cached_ema").into();
+    let output_col = &indicator.get_indicator_columns()[0].0;
+
+    let uncached_df = indicator
+        .set_indicator_columns(df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+    let inputs_df = get_indicator_inputs_df(&indicator, &df).unwrap();
+    let key = get_indicator_cache_key(&indicator, &df).unwrap();
+    for _ in 0..2 {
+        let cached_df = indicator
+            .set_cached_indicator_columns(df.clone().lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_columns_match(&uncached_df, &cached_df, output_col);
+        assert!(INDICATORS_CACHE
+            .lock()
+            .unwrap()
+            .get(key, &inputs_df)
+            .is_some());
+    }
+
+    let other_indicator: IndicatorWrapper =
+        EmaIndicator::from_anchor_close(symbols_pair, 14, "cached_ema").into();
+    let other_df = df!(close_col => get_test_closes(91)).unwrap();
+    assert_ne!(key, get_indicator_cache_key(&other_indicator, &df).unwrap());
+    assert_ne!(key, get_indicator_cache_key(&indicator, &other_df).unwrap());
+
+    let mut unread_column_df = df.clone();
+    unread_column_df
+        .with_column(Series::new("unread", vec![0.0; 90]))
+        .unwrap();
+    assert_eq!(
+        key,
+        get_indicator_cache_key(&indicator, &unread_column_df).unwrap()
+    );
+}
+
+#[test]
+fn test_cached_indicator_columns_are_recomputed_over_same_fingerprint_other_inputs() {
+    let symbols_pair = SymbolsPair::default();
+    let close_col = symbols_pair.anchor.get_close_col();
+    let get_df = |closes: Vec<f64>| {
+        df!(
+            "start_time" => (0..closes.len() as i64).collect::<Vec<i64>>(),
+            close_col => closes
+        )
+        .unwrap()
+    };
+    let df = get_df(get_test_closes(30));
+    // last bar's close changed, as it does while it's still open
+    let mut closes = get_test_closes(30);
+    closes[29] += 1.0;
+    let updated_df = get_df(closes);
+    let indicator: IndicatorWrapper =
+        EmaIndicator::from_anchor_close(symbols_pair, 11, "fingerprinted_ema").into();
+    let output_col = &indicator.get_indicator_columns()[0].0;
+    assert_eq!(
+        get_indicator_cache_key(&indicator, &df).unwrap(),
+        get_indicator_cache_key(&indicator, &updated_df).unwrap()
+    );
+
+    for df in [df, updated_df] {
+        let uncached_df = indicator
+            .set_indicator_columns(df.clone().lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+        let cached_df = indicator
+            .set_cached_indicator_columns(df.lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_columns_match(&uncached_df, &cached_df, output_col);
+    }
+}
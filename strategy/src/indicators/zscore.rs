@@ -0,0 +1,153 @@
+use super::IndicatorWrapper;
+use crate::functions::get_last_valid_index;
+use common::{structs::SymbolsPair, traits::indicator::Indicator};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+const NAME: &str = "Z-Score";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ZScoreParams {
+    pub window: usize,
+}
+
+impl Default for ZScoreParams {
+    fn default() -> Self {
+        Self { window: 20 }
+    }
+}
+
+/// Rolling z-score, `(value - rolling mean) / rolling sample std`, over an arbitrary numeric
+/// `source_col`, emitted at `{source_col}_zscore`.
+///
+/// Rows lacking a full window, as well as flat windows (zero std), are null rather than NaN.
+#[derive(Clone, Debug)]
+pub struct ZScoreIndicator {
+    pub name: &'static str,
+    pub window: usize,
+    pub source_col: String,
+    pub output_col: String,
+    symbols_pair: SymbolsPair,
+    columns: Vec<(String, DataType)>,
+}
+
+impl ZScoreIndicator {
+    pub fn new(symbols_pair: SymbolsPair, window: usize, source_col: String) -> Self {
+        let output_col = format!("{}_zscore", source_col);
+        let columns = vec![(output_col.clone(), DataType::Float64)];
+        Self {
+            name: NAME,
+            window,
+            source_col,
+            output_col,
+            symbols_pair,
+            columns,
+        }
+    }
+
+    fn get_rolling_options(&self) -> RollingOptions {
+        RollingOptions {
+            window_size: Duration::parse(&format!("{}i", self.window)),
+            min_periods: self.window,
+            center: false,
+            by: None,
+            weights: None,
+            closed_window: None,
+            fn_params: None,
+        }
+    }
+}
+
+impl Indicator for ZScoreIndicator {
+    type Params = ZScoreParams;
+    type Wrapper = IndicatorWrapper;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_indicator_columns(&self) -> &Vec<(String, DataType)> {
+        &self.columns
+    }
+
+    fn set_indicator_columns(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        if self.window < 2 {
+            let error = format!("z-score window must be at least 2, got {}", self.window);
+            return Err(GlowError::new(
+                String::from("Invalid Z-Score Window"),
+                error,
+            ));
+        }
+        let source = col(&self.source_col).cast(DataType::Float64);
+        let rolling_mean = source.clone().rolling_mean(self.get_rolling_options());
+        let rolling_std = source.clone().rolling_std(self.get_rolling_options());
+
+        let lf = lf.with_column(
+            when(rolling_std.clone().gt(lit(0.0)))
+                .then((source - rolling_mean) / rolling_std)
+                .otherwise(lit(NULL).cast(DataType::Float64))
+                .alias(&self.output_col),
+        );
+
+        Ok(lf)
+    }
+
+    /// Recomputes only rows appended after the last calculated z-score, alongside the window
+    /// preceding them. If no prior value exists, the whole column is recomputed.
+    fn update_indicator_columns(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        let last_valid_index = get_last_valid_index(df, &self.output_col)?;
+        if last_valid_index.is_none() {
+            let result_df = self.set_indicator_columns(df.clone().lazy())?.collect()?;
+            return Ok(result_df);
+        }
+        let first_pending_index = last_valid_index.unwrap() + 1;
+        if first_pending_index >= df.height() {
+            return Ok(df.clone());
+        }
+
+        let offset = (first_pending_index + 1).saturating_sub(self.window);
+        let window_df = df
+            .select([&self.source_col])?
+            .slice(offset as i64, df.height() - offset);
+        let window_df = self.set_indicator_columns(window_df.lazy())?.collect()?;
+        let pending_values = window_df.column(&self.output_col)?.f64()?;
+
+        let mut updated_values: Vec<Option<f64>> = df
+            .column(&self.output_col)?
+            .f64()?
+            .into_iter()
+            .take(first_pending_index)
+            .collect();
+        updated_values.extend(
+            pending_values
+                .into_iter()
+                .skip(first_pending_index - offset),
+        );
+
+        let mut result_df = df.clone();
+        result_df.with_column(Series::new(&self.output_col, updated_values))?;
+
+        Ok(result_df)
+    }
+
+    fn get_minimum_klines_for_benchmarking(&self) -> u32 {
+        self.window as u32
+    }
+
+    fn patch_params(&self, params: Self::Params) -> Result<Self::Wrapper, GlowError> {
+        let mut updated = self.clone();
+        updated.window = params.window;
+        Ok(updated.into())
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let previous_anchor = self.symbols_pair.anchor.name;
+        let updated_anchor = updated_symbols_pair.anchor.name;
+        let source_col = self.source_col.replacen(previous_anchor, updated_anchor, 1);
+        let updated = Self::new(updated_symbols_pair, self.window, source_col);
+        Ok(updated.into())
+    }
+}
@@ -1,14 +1,22 @@
-use common::structs::SymbolsPair;
+use common::{
+    structs::{SignalPriority, SymbolsPair},
+    traits::signal::Signal,
+};
 use glow_error::GlowError;
 use params::{Param, ParamId};
-use polars::prelude::{DataFrame, DataType, LazyFrame};
+use polars::prelude::{col, lit, DataFrame, DataType, IntoLazy, LazyFrame, Series, TakeRandom};
 use schemas::{Schema, StrategySchema};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use signals::SignalWrapper;
+use std::collections::{HashMap, HashSet};
 pub mod functions;
+pub mod indicators;
 pub mod params;
 pub mod schemas;
+pub mod signals;
 pub mod r#static;
+#[cfg(test)]
+mod tests;
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum StrategyId {
@@ -22,19 +30,62 @@ pub struct Strategy {
     pub schema: StrategySchema,
     pub symbols_pair: SymbolsPair,
     pub params: HashMap<ParamId, Param>,
+    /// Whether signals are shifted by one bar, so that each bar only acts upon signals emitted
+    /// by the last fully closed one, not repainting while it's still forming. As both live
+    /// positions and benchmark ones are taken from signal columns, they stay in parity.
+    ///
+    /// Built-in signals read current bar's values, so none of them avoids repainting on its own:
+    /// `SimpleTrend` EMA crossover, `Supertrend` and `ThresholdCross` compare current bar against
+    /// prior one, `Breakout` compares current close against prior bar's channel, `Confirmed` and
+    /// `Composite` inherit their children's behavior and `External` depends on its source.
+    pub use_closed_bars_only: bool,
+    /// Order in which signals fired at the same bar are acted upon, both live and by benchmark.
+    pub signal_priority: SignalPriority,
 }
 
 impl Strategy {
-    pub fn new(id: StrategyId, symbols_pair: SymbolsPair) -> Self {
+    pub fn new(id: StrategyId, symbols_pair: SymbolsPair) -> Result<Self, GlowError> {
         let schema: StrategySchema = id.into();
         let params = schema.get_params_config();
 
-        Self {
+        let strategy = Self {
             id,
             schema,
             symbols_pair,
             params,
+            use_closed_bars_only: false,
+            signal_priority: SignalPriority::default(),
+        };
+        strategy.validate_signal_dependencies()?;
+
+        Ok(strategy)
+    }
+
+    /// Checks that every column read by strategy's signals is either a kline column
+    /// of the symbols pair or produced by some of its indicators.
+    pub fn validate_signal_dependencies(&self) -> Result<(), GlowError> {
+        let signals = self.schema.get_signals(self.symbols_pair, &self.params)?;
+
+        validate_signals_dependencies(&signals, &self.get_signals_available_columns())
+    }
+
+    /// Kline columns of the symbols pair and columns produced by strategy's indicators.
+    fn get_signals_available_columns(&self) -> Vec<String> {
+        let mut available_columns: Vec<String> = self
+            .get_indicators_columns()
+            .into_iter()
+            .map(|(column, _)| column)
+            .collect();
+        for symbol in [self.symbols_pair.anchor, self.symbols_pair.traded] {
+            let (open_col, high_col, low_col, close_col) = symbol.get_ohlc_cols();
+            available_columns.extend(
+                [open_col, high_col, low_col, close_col]
+                    .iter()
+                    .map(|column| column.to_string()),
+            );
         }
+
+        available_columns
     }
 
     pub fn patch_symbols_pair(&self, updated_symbols_pair: SymbolsPair) -> Self {
@@ -44,6 +95,29 @@ impl Strategy {
         updated_strategy
     }
 
+    /// Rebinds strategy to `updated_symbols_pair`, so that every indicator and signal reads and
+    /// emits that pair's columns, rechecking signal dependencies against them.
+    pub fn rebind_symbols(&self, updated_symbols_pair: SymbolsPair) -> Result<Self, GlowError> {
+        let updated_strategy = self.patch_symbols_pair(updated_symbols_pair);
+        updated_strategy.validate_signal_dependencies()?;
+
+        Ok(updated_strategy)
+    }
+
+    pub fn patch_use_closed_bars_only(&self, use_closed_bars_only: bool) -> Self {
+        let mut updated_strategy = self.clone();
+        updated_strategy.use_closed_bars_only = use_closed_bars_only;
+
+        updated_strategy
+    }
+
+    pub fn patch_signal_priority(&self, signal_priority: SignalPriority) -> Self {
+        let mut updated_strategy = self.clone();
+        updated_strategy.signal_priority = signal_priority;
+
+        updated_strategy
+    }
+
     pub fn patch_param(&self, param_id: ParamId, value: Param) -> Result<Self, GlowError> {
         let mut updated = self.clone();
         let params_config = self.schema.get_params_config();
@@ -67,13 +141,136 @@ impl Strategy {
     }
 
     pub fn append_signals_to_lf(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
-        self.schema
-            .append_signals_to_lf(lf, self.symbols_pair, &self.params)
+        let lf = self
+            .schema
+            .append_signals_to_lf(lf, self.symbols_pair, &self.params)?;
+        Ok(self.shift_signals_to_closed_bars(lf))
     }
 
+    /// Signal columns are expected to be recomputed over the whole `df`, so that shifting
+    /// them for `use_closed_bars_only` doesn't shift already shifted values.
     pub fn append_signals_to_df(&self, df: DataFrame) -> Result<DataFrame, GlowError> {
-        self.schema
-            .append_signals_to_df(df, self.symbols_pair, &self.params)
+        let df = self
+            .schema
+            .append_signals_to_df(df, self.symbols_pair, &self.params)?;
+        if !self.use_closed_bars_only {
+            return Ok(df);
+        }
+        let df = self.shift_signals_to_closed_bars(df.lazy()).collect()?;
+        Ok(df)
+    }
+
+    /// Moves each signal to the bar following the one which emitted it, if `use_closed_bars_only`.
+    fn shift_signals_to_closed_bars(&self, lf: LazyFrame) -> LazyFrame {
+        if !self.use_closed_bars_only {
+            return lf;
+        }
+        let shifted_signals: Vec<_> = self
+            .get_signals_columns()
+            .into_iter()
+            .map(|(column, _)| col(&column).shift(1).fill_null(lit(0)).alias(&column))
+            .collect();
+        lf.with_columns(shifted_signals)
+    }
+
+    /// Melts wide strategy `df` into a long table with `start_time`, `name`, `value` and `kind`
+    /// columns, one row per bar and column. `kind` tags strategy's indicator columns as
+    /// "indicator", its signal columns as "signal", symbols pair's kline columns as "kline" and
+    /// any other numeric column (e.g. benchmark results) as "result". Values are cast to `f64`,
+    /// non numeric columns being left out.
+    pub fn to_long_frame(&self, df: &DataFrame) -> Result<DataFrame, GlowError> {
+        let indicators_columns: HashSet<String> = self
+            .get_indicators_columns()
+            .into_iter()
+            .map(|(column, _)| column)
+            .collect();
+        let signals_columns: HashSet<String> = self
+            .get_signals_columns()
+            .into_iter()
+            .map(|(column, _)| column)
+            .collect();
+        let mut kline_columns = HashSet::new();
+        for symbol in self.symbols_pair.get_unique_symbols() {
+            let (open_col, high_col, low_col, close_col) = symbol.get_ohlc_cols();
+            kline_columns.extend([open_col, high_col, low_col, close_col].map(String::from));
+            kline_columns.insert(format!("{}_volume", symbol.name));
+        }
+
+        let mut kinds_columns: Vec<(&str, Vec<String>)> = vec![
+            ("indicator", vec![]),
+            ("signal", vec![]),
+            ("kline", vec![]),
+            ("result", vec![]),
+        ];
+        for series in df.get_columns() {
+            let column = series.name();
+            let dtype = series.dtype();
+            if column == "start_time" || !dtype.is_numeric() && dtype != &DataType::Boolean {
+                continue;
+            }
+            let kind_index = if indicators_columns.contains(column) {
+                0
+            } else if signals_columns.contains(column) {
+                1
+            } else if kline_columns.contains(column) {
+                2
+            } else {
+                3
+            };
+            kinds_columns[kind_index].1.push(column.to_string());
+        }
+
+        let mut long_df = DataFrame::new(vec![
+            Series::new_empty("start_time", df.column("start_time")?.dtype()),
+            Series::new_empty("name", &DataType::Utf8),
+            Series::new_empty("value", &DataType::Float64),
+            Series::new_empty("kind", &DataType::Utf8),
+        ])?;
+        for (kind, columns) in kinds_columns {
+            if columns.is_empty() {
+                continue;
+            }
+            let mut selected_cols = vec![col("start_time")];
+            selected_cols.extend(
+                columns
+                    .iter()
+                    .map(|column| col(column).cast(DataType::Float64)),
+            );
+            let kind_df = df
+                .clone()
+                .lazy()
+                .select(selected_cols)
+                .collect()?
+                .melt(["start_time"], &columns)?
+                .lazy()
+                .select([
+                    col("start_time"),
+                    col("variable").alias("name"),
+                    col("value"),
+                    lit(kind).alias("kind"),
+                ])
+                .collect()?;
+            long_df.vstack_mut(&kind_df)?;
+        }
+
+        Ok(long_df)
+    }
+
+    /// Gets last row value of each of strategy's indicator columns at `df`, so that what signals
+    /// were computed upon can be inspected. Columns missing from `df`, or null at its last row,
+    /// as while indicators warm up, are left out.
+    pub fn latest_indicator_values(&self, df: &DataFrame) -> HashMap<String, f64> {
+        let Some(last_index) = df.height().checked_sub(1) else {
+            return HashMap::new();
+        };
+        self.get_indicators_columns()
+            .into_iter()
+            .filter_map(|(column, _)| {
+                let series = df.column(&column).ok()?.cast(&DataType::Float64).ok()?;
+                let value = series.f64().ok()?.get(last_index)?;
+                Some((column, value))
+            })
+            .collect()
     }
 
     pub fn get_params_config(&self) -> HashMap<ParamId, Param> {
@@ -92,6 +289,56 @@ impl Strategy {
     pub fn get_minimum_klines_for_calculation(&self) -> u32 {
         self.schema.get_minimum_klines_for_calculation(&self.params)
     }
+
+    pub fn get_indicator_warmup_bars(&self) -> u32 {
+        self.schema.get_indicator_warmup_bars(&self.params)
+    }
+
+    /// See `Schema::is_live_only`.
+    pub fn is_live_only(&self) -> bool {
+        self.schema.is_live_only()
+    }
+
+    /// Closed bars fetched ahead of benchmark start and kept at live trading data, so that
+    /// indicators have their whole lookback from the first benchmark bar, and so from the first
+    /// live one, regardless of how short benchmark window is.
+    pub fn get_warmup_klines(&self) -> u32 {
+        self.get_minimum_klines_for_calculation()
+            .max(self.get_indicator_warmup_bars())
+    }
+}
+
+/// Errors listing every column read by `signals`, as returned by `Signal::required_columns`,
+/// that isn't among `available_columns`.
+fn validate_signals_dependencies(
+    signals: &[SignalWrapper],
+    available_columns: &[String],
+) -> Result<(), GlowError> {
+    let missing_dependencies: Vec<String> = signals
+        .iter()
+        .flat_map(|signal| {
+            let category = signal.signal_category();
+            signal
+                .required_columns()
+                .into_iter()
+                .filter(|column| !available_columns.contains(column))
+                .map(move |column| {
+                    format!(
+                        "{:?} signal requires column {}, which isn't produced by any indicator",
+                        category, column
+                    )
+                })
+        })
+        .collect();
+
+    if !missing_dependencies.is_empty() {
+        return Err(GlowError::new(
+            String::from("Missing Signal Dependencies"),
+            missing_dependencies.join("\n"),
+        ));
+    }
+
+    Ok(())
 }
 
 impl Default for Strategy {
@@ -99,5 +346,6 @@ impl Default for Strategy {
         let symbols_pair = SymbolsPair::default();
         let default_schema_id = StrategyId::default();
         Self::new(default_schema_id, symbols_pair)
+            .expect("default strategy signals to have their dependencies met")
     }
 }
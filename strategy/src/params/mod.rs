@@ -3,7 +3,7 @@
 // use polars::prelude::TimeUnit;
 use std::fmt::Display;
 use glow_error::GlowError;
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum ParamId {
     SlowSpan,
     FastSpan,
@@ -89,8 +89,7 @@ impl<T: Clone + Copy + PartialOrd + PartialEq + Display> NumberParamConfig<T> {
                 ));
             }
         }
-        if self.max.is_some() {
-            let max = self.min.unwrap();
+        if let Some(max) = self.max {
             if value > &max {
                 return Err(GlowError::new(
                     format!("Invalid param"),
@@ -3,6 +3,7 @@ use simple_trend::SimpleTrendStrategySchema;
 mod simple_trend;
 use crate::{
     params::{Param, ParamId},
+    signals::SignalWrapper,
     StrategyId,
 };
 use common::structs::SymbolsPair;
@@ -49,11 +50,27 @@ pub trait Schema: Clone + Sized {
         params: &HashMap<ParamId, Param>,
     ) -> Vec<(String, DataType)>;
     fn get_minimum_klines_for_calculation(&self, params: &HashMap<ParamId, Param>) -> u32;
+    /// Leading bars over which indicators don't have their whole lookback yet,
+    /// i.e. the max lookback across schema's indicators.
+    fn get_indicator_warmup_bars(&self, params: &HashMap<ParamId, Param>) -> u32;
     fn get_signals_columns(
         &self,
         symbols_pair: SymbolsPair,
         params: &HashMap<ParamId, Param>,
     ) -> Vec<(String, DataType)>;
+    /// Whether any of schema's indicators is live only, see `IndicatorWrapper::is_live_only`,
+    /// so that its signals can't be reproduced by benchmarks and data providers must stream
+    /// order book depth.
+    fn is_live_only(&self) -> bool {
+        false
+    }
+    /// Signals setting schema's signal columns, whose `Signal::required_columns` are checked
+    /// against klines and indicators columns.
+    fn get_signals(
+        &self,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<Vec<SignalWrapper>, GlowError>;
 }
 
 impl Default for StrategySchema {
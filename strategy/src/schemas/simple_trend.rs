@@ -1,10 +1,13 @@
 use super::Schema;
 use crate::{
-    functions::calculate_span_alpha,
+    indicators::{ema::EmaIndicator, IndicatorWrapper},
     params::{NumberParamConfig, Param, ParamId},
+    signals::{ema_crossover::EmaCrossoverSignal, SignalWrapper},
     StrategyId,
 };
-use common::{enums::signal_category::SignalCategory, structs::SymbolsPair};
+use common::{
+    enums::signal_category::SignalCategory, structs::SymbolsPair, traits::signal::Signal,
+};
 use glow_error::GlowError;
 use polars::prelude::*;
 use std::collections::HashMap;
@@ -21,66 +24,30 @@ impl Schema for SimpleTrendStrategySchema {
         symbols_pair: SymbolsPair,
         params: &HashMap<ParamId, Param>,
     ) -> Result<LazyFrame, GlowError> {
-        let close_col = symbols_pair.anchor.get_close_col();
-
         let cols = self.get_indicators_columns(symbols_pair, params);
 
         let (ema_fast_col, _) = cols
             .get(0)
             .expect("EMA indicator to have column at index 0");
-        let fast_span_param = params
-            .get(&ParamId::FastSpan)
-            .expect("FastSpan param to be set at ParamsMap");
-        let fast_span = if let Param::UInt32(value, _) = fast_span_param {
-            value.clone()
-        } else {
-            20
-        };
-
         let (ema_slow_col, _) = cols
             .get(1)
             .expect("EMA indicator to have column at index 1");
-        let slow_span_param = params
-            .get(&ParamId::SlowSpan)
-            .expect("SlowSpan param to be set at ParamsMap");
-        let slow_span = if let Param::UInt32(value, _) = slow_span_param {
-            value.clone()
-        } else {
-            100
-        };
 
-        let fast_alpha = calculate_span_alpha(fast_span as f64)?;
-        let slow_alpha = calculate_span_alpha(slow_span as f64)?;
-        let fast_opts = EWMOptions {
-            alpha: fast_alpha,
-            adjust: false,
-            bias: false,
-            min_periods: 1,
-            ignore_nulls: false,
-        };
-        let slow_opts = EWMOptions {
-            alpha: slow_alpha,
-            adjust: false,
-            bias: false,
-            min_periods: 1,
-            ignore_nulls: false,
-        };
+        let mut lf = lf;
+        for ema_indicator in self.get_ema_indicators(symbols_pair, params) {
+            lf = ema_indicator.set_cached_indicator_columns(lf)?;
+        }
 
-        let lf = lf
-            .with_columns([
-                col(close_col).ewm_mean(slow_opts).alias(ema_slow_col),
-                col(close_col).ewm_mean(fast_opts).alias(ema_fast_col),
-            ])
-            .with_column(
-                when(col(ema_fast_col).is_null().or(col(ema_slow_col).is_null()))
-                    .then(lit(NULL))
-                    .otherwise(
-                        when(col(ema_fast_col).gt(col(ema_slow_col)))
-                            .then(true)
-                            .otherwise(false),
-                    )
-                    .alias(&TREND_COL),
-            );
+        let lf = lf.with_column(
+            when(col(ema_fast_col).is_null().or(col(ema_slow_col).is_null()))
+                .then(lit(NULL))
+                .otherwise(
+                    when(col(ema_fast_col).gt(col(ema_slow_col)))
+                        .then(true)
+                        .otherwise(false),
+                )
+                .alias(TREND_COL),
+        );
 
         Ok(lf)
     }
@@ -109,43 +76,12 @@ impl Schema for SimpleTrendStrategySchema {
         &self,
         lf: LazyFrame,
         symbols_pair: SymbolsPair,
-        _: &HashMap<ParamId, Param>,
+        params: &HashMap<ParamId, Param>,
     ) -> Result<LazyFrame, GlowError> {
-        let short_col = SignalCategory::GoShort.get_column();
-        let long_col = SignalCategory::GoLong.get_column();
-        let close_short_col = SignalCategory::CloseShort.get_column();
-        let close_long_col = SignalCategory::CloseLong.get_column();
-        // let select_columns = vec![col("start_time"), col(signal_col)];
-
-        let fast_ema_col = format!("{}_fast_ema", &symbols_pair.anchor.name);
-        let slow_ema_col = format!("{}_slow_ema", &symbols_pair.anchor.name);
-
-        let fast_ema_lesser_than_slow_ema = col(&fast_ema_col).lt(col(&slow_ema_col));
-        let fast_ema_greater_than_slow_ema = col(&fast_ema_col).gt(col(&slow_ema_col));
-
-        let prev_fast_ema_lesser_than_prev_slow_ema =
-            col(&fast_ema_col).shift(1).lt(col(&slow_ema_col).shift(1));
-        let prev_fast_ema_greater_than_prev_slow_ema =
-            col(&fast_ema_col).shift(1).gt(col(&slow_ema_col).shift(1));
-
-        let signal_lf = lf.clone().with_columns([
-            when(fast_ema_lesser_than_slow_ema.clone().and(prev_fast_ema_greater_than_prev_slow_ema.clone()))
-                .then(lit(1))
-                .otherwise(lit(0))
-                .alias(short_col),
-            when(fast_ema_greater_than_slow_ema.clone().and(prev_fast_ema_lesser_than_prev_slow_ema.clone()))
-                .then(lit(1))
-                .otherwise(lit(0))
-                .alias(long_col),
-            when(fast_ema_greater_than_slow_ema.and(prev_fast_ema_lesser_than_prev_slow_ema))
-                .then(lit(1))
-                .otherwise(lit(0))
-                .alias(close_short_col),
-            when(fast_ema_lesser_than_slow_ema.and(prev_fast_ema_greater_than_prev_slow_ema))
-                .then(lit(1))
-                .otherwise(lit(0))
-                .alias(close_long_col),
-        ]);
+        let mut signal_lf = lf;
+        for signal in self.get_signals(symbols_pair, params)? {
+            signal_lf = signal.set_signal_column(&signal_lf)?;
+        }
 
         Ok(signal_lf)
     }
@@ -226,6 +162,17 @@ impl Schema for SimpleTrendStrategySchema {
         slow_span
     }
 
+    fn get_indicator_warmup_bars(&self, params: &HashMap<ParamId, Param>) -> u32 {
+        [ParamId::FastSpan, ParamId::SlowSpan]
+            .iter()
+            .filter_map(|param_id| match params.get(param_id) {
+                Some(Param::UInt32(span, _)) => Some(*span),
+                _ => None,
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
     fn get_signals_columns(
         &self,
         _: SymbolsPair,
@@ -243,6 +190,54 @@ impl Schema for SimpleTrendStrategySchema {
             .map(|s| (s.get_column().to_string(), DataType::UInt32))
             .collect()
     }
+
+    fn get_signals(
+        &self,
+        symbols_pair: SymbolsPair,
+        _: &HashMap<ParamId, Param>,
+    ) -> Result<Vec<SignalWrapper>, GlowError> {
+        [
+            SignalCategory::GoShort,
+            SignalCategory::GoLong,
+            SignalCategory::CloseShort,
+            SignalCategory::CloseLong,
+        ]
+        .into_iter()
+        .map(|category| Ok(EmaCrossoverSignal::new(symbols_pair, category)?.into()))
+        .collect()
+    }
+}
+
+impl SimpleTrendStrategySchema {
+    /// Fast and slow EMAs over anchor's close, set through `INDICATORS_CACHE`, so that sweep
+    /// variants sharing spans reuse each other's columns.
+    fn get_ema_indicators(
+        &self,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> [IndicatorWrapper; 2] {
+        let fast_span_param = params
+            .get(&ParamId::FastSpan)
+            .expect("FastSpan param to be set at ParamsMap");
+        let fast_span = if let Param::UInt32(value, _) = fast_span_param {
+            *value
+        } else {
+            20
+        };
+        let slow_span_param = params
+            .get(&ParamId::SlowSpan)
+            .expect("SlowSpan param to be set at ParamsMap");
+        let slow_span = if let Param::UInt32(value, _) = slow_span_param {
+            *value
+        } else {
+            100
+        };
+
+        [
+            EmaIndicator::from_anchor_close(symbols_pair, fast_span as usize, "fast_ema").into(),
+            EmaIndicator::from_anchor_close(symbols_pair, slow_span as usize, "slow_ema").into(),
+        ]
+    }
 }
 
 impl From<StrategyId> for SimpleTrendStrategySchema {
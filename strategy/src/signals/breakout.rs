@@ -0,0 +1,93 @@
+use super::SignalWrapper;
+use crate::indicators::donchian::{get_donchian_high_col, get_donchian_low_col};
+use common::{
+    enums::signal_category::SignalCategory, structs::SymbolsPair, traits::signal::Signal,
+};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+/// Emits 1 at bars where anchor's close breaks out of the prior bar's Donchian channel,
+/// as set by `DonchianIndicator`: above its high for `GoLong` and `CloseShort`,
+/// below its low for `GoShort` and `CloseLong`.
+#[derive(Clone, Debug)]
+pub struct BreakoutSignal {
+    pub category: SignalCategory,
+    pub close_col: String,
+    pub channel_col: String,
+}
+
+impl BreakoutSignal {
+    pub fn new(symbols_pair: SymbolsPair, category: SignalCategory) -> Result<Self, GlowError> {
+        let anchor = symbols_pair.anchor;
+        let channel_col = match category {
+            SignalCategory::GoLong | SignalCategory::CloseShort => {
+                get_donchian_high_col(anchor.name)
+            }
+            SignalCategory::GoShort | SignalCategory::CloseLong => {
+                get_donchian_low_col(anchor.name)
+            }
+            category => {
+                let error = format!("breakout signal can't have {:?} category", category);
+                return Err(GlowError::new(
+                    String::from("Invalid Breakout Signal"),
+                    error,
+                ));
+            }
+        };
+        Ok(Self {
+            category,
+            close_col: anchor.get_close_col().to_string(),
+            channel_col,
+        })
+    }
+
+    fn get_breakout_expr(&self) -> Expr {
+        let close = col(&self.close_col);
+        let previous_channel = col(&self.channel_col).shift(1);
+        match self.category {
+            SignalCategory::GoLong | SignalCategory::CloseShort => close.gt(previous_channel),
+            _ => close.lt(previous_channel),
+        }
+    }
+}
+
+impl Signal for BreakoutSignal {
+    type Wrapper = SignalWrapper;
+
+    fn signal_category(&self) -> SignalCategory {
+        self.category
+    }
+
+    fn required_columns(&self) -> Vec<String> {
+        vec![self.close_col.clone(), self.channel_col.clone()]
+    }
+
+    fn set_signal_column(&self, lf: &LazyFrame) -> Result<LazyFrame, GlowError> {
+        let signal_col = self.category.get_column();
+        let lf = lf.clone().with_column(
+            when(self.get_breakout_expr().fill_null(lit(false)))
+                .then(lit(1))
+                .otherwise(lit(0))
+                .alias(signal_col),
+        );
+        Ok(lf)
+    }
+
+    fn update_signal_column(&self, data: &DataFrame) -> Result<DataFrame, GlowError> {
+        let signal_col = self.category.get_column();
+        let new_df = self.set_signal_column(&data.clone().lazy())?.collect()?;
+        let series = new_df.column(signal_col)?;
+        let mut result_df = data.clone();
+        result_df.with_column(series.to_owned())?;
+
+        Ok(result_df)
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let updated = Self::new(updated_symbols_pair, self.category)?;
+        Ok(updated.into())
+    }
+}
@@ -0,0 +1,162 @@
+use super::SignalWrapper;
+use common::{
+    enums::signal_category::SignalCategory, structs::SymbolsPair, traits::signal::Signal,
+};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+/// Combines child signals, emitting 1 only at bars where at least `min_confirmations`
+/// of its `signals` emit 1.
+///
+/// Children are evaluated over the same frame, but their own columns are kept untouched,
+/// as only the composite `signal_category` column is set.
+#[derive(Clone, Debug)]
+pub struct CompositeSignal {
+    pub signals: Vec<SignalWrapper>,
+    pub signal_category: SignalCategory,
+    pub min_confirmations: usize,
+}
+
+impl CompositeSignal {
+    pub fn new(
+        signals: Vec<SignalWrapper>,
+        signal_category: SignalCategory,
+        min_confirmations: usize,
+    ) -> Result<Self, GlowError> {
+        if min_confirmations == 0 || min_confirmations > signals.len() {
+            let error = format!(
+                "min_confirmations must be between 1 and {} (number of signals), got {}",
+                signals.len(),
+                min_confirmations
+            );
+            return Err(GlowError::new(
+                String::from("Invalid Composite Signal"),
+                error,
+            ));
+        }
+        Ok(Self {
+            signals,
+            signal_category,
+            min_confirmations,
+        })
+    }
+
+    fn get_confirmation_col(&self, index: usize) -> String {
+        format!(
+            "{}_confirmation_{}",
+            self.signal_category.get_column(),
+            index
+        )
+    }
+}
+
+impl Signal for CompositeSignal {
+    type Wrapper = SignalWrapper;
+
+    fn signal_category(&self) -> SignalCategory {
+        self.signal_category
+    }
+
+    fn required_columns(&self) -> Vec<String> {
+        let mut required_columns: Vec<String> = vec![];
+        for column in self
+            .signals
+            .iter()
+            .flat_map(|child| child.required_columns())
+        {
+            if !required_columns.contains(&column) {
+                required_columns.push(column);
+            }
+        }
+        required_columns
+    }
+
+    fn set_signal_column(&self, lf: &LazyFrame) -> Result<LazyFrame, GlowError> {
+        let signal_col = self.signal_category.get_column();
+        let schema = lf.schema()?;
+
+        // children may overwrite columns of other signal categories, so those are backed up
+        let mut backed_up_cols: Vec<(String, String)> = vec![];
+        let mut signal_lf = lf.clone();
+        for child in self.signals.iter() {
+            let child_category = child.signal_category();
+            let child_col = child_category.get_column();
+            let backup_col = format!("{}_backup", child_col);
+            if child_col == signal_col
+                || schema.get(child_col).is_none()
+                || backed_up_cols.iter().any(|(col, _)| col == child_col)
+            {
+                continue;
+            }
+            signal_lf = signal_lf.with_column(col(child_col).alias(&backup_col));
+            backed_up_cols.push((child_col.to_string(), backup_col));
+        }
+
+        let mut confirmation_cols = vec![];
+        for (index, child) in self.signals.iter().enumerate() {
+            let child_category = child.signal_category();
+            let child_col = child_category.get_column();
+            let confirmation_col = self.get_confirmation_col(index);
+            signal_lf = child
+                .set_signal_column(&signal_lf)?
+                .with_column(col(child_col).alias(&confirmation_col));
+            confirmation_cols.push(confirmation_col);
+        }
+
+        let confirmations = confirmation_cols
+            .iter()
+            .fold(lit(0), |acc, confirmation_col| {
+                acc + col(confirmation_col)
+                    .eq(lit(1))
+                    .fill_null(lit(false))
+                    .cast(DataType::Int32)
+            });
+
+        signal_lf = signal_lf.with_column(
+            when(confirmations.gt_eq(lit(self.min_confirmations as i32)))
+                .then(lit(1))
+                .otherwise(lit(0))
+                .alias(signal_col),
+        );
+
+        let mut drop_cols = confirmation_cols;
+        for (child_col, backup_col) in backed_up_cols {
+            signal_lf = signal_lf.with_column(col(&backup_col).alias(&child_col));
+            drop_cols.push(backup_col);
+        }
+        let new_child_cols: Vec<String> = self
+            .signals
+            .iter()
+            .map(|child| child.signal_category().get_column().to_string())
+            .filter(|child_col| child_col != signal_col && schema.get(child_col).is_none())
+            .collect();
+        drop_cols.extend(new_child_cols);
+        drop_cols.sort();
+        drop_cols.dedup();
+
+        Ok(signal_lf.drop_columns(drop_cols))
+    }
+
+    fn update_signal_column(&self, data: &DataFrame) -> Result<DataFrame, GlowError> {
+        let signal_col = self.signal_category.get_column();
+        let new_df = self.set_signal_column(&data.clone().lazy())?.collect()?;
+        let series = new_df.column(signal_col)?;
+        let mut result_df = data.clone();
+        result_df.with_column(series.to_owned())?;
+
+        Ok(result_df)
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let signals = self
+            .signals
+            .iter()
+            .map(|child| child.patch_symbols_pair(updated_symbols_pair))
+            .collect::<Result<Vec<_>, _>>()?;
+        let updated = Self::new(signals, self.signal_category, self.min_confirmations)?;
+        Ok(updated.into())
+    }
+}
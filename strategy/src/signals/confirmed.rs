@@ -0,0 +1,102 @@
+use super::SignalWrapper;
+use common::{
+    enums::signal_category::SignalCategory, structs::SymbolsPair, traits::signal::Signal,
+};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+/// Wraps a signal so that it only emits 1 at bars where its raw condition held for the last
+/// `confirmation_bars` consecutive bars, current one included, filtering single-bar whipsaws.
+///
+/// Confirmation is a rolling sum over the wrapped signal column, so it's recomputed over
+/// the whole frame both on `set_signal_column` and `update_signal_column`.
+#[derive(Clone, Debug)]
+pub struct ConfirmedSignal {
+    pub signal: Box<SignalWrapper>,
+    pub confirmation_bars: usize,
+}
+
+impl ConfirmedSignal {
+    pub fn new(signal: SignalWrapper, confirmation_bars: usize) -> Result<Self, GlowError> {
+        if confirmation_bars == 0 {
+            return Err(GlowError::new(
+                String::from("Invalid Confirmed Signal"),
+                String::from("confirmation_bars must be at least 1"),
+            ));
+        }
+        Ok(Self {
+            signal: Box::new(signal),
+            confirmation_bars,
+        })
+    }
+
+    fn get_rolling_options(&self) -> RollingOptions {
+        RollingOptions {
+            window_size: Duration::parse(&format!("{}i", self.confirmation_bars)),
+            min_periods: self.confirmation_bars,
+            center: false,
+            by: None,
+            weights: None,
+            closed_window: None,
+            fn_params: None,
+        }
+    }
+}
+
+impl Signal for ConfirmedSignal {
+    type Wrapper = SignalWrapper;
+
+    fn signal_category(&self) -> SignalCategory {
+        self.signal.signal_category()
+    }
+
+    fn required_columns(&self) -> Vec<String> {
+        self.signal.required_columns()
+    }
+
+    fn set_signal_column(&self, lf: &LazyFrame) -> Result<LazyFrame, GlowError> {
+        let signal_lf = self.signal.set_signal_column(lf)?;
+        if self.confirmation_bars == 1 {
+            return Ok(signal_lf);
+        }
+        let signal_category = self.signal_category();
+        let signal_col = signal_category.get_column();
+        let confirmations = col(signal_col)
+            .eq(lit(1))
+            .fill_null(lit(false))
+            .cast(DataType::Int32)
+            .rolling_sum(self.get_rolling_options());
+        let signal_lf = signal_lf.with_column(
+            when(
+                confirmations
+                    .gt_eq(lit(self.confirmation_bars as i32))
+                    .fill_null(lit(false)),
+            )
+            .then(lit(1))
+            .otherwise(lit(0))
+            .alias(signal_col),
+        );
+
+        Ok(signal_lf)
+    }
+
+    fn update_signal_column(&self, data: &DataFrame) -> Result<DataFrame, GlowError> {
+        let signal_category = self.signal_category();
+        let signal_col = signal_category.get_column();
+        let new_df = self.set_signal_column(&data.clone().lazy())?.collect()?;
+        let series = new_df.column(signal_col)?;
+        let mut result_df = data.clone();
+        result_df.with_column(series.to_owned())?;
+
+        Ok(result_df)
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let signal = self.signal.patch_symbols_pair(updated_symbols_pair)?;
+        let updated = Self::new(signal, self.confirmation_bars)?;
+        Ok(updated.into())
+    }
+}
@@ -0,0 +1,96 @@
+use super::SignalWrapper;
+use common::{
+    enums::signal_category::SignalCategory, structs::SymbolsPair, traits::signal::Signal,
+};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+/// Emits 1 at bars where anchor's fast EMA crosses its slow EMA, as set by `SimpleTrend`
+/// schema `EmaIndicator`s: above it for `GoLong` and `CloseShort`, below it for `GoShort`
+/// and `CloseLong`, prior bar being strictly on the other side.
+#[derive(Clone, Debug)]
+pub struct EmaCrossoverSignal {
+    pub category: SignalCategory,
+    pub fast_ema_col: String,
+    pub slow_ema_col: String,
+}
+
+impl EmaCrossoverSignal {
+    pub fn new(symbols_pair: SymbolsPair, category: SignalCategory) -> Result<Self, GlowError> {
+        match category {
+            SignalCategory::GoLong
+            | SignalCategory::CloseShort
+            | SignalCategory::GoShort
+            | SignalCategory::CloseLong => {}
+            category => {
+                let error = format!("EMA crossover signal can't have {:?} category", category);
+                return Err(GlowError::new(
+                    String::from("Invalid EMA Crossover Signal"),
+                    error,
+                ));
+            }
+        };
+        let anchor = symbols_pair.anchor.name;
+        Ok(Self {
+            category,
+            fast_ema_col: format!("{}_fast_ema", anchor),
+            slow_ema_col: format!("{}_slow_ema", anchor),
+        })
+    }
+
+    fn get_crossover_expr(&self) -> Expr {
+        let fast_ema = col(&self.fast_ema_col);
+        let slow_ema = col(&self.slow_ema_col);
+        let previous_fast_ema = col(&self.fast_ema_col).shift(1);
+        let previous_slow_ema = col(&self.slow_ema_col).shift(1);
+        match self.category {
+            SignalCategory::GoLong | SignalCategory::CloseShort => previous_fast_ema
+                .lt(previous_slow_ema)
+                .and(fast_ema.gt(slow_ema)),
+            _ => previous_fast_ema
+                .gt(previous_slow_ema)
+                .and(fast_ema.lt(slow_ema)),
+        }
+    }
+}
+
+impl Signal for EmaCrossoverSignal {
+    type Wrapper = SignalWrapper;
+
+    fn signal_category(&self) -> SignalCategory {
+        self.category
+    }
+
+    fn required_columns(&self) -> Vec<String> {
+        vec![self.fast_ema_col.clone(), self.slow_ema_col.clone()]
+    }
+
+    fn set_signal_column(&self, lf: &LazyFrame) -> Result<LazyFrame, GlowError> {
+        let signal_col = self.category.get_column();
+        let lf = lf.clone().with_column(
+            when(self.get_crossover_expr().fill_null(lit(false)))
+                .then(lit(1))
+                .otherwise(lit(0))
+                .alias(signal_col),
+        );
+        Ok(lf)
+    }
+
+    fn update_signal_column(&self, data: &DataFrame) -> Result<DataFrame, GlowError> {
+        let signal_col = self.category.get_column();
+        let new_df = self.set_signal_column(&data.clone().lazy())?.collect()?;
+        let series = new_df.column(signal_col)?;
+        let mut result_df = data.clone();
+        result_df.with_column(series.to_owned())?;
+
+        Ok(result_df)
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let updated = Self::new(updated_symbols_pair, self.category)?;
+        Ok(updated.into())
+    }
+}
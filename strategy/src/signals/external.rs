@@ -0,0 +1,100 @@
+use super::SignalWrapper;
+use common::{
+    enums::signal_category::SignalCategory, structs::SymbolsPair, traits::signal::Signal,
+};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+/// Passes through `source_col`, supplied along with klines by external logic (e.g. a notebook
+/// or a webhook), as signal column. Boolean columns are cast to 0/1 and nulls are taken as 0.
+///
+/// Fails if column is missing from data or holds values other than 0 and 1.
+#[derive(Clone, Debug)]
+pub struct ExternalSignal {
+    pub source_col: String,
+    pub category: SignalCategory,
+}
+
+impl ExternalSignal {
+    pub fn new(source_col: String, category: SignalCategory) -> Self {
+        Self {
+            source_col,
+            category,
+        }
+    }
+
+    fn get_missing_column_error(&self) -> GlowError {
+        let error = format!(
+            "{:?} external signal requires {} column, which is missing from data",
+            self.category, self.source_col
+        );
+        GlowError::new(String::from("Missing External Signal Column"), error)
+    }
+}
+
+/// Casts external signal values to 0/1, erroring on any other value.
+fn cast_external_signal(series: Series) -> PolarsResult<Option<Series>> {
+    let values = series.cast(&DataType::Float64)?;
+    let invalid_value = values
+        .f64()?
+        .into_iter()
+        .flatten()
+        .find(|value| *value != 0.0 && *value != 1.0);
+    if let Some(invalid_value) = invalid_value {
+        return Err(PolarsError::ComputeError(
+            format!(
+                "external signal column {} must hold 0 or 1 values, found {}",
+                series.name(),
+                invalid_value
+            )
+            .into(),
+        ));
+    }
+    let signal_series = values.cast(&DataType::Int32)?;
+    Ok(Some(signal_series))
+}
+
+impl Signal for ExternalSignal {
+    type Wrapper = SignalWrapper;
+
+    fn signal_category(&self) -> SignalCategory {
+        self.category
+    }
+
+    /// Source column isn't produced by indicators, so it's checked against data instead.
+    fn required_columns(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn set_signal_column(&self, lf: &LazyFrame) -> Result<LazyFrame, GlowError> {
+        let schema = lf.schema()?;
+        if !schema.contains(&self.source_col) {
+            return Err(self.get_missing_column_error());
+        }
+        let signal_col = self.category.get_column();
+        let lf = lf.clone().with_column(
+            col(&self.source_col)
+                .map(cast_external_signal, GetOutput::from_type(DataType::Int32))
+                .fill_null(lit(0))
+                .alias(signal_col),
+        );
+        Ok(lf)
+    }
+
+    fn update_signal_column(&self, data: &DataFrame) -> Result<DataFrame, GlowError> {
+        let signal_col = self.category.get_column();
+        let new_df = self.set_signal_column(&data.clone().lazy())?.collect()?;
+        let series = new_df.column(signal_col)?;
+        let mut result_df = data.clone();
+        result_df.with_column(series.to_owned())?;
+
+        Ok(result_df)
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        _updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        Ok(self.clone().into())
+    }
+}
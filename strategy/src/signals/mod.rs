@@ -0,0 +1,143 @@
+use common::{
+    enums::signal_category::SignalCategory, structs::SymbolsPair, traits::signal::Signal,
+};
+use glow_error::GlowError;
+use polars::prelude::*;
+pub mod breakout;
+pub mod composite;
+pub mod confirmed;
+pub mod ema_crossover;
+pub mod external;
+pub mod supertrend;
+pub mod threshold_cross;
+use breakout::BreakoutSignal;
+use composite::CompositeSignal;
+use confirmed::ConfirmedSignal;
+use ema_crossover::EmaCrossoverSignal;
+use external::ExternalSignal;
+use supertrend::SupertrendSignal;
+use threshold_cross::ThresholdCrossSignal;
+#[cfg(test)]
+mod tests;
+
+#[derive(Clone, Debug)]
+pub enum SignalWrapper {
+    Breakout(BreakoutSignal),
+    Composite(CompositeSignal),
+    Confirmed(ConfirmedSignal),
+    EmaCrossover(EmaCrossoverSignal),
+    External(ExternalSignal),
+    Supertrend(SupertrendSignal),
+    ThresholdCross(ThresholdCrossSignal),
+}
+
+/// Signals are defined as such:
+/// They set a 0/1 column, named after their category, from which positions are taken.
+impl Signal for SignalWrapper {
+    type Wrapper = Self;
+
+    fn signal_category(&self) -> SignalCategory {
+        match self {
+            Self::Breakout(signal) => signal.signal_category(),
+            Self::Composite(signal) => signal.signal_category(),
+            Self::Confirmed(signal) => signal.signal_category(),
+            Self::EmaCrossover(signal) => signal.signal_category(),
+            Self::External(signal) => signal.signal_category(),
+            Self::Supertrend(signal) => signal.signal_category(),
+            Self::ThresholdCross(signal) => signal.signal_category(),
+        }
+    }
+
+    fn required_columns(&self) -> Vec<String> {
+        match self {
+            Self::Breakout(signal) => signal.required_columns(),
+            Self::Composite(signal) => signal.required_columns(),
+            Self::Confirmed(signal) => signal.required_columns(),
+            Self::EmaCrossover(signal) => signal.required_columns(),
+            Self::External(signal) => signal.required_columns(),
+            Self::Supertrend(signal) => signal.required_columns(),
+            Self::ThresholdCross(signal) => signal.required_columns(),
+        }
+    }
+
+    fn set_signal_column(&self, lf: &LazyFrame) -> Result<LazyFrame, GlowError> {
+        match self {
+            Self::Breakout(signal) => signal.set_signal_column(lf),
+            Self::Composite(signal) => signal.set_signal_column(lf),
+            Self::Confirmed(signal) => signal.set_signal_column(lf),
+            Self::EmaCrossover(signal) => signal.set_signal_column(lf),
+            Self::External(signal) => signal.set_signal_column(lf),
+            Self::Supertrend(signal) => signal.set_signal_column(lf),
+            Self::ThresholdCross(signal) => signal.set_signal_column(lf),
+        }
+    }
+
+    fn update_signal_column(&self, data: &DataFrame) -> Result<DataFrame, GlowError> {
+        match self {
+            Self::Breakout(signal) => signal.update_signal_column(data),
+            Self::Composite(signal) => signal.update_signal_column(data),
+            Self::Confirmed(signal) => signal.update_signal_column(data),
+            Self::EmaCrossover(signal) => signal.update_signal_column(data),
+            Self::External(signal) => signal.update_signal_column(data),
+            Self::Supertrend(signal) => signal.update_signal_column(data),
+            Self::ThresholdCross(signal) => signal.update_signal_column(data),
+        }
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        match self {
+            Self::Breakout(signal) => signal.patch_symbols_pair(updated_symbols_pair),
+            Self::Composite(signal) => signal.patch_symbols_pair(updated_symbols_pair),
+            Self::Confirmed(signal) => signal.patch_symbols_pair(updated_symbols_pair),
+            Self::EmaCrossover(signal) => signal.patch_symbols_pair(updated_symbols_pair),
+            Self::External(signal) => signal.patch_symbols_pair(updated_symbols_pair),
+            Self::Supertrend(signal) => signal.patch_symbols_pair(updated_symbols_pair),
+            Self::ThresholdCross(signal) => signal.patch_symbols_pair(updated_symbols_pair),
+        }
+    }
+}
+
+impl From<BreakoutSignal> for SignalWrapper {
+    fn from(value: BreakoutSignal) -> Self {
+        Self::Breakout(value)
+    }
+}
+
+impl From<CompositeSignal> for SignalWrapper {
+    fn from(value: CompositeSignal) -> Self {
+        Self::Composite(value)
+    }
+}
+
+impl From<ConfirmedSignal> for SignalWrapper {
+    fn from(value: ConfirmedSignal) -> Self {
+        Self::Confirmed(value)
+    }
+}
+
+impl From<EmaCrossoverSignal> for SignalWrapper {
+    fn from(value: EmaCrossoverSignal) -> Self {
+        Self::EmaCrossover(value)
+    }
+}
+
+impl From<ExternalSignal> for SignalWrapper {
+    fn from(value: ExternalSignal) -> Self {
+        Self::External(value)
+    }
+}
+
+impl From<SupertrendSignal> for SignalWrapper {
+    fn from(value: SupertrendSignal) -> Self {
+        Self::Supertrend(value)
+    }
+}
+
+impl From<ThresholdCrossSignal> for SignalWrapper {
+    fn from(value: ThresholdCrossSignal) -> Self {
+        Self::ThresholdCross(value)
+    }
+}
@@ -0,0 +1,90 @@
+use super::SignalWrapper;
+use crate::indicators::supertrend::get_supertrend_dir_col;
+use common::{
+    enums::signal_category::SignalCategory, structs::SymbolsPair, traits::signal::Signal,
+};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+/// Emits 1 at bars where anchor's supertrend direction, as set by `SupertrendIndicator`, flips
+/// from prior bar's: to an uptrend for `GoLong` and `CloseShort`, to a downtrend for `GoShort`
+/// and `CloseLong`.
+#[derive(Clone, Debug)]
+pub struct SupertrendSignal {
+    pub category: SignalCategory,
+    pub direction_col: String,
+}
+
+impl SupertrendSignal {
+    pub fn new(symbols_pair: SymbolsPair, category: SignalCategory) -> Result<Self, GlowError> {
+        match category {
+            SignalCategory::GoLong
+            | SignalCategory::CloseShort
+            | SignalCategory::GoShort
+            | SignalCategory::CloseLong => {}
+            category => {
+                let error = format!("supertrend signal can't have {:?} category", category);
+                return Err(GlowError::new(
+                    String::from("Invalid Supertrend Signal"),
+                    error,
+                ));
+            }
+        };
+        Ok(Self {
+            category,
+            direction_col: get_supertrend_dir_col(symbols_pair.anchor.name),
+        })
+    }
+
+    fn get_flip_expr(&self) -> Expr {
+        let direction = col(&self.direction_col);
+        let previous_direction = col(&self.direction_col).shift(1);
+        match self.category {
+            SignalCategory::GoLong | SignalCategory::CloseShort => {
+                previous_direction.eq(lit(-1)).and(direction.eq(lit(1)))
+            }
+            _ => previous_direction.eq(lit(1)).and(direction.eq(lit(-1))),
+        }
+    }
+}
+
+impl Signal for SupertrendSignal {
+    type Wrapper = SignalWrapper;
+
+    fn signal_category(&self) -> SignalCategory {
+        self.category
+    }
+
+    fn required_columns(&self) -> Vec<String> {
+        vec![self.direction_col.clone()]
+    }
+
+    fn set_signal_column(&self, lf: &LazyFrame) -> Result<LazyFrame, GlowError> {
+        let signal_col = self.category.get_column();
+        let lf = lf.clone().with_column(
+            when(self.get_flip_expr().fill_null(lit(false)))
+                .then(lit(1))
+                .otherwise(lit(0))
+                .alias(signal_col),
+        );
+        Ok(lf)
+    }
+
+    fn update_signal_column(&self, data: &DataFrame) -> Result<DataFrame, GlowError> {
+        let signal_col = self.category.get_column();
+        let new_df = self.set_signal_column(&data.clone().lazy())?.collect()?;
+        let series = new_df.column(signal_col)?;
+        let mut result_df = data.clone();
+        result_df.with_column(series.to_owned())?;
+
+        Ok(result_df)
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let updated = Self::new(updated_symbols_pair, self.category)?;
+        Ok(updated.into())
+    }
+}
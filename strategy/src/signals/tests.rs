@@ -0,0 +1,310 @@
+use super::{
+    composite::CompositeSignal,
+    confirmed::ConfirmedSignal,
+    external::ExternalSignal,
+    threshold_cross::{CrossDirection, ThresholdCrossSignal},
+    SignalWrapper,
+};
+use common::{
+    enums::{signal_category::SignalCategory, symbol_id::SymbolId},
+    structs::SymbolsPair,
+    traits::signal::Signal,
+};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+const RAW_SIGNAL_COL: &str = "raw_signal";
+
+fn get_raw_signal_df(raw_signals: &[i32]) -> DataFrame {
+    df!(RAW_SIGNAL_COL => raw_signals).unwrap()
+}
+
+fn get_raw_signal(category: SignalCategory) -> SignalWrapper {
+    ExternalSignal::new(RAW_SIGNAL_COL.to_string(), category).into()
+}
+
+fn get_signal_values(df: &DataFrame, category: SignalCategory) -> Vec<i32> {
+    df.column(category.get_column())
+        .unwrap()
+        .cast(&DataType::Int32)
+        .unwrap()
+        .i32()
+        .unwrap()
+        .into_iter()
+        .map(|value| value.unwrap_or_default())
+        .collect()
+}
+
+fn set_signal_column<S: Signal>(signal: &S, df: &DataFrame) -> DataFrame {
+    signal
+        .set_signal_column(&df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap()
+}
+
+#[test]
+fn test_confirmed_signal_fires_on_nth_consecutive_bar() {
+    let category = SignalCategory::GoLong;
+    let df = get_raw_signal_df(&[0, 1, 1, 1, 1, 0, 1, 1, 0]);
+
+    let confirmed = ConfirmedSignal::new(get_raw_signal(category), 3).unwrap();
+    let result_df = set_signal_column(&confirmed, &df);
+    // third consecutive bar is the first confirmed one, not the second
+    assert_eq!(
+        get_signal_values(&result_df, category),
+        vec![0, 0, 0, 1, 1, 0, 0, 0, 0]
+    );
+
+    let confirmed = ConfirmedSignal::new(get_raw_signal(category), 2).unwrap();
+    let result_df = set_signal_column(&confirmed, &df);
+    assert_eq!(
+        get_signal_values(&result_df, category),
+        vec![0, 0, 1, 1, 1, 0, 0, 1, 0]
+    );
+
+    // a single bar confirmation passes raw signal through
+    let confirmed = ConfirmedSignal::new(get_raw_signal(category), 1).unwrap();
+    let result_df = set_signal_column(&confirmed, &df);
+    assert_eq!(
+        get_signal_values(&result_df, category),
+        vec![0, 1, 1, 1, 1, 0, 1, 1, 0]
+    );
+    assert!(ConfirmedSignal::new(get_raw_signal(category), 0).is_err());
+}
+
+#[test]
+fn test_confirmed_signal_update_matches_set_at_last_row() {
+    let category = SignalCategory::GoShort;
+    let confirmed = ConfirmedSignal::new(get_raw_signal(category), 3).unwrap();
+    let df = get_raw_signal_df(&[1, 0, 1, 1, 1, 0, 1, 1, 1]);
+
+    for length in 3..=df.height() {
+        let initial_df = set_signal_column(&confirmed, &df.slice(0, length - 1));
+        // appended bar hasn't its signal set yet
+        let mut appended_df = df.slice(length as i64 - 1, 1);
+        let nulls = Series::full_null(category.get_column(), 1, &DataType::Int32);
+        appended_df.with_column(nulls).unwrap();
+        let stacked_df = initial_df.vstack(&appended_df).unwrap();
+
+        let updated_df = confirmed.update_signal_column(&stacked_df).unwrap();
+        let set_df = set_signal_column(&confirmed, &df.slice(0, length));
+
+        let updated_values = get_signal_values(&updated_df, category);
+        let set_values = get_signal_values(&set_df, category);
+        assert_eq!(updated_values.last(), set_values.last());
+        assert_eq!(updated_values, set_values);
+    }
+}
+
+fn get_rsi_cross_signal(
+    direction: CrossDirection,
+    category: SignalCategory,
+) -> ThresholdCrossSignal {
+    ThresholdCrossSignal::new(
+        SymbolsPair::default(),
+        String::from("rsi"),
+        70.0,
+        direction,
+        category,
+    )
+}
+
+#[test]
+fn test_threshold_cross_signal_fires_at_crossing_bars_only() {
+    let df = df!("rsi" => [70.0, 75.0, 80.0, 70.0, 65.0, 70.0, 71.0]).unwrap();
+
+    let up_cross = get_rsi_cross_signal(CrossDirection::UpCross, SignalCategory::GoLong);
+    let result_df = set_signal_column(&up_cross, &df);
+    // crossing starts at threshold, but only ends strictly past it
+    assert_eq!(
+        get_signal_values(&result_df, SignalCategory::GoLong),
+        vec![0, 1, 0, 0, 0, 0, 1]
+    );
+
+    let down_cross = get_rsi_cross_signal(CrossDirection::DownCross, SignalCategory::CloseLong);
+    let result_df = set_signal_column(&down_cross, &df);
+    assert_eq!(
+        get_signal_values(&result_df, SignalCategory::CloseLong),
+        vec![0, 0, 0, 0, 1, 0, 0]
+    );
+}
+
+#[test]
+fn test_threshold_cross_signal_doesnt_fire_without_previous_value() {
+    let up_cross = get_rsi_cross_signal(CrossDirection::UpCross, SignalCategory::GoLong);
+    let down_cross = get_rsi_cross_signal(CrossDirection::DownCross, SignalCategory::GoShort);
+    // first bar has no previous value, as it's shifted in as null, nor has the one after a gap
+    let df = df!("rsi" => [Some(90.0), Some(60.0), None, Some(80.0)]).unwrap();
+
+    let result_df = set_signal_column(&up_cross, &df);
+    let up_cross_values = get_signal_values(&result_df, SignalCategory::GoLong);
+    let result_df = set_signal_column(&down_cross, &df);
+    let down_cross_values = get_signal_values(&result_df, SignalCategory::GoShort);
+
+    assert_eq!(up_cross_values, vec![0, 0, 0, 0]);
+    assert_eq!(down_cross_values, vec![0, 1, 0, 0]);
+    let signal_col = result_df
+        .column(SignalCategory::GoShort.get_column())
+        .unwrap();
+    assert_eq!(signal_col.null_count(), 0);
+}
+
+#[test]
+fn test_threshold_cross_signal_column_is_renamed_after_patched_symbols() {
+    let symbols_pair = SymbolsPair::new(&SymbolId::Ethereum, &SymbolId::Solana);
+    let updated_symbols_pair = SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Chainlink);
+    let cases = [
+        ("ETHUSDT_rsi", "BTCUSDT_rsi"),
+        ("SOLUSDT_rsi", "LINKUSDT_rsi"),
+        ("rsi", "rsi"),
+    ];
+
+    for (column, expected_column) in cases {
+        let signal = ThresholdCrossSignal::new(
+            symbols_pair,
+            String::from(column),
+            70.0,
+            CrossDirection::UpCross,
+            SignalCategory::GoLong,
+        );
+
+        let patched_signal = signal.patch_symbols_pair(updated_symbols_pair).unwrap();
+
+        assert_eq!(patched_signal.required_columns(), vec![expected_column]);
+        let SignalWrapper::ThresholdCross(patched_signal) = patched_signal else {
+            panic!("patched signal should be a threshold cross one");
+        };
+        assert_eq!(patched_signal.threshold, 70.0);
+        assert_eq!(patched_signal.direction, CrossDirection::UpCross);
+    }
+}
+
+/// Composite `GoLong` signal over external signals of `a`, `b` and `c` columns, only the
+/// first of which shares its category.
+fn get_composite_signal(min_confirmations: usize) -> Result<CompositeSignal, GlowError> {
+    let signals = vec![
+        ExternalSignal::new(String::from("a"), SignalCategory::GoLong).into(),
+        ExternalSignal::new(String::from("b"), SignalCategory::CloseShort).into(),
+        ExternalSignal::new(String::from("c"), SignalCategory::GoShort).into(),
+    ];
+    CompositeSignal::new(signals, SignalCategory::GoLong, min_confirmations)
+}
+
+fn get_composite_df() -> DataFrame {
+    df!(
+        "a" => [1, 1, 0, 0, 1],
+        "b" => [1, 0, 1, 0, 1],
+        "c" => [1, 0, 0, 0, 0],
+        SignalCategory::GoShort.get_column() => [0, 1, 0, 1, 0]
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_composite_signal_fires_once_min_confirmations_are_met() {
+    let df = get_composite_df();
+    // (min confirmations, expected values), from any (OR) to all (AND) of its signals
+    let cases = [
+        (1, vec![1, 1, 1, 0, 1]),
+        (2, vec![1, 0, 0, 0, 1]),
+        (3, vec![1, 0, 0, 0, 0]),
+    ];
+
+    for (min_confirmations, expected_values) in cases {
+        let composite = get_composite_signal(min_confirmations).unwrap();
+        let result_df = set_signal_column(&composite, &df);
+
+        assert_eq!(
+            get_signal_values(&result_df, SignalCategory::GoLong),
+            expected_values,
+            "min_confirmations = {}",
+            min_confirmations
+        );
+        // children columns are neither kept, nor overwrite existing ones
+        assert_eq!(
+            get_signal_values(&result_df, SignalCategory::GoShort),
+            vec![0, 1, 0, 1, 0]
+        );
+        assert!(result_df
+            .column(SignalCategory::CloseShort.get_column())
+            .is_err());
+        assert_eq!(result_df.width(), df.width() + 1);
+    }
+    assert!(get_composite_signal(0).is_err());
+    assert!(get_composite_signal(4).is_err());
+}
+
+#[test]
+fn test_composite_signal_update_matches_set_at_last_row() {
+    let composite = get_composite_signal(2).unwrap();
+    let df = get_composite_df();
+
+    for length in 2..=df.height() {
+        let initial_df = set_signal_column(&composite, &df.slice(0, length - 1));
+        // appended bar hasn't its signal set yet
+        let mut appended_df = df.slice(length as i64 - 1, 1);
+        let nulls = Series::full_null(SignalCategory::GoLong.get_column(), 1, &DataType::Int32);
+        appended_df.with_column(nulls).unwrap();
+        let stacked_df = initial_df.vstack(&appended_df).unwrap();
+
+        let updated_df = composite.update_signal_column(&stacked_df).unwrap();
+        let set_df = set_signal_column(&composite, &df.slice(0, length));
+
+        assert_eq!(
+            get_signal_values(&updated_df, SignalCategory::GoLong),
+            get_signal_values(&set_df, SignalCategory::GoLong)
+        );
+        assert_eq!(
+            get_signal_values(&updated_df, SignalCategory::GoShort),
+            get_signal_values(&df.slice(0, length), SignalCategory::GoShort)
+        );
+    }
+}
+
+#[test]
+fn test_external_signal_passes_joined_column_through() {
+    let klines_df = df!(
+        "start_time" => [1_i64, 2, 3, 4, 5],
+        "close" => [100.0, 101.0, 102.0, 101.0, 100.0]
+    )
+    .unwrap();
+    // external alerts are only supplied for some of the bars
+    let alerts_df = df!(
+        "start_time" => [2_i64, 3, 5],
+        "alert" => [true, false, true]
+    )
+    .unwrap();
+    let df = klines_df
+        .left_join(&alerts_df, ["start_time"], ["start_time"])
+        .unwrap();
+    let external = ExternalSignal::new(String::from("alert"), SignalCategory::GoShort);
+
+    let result_df = set_signal_column(&external, &df);
+    assert_eq!(
+        get_signal_values(&result_df, SignalCategory::GoShort),
+        vec![0, 1, 0, 0, 1]
+    );
+    assert_eq!(result_df.width(), df.width() + 1);
+
+    let updated_df = external.update_signal_column(&df).unwrap();
+    assert_eq!(
+        get_signal_values(&updated_df, SignalCategory::GoShort),
+        vec![0, 1, 0, 0, 1]
+    );
+}
+
+#[test]
+fn test_external_signal_errors_on_missing_or_non_binary_column() {
+    let df = get_raw_signal_df(&[0, 1, 2]);
+    let missing = ExternalSignal::new(String::from("alert"), SignalCategory::GoLong);
+    let Err(error) = missing.set_signal_column(&df.clone().lazy()) else {
+        panic!("missing external signal column is set");
+    };
+    assert_eq!(error.title, "Missing External Signal Column");
+    assert!(missing.update_signal_column(&df).is_err());
+
+    let non_binary = get_raw_signal(SignalCategory::GoLong);
+    let lf = non_binary.set_signal_column(&df.lazy()).unwrap();
+    assert!(lf.collect().is_err());
+}
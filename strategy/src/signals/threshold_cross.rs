@@ -0,0 +1,114 @@
+use super::SignalWrapper;
+use common::{
+    enums::signal_category::SignalCategory, structs::SymbolsPair, traits::signal::Signal,
+};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CrossDirection {
+    UpCross,
+    DownCross,
+}
+
+/// Emits 1 at bars where `column` crosses `threshold` in `direction`, i.e. previous bar value
+/// was at or on the other side of threshold, and current value is strictly past it.
+///
+/// Column may be named after the strategy symbols (e.g. `BTCUSDT_rsi`),
+/// in which case `patch_symbols_pair` renames it accordingly.
+#[derive(Clone, Debug)]
+pub struct ThresholdCrossSignal {
+    pub column: String,
+    pub threshold: f64,
+    pub direction: CrossDirection,
+    pub category: SignalCategory,
+    symbols_pair: SymbolsPair,
+}
+
+impl ThresholdCrossSignal {
+    pub fn new(
+        symbols_pair: SymbolsPair,
+        column: String,
+        threshold: f64,
+        direction: CrossDirection,
+        category: SignalCategory,
+    ) -> Self {
+        Self {
+            column,
+            threshold,
+            direction,
+            category,
+            symbols_pair,
+        }
+    }
+
+    fn get_cross_expr(&self) -> Expr {
+        let current = col(&self.column);
+        let previous = col(&self.column).shift(1);
+        let threshold = lit(self.threshold);
+        match self.direction {
+            CrossDirection::UpCross => previous.lt_eq(threshold.clone()).and(current.gt(threshold)),
+            CrossDirection::DownCross => {
+                previous.gt_eq(threshold.clone()).and(current.lt(threshold))
+            }
+        }
+    }
+}
+
+impl Signal for ThresholdCrossSignal {
+    type Wrapper = SignalWrapper;
+
+    fn signal_category(&self) -> SignalCategory {
+        self.category
+    }
+
+    fn required_columns(&self) -> Vec<String> {
+        vec![self.column.clone()]
+    }
+
+    fn set_signal_column(&self, lf: &LazyFrame) -> Result<LazyFrame, GlowError> {
+        let signal_col = self.category.get_column();
+        let lf = lf.clone().with_column(
+            when(self.get_cross_expr().fill_null(lit(false)))
+                .then(lit(1))
+                .otherwise(lit(0))
+                .alias(signal_col),
+        );
+        Ok(lf)
+    }
+
+    fn update_signal_column(&self, data: &DataFrame) -> Result<DataFrame, GlowError> {
+        let signal_col = self.category.get_column();
+        let new_df = self.set_signal_column(&data.clone().lazy())?.collect()?;
+        let series = new_df.column(signal_col)?;
+        let mut result_df = data.clone();
+        result_df.with_column(series.to_owned())?;
+
+        Ok(result_df)
+    }
+
+    fn patch_symbols_pair(
+        &self,
+        updated_symbols_pair: SymbolsPair,
+    ) -> Result<Self::Wrapper, GlowError> {
+        let previous_anchor = self.symbols_pair.anchor.name;
+        let previous_traded = self.symbols_pair.traded.name;
+        let column = if self.column.starts_with(previous_anchor) {
+            self.column
+                .replacen(previous_anchor, updated_symbols_pair.anchor.name, 1)
+        } else if self.column.starts_with(previous_traded) {
+            self.column
+                .replacen(previous_traded, updated_symbols_pair.traded.name, 1)
+        } else {
+            self.column.clone()
+        };
+        let updated = Self::new(
+            updated_symbols_pair,
+            column,
+            self.threshold,
+            self.direction,
+            self.category,
+        );
+        Ok(updated.into())
+    }
+}
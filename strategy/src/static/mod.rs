@@ -1,3 +1,12 @@
-use crate::StrategyId;
+use crate::{indicators::cache::IndicatorCache, StrategyId};
+use std::sync::{LazyLock, Mutex};
 
 pub const STRATEGIES_IDS: [StrategyId; 1] = [StrategyId::SimpleTrend];
+
+/// Max rows of indicators' column sets kept at `INDICATORS_CACHE`.
+pub const INDICATORS_CACHE_MAX_ROWS: usize = 1_000_000;
+
+/// Shared across strategies, so that sweep variants reuse each other's indicator columns.
+/// See `IndicatorWrapper::set_cached_indicator_columns`.
+pub static INDICATORS_CACHE: LazyLock<Mutex<IndicatorCache>> =
+    LazyLock::new(|| Mutex::new(IndicatorCache::new(INDICATORS_CACHE_MAX_ROWS)));
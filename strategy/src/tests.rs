@@ -0,0 +1,308 @@
+use super::{
+    indicators::{
+        cache::{get_indicator_cache_key, get_indicator_inputs_df},
+        ema::EmaIndicator,
+        IndicatorWrapper,
+    },
+    r#static::INDICATORS_CACHE,
+    schemas::Schema,
+    signals::{
+        threshold_cross::{CrossDirection, ThresholdCrossSignal},
+        SignalWrapper,
+    },
+    validate_signals_dependencies, Strategy, StrategyId,
+};
+use common::{
+    enums::{signal_category::SignalCategory, symbol_id::SymbolId},
+    structs::SymbolsPair,
+    traits::signal::Signal,
+};
+use polars::prelude::*;
+
+#[test]
+fn test_rebind_symbols_moves_indicators_and_signals_to_new_pair() {
+    let strategy = Strategy::new(
+        StrategyId::SimpleTrend,
+        SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Bitcoin),
+    )
+    .expect("strategy to be created");
+
+    let rebound = strategy
+        .rebind_symbols(SymbolsPair::new(&SymbolId::Ethereum, &SymbolId::Solana))
+        .expect("rebound strategy to have its signal dependencies met");
+
+    assert_eq!(rebound.symbols_pair.get_tuple(), ("ETHUSDT", "SOLUSDT"));
+    let indicators_columns = rebound.get_indicators_columns();
+    assert!(indicators_columns
+        .iter()
+        .any(|(column, _)| column.starts_with("ETHUSDT")));
+    assert!(indicators_columns
+        .iter()
+        .all(|(column, _)| !column.starts_with("BTCUSDT")));
+    assert!(strategy
+        .get_indicators_columns()
+        .iter()
+        .any(|(column, _)| column.starts_with("BTCUSDT")));
+}
+
+#[test]
+fn test_signals_dependencies_are_read_from_schema_signals() {
+    let symbols_pair = SymbolsPair::new(&SymbolId::Ethereum, &SymbolId::Solana);
+    let strategy = Strategy::new(StrategyId::SimpleTrend, symbols_pair)
+        .expect("strategy to have its signal dependencies met");
+
+    let signals = strategy
+        .schema
+        .get_signals(symbols_pair, &strategy.params)
+        .unwrap();
+    let mut required_columns: Vec<String> = signals
+        .iter()
+        .flat_map(|signal| signal.required_columns())
+        .collect();
+    required_columns.sort();
+    required_columns.dedup();
+
+    assert_eq!(signals.len(), 4);
+    assert_eq!(
+        required_columns,
+        vec![
+            String::from("ETHUSDT_fast_ema"),
+            String::from("ETHUSDT_slow_ema")
+        ]
+    );
+}
+
+#[test]
+fn test_signal_requiring_missing_indicator_column_fails_dependencies_check() {
+    let symbols_pair = SymbolsPair::default();
+    let strategy = Strategy::new(StrategyId::SimpleTrend, symbols_pair).unwrap();
+    let available_columns = strategy.get_signals_available_columns();
+    let mut signals = strategy
+        .schema
+        .get_signals(symbols_pair, &strategy.params)
+        .unwrap();
+    assert!(validate_signals_dependencies(&signals, &available_columns).is_ok());
+
+    // no indicator of SimpleTrend sets RSI
+    let rsi_col = format!("{}_rsi", symbols_pair.anchor.name);
+    let rsi_signal: SignalWrapper = ThresholdCrossSignal::new(
+        symbols_pair,
+        rsi_col.clone(),
+        30.0,
+        CrossDirection::UpCross,
+        SignalCategory::GoLong,
+    )
+    .into();
+    signals.push(rsi_signal);
+
+    let error = validate_signals_dependencies(&signals, &available_columns).unwrap_err();
+    assert_eq!(error.title, "Missing Signal Dependencies");
+    assert_eq!(
+        error.description,
+        format!(
+            "GoLong signal requires column {}, which isn't produced by any indicator",
+            rsi_col
+        )
+    );
+}
+
+#[test]
+fn test_use_closed_bars_only_shifts_signals_to_next_bar() {
+    let symbols_pair = SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Bitcoin);
+    let strategy =
+        Strategy::new(StrategyId::SimpleTrend, symbols_pair).expect("strategy to be created");
+    let closes = [10.0, 9.0, 8.0, 7.0, 12.0, 14.0, 15.0, 9.0, 5.0, 4.0];
+    let (open_col, high_col, low_col, close_col) = symbols_pair.anchor.get_ohlc_cols();
+    let df = df!(
+        open_col => closes,
+        high_col => closes,
+        low_col => closes,
+        close_col => closes,
+    )
+    .unwrap();
+
+    let get_signals = |strategy: &Strategy| {
+        let lf = strategy.append_indicators_to_lf(df.clone().lazy()).unwrap();
+        let df = strategy
+            .append_signals_to_lf(lf)
+            .unwrap()
+            .collect()
+            .unwrap();
+        strategy
+            .append_signals_to_df(df)
+            .unwrap()
+            .column(SignalCategory::GoLong.get_column())
+            .unwrap()
+            .i32()
+            .unwrap()
+            .into_no_null_iter()
+            .collect::<Vec<_>>()
+    };
+    let open_bar_signals = get_signals(&strategy);
+    let closed_bar_signals = get_signals(&strategy.patch_use_closed_bars_only(true));
+
+    assert!(open_bar_signals.contains(&1));
+    assert_eq!(closed_bar_signals[0], 0);
+    assert_eq!(
+        closed_bar_signals[1..],
+        open_bar_signals[..open_bar_signals.len() - 1]
+    );
+}
+
+#[test]
+fn test_to_long_frame_tags_each_column_by_kind() {
+    let symbols_pair = SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Bitcoin);
+    let strategy =
+        Strategy::new(StrategyId::SimpleTrend, symbols_pair).expect("strategy to be created");
+    let closes = [10.0, 9.0, 8.0, 7.0, 12.0];
+    let (open_col, high_col, low_col, close_col) = symbols_pair.anchor.get_ohlc_cols();
+    let df = df!(
+        "start_time" => [1_i64, 2, 3, 4, 5],
+        open_col => closes,
+        high_col => closes,
+        low_col => closes,
+        close_col => closes,
+        "returns" => [0.0, 0.1, 0.2, 0.3, 0.4],
+        "action" => ["a", "b", "c", "d", "e"],
+    )
+    .unwrap();
+    let lf = strategy.append_indicators_to_lf(df.lazy()).unwrap();
+    let df = strategy
+        .append_signals_to_lf(lf)
+        .unwrap()
+        .collect()
+        .unwrap();
+
+    let long_df = strategy.to_long_frame(&df).unwrap();
+
+    assert_eq!(
+        long_df.get_column_names(),
+        ["start_time", "name", "value", "kind"]
+    );
+    // every numeric column but start time is melted, "action" being left out
+    assert_eq!(long_df.height(), (df.width() - 2) * df.height());
+    let get_kind_count = |kind: &str| {
+        long_df
+            .column("kind")
+            .unwrap()
+            .utf8()
+            .unwrap()
+            .into_no_null_iter()
+            .filter(|value| *value == kind)
+            .count()
+    };
+    assert_eq!(
+        get_kind_count("indicator"),
+        strategy.get_indicators_columns().len() * df.height()
+    );
+    assert_eq!(
+        get_kind_count("signal"),
+        strategy.get_signals_columns().len() * df.height()
+    );
+    assert_eq!(get_kind_count("kline"), 4 * df.height());
+    assert_eq!(get_kind_count("result"), df.height());
+}
+
+#[test]
+fn test_warmup_klines_make_indicators_valid_from_first_live_bar() {
+    let symbols_pair = SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Bitcoin);
+    let strategy =
+        Strategy::new(StrategyId::SimpleTrend, symbols_pair).expect("strategy to be created");
+    let warmup_klines = strategy.get_warmup_klines();
+    assert!(warmup_klines >= strategy.get_minimum_klines_for_calculation());
+    assert!(warmup_klines >= strategy.get_indicator_warmup_bars());
+
+    // warmup klines followed by first live bar
+    let closes: Vec<f64> = (0..=warmup_klines)
+        .map(|index| 100.0 + index as f64)
+        .collect();
+    let (open_col, high_col, low_col, close_col) = symbols_pair.anchor.get_ohlc_cols();
+    let df = df!(
+        open_col => &closes,
+        high_col => &closes,
+        low_col => &closes,
+        close_col => &closes,
+    )
+    .unwrap();
+    let df = strategy
+        .append_indicators_to_lf(df.lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+
+    for (column, _) in strategy.get_indicators_columns() {
+        let null_count = df.column(&column).unwrap().tail(Some(1)).null_count();
+        assert_eq!(null_count, 0, "{} is null at first live bar", column);
+    }
+}
+
+#[test]
+fn test_latest_indicator_values_are_taken_from_last_row() {
+    let symbols_pair = SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Bitcoin);
+    let strategy =
+        Strategy::new(StrategyId::SimpleTrend, symbols_pair).expect("strategy to be created");
+    let closes = [10.0, 9.0, 8.0, 7.0, 12.0, 14.0, 15.0, 9.0, 5.0, 4.0];
+    let (open_col, high_col, low_col, close_col) = symbols_pair.anchor.get_ohlc_cols();
+    let df = df!(
+        open_col => closes,
+        high_col => closes,
+        low_col => closes,
+        close_col => closes,
+    )
+    .unwrap();
+    let df = strategy
+        .append_indicators_to_lf(df.lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+
+    let latest_values = strategy.latest_indicator_values(&df);
+    let indicators_columns = strategy.get_indicators_columns();
+    assert_eq!(latest_values.len(), indicators_columns.len());
+    for (column, _) in indicators_columns {
+        // boolean indicator columns are cast to 0 or 1
+        let series = df
+            .column(&column)
+            .unwrap()
+            .cast(&DataType::Float64)
+            .unwrap();
+        let last_value = series.f64().unwrap().get(9).unwrap();
+        assert_eq!(latest_values[&column], last_value);
+    }
+    assert!(strategy
+        .latest_indicator_values(&df.select([&close_col]).unwrap())
+        .is_empty());
+}
+
+#[test]
+fn test_indicators_are_set_through_indicators_cache() {
+    let symbols_pair = SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Bitcoin);
+    let strategy =
+        Strategy::new(StrategyId::SimpleTrend, symbols_pair).expect("strategy to be created");
+    let close_col = symbols_pair.anchor.get_close_col();
+    let closes: Vec<f64> = (0..40)
+        .map(|index| 100.0 + (index as f64 / 3.0).cos())
+        .collect();
+    let df = df!(close_col => closes).unwrap();
+
+    let result_df = strategy
+        .append_indicators_to_lf(df.clone().lazy())
+        .unwrap()
+        .collect()
+        .unwrap();
+
+    let fast_ema: IndicatorWrapper =
+        EmaIndicator::from_anchor_close(symbols_pair, 20, "fast_ema").into();
+    let inputs_df = get_indicator_inputs_df(&fast_ema, &df).unwrap();
+    let key = get_indicator_cache_key(&fast_ema, &inputs_df).unwrap();
+    let cached_columns_df = INDICATORS_CACHE
+        .lock()
+        .unwrap()
+        .get(key, &inputs_df)
+        .expect("fast EMA columns to be cached");
+    let fast_ema_col = format!("{}_fast_ema", symbols_pair.anchor.name);
+    assert!(cached_columns_df
+        .column(&fast_ema_col)
+        .unwrap()
+        .series_equal_missing(result_df.column(&fast_ema_col).unwrap()));
+}